@@ -3,12 +3,25 @@
 // Copyright (c) 2026 Relative Companies, Inc.
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
+import Foundation
 import PackageDescription
 
+// Build profile selection. `RELATIVE_PROTOCOL_PROFILE=ios-minimal` compiles out heavy
+// optional subsystems (signature classification, rich packet logging) so the Network
+// Extension dylib stays small; see Docs/Operations.md "Build Profiles and Binary Size".
+let buildProfile = ProcessInfo.processInfo.environment["RELATIVE_PROTOCOL_PROFILE"]
+
+let profileSwiftSettings: [SwiftSetting] = {
+    guard buildProfile == "ios-minimal" else {
+        return []
+    }
+    return [.define("RELATIVE_MINIMAL_PROFILE")]
+}()
+
 let strictSwiftSettings: [SwiftSetting] = [
     .unsafeFlags(["-strict-concurrency=complete"]),
     .unsafeFlags(["-Xfrontend", "-warnings-as-errors"], .when(platforms: [.macOS]))
-]
+] + profileSwiftSettings
 
 let strictCSettings: [CSetting] = [
     .unsafeFlags(["-Wall", "-Wextra", "-Werror", "-Wpedantic"])
@@ -33,7 +46,7 @@ let appleOnlyTargets: [Target] = {
     return [
         .target(
             name: "PacketRelay",
-            dependencies: ["Observability", "TunnelRuntime"],
+            dependencies: ["Observability", "PacketIntelligenceCore", "TunnelRuntime"],
             path: "Sources/PacketRelay",
             swiftSettings: strictSwiftSettings
         ),
@@ -168,7 +181,7 @@ let package = Package(
         ),
         .executableTarget(
             name: "HarnessLocal",
-            dependencies: ["Analytics", "HarnessTunSupport", "Observability", "TunnelRuntime"],
+            dependencies: ["Analytics", "DataplaneFFI", "HarnessTunSupport", "Observability", "TunnelRuntime"],
             path: "Sources/HarnessLocal",
             swiftSettings: strictSwiftSettings
         ),
@@ -199,7 +212,7 @@ let package = Package(
     ] + appleOnlyTargets + appleOnlyTestTargets + [
         .testTarget(
             name: "HarnessLocalTests",
-            dependencies: ["HarnessLocal", "Analytics", "TunnelRuntime"],
+            dependencies: ["HarnessLocal", "Analytics", "HarnessTunSupport", "TunnelRuntime"],
             path: "Tests/HarnessLocalTests",
             resources: [
                 .copy("Fixtures/ReplayScenario.json")
@@ -0,0 +1,53 @@
+#!/usr/bin/env bash
+# Created by Will Kusch, Relative Companies, Inc.
+# Copyright (c) 2026 Relative Companies, Inc.
+# Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+# Builds the package in release mode for the default profile and the ios-minimal
+# profile, then reports per-target object size so binary-size regressions in the
+# Network Extension dylib are visible in CI. See Docs/Operations.md
+# "Build Profiles and Binary Size".
+
+set -euo pipefail
+
+ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+cd "$ROOT_DIR"
+
+FIRST_PARTY_TARGETS=(
+  Observability
+  DataplaneFFI
+  TunnelRuntime
+  Analytics
+  PacketRelay
+  TunnelControl
+  HostClient
+)
+
+report_profile() {
+  local profile_name="$1"
+  local scratch_path="$2"
+  shift 2
+
+  echo "==> building profile: ${profile_name}"
+  env "$@" swift build -c release --scratch-path "$scratch_path" >/dev/null
+
+  echo "profile=${profile_name}"
+  local total_bytes=0
+  for target in "${FIRST_PARTY_TARGETS[@]}"; do
+    local build_dir="${scratch_path}/release/${target}.build"
+    if [[ ! -d "$build_dir" ]]; then
+      continue
+    fi
+    local target_bytes
+    target_bytes="$(find "$build_dir" -name '*.o' -print0 | xargs -0 wc -c 2>/dev/null | awk 'END { print $1 }')"
+    target_bytes="${target_bytes:-0}"
+    printf '  %-16s %10d bytes\n' "$target" "$target_bytes"
+    total_bytes=$((total_bytes + target_bytes))
+  done
+  printf '  %-16s %10d bytes\n' "total" "$total_bytes"
+}
+
+report_profile "default" ".build-size-default"
+report_profile "ios-minimal" ".build-size-minimal" RELATIVE_PROTOCOL_PROFILE=ios-minimal
+
+echo "==> size report complete"
@@ -0,0 +1,66 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Fixed-bucket histograms of telemetry batch shape and processing wall time.
+/// Decision: bucket bounds are compile-time constants shared by writer and reader, mirroring
+/// `PayloadSizeHistogram`, so integrators can tune queue capacity and submit cadence from real
+/// distributions instead of averages that hide burst behavior.
+public struct BatchExecutionHistograms: Codable, Sendable, Equatable {
+    /// Inclusive upper bounds on packets per batch; larger batches land in the overflow bucket.
+    public static let packetCountUpperBounds = [1, 2, 4, 8, 16, 32, 64, 128]
+
+    /// Inclusive upper bounds on per-batch processing wall time in microseconds; slower batches
+    /// land in the overflow bucket.
+    public static let durationUpperBoundsMicroseconds = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000]
+
+    /// Per-bucket batch counts keyed by packets per batch; the final element is the overflow bucket.
+    public private(set) var packetCountBucketCounts: [Int]
+    /// Per-bucket batch counts keyed by processing wall time; the final element is the overflow bucket.
+    public private(set) var durationBucketCounts: [Int]
+    public private(set) var batchCount: Int
+    public private(set) var totalPackets: Int
+    public private(set) var totalMicroseconds: Int
+    public private(set) var maxMicroseconds: Int
+
+    public init() {
+        self.packetCountBucketCounts = [Int](repeating: 0, count: Self.packetCountUpperBounds.count + 1)
+        self.durationBucketCounts = [Int](repeating: 0, count: Self.durationUpperBoundsMicroseconds.count + 1)
+        self.batchCount = 0
+        self.totalPackets = 0
+        self.totalMicroseconds = 0
+        self.maxMicroseconds = 0
+    }
+
+    public var isEmpty: Bool {
+        batchCount == 0
+    }
+
+    /// Records one processed batch.
+    /// - Parameters:
+    ///   - packetCount: Packets the batch carried into processing.
+    ///   - durationMicroseconds: Wall time the batch spent in processing; negative values are clamped to zero.
+    public mutating func record(packetCount: Int, durationMicroseconds: Int) {
+        guard packetCount > 0 else {
+            return
+        }
+        let clampedDuration = max(0, durationMicroseconds)
+        let packetBucket = Self.packetCountUpperBounds.firstIndex { packetCount <= $0 }
+            ?? Self.packetCountUpperBounds.count
+        let durationBucket = Self.durationUpperBoundsMicroseconds.firstIndex { clampedDuration <= $0 }
+            ?? Self.durationUpperBoundsMicroseconds.count
+        packetCountBucketCounts[packetBucket] = saturatingAdd(packetCountBucketCounts[packetBucket], 1)
+        durationBucketCounts[durationBucket] = saturatingAdd(durationBucketCounts[durationBucket], 1)
+        batchCount = saturatingAdd(batchCount, 1)
+        totalPackets = saturatingAdd(totalPackets, packetCount)
+        totalMicroseconds = saturatingAdd(totalMicroseconds, clampedDuration)
+        maxMicroseconds = max(maxMicroseconds, clampedDuration)
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (value, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : value
+    }
+}
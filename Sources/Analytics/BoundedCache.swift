@@ -45,4 +45,12 @@ final class BoundedCache<Key: Hashable, Value>: @unchecked Sendable {
             nextEvictionIndex = 0
         }
     }
+
+    func removeAll() {
+        lock.lock()
+        defer { lock.unlock() }
+        values.removeAll()
+        insertionOrder.removeAll()
+        nextEvictionIndex = 0
+    }
 }
@@ -11,6 +11,86 @@ internal struct DNSAssociationSnapshot: Sendable, Equatable {
     let confidence: Double
 }
 
+/// One observed IP-to-hostname association, exposed so hosts can label raw-IP flows with the
+/// most recent name that resolved to the address.
+public struct DNSHostnameAssociation: Codable, Sendable, Equatable {
+    /// Text form of the looked-up address.
+    public let address: String
+    /// Most recent name observed resolving to the address; falls back to the registrable domain
+    /// when the response carried no usable query name.
+    public let hostname: String
+    public let ageMs: Int
+    public let confidence: Double
+
+    public init(address: String, hostname: String, ageMs: Int, confidence: Double) {
+        self.address = address
+        self.hostname = hostname
+        self.ageMs = max(0, ageMs)
+        self.confidence = confidence
+    }
+}
+
+/// Size and eviction counters for the DNS association cache, so hosts can watch resolver-history
+/// memory behaviour over long sessions.
+public struct DNSAssociationCacheStats: Codable, Sendable, Equatable {
+    /// Live address-to-domain entries currently held.
+    public let entryCount: Int
+    /// Entries dropped because their TTL lapsed.
+    public let expiredEvictionCount: Int
+    /// Entries dropped least-recently-used-first to stay under the global cap.
+    public let overflowEvictionCount: Int
+
+    public init(entryCount: Int = 0, expiredEvictionCount: Int = 0, overflowEvictionCount: Int = 0) {
+        self.entryCount = max(0, entryCount)
+        self.expiredEvictionCount = max(0, expiredEvictionCount)
+        self.overflowEvictionCount = max(0, overflowEvictionCount)
+    }
+
+    public var isEmpty: Bool {
+        entryCount == 0 && expiredEvictionCount == 0 && overflowEvictionCount == 0
+    }
+}
+
+/// One persistable IP-to-hostname mapping with its remaining lifetime, the unit of the
+/// association export/import API. Codable so hosts can serialize a snapshot as compact JSON,
+/// persist it across a tunnel restart, and pre-seed the next session's cache with it.
+public struct DNSAssociationExportEntry: Codable, Sendable, Equatable {
+    /// Text form of the mapped address.
+    public let address: String
+    /// Most recent name observed resolving to the address, when the response carried one.
+    public let hostname: String?
+    /// Registrable domain the address was attributed to.
+    public let associatedDomain: String
+    /// Lifetime the entry had left at export time; imports honor it so persisted mappings
+    /// never outlive what the live cache would have kept.
+    public let remainingTTLMilliseconds: Int
+    public let confidence: Double
+
+    public init(
+        address: String,
+        hostname: String?,
+        associatedDomain: String,
+        remainingTTLMilliseconds: Int,
+        confidence: Double
+    ) {
+        self.address = address
+        self.hostname = hostname
+        self.associatedDomain = associatedDomain
+        self.remainingTTLMilliseconds = max(0, remainingTTLMilliseconds)
+        self.confidence = confidence
+    }
+}
+
+/// Disposition of one parsed DNS response offered to the association cache.
+internal enum DNSResponseDisposition: Sendable, Equatable {
+    /// The response matched a recently observed query and its answers were recorded.
+    case recorded
+    /// The response carried answers but no matching query was seen; treated as potential spoofing.
+    case spoofCandidate
+    /// The packet carried nothing the cache could act on.
+    case notApplicable
+}
+
 internal struct DNSAssociationCache {
     private struct AddressKey: Hashable, Sendable {
         let length: UInt8
@@ -20,6 +100,7 @@ internal struct DNSAssociationCache {
 
     private struct Entry: Sendable {
         let associatedDomain: String
+        let hostname: String?
         let source: DetectorAssociationSource
         let storedAt: Date
         let confidence: Double
@@ -34,10 +115,22 @@ internal struct DNSAssociationCache {
     private var entries: [AddressKey: Entry] = [:]
     private var arrivalQueue: ArraySlice<AddressKey> = []
     private var lastSweepAt: Date?
+    private var expiredEvictionCount = 0
+    private var overflowEvictionCount = 0
 
-    mutating func record(metadata: PacketMetadata, classification: String?, now: Date) {
+    /// Records answer-address mappings from one parsed DNS response, provided a matching query was seen.
+    /// Decision: an unsolicited response (`queryMatched == false`, decided by `DNSTransactionTracker`
+    /// against transaction ID, qname, and resolver address) never updates the cache, so an off-path
+    /// spoofer cannot steer later flow attribution; callers count the rejection as a potential-spoofing
+    /// signal instead.
+    mutating func record(
+        metadata: PacketMetadata,
+        classification: String?,
+        queryMatched: Bool,
+        now: Date
+    ) -> DNSResponseDisposition {
         guard let answers = metadata.dnsAnswerAddresses, !answers.isEmpty else {
-            return
+            return .notApplicable
         }
 
         let associatedDomain = metadata.registrableDomain
@@ -45,15 +138,21 @@ internal struct DNSAssociationCache {
             ?? DomainNormalizer.registrableDomain(from: metadata.dnsQueryName)
             ?? classification
         guard let associatedDomain, !associatedDomain.isEmpty else {
-            return
+            return .notApplicable
+        }
+
+        guard queryMatched else {
+            return .spoofCandidate
         }
 
         evictExpiredIfNeeded(now: now)
 
+        let hostname = metadata.dnsCname ?? metadata.dnsQueryName
         for address in answers {
             let key = Self.key(for: address)
             entries[key] = Entry(
                 associatedDomain: associatedDomain,
+                hostname: hostname,
                 source: .dnsAnswer,
                 storedAt: now,
                 confidence: classification == nil ? 0.82 : 0.9
@@ -62,6 +161,7 @@ internal struct DNSAssociationCache {
         }
 
         trimOverflowIfNeeded()
+        return .recorded
     }
 
     mutating func associate(summary: FastPacketSummary, direction: PacketDirection, now: Date) -> DNSAssociationSnapshot? {
@@ -72,9 +172,11 @@ internal struct DNSAssociationCache {
         }
         guard !Self.isExpired(entry, now: now) else {
             entries.removeValue(forKey: key)
+            expiredEvictionCount = saturatingAdd(expiredEvictionCount, 1)
             pruneArrivalQueue()
             return nil
         }
+        arrivalQueue.append(key)
         let ageMs = millisecondsBetween(entry.storedAt, and: now)
         return DNSAssociationSnapshot(
             associatedDomain: entry.associatedDomain,
@@ -84,6 +186,100 @@ internal struct DNSAssociationCache {
         )
     }
 
+    /// Reverse lookup: returns the most recent in-TTL hostname observed resolving to `address`.
+    mutating func lookupHostname(for address: IPAddress, now: Date) -> DNSHostnameAssociation? {
+        evictExpiredIfNeeded(now: now)
+        let key = Self.key(for: address)
+        guard let entry = entries[key] else {
+            return nil
+        }
+        guard !Self.isExpired(entry, now: now) else {
+            entries.removeValue(forKey: key)
+            expiredEvictionCount = saturatingAdd(expiredEvictionCount, 1)
+            pruneArrivalQueue()
+            return nil
+        }
+        arrivalQueue.append(key)
+        return DNSHostnameAssociation(
+            address: address.stringValue,
+            hostname: entry.hostname ?? entry.associatedDomain,
+            ageMs: millisecondsBetween(entry.storedAt, and: now),
+            confidence: entry.confidence
+        )
+    }
+
+    /// Exports every in-TTL association with its remaining lifetime, addresses in
+    /// deterministic order, so the host can persist the map across a tunnel restart.
+    mutating func exportSnapshot(now: Date) -> [DNSAssociationExportEntry] {
+        evictExpiredIfNeeded(now: now)
+        return entries.compactMap { key, entry -> DNSAssociationExportEntry? in
+            guard !Self.isExpired(entry, now: now),
+                  let address = IPAddress(bytes: Self.addressBytes(for: key)) else {
+                return nil
+            }
+            let remaining = Policy.ttlSeconds - now.timeIntervalSince(entry.storedAt)
+            guard remaining > 0 else {
+                return nil
+            }
+            return DNSAssociationExportEntry(
+                address: address.stringValue,
+                hostname: entry.hostname,
+                associatedDomain: entry.associatedDomain,
+                remainingTTLMilliseconds: Int((remaining * 1_000).rounded()),
+                confidence: entry.confidence
+            )
+        }
+        .sorted { $0.address < $1.address }
+    }
+
+    /// Pre-seeds the cache from a previously exported snapshot, returning how many entries
+    /// were accepted. Unparseable addresses and entries whose remaining lifetime already
+    /// lapsed are skipped, remaining lifetimes are clamped to the cache TTL, and a live
+    /// entry always wins over a seed: this session's observations are fresher than any
+    /// persisted ones.
+    mutating func importEntries(_ imported: [DNSAssociationExportEntry], now: Date) -> Int {
+        evictExpiredIfNeeded(now: now)
+        var accepted = 0
+        for entry in imported {
+            guard let address = IPAddress(string: entry.address),
+                  !entry.associatedDomain.isEmpty,
+                  entry.remainingTTLMilliseconds > 0 else {
+                continue
+            }
+            let key = Self.key(for: address)
+            guard entries[key] == nil else {
+                continue
+            }
+            let remaining = min(Double(entry.remainingTTLMilliseconds) / 1_000, Policy.ttlSeconds)
+            entries[key] = Entry(
+                associatedDomain: entry.associatedDomain,
+                hostname: entry.hostname,
+                source: .hostSeed,
+                storedAt: now.addingTimeInterval(remaining - Policy.ttlSeconds),
+                confidence: entry.confidence
+            )
+            arrivalQueue.append(key)
+            accepted += 1
+        }
+        trimOverflowIfNeeded()
+        return accepted
+    }
+
+    /// Returns the live entry count plus cumulative eviction counters.
+    func statsSnapshot() -> DNSAssociationCacheStats {
+        DNSAssociationCacheStats(
+            entryCount: entries.count,
+            expiredEvictionCount: expiredEvictionCount,
+            overflowEvictionCount: overflowEvictionCount
+        )
+    }
+
+    /// Scheduler-driven sweep entry point; runs unconditionally because the maintenance
+    /// scheduler already owns the cadence.
+    mutating func sweepExpired(now: Date) {
+        sweepNow(now: now)
+    }
+
     private mutating func evictExpiredIfNeeded(now: Date) {
         guard !entries.isEmpty else {
             return
@@ -93,7 +289,10 @@ internal struct DNSAssociationCache {
            entries.count <= Policy.maxEntries {
             return
         }
+        sweepNow(now: now)
+    }
 
+    private mutating func sweepNow(now: Date) {
         lastSweepAt = now
         let expiredKeys = entries.compactMap { key, entry in
             Self.isExpired(entry, now: now) ? key : nil
@@ -101,6 +300,7 @@ internal struct DNSAssociationCache {
         for key in expiredKeys {
             entries.removeValue(forKey: key)
         }
+        expiredEvictionCount = saturatingAdd(expiredEvictionCount, expiredKeys.count)
         pruneArrivalQueue(force: !expiredKeys.isEmpty)
     }
 
@@ -114,7 +314,9 @@ internal struct DNSAssociationCache {
             guard let oldest = arrivalQueue.popFirst() else {
                 break
             }
-            entries.removeValue(forKey: oldest)
+            if entries.removeValue(forKey: oldest) != nil {
+                overflowEvictionCount = saturatingAdd(overflowEvictionCount, 1)
+            }
         }
         pruneArrivalQueue(force: true)
     }
@@ -173,6 +375,20 @@ internal struct DNSAssociationCache {
         }
         return AddressKey(length: UInt8(bytes.count), high: high, low: low)
     }
+
+    private static func addressBytes(for key: AddressKey) -> Data {
+        if key.length == 4 {
+            return Data((0..<4).map { UInt8(truncatingIfNeeded: key.low >> UInt64((3 - $0) * 8)) })
+        }
+        var bytes = (0..<8).map { UInt8(truncatingIfNeeded: key.high >> UInt64((7 - $0) * 8)) }
+        bytes += (0..<8).map { UInt8(truncatingIfNeeded: key.low >> UInt64((7 - $0) * 8)) }
+        return Data(bytes)
+    }
+}
+
+private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+    let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+    return overflow ? Int.max : sum
 }
 
 private func millisecondsBetween(_ earlier: Date, and later: Date) -> Int {
@@ -0,0 +1,37 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Counters for DNS responses the pipeline validated against recently observed queries.
+/// Decision: the association cache previously trusted any parsed response; these counters make
+/// rejected (potentially spoofed) responses measurable without logging per packet, mirroring
+/// `InvalidPacketCounters`.
+public struct DNSIntegrityCounters: Codable, Sendable, Equatable {
+    public private(set) var matchedResponseCount: Int
+    /// Responses carrying answers whose transaction ID and qname matched no recently seen query.
+    public private(set) var spoofCandidateCount: Int
+
+    public init(matchedResponseCount: Int = 0, spoofCandidateCount: Int = 0) {
+        self.matchedResponseCount = max(0, matchedResponseCount)
+        self.spoofCandidateCount = max(0, spoofCandidateCount)
+    }
+
+    public var isEmpty: Bool {
+        matchedResponseCount == 0 && spoofCandidateCount == 0
+    }
+
+    mutating func recordMatchedResponse() {
+        matchedResponseCount = saturatingAdd(matchedResponseCount, 1)
+    }
+
+    mutating func recordSpoofCandidate() {
+        spoofCandidateCount = saturatingAdd(spoofCandidateCount, 1)
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
@@ -0,0 +1,72 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Privacy treatment applied to DNS names before they enter telemetry records.
+/// Decision: redaction happens once, where parsed names are merged into flow context, so every
+/// downstream surface (live tap, detector stream, rich packet log) sees one consistent form
+/// instead of each consumer re-implementing its own truncation.
+public struct DNSNamePrivacyPolicy: Codable, Sendable, Equatable {
+    /// How DNS query and CNAME names are represented in emitted records.
+    public enum Mode: String, Codable, Sendable, Equatable {
+        /// Names pass through unchanged, bounded only by `maxNameLength`.
+        case plaintext
+        /// Names longer than `maxNameLength` UTF-8 bytes are truncated.
+        case truncated
+        /// Names are replaced with a keyed-hash token; no plaintext leaves the pipeline.
+        case hashed
+    }
+
+    public static let defaultMaxNameLength = 128
+
+    public let mode: Mode
+    public let maxNameLength: Int
+
+    public init(mode: Mode = .plaintext, maxNameLength: Int = DNSNamePrivacyPolicy.defaultMaxNameLength) {
+        self.mode = mode
+        self.maxNameLength = min(max(16, maxNameLength), 1_024)
+    }
+
+    public static let plaintext = DNSNamePrivacyPolicy()
+    public static let hashed = DNSNamePrivacyPolicy(mode: .hashed)
+
+    /// Applies the configured treatment to one DNS name.
+    public func apply(to name: String?) -> String? {
+        guard let name, !name.isEmpty else {
+            return name
+        }
+        switch mode {
+        case .plaintext:
+            return name
+        case .truncated:
+            return Self.truncated(name, maxUTF8Bytes: maxNameLength)
+        case .hashed:
+            return Self.hashedToken(for: name)
+        }
+    }
+
+    /// Truncates on character boundaries so a multi-byte scalar is never split mid-sequence.
+    static func truncated(_ name: String, maxUTF8Bytes: Int) -> String {
+        guard name.utf8.count > maxUTF8Bytes else {
+            return name
+        }
+        var truncated = String(name.prefix(maxUTF8Bytes))
+        while truncated.utf8.count > maxUTF8Bytes, !truncated.isEmpty {
+            truncated.removeLast()
+        }
+        return truncated
+    }
+
+    /// Decision: Swift's `Hasher` is SipHash with one random seed per process launch, which gives the
+    /// per-session keyed hash this policy promises without the package managing key material.
+    /// Tokens are stable within a tunnel session so analytics can still group by name, and they are
+    /// deliberately unstable across sessions so stored telemetry cannot be joined back to plaintext.
+    static func hashedToken(for name: String) -> String {
+        var hasher = Hasher()
+        hasher.combine(name)
+        let value = UInt64(bitPattern: Int64(hasher.finalize()))
+        return String(format: "dns#%016llx", value)
+    }
+}
@@ -0,0 +1,206 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Per-resolver aggregates over matched DNS transactions.
+/// Decision: counts plus total/max latency keep the encoded shape tiny; integrators can derive
+/// averages and timeout rates without the pipeline shipping per-query samples.
+public struct DNSResolverStats: Codable, Sendable, Equatable {
+    /// Resolver IP address the queries were sent to.
+    public let resolverAddress: String
+    public let queryCount: Int
+    public let matchedResponseCount: Int
+    /// Queries that saw no matching response within the timeout window.
+    public let timeoutCount: Int
+    public let totalLatencyMs: Int
+    public let maxLatencyMs: Int
+
+    public init(
+        resolverAddress: String,
+        queryCount: Int = 0,
+        matchedResponseCount: Int = 0,
+        timeoutCount: Int = 0,
+        totalLatencyMs: Int = 0,
+        maxLatencyMs: Int = 0
+    ) {
+        self.resolverAddress = resolverAddress
+        self.queryCount = max(0, queryCount)
+        self.matchedResponseCount = max(0, matchedResponseCount)
+        self.timeoutCount = max(0, timeoutCount)
+        self.totalLatencyMs = max(0, totalLatencyMs)
+        self.maxLatencyMs = max(0, maxLatencyMs)
+    }
+
+    public var averageLatencyMs: Int {
+        guard matchedResponseCount > 0 else {
+            return 0
+        }
+        return totalLatencyMs / matchedResponseCount
+    }
+}
+
+/// Result of matching one DNS response against an outstanding query.
+internal struct DNSTransactionMatch: Sendable, Equatable {
+    let resolverAddress: String
+    let latencyMs: Int
+}
+
+/// Tracks outstanding DNS queries (transaction ID, qname, resolver, send time) and matches responses
+/// to them, turning the tunnel's DNS traffic into per-resolver latency and timeout aggregates.
+/// Decision: matching requires the response to arrive from the resolver the query was sent to, so the
+/// same table doubles as the anti-spoofing gate consulted by `DNSAssociationCache`.
+internal struct DNSTransactionTracker {
+    private struct TransactionKey: Hashable, Sendable {
+        let transactionId: UInt16
+        let queryName: String
+    }
+
+    private struct Outstanding: Sendable {
+        let resolverAddress: String
+        let askedAt: Date
+    }
+
+    private struct Aggregate {
+        var queryCount = 0
+        var matchedResponseCount = 0
+        var timeoutCount = 0
+        var totalLatencyMs = 0
+        var maxLatencyMs = 0
+    }
+
+    private enum Policy {
+        static let timeoutSeconds: TimeInterval = 5
+        static let maxOutstanding = 512
+        static let minimumSweepIntervalSeconds: TimeInterval = 1
+        static let maxTrackedResolvers = 64
+    }
+
+    private var outstanding: [TransactionKey: Outstanding] = [:]
+    private var aggregates: [String: Aggregate] = [:]
+    private var lastSweepAt: Date?
+
+    /// Remembers one observed outbound query so the matching response can be timed and validated.
+    mutating func noteQuery(metadata: PacketMetadata, now: Date) {
+        guard metadata.dnsIsResponse == false,
+              let transactionId = metadata.dnsTransactionId,
+              let queryName = metadata.dnsQueryName, !queryName.isEmpty else {
+            return
+        }
+        sweepTimeoutsIfNeeded(now: now)
+
+        let resolverAddress = metadata.dstAddress.stringValue
+        if outstanding.count >= Policy.maxOutstanding,
+           let oldest = outstanding.min(by: { $0.value.askedAt < $1.value.askedAt }) {
+            outstanding.removeValue(forKey: oldest.key)
+        }
+        let key = TransactionKey(transactionId: transactionId, queryName: queryName.lowercased())
+        outstanding[key] = Outstanding(resolverAddress: resolverAddress, askedAt: now)
+        withAggregate(for: resolverAddress) { aggregate in
+            aggregate.queryCount = saturatingAdd(aggregate.queryCount, 1)
+        }
+    }
+
+    /// Matches one response against the outstanding table and records resolver latency on success.
+    /// Returns `nil` when no fresh query with the same transaction ID and qname was sent to the
+    /// responding address.
+    mutating func matchResponse(metadata: PacketMetadata, now: Date) -> DNSTransactionMatch? {
+        guard metadata.dnsIsResponse == true,
+              let transactionId = metadata.dnsTransactionId,
+              let queryName = metadata.dnsQueryName, !queryName.isEmpty else {
+            return nil
+        }
+        sweepTimeoutsIfNeeded(now: now)
+
+        let key = TransactionKey(transactionId: transactionId, queryName: queryName.lowercased())
+        guard let pending = outstanding[key],
+              now.timeIntervalSince(pending.askedAt) <= Policy.timeoutSeconds,
+              pending.resolverAddress == metadata.srcAddress.stringValue else {
+            return nil
+        }
+        outstanding.removeValue(forKey: key)
+
+        let latencyMs = Self.millisecondsBetween(pending.askedAt, and: now)
+        withAggregate(for: pending.resolverAddress) { aggregate in
+            aggregate.matchedResponseCount = saturatingAdd(aggregate.matchedResponseCount, 1)
+            aggregate.totalLatencyMs = saturatingAdd(aggregate.totalLatencyMs, latencyMs)
+            aggregate.maxLatencyMs = max(aggregate.maxLatencyMs, latencyMs)
+        }
+        return DNSTransactionMatch(resolverAddress: pending.resolverAddress, latencyMs: latencyMs)
+    }
+
+    /// Returns per-resolver aggregates sorted by resolver address for stable snapshots.
+    func statsSnapshot() -> [DNSResolverStats] {
+        aggregates
+            .map { resolverAddress, aggregate in
+                DNSResolverStats(
+                    resolverAddress: resolverAddress,
+                    queryCount: aggregate.queryCount,
+                    matchedResponseCount: aggregate.matchedResponseCount,
+                    timeoutCount: aggregate.timeoutCount,
+                    totalLatencyMs: aggregate.totalLatencyMs,
+                    maxLatencyMs: aggregate.maxLatencyMs
+                )
+            }
+            .sorted { $0.resolverAddress < $1.resolverAddress }
+    }
+
+    /// Scheduler-driven sweep entry point; runs unconditionally because the maintenance
+    /// scheduler already owns the cadence.
+    mutating func sweepTimeouts(now: Date) {
+        guard !outstanding.isEmpty else {
+            return
+        }
+        lastSweepAt = now
+
+        let expired = outstanding.filter { _, pending in
+            now.timeIntervalSince(pending.askedAt) > Policy.timeoutSeconds
+        }
+        for (key, pending) in expired {
+            outstanding.removeValue(forKey: key)
+            withAggregate(for: pending.resolverAddress) { aggregate in
+                aggregate.timeoutCount = saturatingAdd(aggregate.timeoutCount, 1)
+            }
+        }
+    }
+
+    private mutating func sweepTimeoutsIfNeeded(now: Date) {
+        if let lastSweepAt, now.timeIntervalSince(lastSweepAt) < Policy.minimumSweepIntervalSeconds {
+            return
+        }
+        sweepTimeouts(now: now)
+    }
+
+    /// Mutates the aggregate for one resolver, dropping new resolvers past the tracking cap so a
+    /// burst of distinct upstreams cannot grow the table without bound.
+    private mutating func withAggregate(for resolverAddress: String, _ body: (inout Aggregate) -> Void) {
+        if aggregates[resolverAddress] == nil {
+            guard aggregates.count < Policy.maxTrackedResolvers else {
+                return
+            }
+            aggregates[resolverAddress] = Aggregate()
+        }
+        if var aggregate = aggregates[resolverAddress] {
+            body(&aggregate)
+            aggregates[resolverAddress] = aggregate
+        }
+    }
+
+    private static func millisecondsBetween(_ earlier: Date, and later: Date) -> Int {
+        let elapsed = later.timeIntervalSince(earlier)
+        guard elapsed.isFinite, elapsed > 0 else {
+            return 0
+        }
+        let milliseconds = (elapsed * 1_000).rounded()
+        guard milliseconds.isFinite, milliseconds < Double(Int.max) else {
+            return Int.max
+        }
+        return Int(milliseconds)
+    }
+}
+
+private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+    let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+    return overflow ? Int.max : sum
+}
@@ -182,6 +182,9 @@ public struct RichPacketLogRecord: Codable, Sendable, Equatable {
     public let dnsAnswerAddresses: [String]?
     public let registrableDomain: String?
     public let tlsServerName: String?
+    public let tlsECH: Bool?
+    public let tlsJA3: String?
+    public let tlsJA4: String?
     public let quicVersion: UInt32?
     public let quicPacketType: String?
     public let quicDestinationConnectionId: String?
@@ -230,6 +233,9 @@ public struct RichPacketLogRecord: Codable, Sendable, Equatable {
         dnsAnswerAddresses: [String]? = nil,
         registrableDomain: String? = nil,
         tlsServerName: String? = nil,
+        tlsECH: Bool? = nil,
+        tlsJA3: String? = nil,
+        tlsJA4: String? = nil,
         quicVersion: UInt32? = nil,
         quicPacketType: String? = nil,
         quicDestinationConnectionId: String? = nil,
@@ -281,6 +287,9 @@ public struct RichPacketLogRecord: Codable, Sendable, Equatable {
         self.dnsAnswerAddresses = dnsAnswerAddresses
         self.registrableDomain = registrableDomain
         self.tlsServerName = tlsServerName
+        self.tlsECH = tlsECH
+        self.tlsJA3 = tlsJA3
+        self.tlsJA4 = tlsJA4
         self.quicVersion = quicVersion
         self.quicPacketType = quicPacketType
         self.quicDestinationConnectionId = quicDestinationConnectionId
@@ -3,6 +3,7 @@
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
 import Foundation
+import TunnelRuntime
 #if os(Linux)
 import Glibc
 #else
@@ -17,6 +18,34 @@ public enum DomainNormalizer {
         "com.tw", "com.my", "com.sg", "com.tr", "com.sa"
     ]
     private static let cache = BoundedCache<String, String>(countLimit: 4_096)
+    private static let suffixListStore = SuffixListStore()
+
+    /// Lock-protected holder for the optionally installed public-suffix list.
+    /// Safety invariant: every read/write goes through `lock`, mirroring `BoundedCache`.
+    private final class SuffixListStore: @unchecked Sendable {
+        private let lock = NSLock()
+        private var list: PublicSuffixList?
+
+        var current: PublicSuffixList? {
+            lock.lock()
+            defer { lock.unlock() }
+            return list
+        }
+
+        func replace(_ newList: PublicSuffixList?) {
+            lock.lock()
+            defer { lock.unlock() }
+            list = newList
+        }
+    }
+
+    /// Installs an optional public-suffix list that replaces the curated two-part TLD
+    /// heuristic for registrable-domain ("same-site") grouping. Pass `nil` to revert to the
+    /// heuristic. Clears the normalization cache so existing keys re-resolve under the new rules.
+    public static func installPublicSuffixList(_ list: PublicSuffixList?) {
+        suffixListStore.replace(list)
+        cache.removeAll()
+    }
 
     public static func registrableDomain(from name: String?) -> String? {
         guard var name else { return nil }
@@ -28,6 +57,14 @@ public enum DomainNormalizer {
             return cached
         }
 
+        if let installed = suffixListStore.current {
+            guard let result = installed.registrableDomain(of: name) else {
+                return nil
+            }
+            cache.insert(result, for: name)
+            return result
+        }
+
         let labels = name.split(separator: ".").map(String.init)
         guard labels.count >= 2 else {
             cache.insert(name, for: name)
@@ -0,0 +1,67 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Counters for unusual-but-legal IP header constructs the pipeline parsed and forwarded.
+/// Decision: packets with IPv4 options or IPv6 extension chains flow through the parsers
+/// like any other traffic, so regressions there were invisible; these counters make exotic
+/// header shapes measurable without logging per packet, mirroring `InvalidPacketCounters`.
+public struct ExoticHeaderCounters: Codable, Sendable, Equatable {
+    /// IPv4 packets whose IHL exceeds five words (options such as router alert present).
+    public private(set) var ipv4OptionsCount: Int
+    /// IPv6 packets whose first next-header is an extension header.
+    public private(set) var ipv6ExtensionHeaderCount: Int
+    /// IPv6 packets with a zero payload-length field, the RFC 2675 jumbogram encoding.
+    public private(set) var ipv6JumboPayloadCount: Int
+
+    public init(
+        ipv4OptionsCount: Int = 0,
+        ipv6ExtensionHeaderCount: Int = 0,
+        ipv6JumboPayloadCount: Int = 0
+    ) {
+        self.ipv4OptionsCount = max(0, ipv4OptionsCount)
+        self.ipv6ExtensionHeaderCount = max(0, ipv6ExtensionHeaderCount)
+        self.ipv6JumboPayloadCount = max(0, ipv6JumboPayloadCount)
+    }
+
+    public var isEmpty: Bool {
+        ipv4OptionsCount == 0 && ipv6ExtensionHeaderCount == 0 && ipv6JumboPayloadCount == 0
+    }
+
+    /// Tallies the exotic header features present in one already-parsed packet.
+    /// Only the fixed header bytes are inspected; malformed packets never reach this point
+    /// because the fast-path parser rejected them first.
+    mutating func record(packet: Data) {
+        guard let firstByte = packet.first else { return }
+        let version = firstByte >> 4
+        if version == 4 {
+            if (firstByte & 0x0F) > 5 {
+                ipv4OptionsCount = saturatingAdd(ipv4OptionsCount, 1)
+            }
+        } else if version == 6, packet.count >= 40 {
+            let payloadLength = UInt16(packet[packet.startIndex + 4]) << 8 | UInt16(packet[packet.startIndex + 5])
+            if payloadLength == 0 {
+                ipv6JumboPayloadCount = saturatingAdd(ipv6JumboPayloadCount, 1)
+            }
+            if Self.isIPv6ExtensionHeader(packet[packet.startIndex + 6]) {
+                ipv6ExtensionHeaderCount = saturatingAdd(ipv6ExtensionHeaderCount, 1)
+            }
+        }
+    }
+
+    private static func isIPv6ExtensionHeader(_ header: UInt8) -> Bool {
+        switch header {
+        case 0, 43, 44, 50, 51, 60:
+            return true
+        default:
+            return false
+        }
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
@@ -237,3 +237,48 @@ struct FastPacketSummary: Sendable {
         }
     }
 }
+
+/// DNS message header summary produced by the C fast path.
+/// Name decompression and record walking stay in `PacketParser`; this carries only the
+/// fixed 12-byte header fields needed to route a message.
+struct DNSHeaderSummary: Sendable {
+    let transactionID: UInt16
+    let questionCount: UInt16
+    let answerCount: UInt16
+    let authorityCount: UInt16
+    let additionalCount: UInt16
+    let isResponse: Bool
+    let opcode: UInt8
+    let isTruncated: Bool
+    let responseCode: UInt8
+
+    /// - Parameters:
+    ///   - message: Packet bytes containing the DNS message.
+    ///   - payloadOffset: Offset of the DNS payload relative to the start of `message`.
+    init?(message: Data, payloadOffset: Int = 0) {
+        guard payloadOffset >= 0, message.count > payloadOffset else {
+            return nil
+        }
+
+        var raw = rbpi_dns_header_t()
+        let parsed = message.withUnsafeBytes { rawBuffer -> Bool in
+            guard let baseAddress = rawBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
+                return false
+            }
+            return rbpi_parse_dns_header(baseAddress + payloadOffset, rawBuffer.count - payloadOffset, &raw)
+        }
+        guard parsed else {
+            return nil
+        }
+
+        self.transactionID = raw.transaction_id
+        self.questionCount = raw.question_count
+        self.answerCount = raw.answer_count
+        self.authorityCount = raw.authority_count
+        self.additionalCount = raw.additional_count
+        self.isResponse = raw.is_response != 0
+        self.opcode = raw.opcode
+        self.isTruncated = raw.is_truncated != 0
+        self.responseCode = raw.response_code
+    }
+}
@@ -0,0 +1,90 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One breadcrumb-style entry in a flow's recent lifecycle history.
+public struct FlowBreadcrumb: Sendable, Equatable {
+    /// Coarse lifecycle moment the breadcrumb marks.
+    public enum Event: String, Sendable {
+        case opened
+        case metadataUpdated
+        case pinned
+        case closed
+    }
+
+    public let event: Event
+    public let closeReason: FlowCloseReason?
+    public let timestamp: Date
+
+    /// - Parameters:
+    ///   - event: Lifecycle moment being recorded.
+    ///   - closeReason: Populated only for `closed` events.
+    ///   - timestamp: When the event happened, from the pipeline clock.
+    public init(event: Event, closeReason: FlowCloseReason? = nil, timestamp: Date) {
+        self.event = event
+        self.closeReason = closeReason
+        self.timestamp = timestamp
+    }
+}
+
+/// Bounded per-flow ring of recent lifecycle events, keyed by flow hash so an app's
+/// diagnostics view can show exactly what the pipeline did for a failing connection.
+/// Decision: entries survive flow close on purpose — the interesting lookups happen
+/// after a connection has already failed — so capacity is reclaimed in arrival order
+/// rather than on close.
+internal struct FlowBreadcrumbLog: Sendable {
+    enum Policy {
+        static let maxEventsPerFlow = 8
+        static let maxTrackedFlows = 256
+        static let arrivalQueueCompactionThreshold = 128
+    }
+
+    private var events: [UInt64: [FlowBreadcrumb]] = [:]
+    private var arrivalQueue: ArraySlice<UInt64> = []
+
+    /// Appends one breadcrumb to the flow's ring, dropping its oldest entry at capacity.
+    mutating func record(
+        flowHash: UInt64,
+        event: FlowBreadcrumb.Event,
+        closeReason: FlowCloseReason? = nil,
+        now: Date
+    ) {
+        var ring = events[flowHash] ?? []
+        let isNewFlow = ring.isEmpty
+        ring.append(FlowBreadcrumb(event: event, closeReason: closeReason, timestamp: now))
+        if ring.count > Policy.maxEventsPerFlow {
+            ring.removeFirst(ring.count - Policy.maxEventsPerFlow)
+        }
+        events[flowHash] = ring
+        if isNewFlow {
+            arrivalQueue.append(flowHash)
+            trimOverflow()
+        }
+        compactArrivalQueueIfNeeded()
+    }
+
+    /// Returns the flow's recorded breadcrumbs in arrival order, oldest first.
+    func breadcrumbs(forFlowHash flowHash: UInt64) -> [FlowBreadcrumb] {
+        events[flowHash] ?? []
+    }
+
+    /// Flows currently holding breadcrumbs, for diagnostics and tests.
+    var trackedFlowCount: Int {
+        events.count
+    }
+
+    private mutating func trimOverflow() {
+        while events.count > Policy.maxTrackedFlows, let oldest = arrivalQueue.popFirst() {
+            events.removeValue(forKey: oldest)
+        }
+    }
+
+    private mutating func compactArrivalQueueIfNeeded() {
+        guard arrivalQueue.startIndex > Policy.arrivalQueueCompactionThreshold else {
+            return
+        }
+        arrivalQueue = ArraySlice(arrivalQueue)
+    }
+}
@@ -29,6 +29,9 @@ public struct FlowKey: Hashable, Sendable, Codable {
     fileprivate let destinationAddressLow: UInt64?
     fileprivate let sourcePort: UInt16?
     fileprivate let destinationPort: UInt16?
+    /// TUN interface the frame arrived on, for hosts splitting traffic across multiple
+    /// tunnel flows; `nil` on single-interface hosts, keeping their keys unchanged.
+    private(set) var interfaceId: UInt16?
 
     /// - Parameters:
     ///   - src: Source endpoint identity used in tests or text-only call sites.
@@ -50,6 +53,7 @@ public struct FlowKey: Hashable, Sendable, Codable {
         self.destinationAddressLow = nil
         self.sourcePort = nil
         self.destinationPort = nil
+        self.interfaceId = nil
     }
 
     init(
@@ -64,7 +68,8 @@ public struct FlowKey: Hashable, Sendable, Codable {
         destinationAddressHigh: UInt64,
         destinationAddressLow: UInt64,
         sourcePort: UInt16,
-        destinationPort: UInt16
+        destinationPort: UInt16,
+        interfaceId: UInt16? = nil
     ) {
         self.textSource = nil
         self.textDestination = nil
@@ -81,6 +86,15 @@ public struct FlowKey: Hashable, Sendable, Codable {
         self.destinationAddressLow = destinationAddressLow
         self.sourcePort = sourcePort
         self.destinationPort = destinationPort
+        self.interfaceId = interfaceId
+    }
+
+    /// Returns a copy of this key scoped to one TUN interface, so identical 5-tuples seen on
+    /// different interfaces track as distinct flows.
+    func tagged(interfaceId: UInt16?) -> FlowKey {
+        var copy = self
+        copy.interfaceId = interfaceId
+        return copy
     }
 
     /// Source endpoint text for diagnostics and tests.
@@ -185,12 +199,14 @@ public struct FlowKey: Hashable, Sendable, Codable {
                 lhs.destinationAddressHigh == rhs.destinationAddressHigh &&
                 lhs.destinationAddressLow == rhs.destinationAddressLow &&
                 lhs.sourcePort == rhs.sourcePort &&
-                lhs.destinationPort == rhs.destinationPort
+                lhs.destinationPort == rhs.destinationPort &&
+                lhs.interfaceId == rhs.interfaceId
         }
 
         return lhs.textSource == rhs.textSource &&
             lhs.textDestination == rhs.textDestination &&
-            lhs.textProtocol == rhs.textProtocol
+            lhs.textProtocol == rhs.textProtocol &&
+            lhs.interfaceId == rhs.interfaceId
     }
 
     public func hash(into hasher: inout Hasher) {
@@ -208,6 +224,7 @@ public struct FlowKey: Hashable, Sendable, Codable {
             hasher.combine(destinationAddressLow)
             hasher.combine(sourcePort)
             hasher.combine(destinationPort)
+            hasher.combine(interfaceId)
             return
         }
 
@@ -215,6 +232,7 @@ public struct FlowKey: Hashable, Sendable, Codable {
         hasher.combine(textSource)
         hasher.combine(textDestination)
         hasher.combine(textProtocol)
+        hasher.combine(interfaceId)
     }
 
     private static func addressString(high: UInt64, low: UInt64, length: Int) -> String {
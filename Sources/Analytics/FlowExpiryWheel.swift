@@ -0,0 +1,51 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Hashed timing wheel over flow idle deadlines, so expiry sweeps visit only the flows whose
+/// deadline slot has lapsed instead of scanning every tracked flow each poll.
+/// Decision: entries are scheduled once at flow creation and revalidated lazily on drain — the
+/// caller checks the flow's real `lastSeen` and reschedules still-active flows — because updating
+/// the wheel on every packet would put a hash mutation on the per-packet path.
+internal struct FlowExpiryWheel {
+    private let slotSeconds: TimeInterval
+    private var buckets: [Int64: Set<FlowKey>] = [:]
+
+    init(slotSeconds: TimeInterval = 15) {
+        self.slotSeconds = max(1, slotSeconds)
+    }
+
+    /// Schedules one flow for expiry consideration at `deadline`.
+    mutating func schedule(_ flow: FlowKey, deadline: Date) {
+        buckets[slot(for: deadline), default: []].insert(flow)
+    }
+
+    /// Drains and returns every flow whose deadline slot has lapsed. Callers must revalidate each
+    /// candidate against live state: a candidate may have been touched since it was scheduled, or
+    /// already closed through another eviction path.
+    mutating func drainExpiredCandidates(now: Date) -> [FlowKey] {
+        guard !buckets.isEmpty else {
+            return []
+        }
+        let currentSlot = slot(for: now)
+        let dueSlots = buckets.keys.filter { $0 <= currentSlot }.sorted()
+        var candidates: [FlowKey] = []
+        for dueSlot in dueSlots {
+            if let drained = buckets.removeValue(forKey: dueSlot) {
+                candidates.append(contentsOf: drained)
+            }
+        }
+        return candidates
+    }
+
+    /// Occupied slots plus pending flows, for diagnostics.
+    var pendingCount: Int {
+        buckets.values.reduce(0) { $0 + $1.count }
+    }
+
+    private func slot(for date: Date) -> Int64 {
+        Int64((date.timeIntervalSinceReferenceDate / slotSeconds).rounded(.down))
+    }
+}
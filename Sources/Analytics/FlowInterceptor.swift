@@ -0,0 +1,163 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Protocol implemented by package clients that need per-flow lifecycle hooks without patching the pipeline.
+/// Ownership: interceptors are pipeline-owned and invoked inline on the actor's ingest path, so implementations
+/// can keep mutable state without adding their own synchronization.
+/// Contract: hooks run on the tunnel's hot telemetry path. Implementations must avoid blocking I/O, sleeps,
+/// long CPU work, cross-process calls, or unbounded allocations. Implementations that cannot promise this
+/// should be wrapped in `IsolatedFlowInterceptor`, which moves execution off the ingest path.
+/// Decision: the analytics pipeline is an observe-only tap, so hooks inspect flows but cannot rewrite packets.
+public protocol FlowInterceptor: AnyObject {
+    var identifier: String { get }
+    /// Called once when a tracked flow is first observed.
+    func flowOpened(flow: FlowKey)
+    /// Called for each client-originated packet carrying transport payload on a tracked flow.
+    func clientPayload(flow: FlowKey, payloadLength: Int)
+    /// Called for each remote-originated packet carrying transport payload on a tracked flow.
+    func remotePayload(flow: FlowKey, payloadLength: Int)
+    /// Called when a tracked flow closes, whether by TCP teardown or cache eviction.
+    func flowClosed(flow: FlowKey, reason: FlowCloseReason)
+}
+
+public extension FlowInterceptor {
+    func flowOpened(flow: FlowKey) {}
+    func clientPayload(flow: FlowKey, payloadLength: Int) {}
+    func remotePayload(flow: FlowKey, payloadLength: Int) {}
+    func flowClosed(flow: FlowKey, reason: FlowCloseReason) {}
+}
+
+/// Counters and latency aggregates for one isolated interceptor's executor.
+public struct IsolatedFlowInterceptorStats: Sendable, Equatable {
+    /// Events whose callback has run to completion on the executor queue.
+    public let executedEventCount: Int
+    /// Events refused because the bounded hand-off queue was full.
+    public let droppedEventCount: Int
+    /// Events accepted but not yet executed.
+    public let pendingEventCount: Int
+    /// Deepest the hand-off queue has been.
+    public let peakPendingEventCount: Int
+    /// Total wall time spent inside the wrapped callbacks, in milliseconds.
+    public let totalCallbackMilliseconds: Int
+    /// Longest single callback, in milliseconds.
+    public let maxCallbackMilliseconds: Int
+}
+
+/// Executes a wrapped interceptor's callbacks on a dedicated serial queue behind a
+/// bounded hand-off, so a slow callback can never extend the pipeline actor's ingest
+/// pass and callback latency is measurable separately from ingest latency.
+/// Decision: when the hand-off is full, new events are dropped and counted rather than
+/// blocking ingest — the pipeline is an observe-only tap, so shedding observer events
+/// under pressure mirrors how telemetry batch submission sheds.
+/// Ordering: events execute in arrival order on one serial queue, so the wrapped
+/// interceptor keeps the unsynchronized-state guarantee of the inline contract.
+public final class IsolatedFlowInterceptor: FlowInterceptor, @unchecked Sendable {
+    public static let defaultMaxPendingEvents = 1_024
+
+    private enum Event {
+        case opened(FlowKey)
+        case clientPayload(FlowKey, Int)
+        case remotePayload(FlowKey, Int)
+        case closed(FlowKey, FlowCloseReason)
+    }
+
+    private let wrapped: any FlowInterceptor
+    private let queue: DispatchQueue
+    private let maxPendingEvents: Int
+    private let lock = NSLock()
+    private var pendingEventCount = 0
+    private var peakPendingEventCount = 0
+    private var executedEventCount = 0
+    private var droppedEventCount = 0
+    private var totalCallbackNanoseconds: UInt64 = 0
+    private var maxCallbackNanoseconds: UInt64 = 0
+
+    /// - Parameters:
+    ///   - wrapped: Interceptor whose callbacks move onto the dedicated queue. It is only
+    ///     ever invoked from that queue, so it may keep mutable state without locking.
+    ///   - maxPendingEvents: Hand-off depth before new events are dropped and counted.
+    public init(wrapping wrapped: any FlowInterceptor, maxPendingEvents: Int = IsolatedFlowInterceptor.defaultMaxPendingEvents) {
+        self.wrapped = wrapped
+        self.maxPendingEvents = max(1, maxPendingEvents)
+        self.queue = DispatchQueue(label: "com.vpnbridge.analytics.interceptor.\(wrapped.identifier)")
+    }
+
+    public var identifier: String {
+        wrapped.identifier
+    }
+
+    public func flowOpened(flow: FlowKey) {
+        enqueue(.opened(flow))
+    }
+
+    public func clientPayload(flow: FlowKey, payloadLength: Int) {
+        enqueue(.clientPayload(flow, payloadLength))
+    }
+
+    public func remotePayload(flow: FlowKey, payloadLength: Int) {
+        enqueue(.remotePayload(flow, payloadLength))
+    }
+
+    public func flowClosed(flow: FlowKey, reason: FlowCloseReason) {
+        enqueue(.closed(flow, reason))
+    }
+
+    /// Returns the executor's counters and callback-latency aggregates.
+    public func stats() -> IsolatedFlowInterceptorStats {
+        lock.lock()
+        defer { lock.unlock() }
+        return IsolatedFlowInterceptorStats(
+            executedEventCount: executedEventCount,
+            droppedEventCount: droppedEventCount,
+            pendingEventCount: pendingEventCount,
+            peakPendingEventCount: peakPendingEventCount,
+            totalCallbackMilliseconds: Int(totalCallbackNanoseconds / 1_000_000),
+            maxCallbackMilliseconds: Int(maxCallbackNanoseconds / 1_000_000)
+        )
+    }
+
+    /// Blocks until every event accepted so far has executed; test and shutdown aid.
+    public func drainAndWait() {
+        queue.sync {}
+    }
+
+    private func enqueue(_ event: Event) {
+        lock.lock()
+        guard pendingEventCount < maxPendingEvents else {
+            droppedEventCount += 1
+            lock.unlock()
+            return
+        }
+        pendingEventCount += 1
+        peakPendingEventCount = max(peakPendingEventCount, pendingEventCount)
+        lock.unlock()
+
+        queue.async { [self] in
+            let started = DispatchTime.now().uptimeNanoseconds
+            execute(event)
+            let elapsed = DispatchTime.now().uptimeNanoseconds - started
+            lock.lock()
+            pendingEventCount -= 1
+            executedEventCount += 1
+            totalCallbackNanoseconds &+= elapsed
+            maxCallbackNanoseconds = max(maxCallbackNanoseconds, elapsed)
+            lock.unlock()
+        }
+    }
+
+    private func execute(_ event: Event) {
+        switch event {
+        case .opened(let flow):
+            wrapped.flowOpened(flow: flow)
+        case .clientPayload(let flow, let payloadLength):
+            wrapped.clientPayload(flow: flow, payloadLength: payloadLength)
+        case .remotePayload(let flow, let payloadLength):
+            wrapped.remotePayload(flow: flow, payloadLength: payloadLength)
+        case .closed(let flow, let reason):
+            wrapped.flowClosed(flow: flow, reason: reason)
+        }
+    }
+}
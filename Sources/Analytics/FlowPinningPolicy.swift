@@ -0,0 +1,115 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import TunnelRuntime
+
+/// One host-supplied match rule that pins flows against idle and overflow pruning.
+/// Decision: rules match on destination port and/or host suffix because those are the
+/// stable identifiers hosts have for push-notification and VoIP signaling channels;
+/// raw flow keys are pipeline-internal and churn across reconnects.
+public struct FlowPinRule: Codable, Sendable, Equatable {
+    /// Destination port the flow must target, when set.
+    public let destinationPort: UInt16?
+    /// Case-insensitive host suffix (for example `push.apple.com`) matched against the
+    /// flow's TLS server name, DNS association, or registrable domain, when set.
+    public let hostSuffix: String?
+
+    public init(destinationPort: UInt16? = nil, hostSuffix: String? = nil) {
+        self.destinationPort = destinationPort
+        let normalized = hostSuffix.map(HostNormalizer.normalize)
+        self.hostSuffix = (normalized?.isEmpty ?? true) ? nil : normalized
+    }
+
+    /// `true` when the rule has no criteria and can never match.
+    public var isEmpty: Bool {
+        destinationPort == nil && hostSuffix == nil
+    }
+
+    /// Returns `true` when every criterion the rule declares matches the flow.
+    func matches(destinationPort flowPort: UInt16?, hostCandidates: [String]) -> Bool {
+        guard !isEmpty else {
+            return false
+        }
+        if let destinationPort, destinationPort != flowPort {
+            return false
+        }
+        if let hostSuffix {
+            let matched = hostCandidates.contains { candidate in
+                let normalized = HostNormalizer.normalize(candidate)
+                return normalized == hostSuffix || normalized.hasSuffix("." + hostSuffix)
+            }
+            guard matched else {
+                return false
+            }
+        }
+        return true
+    }
+}
+
+/// Batched flow-pinning edits committed as one atomic policy swap.
+/// Decision: the pipeline actor serializes policy swaps against packet ingest, so committing a
+/// transaction as a single replacement guarantees no batch is ever admitted under a half-applied
+/// rule set. Rolling back is simply discarding the value without committing it; the deltas are
+/// resolved against the live policy at commit time, so concurrent edits compose instead of
+/// clobbering each other.
+public struct FlowPinningTransaction: Sendable, Equatable {
+    public private(set) var addedRules: [FlowPinRule] = []
+    public private(set) var removedRules: [FlowPinRule] = []
+    /// Replaces the pinned-flow cap at commit when set; `nil` keeps the live policy's cap.
+    public var maxPinnedFlows: Int?
+
+    public init() {}
+
+    /// Stages one rule for installation; empty rules are dropped because they can never match.
+    public mutating func add(_ rule: FlowPinRule) {
+        guard !rule.isEmpty, !addedRules.contains(rule) else {
+            return
+        }
+        addedRules.append(rule)
+    }
+
+    /// Stages one rule for removal.
+    public mutating func remove(_ rule: FlowPinRule) {
+        guard !removedRules.contains(rule) else {
+            return
+        }
+        removedRules.append(rule)
+    }
+
+    /// `true` when committing would leave any policy unchanged.
+    public var isEmpty: Bool {
+        addedRules.isEmpty && removedRules.isEmpty && maxPinnedFlows == nil
+    }
+
+    /// Resolves the staged deltas against a live policy into the replacement to install.
+    func applying(to policy: FlowPinningPolicy) -> FlowPinningPolicy {
+        var rules = policy.rules.filter { !removedRules.contains($0) }
+        for rule in addedRules where !rules.contains(rule) {
+            rules.append(rule)
+        }
+        return FlowPinningPolicy(rules: rules, maxPinnedFlows: maxPinnedFlows ?? policy.maxPinnedFlows)
+    }
+}
+
+/// Host-supplied pinning policy exempting critical long-lived flows from pruning.
+/// Contract: pinning only shields flows from idle-TTL and overflow eviction; real
+/// protocol closes (FIN/RST) still tear pinned flows down normally.
+public struct FlowPinningPolicy: Codable, Sendable, Equatable {
+    public let rules: [FlowPinRule]
+    /// Upper bound on concurrently pinned flows so a broad rule cannot exempt the
+    /// whole flow table from memory-pressure trimming.
+    public let maxPinnedFlows: Int
+
+    public init(rules: [FlowPinRule] = [], maxPinnedFlows: Int = 64) {
+        self.rules = rules
+        self.maxPinnedFlows = min(max(maxPinnedFlows, 1), 512)
+    }
+
+    public static let disabled = FlowPinningPolicy()
+
+    public var isEnabled: Bool {
+        rules.contains { !$0.isEmpty }
+    }
+}
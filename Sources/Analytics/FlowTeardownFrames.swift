@@ -0,0 +1,309 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Synthesized IP frames that abort live flows when the tunnel is about to go away.
+/// Decision: a stopping tunnel cannot hand its flows to anything, so the kindest exit is an
+/// explicit failure the client stack acts on immediately — a TCP RST stamped with the client's
+/// last-acknowledged sequence, or an ICMP port-unreachable quoting the flow's original header —
+/// instead of leaving apps to time out against a dead interface. Frames are best effort: a
+/// client whose receive window moved past the recorded sequence may discard the reset, which
+/// leaves it no worse off than getting nothing.
+enum FlowTeardownFrames {
+    /// Sequence bookkeeping captured from the client's most recent acknowledged segment.
+    struct TCPState: Sendable, Equatable {
+        /// What the client expects to receive next; the forged reset's sequence number.
+        let clientExpectedSequence: UInt32
+        /// What the client will send next; the forged reset's acknowledgment number.
+        let clientNextSequence: UInt32
+    }
+
+    /// Extracts reset bookkeeping from a raw client-originated TCP packet.
+    /// Returns `nil` until the client has acknowledged remote data (pure SYNs carry no
+    /// usable acknowledgment), and for packets without a directly-reachable TCP header.
+    static func parseTCPState(packet: Data) -> TCPState? {
+        packet.withUnsafeBytes { buffer -> TCPState? in
+            guard buffer.count >= 40, let base = buffer.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
+                return nil
+            }
+            let tcpStart: Int
+            let totalLength: Int
+            switch (base[0] >> 4) & 0x0f {
+            case 4:
+                let headerLength = Int(base[0] & 0x0f) * 4
+                guard headerLength >= 20, base[9] == 6 else {
+                    return nil
+                }
+                totalLength = min(buffer.count, Int(base[2]) << 8 | Int(base[3]))
+                tcpStart = headerLength
+            case 6:
+                guard buffer.count >= 60, base[6] == 6 else {
+                    return nil
+                }
+                totalLength = min(buffer.count, 40 + (Int(base[4]) << 8 | Int(base[5])))
+                tcpStart = 40
+            default:
+                return nil
+            }
+            guard totalLength >= tcpStart + 20 else {
+                return nil
+            }
+            let flags = base[tcpStart + 13]
+            guard flags & 0x10 != 0 else {
+                return nil
+            }
+            let dataOffset = Int(base[tcpStart + 12] >> 4) * 4
+            guard dataOffset >= 20, totalLength >= tcpStart + dataOffset else {
+                return nil
+            }
+            let sequence = load32(base, at: tcpStart + 4)
+            let acknowledgment = load32(base, at: tcpStart + 8)
+            var next = sequence &+ UInt32(totalLength - tcpStart - dataOffset)
+            if flags & 0x02 != 0 {
+                next &+= 1
+            }
+            if flags & 0x01 != 0 {
+                next &+= 1
+            }
+            return TCPState(clientExpectedSequence: acknowledgment, clientNextSequence: next)
+        }
+    }
+
+    /// Builds a remote-to-client RST|ACK segment for an established TCP flow.
+    /// Returns `nil` when the endpoint addresses are not a matching IPv4 or IPv6 pair.
+    static func makeTCPReset(
+        clientAddress: [UInt8],
+        clientPort: UInt16,
+        remoteAddress: [UInt8],
+        remotePort: UInt16,
+        state: TCPState
+    ) -> Data? {
+        guard clientAddress.count == remoteAddress.count,
+              clientAddress.count == 4 || clientAddress.count == 16 else {
+            return nil
+        }
+        var segment = [UInt8](repeating: 0, count: 20)
+        store16(&segment, at: 0, remotePort)
+        store16(&segment, at: 2, clientPort)
+        store32(&segment, at: 4, state.clientExpectedSequence)
+        store32(&segment, at: 8, state.clientNextSequence)
+        segment[12] = 0x50
+        segment[13] = 0x14
+        let checksum = transportChecksum(
+            source: remoteAddress,
+            destination: clientAddress,
+            protocolNumber: 6,
+            payload: segment
+        )
+        store16(&segment, at: 16, checksum)
+        return wrapIP(source: remoteAddress, destination: clientAddress, protocolNumber: 6, payload: segment)
+    }
+
+    /// Builds a remote-to-client ICMP port-unreachable for a UDP flow, quoting a synthesized
+    /// copy of the flow's original client-to-remote IP and UDP headers so the client stack can
+    /// match the error back to its socket.
+    /// Returns `nil` when the endpoint addresses are not a matching IPv4 or IPv6 pair.
+    static func makeUDPUnreachable(
+        clientAddress: [UInt8],
+        clientPort: UInt16,
+        remoteAddress: [UInt8],
+        remotePort: UInt16
+    ) -> Data? {
+        guard clientAddress.count == remoteAddress.count,
+              clientAddress.count == 4 || clientAddress.count == 16 else {
+            return nil
+        }
+        var embeddedUDP = [UInt8](repeating: 0, count: 8)
+        store16(&embeddedUDP, at: 0, clientPort)
+        store16(&embeddedUDP, at: 2, remotePort)
+        store16(&embeddedUDP, at: 4, 8)
+
+        if clientAddress.count == 4 {
+            let embedded = ipv4Header(
+                source: clientAddress,
+                destination: remoteAddress,
+                protocolNumber: 17,
+                payloadLength: embeddedUDP.count
+            ) + embeddedUDP
+            var message = [UInt8](repeating: 0, count: 8)
+            message[0] = 3
+            message[1] = 3
+            message += embedded
+            let checksum = internetChecksum(message, initial: 0)
+            store16(&message, at: 2, checksum)
+            return wrapIP(source: remoteAddress, destination: clientAddress, protocolNumber: 1, payload: message)
+        }
+
+        let embedded = ipv6Header(
+            source: clientAddress,
+            destination: remoteAddress,
+            protocolNumber: 17,
+            payloadLength: embeddedUDP.count
+        ) + embeddedUDP
+        var message = [UInt8](repeating: 0, count: 8)
+        message[0] = 1
+        message[1] = 4
+        message += embedded
+        let checksum = transportChecksum(
+            source: remoteAddress,
+            destination: clientAddress,
+            protocolNumber: 58,
+            payload: message
+        )
+        store16(&message, at: 2, checksum)
+        return wrapIP(source: remoteAddress, destination: clientAddress, protocolNumber: 58, payload: message)
+    }
+
+    /// Unpacks a fast-path packed address back into network-order bytes.
+    /// Returns an empty array for lengths other than 4 or 16.
+    static func addressBytes(high: UInt64, low: UInt64, length: UInt8) -> [UInt8] {
+        switch length {
+        case 4:
+            return [
+                UInt8(truncatingIfNeeded: low >> 24),
+                UInt8(truncatingIfNeeded: low >> 16),
+                UInt8(truncatingIfNeeded: low >> 8),
+                UInt8(truncatingIfNeeded: low)
+            ]
+        case 16:
+            var bytes = [UInt8]()
+            bytes.reserveCapacity(16)
+            for shift in stride(from: 56, through: 0, by: -8) {
+                bytes.append(UInt8(truncatingIfNeeded: high >> UInt64(shift)))
+            }
+            for shift in stride(from: 56, through: 0, by: -8) {
+                bytes.append(UInt8(truncatingIfNeeded: low >> UInt64(shift)))
+            }
+            return bytes
+        default:
+            return []
+        }
+    }
+
+    /// Address family hint matching the frame's IP version, for packet-flow writes.
+    static func addressFamily(forIPVersion version: UInt8) -> Int32 {
+        version == 6 ? AF_INET6 : AF_INET
+    }
+
+    static func wrapIP(
+        source: [UInt8],
+        destination: [UInt8],
+        protocolNumber: UInt8,
+        payload: [UInt8]
+    ) -> Data {
+        if source.count == 4 {
+            return Data(
+                ipv4Header(
+                    source: source,
+                    destination: destination,
+                    protocolNumber: protocolNumber,
+                    payloadLength: payload.count
+                ) + payload
+            )
+        }
+        return Data(
+            ipv6Header(
+                source: source,
+                destination: destination,
+                protocolNumber: protocolNumber,
+                payloadLength: payload.count
+            ) + payload
+        )
+    }
+
+    private static func ipv4Header(
+        source: [UInt8],
+        destination: [UInt8],
+        protocolNumber: UInt8,
+        payloadLength: Int
+    ) -> [UInt8] {
+        var header = [UInt8](repeating: 0, count: 20)
+        header[0] = 0x45
+        store16(&header, at: 2, UInt16(20 + payloadLength))
+        store16(&header, at: 6, 0x4000)
+        header[8] = 64
+        header[9] = protocolNumber
+        header.replaceSubrange(12..<16, with: source)
+        header.replaceSubrange(16..<20, with: destination)
+        let checksum = internetChecksum(header, initial: 0)
+        store16(&header, at: 10, checksum)
+        return header
+    }
+
+    private static func ipv6Header(
+        source: [UInt8],
+        destination: [UInt8],
+        protocolNumber: UInt8,
+        payloadLength: Int
+    ) -> [UInt8] {
+        var header = [UInt8](repeating: 0, count: 40)
+        header[0] = 0x60
+        store16(&header, at: 4, UInt16(payloadLength))
+        header[6] = protocolNumber
+        header[7] = 64
+        header.replaceSubrange(8..<24, with: source)
+        header.replaceSubrange(24..<40, with: destination)
+        return header
+    }
+
+    /// RFC 1071 ones-complement checksum over a transport payload plus its pseudo-header.
+    static func transportChecksum(
+        source: [UInt8],
+        destination: [UInt8],
+        protocolNumber: UInt8,
+        payload: [UInt8]
+    ) -> UInt16 {
+        var sum: UInt32 = sumWords(source) &+ sumWords(destination)
+        sum &+= UInt32(protocolNumber)
+        sum &+= UInt32(payload.count)
+        return internetChecksum(payload, initial: sum)
+    }
+
+    static func internetChecksum(_ bytes: [UInt8], initial: UInt32) -> UInt16 {
+        var sum = initial &+ sumWords(bytes)
+        while sum > 0xffff {
+            sum = (sum & 0xffff) &+ (sum >> 16)
+        }
+        let folded = UInt16(truncatingIfNeeded: sum)
+        return folded == 0xffff ? 0xffff : ~folded
+    }
+
+    private static func sumWords(_ bytes: [UInt8]) -> UInt32 {
+        var sum: UInt32 = 0
+        var index = 0
+        while index + 1 < bytes.count {
+            sum &+= UInt32(bytes[index]) << 8 | UInt32(bytes[index + 1])
+            index += 2
+        }
+        if index < bytes.count {
+            sum &+= UInt32(bytes[index]) << 8
+        }
+        return sum
+    }
+
+    private static func load32(_ base: UnsafePointer<UInt8>, at offset: Int) -> UInt32 {
+        UInt32(base[offset]) << 24
+            | UInt32(base[offset + 1]) << 16
+            | UInt32(base[offset + 2]) << 8
+            | UInt32(base[offset + 3])
+    }
+
+    static func store16(_ bytes: inout [UInt8], at offset: Int, _ value: UInt16) {
+        bytes[offset] = UInt8(truncatingIfNeeded: value >> 8)
+        bytes[offset + 1] = UInt8(truncatingIfNeeded: value)
+    }
+
+    private static func store32(_ bytes: inout [UInt8], at offset: Int, _ value: UInt32) {
+        bytes[offset] = UInt8(truncatingIfNeeded: value >> 24)
+        bytes[offset + 1] = UInt8(truncatingIfNeeded: value >> 16)
+        bytes[offset + 2] = UInt8(truncatingIfNeeded: value >> 8)
+        bytes[offset + 3] = UInt8(truncatingIfNeeded: value)
+    }
+}
@@ -0,0 +1,264 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One observed HTTP request/response exchange on a flow with visible payload, with the
+/// timing needed for app-level waterfall analysis through the tunnel.
+public struct HTTPExchange: Sendable, Equatable {
+    /// Flow hash of the request-side context.
+    public let flowHash: UInt64
+    /// "1.0", "1.1", or "2". HTTP/2 exchanges carry timing only — header fields are
+    /// HPACK-compressed and are not decoded.
+    public let httpVersion: String
+    public let method: String?
+    public let target: String?
+    /// Host header value, when one appeared in the inspected prefix.
+    public let host: String?
+    public let statusCode: Int?
+    public let requestAt: Date
+    public let responseAt: Date?
+
+    /// Milliseconds from request to first response bytes; `nil` while unanswered.
+    public var responseLatencyMs: Int? {
+        guard let responseAt else {
+            return nil
+        }
+        return Int((responseAt.timeIntervalSince(requestAt) * 1_000).rounded())
+    }
+
+    /// - Parameters:
+    ///   - flowHash: Flow hash of the request-side context.
+    ///   - httpVersion: HTTP version label.
+    ///   - method: Request method, when parsed.
+    ///   - target: Request target (path), when parsed.
+    ///   - host: Host header value, when present.
+    ///   - statusCode: Response status, when the response arrived.
+    ///   - requestAt: Pipeline-clock time of the request packet.
+    ///   - responseAt: Pipeline-clock time of the first response packet.
+    public init(
+        flowHash: UInt64,
+        httpVersion: String,
+        method: String?,
+        target: String?,
+        host: String?,
+        statusCode: Int?,
+        requestAt: Date,
+        responseAt: Date?
+    ) {
+        self.flowHash = flowHash
+        self.httpVersion = httpVersion
+        self.method = method
+        self.target = target
+        self.host = host
+        self.statusCode = statusCode
+        self.requestAt = requestAt
+        self.responseAt = responseAt
+    }
+}
+
+/// Parses HTTP/1.x request/status lines and the HTTP/2 connection preface from the
+/// leading bytes of a transport payload. Only flows where payload is actually visible
+/// (plaintext port 80, or lab TLS termination) ever reach this parser.
+public enum HTTPMetadataExtractor {
+    /// Bytes of payload the parser will inspect; request lines and the Host header of
+    /// real traffic fit comfortably, and anything longer is not worth the scan.
+    static let inspectionByteLimit = 1_024
+
+    private static let knownMethods: Set<String> = [
+        "GET", "HEAD", "POST", "PUT", "DELETE", "OPTIONS", "PATCH", "CONNECT", "TRACE"
+    ]
+    private static let http2Preface = Data("PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n".utf8)
+
+    public enum Message: Sendable, Equatable {
+        case request(method: String, target: String, version: String, host: String?)
+        /// `upgrade` carries the Upgrade header value on 101 responses so callers can tell
+        /// WebSocket switches from other protocol upgrades; `nil` on every other status.
+        case response(version: String, statusCode: Int, upgrade: String?)
+        case http2Preface
+    }
+
+    /// Parses the start of one transport payload into an HTTP message, or `nil` when the
+    /// payload is not the start of an HTTP message (continuation segments included).
+    public static func parse(payload: Data) -> Message? {
+        guard !payload.isEmpty else {
+            return nil
+        }
+        let window = payload.prefix(inspectionByteLimit)
+        if window.starts(with: http2Preface) {
+            return .http2Preface
+        }
+        guard let prefix = String(data: Data(window), encoding: .ascii) else {
+            return nil
+        }
+        guard let lineEnd = prefix.range(of: "\r\n") else {
+            return nil
+        }
+        let line = prefix[prefix.startIndex ..< lineEnd.lowerBound]
+        let parts = line.split(separator: " ", maxSplits: 2, omittingEmptySubsequences: false)
+        guard parts.count == 3 else {
+            return nil
+        }
+
+        if parts[0].hasPrefix("HTTP/1.") {
+            guard let statusCode = Int(parts[1]), (100 ... 599).contains(statusCode) else {
+                return nil
+            }
+            let upgrade = statusCode == 101 ? headerValue(named: "upgrade", in: prefix[lineEnd.upperBound...]) : nil
+            return .response(version: String(parts[0].dropFirst(5)), statusCode: statusCode, upgrade: upgrade)
+        }
+
+        guard knownMethods.contains(String(parts[0])), parts[2].hasPrefix("HTTP/1.") else {
+            return nil
+        }
+        return .request(
+            method: String(parts[0]),
+            target: String(parts[1]),
+            version: String(parts[2].dropFirst(5)),
+            host: headerValue(named: "host", in: prefix[lineEnd.upperBound...])
+        )
+    }
+
+    private static func headerValue(named name: String, in headers: Substring) -> String? {
+        for line in headers.split(separator: "\r\n", omittingEmptySubsequences: true) {
+            guard let colon = line.firstIndex(of: ":") else {
+                continue
+            }
+            guard line[line.startIndex ..< colon].lowercased() == name else {
+                continue
+            }
+            let value = line[line.index(after: colon)...].trimmingCharacters(in: .whitespaces)
+            return value.isEmpty ? nil : value
+        }
+        return nil
+    }
+}
+
+/// Bounded store of observed HTTP exchanges, keyed by flow pair so a response on the
+/// reverse-direction sibling context completes the request recorded on the outbound one.
+/// Decision: exchanges survive flow close like breadcrumbs do — waterfall analysis reads
+/// them after the page load has finished — so capacity is reclaimed oldest-first.
+internal struct HTTPExchangeLog: Sendable {
+    enum Policy {
+        static let maxExchanges = 256
+        static let maxPendingRequests = 512
+    }
+
+    private struct PendingRequest: Sendable {
+        let flowHash: UInt64
+        let httpVersion: String
+        let method: String?
+        let target: String?
+        let host: String?
+        let requestAt: Date
+    }
+
+    private var completedExchanges: [HTTPExchange] = []
+    private var pendingByPair: [String: PendingRequest] = [:]
+    private var pendingArrivalQueue: ArraySlice<String> = []
+
+    /// Records one parsed HTTP message observed on a flow pair.
+    /// A request replaces any unanswered request on the same pair (pipelining is rare
+    /// enough that per-pair depth-one matching keeps the store simple and bounded).
+    mutating func record(
+        pairKey: String,
+        flowHash: UInt64,
+        message: HTTPMetadataExtractor.Message,
+        now: Date
+    ) {
+        switch message {
+        case .request(let method, let target, let version, let host):
+            storePending(
+                pairKey: pairKey,
+                request: PendingRequest(
+                    flowHash: flowHash,
+                    httpVersion: version,
+                    method: method,
+                    target: target,
+                    host: host,
+                    requestAt: now
+                )
+            )
+        case .http2Preface:
+            storePending(
+                pairKey: pairKey,
+                request: PendingRequest(
+                    flowHash: flowHash,
+                    httpVersion: "2",
+                    method: nil,
+                    target: nil,
+                    host: nil,
+                    requestAt: now
+                )
+            )
+        case .response(_, let statusCode, _):
+            guard let pending = pendingByPair.removeValue(forKey: pairKey) else {
+                return
+            }
+            appendExchange(
+                HTTPExchange(
+                    flowHash: pending.flowHash,
+                    httpVersion: pending.httpVersion,
+                    method: pending.method,
+                    target: pending.target,
+                    host: pending.host,
+                    statusCode: statusCode,
+                    requestAt: pending.requestAt,
+                    responseAt: now
+                )
+            )
+        }
+    }
+
+    /// Completes an HTTP/2 pending entry with response timing only; HPACK is not decoded,
+    /// so there is no status to attach.
+    mutating func recordHTTP2ResponseBytes(pairKey: String, now: Date) {
+        guard let pending = pendingByPair[pairKey], pending.httpVersion == "2" else {
+            return
+        }
+        pendingByPair.removeValue(forKey: pairKey)
+        appendExchange(
+            HTTPExchange(
+                flowHash: pending.flowHash,
+                httpVersion: pending.httpVersion,
+                method: nil,
+                target: nil,
+                host: nil,
+                statusCode: nil,
+                requestAt: pending.requestAt,
+                responseAt: now
+            )
+        )
+    }
+
+    /// Whether the pair currently has an unanswered HTTP/2 request.
+    func hasPendingHTTP2Request(pairKey: String) -> Bool {
+        pendingByPair[pairKey]?.httpVersion == "2"
+    }
+
+    /// Completed exchanges in completion order, oldest first.
+    var exchanges: [HTTPExchange] {
+        completedExchanges
+    }
+
+    private mutating func storePending(pairKey: String, request: PendingRequest) {
+        if pendingByPair[pairKey] == nil {
+            pendingArrivalQueue.append(pairKey)
+        }
+        pendingByPair[pairKey] = request
+        while pendingByPair.count > Policy.maxPendingRequests, let oldest = pendingArrivalQueue.popFirst() {
+            pendingByPair.removeValue(forKey: oldest)
+        }
+        if pendingArrivalQueue.startIndex > Policy.maxPendingRequests {
+            pendingArrivalQueue = ArraySlice(pendingArrivalQueue)
+        }
+    }
+
+    private mutating func appendExchange(_ exchange: HTTPExchange) {
+        completedExchanges.append(exchange)
+        if completedExchanges.count > Policy.maxExchanges {
+            completedExchanges.removeFirst(completedExchanges.count - Policy.maxExchanges)
+        }
+    }
+}
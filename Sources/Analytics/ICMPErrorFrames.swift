@@ -0,0 +1,155 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Synthesized ICMP error frames for packets the engine refuses to forward.
+/// Decision: silently dropping a TTL-expired or policy-refused packet leaves client
+/// diagnostics blind — traceroute probes time out instead of naming a hop, and UDP
+/// probes to unserved virtual addresses wait out their full timeout. Each builder
+/// quotes the offending packet's IP header plus its first eight payload bytes, per
+/// RFC 792 and RFC 4443, so the client stack can route the error back to the
+/// originating socket. Frames are best effort: a malformed or truncated offender
+/// yields `nil` and the caller falls back to the silent drop it had before.
+public enum ICMPErrorFrames {
+    /// Builds a Time Exceeded (ICMPv4 type 11 code 0, ICMPv6 type 3 code 0) answering
+    /// a packet whose TTL or hop limit ran out at the tunnel gateway.
+    /// Returns `nil` when the packet is malformed or `gatewayAddress` does not match
+    /// the packet's IP version.
+    public static func makeTimeExceeded(expiredPacket: Data, gatewayAddress: [UInt8]) -> Data? {
+        makeError(
+            offendingPacket: expiredPacket,
+            source: gatewayAddress,
+            icmpv4Type: 11,
+            icmpv4Code: 0,
+            icmpv6Type: 3,
+            icmpv6Code: 0
+        )
+    }
+
+    /// Builds a Destination Unreachable, administratively prohibited (ICMPv4 type 3
+    /// code 13, ICMPv6 type 1 code 1) answering a packet a configured policy refused.
+    /// Returns `nil` when the packet is malformed or `gatewayAddress` does not match
+    /// the packet's IP version.
+    public static func makeAdminProhibited(rejectedPacket: Data, gatewayAddress: [UInt8]) -> Data? {
+        makeError(
+            offendingPacket: rejectedPacket,
+            source: gatewayAddress,
+            icmpv4Type: 3,
+            icmpv4Code: 13,
+            icmpv6Type: 1,
+            icmpv6Code: 1
+        )
+    }
+
+    /// Builds a Destination Unreachable, port (ICMPv4 type 3 code 3, ICMPv6 type 1
+    /// code 4) answering a UDP datagram sent to an address that hosts no listener.
+    /// The reply is sourced from the datagram's own destination, matching what a real
+    /// host with a closed port would send. Returns `nil` for malformed packets.
+    public static func makePortUnreachable(undeliverablePacket: Data) -> Data? {
+        makeError(
+            offendingPacket: undeliverablePacket,
+            source: nil,
+            icmpv4Type: 3,
+            icmpv4Code: 3,
+            icmpv6Type: 1,
+            icmpv6Code: 4
+        )
+    }
+
+    /// Builds a Fragmentation Needed (ICMPv4 type 3 code 4) or Packet Too Big (ICMPv6
+    /// type 2 code 0) answering a packet larger than the delivery path can carry.
+    /// `mtu` lands in the next-hop MTU field so the sender's path MTU discovery
+    /// converges instead of retrying the same oversized frame. The reply is sourced
+    /// from the packet's own destination, like `makePortUnreachable`.
+    /// Returns `nil` for malformed packets.
+    public static func makePacketTooBig(oversizedPacket: Data, mtu: Int) -> Data? {
+        makeError(
+            offendingPacket: oversizedPacket,
+            source: nil,
+            icmpv4Type: 3,
+            icmpv4Code: 4,
+            icmpv6Type: 2,
+            icmpv6Code: 0,
+            nextHopMTU: UInt32(clamping: mtu)
+        )
+    }
+
+    /// Builds one ICMP error frame addressed back to the offending packet's source.
+    /// A `nil` source replies from the offending packet's destination address.
+    /// `nextHopMTU` fills the RFC 1191 next-hop MTU field (v4) or the RFC 4443 MTU
+    /// field (v6); the default leaves those bytes zero, as the other errors require.
+    private static func makeError(
+        offendingPacket: Data,
+        source: [UInt8]?,
+        icmpv4Type: UInt8,
+        icmpv4Code: UInt8,
+        icmpv6Type: UInt8,
+        icmpv6Code: UInt8,
+        nextHopMTU: UInt32? = nil
+    ) -> Data? {
+        let bytes = [UInt8](offendingPacket)
+        guard let first = bytes.first else {
+            return nil
+        }
+        switch (first >> 4) & 0x0f {
+        case 4:
+            let headerLength = Int(bytes[0] & 0x0f) * 4
+            guard bytes.count >= 20, headerLength >= 20, bytes.count >= headerLength else {
+                return nil
+            }
+            let replySource = source ?? Array(bytes[16..<20])
+            guard replySource.count == 4 else {
+                return nil
+            }
+            var message = [UInt8](repeating: 0, count: 8)
+            message[0] = icmpv4Type
+            message[1] = icmpv4Code
+            if let nextHopMTU {
+                FlowTeardownFrames.store16(&message, at: 6, UInt16(clamping: nextHopMTU))
+            }
+            message += bytes[0..<min(bytes.count, headerLength + 8)]
+            let checksum = FlowTeardownFrames.internetChecksum(message, initial: 0)
+            FlowTeardownFrames.store16(&message, at: 2, checksum)
+            return FlowTeardownFrames.wrapIP(
+                source: replySource,
+                destination: Array(bytes[12..<16]),
+                protocolNumber: 1,
+                payload: message
+            )
+        case 6:
+            guard bytes.count >= 40 else {
+                return nil
+            }
+            let replySource = source ?? Array(bytes[24..<40])
+            guard replySource.count == 16 else {
+                return nil
+            }
+            let replyDestination = Array(bytes[8..<24])
+            var message = [UInt8](repeating: 0, count: 8)
+            message[0] = icmpv6Type
+            message[1] = icmpv6Code
+            if let nextHopMTU {
+                FlowTeardownFrames.store16(&message, at: 4, UInt16(truncatingIfNeeded: nextHopMTU >> 16))
+                FlowTeardownFrames.store16(&message, at: 6, UInt16(truncatingIfNeeded: nextHopMTU))
+            }
+            message += bytes[0..<min(bytes.count, 48)]
+            let checksum = FlowTeardownFrames.transportChecksum(
+                source: replySource,
+                destination: replyDestination,
+                protocolNumber: 58,
+                payload: message
+            )
+            FlowTeardownFrames.store16(&message, at: 2, checksum)
+            return FlowTeardownFrames.wrapIP(
+                source: replySource,
+                destination: replyDestination,
+                protocolNumber: 58,
+                payload: message
+            )
+        default:
+            return nil
+        }
+    }
+}
@@ -0,0 +1,91 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// How aggressively the pipeline validates packets beyond the fast-path parse.
+/// Decision: the host adapter normally validates checksums before packets reach the extension,
+/// so `permissive` is the default; `strict` exists for paths where the adapter is suspect and
+/// corruption would otherwise flow through silently.
+public enum PacketValidationMode: String, Codable, Sendable, Equatable {
+    /// Forward packets even when checksums are wrong or header fields are suspicious.
+    /// Packets the parser cannot read at all are still counted and dropped.
+    case permissive
+    /// Reject and count packets with incorrect IPv4 header or L4 checksums, or illegal
+    /// header combinations (SYN+FIN, zero UDP checksum over IPv6).
+    case strict
+}
+
+/// Coarse reason a packet failed fast-path parsing.
+public enum InvalidPacketReason: String, Codable, Sendable, Equatable {
+    /// Shorter than the minimum header length for its claimed IP version.
+    case truncated
+    /// Version nibble is neither 4 nor 6.
+    case unsupportedVersion
+    /// Long enough for its version but rejected by the parser (bad lengths, unknown transport framing).
+    case malformedHeader
+    /// An IPv4 header or L4 checksum failed verification. Only counted under
+    /// `PacketValidationMode.strict`; the permissive default trusts the host stack.
+    case checksumMismatch
+}
+
+/// Direction-aware counters for packets the pipeline could not parse.
+/// Decision: the hot path previously dropped unparseable packets silently, which made
+/// malformed-input rates invisible; these counters make them measurable without logging per packet.
+public struct InvalidPacketCounters: Codable, Sendable, Equatable {
+    public private(set) var outboundCount: Int
+    public private(set) var inboundCount: Int
+    public private(set) var truncatedCount: Int
+    public private(set) var unsupportedVersionCount: Int
+    public private(set) var malformedHeaderCount: Int
+    public private(set) var checksumMismatchCount: Int
+
+    public init(
+        outboundCount: Int = 0,
+        inboundCount: Int = 0,
+        truncatedCount: Int = 0,
+        unsupportedVersionCount: Int = 0,
+        malformedHeaderCount: Int = 0,
+        checksumMismatchCount: Int = 0
+    ) {
+        self.outboundCount = max(0, outboundCount)
+        self.inboundCount = max(0, inboundCount)
+        self.truncatedCount = max(0, truncatedCount)
+        self.unsupportedVersionCount = max(0, unsupportedVersionCount)
+        self.malformedHeaderCount = max(0, malformedHeaderCount)
+        self.checksumMismatchCount = max(0, checksumMismatchCount)
+    }
+
+    public var totalCount: Int {
+        saturatingAdd(outboundCount, inboundCount)
+    }
+
+    public var isEmpty: Bool {
+        totalCount == 0
+    }
+
+    mutating func record(direction: PacketDirection, reason: InvalidPacketReason) {
+        switch direction {
+        case .outbound:
+            outboundCount = saturatingAdd(outboundCount, 1)
+        case .inbound:
+            inboundCount = saturatingAdd(inboundCount, 1)
+        }
+        switch reason {
+        case .truncated:
+            truncatedCount = saturatingAdd(truncatedCount, 1)
+        case .unsupportedVersion:
+            unsupportedVersionCount = saturatingAdd(unsupportedVersionCount, 1)
+        case .malformedHeader:
+            malformedHeaderCount = saturatingAdd(malformedHeaderCount, 1)
+        case .checksumMismatch:
+            checksumMismatchCount = saturatingAdd(checksumMismatchCount, 1)
+        }
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
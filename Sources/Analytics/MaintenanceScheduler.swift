@@ -0,0 +1,48 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Coalesces the pipeline's periodic upkeep — flow-context eviction, DNS association sweeping,
+/// and DNS transaction timeout sweeping — behind one due-time table so each task runs at its own
+/// cadence no matter how often packet batches arrive.
+/// Decision: a flat per-task due table instead of a hashed timer wheel; the task set is tiny and
+/// fixed, so wheel slots would add state and bucketing error without saving any scanning work.
+internal struct MaintenanceScheduler {
+    enum Task: CaseIterable, Hashable, Sendable {
+        case flowContextSweep
+        case dnsAssociationSweep
+        case dnsTransactionSweep
+    }
+
+    private var intervals: [Task: TimeInterval] = [:]
+    private var nextDueAt: [Task: Date] = [:]
+
+    /// Registers one task at a fixed cadence. The first `dueTasks` call after registration
+    /// reports the task as due so a long-idle component is swept promptly.
+    mutating func register(_ task: Task, every interval: TimeInterval) {
+        guard interval > 0 else {
+            return
+        }
+        intervals[task] = interval
+        nextDueAt[task] = nil
+    }
+
+    /// Returns the registered tasks whose cadence has lapsed and advances their due times.
+    /// Tasks are reported in declaration order for deterministic upkeep.
+    mutating func dueTasks(now: Date) -> [Task] {
+        var due: [Task] = []
+        for task in Task.allCases {
+            guard let interval = intervals[task] else {
+                continue
+            }
+            if let dueAt = nextDueAt[task], now < dueAt {
+                continue
+            }
+            nextDueAt[task] = now.addingTimeInterval(interval)
+            due.append(task)
+        }
+        return due
+    }
+}
@@ -3,6 +3,7 @@
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
 import Foundation
+import PacketIntelligenceCore
 import TunnelRuntime
 
 /// Runtime packet pipeline that favors detector-friendly flow and burst events over rich per-packet logging.
@@ -11,10 +12,22 @@ public actor PacketAnalyticsPipeline {
     private enum FlowCachePolicy {
         static let maxTrackedFlows = 2_048
         static let flowTTLSeconds: TimeInterval = 120
+        /// WebSocket push channels idle legitimately between server-initiated messages, so
+        /// upgraded flows get a much longer leash before idle eviction prunes their context.
+        static let websocketFlowTTLSeconds: TimeInterval = 900
         static let evictionSweepIntervalSeconds: TimeInterval = 15
         static let arrivalQueueCompactionThreshold = 128
     }
 
+    /// Thresholds for the automatic anomaly snapshots in `PipelineDiagnosticBuffer`.
+    private enum DiagnosticPolicy {
+        /// How long an outbound TCP flow may go without a single reply packet before it
+        /// counts as a stuck handshake; well past any sane SYN retransmission schedule.
+        static let stuckHandshakeSeconds: TimeInterval = 60
+        /// Processing budget for one ingest batch before it counts as a latency spike.
+        static let ingestLatencySpikeSeconds: TimeInterval = 0.25
+    }
+
     /// Emission policy applied by the long-lived telemetry worker.
     /// Decision: always-on capture stays cheap, while richer metadata and activity samples are reduced as thermal pressure rises.
     public struct EmissionPolicy: Sendable {
@@ -39,6 +52,14 @@ public actor PacketAnalyticsPipeline {
         public let emitActivitySamples: Bool
         public let emitPacketCues: Bool
         public let packetCuePolicy: PacketCueEmissionPolicy
+        public let dnsNamePrivacy: DNSNamePrivacyPolicy
+        /// Number of leading bytes of each flow's first client payload copied into flow
+        /// records as base64, for field debugging of protocol misclassification.
+        /// 0 (the default) captures nothing; values are clamped to 64 bytes.
+        public let firstPayloadPreviewBytes: Int
+        /// Parses request/status lines from flows with visible HTTP payload (plaintext
+        /// port 80, or lab TLS termination) into the host-fetchable exchange log.
+        public let emitHTTPMetadata: Bool
 
         public init(
             allowDeepMetadata: Bool,
@@ -61,7 +82,10 @@ public actor PacketAnalyticsPipeline {
             emitBurstEvents: Bool,
             emitActivitySamples: Bool,
             emitPacketCues: Bool = false,
-            packetCuePolicy: PacketCueEmissionPolicy = .disabled
+            packetCuePolicy: PacketCueEmissionPolicy = .disabled,
+            dnsNamePrivacy: DNSNamePrivacyPolicy = .plaintext,
+            firstPayloadPreviewBytes: Int = 0,
+            emitHTTPMetadata: Bool = false
         ) {
             self.allowDeepMetadata = allowDeepMetadata
             self.maxMetadataProbesPerBatch = max(0, maxMetadataProbesPerBatch)
@@ -84,16 +108,27 @@ public actor PacketAnalyticsPipeline {
             self.emitActivitySamples = emitActivitySamples
             self.emitPacketCues = emitPacketCues && packetCuePolicy.isEnabled
             self.packetCuePolicy = packetCuePolicy
+            self.dnsNamePrivacy = dnsNamePrivacy
+            self.firstPayloadPreviewBytes = min(max(0, firstPayloadPreviewBytes), 64)
+            self.emitHTTPMetadata = emitHTTPMetadata
         }
     }
 
     struct RuntimeContext: Sendable {
         let pathRegime: PathRegimeSnapshot?
         let sessionContext: DetectorSessionContext?
-
-        init(pathRegime: PathRegimeSnapshot?, sessionContext: DetectorSessionContext? = nil) {
+        /// TUN interface the batch arrived on, for hosts attaching multiple tunnel flows;
+        /// `nil` on single-interface hosts.
+        let interfaceId: UInt16?
+
+        init(
+            pathRegime: PathRegimeSnapshot?,
+            sessionContext: DetectorSessionContext? = nil,
+            interfaceId: UInt16? = nil
+        ) {
             self.pathRegime = pathRegime
             self.sessionContext = sessionContext
+            self.interfaceId = interfaceId
         }
 
         static let empty = RuntimeContext(pathRegime: nil)
@@ -112,6 +147,7 @@ public actor PacketAnalyticsPipeline {
         let destinationAddressLength: UInt8
         let destinationAddressHigh: UInt64
         let destinationAddressLow: UInt64
+        let interfaceId: UInt16?
     }
 
     private static func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
@@ -249,11 +285,18 @@ public actor PacketAnalyticsPipeline {
 
     private struct FlowContext: Sendable {
         let recordTemplate: FlowRecordTemplate
+        /// Direction of the packet that opened this context; teardown frames are only
+        /// synthesized for client-oriented (outbound-opened) contexts so each connection
+        /// contributes one frame.
+        let openedDirection: PacketDirection
         var registrableDomain: String?
         var dnsQueryName: String?
         var dnsCname: String?
         var dnsAnswerAddresses: [String]?
         var tlsServerName: String?
+        var tlsECH: Bool?
+        var tlsJA3: String?
+        var tlsJA4: String?
         var quicVersion: UInt32?
         var quicPacketType: String?
         var quicDestinationConnectionId: String?
@@ -265,8 +308,18 @@ public actor PacketAnalyticsPipeline {
         var serviceAttribution: ServiceAttribution?
         var sessionContext: DetectorSessionContext?
         var addressScope: AddressScopeClassifier.Match?
+        var encryptedDNS: EncryptedDNSKind?
+        var tcpOfferedOptions: TCPHandshakeOptions?
+        var tcpNegotiatedOptions: TCPNegotiatedOptions?
+        var outboundPayloadEntropy = PayloadEntropyEstimator()
+        var inboundPayloadEntropy = PayloadEntropyEstimator()
+        var firstPayloadPreview: Data?
+        var tcpTeardown: FlowTeardownFrames.TCPState?
+        var isWebSocket = false
+        let openedAt: Date
         var lastSeen: Date
         var lastDirection: PacketDirection
+        var isPinned = false
         var hasEmittedFlowOpen = false
         var lastMetadataFingerprint: UInt64?
         var lastActivityEmissionAt: Date?
@@ -274,6 +327,10 @@ public actor PacketAnalyticsPipeline {
         var sawInboundFIN = false
         var totalPacketCount = 0
         var totalByteCount = 0
+        var outboundPacketCount = 0
+        var outboundByteCount = 0
+        var inboundPacketCount = 0
+        var inboundByteCount = 0
         var activityCounters = CounterSet()
         var slice = FlowSliceAccumulator()
         var currentBurst = BurstAccumulator()
@@ -292,14 +349,38 @@ public actor PacketAnalyticsPipeline {
     private let burstTracker: BurstTracker
     private let signatureClassifier: SignatureClassifier
     private let addressScopeClassifier: AddressScopeClassifier
+    private let validationMode: PacketValidationMode
+    private let flowInterceptors: [any FlowInterceptor]
 
     private var flowContexts: [FlowKey: FlowContext] = [:]
     private var flowContextArrivalQueue: ArraySlice<FlowKey> = []
     private var flowKeysByPair: [String: Set<FlowKey>] = [:]
     private var tcpFinStatesByPair: [String: TCPFinState] = [:]
-    private var lastFlowContextSweepAt: Date?
     private var dnsAssociationCache = DNSAssociationCache()
+    private var dnsTransactionTracker = DNSTransactionTracker()
+    private var serviceDiscoveryCatalog = ServiceDiscoveryCatalog()
+    private var flowBreadcrumbLog = FlowBreadcrumbLog()
+    private var httpExchangeLog = HTTPExchangeLog()
+    private var flowExpiryWheel = FlowExpiryWheel(slotSeconds: FlowCachePolicy.evictionSweepIntervalSeconds)
+    private var maintenanceScheduler: MaintenanceScheduler = {
+        var scheduler = MaintenanceScheduler()
+        scheduler.register(.flowContextSweep, every: FlowCachePolicy.evictionSweepIntervalSeconds)
+        scheduler.register(.dnsAssociationSweep, every: 10)
+        scheduler.register(.dnsTransactionSweep, every: 1)
+        return scheduler
+    }()
     private var lineageTracker = FlowLineageTracker()
+    private var payloadHistograms = FlowClassPayloadHistograms()
+    private var usageAccountant = UsageAccountant()
+    private var invalidPacketCounters = InvalidPacketCounters()
+    private var exoticHeaderCounters = ExoticHeaderCounters()
+    private var tlsHandshakeVariationCounters = TLSHandshakeVariationCounters()
+    private var telemetrySeriesRecorder = TelemetrySeriesRecorder()
+    private var dnsIntegrityCounters = DNSIntegrityCounters()
+    private var flowPinningPolicy = FlowPinningPolicy.disabled
+    private var policyAuditLog = PolicyAuditLog()
+    private var diagnosticBuffer = PipelineDiagnosticBuffer()
+    private var pinnedFlowCount = 0
 
     private struct TCPFinState: Sendable {
         var outbound = false
@@ -323,16 +404,24 @@ public actor PacketAnalyticsPipeline {
     ///   - clock: Time source used for deterministic timestamps.
     ///   - burstTracker: Burst detector keyed by stable flow identity.
     ///   - signatureClassifier: Domain classifier for packet-level labeling.
+    ///   - validationMode: Strictness of checksum and header validation. Defaults to `.permissive`
+    ///     because the host TUN stack has already validated checksums on the platforms we ship,
+    ///     so re-verifying every packet is redundant work.
+    ///   - flowInterceptors: Client-supplied per-flow lifecycle hooks, fixed at build time.
     public init(
         clock: any Clock,
         burstTracker: BurstTracker,
         signatureClassifier: SignatureClassifier,
-        addressScopeClassifier: AddressScopeClassifier = .empty
+        addressScopeClassifier: AddressScopeClassifier = .empty,
+        validationMode: PacketValidationMode = .permissive,
+        flowInterceptors: [any FlowInterceptor] = []
     ) {
         self.clock = clock
         self.burstTracker = burstTracker
         self.signatureClassifier = signatureClassifier
         self.addressScopeClassifier = addressScopeClassifier
+        self.validationMode = validationMode
+        self.flowInterceptors = flowInterceptors
     }
 
     /// Ingests a packet batch and returns compact detector-facing records.
@@ -358,14 +447,17 @@ public actor PacketAnalyticsPipeline {
             return []
         }
 
+        let ingestStartedUptime = ProcessInfo.processInfo.systemUptime
         let batchInstant = await clock.instant()
         let batchNow = batchInstant.date
         let batchTimestampMs = batchInstant.milliseconds
         var records: [PacketSampleStream.PacketStreamRecord] = []
         records.reserveCapacity(min(packets.count, 64) * 2)
-        records.append(contentsOf: maybeEvictExpiredFlowContexts(now: batchNow, timestampMs: batchTimestampMs, policy: policy))
+        records.append(contentsOf: runDueMaintenance(now: batchNow, timestampMs: batchTimestampMs, policy: policy))
 
         var metadataProbesRemaining = policy.maxMetadataProbesPerBatch
+        var batchByteCount = 0
+        var batchDNSQueryCount = 0
 
         // One clock read per batch: packets inside one ingest batch arrive together, so a shared
         // timestamp preserves burst/slice semantics while removing an async clock call per packet.
@@ -378,11 +470,43 @@ public actor PacketAnalyticsPipeline {
                 summary = summaries[index]
             } else {
                 guard let parsed = FastPacketSummary(data: packet, ipVersionHint: familyHint) else {
+                    invalidPacketCounters.record(direction: direction, reason: Self.invalidPacketReason(for: packet))
                     continue
                 }
                 summary = parsed
             }
 
+            if validationMode == .strict, let reason = Self.strictValidationFailure(packet: packet) {
+                invalidPacketCounters.record(direction: direction, reason: reason)
+                continue
+            }
+
+            exoticHeaderCounters.record(packet: packet)
+            batchByteCount += packet.count
+            if direction == .outbound, summary.destinationPort == 53 {
+                batchDNSQueryCount += 1
+            }
+
+            // Variation features come from the fast-path candidate check alone, so every
+            // observed hello is counted even when deep metadata probes are budget-capped.
+            if summary.isTLSClientHelloCandidate,
+               summary.transportPayloadOffset > 0,
+               let variation = TLSFingerprinter.variation(record: packet, at: Int(summary.transportPayloadOffset)) {
+                tlsHandshakeVariationCounters.record(variation: variation)
+            }
+
+            // NAT keepalives refresh `lastSeen` so pinned flows are not evicted as idle, but they stay
+            // out of byte accounting and burst/activity counters so they cannot mask truly idle flows.
+            if Self.isUDPNATKeepalive(summary: summary, packet: packet) {
+                let flow = summary.flowKey.tagged(interfaceId: runtimeContext.interfaceId)
+                if var context = flowContexts[flow] {
+                    context.lastSeen = now
+                    context.lastDirection = direction
+                    flowContexts[flow] = context
+                }
+                continue
+            }
+
             let shouldTrackForTelemetry = shouldTrackForTelemetry(summary: summary)
             guard shouldTrackForTelemetry else {
                 continue
@@ -391,10 +515,23 @@ public actor PacketAnalyticsPipeline {
             let allowMetadataProbe = policy.allowDeepMetadata &&
                 metadataProbesRemaining > 0
 
-            let flow = summary.flowKey
+            let flow = summary.flowKey.tagged(interfaceId: runtimeContext.interfaceId)
             rememberFlow(flow)
             let isNewFlow = flowContexts[flow] == nil
-            var context = flowContexts[flow] ?? makeFlowContext(for: summary, now: now, direction: direction, policy: policy)
+            if isNewFlow {
+                for interceptor in flowInterceptors {
+                    interceptor.flowOpened(flow: flow)
+                }
+                flowExpiryWheel.schedule(flow, deadline: now.addingTimeInterval(FlowCachePolicy.flowTTLSeconds))
+                flowBreadcrumbLog.record(flowHash: summary.flowHash, event: .opened, now: now)
+            }
+            var context = flowContexts[flow] ?? makeFlowContext(
+                for: summary,
+                now: now,
+                direction: direction,
+                policy: policy,
+                interfaceId: runtimeContext.interfaceId
+            )
             context.lastSeen = now
             context.lastDirection = direction
             if policy.emitPathRegimeFields {
@@ -419,11 +556,56 @@ public actor PacketAnalyticsPipeline {
 
             context.totalPacketCount = Self.saturatingAdd(context.totalPacketCount, 1)
             context.totalByteCount = Self.saturatingAdd(context.totalByteCount, summary.packetLength)
+            switch direction {
+            case .outbound:
+                context.outboundPacketCount = Self.saturatingAdd(context.outboundPacketCount, 1)
+                context.outboundByteCount = Self.saturatingAdd(context.outboundByteCount, summary.packetLength)
+            case .inbound:
+                context.inboundPacketCount = Self.saturatingAdd(context.inboundPacketCount, 1)
+                context.inboundByteCount = Self.saturatingAdd(context.inboundByteCount, summary.packetLength)
+            }
             context.activityCounters.record(summary: summary)
             context.slice.record(summary: summary, now: now)
             context.currentBurst.record(summary: summary, now: now)
 
+            if summary.hasTCPSYN {
+                recordTCPHandshakeOptions(into: &context, summary: summary, packet: packet)
+            }
+            if direction == .outbound, summary.hasTCPACK,
+               let teardown = FlowTeardownFrames.parseTCPState(packet: packet) {
+                context.tcpTeardown = teardown
+            }
             mergeCheapMetadata(into: &context, summary: summary, policy: policy)
+            if context.encryptedDNS == nil {
+                context.encryptedDNS = Self.encryptedDNSKind(for: context)
+            }
+            usageAccountant.record(
+                category: context.classification ?? Self.protocolClass(for: context).rawValue,
+                direction: direction,
+                byteCount: summary.packetLength,
+                now: now
+            )
+            if summary.hasTransportPayload {
+                if policy.emitHTTPMetadata {
+                    observeHTTPMetadata(flow: flow, context: &context, summary: summary, packet: packet, now: now)
+                }
+                samplePayloadEntropy(into: &context, summary: summary, packet: packet, direction: direction)
+                if direction == .outbound {
+                    captureFirstPayloadPreview(into: &context, summary: summary, packet: packet, policy: policy)
+                }
+                payloadHistograms.record(
+                    protocolClass: Self.protocolClass(for: context),
+                    payloadLength: summary.transportPayloadLength
+                )
+                for interceptor in flowInterceptors {
+                    switch direction {
+                    case .outbound:
+                        interceptor.clientPayload(flow: flow, payloadLength: summary.transportPayloadLength)
+                    case .inbound:
+                        interceptor.remotePayload(flow: flow, payloadLength: summary.transportPayloadLength)
+                    }
+                }
+            }
             if policy.emitServiceAttributionFields {
                 context.serviceAttribution = ServiceAttributionBuilder.make(flowContext: flowContextView(context))
             }
@@ -452,8 +634,36 @@ public actor PacketAnalyticsPipeline {
                     metadataProbesRemaining -= 1
                     let previousFingerprint = context.lastMetadataFingerprint
                     await mergeDeepMetadata(into: &context, metadata: deepMetadata, policy: policy)
-                    if policy.emitDNSAssociationFields || policy.emitServiceAttributionFields {
-                        dnsAssociationCache.record(metadata: deepMetadata, classification: context.classification, now: now)
+                    // mDNS responses are unsolicited by design, so multicast DNS feeds service
+                    // discovery only and stays out of the spoof-detection accounting below.
+                    let isMulticastDNS = deepMetadata.srcPort == 5_353 || deepMetadata.dstPort == 5_353
+                    if isMulticastDNS, let serviceRecords = deepMetadata.dnsServiceRecords {
+                        serviceDiscoveryCatalog.record(
+                            serviceRecords: serviceRecords,
+                            advertiserAddress: deepMetadata.srcAddress.stringValue,
+                            now: now
+                        )
+                    }
+                    if !isMulticastDNS {
+                        dnsTransactionTracker.noteQuery(metadata: deepMetadata, now: now)
+                    }
+                    let transactionMatch = isMulticastDNS
+                        ? nil
+                        : dnsTransactionTracker.matchResponse(metadata: deepMetadata, now: now)
+                    if !isMulticastDNS, policy.emitDNSAssociationFields || policy.emitServiceAttributionFields {
+                        switch dnsAssociationCache.record(
+                            metadata: deepMetadata,
+                            classification: context.classification,
+                            queryMatched: transactionMatch != nil,
+                            now: now
+                        ) {
+                        case .recorded:
+                            dnsIntegrityCounters.recordMatchedResponse()
+                        case .spoofCandidate:
+                            dnsIntegrityCounters.recordSpoofCandidate()
+                        case .notApplicable:
+                            break
+                        }
                         context.association = dnsAssociationCache.associate(summary: summary, direction: direction, now: now)
                     }
                     if policy.emitServiceAttributionFields {
@@ -466,6 +676,7 @@ public actor PacketAnalyticsPipeline {
                     if nextFingerprint != previousFingerprint {
                         context.lastMetadataFingerprint = nextFingerprint
                         didUpdateMetadata = true
+                        flowBreadcrumbLog.record(flowHash: summary.flowHash, event: .metadataUpdated, now: now)
                         records.append(
                             makeRecord(
                                 kind: .metadata,
@@ -540,16 +751,292 @@ public actor PacketAnalyticsPipeline {
                 continue
             }
 
+            if flowPinningPolicy.isEnabled, !context.isPinned,
+               pinnedFlowCount < flowPinningPolicy.maxPinnedFlows,
+               pinRuleMatches(context) {
+                context.isPinned = true
+                pinnedFlowCount += 1
+                flowBreadcrumbLog.record(flowHash: summary.flowHash, event: .pinned, now: now)
+            }
+
             flowContexts[flow] = context
             if isNewFlow {
                 flowContextArrivalQueue.append(flow)
             }
         }
 
+        telemetrySeriesRecorder.record(
+            inboundBytes: direction == .inbound ? batchByteCount : 0,
+            outboundBytes: direction == .outbound ? batchByteCount : 0,
+            activeFlowCount: flowContexts.count,
+            dnsQueryCount: batchDNSQueryCount,
+            now: batchNow
+        )
         records.append(contentsOf: trimOverflowFlowContextsIfNeeded(policy: policy, now: batchNow, timestampMs: batchTimestampMs))
+        captureIngestLatencySpikeIfNeeded(startedUptime: ingestStartedUptime, packetCount: packets.count, now: batchNow)
         return records
     }
 
+    /// Returns the session-scoped payload-size histograms accumulated per protocol class.
+    func payloadHistogramSnapshot() -> FlowClassPayloadHistograms {
+        payloadHistograms
+    }
+
+    /// Returns the direction-aware counters for packets that failed fast-path parsing.
+    func invalidPacketCountersSnapshot() -> InvalidPacketCounters {
+        invalidPacketCounters
+    }
+
+    /// Returns the session-scoped counters for unusual-but-legal header constructs.
+    func exoticHeaderCountersSnapshot() -> ExoticHeaderCounters {
+        exoticHeaderCounters
+    }
+
+    /// Returns the session-scoped ClientHello randomization counters.
+    func tlsHandshakeVariationCountersSnapshot() -> TLSHandshakeVariationCounters {
+        tlsHandshakeVariationCounters
+    }
+
+    /// Returns the per-second dashboard series for the trailing `windowSeconds`.
+    func telemetrySeries(windowSeconds: Int) async -> TunnelTelemetrySeries {
+        telemetrySeriesRecorder.series(windowSeconds: windowSeconds, now: await clock.instant().date)
+    }
+
+    /// Tallies policy-blocked flows into the dashboard series; the relay reports blocks
+    /// through the worker because the pipeline never sees its verdicts.
+    func noteBlockedFlows(_ count: Int) async {
+        telemetrySeriesRecorder.record(blockedFlowCount: count, now: await clock.instant().date)
+    }
+
+    /// Returns the session-scoped DNS response validation counters.
+    func dnsIntegrityCountersSnapshot() -> DNSIntegrityCounters {
+        dnsIntegrityCounters
+    }
+
+    /// Returns per-resolver DNS latency and timeout aggregates.
+    func dnsResolverStatsSnapshot() -> [DNSResolverStats] {
+        dnsTransactionTracker.statsSnapshot()
+    }
+
+    /// Returns the local-network services observed via mDNS DNS-SD announcements.
+    func discoveredServicesSnapshot() -> [DiscoveredService] {
+        serviceDiscoveryCatalog.snapshot()
+    }
+
+    /// Reverse lookup over observed DNS answers: the most recent hostname that resolved to `address`.
+    func hostnameAssociation(for address: IPAddress) async -> DNSHostnameAssociation? {
+        let now = await clock.instant().date
+        return dnsAssociationCache.lookupHostname(for: address, now: now)
+    }
+
+    /// Returns the audited flow-pinning rule changes in application order, oldest first.
+    func policyAuditSnapshot() -> [PolicyAuditEntry] {
+        policyAuditLog.snapshot()
+    }
+
+    /// Returns the recent lifecycle breadcrumbs recorded for one flow, oldest first.
+    /// The flow hash is the same value stamped onto that flow's stream records.
+    func flowBreadcrumbs(forFlowHash flowHash: UInt64) -> [FlowBreadcrumb] {
+        flowBreadcrumbLog.breadcrumbs(forFlowHash: flowHash)
+    }
+
+    /// Returns the completed HTTP exchanges observed on payload-visible flows, oldest
+    /// first. Responses arrive on the reverse-direction sibling context, so matching is
+    /// done per flow pair.
+    func httpExchanges() -> [HTTPExchange] {
+        httpExchangeLog.exchanges
+    }
+
+    /// Returns the diagnostic snapshots captured automatically on anomalies, oldest first.
+    func diagnosticSnapshots() -> [PipelineDiagnosticSnapshot] {
+        diagnosticBuffer.snapshots
+    }
+
+    /// Drops fetched diagnostic snapshots; per-trigger capture cooldowns are unaffected.
+    func clearDiagnosticSnapshots() {
+        diagnosticBuffer.clear()
+    }
+
+    /// Number of distinct endpoint pairs with at least one live tracked flow. Directional
+    /// sibling contexts count once, so the value matches a host's intuition of "connections".
+    func activeFlowPairCount() -> Int {
+        flowKeysByPair.count
+    }
+
+    /// Builds best-effort abort frames for every flow the pipeline still tracks, so a stopping
+    /// tunnel can fail client apps immediately instead of leaving them to time out against a
+    /// dead interface. Established TCP flows get an RST stamped with the client's
+    /// last-acknowledged sequence; UDP flows get an ICMP port-unreachable quoting the flow's
+    /// original header. Families align with packets, matching the ingest batch convention.
+    func makeTeardownFrames() -> (packets: [Data], families: [Int32]) {
+        var packets: [Data] = []
+        var families: [Int32] = []
+        for context in flowContexts.values {
+            let template = context.recordTemplate
+            guard context.openedDirection == .outbound,
+                  let clientPort = template.sourcePort,
+                  let remotePort = template.destinationPort else {
+                continue
+            }
+            let clientAddress = FlowTeardownFrames.addressBytes(
+                high: template.sourceAddressHigh,
+                low: template.sourceAddressLow,
+                length: template.sourceAddressLength
+            )
+            let remoteAddress = FlowTeardownFrames.addressBytes(
+                high: template.destinationAddressHigh,
+                low: template.destinationAddressLow,
+                length: template.destinationAddressLength
+            )
+            let frame: Data?
+            switch template.transportProtocolNumber {
+            case 6:
+                guard let state = context.tcpTeardown else {
+                    continue
+                }
+                frame = FlowTeardownFrames.makeTCPReset(
+                    clientAddress: clientAddress,
+                    clientPort: clientPort,
+                    remoteAddress: remoteAddress,
+                    remotePort: remotePort,
+                    state: state
+                )
+            case 17:
+                frame = FlowTeardownFrames.makeUDPUnreachable(
+                    clientAddress: clientAddress,
+                    clientPort: clientPort,
+                    remoteAddress: remoteAddress,
+                    remotePort: remotePort
+                )
+            default:
+                continue
+            }
+            if let frame {
+                packets.append(frame)
+                families.append(FlowTeardownFrames.addressFamily(forIPVersion: template.ipVersion))
+            }
+        }
+        return (packets, families)
+    }
+
+    /// Returns the DNS association cache's live size and cumulative eviction counters.
+    func dnsAssociationCacheStatsSnapshot() -> DNSAssociationCacheStats {
+        dnsAssociationCache.statsSnapshot()
+    }
+
+    /// Exports every in-TTL observed IP-to-hostname association with its remaining lifetime.
+    func dnsAssociationExport() async -> [DNSAssociationExportEntry] {
+        let now = await clock.instant().date
+        return dnsAssociationCache.exportSnapshot(now: now)
+    }
+
+    /// Pre-seeds the association cache from a persisted export; live entries always win.
+    func seedDNSAssociations(_ entries: [DNSAssociationExportEntry]) async -> Int {
+        let now = await clock.instant().date
+        return dnsAssociationCache.importEntries(entries, now: now)
+    }
+
+    /// Resets the invalid-packet counters so hosts can measure malformed-input rates per interval.
+    func resetInvalidPacketCounters() {
+        invalidPacketCounters = InvalidPacketCounters()
+    }
+
+    /// Records parse failures detected by the worker's prefilter, which parses before `ingest` runs.
+    func recordInvalidPackets(direction: PacketDirection, reasons: [InvalidPacketReason]) {
+        for reason in reasons {
+            invalidPacketCounters.record(direction: direction, reason: reason)
+        }
+    }
+
+    /// Returns the hourly per-category usage buckets accumulated since start (or the last reset).
+    func usageAccountingReport() -> UsageAccountingReport {
+        usageAccountant.report()
+    }
+
+    /// Clears the usage buckets, typically after the host has persisted a report.
+    func resetUsageAccounting() {
+        usageAccountant.reset()
+    }
+
+    /// Classifies why a packet failed fast-path parsing without re-running the parser.
+    static func invalidPacketReason(for packet: Data) -> InvalidPacketReason {
+        guard let first = packet.first else {
+            return .truncated
+        }
+        switch (first >> 4) & 0x0f {
+        case 4:
+            return packet.count < 20 ? .truncated : .malformedHeader
+        case 6:
+            return packet.count < 40 ? .truncated : .malformedHeader
+        default:
+            return .unsupportedVersion
+        }
+    }
+
+    /// Applies strict-mode validation to a packet that already passed the fast-path parse.
+    /// Returns the counter reason for the first failed check, or `nil` when the packet is clean.
+    /// The checks themselves (IPv4 header checksum, illegal header combinations, TCP/UDP
+    /// pseudo-header checksums) live in the freestanding C core so they can be reused and
+    /// fuzzed without the engine; this wrapper only maps the result onto counter reasons.
+    static func strictValidationFailure(packet: Data) -> InvalidPacketReason? {
+        let failure = packet.withUnsafeBytes { rawBuffer -> Int32 in
+            guard let baseAddress = rawBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
+                return Int32(RBPI_STRICT_OK)
+            }
+            return rbpi_strict_validation_failure(baseAddress, rawBuffer.count)
+        }
+        switch failure {
+        case Int32(RBPI_STRICT_CHECKSUM_MISMATCH):
+            return .checksumMismatch
+        case Int32(RBPI_STRICT_MALFORMED_HEADER):
+            return .malformedHeader
+        default:
+            return nil
+        }
+    }
+
+    /// Replaces the active pinning policy and re-evaluates tracked flows against the new rules.
+    /// The rule-level diff is recorded in the policy audit log under the caller's source label.
+    func updateFlowPinning(_ policy: FlowPinningPolicy, source: String = "host") async {
+        let now = await clock.instant().date
+        policyAuditLog.recordReplacement(from: flowPinningPolicy, to: policy, source: source, now: now)
+        flowPinningPolicy = policy
+        pinnedFlowCount = 0
+        for (flow, var context) in flowContexts {
+            let shouldPin = policy.isEnabled &&
+                pinnedFlowCount < policy.maxPinnedFlows &&
+                pinRuleMatches(context)
+            if shouldPin {
+                pinnedFlowCount += 1
+            }
+            if context.isPinned != shouldPin {
+                context.isPinned = shouldPin
+                flowContexts[flow] = context
+            }
+        }
+    }
+
+    /// Commits a transaction's batched adds and removes as one policy replacement, so flow
+    /// admission never observes a half-applied rule set.
+    func commitFlowPinningTransaction(_ transaction: FlowPinningTransaction, source: String = "host") async {
+        guard !transaction.isEmpty else {
+            return
+        }
+        await updateFlowPinning(transaction.applying(to: flowPinningPolicy), source: source)
+    }
+
+    private func pinRuleMatches(_ context: FlowContext) -> Bool {
+        let hostCandidates = [
+            context.tlsServerName,
+            context.association?.associatedDomain,
+            context.registrableDomain,
+            context.dnsQueryName
+        ].compactMap { $0 }
+        return flowPinningPolicy.rules.contains { rule in
+            rule.matches(destinationPort: context.recordTemplate.destinationPort, hostCandidates: hostCandidates)
+        }
+    }
+
     /// Returns `true` when a packet is worth tracking for burst/activity detection.
     /// Decision: pure TCP ACK traffic is ignored because it adds a lot of heat without improving detector signal.
     private func shouldTrackForTelemetry(summary: FastPacketSummary) -> Bool {
@@ -565,7 +1052,37 @@ public actor PacketAnalyticsPipeline {
         }
     }
 
-    private func makeFlowContext(for summary: FastPacketSummary, now: Date, direction: PacketDirection, policy: EmissionPolicy) -> FlowContext {
+    /// Returns `true` for zero-length or STUN binding-indication datagrams used as UDP NAT keepalives.
+    static func isUDPNATKeepalive(summary: FastPacketSummary, packet: Data) -> Bool {
+        guard summary.transport == .udp, summary.transportPayloadOffset > 0 else {
+            return false
+        }
+        if summary.transportPayloadLength == 0 {
+            return true
+        }
+        guard summary.transportPayloadLength >= 20 else {
+            return false
+        }
+        let offset = packet.startIndex + Int(summary.transportPayloadOffset)
+        guard packet.endIndex >= offset + 8 else {
+            return false
+        }
+        // STUN binding indication (RFC 5389): message type 0x0011 followed by the fixed magic cookie.
+        return packet[offset] == 0x00 &&
+            packet[offset + 1] == 0x11 &&
+            packet[offset + 4] == 0x21 &&
+            packet[offset + 5] == 0x12 &&
+            packet[offset + 6] == 0xa4 &&
+            packet[offset + 7] == 0x42
+    }
+
+    private func makeFlowContext(
+        for summary: FastPacketSummary,
+        now: Date,
+        direction: PacketDirection,
+        policy: EmissionPolicy,
+        interfaceId: UInt16? = nil
+    ) -> FlowContext {
         FlowContext(
             recordTemplate: FlowRecordTemplate(
                 protocolHint: summary.protocolHint,
@@ -579,13 +1096,18 @@ public actor PacketAnalyticsPipeline {
                 sourceAddressLow: summary.sourceAddressLow,
                 destinationAddressLength: summary.destinationAddressLength,
                 destinationAddressHigh: summary.destinationAddressHigh,
-                destinationAddressLow: summary.destinationAddressLow
+                destinationAddressLow: summary.destinationAddressLow,
+                interfaceId: interfaceId
             ),
+            openedDirection: direction,
             registrableDomain: nil,
             dnsQueryName: nil,
             dnsCname: nil,
             dnsAnswerAddresses: nil,
             tlsServerName: nil,
+            tlsECH: nil,
+            tlsJA3: nil,
+            tlsJA4: nil,
             quicVersion: policy.includeQUICIdentity ? summary.quicVersion : nil,
             quicPacketType: policy.includeQUICIdentity ? summary.quicPacketType?.rawValue : nil,
             quicDestinationConnectionId: policy.includeQUICIdentity ? Self.hexString(summary.quicDestinationConnectionID) : nil,
@@ -597,6 +1119,7 @@ public actor PacketAnalyticsPipeline {
             serviceAttribution: nil,
             sessionContext: nil,
             addressScope: nil,
+            openedAt: now,
             lastSeen: now,
             lastDirection: direction
         )
@@ -638,10 +1161,10 @@ public actor PacketAnalyticsPipeline {
             flowContext.registrableDomain = registrableDomain
         }
         if policy.includeHostHints, let dnsQueryName = metadata.dnsQueryName, !dnsQueryName.isEmpty {
-            flowContext.dnsQueryName = dnsQueryName
+            flowContext.dnsQueryName = policy.dnsNamePrivacy.apply(to: dnsQueryName)
         }
         if policy.includeHostHints, let dnsCname = metadata.dnsCname, !dnsCname.isEmpty {
-            flowContext.dnsCname = dnsCname
+            flowContext.dnsCname = policy.dnsNamePrivacy.apply(to: dnsCname)
         }
         if policy.includeHostHints || policy.includeDNSAnswerAddresses,
            let dnsAnswerAddresses = metadata.dnsAnswerAddresses,
@@ -651,6 +1174,18 @@ public actor PacketAnalyticsPipeline {
         if policy.includeHostHints, let tlsServerName = metadata.tlsServerName, !tlsServerName.isEmpty {
             flowContext.tlsServerName = tlsServerName
         }
+        // Not a host hint: records only that the hello was ECH-protected, never what it named.
+        if let tlsECH = metadata.tlsECH {
+            flowContext.tlsECH = flowContext.tlsECH == true ? true : tlsECH
+        }
+        // Fingerprints identify the local client stack, not the destination, so they are not
+        // gated behind host hints either.
+        if let tlsJA3 = metadata.tlsJA3, !tlsJA3.isEmpty {
+            flowContext.tlsJA3 = tlsJA3
+        }
+        if let tlsJA4 = metadata.tlsJA4, !tlsJA4.isEmpty {
+            flowContext.tlsJA4 = tlsJA4
+        }
         if policy.includeQUICIdentity, let quicVersion = metadata.quicVersion {
             flowContext.quicVersion = quicVersion
         }
@@ -808,6 +1343,103 @@ public actor PacketAnalyticsPipeline {
         flowKeysByPair[pairID] = flows
     }
 
+    /// Captures TCP handshake options from SYN and SYN-ACK packets so flow records can report
+    /// what the endpoints actually negotiated (timestamps, SACK, window scale, MSS).
+    /// The SYN stores the client's offer; the SYN-ACK resolves it into the negotiated set.
+    private func recordTCPHandshakeOptions(into context: inout FlowContext, summary: FastPacketSummary, packet: Data) {
+        guard let options = TCPHandshakeOptions.parse(packet: packet) else {
+            return
+        }
+        if summary.hasTCPACK {
+            guard context.tcpNegotiatedOptions == nil, let offered = context.tcpOfferedOptions else {
+                return
+            }
+            context.tcpNegotiatedOptions = TCPNegotiatedOptions(offered: offered, answered: options)
+        } else if context.tcpOfferedOptions == nil {
+            context.tcpOfferedOptions = options
+        }
+    }
+
+    /// Feeds the first payload bytes of each direction into that direction's entropy
+    /// estimator; a no-op once the sampling cap is reached.
+    private func samplePayloadEntropy(into context: inout FlowContext, summary: FastPacketSummary, packet: Data, direction: PacketDirection) {
+        switch direction {
+        case .outbound:
+            guard context.outboundPayloadEntropy.wantsMoreBytes else { return }
+        case .inbound:
+            guard context.inboundPayloadEntropy.wantsMoreBytes else { return }
+        }
+        guard summary.transportPayloadOffset > 0, summary.transportPayloadLength > 0 else {
+            return
+        }
+        let start = packet.startIndex + Int(summary.transportPayloadOffset)
+        let end = min(packet.endIndex, start + summary.transportPayloadLength)
+        guard start < end else {
+            return
+        }
+        switch direction {
+        case .outbound:
+            context.outboundPayloadEntropy.observe(packet[start..<end])
+        case .inbound:
+            context.inboundPayloadEntropy.observe(packet[start..<end])
+        }
+    }
+
+    /// Parses plaintext HTTP messages on flows whose payload is visible. Port 80 is the
+    /// plaintext signal today; lab TLS termination feeds decrypted payloads through this
+    /// same ingest path, so terminated flows take the same route.
+    private func observeHTTPMetadata(flow: FlowKey, context: inout FlowContext, summary: FastPacketSummary, packet: Data, now: Date) {
+        guard summary.transport == .tcp,
+              summary.sourcePort == 80 || summary.destinationPort == 80 else {
+            return
+        }
+        guard summary.transportPayloadOffset > 0, summary.transportPayloadLength > 0 else {
+            return
+        }
+        let start = packet.startIndex + Int(summary.transportPayloadOffset)
+        let end = min(packet.endIndex, start + summary.transportPayloadLength)
+        guard start < end else {
+            return
+        }
+        let pairKey = flow.bidirectionalIdentifierHex
+        if let message = HTTPMetadataExtractor.parse(payload: Data(packet[start ..< end])) {
+            httpExchangeLog.record(pairKey: pairKey, flowHash: summary.flowHash, message: message, now: now)
+            if case .response(_, 101, let upgrade) = message, upgrade?.lowercased() == "websocket" {
+                markFlowPairAsWebSocket(pairKey: pairKey, flow: flow, context: &context)
+            }
+        } else if summary.sourcePort == 80, httpExchangeLog.hasPendingHTTP2Request(pairKey: pairKey) {
+            // HTTP/2 frames are not parsed, so on an h2 connection the first
+            // server-to-client payload bytes close the timing window.
+            httpExchangeLog.recordHTTP2ResponseBytes(pairKey: pairKey, now: now)
+        }
+    }
+
+    /// Tags both directional contexts of an upgraded connection, so records emitted for
+    /// either side carry the websocket class and both sides earn the longer idle TTL.
+    private func markFlowPairAsWebSocket(pairKey: String, flow: FlowKey, context: inout FlowContext) {
+        context.isWebSocket = true
+        for sibling in flowKeysByPair[pairKey] ?? [] where sibling != flow {
+            flowContexts[sibling]?.isWebSocket = true
+        }
+    }
+
+    /// Copies the leading bytes of the flow's first client payload when the emission policy
+    /// opts in; a no-op once a preview was captured or while previews are disabled.
+    private func captureFirstPayloadPreview(into context: inout FlowContext, summary: FastPacketSummary, packet: Data, policy: EmissionPolicy) {
+        guard policy.firstPayloadPreviewBytes > 0, context.firstPayloadPreview == nil else {
+            return
+        }
+        guard summary.transportPayloadOffset > 0, summary.transportPayloadLength > 0 else {
+            return
+        }
+        let start = packet.startIndex + Int(summary.transportPayloadOffset)
+        let end = min(packet.endIndex, start + min(summary.transportPayloadLength, policy.firstPayloadPreviewBytes))
+        guard start < end else {
+            return
+        }
+        context.firstPayloadPreview = Data(packet[start..<end])
+    }
+
     private func metadataFingerprint(for flowContext: FlowContext) -> UInt64 {
         var hash: UInt64 = 14_695_981_039_346_656_037
         func mix(_ value: String?) {
@@ -904,6 +1536,12 @@ public actor PacketAnalyticsPipeline {
             fallback: nil
         )
         let flowId = String(format: "%016llx", template.flowHash)
+        // Decision: flow-close records double as per-connection summaries (duration plus directional
+        // totals) so consumers that ignore packet-level events still get one complete record per flow.
+        let isCloseSummary = kind == .flowClose
+        let flowDurationMs = isCloseSummary
+            ? max(0, Int((timestamp.timeIntervalSince(flowContext.openedAt) * 1_000).rounded()))
+            : nil
         return PacketSampleStream.PacketStreamRecord(
             kind: kind,
             timestamp: timestamp,
@@ -913,12 +1551,19 @@ public actor PacketAnalyticsPipeline {
             packetCount: counters.isEmpty ? nil : counters.packetCount,
             flowPacketCount: flowContext.totalPacketCount,
             flowByteCount: flowContext.totalByteCount,
+            flowDurationMs: flowDurationMs,
+            flowOutboundByteCount: isCloseSummary ? flowContext.outboundByteCount : nil,
+            flowInboundByteCount: isCloseSummary ? flowContext.inboundByteCount : nil,
+            flowOutboundPacketCount: isCloseSummary ? flowContext.outboundPacketCount : nil,
+            flowInboundPacketCount: isCloseSummary ? flowContext.inboundPacketCount : nil,
             protocolHint: template.protocolHint,
+            protocolClass: Self.protocolClass(for: flowContext),
             ipVersion: template.ipVersion,
             transportProtocolNumber: template.transportProtocolNumber,
             sourcePort: template.sourcePort,
             destinationPort: template.destinationPort,
             flowHash: template.flowHash,
+            interfaceId: template.interfaceId,
             textFlowId: nil,
             sourceAddressLength: template.sourceAddressLength,
             sourceAddressHigh: template.sourceAddressHigh,
@@ -933,6 +1578,9 @@ public actor PacketAnalyticsPipeline {
             dnsCname: flowContext.dnsCname,
             dnsAnswerAddresses: flowContext.dnsAnswerAddresses,
             tlsServerName: flowContext.tlsServerName,
+            tlsECH: flowContext.tlsECH,
+            tlsJA3: flowContext.tlsJA3,
+            tlsJA4: flowContext.tlsJA4,
             quicVersion: flowContext.quicVersion,
             quicPacketType: flowContext.quicPacketType,
             quicDestinationConnectionId: flowContext.quicDestinationConnectionId,
@@ -981,6 +1629,10 @@ public actor PacketAnalyticsPipeline {
             tcpFlags: packetSummary?.transport == .tcp ? packetSummary?.tcpFlags : nil,
             tcpAck: packetSummary?.transport == .tcp ? packetSummary?.hasTCPACK : nil,
             tcpPsh: packetSummary?.transport == .tcp ? packetSummary?.hasTCPPSH : nil,
+            tcpNegotiatedOptions: flowContext.tcpNegotiatedOptions,
+            payloadEntropyOut: flowContext.outboundPayloadEntropy.bitsPerByte,
+            payloadEntropyIn: flowContext.inboundPayloadEntropy.bitsPerByte,
+            firstPayloadPreview: flowContext.firstPayloadPreview?.base64EncodedString(),
             packetCueReason: packetCueReason,
             sessionContext: flowContext.sessionContext,
             flowIdentity: DetectorRecordDerivation.flowIdentity(
@@ -1016,31 +1668,156 @@ public actor PacketAnalyticsPipeline {
         return addressScopeClassifier.classify(addressLength: addressLength, high: high, low: low)
     }
 
-    /// Decision: flow-context cleanup is amortized because sweeping a large dictionary on every batch adds heat
-    /// without improving detector quality.
-    private func maybeEvictExpiredFlowContexts(
+    /// Runs whichever periodic upkeep tasks are due on this batch. The scheduler keeps each task
+    /// on its own cadence, while cache pressure forces a flow-context sweep regardless.
+    private func runDueMaintenance(
         now: Date,
         timestampMs: Double,
         policy: EmissionPolicy
     ) -> [PacketSampleStream.PacketStreamRecord] {
-        if let lastFlowContextSweepAt,
-           now.timeIntervalSince(lastFlowContextSweepAt) < FlowCachePolicy.evictionSweepIntervalSeconds,
-           flowContexts.count < FlowCachePolicy.maxTrackedFlows {
-            return []
+        var records: [PacketSampleStream.PacketStreamRecord] = []
+        var sweptFlowContexts = false
+        for task in maintenanceScheduler.dueTasks(now: now) {
+            switch task {
+            case .flowContextSweep:
+                records.append(contentsOf: evictExpiredFlowContexts(now: now, timestampMs: timestampMs, policy: policy))
+                captureStuckHandshakesIfNeeded(now: now)
+                sweptFlowContexts = true
+            case .dnsAssociationSweep:
+                dnsAssociationCache.sweepExpired(now: now)
+            case .dnsTransactionSweep:
+                dnsTransactionTracker.sweepTimeouts(now: now)
+            }
+        }
+        if !sweptFlowContexts, flowContexts.count >= FlowCachePolicy.maxTrackedFlows {
+            records.append(contentsOf: evictExpiredFlowContexts(now: now, timestampMs: timestampMs, policy: policy))
+        }
+        return records
+    }
+
+    /// Captures a stuck-handshake snapshot when any outbound TCP flow has been sending for
+    /// longer than the threshold without a single reply packet — the shape of a connection
+    /// stuck retransmitting its SYN against a black hole. Contexts are directional, so
+    /// "no reply" means the flow's pair never grew a reverse-direction sibling.
+    private func captureStuckHandshakesIfNeeded(now: Date) {
+        guard diagnosticBuffer.shouldCapture(.stuckHandshake, now: now) else {
+            return
+        }
+        let stuckContexts = flowContexts.compactMap { flow, context -> FlowContext? in
+            guard context.recordTemplate.transportProtocolNumber == 6,
+                  context.openedDirection == .outbound,
+                  context.inboundPacketCount == 0,
+                  now.timeIntervalSince(context.openedAt) >= DiagnosticPolicy.stuckHandshakeSeconds,
+                  (flowKeysByPair[flow.bidirectionalIdentifierHex]?.count ?? 1) <= 1 else {
+                return nil
+            }
+            return context
+        }
+        guard !stuckContexts.isEmpty else {
+            return
         }
+        diagnosticBuffer.record(
+            makeDiagnosticSnapshot(
+                trigger: .stuckHandshake,
+                detail: "\(stuckContexts.count) outbound TCP flow(s) with no reply after \(Int(DiagnosticPolicy.stuckHandshakeSeconds))s",
+                now: now,
+                contexts: stuckContexts
+            )
+        )
+    }
 
-        lastFlowContextSweepAt = now
-        let expiredFlows = flowContexts.compactMap { flow, context in
-            now.timeIntervalSince(context.lastSeen) > FlowCachePolicy.flowTTLSeconds ? flow : nil
+    /// Captures a latency-spike snapshot when one ingest batch exceeded its processing budget.
+    /// Duration comes from the monotonic system clock rather than the pipeline clock, which
+    /// is deliberately read once and frozen for the whole batch.
+    private func captureIngestLatencySpikeIfNeeded(startedUptime: TimeInterval, packetCount: Int, now: Date) {
+        let elapsed = ProcessInfo.processInfo.systemUptime - startedUptime
+        guard elapsed >= DiagnosticPolicy.ingestLatencySpikeSeconds,
+              diagnosticBuffer.shouldCapture(.ingestLatencySpike, now: now) else {
+            return
         }
+        diagnosticBuffer.record(
+            makeDiagnosticSnapshot(
+                trigger: .ingestLatencySpike,
+                detail: String(format: "batch of %d packet(s) took %.0f ms", packetCount, elapsed * 1_000),
+                now: now,
+                contexts: mostRecentlySeenContexts()
+            )
+        )
+    }
+
+    /// Builds a snapshot of the current actor state, bounding the flow selection and
+    /// attaching each selected flow's breadcrumbs.
+    private func makeDiagnosticSnapshot(
+        trigger: PipelineDiagnosticSnapshot.Trigger,
+        detail: String,
+        now: Date,
+        contexts: [FlowContext]
+    ) -> PipelineDiagnosticSnapshot {
+        let flows = contexts.prefix(PipelineDiagnosticBuffer.Policy.maxFlowsPerSnapshot).map { context in
+            PipelineFlowDiagnostic(
+                flowHash: context.recordTemplate.flowHash,
+                protocolHint: context.recordTemplate.protocolHint,
+                classification: context.classification,
+                isPinned: context.isPinned,
+                openedAt: context.openedAt,
+                lastSeen: context.lastSeen,
+                totalPacketCount: context.totalPacketCount,
+                totalByteCount: context.totalByteCount,
+                outboundPacketCount: context.outboundPacketCount,
+                inboundPacketCount: context.inboundPacketCount,
+                breadcrumbs: flowBreadcrumbLog.breadcrumbs(forFlowHash: context.recordTemplate.flowHash)
+            )
+        }
+        return PipelineDiagnosticSnapshot(
+            trigger: trigger,
+            detail: detail,
+            capturedAt: now,
+            trackedFlowCount: flowContexts.count,
+            pinnedFlowCount: pinnedFlowCount,
+            invalidPackets: invalidPacketCounters,
+            flows: Array(flows)
+        )
+    }
+
+    private func mostRecentlySeenContexts() -> [FlowContext] {
+        Array(
+            flowContexts.values
+                .sorted { $0.lastSeen > $1.lastSeen }
+                .prefix(PipelineDiagnosticBuffer.Policy.maxFlowsPerSnapshot)
+        )
+    }
+
+    /// Idle TTL for one flow context; upgraded WebSocket flows get the push-channel leash.
+    /// The expiry sweep recomputes deadlines from the live context, so a flow tagged after
+    /// its initial schedule still picks up the longer TTL at its first sweep visit.
+    private static func flowTTLSeconds(for context: FlowContext) -> TimeInterval {
+        context.isWebSocket ? FlowCachePolicy.websocketFlowTTLSeconds : FlowCachePolicy.flowTTLSeconds
+    }
+
+    /// Decision: flow-context cleanup is amortized because sweeping a large dictionary on every batch adds heat
+    /// without improving detector quality.
+    private func evictExpiredFlowContexts(
+        now: Date,
+        timestampMs: Double,
+        policy: EmissionPolicy
+    ) -> [PacketSampleStream.PacketStreamRecord] {
         var records: [PacketSampleStream.PacketStreamRecord] = []
-        for flow in expiredFlows {
+        var closedAny = false
+        for flow in flowExpiryWheel.drainExpiredCandidates(now: now) {
             guard let context = flowContexts[flow] else {
                 continue
             }
+            let deadline = context.lastSeen.addingTimeInterval(Self.flowTTLSeconds(for: context))
+            guard !context.isPinned, deadline <= now else {
+                // Touched (or pinned) since it was scheduled; push it out to its current deadline.
+                let rescheduleAt = max(deadline, now.addingTimeInterval(FlowCachePolicy.evictionSweepIntervalSeconds))
+                flowExpiryWheel.schedule(flow, deadline: rescheduleAt)
+                continue
+            }
             records.append(contentsOf: closeFlow(flow: flow, context: context, timestamp: now, timestampMs: timestampMs, direction: context.lastDirection, reason: .idleEviction, policy: policy))
+            closedAny = true
         }
-        pruneFlowContextArrivalQueueIfNeeded(force: !expiredFlows.isEmpty)
+        pruneFlowContextArrivalQueueIfNeeded(force: closedAny)
         return records
     }
 
@@ -1053,16 +1830,33 @@ public actor PacketAnalyticsPipeline {
             return []
         }
 
+        // Capture before evicting so the snapshot reflects the overloaded table.
+        if diagnosticBuffer.shouldCapture(.flowTableOverflow, now: now) {
+            diagnosticBuffer.record(
+                makeDiagnosticSnapshot(
+                    trigger: .flowTableOverflow,
+                    detail: "\(flowContexts.count) tracked flows exceed the \(FlowCachePolicy.maxTrackedFlows)-flow budget",
+                    now: now,
+                    contexts: mostRecentlySeenContexts()
+                )
+            )
+        }
+
         pruneFlowContextArrivalQueueIfNeeded(force: true)
         var records: [PacketSampleStream.PacketStreamRecord] = []
+        var skippedPinnedFlows: [FlowKey] = []
 
         while flowContexts.count > FlowCachePolicy.maxTrackedFlows {
             if let candidate = flowContextArrivalQueue.popFirst() {
                 guard let context = flowContexts[candidate] else {
                     continue
                 }
+                if context.isPinned {
+                    skippedPinnedFlows.append(candidate)
+                    continue
+                }
                 records.append(contentsOf: closeFlow(flow: candidate, context: context, timestamp: now, timestampMs: timestampMs, direction: context.lastDirection, reason: .overflowEviction, policy: policy))
-            } else if let fallback = flowContexts.keys.first {
+            } else if let fallback = flowContexts.first(where: { !$0.value.isPinned })?.key {
                 // Decision: this should stay cold because the arrival queue is the primary eviction path.
                 // If the queue is unexpectedly empty, removing any active flow is cheaper than re-sorting the actor state.
                 guard let context = flowContexts[fallback] else {
@@ -1070,10 +1864,12 @@ public actor PacketAnalyticsPipeline {
                 }
                 records.append(contentsOf: closeFlow(flow: fallback, context: context, timestamp: now, timestampMs: timestampMs, direction: context.lastDirection, reason: .overflowEviction, policy: policy))
             } else {
+                // Every remaining flow is pinned; maxPinnedFlows keeps this bounded.
                 break
             }
         }
 
+        flowContextArrivalQueue.append(contentsOf: skippedPinnedFlows)
         pruneFlowContextArrivalQueueIfNeeded()
         return records
     }
@@ -1158,10 +1954,22 @@ public actor PacketAnalyticsPipeline {
                 )
             )
         }
+        flowBreadcrumbLog.record(
+            flowHash: context.recordTemplate.flowHash,
+            event: .closed,
+            closeReason: reason,
+            now: timestamp
+        )
         flowContexts.removeValue(forKey: flow)
         removeFlowFromPairIndex(flow)
         burstTracker.removeFlow(flow: flow)
         lineageTracker.close(flow: flow, now: timestamp)
+        for interceptor in flowInterceptors {
+            interceptor.flowClosed(flow: flow, reason: reason)
+        }
+        if context.isPinned {
+            pinnedFlowCount = max(0, pinnedFlowCount - 1)
+        }
         return records
     }
 
@@ -1212,6 +2020,53 @@ public actor PacketAnalyticsPipeline {
         flowContextArrivalQueue = ArraySlice(activeQueue)
     }
 
+    /// Classifies a flow into a coarse application-protocol bucket from already-merged context.
+    /// Decision: classification reuses flow-context facts so record emission never adds parsing work.
+    private static func protocolClass(for flowContext: FlowContext) -> FlowProtocolClass {
+        let template = flowContext.recordTemplate
+        // An observed 101 upgrade is definitive, so it wins over the port-based hints below.
+        if flowContext.isWebSocket {
+            return .websocket
+        }
+        if flowContext.encryptedDNS != nil {
+            return .encryptedDNS
+        }
+        if flowContext.dnsQueryName != nil || flowContext.dnsCname != nil ||
+            template.sourcePort == 53 || template.destinationPort == 53 {
+            return .dns
+        }
+        if flowContext.quicVersion != nil || flowContext.quicPacketType != nil {
+            return .quic
+        }
+        if flowContext.tlsServerName != nil || (template.transportProtocolNumber == 6 && template.destinationPort == 443) {
+            return .tls
+        }
+        return .other
+    }
+
+    /// Tags DoT by destination port and DoH by known resolver SNI or IPv4 destination.
+    private static func encryptedDNSKind(for flowContext: FlowContext) -> EncryptedDNSKind? {
+        let template = flowContext.recordTemplate
+        guard let destinationPort = template.destinationPort else {
+            return nil
+        }
+        if destinationPort == EncryptedDNSClassifier.dotPort {
+            return .dot
+        }
+        guard destinationPort == 443 else {
+            return nil
+        }
+        if let serverName = flowContext.tlsServerName,
+           EncryptedDNSClassifier.isKnownDoHResolverHost(serverName) {
+            return .doh
+        }
+        if template.destinationAddressLength == 4,
+           EncryptedDNSClassifier.knownDoHResolverIPv4Addresses.contains(UInt32(truncatingIfNeeded: template.destinationAddressLow)) {
+            return .doh
+        }
+        return nil
+    }
+
     private static func hexString(_ data: Data?) -> String? {
         guard let data, !data.isEmpty else {
             return nil
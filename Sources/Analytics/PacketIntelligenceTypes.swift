@@ -57,6 +57,21 @@ public struct IPAddress: Hashable, Codable, Sendable {
         self.bytes = bytes
     }
 
+    /// - Parameter string: Dotted-quad IPv4 or RFC 4291 IPv6 text form.
+    public init?(string: String) {
+        var v4 = in_addr()
+        if inet_pton(AF_INET, string, &v4) == 1 {
+            self.bytes = withUnsafeBytes(of: v4) { Data($0) }
+            return
+        }
+        var v6 = in6_addr()
+        if inet_pton(AF_INET6, string, &v6) == 1 {
+            self.bytes = withUnsafeBytes(of: v6) { Data($0) }
+            return
+        }
+        return nil
+    }
+
     /// Inferred address family from byte length.
     public var version: IPVersion {
         bytes.count == 4 ? .v4 : .v6
@@ -108,8 +123,14 @@ public struct PacketMetadata: Sendable {
     public let dnsQueryName: String?
     public let dnsCname: String?
     public let dnsAnswerAddresses: [IPAddress]?
+    public let dnsTransactionId: UInt16?
+    public let dnsIsResponse: Bool?
+    public let dnsServiceRecords: [DNSServiceRecord]?
     public let registrableDomain: String?
     public let tlsServerName: String?
+    public let tlsECH: Bool?
+    public let tlsJA3: String?
+    public let tlsJA4: String?
     public let quicVersion: UInt32?
     public let quicPacketType: QuicPacketType?
     public let quicDestinationConnectionId: String?
@@ -126,8 +147,14 @@ public struct PacketMetadata: Sendable {
     ///   - dnsQueryName: DNS query name when packet carries DNS.
     ///   - dnsCname: DNS CNAME answer, when present.
     ///   - dnsAnswerAddresses: DNS A/AAAA answer addresses.
+    ///   - dnsTransactionId: DNS header transaction ID, when the packet carried a parseable DNS header.
+    ///   - dnsIsResponse: Whether the DNS header QR bit marked the message as a response.
+    ///   - dnsServiceRecords: DNS-SD PTR/SRV/TXT records parsed from an mDNS message.
     ///   - registrableDomain: Normalized registrable domain.
     ///   - tlsServerName: TLS SNI hostname.
+    ///   - tlsECH: Whether the ClientHello carried an ECH extension; `nil` when no hello was parsed.
+    ///   - tlsJA3: JA3 fingerprint of the observed ClientHello.
+    ///   - tlsJA4: JA4 fingerprint of the observed ClientHello.
     ///   - quicVersion: QUIC version (long header only).
     ///   - quicPacketType: Parsed QUIC packet type.
     ///   - quicDestinationConnectionId: QUIC destination connection ID (hex).
@@ -143,8 +170,14 @@ public struct PacketMetadata: Sendable {
         dnsQueryName: String?,
         dnsCname: String?,
         dnsAnswerAddresses: [IPAddress]? = nil,
+        dnsTransactionId: UInt16? = nil,
+        dnsIsResponse: Bool? = nil,
+        dnsServiceRecords: [DNSServiceRecord]? = nil,
         registrableDomain: String?,
         tlsServerName: String?,
+        tlsECH: Bool? = nil,
+        tlsJA3: String? = nil,
+        tlsJA4: String? = nil,
         quicVersion: UInt32?,
         quicPacketType: QuicPacketType?,
         quicDestinationConnectionId: String?,
@@ -160,8 +193,14 @@ public struct PacketMetadata: Sendable {
         self.dnsQueryName = dnsQueryName
         self.dnsCname = dnsCname
         self.dnsAnswerAddresses = dnsAnswerAddresses
+        self.dnsTransactionId = dnsTransactionId
+        self.dnsIsResponse = dnsIsResponse
+        self.dnsServiceRecords = dnsServiceRecords
         self.registrableDomain = registrableDomain
         self.tlsServerName = tlsServerName
+        self.tlsECH = tlsECH
+        self.tlsJA3 = tlsJA3
+        self.tlsJA4 = tlsJA4
         self.quicVersion = quicVersion
         self.quicPacketType = quicPacketType
         self.quicDestinationConnectionId = quicDestinationConnectionId
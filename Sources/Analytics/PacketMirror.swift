@@ -0,0 +1,111 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Out-of-band tap delivering copies of selected frames to host analysis tooling.
+/// Decision: kept separate from the telemetry event path so external inspection can never
+/// back-pressure or reorder analytics; frames over the rate caps are dropped, never queued.
+/// Contract: `offer` is called on the packet I/O queue; the handler must return quickly and
+/// hop to its own queue for anything heavier than an enqueue.
+public final class PacketMirror: @unchecked Sendable {
+    /// Sampling and rate-cap knobs for the mirror.
+    public struct Configuration: Sendable {
+        /// Mirror one of every `sampleEvery` eligible frames; `1` mirrors every eligible frame.
+        public let sampleEvery: Int
+        /// Hard cap on mirrored frames per one-second window; excess frames are dropped.
+        public let maxPacketsPerSecond: Int
+        /// Hard cap on mirrored bytes per one-second window; excess frames are dropped.
+        public let maxBytesPerSecond: Int
+
+        /// - Parameters:
+        ///   - sampleEvery: Sampling stride over eligible frames; values below 1 are clamped to 1.
+        ///   - maxPacketsPerSecond: Per-second frame cap; values below 1 are clamped to 1.
+        ///   - maxBytesPerSecond: Per-second byte cap; values below 1 are clamped to 1.
+        public init(sampleEvery: Int = 1, maxPacketsPerSecond: Int = 200, maxBytesPerSecond: Int = 262_144) {
+            self.sampleEvery = max(1, sampleEvery)
+            self.maxPacketsPerSecond = max(1, maxPacketsPerSecond)
+            self.maxBytesPerSecond = max(1, maxBytesPerSecond)
+        }
+
+        public static let `default` = Configuration()
+    }
+
+    private let configuration: Configuration
+    private let isEligible: (@Sendable (Data, PacketDirection) -> Bool)?
+    private let handler: @Sendable (Data, PacketDirection) -> Void
+    private let nowProvider: @Sendable () -> Date
+    private let lock = NSLock()
+    private var sampleCounter = 0
+    private var windowStart: Date?
+    private var windowPacketCount = 0
+    private var windowByteCount = 0
+    private var droppedFrames = 0
+
+    /// - Parameters:
+    ///   - configuration: Sampling stride and per-second rate caps.
+    ///   - isEligible: Optional per-frame predicate, letting hosts mirror only rule-selected
+    ///     traffic; `nil` makes every frame eligible.
+    ///   - handler: Receives a copy of each mirrored frame with its direction.
+    ///   - nowProvider: Time source used for the rate windows.
+    public init(
+        configuration: Configuration = .default,
+        isEligible: (@Sendable (Data, PacketDirection) -> Bool)? = nil,
+        handler: @escaping @Sendable (Data, PacketDirection) -> Void,
+        nowProvider: @escaping @Sendable () -> Date = { Date() }
+    ) {
+        self.configuration = configuration
+        self.isEligible = isEligible
+        self.handler = handler
+        self.nowProvider = nowProvider
+    }
+
+    /// Offers a batch of frames to the mirror; sampled, cap-admitted frames are handed to the
+    /// handler and everything else is dropped.
+    public func offer(packets: [Data], direction: PacketDirection) {
+        let now = nowProvider()
+        for packet in packets {
+            if let isEligible, !isEligible(packet, direction) {
+                continue
+            }
+            guard admit(packet: packet, now: now) else {
+                continue
+            }
+            handler(packet, direction)
+        }
+    }
+
+    /// Frames dropped by the rate caps since the mirror was created.
+    public func droppedFrameCount() -> Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return droppedFrames
+    }
+
+    private func admit(packet: Data, now: Date) -> Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        sampleCounter += 1
+        if sampleCounter >= configuration.sampleEvery {
+            sampleCounter = 0
+        } else {
+            return false
+        }
+        if let windowStart, now.timeIntervalSince(windowStart) < 1 {
+            guard windowPacketCount < configuration.maxPacketsPerSecond,
+                  windowByteCount + packet.count <= configuration.maxBytesPerSecond
+            else {
+                droppedFrames += 1
+                return false
+            }
+        } else {
+            windowStart = now
+            windowPacketCount = 0
+            windowByteCount = 0
+        }
+        windowPacketCount += 1
+        windowByteCount += packet.count
+        return true
+    }
+}
@@ -3,6 +3,7 @@
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
 import Foundation
+import TunnelRuntime
 #if canImport(CommonCrypto)
 import CommonCrypto
 #endif
@@ -19,6 +20,7 @@ import Darwin
 /// Parser is intentionally allocation-light and returns `nil` for malformed/unsupported frames.
 public enum PacketParser {
     private static let dnsPort: UInt16 = 53
+    private static let mdnsPort: UInt16 = 5_353
     private static let maxIPv6Extensions = 8
 
     /// Parses one raw IP packet into structured metadata.
@@ -78,8 +80,14 @@ public enum PacketParser {
         var dnsQuery: String?
         var dnsCname: String?
         var dnsAnswers: [IPAddress]?
+        var dnsTransactionId: UInt16?
+        var dnsIsResponse: Bool?
+        var dnsServiceRecords: [DNSServiceRecord]?
         var registrableDomain: String?
         var tlsServerName: String?
+        var tlsECH: Bool?
+        var tlsJA3: String?
+        var tlsJA4: String?
         var quicVersion: UInt32?
         var quicPacketType: QuicPacketType?
         var quicDestinationConnectionId: String?
@@ -96,13 +104,16 @@ public enum PacketParser {
                 guard udpLength >= 8, ihl + udpLength <= packet.count else { return nil }
                 let udpPacketEnd = ihl + udpLength
                 let boundedTransportPacket = udpPacketEnd == packet.count ? packet : Data(packet.prefix(udpPacketEnd))
-                if let srcPort, let dstPort, (srcPort == dnsPort || dstPort == dnsPort) {
+                if let srcPort, let dstPort, (srcPort == dnsPort || dstPort == dnsPort || srcPort == mdnsPort || dstPort == mdnsPort) {
                     let payloadOffset = ihl + 8
                     if udpPacketEnd > payloadOffset {
                         let dnsInfo = parseDNSInfo(boundedTransportPacket, payloadOffset: payloadOffset)
                         dnsQuery = dnsInfo.query
                         dnsCname = dnsInfo.cname
                         dnsAnswers = dnsInfo.answers.isEmpty ? nil : dnsInfo.answers
+                        dnsTransactionId = dnsInfo.transactionId
+                        dnsIsResponse = dnsInfo.isResponse
+                        dnsServiceRecords = dnsInfo.serviceRecords.isEmpty ? nil : dnsInfo.serviceRecords
                     }
                 }
 
@@ -116,13 +127,17 @@ public enum PacketParser {
                         if tlsServerName == nil,
                            quicPacketType == .initial,
                            let quicVersion = quicInfo.version,
-                           let dcidData = quicInfo.dcidData {
-                            tlsServerName = decryptQuicInitialServerName(
-                                boundedTransportPacket,
-                                payloadOffset: payloadOffset,
-                                version: quicVersion,
-                                dcid: dcidData
-                            )
+                           let dcidData = quicInfo.dcidData,
+                           let hello = decryptQuicInitialClientHello(
+                               boundedTransportPacket,
+                               payloadOffset: payloadOffset,
+                               version: quicVersion,
+                               dcid: dcidData
+                           ) {
+                            tlsServerName = hello.serverName
+                            tlsECH = hello.echPresent
+                            tlsJA3 = hello.ja3
+                            tlsJA4 = hello.ja4
                         }
                     }
                 }
@@ -130,8 +145,12 @@ public enum PacketParser {
                 if packet.count > ihl + 13 {
                     let dataOffset = Int((packet[packet.startIndex + ihl + 12] >> 4) * 4)
                     let payloadOffset = ihl + dataOffset
-                    if dataOffset >= 20, packet.count > payloadOffset {
-                        tlsServerName = parseTLSServerName(packet, payloadOffset: payloadOffset)
+                    if dataOffset >= 20, packet.count > payloadOffset,
+                       let hello = parseTLSClientHello(packet, payloadOffset: payloadOffset) {
+                        tlsServerName = hello.serverName
+                        tlsECH = hello.echPresent
+                        tlsJA3 = hello.ja3
+                        tlsJA4 = hello.ja4
                     }
                 }
             }
@@ -150,8 +169,14 @@ public enum PacketParser {
             dnsQueryName: dnsQuery,
             dnsCname: dnsCname,
             dnsAnswerAddresses: dnsAnswers,
+            dnsTransactionId: dnsTransactionId,
+            dnsIsResponse: dnsIsResponse,
+            dnsServiceRecords: dnsServiceRecords,
             registrableDomain: registrableDomain,
             tlsServerName: tlsServerName,
+            tlsECH: tlsECH,
+            tlsJA3: tlsJA3,
+            tlsJA4: tlsJA4,
             quicVersion: quicVersion,
             quicPacketType: quicPacketType,
             quicDestinationConnectionId: quicDestinationConnectionId,
@@ -215,6 +240,9 @@ public enum PacketParser {
                     dnsAnswerAddresses: nil,
                     registrableDomain: nil,
                     tlsServerName: nil,
+                    tlsECH: nil,
+                    tlsJA3: nil,
+                    tlsJA4: nil,
                     quicVersion: nil,
                     quicPacketType: nil,
                     quicDestinationConnectionId: nil,
@@ -235,8 +263,14 @@ public enum PacketParser {
         var dnsQuery: String?
         var dnsCname: String?
         var dnsAnswers: [IPAddress]?
+        var dnsTransactionId: UInt16?
+        var dnsIsResponse: Bool?
+        var dnsServiceRecords: [DNSServiceRecord]?
         var registrableDomain: String?
         var tlsServerName: String?
+        var tlsECH: Bool?
+        var tlsJA3: String?
+        var tlsJA4: String?
         var quicVersion: UInt32?
         var quicPacketType: QuicPacketType?
         var quicDestinationConnectionId: String?
@@ -253,13 +287,16 @@ public enum PacketParser {
                 guard udpLength >= 8, offset + udpLength <= packet.count else { return nil }
                 let udpPacketEnd = offset + udpLength
                 let boundedTransportPacket = udpPacketEnd == packet.count ? packet : Data(packet.prefix(udpPacketEnd))
-                if let srcPort, let dstPort, (srcPort == dnsPort || dstPort == dnsPort) {
+                if let srcPort, let dstPort, (srcPort == dnsPort || dstPort == dnsPort || srcPort == mdnsPort || dstPort == mdnsPort) {
                     let payloadOffset = offset + 8
                     if udpPacketEnd > payloadOffset {
                         let dnsInfo = parseDNSInfo(boundedTransportPacket, payloadOffset: payloadOffset)
                         dnsQuery = dnsInfo.query
                         dnsCname = dnsInfo.cname
                         dnsAnswers = dnsInfo.answers.isEmpty ? nil : dnsInfo.answers
+                        dnsTransactionId = dnsInfo.transactionId
+                        dnsIsResponse = dnsInfo.isResponse
+                        dnsServiceRecords = dnsInfo.serviceRecords.isEmpty ? nil : dnsInfo.serviceRecords
                     }
                 }
 
@@ -273,13 +310,17 @@ public enum PacketParser {
                         if tlsServerName == nil,
                            quicPacketType == .initial,
                            let quicVersion = quicInfo.version,
-                           let dcidData = quicInfo.dcidData {
-                            tlsServerName = decryptQuicInitialServerName(
-                                boundedTransportPacket,
-                                payloadOffset: payloadOffset,
-                                version: quicVersion,
-                                dcid: dcidData
-                            )
+                           let dcidData = quicInfo.dcidData,
+                           let hello = decryptQuicInitialClientHello(
+                               boundedTransportPacket,
+                               payloadOffset: payloadOffset,
+                               version: quicVersion,
+                               dcid: dcidData
+                           ) {
+                            tlsServerName = hello.serverName
+                            tlsECH = hello.echPresent
+                            tlsJA3 = hello.ja3
+                            tlsJA4 = hello.ja4
                         }
                     }
                 }
@@ -287,8 +328,12 @@ public enum PacketParser {
                 if packet.count > offset + 13 {
                     let dataOffset = Int((packet[packet.startIndex + offset + 12] >> 4) * 4)
                     let payloadOffset = offset + dataOffset
-                    if dataOffset >= 20, packet.count > payloadOffset {
-                        tlsServerName = parseTLSServerName(packet, payloadOffset: payloadOffset)
+                    if dataOffset >= 20, packet.count > payloadOffset,
+                       let hello = parseTLSClientHello(packet, payloadOffset: payloadOffset) {
+                        tlsServerName = hello.serverName
+                        tlsECH = hello.echPresent
+                        tlsJA3 = hello.ja3
+                        tlsJA4 = hello.ja4
                     }
                 }
             }
@@ -307,8 +352,14 @@ public enum PacketParser {
             dnsQueryName: dnsQuery,
             dnsCname: dnsCname,
             dnsAnswerAddresses: dnsAnswers,
+            dnsTransactionId: dnsTransactionId,
+            dnsIsResponse: dnsIsResponse,
+            dnsServiceRecords: dnsServiceRecords,
             registrableDomain: registrableDomain,
             tlsServerName: tlsServerName,
+            tlsECH: tlsECH,
+            tlsJA3: tlsJA3,
+            tlsJA4: tlsJA4,
             quicVersion: quicVersion,
             quicPacketType: quicPacketType,
             quicDestinationConnectionId: quicDestinationConnectionId,
@@ -336,6 +387,9 @@ public enum PacketParser {
             dnsAnswerAddresses: nil,
             registrableDomain: nil,
             tlsServerName: nil,
+            tlsECH: nil,
+            tlsJA3: nil,
+            tlsJA4: nil,
             quicVersion: nil,
             quicPacketType: nil,
             quicDestinationConnectionId: nil,
@@ -446,7 +500,20 @@ public enum PacketParser {
         }
     }
 
-    private static func parseTLSServerName(_ data: Data, payloadOffset: Int) -> String? {
+    /// Fields recovered from a parsed ClientHello's extension block.
+    /// `echPresent` reflects the RFC-draft `encrypted_client_hello` extension (0xfe0d); an ECH'd
+    /// hello usually carries only a cover SNI, so both fields travel together.
+    /// `ja3`/`ja4` are the passive client fingerprints computed by `TLSFingerprinter`.
+    private struct ClientHelloFields {
+        let serverName: String?
+        let echPresent: Bool
+        var ja3: String?
+        var ja4: String?
+    }
+
+    private static let echExtensionType: UInt16 = 0xfe0d
+
+    private static func parseTLSClientHello(_ data: Data, payloadOffset: Int) -> ClientHelloFields? {
         guard data.count >= payloadOffset + 5 else { return nil }
         let contentType = data[data.startIndex + payloadOffset]
         guard contentType == 22 else { return nil }
@@ -488,6 +555,8 @@ public enum PacketParser {
         let extensionsEnd = index + extensionsLength
         guard extensionsEnd <= data.count, extensionsEnd <= handshakeEnd else { return nil }
 
+        var serverName: String?
+        var echPresent = false
         while index + 4 <= extensionsEnd {
             let extType = readUInt16(data, offset: index)
             index += 2
@@ -508,19 +577,28 @@ public enum PacketParser {
                     nameIndex += 2
                     guard nameIndex + nameLength <= listEnd else { break }
                     if nameType == 0 {
-                        return decodeUTF8(data, start: nameIndex, length: nameLength)
+                        serverName = decodeUTF8(data, start: nameIndex, length: nameLength)
+                        break
                     }
                     nameIndex += nameLength
                 }
+            } else if extType == echExtensionType {
+                echPresent = true
             }
 
             index += extLen
         }
 
-        return nil
+        let fingerprint = TLSFingerprinter.fingerprint(record: data, at: payloadOffset, transport: .tcp)
+        return ClientHelloFields(
+            serverName: serverName,
+            echPresent: echPresent,
+            ja3: fingerprint?.ja3,
+            ja4: fingerprint?.ja4
+        )
     }
 
-    private static func parseTLSClientHelloServerName(_ data: Data) -> String? {
+    private static func parseTLSClientHello(_ data: Data) -> ClientHelloFields? {
         guard data.count >= 4 else { return nil }
         guard data[data.startIndex] == 1 else { return nil }
         let handshakeLength = readUInt24(data, offset: 1)
@@ -552,6 +630,8 @@ public enum PacketParser {
         guard extensionsLength > 0 else { return nil }
         let extensionsEnd = min(handshakeEnd, index + extensionsLength)
 
+        var serverName: String?
+        var echPresent = false
         while index + 4 <= extensionsEnd {
             let extType = readUInt16(data, offset: index)
             index += 2
@@ -572,16 +652,25 @@ public enum PacketParser {
                     nameIndex += 2
                     guard nameIndex + nameLength <= listEnd else { break }
                     if nameType == 0 {
-                        return decodeUTF8(data, start: nameIndex, length: nameLength)
+                        serverName = decodeUTF8(data, start: nameIndex, length: nameLength)
+                        break
                     }
                     nameIndex += nameLength
                 }
+            } else if extType == echExtensionType {
+                echPresent = true
             }
 
             index += extLen
         }
 
-        return nil
+        let fingerprint = TLSFingerprinter.fingerprint(handshake: data, transport: .quic)
+        return ClientHelloFields(
+            serverName: serverName,
+            echPresent: echPresent,
+            ja3: fingerprint?.ja3,
+            ja4: fingerprint?.ja4
+        )
     }
 
     private static let quicV1Version: UInt32 = 0x00000001
@@ -717,12 +806,12 @@ public enum PacketParser {
         return Data(output)
     }
 
-    private static func decryptQuicInitialServerName(
+    private static func decryptQuicInitialClientHello(
         _ data: Data,
         payloadOffset: Int,
         version: UInt32,
         dcid: Data
-    ) -> String? {
+    ) -> ClientHelloFields? {
         guard let secrets = deriveQuicInitialSecrets(version: version, dcid: dcid) else { return nil }
         guard data.count > payloadOffset else { return nil }
 
@@ -759,7 +848,7 @@ public enum PacketParser {
         guard data.count >= sampleOffset + 16 else { return nil }
         guard let sample = copyDataSlice(data, offset: sampleOffset, length: 16) else { return nil }
 
-        if let serverName = decryptQuicInitial(
+        if let hello = decryptQuicInitial(
             data,
             payloadOffset: payloadOffset,
             pnOffset: pnOffset,
@@ -770,7 +859,7 @@ public enum PacketParser {
             iv: secrets.clientIv,
             hp: secrets.clientHp
         ) {
-            return serverName
+            return hello
         }
 
         return decryptQuicInitial(
@@ -796,7 +885,7 @@ public enum PacketParser {
         key: Data,
         iv: Data,
         hp: Data
-    ) -> String? {
+    ) -> ClientHelloFields? {
         guard let mask = aes128EncryptBlock(key: hp, block: sample) else { return nil }
         let headerFirstByte = firstByte ^ (mask[0] & 0x0f)
         let pnLength = Int(headerFirstByte & 0x03) + 1
@@ -843,7 +932,7 @@ public enum PacketParser {
         guard let gcmNonce = try? AES.GCM.Nonce(data: Data(nonce)) else { return nil }
         guard let sealedBox = try? AES.GCM.SealedBox(nonce: gcmNonce, ciphertext: ciphertext, tag: tag) else { return nil }
         guard let plaintext = try? AES.GCM.open(sealedBox, using: SymmetricKey(data: key), authenticating: aad) else { return nil }
-        return parseQuicFramesForServerName(plaintext)
+        return parseQuicFramesForClientHello(plaintext)
     }
 
     private static func readQuicVarInt(_ data: Data, offset: Int) -> (value: Int, nextIndex: Int)? {
@@ -861,7 +950,7 @@ public enum PacketParser {
         return (value, offset + length)
     }
 
-    private static func parseQuicFramesForServerName(_ data: Data) -> String? {
+    private static func parseQuicFramesForClientHello(_ data: Data) -> ClientHelloFields? {
         var index = 0
         let end = data.count
         while index < end {
@@ -877,7 +966,7 @@ public enum PacketParser {
                 guard cryptoLength >= 4, index + cryptoLength <= end else { return nil }
                 if offsetInfo.value == 0 {
                     guard let cryptoData = copyDataSlice(data, offset: index, length: cryptoLength) else { return nil }
-                    return parseTLSClientHelloServerName(cryptoData)
+                    return parseTLSClientHello(cryptoData)
                 }
                 index += cryptoLength
             } else if frameType == 0x00 || frameType == 0x01 {
@@ -894,12 +983,12 @@ public enum PacketParser {
         return nil
     }
 #else
-    private static func decryptQuicInitialServerName(
+    private static func decryptQuicInitialClientHello(
         _ data: Data,
         payloadOffset: Int,
         version: UInt32,
         dcid: Data
-    ) -> String? {
+    ) -> ClientHelloFields? {
         _ = data
         _ = payloadOffset
         _ = version
@@ -917,14 +1006,24 @@ public enum PacketParser {
         let query: String?
         let cname: String?
         let answers: [IPAddress]
+        let transactionId: UInt16?
+        let isResponse: Bool?
+        let serviceRecords: [DNSServiceRecord]
     }
 
+    /// Maximum DNS-SD records collected from one message; mDNS announcements can carry dozens.
+    private static let maxServiceRecordsPerMessage = 16
+
     private static func parseDNSInfo(_ data: Data, payloadOffset: Int) -> DNSParseResult {
-        guard data.count >= payloadOffset + 12 else { return DNSParseResult(query: nil, cname: nil, answers: []) }
-        let flags = readUInt16(data, offset: payloadOffset + 2)
-        let qdCount = readUInt16(data, offset: payloadOffset + 4)
-        let anCount = readUInt16(data, offset: payloadOffset + 6)
-        let isResponse = (flags & 0x8000) != 0
+        guard let header = DNSHeaderSummary(message: data, payloadOffset: payloadOffset) else {
+            return DNSParseResult(query: nil, cname: nil, answers: [], transactionId: nil, isResponse: nil, serviceRecords: [])
+        }
+        let transactionId = header.transactionID
+        let qdCount = header.questionCount
+        let anCount = header.answerCount
+        let nsCount = header.authorityCount
+        let arCount = header.additionalCount
+        let isResponse = header.isResponse
 
         var index = payloadOffset + 12
         var queryName: String?
@@ -935,19 +1034,39 @@ public enum PacketParser {
             if index + 4 <= data.count {
                 index += 4
             } else {
-                return DNSParseResult(query: queryName, cname: nil, answers: [])
+                return DNSParseResult(
+                    query: queryName,
+                    cname: nil,
+                    answers: [],
+                    transactionId: transactionId,
+                    isResponse: isResponse,
+                    serviceRecords: []
+                )
             }
         }
 
         guard isResponse, anCount > 0 else {
-            return DNSParseResult(query: queryName, cname: nil, answers: [])
+            return DNSParseResult(
+                query: queryName,
+                cname: nil,
+                answers: [],
+                transactionId: transactionId,
+                isResponse: isResponse,
+                serviceRecords: []
+            )
         }
 
         var cname: String?
         var answers: [IPAddress] = []
-        var answersParsed = 0
-        while answersParsed < anCount, index < data.count {
-            _ = readDNSName(data, offset: &index, messageStart: payloadOffset, depth: 0)
+        var serviceRecords: [DNSServiceRecord] = []
+        var recordsParsed = 0
+        // DNS-SD puts SRV/TXT details in the authority and additional sections, so the walk
+        // continues past the answer section; address answers still come from answers only.
+        let totalRecords = Int(anCount) + Int(nsCount) + Int(arCount)
+        while recordsParsed < totalRecords, index < data.count {
+            var ownerOffset = index
+            let ownerName = readDNSName(data, offset: &ownerOffset, messageStart: payloadOffset, depth: 0)
+            index = ownerOffset
             guard index + 10 <= data.count else { break }
             let type = readUInt16(data, offset: index)
             index += 2
@@ -957,27 +1076,79 @@ public enum PacketParser {
             let rdLength = Int(readUInt16(data, offset: index))
             index += 2
             guard index + rdLength <= data.count else { break }
+            let isAnswerSection = recordsParsed < Int(anCount)
 
-            if type == 5 { // CNAME
+            if type == 5, isAnswerSection { // CNAME
                 var rdataOffset = index
                 if let cnameName = readDNSName(data, offset: &rdataOffset, messageStart: payloadOffset, depth: 0) {
                     cname = cnameName
                 }
-            } else if type == 1, rdLength == 4 { // A
+            } else if type == 1, rdLength == 4, isAnswerSection { // A
                 if let ip = readIPAddress(data, offset: index, length: 4) {
                     answers.append(ip)
                 }
-            } else if type == 28, rdLength == 16 { // AAAA
+            } else if type == 28, rdLength == 16, isAnswerSection { // AAAA
                 if let ip = readIPAddress(data, offset: index, length: 16) {
                     answers.append(ip)
                 }
+            } else if type == 12, serviceRecords.count < maxServiceRecordsPerMessage { // PTR
+                var rdataOffset = index
+                if let ownerName, isDNSSDName(ownerName),
+                   let instanceName = readDNSName(data, offset: &rdataOffset, messageStart: payloadOffset, depth: 0) {
+                    serviceRecords.append(DNSServiceRecord(kind: .pointer, name: ownerName, target: instanceName))
+                }
+            } else if type == 33, rdLength >= 6, serviceRecords.count < maxServiceRecordsPerMessage { // SRV
+                var targetOffset = index + 6
+                if let ownerName, isDNSSDName(ownerName) {
+                    let port = readUInt16(data, offset: index + 4)
+                    let target = readDNSName(data, offset: &targetOffset, messageStart: payloadOffset, depth: 0)
+                    serviceRecords.append(DNSServiceRecord(kind: .service, name: ownerName, target: target, port: port))
+                }
+            } else if type == 16, serviceRecords.count < maxServiceRecordsPerMessage { // TXT
+                if let ownerName, isDNSSDName(ownerName) {
+                    let entries = readTXTEntries(data, offset: index, rdLength: rdLength)
+                    if !entries.isEmpty {
+                        serviceRecords.append(DNSServiceRecord(kind: .text, name: ownerName, textEntries: entries))
+                    }
+                }
             }
 
             index += rdLength
-            answersParsed += 1
+            recordsParsed += 1
         }
 
-        return DNSParseResult(query: queryName, cname: cname, answers: answers)
+        return DNSParseResult(
+            query: queryName,
+            cname: cname,
+            answers: answers,
+            transactionId: transactionId,
+            isResponse: isResponse,
+            serviceRecords: serviceRecords
+        )
+    }
+
+    /// Whether a record owner name follows the DNS-SD `<instance>.<service>._tcp|_udp.<domain>` shape.
+    private static func isDNSSDName(_ name: String) -> Bool {
+        let lowered = name.lowercased()
+        return lowered.contains("._tcp") || lowered.contains("._udp")
+    }
+
+    private static func readTXTEntries(_ data: Data, offset: Int, rdLength: Int) -> [String] {
+        var entries: [String] = []
+        var cursor = offset
+        let end = offset + rdLength
+        while cursor < end, entries.count < 8 {
+            let entryLength = Int(data[data.startIndex + cursor])
+            cursor += 1
+            guard entryLength > 0, cursor + entryLength <= end else {
+                continue
+            }
+            if let entry = decodeUTF8(data, start: cursor, length: entryLength) {
+                entries.append(entry)
+            }
+            cursor += entryLength
+        }
+        return entries
     }
 
     private static func readDNSName(_ data: Data, offset: inout Int, messageStart: Int, depth: Int) -> String? {
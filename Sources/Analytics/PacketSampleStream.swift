@@ -144,7 +144,16 @@ public struct PacketSample: Codable, Sendable, Equatable {
     public let packetCount: Int?
     public let flowPacketCount: Int?
     public let flowByteCount: Int?
+    public let flowDurationMs: Int?
+    public let flowOutboundByteCount: Int?
+    public let flowInboundByteCount: Int?
+    public let flowOutboundPacketCount: Int?
+    public let flowInboundPacketCount: Int?
+    public let flowHandle: UInt64?
+    /// TUN interface the flow's frames arrived on, for hosts attaching multiple tunnel flows.
+    public let interfaceId: UInt16?
     public let protocolHint: String
+    public let protocolClass: FlowProtocolClass?
     public let ipVersion: UInt8?
     public let transportProtocolNumber: UInt8?
     public let sourceAddress: String?
@@ -156,6 +165,9 @@ public struct PacketSample: Codable, Sendable, Equatable {
     public let dnsCname: String?
     public let dnsAnswerAddresses: [String]?
     public let tlsServerName: String?
+    public let tlsECH: Bool?
+    public let tlsJA3: String?
+    public let tlsJA4: String?
     public let quicVersion: UInt32?
     public let quicPacketType: String?
     public let quicDestinationConnectionId: String?
@@ -204,6 +216,16 @@ public struct PacketSample: Codable, Sendable, Equatable {
     public let tcpFlags: UInt8?
     public let tcpAck: Bool?
     public let tcpPsh: Bool?
+    /// Options both TCP endpoints agreed to during the handshake, when the tap observed both
+    /// the SYN and the SYN-ACK.
+    public let tcpNegotiatedOptions: TCPNegotiatedOptions?
+    /// Shannon entropy (bits per byte) of the flow's first sampled client payload bytes.
+    public let payloadEntropyOut: Double?
+    /// Shannon entropy (bits per byte) of the flow's first sampled remote payload bytes.
+    public let payloadEntropyIn: Double?
+    /// Base64 of the leading bytes of the flow's first client payload, present only when the
+    /// emission policy opts into payload previews.
+    public let firstPayloadPreview: String?
     public let packetCueReason: PacketCueReason?
     public let sessionId: String?
     public let packetStreamStartedAtMs: Double?
@@ -242,7 +264,15 @@ public struct PacketSample: Codable, Sendable, Equatable {
         packetCount: Int? = nil,
         flowPacketCount: Int? = nil,
         flowByteCount: Int? = nil,
+        flowDurationMs: Int? = nil,
+        flowOutboundByteCount: Int? = nil,
+        flowInboundByteCount: Int? = nil,
+        flowOutboundPacketCount: Int? = nil,
+        flowInboundPacketCount: Int? = nil,
+        flowHandle: UInt64? = nil,
+        interfaceId: UInt16? = nil,
         protocolHint: String,
+        protocolClass: FlowProtocolClass? = nil,
         ipVersion: UInt8? = nil,
         transportProtocolNumber: UInt8? = nil,
         sourceAddress: String? = nil,
@@ -254,6 +284,9 @@ public struct PacketSample: Codable, Sendable, Equatable {
         dnsCname: String? = nil,
         dnsAnswerAddresses: [String]? = nil,
         tlsServerName: String? = nil,
+        tlsECH: Bool? = nil,
+        tlsJA3: String? = nil,
+        tlsJA4: String? = nil,
         quicVersion: UInt32? = nil,
         quicPacketType: String? = nil,
         quicDestinationConnectionId: String? = nil,
@@ -302,6 +335,10 @@ public struct PacketSample: Codable, Sendable, Equatable {
         tcpFlags: UInt8? = nil,
         tcpAck: Bool? = nil,
         tcpPsh: Bool? = nil,
+        tcpNegotiatedOptions: TCPNegotiatedOptions? = nil,
+        payloadEntropyOut: Double? = nil,
+        payloadEntropyIn: Double? = nil,
+        firstPayloadPreview: String? = nil,
         packetCueReason: PacketCueReason? = nil,
         sessionContext: DetectorSessionContext? = nil,
         remoteAddress: String? = nil,
@@ -335,7 +372,15 @@ public struct PacketSample: Codable, Sendable, Equatable {
         self.packetCount = packetCount
         self.flowPacketCount = flowPacketCount
         self.flowByteCount = flowByteCount
+        self.flowDurationMs = flowDurationMs
+        self.flowOutboundByteCount = flowOutboundByteCount
+        self.flowInboundByteCount = flowInboundByteCount
+        self.flowOutboundPacketCount = flowOutboundPacketCount
+        self.flowInboundPacketCount = flowInboundPacketCount
+        self.flowHandle = flowHandle
+        self.interfaceId = interfaceId
         self.protocolHint = protocolHint
+        self.protocolClass = protocolClass
         self.ipVersion = ipVersion
         self.transportProtocolNumber = transportProtocolNumber
         self.sourceAddress = sourceAddress
@@ -347,6 +392,9 @@ public struct PacketSample: Codable, Sendable, Equatable {
         self.dnsCname = dnsCname
         self.dnsAnswerAddresses = dnsAnswerAddresses
         self.tlsServerName = tlsServerName
+        self.tlsECH = tlsECH
+        self.tlsJA3 = tlsJA3
+        self.tlsJA4 = tlsJA4
         self.quicVersion = quicVersion
         self.quicPacketType = quicPacketType
         self.quicDestinationConnectionId = quicDestinationConnectionId
@@ -395,6 +443,10 @@ public struct PacketSample: Codable, Sendable, Equatable {
         self.tcpFlags = tcpFlags
         self.tcpAck = tcpAck
         self.tcpPsh = tcpPsh
+        self.tcpNegotiatedOptions = tcpNegotiatedOptions
+        self.payloadEntropyOut = payloadEntropyOut
+        self.payloadEntropyIn = payloadEntropyIn
+        self.firstPayloadPreview = firstPayloadPreview
         self.packetCueReason = packetCueReason
         self.sessionId = sessionContext?.sessionId
         self.packetStreamStartedAtMs = sessionContext?.packetStreamStartedAtMs
@@ -517,12 +569,19 @@ public actor PacketSampleStream {
         let packetCount: Int?
         let flowPacketCount: Int?
         let flowByteCount: Int?
+        let flowDurationMs: Int?
+        let flowOutboundByteCount: Int?
+        let flowInboundByteCount: Int?
+        let flowOutboundPacketCount: Int?
+        let flowInboundPacketCount: Int?
         let protocolHint: String
+        let protocolClass: FlowProtocolClass?
         let ipVersion: UInt8?
         let transportProtocolNumber: UInt8?
         let sourcePort: UInt16?
         let destinationPort: UInt16?
         let flowHash: UInt64?
+        let interfaceId: UInt16?
         let textFlowId: String?
         let sourceAddressLength: UInt8?
         let sourceAddressHigh: UInt64?
@@ -537,6 +596,9 @@ public actor PacketSampleStream {
         let dnsCname: String?
         let dnsAnswerAddresses: [String]?
         let tlsServerName: String?
+        let tlsECH: Bool?
+        let tlsJA3: String?
+        let tlsJA4: String?
         let quicVersion: UInt32?
         let quicPacketType: String?
         let quicDestinationConnectionId: String?
@@ -585,6 +647,10 @@ public actor PacketSampleStream {
         let tcpFlags: UInt8?
         let tcpAck: Bool?
         let tcpPsh: Bool?
+        let tcpNegotiatedOptions: TCPNegotiatedOptions?
+        let payloadEntropyOut: Double?
+        let payloadEntropyIn: Double?
+        let firstPayloadPreview: String?
         let packetCueReason: PacketCueReason?
         let sessionId: String?
         let packetStreamStartedAtMs: Double?
@@ -622,12 +688,19 @@ public actor PacketSampleStream {
             packetCount: Int?,
             flowPacketCount: Int?,
             flowByteCount: Int?,
+            flowDurationMs: Int? = nil,
+            flowOutboundByteCount: Int? = nil,
+            flowInboundByteCount: Int? = nil,
+            flowOutboundPacketCount: Int? = nil,
+            flowInboundPacketCount: Int? = nil,
             protocolHint: String,
+            protocolClass: FlowProtocolClass? = nil,
             ipVersion: UInt8?,
             transportProtocolNumber: UInt8?,
             sourcePort: UInt16?,
             destinationPort: UInt16?,
             flowHash: UInt64?,
+            interfaceId: UInt16? = nil,
             textFlowId: String?,
             sourceAddressLength: UInt8?,
             sourceAddressHigh: UInt64?,
@@ -642,6 +715,9 @@ public actor PacketSampleStream {
             dnsCname: String?,
             dnsAnswerAddresses: [String]?,
             tlsServerName: String?,
+            tlsECH: Bool? = nil,
+            tlsJA3: String? = nil,
+            tlsJA4: String? = nil,
             quicVersion: UInt32?,
             quicPacketType: String?,
             quicDestinationConnectionId: String?,
@@ -690,6 +766,10 @@ public actor PacketSampleStream {
             tcpFlags: UInt8? = nil,
             tcpAck: Bool? = nil,
             tcpPsh: Bool? = nil,
+            tcpNegotiatedOptions: TCPNegotiatedOptions? = nil,
+            payloadEntropyOut: Double? = nil,
+            payloadEntropyIn: Double? = nil,
+            firstPayloadPreview: String? = nil,
             packetCueReason: PacketCueReason? = nil,
             sessionContext: DetectorSessionContext? = nil,
             remoteAddress: String? = nil,
@@ -722,12 +802,19 @@ public actor PacketSampleStream {
             self.packetCount = packetCount
             self.flowPacketCount = flowPacketCount
             self.flowByteCount = flowByteCount
+            self.flowDurationMs = flowDurationMs
+            self.flowOutboundByteCount = flowOutboundByteCount
+            self.flowInboundByteCount = flowInboundByteCount
+            self.flowOutboundPacketCount = flowOutboundPacketCount
+            self.flowInboundPacketCount = flowInboundPacketCount
             self.protocolHint = protocolHint
+            self.protocolClass = protocolClass
             self.ipVersion = ipVersion
             self.transportProtocolNumber = transportProtocolNumber
             self.sourcePort = sourcePort
             self.destinationPort = destinationPort
             self.flowHash = flowHash
+            self.interfaceId = interfaceId
             self.textFlowId = textFlowId
             self.sourceAddressLength = sourceAddressLength
             self.sourceAddressHigh = sourceAddressHigh
@@ -742,6 +829,9 @@ public actor PacketSampleStream {
             self.dnsCname = dnsCname
             self.dnsAnswerAddresses = dnsAnswerAddresses
             self.tlsServerName = tlsServerName
+            self.tlsECH = tlsECH
+            self.tlsJA3 = tlsJA3
+            self.tlsJA4 = tlsJA4
             self.quicVersion = quicVersion
             self.quicPacketType = quicPacketType
             self.quicDestinationConnectionId = quicDestinationConnectionId
@@ -790,6 +880,10 @@ public actor PacketSampleStream {
             self.tcpFlags = tcpFlags
             self.tcpAck = tcpAck
             self.tcpPsh = tcpPsh
+            self.tcpNegotiatedOptions = tcpNegotiatedOptions
+            self.payloadEntropyOut = payloadEntropyOut
+            self.payloadEntropyIn = payloadEntropyIn
+            self.firstPayloadPreview = firstPayloadPreview
             self.packetCueReason = packetCueReason
             self.sessionId = sessionContext?.sessionId
             self.packetStreamStartedAtMs = sessionContext?.packetStreamStartedAtMs
@@ -966,6 +1060,8 @@ public actor PacketSampleStream {
         add(sample.dnsCname)
         add(sample.dnsAnswerAddresses)
         add(sample.tlsServerName)
+        add(sample.tlsJA3)
+        add(sample.tlsJA4)
         add(sample.quicPacketType)
         add(sample.quicDestinationConnectionId)
         add(sample.quicSourceConnectionId)
@@ -1018,6 +1114,8 @@ public actor PacketSampleStream {
         add(record.dnsCname)
         add(record.dnsAnswerAddresses)
         add(record.tlsServerName)
+        add(record.tlsJA3)
+        add(record.tlsJA4)
         add(record.quicPacketType)
         add(record.quicDestinationConnectionId)
         add(record.quicSourceConnectionId)
@@ -1196,7 +1294,15 @@ public actor PacketSampleStream {
             packetCount: record.packetCount,
             flowPacketCount: record.flowPacketCount,
             flowByteCount: record.flowByteCount,
+            flowDurationMs: record.flowDurationMs,
+            flowOutboundByteCount: record.flowOutboundByteCount,
+            flowInboundByteCount: record.flowInboundByteCount,
+            flowOutboundPacketCount: record.flowOutboundPacketCount,
+            flowInboundPacketCount: record.flowInboundPacketCount,
+            flowHandle: record.flowHash,
+            interfaceId: record.interfaceId,
             protocolHint: record.protocolHint,
+            protocolClass: record.protocolClass,
             ipVersion: record.ipVersion,
             transportProtocolNumber: record.transportProtocolNumber,
             sourceAddress: sourceAddress,
@@ -1208,6 +1314,9 @@ public actor PacketSampleStream {
             dnsCname: record.dnsCname,
             dnsAnswerAddresses: record.dnsAnswerAddresses,
             tlsServerName: record.tlsServerName,
+            tlsECH: record.tlsECH,
+            tlsJA3: record.tlsJA3,
+            tlsJA4: record.tlsJA4,
             quicVersion: record.quicVersion,
             quicPacketType: record.quicPacketType,
             quicDestinationConnectionId: record.quicDestinationConnectionId,
@@ -1256,6 +1365,10 @@ public actor PacketSampleStream {
             tcpFlags: record.tcpFlags,
             tcpAck: record.tcpAck,
             tcpPsh: record.tcpPsh,
+            tcpNegotiatedOptions: record.tcpNegotiatedOptions,
+            payloadEntropyOut: record.payloadEntropyOut,
+            payloadEntropyIn: record.payloadEntropyIn,
+            firstPayloadPreview: record.firstPayloadPreview,
             packetCueReason: record.packetCueReason,
             sessionContext: DetectorSessionContext(
                 sessionId: record.sessionId,
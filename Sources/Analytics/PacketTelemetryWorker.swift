@@ -160,12 +160,15 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         let direction: PacketDirection
         let byteCount: Int
         let trackingMode: TrackingMode
+        let interfaceId: UInt16?
     }
 
     private final class SharedState: @unchecked Sendable {
         let lock = NSLock()
         var continuation: AsyncStream<Command>.Continuation?
         var acceptedBatches = 0
+        var acceptedPackets = 0
+        var pollPlanner = TelemetryPollPlanner()
         var queuedBatches = 0
         var queuedBytes = 0
         var droppedBatches = 0
@@ -178,6 +181,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         var lastPacketTimestampMs: Double?
         var sessionId: String?
         var detectionSnapshot: DetectionSnapshot
+        var batchExecutionHistograms = BatchExecutionHistograms()
         var hasEnteredShedMode = false
         var isStopped = false
 
@@ -195,8 +199,12 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
     private enum Command: Sendable {
         case batch(Batch)
         case updateSessionContext(DetectorSessionContext?, CommandSignal?)
+        case updateFlowPinning(FlowPinningPolicy, String, CommandSignal?)
+        case commitFlowPinningTransaction(FlowPinningTransaction, String, CommandSignal?)
         case reset(CommandSignal?)
         case clearDetections(CommandSignal?)
+        case resetInvalidPacketCounters(CommandSignal?)
+        case resetUsageAccounting(CommandSignal?)
         case barrier(CommandSignal?)
         case stop(CommandSignal?)
     }
@@ -221,6 +229,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         public let lowPowerModeEnabled: Bool
         public let health: TelemetryHealthRecord
         public let liveness: TelemetryStreamLiveness
+        public let batchExecution: BatchExecutionHistograms
     }
 
     private let pipeline: PacketAnalyticsPipeline
@@ -237,6 +246,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
     private let pathRegimeProvider: (any PathRegimeProvider)?
     private let liveTapPolicy: LiveTapPolicy
     private let packetCuePolicy: PacketCueEmissionPolicy
+    private let dnsNamePrivacyPolicy: DNSNamePrivacyPolicy
     private let telemetryDegradationPolicy: TelemetryDegradationPolicy
     private let writerProcess: String
 
@@ -261,6 +271,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         includePacketCuesInLiveTap: Bool = false,
         includeValidationRecordsInLiveTap: Bool = false,
         packetCuePolicy: PacketCueEmissionPolicy = .disabled,
+        dnsNamePrivacyPolicy: DNSNamePrivacyPolicy = .plaintext,
         telemetryDegradationPolicy: TelemetryDegradationPolicy = .default,
         writerProcess: String = "packetTunnelProvider"
     ) {
@@ -280,6 +291,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
             includePacketCuesInLiveTap: includePacketCuesInLiveTap,
             includeValidationRecordsInLiveTap: includeValidationRecordsInLiveTap,
             packetCuePolicy: packetCuePolicy,
+            dnsNamePrivacyPolicy: dnsNamePrivacyPolicy,
             telemetryDegradationPolicy: telemetryDegradationPolicy,
             writerProcess: writerProcess
         )
@@ -301,6 +313,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         includePacketCuesInLiveTap: Bool = false,
         includeValidationRecordsInLiveTap: Bool = false,
         packetCuePolicy: PacketCueEmissionPolicy = .disabled,
+        dnsNamePrivacyPolicy: DNSNamePrivacyPolicy = .plaintext,
         telemetryDegradationPolicy: TelemetryDegradationPolicy = .default,
         writerProcess: String = "packetTunnelProvider"
     ) {
@@ -310,6 +323,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         self.detectors = detectors
         self.richPacketLogStore = richPacketLogStore
         self.packetCuePolicy = packetCuePolicy
+        self.dnsNamePrivacyPolicy = dnsNamePrivacyPolicy
         self.telemetryDegradationPolicy = telemetryDegradationPolicy
         self.writerProcess = writerProcess
         let includePacketCueStream = includePacketCuesInLiveTap || includeValidationRecordsInLiveTap
@@ -360,10 +374,11 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         let pathRegimeProvider = self.pathRegimeProvider
         let liveTapPolicy = self.liveTapPolicy
         let packetCuePolicy = self.packetCuePolicy
+        let dnsNamePrivacyPolicy = self.dnsNamePrivacyPolicy
         let telemetryDegradationPolicy = self.telemetryDegradationPolicy
         let writerProcess = self.writerProcess
 
-        self.workerTask = Task { [state, pipeline, clock, packetStream, detectors, detectionPersistence, richPacketLogStore, logger, processInfo, emissionPolicyOverride, runtimePlan, pathRegimeProvider, liveTapPolicy, packetCuePolicy, telemetryDegradationPolicy, writerProcess] in
+        self.workerTask = Task { [state, pipeline, clock, packetStream, detectors, detectionPersistence, richPacketLogStore, logger, processInfo, emissionPolicyOverride, runtimePlan, pathRegimeProvider, liveTapPolicy, packetCuePolicy, dnsNamePrivacyPolicy, telemetryDegradationPolicy, writerProcess] in
             await Self.runLoop(
                 stream: stream,
                 state: state,
@@ -380,6 +395,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 pathRegimeProvider: pathRegimeProvider,
                 liveTapPolicy: liveTapPolicy,
                 packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacyPolicy: dnsNamePrivacyPolicy,
                 telemetryDegradationPolicy: telemetryDegradationPolicy,
                 writerProcess: writerProcess
             )
@@ -402,7 +418,14 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
     /// Enqueues one raw packet batch for telemetry processing.
     /// Decision: admission remains cheap on the provider queue; packet parsing and payload-only filtering happen on
     /// the worker task so telemetry cannot add per-packet parsing heat to the tunnel IO path.
-    public func submit(packets: [Data], families: [Int32], direction: PacketDirection) -> SubmitResult {
+    /// `interfaceId` tags the batch with the TUN interface it was read from, for hosts that
+    /// attach multiple tunnel flows; flows and telemetry records carry the tag through.
+    public func submit(
+        packets: [Data],
+        families: [Int32],
+        direction: PacketDirection,
+        interfaceId: UInt16? = nil
+    ) -> SubmitResult {
         guard !packets.isEmpty else {
             return state.withLock { state in
                 Self.incrementCounter(&state.skippedBatches)
@@ -461,6 +484,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
             state.queuedBatches = nextQueuedBatches
             state.queuedBytes = nextQueuedBytes
             Self.incrementCounter(&state.acceptedBatches)
+            state.acceptedPackets = Self.saturatingAdd(state.acceptedPackets, packets.count)
             state.continuation?.yield(
                 .batch(
                     Batch(
@@ -468,7 +492,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                         families: families,
                         direction: direction,
                         byteCount: rawByteCount,
-                        trackingMode: trackingMode
+                        trackingMode: trackingMode,
+                        interfaceId: interfaceId
                     )
                 )
             )
@@ -491,6 +516,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 processInfo: processInfo,
                 runtimePlan: runtimePlan,
                 packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacyPolicy: dnsNamePrivacyPolicy,
                 telemetryDegradationPolicy: telemetryDegradationPolicy
             )
             return Snapshot(
@@ -516,7 +542,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                     droppedSequenceCount: state.droppedSequenceCount,
                     sessionId: state.sessionId,
                     writerProcess: writerProcess
-                )
+                ),
+                batchExecution: state.batchExecutionHistograms
             )
         }
     }
@@ -537,6 +564,24 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         }
         let state = snapshot()
         let detections = Self.currentDetectionSnapshot(state: self.state)
+        let activeFlowCount = await pipeline.activeFlowPairCount()
+        // Interval selection happens here — at snapshot assembly — so the number the planner
+        // rates against is the same accepted-packet total the snapshot itself reports.
+        let recommendedPollIntervalMs = self.state.withLock { shared -> Int in
+            let interval = shared.pollPlanner.nextIntervalSeconds(
+                now: Date(),
+                activeFlowCount: activeFlowCount,
+                cumulativePacketCount: shared.acceptedPackets
+            )
+            return Int((interval * 1_000).rounded())
+        }
+        let payloadHistograms = await pipeline.payloadHistogramSnapshot()
+        let invalidPacketCounters = await pipeline.invalidPacketCountersSnapshot()
+        let exoticHeaders = await pipeline.exoticHeaderCountersSnapshot()
+        let tlsHandshakeVariation = await pipeline.tlsHandshakeVariationCountersSnapshot()
+        let dnsIntegrity = await pipeline.dnsIntegrityCountersSnapshot()
+        let dnsResolverStats = await pipeline.dnsResolverStatsSnapshot()
+        let discoveredServices = await pipeline.discoveredServicesSnapshot()
         return TunnelTelemetrySnapshot(
             samples: streamSnapshot.samples,
             retainedSampleCount: streamSnapshot.retainedSampleCount,
@@ -556,7 +601,17 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
             liveness: state.liveness,
             validationRecords: liveTapPolicy.includeValidationRecords || includeValidationRecords
                 ? streamSnapshot.samples.filter { $0.kind == .packetCue || $0.kind == .metadata || $0.kind == .sourceAppFlow }
-                : []
+                : [],
+            payloadHistograms: payloadHistograms.isEmpty ? nil : payloadHistograms,
+            invalidPacketCounters: invalidPacketCounters.isEmpty ? nil : invalidPacketCounters,
+            exoticHeaders: exoticHeaders.isEmpty ? nil : exoticHeaders,
+            tlsHandshakeVariation: tlsHandshakeVariation.isEmpty ? nil : tlsHandshakeVariation,
+            batchExecution: state.batchExecution.isEmpty ? nil : state.batchExecution,
+            dnsIntegrity: dnsIntegrity.isEmpty ? nil : dnsIntegrity,
+            dnsResolverStats: dnsResolverStats.isEmpty ? nil : dnsResolverStats,
+            discoveredServices: discoveredServices.isEmpty ? nil : discoveredServices,
+            activeFlowCount: activeFlowCount,
+            recommendedPollIntervalMs: recommendedPollIntervalMs
         )
     }
 
@@ -580,6 +635,110 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         await enqueueAndWait { .clearDetections($0) }
     }
 
+    /// Resets the invalid-packet counters so callers can measure malformed-input rates per interval.
+    public func resetInvalidPacketCounters() {
+        enqueue(.resetInvalidPacketCounters(nil))
+    }
+
+    /// Resets the invalid-packet counters and waits until the worker has applied the reset.
+    public func resetInvalidPacketCountersAndWait() async {
+        await enqueueAndWait { .resetInvalidPacketCounters($0) }
+    }
+
+    /// Returns hourly per-category usage buckets for "screen time"-style host features.
+    public func usageAccountingReport() async -> UsageAccountingReport {
+        await pipeline.usageAccountingReport()
+    }
+
+    /// Returns the per-second dashboard series (throughput, flow counts, DNS queries,
+    /// block hits) for the trailing window, clamped to the recorder's two-minute depth.
+    public func telemetrySeries(windowSeconds: Int = 60) async -> TunnelTelemetrySeries {
+        await pipeline.telemetrySeries(windowSeconds: windowSeconds)
+    }
+
+    /// Tallies relay policy-blocked flows into the dashboard series, since blocks happen
+    /// outside the packet path the pipeline observes.
+    public func noteBlockedFlows(_ count: Int = 1) async {
+        await pipeline.noteBlockedFlows(count)
+    }
+
+    /// Resolves one raw IP to the most recent hostname the tunnel observed resolving to it, so
+    /// hosts can label raw-IP flows. Returns `nil` for unparseable addresses and when no
+    /// association is inside its TTL.
+    public func hostnameAssociation(forAddress address: String) async -> DNSHostnameAssociation? {
+        guard let parsed = IPAddress(string: address) else {
+            return nil
+        }
+        return await pipeline.hostnameAssociation(for: parsed)
+    }
+
+    /// Returns the recent lifecycle breadcrumbs for one flow so an in-UI diagnostics view can
+    /// show what the pipeline did for a failing connection. Breadcrumbs outlive flow close and
+    /// are keyed by the flow hash stamped onto the flow's stream records.
+    public func flowBreadcrumbs(forFlowHash flowHash: UInt64) async -> [FlowBreadcrumb] {
+        await pipeline.flowBreadcrumbs(forFlowHash: flowHash)
+    }
+
+    /// Returns the completed HTTP exchanges (method, path, status, request-to-response
+    /// timing) observed on payload-visible flows, oldest first, for app-level waterfall
+    /// analysis. Requires `emitHTTPMetadata` in the emission policy; flows without
+    /// visible payload never produce entries.
+    public func httpExchanges() async -> [HTTPExchange] {
+        await pipeline.httpExchanges()
+    }
+
+    /// Returns the diagnostic snapshots the pipeline captured automatically when it observed
+    /// an anomaly (stuck TCP handshake, slow ingest batch, flow-table overflow), oldest first.
+    /// Each snapshot carries the implicated flows with their breadcrumbs plus table-level
+    /// counters, so field reports are investigable without verbose logging having been on.
+    /// The buffer is bounded; fetch and `clearDiagnosticSnapshots()` on a host cadence.
+    public func diagnosticSnapshots() async -> [PipelineDiagnosticSnapshot] {
+        await pipeline.diagnosticSnapshots()
+    }
+
+    /// Drops fetched diagnostic snapshots, typically after the host has persisted them.
+    public func clearDiagnosticSnapshots() async {
+        await pipeline.clearDiagnosticSnapshots()
+    }
+
+    /// Builds best-effort abort frames (TCP RST, ICMP port unreachable) for every flow the
+    /// pipeline still tracks. A stopping host writes these back to the client side so apps
+    /// fail immediately instead of waiting out connection timeouts after the tunnel goes away.
+    public func makeFlowTeardownFrames() async -> (packets: [Data], families: [Int32]) {
+        await pipeline.makeTeardownFrames()
+    }
+
+    /// Returns the DNS association cache's size and eviction counters for host-side monitoring.
+    public func dnsAssociationCacheStats() async -> DNSAssociationCacheStats {
+        await pipeline.dnsAssociationCacheStatsSnapshot()
+    }
+
+    /// Exports the observed IP-to-hostname map with per-entry remaining TTLs. Entries are
+    /// Codable, so the host can persist the snapshot as compact JSON and hand it back to
+    /// `importDNSAssociations` after a tunnel restart instead of starting with a cold cache.
+    public func exportDNSAssociations() async -> [DNSAssociationExportEntry] {
+        await pipeline.dnsAssociationExport()
+    }
+
+    /// Pre-seeds the association cache from a previously exported snapshot, returning how
+    /// many entries were accepted. Remaining TTLs are honored and clamped, so persisted
+    /// mappings never outlive what the live cache would have kept, and anything observed
+    /// this session wins over a seed.
+    @discardableResult
+    public func importDNSAssociations(_ entries: [DNSAssociationExportEntry]) async -> Int {
+        await pipeline.seedDNSAssociations(entries)
+    }
+
+    /// Clears usage buckets, typically after the host has persisted a report.
+    public func resetUsageAccounting() {
+        enqueue(.resetUsageAccounting(nil))
+    }
+
+    /// Clears usage buckets and waits until the worker has applied the reset.
+    public func resetUsageAccountingAndWait() async {
+        await enqueueAndWait { .resetUsageAccounting($0) }
+    }
+
     /// Updates app-supplied detector session context stamped onto future records.
     public func updateSessionContext(_ context: DetectorSessionContext?) {
         enqueue(.updateSessionContext(context, nil))
@@ -590,6 +749,35 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         await enqueueAndWait { .updateSessionContext(context, $0) }
     }
 
+    /// Replaces the flow-pinning rules that exempt critical long-lived flows from pruning.
+    /// The source label is stamped onto the policy audit log for attribution.
+    public func updateFlowPinning(_ policy: FlowPinningPolicy, source: String = "host") {
+        enqueue(.updateFlowPinning(policy, source, nil))
+    }
+
+    /// Replaces the flow-pinning rules and waits until future batches will observe them.
+    public func updateFlowPinningAndWait(_ policy: FlowPinningPolicy, source: String = "host") async {
+        await enqueueAndWait { .updateFlowPinning(policy, source, $0) }
+    }
+
+    /// Commits a batch of pinning-rule adds and removes as one atomic policy swap, so flow
+    /// admission never runs against a half-applied rule set. Discarding an uncommitted
+    /// transaction is the rollback.
+    public func commitFlowPinningTransaction(_ transaction: FlowPinningTransaction, source: String = "host") {
+        enqueue(.commitFlowPinningTransaction(transaction, source, nil))
+    }
+
+    /// Commits a pinning transaction and waits until future batches will observe it.
+    public func commitFlowPinningTransactionAndWait(_ transaction: FlowPinningTransaction, source: String = "host") async {
+        await enqueueAndWait { .commitFlowPinningTransaction(transaction, source, $0) }
+    }
+
+    /// Returns the audited flow-pinning rule changes (installed/removed, source, timestamp)
+    /// so multi-component hosts can reconstruct who changed policy and when.
+    public func policyChangeAudit() async -> [PolicyAuditEntry] {
+        await pipeline.policyAuditSnapshot()
+    }
+
     /// Waits until all previously enqueued telemetry work has been processed.
     public func flushAndWait() async {
         await enqueueAndWait { .barrier($0) }
@@ -630,6 +818,13 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         }
     }
 
+    private static func recordBatchExecution(state: SharedState, packetCount: Int, startedAt: Date, endedAt: Date) {
+        let durationMicroseconds = Int((endedAt.timeIntervalSince(startedAt) * 1_000_000).rounded())
+        state.withLock { state in
+            state.batchExecutionHistograms.record(packetCount: packetCount, durationMicroseconds: durationMicroseconds)
+        }
+    }
+
     private static func didSkipBatch(state: SharedState) {
         state.withLock { state in
             Self.incrementCounter(&state.skippedBatches)
@@ -888,6 +1083,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         pathRegimeProvider: (any PathRegimeProvider)?,
         liveTapPolicy: LiveTapPolicy,
         packetCuePolicy: PacketCueEmissionPolicy,
+        dnsNamePrivacyPolicy: DNSNamePrivacyPolicy,
         telemetryDegradationPolicy: TelemetryDegradationPolicy,
         writerProcess: String
     ) async {
@@ -899,11 +1095,15 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
             switch command {
             case .batch(let batch):
                 Self.didStartBatch(state: state, byteCount: batch.byteCount)
+                let batchStartedAt = await clock.now()
                 let filtered = Self.prefilter(
                     packets: batch.packets,
                     families: batch.families,
                     trackingMode: batch.trackingMode
                 )
+                if !filtered.invalidReasons.isEmpty {
+                    await pipeline.recordInvalidPackets(direction: batch.direction, reasons: filtered.invalidReasons)
+                }
                 guard !filtered.packets.isEmpty else {
                     Self.didSkipBatch(state: state)
                     continue
@@ -930,11 +1130,13 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                     processInfo: processInfo,
                     runtimePlan: runtimePlan,
                     packetCuePolicy: packetCuePolicy,
+                    dnsNamePrivacyPolicy: dnsNamePrivacyPolicy,
                     telemetryDegradationPolicy: telemetryDegradationPolicy
                 )
                 let runtimeContext = PacketAnalyticsPipeline.RuntimeContext(
                     pathRegime: policy.emitPathRegimeFields ? pathRegimeProvider?.currentSnapshot : nil,
-                    sessionContext: sessionContext
+                    sessionContext: sessionContext,
+                    interfaceId: batch.interfaceId
                 )
                 let records = await pipeline.ingest(
                     packets: filtered.packets,
@@ -945,6 +1147,12 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                     runtimeContext: runtimeContext
                 )
                 guard !records.isEmpty else {
+                    Self.recordBatchExecution(
+                        state: state,
+                        packetCount: filtered.packets.count,
+                        startedAt: batchStartedAt,
+                        endedAt: await clock.now()
+                    )
                     continue
                 }
                 Self.notePipelineRecords(state: state, records: records)
@@ -974,12 +1182,26 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                     await Self.publish(packetStream: packetStream, logger: logger, snapshotRecords)
                     Self.notePublishedRecords(state: state, records: snapshotRecords)
                 }
+                Self.recordBatchExecution(
+                    state: state,
+                    packetCount: filtered.packets.count,
+                    startedAt: batchStartedAt,
+                    endedAt: await clock.now()
+                )
 
             case .updateSessionContext(let context, let signal):
                 sessionContext = context
                 Self.setSessionContext(state: state, context)
                 signal?.resume()
 
+            case .updateFlowPinning(let policy, let source, let signal):
+                await pipeline.updateFlowPinning(policy, source: source)
+                signal?.resume()
+
+            case .commitFlowPinningTransaction(let transaction, let source, let signal):
+                await pipeline.commitFlowPinningTransaction(transaction, source: source)
+                signal?.resume()
+
             case .reset(let signal):
                 detailRecords.removeAll(keepingCapacity: false)
                 Self.setBufferedRecordCount(state: state, 0)
@@ -997,6 +1219,14 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 await detectionPersistence?.persistNow(cleared)
                 signal?.resume()
 
+            case .resetInvalidPacketCounters(let signal):
+                await pipeline.resetInvalidPacketCounters()
+                signal?.resume()
+
+            case .resetUsageAccounting(let signal):
+                await pipeline.resetUsageAccounting()
+                signal?.resume()
+
             case .barrier(let signal):
                 signal?.resume()
 
@@ -1289,6 +1519,9 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                     dnsAnswerAddresses: dnsAnswerAddresses,
                     registrableDomain: metadata?.registrableDomain,
                     tlsServerName: metadata?.tlsServerName,
+                    tlsECH: metadata?.tlsECH,
+                    tlsJA3: metadata?.tlsJA3,
+                    tlsJA4: metadata?.tlsJA4,
                     quicVersion: metadata?.quicVersion ?? summary.quicVersion,
                     quicPacketType: metadata?.quicPacketType?.rawValue ?? summary.quicPacketType?.rawValue,
                     quicDestinationConnectionId: policy.includeQUICConnectionIDs
@@ -1317,6 +1550,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         processInfo: ProcessInfo,
         runtimePlan: DetectorRuntimePlan,
         packetCuePolicy: PacketCueEmissionPolicy,
+        dnsNamePrivacyPolicy: DNSNamePrivacyPolicy,
         telemetryDegradationPolicy: TelemetryDegradationPolicy
     ) -> PacketAnalyticsPipeline.EmissionPolicy {
         let reportedThermalState = processInfo.tunnelThermalState
@@ -1345,7 +1579,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 emitBurstEvents: true,
                 emitActivitySamples: false,
                 emitPacketCues: runtimePlan.needsPacketCues,
-                packetCuePolicy: packetCuePolicy
+                packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacy: dnsNamePrivacyPolicy
             )
         }
 
@@ -1374,7 +1609,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 emitBurstEvents: true,
                 emitActivitySamples: true,
                 emitPacketCues: runtimePlan.needsPacketCues,
-                packetCuePolicy: packetCuePolicy
+                packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacy: dnsNamePrivacyPolicy
             )
         case .fair:
             return PacketAnalyticsPipeline.EmissionPolicy(
@@ -1398,7 +1634,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 emitBurstEvents: true,
                 emitActivitySamples: false,
                 emitPacketCues: runtimePlan.needsPacketCues,
-                packetCuePolicy: packetCuePolicy
+                packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacy: dnsNamePrivacyPolicy
             )
         case .serious, .critical, .unknown:
             return PacketAnalyticsPipeline.EmissionPolicy(
@@ -1422,7 +1659,8 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
                 emitBurstEvents: true,
                 emitActivitySamples: false,
                 emitPacketCues: runtimePlan.needsPacketCues,
-                packetCuePolicy: packetCuePolicy
+                packetCuePolicy: packetCuePolicy,
+                dnsNamePrivacy: dnsNamePrivacyPolicy
             )
         }
     }
@@ -1431,14 +1669,15 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         packets: [Data],
         families: [Int32],
         trackingMode: TrackingMode = .full
-    ) -> (packets: [Data], families: [Int32], summaries: [FastPacketSummary], byteCount: Int) {
+    ) -> (packets: [Data], families: [Int32], summaries: [FastPacketSummary], byteCount: Int, invalidReasons: [InvalidPacketReason]) {
         guard !packets.isEmpty else {
-            return ([], [], [], 0)
+            return ([], [], [], 0, [])
         }
 
         var filteredPackets: [Data] = []
         var filteredFamilies: [Int32] = []
         var filteredSummaries: [FastPacketSummary] = []
+        var invalidReasons: [InvalidPacketReason] = []
         filteredPackets.reserveCapacity(packets.count)
         filteredFamilies.reserveCapacity(packets.count)
         filteredSummaries.reserveCapacity(packets.count)
@@ -1446,8 +1685,11 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
         var totalBytes = 0
         for (index, packet) in packets.enumerated() {
             let familyHint = families.indices.contains(index) ? families[index] : 0
-            guard let summary = FastPacketSummary(data: packet, ipVersionHint: familyHint),
-                  shouldTrack(summary: summary, trackingMode: trackingMode) else {
+            guard let summary = FastPacketSummary(data: packet, ipVersionHint: familyHint) else {
+                invalidReasons.append(PacketAnalyticsPipeline.invalidPacketReason(for: packet))
+                continue
+            }
+            guard shouldTrack(summary: summary, trackingMode: trackingMode) else {
                 continue
             }
 
@@ -1457,7 +1699,7 @@ public final class PacketTelemetryWorker: @unchecked Sendable {
             totalBytes = Self.saturatingAdd(totalBytes, packet.count)
         }
 
-        return (filteredPackets, filteredFamilies, filteredSummaries, totalBytes)
+        return (filteredPackets, filteredFamilies, filteredSummaries, totalBytes, invalidReasons)
     }
 
     private static func incrementCounter(_ value: inout Int) {
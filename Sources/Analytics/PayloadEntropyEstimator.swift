@@ -0,0 +1,56 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Streaming Shannon-entropy estimate over the first payload bytes of a flow in one direction.
+/// Decision: sampling is capped at `sampleLimit` bytes so only a flow's first packets touch the
+/// histogram; that prefix is enough to separate encrypted transports (near 8 bits/byte) from
+/// plaintext protocols (typically under 6) without any DPI signatures.
+struct PayloadEntropyEstimator: Sendable, Equatable {
+    /// Payload bytes sampled per direction before the histogram is frozen.
+    static let sampleLimit = 512
+    /// Minimum sample below which the estimate is too noisy to report.
+    static let minimumSampleBytes = 64
+
+    private var byteCounts = [UInt16](repeating: 0, count: 256)
+    private(set) var sampledByteCount = 0
+
+    /// Whether the sampling cap has not been reached yet; callers skip the payload walk once
+    /// this turns false.
+    var wantsMoreBytes: Bool {
+        sampledByteCount < Self.sampleLimit
+    }
+
+    /// Folds payload bytes into the histogram until the sampling cap is reached.
+    mutating func observe(_ bytes: Data.SubSequence) {
+        var remaining = Self.sampleLimit - sampledByteCount
+        guard remaining > 0 else {
+            return
+        }
+        for byte in bytes {
+            byteCounts[Int(byte)] &+= 1
+            sampledByteCount += 1
+            remaining -= 1
+            if remaining == 0 {
+                break
+            }
+        }
+    }
+
+    /// Entropy of the sampled prefix in bits per byte (0...8), or `nil` while the sample is
+    /// below `minimumSampleBytes`.
+    var bitsPerByte: Double? {
+        guard sampledByteCount >= Self.minimumSampleBytes else {
+            return nil
+        }
+        let total = Double(sampledByteCount)
+        var entropy = 0.0
+        for count in byteCounts where count > 0 {
+            let probability = Double(count) / total
+            entropy -= probability * log2(probability)
+        }
+        return entropy
+    }
+}
@@ -0,0 +1,133 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Fixed-bucket histogram of transport payload sizes.
+/// Decision: bucket bounds are compile-time constants shared by writer and reader so snapshots stay
+/// comparable across sessions and the per-packet cost is one linear scan over seven bounds.
+public struct PayloadSizeHistogram: Codable, Sendable, Equatable {
+    /// Inclusive upper bounds in bytes; payloads above the last bound land in the overflow bucket.
+    public static let bucketUpperBounds = [64, 128, 256, 512, 1_024, 1_400, 1_500]
+
+    /// Per-bucket packet counts; the final element counts payloads above the last bound.
+    public private(set) var bucketCounts: [Int]
+    public private(set) var packetCount: Int
+    public private(set) var payloadBytes: Int
+
+    public init() {
+        self.bucketCounts = [Int](repeating: 0, count: Self.bucketUpperBounds.count + 1)
+        self.packetCount = 0
+        self.payloadBytes = 0
+    }
+
+    public var isEmpty: Bool {
+        packetCount == 0
+    }
+
+    /// Records one payload observation.
+    /// - Parameter payloadLength: Transport payload length in bytes.
+    public mutating func record(payloadLength: Int) {
+        guard payloadLength > 0 else {
+            return
+        }
+        let bucketIndex = Self.bucketUpperBounds.firstIndex { payloadLength <= $0 }
+            ?? Self.bucketUpperBounds.count
+        bucketCounts[bucketIndex] = saturatingAdd(bucketCounts[bucketIndex], 1)
+        packetCount = saturatingAdd(packetCount, 1)
+        payloadBytes = saturatingAdd(payloadBytes, payloadLength)
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (value, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : value
+    }
+}
+
+/// Payload-size histograms bucketed by coarse application-protocol class.
+/// Decision: explicit per-class properties instead of a dictionary keyed by `FlowProtocolClass` keep the
+/// encoded shape stable and make snapshot diffs readable without exporting raw packet events.
+public struct FlowClassPayloadHistograms: Codable, Sendable, Equatable {
+    private enum CodingKeys: String, CodingKey {
+        case dns
+        case tls
+        case quic
+        case encryptedDns
+        case websocket
+        case other
+    }
+
+    public private(set) var dns: PayloadSizeHistogram
+    public private(set) var tls: PayloadSizeHistogram
+    public private(set) var quic: PayloadSizeHistogram
+    public private(set) var encryptedDns: PayloadSizeHistogram
+    public private(set) var websocket: PayloadSizeHistogram
+    public private(set) var other: PayloadSizeHistogram
+
+    public init() {
+        self.dns = PayloadSizeHistogram()
+        self.tls = PayloadSizeHistogram()
+        self.quic = PayloadSizeHistogram()
+        self.encryptedDns = PayloadSizeHistogram()
+        self.websocket = PayloadSizeHistogram()
+        self.other = PayloadSizeHistogram()
+    }
+
+    public init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: CodingKeys.self)
+        self.dns = try container.decode(PayloadSizeHistogram.self, forKey: .dns)
+        self.tls = try container.decode(PayloadSizeHistogram.self, forKey: .tls)
+        self.quic = try container.decode(PayloadSizeHistogram.self, forKey: .quic)
+        // Snapshots written before the encrypted-DNS split decode as an empty bucket.
+        self.encryptedDns = try container.decodeIfPresent(PayloadSizeHistogram.self, forKey: .encryptedDns)
+            ?? PayloadSizeHistogram()
+        // Snapshots written before WebSocket tagging decode as an empty bucket the same way.
+        self.websocket = try container.decodeIfPresent(PayloadSizeHistogram.self, forKey: .websocket)
+            ?? PayloadSizeHistogram()
+        self.other = try container.decode(PayloadSizeHistogram.self, forKey: .other)
+    }
+
+    public var isEmpty: Bool {
+        dns.isEmpty && tls.isEmpty && quic.isEmpty && encryptedDns.isEmpty && websocket.isEmpty && other.isEmpty
+    }
+
+    /// Returns the histogram tracked for one protocol class.
+    public subscript(protocolClass: FlowProtocolClass) -> PayloadSizeHistogram {
+        switch protocolClass {
+        case .dns:
+            return dns
+        case .tls:
+            return tls
+        case .quic:
+            return quic
+        case .encryptedDNS:
+            return encryptedDns
+        case .websocket:
+            return websocket
+        case .other:
+            return other
+        }
+    }
+
+    /// Records one payload observation against the given protocol class.
+    /// - Parameters:
+    ///   - protocolClass: Coarse class derived from flow metadata.
+    ///   - payloadLength: Transport payload length in bytes.
+    public mutating func record(protocolClass: FlowProtocolClass, payloadLength: Int) {
+        switch protocolClass {
+        case .dns:
+            dns.record(payloadLength: payloadLength)
+        case .tls:
+            tls.record(payloadLength: payloadLength)
+        case .quic:
+            quic.record(payloadLength: payloadLength)
+        case .encryptedDNS:
+            encryptedDns.record(payloadLength: payloadLength)
+        case .websocket:
+            websocket.record(payloadLength: payloadLength)
+        case .other:
+            other.record(payloadLength: payloadLength)
+        }
+    }
+}
@@ -0,0 +1,156 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One flow-table entry as captured inside a diagnostic snapshot, paired with the
+/// flow's recent lifecycle breadcrumbs.
+public struct PipelineFlowDiagnostic: Sendable, Equatable {
+    public let flowHash: UInt64
+    public let protocolHint: String
+    public let classification: String?
+    public let isPinned: Bool
+    public let openedAt: Date
+    public let lastSeen: Date
+    public let totalPacketCount: Int
+    public let totalByteCount: Int
+    public let outboundPacketCount: Int
+    public let inboundPacketCount: Int
+    public let breadcrumbs: [FlowBreadcrumb]
+
+    /// - Parameters:
+    ///   - flowHash: Hash stamped onto the flow's stream records.
+    ///   - protocolHint: Transport-level protocol hint from the flow's record template.
+    ///   - classification: Signature classification, when one was assigned.
+    ///   - isPinned: Whether pinning rules exempt the flow from eviction.
+    ///   - openedAt: When the pipeline first saw the flow.
+    ///   - lastSeen: When the pipeline last saw a packet for the flow.
+    ///   - totalPacketCount: Packets observed in both directions.
+    ///   - totalByteCount: Bytes observed in both directions.
+    ///   - outboundPacketCount: Client-to-remote packets observed.
+    ///   - inboundPacketCount: Remote-to-client packets observed.
+    ///   - breadcrumbs: Recent lifecycle breadcrumbs for the flow, oldest first.
+    public init(
+        flowHash: UInt64,
+        protocolHint: String,
+        classification: String?,
+        isPinned: Bool,
+        openedAt: Date,
+        lastSeen: Date,
+        totalPacketCount: Int,
+        totalByteCount: Int,
+        outboundPacketCount: Int,
+        inboundPacketCount: Int,
+        breadcrumbs: [FlowBreadcrumb]
+    ) {
+        self.flowHash = flowHash
+        self.protocolHint = protocolHint
+        self.classification = classification
+        self.isPinned = isPinned
+        self.openedAt = openedAt
+        self.lastSeen = lastSeen
+        self.totalPacketCount = totalPacketCount
+        self.totalByteCount = totalByteCount
+        self.outboundPacketCount = outboundPacketCount
+        self.inboundPacketCount = inboundPacketCount
+        self.breadcrumbs = breadcrumbs
+    }
+}
+
+/// Structured diagnostic state the pipeline captured automatically when it observed an
+/// anomaly, so a field report can be investigated from what was already recorded instead
+/// of asking the user to reproduce it with verbose logging enabled.
+public struct PipelineDiagnosticSnapshot: Sendable, Equatable {
+    /// Anomaly that caused the capture.
+    public enum Trigger: String, Sendable {
+        /// An outbound TCP flow sent traffic but saw no reply long past handshake timescales.
+        case stuckHandshake = "stuck-handshake"
+        /// One ingest batch took longer to process than the latency budget.
+        case ingestLatencySpike = "ingest-latency-spike"
+        /// The flow table hit its tracking budget and had to evict live flows.
+        case flowTableOverflow = "flow-table-overflow"
+    }
+
+    public let trigger: Trigger
+    /// Human-readable specifics of the trigger (which flow, how slow, how full).
+    public let detail: String
+    public let capturedAt: Date
+    public let trackedFlowCount: Int
+    public let pinnedFlowCount: Int
+    public let invalidPackets: InvalidPacketCounters
+    /// Bounded selection of the flows most relevant to the trigger, with breadcrumbs.
+    public let flows: [PipelineFlowDiagnostic]
+
+    /// - Parameters:
+    ///   - trigger: Anomaly that caused the capture.
+    ///   - detail: Human-readable specifics of the trigger.
+    ///   - capturedAt: Pipeline-clock time of the capture.
+    ///   - trackedFlowCount: Flow-table size at capture time.
+    ///   - pinnedFlowCount: Pinned flows at capture time.
+    ///   - invalidPackets: Direction-aware invalid-packet counters at capture time.
+    ///   - flows: Bounded selection of implicated flows.
+    public init(
+        trigger: Trigger,
+        detail: String,
+        capturedAt: Date,
+        trackedFlowCount: Int,
+        pinnedFlowCount: Int,
+        invalidPackets: InvalidPacketCounters,
+        flows: [PipelineFlowDiagnostic]
+    ) {
+        self.trigger = trigger
+        self.detail = detail
+        self.capturedAt = capturedAt
+        self.trackedFlowCount = trackedFlowCount
+        self.pinnedFlowCount = pinnedFlowCount
+        self.invalidPackets = invalidPackets
+        self.flows = flows
+    }
+}
+
+/// Bounded buffer of automatically captured diagnostic snapshots with a per-trigger
+/// cooldown, so one persistent anomaly cannot flood the buffer or the hot path.
+/// Decision: snapshots survive until the host fetches and clears them — the interesting
+/// reads happen well after the anomaly, often from a support workflow.
+internal struct PipelineDiagnosticBuffer: Sendable {
+    enum Policy {
+        static let maxSnapshots = 8
+        static let cooldownSeconds: TimeInterval = 30
+        static let maxFlowsPerSnapshot = 16
+    }
+
+    private var storedSnapshots: [PipelineDiagnosticSnapshot] = []
+    private var lastCapturedByTrigger: [PipelineDiagnosticSnapshot.Trigger.RawValue: Date] = [:]
+
+    /// Whether capturing this trigger now is allowed, i.e. its cooldown has elapsed.
+    /// Callers check before assembling a snapshot so the hot path skips the expensive
+    /// flow-table walk while a trigger is cooling down.
+    func shouldCapture(_ trigger: PipelineDiagnosticSnapshot.Trigger, now: Date) -> Bool {
+        guard let lastCapturedAt = lastCapturedByTrigger[trigger.rawValue] else {
+            return true
+        }
+        return now.timeIntervalSince(lastCapturedAt) >= Policy.cooldownSeconds
+    }
+
+    /// Appends one snapshot, dropping the oldest beyond capacity, and starts the
+    /// trigger's cooldown.
+    mutating func record(_ snapshot: PipelineDiagnosticSnapshot) {
+        storedSnapshots.append(snapshot)
+        if storedSnapshots.count > Policy.maxSnapshots {
+            storedSnapshots.removeFirst(storedSnapshots.count - Policy.maxSnapshots)
+        }
+        lastCapturedByTrigger[snapshot.trigger.rawValue] = snapshot.capturedAt
+    }
+
+    /// Captured snapshots in capture order, oldest first.
+    var snapshots: [PipelineDiagnosticSnapshot] {
+        storedSnapshots
+    }
+
+    /// Drops captured snapshots; cooldowns are kept so clearing cannot re-arm a
+    /// persistent anomaly into immediate recapture.
+    mutating func clear() {
+        storedSnapshots.removeAll()
+    }
+}
@@ -0,0 +1,88 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One audited rule change: which rule appeared or disappeared, who pushed it, and when.
+public struct PolicyAuditEntry: Sendable, Equatable {
+    /// Direction of the rule change within a policy replacement.
+    public enum Change: String, Sendable {
+        case installed
+        case removed
+    }
+
+    public let change: Change
+    public let destinationPort: UInt16?
+    public let hostSuffix: String?
+    /// Host-supplied label for the component that pushed the change.
+    public let source: String
+    public let timestamp: Date
+
+    /// - Parameters:
+    ///   - change: Whether the rule was installed or removed.
+    ///   - destinationPort: Port criterion of the affected rule, when set.
+    ///   - hostSuffix: Host-suffix criterion of the affected rule, when set.
+    ///   - source: Label naming the component that pushed the change.
+    ///   - timestamp: When the replacement was applied, from the pipeline clock.
+    public init(
+        change: Change,
+        destinationPort: UInt16?,
+        hostSuffix: String?,
+        source: String,
+        timestamp: Date
+    ) {
+        self.change = change
+        self.destinationPort = destinationPort
+        self.hostSuffix = hostSuffix
+        self.source = source
+        self.timestamp = timestamp
+    }
+}
+
+/// Bounded audit trail of flow-pinning rule changes so multi-component hosts can reconstruct
+/// "who pinned (or unpinned) this domain and when".
+/// Decision: replacements are recorded as per-rule installed/removed diffs instead of whole-policy
+/// snapshots; the questions hosts ask are about individual rules, and diffs keep the buffer dense.
+internal struct PolicyAuditLog: Sendable {
+    enum Policy {
+        static let maxEntries = 128
+    }
+
+    private var entries: [PolicyAuditEntry] = []
+
+    /// Records the rule-level diff between the outgoing and incoming policies.
+    mutating func recordReplacement(
+        from oldPolicy: FlowPinningPolicy,
+        to newPolicy: FlowPinningPolicy,
+        source: String,
+        now: Date
+    ) {
+        for rule in oldPolicy.rules where !rule.isEmpty && !newPolicy.rules.contains(rule) {
+            append(change: .removed, rule: rule, source: source, now: now)
+        }
+        for rule in newPolicy.rules where !rule.isEmpty && !oldPolicy.rules.contains(rule) {
+            append(change: .installed, rule: rule, source: source, now: now)
+        }
+    }
+
+    /// Returns the audited changes in application order, oldest first.
+    func snapshot() -> [PolicyAuditEntry] {
+        entries
+    }
+
+    private mutating func append(change: PolicyAuditEntry.Change, rule: FlowPinRule, source: String, now: Date) {
+        entries.append(
+            PolicyAuditEntry(
+                change: change,
+                destinationPort: rule.destinationPort,
+                hostSuffix: rule.hostSuffix,
+                source: source,
+                timestamp: now
+            )
+        )
+        if entries.count > Policy.maxEntries {
+            entries.removeFirst(entries.count - Policy.maxEntries)
+        }
+    }
+}
@@ -128,6 +128,11 @@ public actor RichPacketLogStore {
     }
 
     public func append(records: [RichPacketLogRecord]) async {
+#if RELATIVE_MINIMAL_PROFILE
+        // Rich packet capture is compiled out in the ios-minimal profile; reads of any files
+        // written by a full-profile build still work, only new writes are disabled.
+        _ = records
+#else
         guard policy.isEnabled, !records.isEmpty else {
             return
         }
@@ -146,6 +151,7 @@ public actor RichPacketLogStore {
         } catch {
             drops.incrementIOError()
         }
+#endif
     }
 
     public func snapshot() throws -> RichPacketLogStoreSnapshot {
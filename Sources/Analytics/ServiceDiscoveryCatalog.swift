@@ -0,0 +1,166 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One DNS-SD resource record parsed from an mDNS message.
+public struct DNSServiceRecord: Codable, Sendable, Equatable {
+    public enum Kind: String, Codable, Sendable {
+        /// PTR record mapping a service type to a service instance name.
+        case pointer
+        /// SRV record mapping a service instance to a target host and port.
+        case service
+        /// TXT record carrying the instance's advertised key/value entries.
+        case text
+    }
+
+    public let kind: Kind
+    /// Record owner name, e.g. `_airplay._tcp.local` for pointers or the full instance name otherwise.
+    public let name: String
+    /// Pointer target (instance name) or SRV target host.
+    public let target: String?
+    /// SRV port the instance listens on.
+    public let port: UInt16?
+    /// Decoded TXT entries, in record order.
+    public let textEntries: [String]?
+
+    public init(kind: Kind, name: String, target: String? = nil, port: UInt16? = nil, textEntries: [String]? = nil) {
+        self.kind = kind
+        self.name = name
+        self.target = target
+        self.port = port
+        self.textEntries = textEntries
+    }
+}
+
+/// One local-network service assembled from the DNS-SD records observed for a service instance.
+public struct DiscoveredService: Codable, Sendable, Equatable {
+    /// Service type, e.g. `_airplay._tcp.local`.
+    public let serviceType: String
+    /// Full instance name, e.g. `Living Room._airplay._tcp.local`.
+    public let instanceName: String
+    /// SRV target host, once an SRV record has been seen.
+    public let targetHost: String?
+    /// SRV port, once an SRV record has been seen.
+    public let port: UInt16?
+    /// TXT entries from the most recent TXT record, when present.
+    public let textEntries: [String]?
+    /// Address the announcement came from.
+    public let advertiserAddress: String
+    public let lastSeenAt: Date
+
+    public init(
+        serviceType: String,
+        instanceName: String,
+        targetHost: String? = nil,
+        port: UInt16? = nil,
+        textEntries: [String]? = nil,
+        advertiserAddress: String,
+        lastSeenAt: Date
+    ) {
+        self.serviceType = serviceType
+        self.instanceName = instanceName
+        self.targetHost = targetHost
+        self.port = port
+        self.textEntries = textEntries
+        self.advertiserAddress = advertiserAddress
+        self.lastSeenAt = lastSeenAt
+    }
+}
+
+/// Accumulates DNS-SD records from observed mDNS traffic into per-instance service entries.
+/// Decision: PTR records establish the instance and its type; SRV and TXT records seen for the same
+/// instance fill in host, port, and attributes. Entries merge in place so repeated announcements
+/// refresh rather than duplicate, and the catalog is capped so a chatty network cannot grow it
+/// without bound.
+internal struct ServiceDiscoveryCatalog {
+    private struct Entry {
+        let serviceType: String
+        let instanceName: String
+        var targetHost: String?
+        var port: UInt16?
+        var textEntries: [String]?
+        var advertiserAddress: String
+        var lastSeenAt: Date
+    }
+
+    private enum Policy {
+        static let maxTrackedServices = 256
+    }
+
+    /// Keyed by lowercased instance name.
+    private var entries: [String: Entry] = [:]
+
+    /// Folds one message's service records into the catalog.
+    mutating func record(serviceRecords: [DNSServiceRecord], advertiserAddress: String, now: Date) {
+        for record in serviceRecords {
+            switch record.kind {
+            case .pointer:
+                guard let instanceName = record.target else {
+                    continue
+                }
+                withEntry(instanceName: instanceName, serviceType: record.name, advertiserAddress: advertiserAddress, now: now) { _ in }
+            case .service:
+                withEntry(instanceName: record.name, serviceType: serviceType(ofInstance: record.name), advertiserAddress: advertiserAddress, now: now) { entry in
+                    entry.targetHost = record.target
+                    entry.port = record.port
+                }
+            case .text:
+                withEntry(instanceName: record.name, serviceType: serviceType(ofInstance: record.name), advertiserAddress: advertiserAddress, now: now) { entry in
+                    entry.textEntries = record.textEntries
+                }
+            }
+        }
+    }
+
+    /// Returns discovered services sorted by instance name for stable snapshots.
+    func snapshot() -> [DiscoveredService] {
+        entries
+            .map { _, entry in
+                DiscoveredService(
+                    serviceType: entry.serviceType,
+                    instanceName: entry.instanceName,
+                    targetHost: entry.targetHost,
+                    port: entry.port,
+                    textEntries: entry.textEntries,
+                    advertiserAddress: entry.advertiserAddress,
+                    lastSeenAt: entry.lastSeenAt
+                )
+            }
+            .sorted { $0.instanceName < $1.instanceName }
+    }
+
+    /// Updates or creates the entry for one instance, dropping new instances past the cap.
+    private mutating func withEntry(
+        instanceName: String,
+        serviceType: String,
+        advertiserAddress: String,
+        now: Date,
+        _ body: (inout Entry) -> Void
+    ) {
+        let key = instanceName.lowercased()
+        if entries[key] == nil {
+            guard entries.count < Policy.maxTrackedServices else {
+                return
+            }
+            entries[key] = Entry(serviceType: serviceType, instanceName: instanceName, advertiserAddress: advertiserAddress, lastSeenAt: now)
+        }
+        if var entry = entries[key] {
+            entry.advertiserAddress = advertiserAddress
+            entry.lastSeenAt = now
+            body(&entry)
+            entries[key] = entry
+        }
+    }
+
+    /// Derives the service type from an instance name by dropping the leading instance label,
+    /// e.g. `Living Room._airplay._tcp.local` -> `_airplay._tcp.local`.
+    private func serviceType(ofInstance instanceName: String) -> String {
+        let labels = instanceName.split(separator: ".", omittingEmptySubsequences: false)
+        if let typeStart = labels.firstIndex(where: { $0.hasPrefix("_") }), typeStart > 0 {
+            return labels[typeStart...].joined(separator: ".")
+        }
+        return instanceName
+    }
+}
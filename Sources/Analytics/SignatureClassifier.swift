@@ -53,10 +53,12 @@ public actor SignatureClassifier {
     private let decoder: JSONDecoder
     private let maxCachedLookups = 4_096
 
+#if !RELATIVE_MINIMAL_PROFILE
     private var cache: SignatureDocument?
     private var cacheURL: URL?
     private var suffixTrieNodes: [SuffixTrieNode] = [SuffixTrieNode()]
     private var classificationCache: [String: String?] = [:]
+#endif
 
     /// Creates a classifier with an empty in-memory cache.
     /// - Parameter logger: Structured logger used for reload events and errors.
@@ -70,6 +72,19 @@ public actor SignatureClassifier {
     /// - Parameter url: JSON file containing a `SignatureDocument`.
     /// - Throws: File read or decode errors.
     public func load(from url: URL) async throws {
+#if RELATIVE_MINIMAL_PROFILE
+        // Signature classification is compiled out in the ios-minimal profile; the load is
+        // acknowledged so hosts sharing config across profiles do not treat it as a failure.
+        await logger.log(
+            level: .info,
+            phase: .config,
+            category: .analyticsClassifier,
+            component: "SignatureClassifier",
+            event: "reload-skipped",
+            message: "Signature classification is compiled out in the minimal profile",
+            metadata: ["source": url.path]
+        )
+#else
         let payload = try Data(contentsOf: url)
         let document = try decoder.decode(SignatureDocument.self, from: payload)
         cache = document
@@ -93,12 +108,16 @@ public actor SignatureClassifier {
                 "source": url.path
             ]
         )
+#endif
     }
 
     /// Resolves a hostname to a classification label using suffix matching.
     /// - Parameter host: Hostname to classify.
     /// - Returns: Matching label, or `nil` when no signature matches.
     public func classify(host: String) -> String? {
+#if RELATIVE_MINIMAL_PROFILE
+        return nil
+#else
         guard cache != nil else {
             return nil
         }
@@ -119,8 +138,10 @@ public actor SignatureClassifier {
         }
         classificationCache[normalized] = classification
         return classification
+#endif
     }
 
+#if !RELATIVE_MINIMAL_PROFILE
     private func indexedClassification(for normalizedHost: String) -> String? {
         var nodeIndex = 0
         var bestMatch = suffixTrieNodes[nodeIndex].match
@@ -173,4 +194,5 @@ public actor SignatureClassifier {
         }
         suffixTrieNodes[nodeIndex].match = match
     }
+#endif
 }
@@ -0,0 +1,128 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// TCP options one endpoint advertised in a SYN or SYN-ACK.
+/// Decision: only the four options that matter for loss recovery and throughput diagnosis are
+/// extracted (timestamps, SACK-permitted, window scale, MSS); everything else is skipped by length.
+struct TCPHandshakeOptions: Sendable, Equatable {
+    let timestamps: Bool
+    let sackPermitted: Bool
+    let windowScale: UInt8?
+    let maximumSegmentSize: UInt16?
+
+    /// Parses the TCP option list from a raw SYN or SYN-ACK packet.
+    /// Returns `nil` when the packet does not carry a directly-reachable TCP header
+    /// (IPv6 extension chains pass through unparsed, matching the fast path).
+    static func parse(packet: Data) -> TCPHandshakeOptions? {
+        let bytes = [UInt8](packet)
+        guard let first = bytes.first else {
+            return nil
+        }
+        let tcpStart: Int
+        switch (first >> 4) & 0x0f {
+        case 4:
+            let headerLength = Int(bytes[0] & 0x0f) * 4
+            guard headerLength >= 20, bytes.count > headerLength, bytes[9] == 6 else {
+                return nil
+            }
+            tcpStart = headerLength
+        case 6:
+            guard bytes.count > 40, bytes[6] == 6 else {
+                return nil
+            }
+            tcpStart = 40
+        default:
+            return nil
+        }
+        guard bytes.count >= tcpStart + 20 else {
+            return nil
+        }
+        let dataOffset = Int(bytes[tcpStart + 12] >> 4) * 4
+        guard dataOffset >= 20, bytes.count >= tcpStart + dataOffset else {
+            return nil
+        }
+
+        var timestamps = false
+        var sackPermitted = false
+        var windowScale: UInt8?
+        var maximumSegmentSize: UInt16?
+        var index = tcpStart + 20
+        let end = tcpStart + dataOffset
+        while index < end {
+            let kind = bytes[index]
+            if kind == 0 {
+                break
+            }
+            if kind == 1 {
+                index += 1
+                continue
+            }
+            guard index + 1 < end else {
+                break
+            }
+            let length = Int(bytes[index + 1])
+            guard length >= 2, index + length <= end else {
+                break
+            }
+            switch kind {
+            case 2 where length == 4:
+                maximumSegmentSize = UInt16(bytes[index + 2]) << 8 | UInt16(bytes[index + 3])
+            case 3 where length == 3:
+                windowScale = bytes[index + 2]
+            case 4 where length == 2:
+                sackPermitted = true
+            case 8 where length == 10:
+                timestamps = true
+            default:
+                break
+            }
+            index += length
+        }
+        return TCPHandshakeOptions(
+            timestamps: timestamps,
+            sackPermitted: sackPermitted,
+            windowScale: windowScale,
+            maximumSegmentSize: maximumSegmentSize
+        )
+    }
+}
+
+/// TCP options both endpoints agreed to use, derived from a flow's SYN and SYN-ACK.
+/// Reported on flow records so high-BDP loss-recovery behavior (timestamps, SACK) is
+/// observable per flow instead of inferred from retransmission shapes.
+public struct TCPNegotiatedOptions: Codable, Sendable, Equatable {
+    /// RFC 7323 timestamps are active: both sides sent the timestamps option.
+    public let timestampsEnabled: Bool
+    /// RFC 2018 selective acknowledgment is active: both sides sent SACK-permitted.
+    public let selectiveAckEnabled: Bool
+    /// Window-scale shift the server advertised, present only when both sides offered scaling.
+    public let windowScale: UInt8?
+    /// Smaller of the two advertised maximum segment sizes.
+    public let maximumSegmentSize: UInt16?
+
+    public init(
+        timestampsEnabled: Bool,
+        selectiveAckEnabled: Bool,
+        windowScale: UInt8? = nil,
+        maximumSegmentSize: UInt16? = nil
+    ) {
+        self.timestampsEnabled = timestampsEnabled
+        self.selectiveAckEnabled = selectiveAckEnabled
+        self.windowScale = windowScale
+        self.maximumSegmentSize = maximumSegmentSize
+    }
+
+    init(offered: TCPHandshakeOptions, answered: TCPHandshakeOptions) {
+        timestampsEnabled = offered.timestamps && answered.timestamps
+        selectiveAckEnabled = offered.sackPermitted && answered.sackPermitted
+        windowScale = offered.windowScale != nil ? answered.windowScale : nil
+        if let offeredMSS = offered.maximumSegmentSize, let answeredMSS = answered.maximumSegmentSize {
+            maximumSegmentSize = min(offeredMSS, answeredMSS)
+        } else {
+            maximumSegmentSize = offered.maximumSegmentSize ?? answered.maximumSegmentSize
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import TunnelRuntime
+
+/// Counters over ClientHello randomization features the pipeline observed in a window.
+/// Decision: bot-mitigation research needs to know how much of the tunnel's TLS traffic
+/// uses GREASE, unusual cipher orders, or resumption — aggregate counts answer that
+/// without exporting fingerprints or raw handshakes, mirroring `ExoticHeaderCounters`.
+public struct TLSHandshakeVariationCounters: Codable, Sendable, Equatable {
+    /// ClientHellos that parsed far enough to yield variation features.
+    public private(set) var observedHelloCount: Int
+    /// Hellos carrying at least one GREASE value (RFC 8701).
+    public private(set) var greaseHelloCount: Int
+    /// Hellos offering a TLS 1.3 suite after a legacy suite in the cipher list.
+    public private(set) var unusualCipherOrderCount: Int
+    /// Hellos offering session resumption (legacy session ID, ticket, or pre-shared key).
+    public private(set) var sessionResumptionCount: Int
+
+    public init(
+        observedHelloCount: Int = 0,
+        greaseHelloCount: Int = 0,
+        unusualCipherOrderCount: Int = 0,
+        sessionResumptionCount: Int = 0
+    ) {
+        self.observedHelloCount = max(0, observedHelloCount)
+        self.greaseHelloCount = max(0, greaseHelloCount)
+        self.unusualCipherOrderCount = max(0, unusualCipherOrderCount)
+        self.sessionResumptionCount = max(0, sessionResumptionCount)
+    }
+
+    public var isEmpty: Bool {
+        observedHelloCount == 0
+            && greaseHelloCount == 0
+            && unusualCipherOrderCount == 0
+            && sessionResumptionCount == 0
+    }
+
+    /// Tallies the features of one parsed ClientHello.
+    mutating func record(variation: TLSClientHelloVariation) {
+        observedHelloCount = saturatingAdd(observedHelloCount, 1)
+        if variation.greaseValueCount > 0 {
+            greaseHelloCount = saturatingAdd(greaseHelloCount, 1)
+        }
+        if variation.hasUnusualCipherOrder {
+            unusualCipherOrderCount = saturatingAdd(unusualCipherOrderCount, 1)
+        }
+        if variation.offersSessionResumption {
+            sessionResumptionCount = saturatingAdd(sessionResumptionCount, 1)
+        }
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
@@ -12,6 +12,23 @@ public enum DetectorAssociationSource: String, Codable, Sendable, Equatable {
     case tlsServerName
     case registrableDomain
     case classification
+    /// Pre-seeded by the host from a persisted export rather than observed this session.
+    case hostSeed = "host-seed"
+}
+
+/// Coarse application-protocol bucket attached to detector-facing records.
+/// Decision: hosts join packet events to flows and policy by `flowHandle` plus this class instead of
+/// re-deriving protocol families from 5-tuples on every snapshot read.
+public enum FlowProtocolClass: String, Codable, Sendable, Equatable {
+    case dns
+    case tls
+    case quic
+    /// DoH/DoT flows, split out from `tls` so policy features can observe resolver bypass attempts.
+    case encryptedDNS = "encrypted-dns"
+    /// Flows that completed an HTTP 101 upgrade to WebSocket; long-lived push channels
+    /// that idle legitimately between server-initiated messages.
+    case websocket
+    case other
 }
 
 /// Coarse network regime bucket attached to detector-facing records when path tracking is enabled.
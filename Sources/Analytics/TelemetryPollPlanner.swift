@@ -0,0 +1,92 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Adaptive replacement for a fixed foreground snapshot-poll cadence.
+/// Decision: a constant interval is wrong at both ends — it wakes the provider (and the radio)
+/// for an idle tunnel and staleness-lags a busy one. The planner grades load from the live flow
+/// count and the packet rate observed between consecutive snapshot requests, then interpolates
+/// between the configured floor and ceiling: a busy tunnel is sampled near the floor for fresh
+/// numbers, an idle one near the ceiling. The chosen interval rides back on the snapshot so
+/// hosts can honor it without re-deriving the heuristics.
+public struct TelemetryPollPlanner: Sendable, Equatable {
+    /// Bounds and load thresholds for interval selection.
+    public struct Configuration: Sendable, Equatable {
+        /// Shortest interval handed out, used when the tunnel is fully busy.
+        public let minimumIntervalSeconds: TimeInterval
+        /// Longest interval handed out, used when the tunnel is fully idle.
+        public let maximumIntervalSeconds: TimeInterval
+        /// Live flow count at or above which the flow signal reads as fully busy.
+        public let busyFlowCount: Int
+        /// Recent packet rate at or above which the rate signal reads as fully busy.
+        public let busyPacketsPerSecond: Double
+
+        /// - Parameters:
+        ///   - minimumIntervalSeconds: Busy-end interval floor, clamped to at least 100ms.
+        ///   - maximumIntervalSeconds: Idle-end interval ceiling, clamped to at least the floor.
+        ///   - busyFlowCount: Flow count treated as full load, clamped to at least 1.
+        ///   - busyPacketsPerSecond: Packet rate treated as full load, clamped to at least 1.
+        public init(
+            minimumIntervalSeconds: TimeInterval = 1,
+            maximumIntervalSeconds: TimeInterval = 30,
+            busyFlowCount: Int = 32,
+            busyPacketsPerSecond: Double = 250
+        ) {
+            self.minimumIntervalSeconds = max(0.1, minimumIntervalSeconds)
+            self.maximumIntervalSeconds = max(self.minimumIntervalSeconds, maximumIntervalSeconds)
+            self.busyFlowCount = max(1, busyFlowCount)
+            self.busyPacketsPerSecond = max(1, busyPacketsPerSecond)
+        }
+    }
+
+    public let configuration: Configuration
+    /// The interval most recently handed out, for stats reporting.
+    public private(set) var lastChosenIntervalSeconds: TimeInterval?
+
+    private var lastSampleAt: Date?
+    private var lastPacketCount: Int?
+
+    /// - Parameter configuration: Interval bounds and busy thresholds.
+    public init(configuration: Configuration = Configuration()) {
+        self.configuration = configuration
+    }
+
+    /// Chooses how long the caller should wait before its next snapshot poll.
+    /// The first call reports a zero recent packet rate (there is no prior sample to rate
+    /// against), as does any call after the cumulative counter resets backwards.
+    /// - Parameters:
+    ///   - now: Current time, injected for deterministic tests.
+    ///   - activeFlowCount: Live tracked flow count at sampling time.
+    ///   - cumulativePacketCount: Monotonic accepted-packet total at sampling time.
+    /// - Returns: Interval between the configured floor and ceiling.
+    public mutating func nextIntervalSeconds(
+        now: Date,
+        activeFlowCount: Int,
+        cumulativePacketCount: Int
+    ) -> TimeInterval {
+        let packetsPerSecond = recentPacketsPerSecond(now: now, cumulativePacketCount: cumulativePacketCount)
+        lastSampleAt = now
+        lastPacketCount = cumulativePacketCount
+
+        let flowLoad = min(1.0, Double(max(0, activeFlowCount)) / Double(configuration.busyFlowCount))
+        let rateLoad = min(1.0, packetsPerSecond / configuration.busyPacketsPerSecond)
+        let load = max(flowLoad, rateLoad)
+        let interval = configuration.maximumIntervalSeconds
+            - (configuration.maximumIntervalSeconds - configuration.minimumIntervalSeconds) * load
+        lastChosenIntervalSeconds = interval
+        return interval
+    }
+
+    private func recentPacketsPerSecond(now: Date, cumulativePacketCount: Int) -> Double {
+        guard let lastSampleAt, let lastPacketCount else {
+            return 0
+        }
+        let elapsed = now.timeIntervalSince(lastSampleAt)
+        guard elapsed > 0, elapsed.isFinite else {
+            return 0
+        }
+        return Double(max(0, cumulativePacketCount - lastPacketCount)) / elapsed
+    }
+}
@@ -0,0 +1,147 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Fixed-size per-second time series of headline tunnel signals for host dashboards.
+/// Decision: the tunnel maintains these buckets internally so hosts render throughput,
+/// flow, DNS, and block charts from one small poll instead of rebuilding the aggregation
+/// from telemetry drains themselves, mirroring `UsageAccountingReport`.
+public struct TunnelTelemetrySeries: Codable, Sendable, Equatable {
+    /// One second of accumulated signals.
+    public struct Bucket: Codable, Sendable, Equatable {
+        /// Start of the second (timestamp floored to the second, UTC).
+        public let bucketStart: Date
+        public let inboundBytes: Int
+        public let outboundBytes: Int
+        /// Highest live tracked-flow count observed during the second.
+        public let activeFlowCount: Int
+        /// Outbound DNS queries observed on the fast path during the second.
+        public let dnsQueryCount: Int
+        /// Policy-blocked flows reported to the worker during the second.
+        public let blockedFlowCount: Int
+
+        public init(
+            bucketStart: Date,
+            inboundBytes: Int,
+            outboundBytes: Int,
+            activeFlowCount: Int,
+            dnsQueryCount: Int,
+            blockedFlowCount: Int
+        ) {
+            self.bucketStart = bucketStart
+            self.inboundBytes = max(0, inboundBytes)
+            self.outboundBytes = max(0, outboundBytes)
+            self.activeFlowCount = max(0, activeFlowCount)
+            self.dnsQueryCount = max(0, dnsQueryCount)
+            self.blockedFlowCount = max(0, blockedFlowCount)
+        }
+    }
+
+    /// Seconds each bucket spans; the recorder currently always buckets by one second.
+    public static let bucketSeconds = 1
+
+    /// Buckets ordered oldest to newest; seconds with no recorded activity appear as
+    /// zero buckets so chart axes stay uniform.
+    public let buckets: [Bucket]
+
+    public init(buckets: [Bucket]) {
+        self.buckets = buckets
+    }
+
+    public var isEmpty: Bool {
+        buckets.allSatisfy {
+            $0.inboundBytes == 0 && $0.outboundBytes == 0 && $0.activeFlowCount == 0
+                && $0.dnsQueryCount == 0 && $0.blockedFlowCount == 0
+        }
+    }
+
+    public static let empty = TunnelTelemetrySeries(buckets: [])
+}
+
+/// Pipeline-owned ring of per-second accumulation cells behind `TunnelTelemetrySeries`.
+/// Bounded: cells are indexed by epoch second modulo the capacity, and each cell carries
+/// the second it was written for — a stale cell from a prior lap reads as an empty
+/// second, so no sweep or zero-fill pass is ever needed.
+struct TelemetrySeriesRecorder {
+    /// Deepest window a snapshot can cover; older seconds are overwritten in place.
+    static let capacitySeconds = 120
+
+    private struct Cell {
+        var second: Int64 = .min
+        var inboundBytes = 0
+        var outboundBytes = 0
+        var activeFlowCount = 0
+        var dnsQueryCount = 0
+        var blockedFlowCount = 0
+    }
+
+    private var cells = [Cell](repeating: Cell(), count: TelemetrySeriesRecorder.capacitySeconds)
+
+    /// Accumulates one batch's signals into the current second's cell.
+    mutating func record(
+        inboundBytes: Int = 0,
+        outboundBytes: Int = 0,
+        activeFlowCount: Int = 0,
+        dnsQueryCount: Int = 0,
+        blockedFlowCount: Int = 0,
+        now: Date
+    ) {
+        let index = claimCell(for: now)
+        cells[index].inboundBytes = saturatingAdd(cells[index].inboundBytes, max(0, inboundBytes))
+        cells[index].outboundBytes = saturatingAdd(cells[index].outboundBytes, max(0, outboundBytes))
+        cells[index].activeFlowCount = max(cells[index].activeFlowCount, activeFlowCount)
+        cells[index].dnsQueryCount = saturatingAdd(cells[index].dnsQueryCount, max(0, dnsQueryCount))
+        cells[index].blockedFlowCount = saturatingAdd(cells[index].blockedFlowCount, max(0, blockedFlowCount))
+    }
+
+    /// Returns the last `windowSeconds` seconds ending at `now`, oldest bucket first.
+    /// Windows are clamped to `capacitySeconds`; unwritten seconds come back as zeros.
+    func series(windowSeconds: Int, now: Date) -> TunnelTelemetrySeries {
+        let window = max(1, min(windowSeconds, Self.capacitySeconds))
+        let latestSecond = Self.epochSecond(for: now)
+        var buckets: [TunnelTelemetrySeries.Bucket] = []
+        buckets.reserveCapacity(window)
+        for second in (latestSecond - Int64(window) + 1) ... latestSecond {
+            let cell = cells[Self.index(for: second)]
+            let isLive = cell.second == second
+            buckets.append(TunnelTelemetrySeries.Bucket(
+                bucketStart: Date(timeIntervalSince1970: TimeInterval(second)),
+                inboundBytes: isLive ? cell.inboundBytes : 0,
+                outboundBytes: isLive ? cell.outboundBytes : 0,
+                activeFlowCount: isLive ? cell.activeFlowCount : 0,
+                dnsQueryCount: isLive ? cell.dnsQueryCount : 0,
+                blockedFlowCount: isLive ? cell.blockedFlowCount : 0
+            ))
+        }
+        return TunnelTelemetrySeries(buckets: buckets)
+    }
+
+    mutating func reset() {
+        cells = [Cell](repeating: Cell(), count: Self.capacitySeconds)
+    }
+
+    private mutating func claimCell(for now: Date) -> Int {
+        let second = Self.epochSecond(for: now)
+        let index = Self.index(for: second)
+        if cells[index].second != second {
+            cells[index] = Cell(second: second)
+        }
+        return index
+    }
+
+    private static func epochSecond(for date: Date) -> Int64 {
+        Int64(date.timeIntervalSince1970.rounded(.down))
+    }
+
+    private static func index(for second: Int64) -> Int {
+        let capacity = Int64(capacitySeconds)
+        return Int(((second % capacity) + capacity) % capacity)
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
@@ -0,0 +1,503 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Built-in anomaly heuristics packaged as `TrafficDetector` implementations.
+/// Decision: these run inside the tunnel on the sparse record stream so the containing app can alert on
+/// aggregate anomalies without ever streaming raw packet data across the process boundary.
+public enum TrafficAnomalyDetectors {
+    /// Convenience bundle installing all built-in anomaly heuristics with default policies.
+    public static func makeDefaultDetectors() -> [any TrafficDetector] {
+        [
+            DNSQueryFloodDetector(),
+            ConnectionRateSpikeDetector(),
+            PortScanFanOutDetector(),
+            BeaconingIntervalDetector()
+        ]
+    }
+}
+
+/// Flags bursts of DNS queries that exceed a sliding-window rate.
+/// Contract: state is worker-owned and mutated inline on the telemetry task, so no synchronization is needed.
+public final class DNSQueryFloodDetector: TrafficDetector {
+    /// Tunable flood thresholds with clamped bounds.
+    public struct Policy: Codable, Sendable, Equatable {
+        public let windowSeconds: TimeInterval
+        public let queryThreshold: Int
+        public let cooldownSeconds: TimeInterval
+
+        /// - Parameters:
+        ///   - windowSeconds: Sliding window length, clamped to 1...60 seconds.
+        ///   - queryThreshold: Queries inside one window that trigger a detection, clamped to 10...10_000.
+        ///   - cooldownSeconds: Minimum spacing between detections, clamped to 1...3_600 seconds.
+        public init(
+            windowSeconds: TimeInterval = 10,
+            queryThreshold: Int = 120,
+            cooldownSeconds: TimeInterval = 60
+        ) {
+            self.windowSeconds = min(max(1, windowSeconds), 60)
+            self.queryThreshold = min(max(10, queryThreshold), 10_000)
+            self.cooldownSeconds = min(max(1, cooldownSeconds), 3_600)
+        }
+
+        public static let `default` = Policy()
+    }
+
+    public let identifier = "dns-query-flood"
+    public let requirements = DetectorRequirements(
+        recordKinds: [.flowOpen, .metadata],
+        featureFamilies: [.hostHints]
+    )
+
+    private let policy: Policy
+    private var queryTimestamps: [Date] = []
+    private var lastFiredAt: Date?
+
+    /// - Parameter policy: Flood thresholds; defaults match the shipped tunnel configuration.
+    public init(policy: Policy = .default) {
+        self.policy = policy
+    }
+
+    public func ingest(_ records: DetectorRecordCollection) -> [DetectionEvent] {
+        var events: [DetectionEvent] = []
+        for record in records {
+            guard isDNSQuery(record) else {
+                continue
+            }
+            let now = record.timestamp
+            queryTimestamps.append(now)
+            queryTimestamps.removeAll { now.timeIntervalSince($0) > policy.windowSeconds }
+
+            guard queryTimestamps.count >= policy.queryThreshold else {
+                continue
+            }
+            if let lastFiredAt, now.timeIntervalSince(lastFiredAt) < policy.cooldownSeconds {
+                continue
+            }
+            lastFiredAt = now
+            events.append(
+                DetectionEvent(
+                    id: UUID().uuidString,
+                    detectorIdentifier: identifier,
+                    signal: "query-flood",
+                    target: nil,
+                    timestamp: now,
+                    confidence: 0.8,
+                    trigger: record.kind.rawValue,
+                    flowId: record.flowId,
+                    host: nil,
+                    classification: nil,
+                    bytes: 0,
+                    packetCount: queryTimestamps.count,
+                    durationMs: Int(policy.windowSeconds * 1_000)
+                )
+            )
+        }
+        return events
+    }
+
+    public func reset() {
+        queryTimestamps.removeAll()
+        lastFiredAt = nil
+    }
+
+    private func isDNSQuery(_ record: DetectorRecord) -> Bool {
+        record.dnsQueryName != nil || record.destinationPort == 53
+    }
+}
+
+/// Flags connection-rate spikes toward one host inside a sliding window.
+/// Contract: state is worker-owned and mutated inline on the telemetry task, so no synchronization is needed.
+public final class ConnectionRateSpikeDetector: TrafficDetector {
+    /// Tunable spike thresholds with clamped bounds.
+    public struct Policy: Codable, Sendable, Equatable {
+        public let windowSeconds: TimeInterval
+        public let openThreshold: Int
+        public let cooldownSeconds: TimeInterval
+        public let maxTrackedHosts: Int
+
+        /// - Parameters:
+        ///   - windowSeconds: Sliding window length, clamped to 1...60 seconds.
+        ///   - openThreshold: Flow opens toward one host that trigger a detection, clamped to 5...1_000.
+        ///   - cooldownSeconds: Minimum per-host spacing between detections, clamped to 1...3_600 seconds.
+        ///   - maxTrackedHosts: Max host windows retained in memory, clamped to 16...4_096.
+        public init(
+            windowSeconds: TimeInterval = 10,
+            openThreshold: Int = 30,
+            cooldownSeconds: TimeInterval = 60,
+            maxTrackedHosts: Int = 512
+        ) {
+            self.windowSeconds = min(max(1, windowSeconds), 60)
+            self.openThreshold = min(max(5, openThreshold), 1_000)
+            self.cooldownSeconds = min(max(1, cooldownSeconds), 3_600)
+            self.maxTrackedHosts = min(max(16, maxTrackedHosts), 4_096)
+        }
+
+        public static let `default` = Policy()
+    }
+
+    private struct HostWindow {
+        var openTimestamps: [Date] = []
+        var lastFiredAt: Date?
+        var lastSeen: Date
+    }
+
+    public let identifier = "connection-rate-spike"
+    public let requirements = DetectorRequirements(
+        recordKinds: [.flowOpen],
+        featureFamilies: [.hostHints, .dnsAssociation]
+    )
+
+    private let policy: Policy
+    private var windowsByHost: [String: HostWindow] = [:]
+
+    /// - Parameter policy: Spike thresholds; defaults match the shipped tunnel configuration.
+    public init(policy: Policy = .default) {
+        self.policy = policy
+    }
+
+    public func ingest(_ records: DetectorRecordCollection) -> [DetectionEvent] {
+        var events: [DetectionEvent] = []
+        for record in records where record.kind == .flowOpen {
+            guard let host = hostKey(record) else {
+                continue
+            }
+            let now = record.timestamp
+            var window = windowsByHost[host] ?? HostWindow(lastSeen: now)
+            window.lastSeen = now
+            window.openTimestamps.append(now)
+            window.openTimestamps.removeAll { now.timeIntervalSince($0) > policy.windowSeconds }
+
+            defer {
+                windowsByHost[host] = window
+                evictOldestIfNeeded(now: now)
+            }
+
+            guard window.openTimestamps.count >= policy.openThreshold else {
+                continue
+            }
+            if let lastFiredAt = window.lastFiredAt, now.timeIntervalSince(lastFiredAt) < policy.cooldownSeconds {
+                continue
+            }
+            window.lastFiredAt = now
+            events.append(
+                DetectionEvent(
+                    id: UUID().uuidString,
+                    detectorIdentifier: identifier,
+                    signal: "connection-rate-spike",
+                    target: host,
+                    timestamp: now,
+                    confidence: 0.7,
+                    trigger: record.kind.rawValue,
+                    flowId: record.flowId,
+                    host: host,
+                    classification: record.classification,
+                    bytes: 0,
+                    packetCount: window.openTimestamps.count,
+                    durationMs: Int(policy.windowSeconds * 1_000)
+                )
+            )
+        }
+        return events
+    }
+
+    public func reset() {
+        windowsByHost.removeAll()
+    }
+
+    private func hostKey(_ record: DetectorRecord) -> String? {
+        record.associatedDomain
+            ?? record.tlsServerName
+            ?? record.registrableDomain
+            ?? record.destinationAddress
+    }
+
+    private func evictOldestIfNeeded(now: Date) {
+        guard windowsByHost.count > policy.maxTrackedHosts else {
+            return
+        }
+        windowsByHost = windowsByHost.filter { _, window in
+            now.timeIntervalSince(window.lastSeen) <= policy.windowSeconds
+        }
+        while windowsByHost.count > policy.maxTrackedHosts,
+              let oldest = windowsByHost.min(by: { $0.value.lastSeen < $1.value.lastSeen }) {
+            windowsByHost.removeValue(forKey: oldest.key)
+        }
+    }
+}
+
+/// Flags one source fanning out to many distinct destination address:port pairs in a window.
+/// Decision: scanners spread flow opens across ports and addresses while real apps concentrate on a few
+/// services, so the heuristic counts distinct destinations per source instead of raw open rates, which
+/// `ConnectionRateSpikeDetector` already covers per host.
+/// Contract: state is worker-owned and mutated inline on the telemetry task, so no synchronization is needed.
+public final class PortScanFanOutDetector: TrafficDetector {
+    /// Tunable fan-out thresholds with clamped bounds.
+    public struct Policy: Codable, Sendable, Equatable {
+        public let windowSeconds: TimeInterval
+        public let distinctDestinationThreshold: Int
+        public let cooldownSeconds: TimeInterval
+        public let maxTrackedSources: Int
+
+        /// - Parameters:
+        ///   - windowSeconds: Sliding window length, clamped to 1...60 seconds.
+        ///   - distinctDestinationThreshold: Distinct destination address:port pairs one source must touch
+        ///     inside one window to trigger a detection, clamped to 10...4_096.
+        ///   - cooldownSeconds: Minimum per-source spacing between detections, clamped to 1...3_600 seconds.
+        ///   - maxTrackedSources: Max source windows retained in memory, clamped to 16...4_096.
+        public init(
+            windowSeconds: TimeInterval = 10,
+            distinctDestinationThreshold: Int = 40,
+            cooldownSeconds: TimeInterval = 60,
+            maxTrackedSources: Int = 256
+        ) {
+            self.windowSeconds = min(max(1, windowSeconds), 60)
+            self.distinctDestinationThreshold = min(max(10, distinctDestinationThreshold), 4_096)
+            self.cooldownSeconds = min(max(1, cooldownSeconds), 3_600)
+            self.maxTrackedSources = min(max(16, maxTrackedSources), 4_096)
+        }
+
+        public static let `default` = Policy()
+    }
+
+    private struct SourceWindow {
+        var lastSeenByDestination: [String: Date] = [:]
+        var lastFiredAt: Date?
+        var lastSeen: Date
+    }
+
+    public let identifier = "port-scan-fan-out"
+    public let requirements = DetectorRequirements(
+        recordKinds: [.flowOpen],
+        featureFamilies: [.stringAddresses]
+    )
+
+    private let policy: Policy
+    private var windowsBySource: [String: SourceWindow] = [:]
+
+    /// - Parameter policy: Fan-out thresholds; defaults match the shipped tunnel configuration.
+    public init(policy: Policy = .default) {
+        self.policy = policy
+    }
+
+    public func ingest(_ records: DetectorRecordCollection) -> [DetectionEvent] {
+        var events: [DetectionEvent] = []
+        for record in records where record.kind == .flowOpen {
+            guard let source = record.sourceAddress, let destination = record.destinationAddress else {
+                continue
+            }
+            let now = record.timestamp
+            var window = windowsBySource[source] ?? SourceWindow(lastSeen: now)
+            window.lastSeen = now
+            window.lastSeenByDestination["\(destination):\(record.destinationPort ?? 0)"] = now
+            window.lastSeenByDestination = window.lastSeenByDestination.filter { _, seenAt in
+                now.timeIntervalSince(seenAt) <= policy.windowSeconds
+            }
+
+            defer {
+                windowsBySource[source] = window
+                evictOldestIfNeeded(now: now)
+            }
+
+            guard window.lastSeenByDestination.count >= policy.distinctDestinationThreshold else {
+                continue
+            }
+            if let lastFiredAt = window.lastFiredAt, now.timeIntervalSince(lastFiredAt) < policy.cooldownSeconds {
+                continue
+            }
+            window.lastFiredAt = now
+            events.append(
+                DetectionEvent(
+                    id: UUID().uuidString,
+                    detectorIdentifier: identifier,
+                    signal: "port-scan-fan-out",
+                    target: source,
+                    timestamp: now,
+                    confidence: 0.75,
+                    trigger: record.kind.rawValue,
+                    flowId: record.flowId,
+                    host: nil,
+                    classification: record.classification,
+                    bytes: 0,
+                    packetCount: window.lastSeenByDestination.count,
+                    durationMs: Int(policy.windowSeconds * 1_000)
+                )
+            )
+        }
+        return events
+    }
+
+    public func reset() {
+        windowsBySource.removeAll()
+    }
+
+    private func evictOldestIfNeeded(now: Date) {
+        guard windowsBySource.count > policy.maxTrackedSources else {
+            return
+        }
+        windowsBySource = windowsBySource.filter { _, window in
+            now.timeIntervalSince(window.lastSeen) <= policy.windowSeconds
+        }
+        while windowsBySource.count > policy.maxTrackedSources,
+              let oldest = windowsBySource.min(by: { $0.value.lastSeen < $1.value.lastSeen }) {
+            windowsBySource.removeValue(forKey: oldest.key)
+        }
+    }
+}
+
+/// Flags hosts contacted on a suspiciously regular cadence.
+/// Decision: the heuristic measures the spread of flow-open intervals against their mean instead of fitting a
+/// periodicity model, which is cheap enough for the hot telemetry path and still separates timers from browsing.
+/// Contract: state is worker-owned and mutated inline on the telemetry task, so no synchronization is needed.
+public final class BeaconingIntervalDetector: TrafficDetector {
+    /// Tunable cadence thresholds with clamped bounds.
+    public struct Policy: Codable, Sendable, Equatable {
+        public let minimumIntervalSeconds: TimeInterval
+        public let maximumIntervalSeconds: TimeInterval
+        public let minimumObservations: Int
+        public let jitterTolerance: Double
+        public let cooldownSeconds: TimeInterval
+        public let maxTrackedHosts: Int
+
+        /// - Parameters:
+        ///   - minimumIntervalSeconds: Shortest cadence considered beaconing, clamped to 1...600 seconds.
+        ///   - maximumIntervalSeconds: Longest cadence considered beaconing, clamped above the minimum and to 3_600 seconds.
+        ///   - minimumObservations: Consecutive intervals required before firing, clamped to 3...64.
+        ///   - jitterTolerance: Allowed relative spread around the mean interval, clamped to 0.01...0.5.
+        ///   - cooldownSeconds: Minimum per-host spacing between detections, clamped to 1...86_400 seconds.
+        ///   - maxTrackedHosts: Max host cadences retained in memory, clamped to 16...4_096.
+        public init(
+            minimumIntervalSeconds: TimeInterval = 5,
+            maximumIntervalSeconds: TimeInterval = 600,
+            minimumObservations: Int = 6,
+            jitterTolerance: Double = 0.15,
+            cooldownSeconds: TimeInterval = 600,
+            maxTrackedHosts: Int = 256
+        ) {
+            self.minimumIntervalSeconds = min(max(1, minimumIntervalSeconds), 600)
+            self.maximumIntervalSeconds = min(max(self.minimumIntervalSeconds, maximumIntervalSeconds), 3_600)
+            self.minimumObservations = min(max(3, minimumObservations), 64)
+            self.jitterTolerance = min(max(0.01, jitterTolerance), 0.5)
+            self.cooldownSeconds = min(max(1, cooldownSeconds), 86_400)
+            self.maxTrackedHosts = min(max(16, maxTrackedHosts), 4_096)
+        }
+
+        public static let `default` = Policy()
+    }
+
+    private struct HostCadence {
+        var lastOpenAt: Date?
+        var intervals: [TimeInterval] = []
+        var lastFiredAt: Date?
+        var lastSeen: Date
+    }
+
+    public let identifier = "beaconing-interval"
+    public let requirements = DetectorRequirements(
+        recordKinds: [.flowOpen],
+        featureFamilies: [.hostHints, .dnsAssociation]
+    )
+
+    private let policy: Policy
+    private var cadencesByHost: [String: HostCadence] = [:]
+
+    /// - Parameter policy: Cadence thresholds; defaults match the shipped tunnel configuration.
+    public init(policy: Policy = .default) {
+        self.policy = policy
+    }
+
+    public func ingest(_ records: DetectorRecordCollection) -> [DetectionEvent] {
+        var events: [DetectionEvent] = []
+        for record in records where record.kind == .flowOpen {
+            guard let host = hostKey(record) else {
+                continue
+            }
+            let now = record.timestamp
+            var cadence = cadencesByHost[host] ?? HostCadence(lastSeen: now)
+            cadence.lastSeen = now
+            defer {
+                cadencesByHost[host] = cadence
+                evictOldestIfNeeded(now: now)
+            }
+
+            guard let lastOpenAt = cadence.lastOpenAt else {
+                cadence.lastOpenAt = now
+                continue
+            }
+            cadence.lastOpenAt = now
+
+            let interval = now.timeIntervalSince(lastOpenAt)
+            guard interval >= policy.minimumIntervalSeconds, interval <= policy.maximumIntervalSeconds else {
+                cadence.intervals.removeAll()
+                continue
+            }
+            cadence.intervals.append(interval)
+            if cadence.intervals.count > policy.minimumObservations {
+                cadence.intervals.removeFirst()
+            }
+
+            guard cadence.intervals.count >= policy.minimumObservations,
+                  isRegular(cadence.intervals) else {
+                continue
+            }
+            if let lastFiredAt = cadence.lastFiredAt, now.timeIntervalSince(lastFiredAt) < policy.cooldownSeconds {
+                continue
+            }
+            cadence.lastFiredAt = now
+            let meanIntervalMs = Int(cadence.intervals.reduce(0, +) / Double(cadence.intervals.count) * 1_000)
+            events.append(
+                DetectionEvent(
+                    id: UUID().uuidString,
+                    detectorIdentifier: identifier,
+                    signal: "beaconing-interval",
+                    target: host,
+                    timestamp: now,
+                    confidence: 0.6,
+                    trigger: record.kind.rawValue,
+                    flowId: record.flowId,
+                    host: host,
+                    classification: record.classification,
+                    bytes: 0,
+                    packetCount: cadence.intervals.count + 1,
+                    durationMs: meanIntervalMs
+                )
+            )
+        }
+        return events
+    }
+
+    public func reset() {
+        cadencesByHost.removeAll()
+    }
+
+    private func hostKey(_ record: DetectorRecord) -> String? {
+        record.associatedDomain
+            ?? record.tlsServerName
+            ?? record.registrableDomain
+            ?? record.destinationAddress
+    }
+
+    private func isRegular(_ intervals: [TimeInterval]) -> Bool {
+        guard intervals.count > 1 else {
+            return false
+        }
+        let mean = intervals.reduce(0, +) / Double(intervals.count)
+        guard mean > 0 else {
+            return false
+        }
+        let maxDeviation = intervals.map { abs($0 - mean) }.max() ?? 0
+        return maxDeviation / mean <= policy.jitterTolerance
+    }
+
+    private func evictOldestIfNeeded(now: Date) {
+        guard cadencesByHost.count > policy.maxTrackedHosts else {
+            return
+        }
+        while cadencesByHost.count > policy.maxTrackedHosts,
+              let oldest = cadencesByHost.min(by: { $0.value.lastSeen < $1.value.lastSeen }) {
+            cadencesByHost.removeValue(forKey: oldest.key)
+        }
+    }
+}
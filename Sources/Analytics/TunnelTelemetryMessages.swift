@@ -73,24 +73,36 @@ public struct TunnelTelemetryRequest: Codable, Sendable, Equatable {
     public let command: TunnelTelemetryCommand
     public let packetLimit: Int?
     public let includeValidationRecords: Bool?
+    /// Asks the provider to return the snapshot as a compressed blob instead of inline
+    /// JSON, for hosts that forward the payload to a server verbatim. Optional so old
+    /// requests decode unchanged; providers that cannot compress fall back to a plain
+    /// snapshot, which callers must handle.
+    public let compressSnapshot: Bool?
 
     public init(
         version: Int = TunnelTelemetryProtocolVersion.current,
         command: TunnelTelemetryCommand,
         packetLimit: Int? = nil,
-        includeValidationRecords: Bool? = nil
+        includeValidationRecords: Bool? = nil,
+        compressSnapshot: Bool? = nil
     ) {
         self.version = version
         self.command = command
         self.packetLimit = packetLimit
         self.includeValidationRecords = includeValidationRecords
+        self.compressSnapshot = compressSnapshot
     }
 
-    public static func snapshot(packetLimit: Int? = nil, includeValidationRecords: Bool? = nil) -> TunnelTelemetryRequest {
+    public static func snapshot(
+        packetLimit: Int? = nil,
+        includeValidationRecords: Bool? = nil,
+        compressSnapshot: Bool? = nil
+    ) -> TunnelTelemetryRequest {
         TunnelTelemetryRequest(
             command: .snapshot,
             packetLimit: packetLimit,
-            includeValidationRecords: includeValidationRecords
+            includeValidationRecords: includeValidationRecords,
+            compressSnapshot: compressSnapshot
         )
     }
 
@@ -121,6 +133,16 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
         case health
         case liveness
         case validationRecords
+        case payloadHistograms
+        case invalidPacketCounters
+        case exoticHeaders
+        case tlsHandshakeVariation
+        case batchExecution
+        case dnsIntegrity
+        case dnsResolverStats
+        case discoveredServices
+        case activeFlowCount
+        case recommendedPollIntervalMs
     }
 
     public let samples: [PacketSample]
@@ -140,6 +162,18 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
     public let health: TelemetryHealthRecord?
     public let liveness: TelemetryStreamLiveness?
     public let validationRecords: [PacketSample]
+    public let payloadHistograms: FlowClassPayloadHistograms?
+    public let invalidPacketCounters: InvalidPacketCounters?
+    public let exoticHeaders: ExoticHeaderCounters?
+    public let tlsHandshakeVariation: TLSHandshakeVariationCounters?
+    public let batchExecution: BatchExecutionHistograms?
+    public let dnsIntegrity: DNSIntegrityCounters?
+    public let dnsResolverStats: [DNSResolverStats]?
+    public let discoveredServices: [DiscoveredService]?
+    /// Distinct endpoint pairs with live tracked flows at snapshot time.
+    public let activeFlowCount: Int?
+    /// Planner-chosen delay before the host's next snapshot poll; see `TelemetryPollPlanner`.
+    public let recommendedPollIntervalMs: Int?
 
     public init(
         samples: [PacketSample],
@@ -158,7 +192,17 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
         detections: DetectionSnapshot,
         health: TelemetryHealthRecord? = nil,
         liveness: TelemetryStreamLiveness? = nil,
-        validationRecords: [PacketSample] = []
+        validationRecords: [PacketSample] = [],
+        payloadHistograms: FlowClassPayloadHistograms? = nil,
+        invalidPacketCounters: InvalidPacketCounters? = nil,
+        exoticHeaders: ExoticHeaderCounters? = nil,
+        tlsHandshakeVariation: TLSHandshakeVariationCounters? = nil,
+        batchExecution: BatchExecutionHistograms? = nil,
+        dnsIntegrity: DNSIntegrityCounters? = nil,
+        dnsResolverStats: [DNSResolverStats]? = nil,
+        discoveredServices: [DiscoveredService]? = nil,
+        activeFlowCount: Int? = nil,
+        recommendedPollIntervalMs: Int? = nil
     ) {
         self.samples = samples
         self.retainedSampleCount = retainedSampleCount
@@ -177,6 +221,16 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
         self.health = health
         self.liveness = liveness
         self.validationRecords = validationRecords
+        self.payloadHistograms = payloadHistograms
+        self.invalidPacketCounters = invalidPacketCounters
+        self.exoticHeaders = exoticHeaders
+        self.tlsHandshakeVariation = tlsHandshakeVariation
+        self.batchExecution = batchExecution
+        self.dnsIntegrity = dnsIntegrity
+        self.dnsResolverStats = dnsResolverStats
+        self.discoveredServices = discoveredServices
+        self.activeFlowCount = activeFlowCount
+        self.recommendedPollIntervalMs = recommendedPollIntervalMs
     }
 
     public init(from decoder: Decoder) throws {
@@ -198,6 +252,16 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
         self.health = try container.decodeIfPresent(TelemetryHealthRecord.self, forKey: .health)
         self.liveness = try container.decodeIfPresent(TelemetryStreamLiveness.self, forKey: .liveness)
         self.validationRecords = try container.decodeIfPresent([PacketSample].self, forKey: .validationRecords) ?? []
+        self.payloadHistograms = try container.decodeIfPresent(FlowClassPayloadHistograms.self, forKey: .payloadHistograms)
+        self.invalidPacketCounters = try container.decodeIfPresent(InvalidPacketCounters.self, forKey: .invalidPacketCounters)
+        self.exoticHeaders = try container.decodeIfPresent(ExoticHeaderCounters.self, forKey: .exoticHeaders)
+        self.tlsHandshakeVariation = try container.decodeIfPresent(TLSHandshakeVariationCounters.self, forKey: .tlsHandshakeVariation)
+        self.batchExecution = try container.decodeIfPresent(BatchExecutionHistograms.self, forKey: .batchExecution)
+        self.dnsIntegrity = try container.decodeIfPresent(DNSIntegrityCounters.self, forKey: .dnsIntegrity)
+        self.dnsResolverStats = try container.decodeIfPresent([DNSResolverStats].self, forKey: .dnsResolverStats)
+        self.discoveredServices = try container.decodeIfPresent([DiscoveredService].self, forKey: .discoveredServices)
+        self.activeFlowCount = try container.decodeIfPresent(Int.self, forKey: .activeFlowCount)
+        self.recommendedPollIntervalMs = try container.decodeIfPresent(Int.self, forKey: .recommendedPollIntervalMs)
     }
 
     public static let empty = TunnelTelemetrySnapshot(
@@ -221,6 +285,34 @@ public struct TunnelTelemetrySnapshot: Codable, Sendable, Equatable {
     )
 }
 
+/// Snapshot serialized once and compressed for hosts that relay telemetry verbatim.
+/// Decision: the blob is the codec's own JSON encoding of `TunnelTelemetrySnapshot`,
+/// so a relay server (or `TunnelTelemetryMessageCodec.decompressSnapshot`) recovers the
+/// exact payload a plain snapshot response would have carried.
+public struct TunnelTelemetryCompressedSnapshot: Codable, Sendable, Equatable {
+    /// Compression algorithm applied to `payload`; currently always `lz4`.
+    public let algorithm: String
+    /// Number of packet samples inside the compressed snapshot, available without
+    /// decompressing.
+    public let sampleCount: Int
+    /// Size of the encoded snapshot before compression, for integrity checks and
+    /// buffer sizing on the receiving side.
+    public let uncompressedByteCount: Int
+    public let payload: Data
+
+    /// - Parameters:
+    ///   - algorithm: Compression algorithm applied to `payload`.
+    ///   - sampleCount: Packet samples inside the compressed snapshot.
+    ///   - uncompressedByteCount: Encoded snapshot size before compression.
+    ///   - payload: Compressed snapshot bytes.
+    public init(algorithm: String, sampleCount: Int, uncompressedByteCount: Int, payload: Data) {
+        self.algorithm = algorithm
+        self.sampleCount = sampleCount
+        self.uncompressedByteCount = uncompressedByteCount
+        self.payload = payload
+    }
+}
+
 /// App-message response returned by the packet tunnel provider.
 public struct TunnelTelemetryResponse: Codable, Sendable, Equatable {
     public enum Kind: String, Codable, Sendable, Equatable {
@@ -233,17 +325,22 @@ public struct TunnelTelemetryResponse: Codable, Sendable, Equatable {
     public let version: Int
     public let kind: Kind
     public let snapshot: TunnelTelemetrySnapshot?
+    /// Present instead of `snapshot` when the request asked for compression and the
+    /// provider could honor it.
+    public let compressedSnapshot: TunnelTelemetryCompressedSnapshot?
     public let message: String?
 
     public init(
         version: Int = TunnelTelemetryProtocolVersion.current,
         kind: Kind,
         snapshot: TunnelTelemetrySnapshot? = nil,
+        compressedSnapshot: TunnelTelemetryCompressedSnapshot? = nil,
         message: String? = nil
     ) {
         self.version = version
         self.kind = kind
         self.snapshot = snapshot
+        self.compressedSnapshot = compressedSnapshot
         self.message = message
     }
 
@@ -251,6 +348,10 @@ public struct TunnelTelemetryResponse: Codable, Sendable, Equatable {
         TunnelTelemetryResponse(kind: .snapshot, snapshot: snapshot, message: nil)
     }
 
+    public static func compressedSnapshot(_ compressed: TunnelTelemetryCompressedSnapshot) -> TunnelTelemetryResponse {
+        TunnelTelemetryResponse(kind: .snapshot, compressedSnapshot: compressed, message: nil)
+    }
+
     public static let cleared = TunnelTelemetryResponse(kind: .cleared)
 
     public static let flushed = TunnelTelemetryResponse(kind: .flushed)
@@ -267,11 +368,17 @@ public struct TunnelTelemetryResponse: Codable, Sendable, Equatable {
 public enum TunnelTelemetryMessageCodec {
     public enum Error: LocalizedError, Equatable {
         case unsupportedVersion(Int)
+        case compressionUnavailable
+        case malformedCompressedSnapshot(String)
 
         public var errorDescription: String? {
             switch self {
             case .unsupportedVersion(let version):
                 return "Unsupported tunnel telemetry message version: \(version)"
+            case .compressionUnavailable:
+                return "Snapshot compression is not available on this platform."
+            case .malformedCompressedSnapshot(let reason):
+                return "Compressed telemetry snapshot is malformed: \(reason)"
             }
         }
     }
@@ -296,6 +403,50 @@ public enum TunnelTelemetryMessageCodec {
         return response
     }
 
+    /// Encodes `snapshot` with the shared JSON configuration and LZ4-compresses the result.
+    /// Decision: the codec owns both halves of the transform so the blob a host forwards verbatim is
+    /// guaranteed to round-trip through `decompressSnapshot(_:)` regardless of which process produced it.
+    public static func compressSnapshot(_ snapshot: TunnelTelemetrySnapshot) throws -> TunnelTelemetryCompressedSnapshot {
+        #if os(Linux)
+        _ = snapshot
+        throw Error.compressionUnavailable
+        #else
+        let encoded = try makeEncoder().encode(snapshot)
+        let payload = try (encoded as NSData).compressed(using: .lz4) as Data
+        return TunnelTelemetryCompressedSnapshot(
+            algorithm: "lz4",
+            sampleCount: snapshot.samples.count,
+            uncompressedByteCount: encoded.count,
+            payload: payload
+        )
+        #endif
+    }
+
+    /// Reverses `compressSnapshot(_:)`, validating the declared algorithm and uncompressed size
+    /// before decoding so a corrupted blob fails with a telemetry-specific error.
+    public static func decompressSnapshot(_ compressed: TunnelTelemetryCompressedSnapshot) throws -> TunnelTelemetrySnapshot {
+        #if os(Linux)
+        _ = compressed
+        throw Error.compressionUnavailable
+        #else
+        guard compressed.algorithm == "lz4" else {
+            throw Error.malformedCompressedSnapshot("unsupported algorithm '\(compressed.algorithm)'")
+        }
+        let decompressed: Data
+        do {
+            decompressed = try (compressed.payload as NSData).decompressed(using: .lz4) as Data
+        } catch {
+            throw Error.malformedCompressedSnapshot("payload failed to decompress")
+        }
+        guard decompressed.count == compressed.uncompressedByteCount else {
+            throw Error.malformedCompressedSnapshot(
+                "decompressed to \(decompressed.count) bytes, expected \(compressed.uncompressedByteCount)"
+            )
+        }
+        return try makeDecoder().decode(TunnelTelemetrySnapshot.self, from: decompressed)
+        #endif
+    }
+
     private static func validate(version: Int) throws {
         guard version == TunnelTelemetryProtocolVersion.current else {
             throw Error.unsupportedVersion(version)
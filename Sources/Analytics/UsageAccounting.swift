@@ -0,0 +1,124 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Compact hour-by-category usage report for "screen time"-style features.
+/// Decision: the tunnel aggregates bytes into hourly buckets in memory so host apps can poll a
+/// small report instead of persisting every telemetry event to reconstruct usage themselves.
+public struct UsageAccountingReport: Codable, Sendable, Equatable {
+    /// One hour-by-category accumulation cell.
+    public struct Bucket: Codable, Sendable, Equatable {
+        /// Start of the hour (timestamp floored to the hour, UTC).
+        public let hourStart: Date
+        /// Classification label when one matched, otherwise the coarse protocol class.
+        public let category: String
+        public let outboundBytes: Int
+        public let inboundBytes: Int
+        public let packetCount: Int
+
+        public init(hourStart: Date, category: String, outboundBytes: Int, inboundBytes: Int, packetCount: Int) {
+            self.hourStart = hourStart
+            self.category = category
+            self.outboundBytes = max(0, outboundBytes)
+            self.inboundBytes = max(0, inboundBytes)
+            self.packetCount = max(0, packetCount)
+        }
+    }
+
+    /// Buckets ordered by hour then category so reports diff cleanly across polls.
+    public let buckets: [Bucket]
+
+    public init(buckets: [Bucket]) {
+        self.buckets = buckets
+    }
+
+    public var isEmpty: Bool {
+        buckets.isEmpty
+    }
+
+    public static let empty = UsageAccountingReport(buckets: [])
+}
+
+/// Pipeline-owned accumulator behind `UsageAccountingReport`.
+/// Bounded: when distinct hour-by-category cells exceed the cap, whole oldest hours are dropped
+/// first so recent usage stays accurate for the report consumer.
+struct UsageAccountant {
+    static let maxTrackedBuckets = 1_024
+    private static let secondsPerHour: TimeInterval = 3_600
+
+    private struct Key: Hashable {
+        let hourStart: Date
+        let category: String
+    }
+
+    private struct Counters {
+        var outboundBytes = 0
+        var inboundBytes = 0
+        var packetCount = 0
+    }
+
+    private var cells: [Key: Counters] = [:]
+
+    var isEmpty: Bool {
+        cells.isEmpty
+    }
+
+    mutating func record(category: String, direction: PacketDirection, byteCount: Int, now: Date) {
+        let hourStart = Date(
+            timeIntervalSince1970: (now.timeIntervalSince1970 / Self.secondsPerHour).rounded(.down) * Self.secondsPerHour
+        )
+        let key = Key(hourStart: hourStart, category: category)
+        var counters = cells[key] ?? Counters()
+        switch direction {
+        case .outbound:
+            counters.outboundBytes = saturatingAdd(counters.outboundBytes, byteCount)
+        case .inbound:
+            counters.inboundBytes = saturatingAdd(counters.inboundBytes, byteCount)
+        }
+        counters.packetCount = saturatingAdd(counters.packetCount, 1)
+        if cells[key] == nil, cells.count >= Self.maxTrackedBuckets {
+            evictOldestHour(protecting: hourStart)
+        }
+        cells[key] = counters
+    }
+
+    func report() -> UsageAccountingReport {
+        let buckets = cells
+            .map { key, counters in
+                UsageAccountingReport.Bucket(
+                    hourStart: key.hourStart,
+                    category: key.category,
+                    outboundBytes: counters.outboundBytes,
+                    inboundBytes: counters.inboundBytes,
+                    packetCount: counters.packetCount
+                )
+            }
+            .sorted {
+                if $0.hourStart != $1.hourStart {
+                    return $0.hourStart < $1.hourStart
+                }
+                return $0.category < $1.category
+            }
+        return UsageAccountingReport(buckets: buckets)
+    }
+
+    mutating func reset() {
+        cells = [:]
+    }
+
+    private mutating func evictOldestHour(protecting currentHour: Date) {
+        guard let oldestHour = cells.keys.map(\.hourStart).min(), oldestHour < currentHour else {
+            return
+        }
+        for key in cells.keys where key.hourStart == oldestHour {
+            cells.removeValue(forKey: key)
+        }
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (value, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : value
+    }
+}
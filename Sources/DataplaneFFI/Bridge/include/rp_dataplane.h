@@ -21,21 +21,144 @@ typedef struct {
     uint64_t packets_out;
     uint64_t bytes_in;
     uint64_t bytes_out;
+    /// Monotonically increasing counter epoch. Bumps on every engine start and
+    /// every `rp_dp_reset_stats`, so periodic collectors can detect that the
+    /// counters were rebased and skip one delta instead of reporting a
+    /// negative or wildly inflated sample.
+    uint64_t epoch;
 } rp_dp_stats_t;
 
+/// Explicit lifecycle states. A handle moves Created -> Running -> Stopped (or
+/// Failed when the poll worker exhausted automatic restarts); both Stopped and
+/// Failed are restartable via `rp_dp_start`, optionally after swapping
+/// callbacks with `rp_dp_update_callbacks`.
+#define RP_DP_STATE_CREATED 0u
+#define RP_DP_STATE_RUNNING 1u
+#define RP_DP_STATE_STOPPED 2u
+#define RP_DP_STATE_FAILED 3u
+
+/// Status codes shared by the lifecycle entry points.
+#define RP_DP_OK 0
+#define RP_DP_ERR_INVALID_ARGUMENT (-1)
+#define RP_DP_ERR_REENTRANT_CALL (-2)
+#define RP_DP_ERR_BAD_TUN_FD (-3)
+#define RP_DP_ERR_NO_CONFIG (-4)
+#define RP_DP_ERR_HANDLE_BUSY (-5)
+#define RP_DP_ERR_START_PENDING (-6)
+#define RP_DP_ERR_ENGINE_EXIT (-7)
+#define RP_DP_ERR_WORKER_SPAWN (-8)
+#define RP_DP_ERR_STILL_RUNNING (-9)
+#define RP_DP_ERR_ABI_MISMATCH (-10)
+
+#define RP_DP_STOP_REASON_NONE 0u
+#define RP_DP_STOP_REASON_HOST_STOP 1u
+#define RP_DP_STOP_REASON_ENGINE_EXIT 2u
+#define RP_DP_STOP_REASON_START_TIMEOUT 3u
+
+/// Engine lifecycle snapshot for correlating host-visible reconnects with
+/// engine-internal restarts. Times are CLOCK_MONOTONIC milliseconds.
+typedef struct {
+    uint64_t started_at_ms;
+    uint64_t uptime_ms;
+    uint64_t start_count;
+    uint64_t stop_count;
+    uint64_t worker_restart_count;
+    uint32_t is_running;
+    uint32_t last_stop_reason;
+    int32_t last_exit_code;
+} rp_dp_lifecycle_info_t;
+
+#define RP_DP_EVENT_KIND_LOG 1u
+#define RP_DP_EVENT_KIND_STATE 2u
+/// Epoch marker published on every successful engine start. Its `state` field
+/// carries the engine's start count and its timestamp pair anchors the
+/// monotonic clock to wall time for the epoch, so downstream analytics can
+/// order records across engine restarts and device sleeps without guessing at
+/// clock discontinuities.
+#define RP_DP_EVENT_KIND_EPOCH 3u
+#define RP_DP_EVENT_MESSAGE_CAPACITY 96u
+
+/// Fixed-size telemetry record written into a host-registered event ring.
+/// Layout contract: the struct is 128 bytes, string-free beyond the inline
+/// message buffer, and safe to place in shared (mmap'd) memory.
+/// Timestamp contract: `timestamp_ms` is CLOCK_MONOTONIC milliseconds — it
+/// never jumps but does not advance while the device sleeps; `wall_clock_ms`
+/// is CLOCK_REALTIME milliseconds — it survives sleep but may jump on NTP or
+/// user clock changes. Order records within an epoch by `timestamp_ms` and
+/// map epochs onto real time via their RP_DP_EVENT_KIND_EPOCH marker.
+typedef struct {
+    uint64_t sequence;
+    uint64_t timestamp_ms;
+    uint32_t kind;
+    uint32_t state;
+    uint64_t wall_clock_ms;
+    char message[RP_DP_EVENT_MESSAGE_CAPACITY];
+} rp_dp_event_record_t;
+
+#define RP_DP_PATH_INTERFACE_OTHER 0u
+#define RP_DP_PATH_INTERFACE_WIFI 1u
+#define RP_DP_PATH_INTERFACE_CELLULAR 2u
+#define RP_DP_PATH_INTERFACE_WIRED 3u
+
+#define RP_DP_PATH_FLAG_EXPENSIVE 1u
+#define RP_DP_PATH_FLAG_CONSTRAINED 2u
+
+/// Snapshot of the device's active network path handed to the engine on change.
+typedef struct {
+    uint32_t interface_kind;
+    uint32_t flags;
+} rp_dp_path_info_t;
+
 typedef void (*rp_dp_on_log_fn)(const char *message, void *user_ctx);
 typedef void (*rp_dp_on_state_fn)(uint32_t state, void *user_ctx);
+typedef void (*rp_dp_on_fatal_fn)(int32_t exit_code, void *user_ctx);
 
 typedef struct {
     rp_dp_on_log_fn on_log;
     rp_dp_on_state_fn on_state;
+    /// Invoked when the poll task exits with an error and automatic restarts
+    /// are exhausted; the engine will not forward packets again until the host
+    /// restarts it.
+    rp_dp_on_fatal_fn on_fatal;
 } rp_dp_callbacks_t;
 
+typedef void (*rp_dp_ctx_free_fn)(void *ctx);
+
+/// v2 callback table: one context pointer per callback instead of the shared
+/// `user_ctx`, plus an optional destructor so hosts can hand the bridge
+/// separately captured objects without tracking their lifetimes themselves.
+typedef struct {
+    rp_dp_on_log_fn on_log;
+    void *log_ctx;
+    rp_dp_on_state_fn on_state;
+    void *state_ctx;
+    rp_dp_on_fatal_fn on_fatal;
+    void *fatal_ctx;
+    /// Invoked once per distinct non-NULL context pointer of a callback
+    /// generation when the handle is freed, after the delivery queue has
+    /// drained, so no callback can run against a freed context. Contexts
+    /// superseded by an update call are retained until destroy — queued
+    /// callbacks may still reference them — and are then freed with the
+    /// destructor installed alongside them. NULL disables automatic cleanup.
+    rp_dp_ctx_free_fn free_ctx;
+} rp_dp_callbacks_v2_t;
+
 typedef struct rp_dp_handle rp_dp_handle_t;
 
 /// Returns the dataplane API and ABI versions.
 rp_dp_version_t rp_dp_get_version(void);
 
+/// Runtime ABI handshake a host calls before creating its first handle: each
+/// `sizeof_*` is the size the host compiled for the named struct, and
+/// `header_version` is the version pair from the header it compiled against.
+/// Returns RP_DP_OK when every size and both version numbers match this
+/// binary, RP_DP_ERR_ABI_MISMATCH otherwise — turning a stale or divergent
+/// header into a clear refusal instead of silently corrupted struct fields.
+int32_t rp_dp_verify_abi(uint32_t sizeof_callbacks_v2,
+                         uint32_t sizeof_event_record,
+                         uint32_t sizeof_metric_entry,
+                         rp_dp_version_t header_version);
+
 /// Creates a dataplane handle bound to a dedicated callback queue.
 /// Callback contract:
 /// - exactly one serial callback queue per handle
@@ -44,12 +167,42 @@ rp_dp_version_t rp_dp_get_version(void);
 /// - control APIs must not be called reentrantly from callback context
 rp_dp_handle_t *rp_dp_create(const char *config_json, const rp_dp_callbacks_t *callbacks, void *user_ctx);
 
+/// Creates a dataplane handle from a v2 callback table with per-callback
+/// contexts. Same callback contract as `rp_dp_create`. Ownership: on success
+/// the handle owns the table's contexts and frees them through `free_ctx` at
+/// destroy; on failure the bridge invokes `free_ctx` before returning NULL, so
+/// the caller never has to track which side owns a context.
+rp_dp_handle_t *rp_dp_create_v2(const char *config_json, const rp_dp_callbacks_v2_t *callbacks);
+
 /// Starts packet processing using the supplied TUN file descriptor.
 int32_t rp_dp_start(rp_dp_handle_t *handle, int32_t tun_fd);
 
 /// Stops packet processing for an active dataplane handle.
 int32_t rp_dp_stop(rp_dp_handle_t *handle);
 
+/// Returns the handle's current lifecycle state (one of `RP_DP_STATE_*`) or a
+/// negative status code.
+int32_t rp_dp_get_state(rp_dp_handle_t *handle);
+
+/// Replaces the callback table and context used for subsequent dispatches, so a
+/// stopped or failed handle can be restarted with fresh callbacks. Only legal
+/// while the engine is not started (`RP_DP_ERR_STILL_RUNNING` otherwise).
+/// Contract: call after rp_dp_stop returns or after observing the stopped or
+/// failed state callback; the bridge does not synchronize against callbacks
+/// already in flight on the delivery queue, which may still carry the previous
+/// context.
+int32_t rp_dp_update_callbacks(rp_dp_handle_t *handle,
+                               const rp_dp_callbacks_t *callbacks,
+                               void *user_ctx);
+
+/// v2 variant of `rp_dp_update_callbacks` taking per-callback contexts. The
+/// superseded generation's contexts stay retained until destroy and are then
+/// freed with their own `free_ctx`, because the delivery queue may still carry
+/// callbacks captured against them. On a non-zero return the caller keeps
+/// ownership of the new table's contexts.
+int32_t rp_dp_update_callbacks_v2(rp_dp_handle_t *handle,
+                                  const rp_dp_callbacks_v2_t *callbacks);
+
 /// Destroys an existing dataplane handle. Returns 0 when destroyed synchronously,
 /// 1 when destruction was scheduled off callback context, or a negative error code.
 int32_t rp_dp_destroy(rp_dp_handle_t *handle);
@@ -57,6 +210,132 @@ int32_t rp_dp_destroy(rp_dp_handle_t *handle);
 /// Retrieves dataplane statistics.
 int32_t rp_dp_get_stats(rp_dp_handle_t *handle, rp_dp_stats_t *out_stats);
 
+/// Zeroes the packet/byte counters and opens a new stats epoch. Subsequent
+/// `rp_dp_get_stats` snapshots count from the reset point and carry the new
+/// epoch. Returns 0 on success or a negative error code.
+int32_t rp_dp_reset_stats(rp_dp_handle_t *handle);
+
+/// Retrieves engine lifecycle statistics: uptime, start/stop totals, worker
+/// restarts, and the reason the engine last stopped.
+int32_t rp_dp_get_lifecycle_info(rp_dp_handle_t *handle, rp_dp_lifecycle_info_t *out_info);
+
+/// Notifies the engine that the device's network path changed (for example a
+/// Wi-Fi to cellular handover). Established flows are revalidated on their
+/// next activity and the engine drops path-specific negative dial state; the
+/// notification itself is a droppable hint and never fails a running engine.
+/// Returns 0 on success or a negative error code.
+int32_t rp_dp_on_path_changed(rp_dp_handle_t *handle, const rp_dp_path_info_t *info);
+
+/// Registers a caller-owned ring of fixed-size event records.
+/// Ring contract:
+/// - the host owns the memory (heap or mmap'd) and must keep it valid until
+///   the ring is unregistered or the handle is destroyed
+/// - the bridge overwrites the slot at `sequence % capacity`, zeroing the
+///   record's sequence number first and republishing it last with release
+///   semantics; read slots through `rp_dp_event_record_snapshot`, whose
+///   acquire ordering makes mid-overwrite records detectable on weakly
+///   ordered hardware
+/// - sequence numbers start at 1 (or one past the partitioned registration's
+///   sequence base) and increase monotonically per handle
+/// Returns 0 on success or a negative error code.
+int32_t rp_dp_register_event_ring(rp_dp_handle_t *handle,
+                                  rp_dp_event_record_t *records,
+                                  uint32_t capacity);
+
+/// Registers an event ring whose published sequence numbers are offset by
+/// `sequence_base`: the first record carries `sequence_base + 1`. Hosts running
+/// several engines (or embedding other sequence-keyed systems) hand each
+/// instance a disjoint base — for example instance N passes `N << 48` — so
+/// records merged into one host-side map never collide. Slot placement still
+/// cycles through the ring from slot 0, and `rp_dp_register_event_ring` is
+/// equivalent to a zero base. Returns 0 on success or a negative error code.
+int32_t rp_dp_register_event_ring_partitioned(rp_dp_handle_t *handle,
+                                              rp_dp_event_record_t *records,
+                                              uint32_t capacity,
+                                              uint64_t sequence_base);
+
+/// Unregisters a previously registered event ring. Safe to call when no ring
+/// is registered. Returns 0 on success or a negative error code.
+int32_t rp_dp_unregister_event_ring(rp_dp_handle_t *handle);
+
+/// Returns the sequence number of the most recently published ring record,
+/// or 0 when no ring is registered or nothing has been published.
+uint64_t rp_dp_event_ring_sequence(rp_dp_handle_t *handle);
+
+/// Copies one ring slot into `out_record` using the acquire side of the
+/// publish protocol, retrying a bounded number of times when a concurrent
+/// publish is caught rewriting the slot. Returns 0 on a stable copy, or -1
+/// when the slot is unpublished, still unstable after the retries, or an
+/// argument is NULL.
+int32_t rp_dp_event_record_snapshot(const rp_dp_event_record_t *record,
+                                    rp_dp_event_record_t *out_record);
+
+/// Returns the number of bridge-owned heap allocations currently live across
+/// all handles: handles, config copies, queued callback tasks, and retired
+/// callback contexts. Diagnostic surface for teardown audits — a process with
+/// no live handles and a drained delivery queue reads 0, so tests can assert
+/// create/destroy cycles return to baseline without sampling process RSS.
+uint64_t rp_dp_debug_live_allocations(void);
+
+/// Per-subsystem breakdown of bridge-owned heap allocations: live counts for
+/// leak audits plus cumulative totals for catching hot-path allocation
+/// regressions (for example a change that starts allocating per ring publish
+/// shows up as a callback-task total growing faster than the event count).
+typedef struct {
+    uint64_t live_handles;
+    uint64_t live_config_copies;
+    uint64_t live_callback_tasks;
+    uint64_t live_retired_contexts;
+    uint64_t total_handles;
+    uint64_t total_config_copies;
+    uint64_t total_callback_tasks;
+    uint64_t total_retired_contexts;
+} rp_dp_debug_alloc_counters_t;
+
+/// Copies the current per-subsystem allocation counters. Diagnostic surface;
+/// counters are process-global across handles.
+void rp_dp_debug_allocation_counters(rp_dp_debug_alloc_counters_t *out_counters);
+
+/// Schema version carried by `rp_dp_query_metrics`. Bumps only when an existing
+/// metric name changes meaning or disappears; appending new metrics to a
+/// category does not bump it, which is the point of the name/value shape.
+#define RP_DP_METRICS_SCHEMA_VERSION 1u
+
+#define RP_DP_METRICS_CATEGORY_TRAFFIC 1u
+#define RP_DP_METRICS_CATEGORY_LIFECYCLE 2u
+#define RP_DP_METRICS_CATEGORY_ALLOCATIONS 3u
+/// Network-stack drop and error counters (rx drops, header/checksum errors,
+/// unroutable packets, memory exhaustion), so throughput investigations can
+/// separate stack-internal drops from host-side drops. Process-global and
+/// cumulative, unlike the epoch-rebased traffic counters.
+#define RP_DP_METRICS_CATEGORY_STACK 4u
+
+#define RP_DP_METRIC_NAME_CAPACITY 48u
+
+/// One named counter. Names are stable ASCII identifiers (for example
+/// "packets_in"); hosts key on the name, never on array position, so the
+/// bridge is free to reorder and append.
+typedef struct {
+    char name[RP_DP_METRIC_NAME_CAPACITY];
+    uint64_t value;
+} rp_dp_metric_entry_t;
+
+/// Generic metrics query over name/value pairs, replacing per-subsystem stats
+/// structs as the growth surface: new metrics ship by appending entries, with
+/// no new ABI types per release. Copies up to `capacity` entries for the given
+/// `RP_DP_METRICS_CATEGORY_*` into `out_entries` and returns the number of
+/// metrics the category currently exposes — which may exceed `capacity`, so a
+/// caller passing capacity 0 (entries may then be NULL) learns the required
+/// array size. `out_schema_version`, when non-NULL, receives
+/// `RP_DP_METRICS_SCHEMA_VERSION` so collectors can gate interpretation.
+/// Returns a negative status code for an unknown category or invalid
+/// arguments.
+int32_t rp_dp_query_metrics(rp_dp_handle_t *handle,
+                            uint32_t category,
+                            rp_dp_metric_entry_t *out_entries,
+                            uint32_t capacity,
+                            uint32_t *out_schema_version);
+
 #ifdef __cplusplus
 }
 #endif
@@ -2,8 +2,14 @@
 // Copyright (c) 2026 Relative Companies, Inc.
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
+#if defined(__linux__)
+/* pthread_setname_np is a GNU extension. */
+#define _GNU_SOURCE
+#endif
+
 #include "rp_dataplane.h"
 
+#include <assert.h>
 #include <errno.h>
 #include <fcntl.h>
 #include <stddef.h>
@@ -16,12 +22,14 @@
 #include <time.h>
 #include <unistd.h>
 
-#define RP_DP_API_VERSION 1
-#define RP_DP_ABI_VERSION 1
-#define RP_DP_STATE_CREATED 0
-#define RP_DP_STATE_RUNNING 1
-#define RP_DP_STATE_STOPPED 2
+#if defined(__APPLE__)
+#include <pthread/qos.h>
+#endif
+
+#define RP_DP_API_VERSION 9
+#define RP_DP_ABI_VERSION 4
 #define RP_DP_MAX_CALLBACK_QUEUE_DEPTH 4096
+#define RP_DP_MAX_WORKER_RESTARTS 3u
 
 static const char *RP_DP_CREATED_MSG = "dataplane-created";
 static const char *RP_DP_RUNNING_MSG = "dataplane-running";
@@ -30,6 +38,24 @@ static const char *RP_DP_EXITED_MSG = "dataplane-exited";
 static const char *RP_DP_EXITED_ERROR_MSG = "dataplane-exited-error";
 static const char *RP_DP_READY_MSG = "dataplane-ready";
 
+/*
+ * Thread identity for Instruments traces and jetsam reports. The engine runs exactly one poll
+ * thread (the vendored tunnel loop is process-global) plus one callback delivery thread, so
+ * naming them and setting QoS is what makes traces attributable; there is no worker count to
+ * configure.
+ */
+static void rp_dp_adopt_thread_identity(const char *name, int is_packet_path)
+{
+#if defined(__APPLE__)
+    pthread_setname_np(name);
+    pthread_set_qos_class_self_np(
+        is_packet_path ? QOS_CLASS_USER_INITIATED : QOS_CLASS_UTILITY, 0);
+#else
+    pthread_setname_np(pthread_self(), name);
+    (void)is_packet_path;
+#endif
+}
+
 /*
  * Vendored symbols exported by HevSocks5Tunnel. We declare them here to avoid
  * patching vendored headers.
@@ -40,6 +66,9 @@ extern int hev_socks5_tunnel_main_from_str(const unsigned char *config_str,
 extern void hev_socks5_tunnel_quit(void);
 extern void hev_socks5_tunnel_stats(size_t *tx_packets, size_t *tx_bytes,
                                     size_t *rx_packets, size_t *rx_bytes);
+extern void hev_socks5_tunnel_stack_stats(size_t *rx_drops, size_t *rx_errors,
+                                          size_t *unaddressable,
+                                          size_t *memory_failures);
 
 static pthread_key_t rp_dp_callback_queue_key;
 static pthread_key_t rp_dp_worker_queue_key;
@@ -48,9 +77,79 @@ static pthread_mutex_t rp_dp_global_lock = PTHREAD_MUTEX_INITIALIZER;
 static struct rp_dp_handle *rp_dp_active_handle;
 static uint32_t rp_dp_tcp_isn_counter;
 
+/*
+ * Teardown audit: every bridge-owned heap allocation (handles, config copies,
+ * callback tasks, retired callback contexts) is counted here per subsystem,
+ * so tests can assert the bridge returns to its baseline after create/destroy
+ * cycles and attribute allocation regressions to the path that caused them,
+ * instead of watching process RSS, which page caching makes unreliable.
+ */
+enum {
+    RP_DP_ALLOC_HANDLE = 0,
+    RP_DP_ALLOC_CONFIG = 1,
+    RP_DP_ALLOC_TASK = 2,
+    RP_DP_ALLOC_RETIRED_CTX = 3,
+    RP_DP_ALLOC_CATEGORY_COUNT = 4
+};
+
+static pthread_mutex_t rp_dp_mem_lock = PTHREAD_MUTEX_INITIALIZER;
+static uint64_t rp_dp_live_allocation_counts[RP_DP_ALLOC_CATEGORY_COUNT];
+static uint64_t rp_dp_total_allocation_counts[RP_DP_ALLOC_CATEGORY_COUNT];
+
+static void rp_dp_mem_note_alloc(int category)
+{
+    pthread_mutex_lock(&rp_dp_mem_lock);
+    rp_dp_live_allocation_counts[category]++;
+    rp_dp_total_allocation_counts[category]++;
+    pthread_mutex_unlock(&rp_dp_mem_lock);
+}
+
+static void rp_dp_mem_note_free(int category)
+{
+    pthread_mutex_lock(&rp_dp_mem_lock);
+    assert(rp_dp_live_allocation_counts[category] > 0);
+    if (rp_dp_live_allocation_counts[category] > 0) {
+        rp_dp_live_allocation_counts[category]--;
+    }
+    pthread_mutex_unlock(&rp_dp_mem_lock);
+}
+
+uint64_t rp_dp_debug_live_allocations(void)
+{
+    uint64_t count = 0;
+    size_t i;
+
+    pthread_mutex_lock(&rp_dp_mem_lock);
+    for (i = 0; i < RP_DP_ALLOC_CATEGORY_COUNT; i++) {
+        count += rp_dp_live_allocation_counts[i];
+    }
+    pthread_mutex_unlock(&rp_dp_mem_lock);
+    return count;
+}
+
+void rp_dp_debug_allocation_counters(rp_dp_debug_alloc_counters_t *out_counters)
+{
+    if (out_counters == NULL) {
+        return;
+    }
+    pthread_mutex_lock(&rp_dp_mem_lock);
+    out_counters->live_handles = rp_dp_live_allocation_counts[RP_DP_ALLOC_HANDLE];
+    out_counters->live_config_copies = rp_dp_live_allocation_counts[RP_DP_ALLOC_CONFIG];
+    out_counters->live_callback_tasks = rp_dp_live_allocation_counts[RP_DP_ALLOC_TASK];
+    out_counters->live_retired_contexts =
+        rp_dp_live_allocation_counts[RP_DP_ALLOC_RETIRED_CTX];
+    out_counters->total_handles = rp_dp_total_allocation_counts[RP_DP_ALLOC_HANDLE];
+    out_counters->total_config_copies = rp_dp_total_allocation_counts[RP_DP_ALLOC_CONFIG];
+    out_counters->total_callback_tasks = rp_dp_total_allocation_counts[RP_DP_ALLOC_TASK];
+    out_counters->total_retired_contexts =
+        rp_dp_total_allocation_counts[RP_DP_ALLOC_RETIRED_CTX];
+    pthread_mutex_unlock(&rp_dp_mem_lock);
+}
+
 struct rp_dp_callback_task {
     uint8_t kind;
     uint32_t state;
+    int32_t exit_code;
     char *message;
     struct rp_dp_callback_task *next;
 };
@@ -68,14 +167,41 @@ struct rp_dp_callback_queue {
     uint8_t stopped;
 };
 
+/* One superseded callback context awaiting destroy-time cleanup, paired with
+ * the destructor that was installed alongside it. */
+struct rp_dp_retired_ctx {
+    void *ctx;
+    rp_dp_ctx_free_fn free_fn;
+    struct rp_dp_retired_ctx *next;
+};
+
+struct rp_dp_event_ring {
+    rp_dp_event_record_t *records;
+    uint32_t capacity;
+    /* Count of records published into the current ring; published sequence
+     * numbers are sequence_base + this count, so multiplexed hosts can give
+     * every engine instance a disjoint sequence range. */
+    uint64_t next_sequence;
+    uint64_t sequence_base;
+};
+
 struct rp_dp_handle {
     struct rp_dp_callback_queue callback_queue;
+    struct rp_dp_event_ring event_ring;
     pthread_t worker_thread;
     pthread_mutex_t startup_lock;
     pthread_cond_t startup_cond;
-    rp_dp_callbacks_t callbacks;
-    void *user_ctx;
+    /* Callbacks always live in v2 form internally; the v1 entry points bridge
+     * their shared user_ctx into the three per-callback slots. */
+    rp_dp_callbacks_v2_t callbacks;
+    /* Contexts superseded by callback updates, freed only at destroy because
+     * the delivery queue may still carry tasks captured against them. */
+    struct rp_dp_retired_ctx *retired_ctxs;
     rp_dp_stats_t stats;
+    /* Counter values at the last reset; snapshots subtract these so resets do
+     * not disturb the vendored engine's cumulative counters. */
+    rp_dp_stats_t stats_base;
+    uint64_t stats_epoch;
     char *config_json;
     size_t config_len;
     int32_t tun_fd;
@@ -86,11 +212,20 @@ struct rp_dp_handle {
     uint8_t ready;
     uint8_t exited;
     int32_t exit_code;
+    uint64_t started_at_ms;
+    uint64_t start_count;
+    uint64_t stop_count;
+    uint64_t worker_launch_count;
+    uint64_t engine_restart_count;
+    uint32_t restart_attempts;
+    uint32_t last_stop_reason;
+    uint32_t lifecycle_state;
 };
 
 enum {
     RP_DP_CALLBACK_LOG = 1,
-    RP_DP_CALLBACK_STATE = 2
+    RP_DP_CALLBACK_STATE = 2,
+    RP_DP_CALLBACK_FATAL = 3
 };
 
 static void rp_dp_init_thread_keys(void)
@@ -106,6 +241,7 @@ static void rp_dp_callback_task_destroy(struct rp_dp_callback_task *task)
     }
     free(task->message);
     free(task);
+    rp_dp_mem_note_free(RP_DP_ALLOC_TASK);
 }
 
 static void *rp_dp_callback_queue_main(void *ctx)
@@ -113,6 +249,7 @@ static void *rp_dp_callback_queue_main(void *ctx)
     struct rp_dp_callback_queue *queue = (struct rp_dp_callback_queue *)ctx;
     struct rp_dp_handle *handle = queue->handle;
 
+    rp_dp_adopt_thread_identity("rp-engine-io", 0);
     pthread_once(&rp_dp_thread_keys_once, rp_dp_init_thread_keys);
     pthread_setspecific(rp_dp_callback_queue_key, handle);
 
@@ -138,12 +275,17 @@ static void *rp_dp_callback_queue_main(void *ctx)
         switch (task->kind) {
         case RP_DP_CALLBACK_LOG:
             if (handle->callbacks.on_log != NULL && task->message != NULL) {
-                handle->callbacks.on_log(task->message, handle->user_ctx);
+                handle->callbacks.on_log(task->message, handle->callbacks.log_ctx);
             }
             break;
         case RP_DP_CALLBACK_STATE:
             if (handle->callbacks.on_state != NULL) {
-                handle->callbacks.on_state(task->state, handle->user_ctx);
+                handle->callbacks.on_state(task->state, handle->callbacks.state_ctx);
+            }
+            break;
+        case RP_DP_CALLBACK_FATAL:
+            if (handle->callbacks.on_fatal != NULL) {
+                handle->callbacks.on_fatal(task->exit_code, handle->callbacks.fatal_ctx);
             }
             break;
         default:
@@ -285,8 +427,86 @@ static int32_t rp_dp_reentrant_call_guard(void)
     return 0;
 }
 
+static uint64_t rp_dp_monotonic_ms(void)
+{
+    struct timespec now;
+
+    if (clock_gettime(CLOCK_MONOTONIC, &now) != 0) {
+        return 0;
+    }
+    return (uint64_t)now.tv_sec * 1000u + (uint64_t)(now.tv_nsec / 1000000);
+}
+
+static uint64_t rp_dp_wall_clock_ms(void)
+{
+    struct timespec now;
+
+    if (clock_gettime(CLOCK_REALTIME, &now) != 0) {
+        return 0;
+    }
+    return (uint64_t)now.tv_sec * 1000u + (uint64_t)(now.tv_nsec / 1000000);
+}
+
+/*
+ * Publishes one fixed-size record into the host-registered ring, if any.
+ * Seqlock publication: the sequence field is zeroed before the payload is
+ * written and republished with release semantics afterwards, with a release
+ * fence keeping the zero store ahead of the payload stores. Lock-free readers
+ * (rp_dp_event_record_snapshot) pair these with acquire loads so the
+ * payload/sequence ordering holds on weakly ordered hardware, not just x86.
+ */
+static void rp_dp_event_ring_publish(struct rp_dp_handle *handle, uint32_t kind,
+                                     uint32_t state, const char *message)
+{
+    rp_dp_event_record_t *slot;
+    uint64_t sequence;
+
+    if (handle == NULL) {
+        return;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    if (handle->event_ring.records == NULL || handle->event_ring.capacity == 0) {
+        pthread_mutex_unlock(&rp_dp_global_lock);
+        return;
+    }
+    sequence = handle->event_ring.sequence_base + ++handle->event_ring.next_sequence;
+    slot = &handle->event_ring.records[(handle->event_ring.next_sequence - 1u) % handle->event_ring.capacity];
+    __atomic_store_n(&slot->sequence, 0u, __ATOMIC_RELAXED);
+    __atomic_thread_fence(__ATOMIC_RELEASE);
+    slot->timestamp_ms = rp_dp_monotonic_ms();
+    slot->wall_clock_ms = rp_dp_wall_clock_ms();
+    slot->kind = kind;
+    slot->state = state;
+    if (message != NULL) {
+        strncpy(slot->message, message, RP_DP_EVENT_MESSAGE_CAPACITY - 1u);
+        slot->message[RP_DP_EVENT_MESSAGE_CAPACITY - 1u] = '\0';
+    } else {
+        slot->message[0] = '\0';
+    }
+    __atomic_store_n(&slot->sequence, sequence, __ATOMIC_RELEASE);
+    pthread_mutex_unlock(&rp_dp_global_lock);
+}
+
+/*
+ * Publishes the epoch marker that opens a new engine run, so ring readers can
+ * re-anchor the monotonic clock against wall time and detect restarts without
+ * consulting lifecycle info.
+ */
+static void rp_dp_publish_epoch_marker(struct rp_dp_handle *handle,
+                                       uint64_t start_count)
+{
+    char message[RP_DP_EVENT_MESSAGE_CAPACITY];
+
+    snprintf(message, sizeof(message), "engine-epoch start_count=%llu",
+             (unsigned long long)start_count);
+    rp_dp_event_ring_publish(handle, RP_DP_EVENT_KIND_EPOCH,
+                             (uint32_t)start_count, message);
+}
+
 static void rp_dp_dispatch_log(struct rp_dp_handle *handle, const char *message)
 {
+    rp_dp_event_ring_publish(handle, RP_DP_EVENT_KIND_LOG, 0, message);
     if (handle == NULL || handle->callbacks.on_log == NULL || message == NULL) {
         return;
     }
@@ -302,6 +522,7 @@ static void rp_dp_dispatch_log(struct rp_dp_handle *handle, const char *message)
         free(payload);
         return;
     }
+    rp_dp_mem_note_alloc(RP_DP_ALLOC_TASK);
     task->kind = RP_DP_CALLBACK_LOG;
     task->message = payload;
     if (rp_dp_callback_queue_enqueue(handle, task) != 0) {
@@ -345,8 +566,29 @@ static void rp_dp_dispatch_logf(struct rp_dp_handle *handle, const char *format,
     free(heap_buffer);
 }
 
+static void rp_dp_dispatch_fatal(struct rp_dp_handle *handle, int32_t exit_code)
+{
+    rp_dp_dispatch_logf(handle, "dataplane-fatal exit_code=%d", exit_code);
+    if (handle == NULL || handle->callbacks.on_fatal == NULL) {
+        return;
+    }
+
+    struct rp_dp_callback_task *task =
+        (struct rp_dp_callback_task *)calloc(1, sizeof(struct rp_dp_callback_task));
+    if (task == NULL) {
+        return;
+    }
+    rp_dp_mem_note_alloc(RP_DP_ALLOC_TASK);
+    task->kind = RP_DP_CALLBACK_FATAL;
+    task->exit_code = exit_code;
+    if (rp_dp_callback_queue_enqueue(handle, task) != 0) {
+        rp_dp_callback_task_destroy(task);
+    }
+}
+
 static void rp_dp_dispatch_state(struct rp_dp_handle *handle, uint32_t state)
 {
+    rp_dp_event_ring_publish(handle, RP_DP_EVENT_KIND_STATE, state, NULL);
     if (handle == NULL || handle->callbacks.on_state == NULL) {
         return;
     }
@@ -356,6 +598,7 @@ static void rp_dp_dispatch_state(struct rp_dp_handle *handle, uint32_t state)
     if (task == NULL) {
         return;
     }
+    rp_dp_mem_note_alloc(RP_DP_ALLOC_TASK);
     task->kind = RP_DP_CALLBACK_STATE;
     task->state = state;
     if (rp_dp_callback_queue_enqueue(handle, task) != 0) {
@@ -376,10 +619,23 @@ static void rp_dp_refresh_stats(struct rp_dp_handle *handle)
 
     hev_socks5_tunnel_stats(&tx_packets, &tx_bytes, &rx_packets, &rx_bytes);
     pthread_mutex_lock(&rp_dp_global_lock);
-    handle->stats.packets_in = (uint64_t)rx_packets;
-    handle->stats.bytes_in = (uint64_t)rx_bytes;
-    handle->stats.packets_out = (uint64_t)tx_packets;
-    handle->stats.bytes_out = (uint64_t)tx_bytes;
+    if ((uint64_t)rx_packets < handle->stats_base.packets_in ||
+        (uint64_t)rx_bytes < handle->stats_base.bytes_in ||
+        (uint64_t)tx_packets < handle->stats_base.packets_out ||
+        (uint64_t)tx_bytes < handle->stats_base.bytes_out) {
+        /*
+         * The vendored engine restarted and zeroed its counters underneath a
+         * reset base; rebase and open a new epoch so collectors never compute
+         * a negative delta.
+         */
+        memset(&handle->stats_base, 0, sizeof(handle->stats_base));
+        handle->stats_epoch++;
+    }
+    handle->stats.packets_in = (uint64_t)rx_packets - handle->stats_base.packets_in;
+    handle->stats.bytes_in = (uint64_t)rx_bytes - handle->stats_base.bytes_in;
+    handle->stats.packets_out = (uint64_t)tx_packets - handle->stats_base.packets_out;
+    handle->stats.bytes_out = (uint64_t)tx_bytes - handle->stats_base.bytes_out;
+    handle->stats.epoch = handle->stats_epoch;
     pthread_mutex_unlock(&rp_dp_global_lock);
 }
 
@@ -427,6 +683,8 @@ void rp_dp_hev_notify_ready(void)
     handle = rp_dp_active_handle;
     if (handle != NULL) {
         handle->ready = 1;
+        handle->restart_attempts = 0;
+        handle->lifecycle_state = RP_DP_STATE_RUNNING;
         should_stop = handle->stopping != 0;
         should_signal = 1;
     }
@@ -461,10 +719,32 @@ rp_dp_version_t rp_dp_get_version(void)
     return version;
 }
 
+int32_t rp_dp_verify_abi(uint32_t sizeof_callbacks_v2,
+                         uint32_t sizeof_event_record,
+                         uint32_t sizeof_metric_entry,
+                         rp_dp_version_t header_version)
+{
+    if (sizeof_callbacks_v2 != (uint32_t)sizeof(rp_dp_callbacks_v2_t)) {
+        return RP_DP_ERR_ABI_MISMATCH;
+    }
+    if (sizeof_event_record != (uint32_t)sizeof(rp_dp_event_record_t)) {
+        return RP_DP_ERR_ABI_MISMATCH;
+    }
+    if (sizeof_metric_entry != (uint32_t)sizeof(rp_dp_metric_entry_t)) {
+        return RP_DP_ERR_ABI_MISMATCH;
+    }
+    if (header_version.api_version != RP_DP_API_VERSION ||
+        header_version.abi_version != RP_DP_ABI_VERSION) {
+        return RP_DP_ERR_ABI_MISMATCH;
+    }
+    return RP_DP_OK;
+}
+
 static void *rp_dp_worker_main(void *ctx)
 {
     struct rp_dp_handle *handle = (struct rp_dp_handle *)ctx;
 
+    rp_dp_adopt_thread_identity("rp-engine-poll", 1);
     pthread_once(&rp_dp_thread_keys_once, rp_dp_init_thread_keys);
     pthread_setspecific(rp_dp_worker_queue_key, handle);
 
@@ -481,10 +761,48 @@ static void *rp_dp_worker_main(void *ctx)
     worker_config_len = handle->config_len;
     pthread_mutex_unlock(&rp_dp_global_lock);
 
-    int result = hev_socks5_tunnel_main_from_str(
-        (const unsigned char *)worker_config_json,
-        (unsigned int)worker_config_len,
-        worker_tun_fd);
+    int result;
+    for (;;) {
+        result = hev_socks5_tunnel_main_from_str(
+            (const unsigned char *)worker_config_json,
+            (unsigned int)worker_config_len,
+            worker_tun_fd);
+
+        pthread_mutex_lock(&rp_dp_global_lock);
+        int should_restart = 0;
+        if (handle->stopping == 0 && result != 0 &&
+            handle->restart_attempts < RP_DP_MAX_WORKER_RESTARTS) {
+            handle->restart_attempts++;
+            handle->engine_restart_count++;
+            should_restart = 1;
+        }
+        uint32_t attempt = handle->restart_attempts;
+        pthread_mutex_unlock(&rp_dp_global_lock);
+
+        if (should_restart == 0) {
+            break;
+        }
+
+        rp_dp_dispatch_logf(handle,
+                            "dataplane-worker-restart attempt=%u max=%u exit_code=%d",
+                            attempt, RP_DP_MAX_WORKER_RESTARTS, result);
+
+        /*
+         * Linear backoff so a crash loop cannot spin a core; a stop request
+         * arriving during the backoff aborts the retry.
+         */
+        struct timespec backoff;
+        backoff.tv_sec = 0;
+        backoff.tv_nsec = (long)attempt * 100000000L;
+        (void)nanosleep(&backoff, NULL);
+
+        pthread_mutex_lock(&rp_dp_global_lock);
+        int host_stopping = handle->stopping != 0;
+        pthread_mutex_unlock(&rp_dp_global_lock);
+        if (host_stopping != 0) {
+            break;
+        }
+    }
 
     rp_dp_refresh_stats(handle);
     pthread_mutex_lock(&rp_dp_global_lock);
@@ -492,6 +810,13 @@ static void *rp_dp_worker_main(void *ctx)
     handle->exited = 1;
     handle->started = 0;
     int should_dispatch_stopped = !handle->stopping;
+    int retries_exhausted = should_dispatch_stopped && result != 0;
+    if (should_dispatch_stopped) {
+        handle->stop_count++;
+        handle->last_stop_reason = RP_DP_STOP_REASON_ENGINE_EXIT;
+        handle->lifecycle_state =
+            retries_exhausted != 0 ? RP_DP_STATE_FAILED : RP_DP_STATE_STOPPED;
+    }
     pthread_mutex_unlock(&rp_dp_global_lock);
 
     if (result == 0) {
@@ -502,6 +827,9 @@ static void *rp_dp_worker_main(void *ctx)
                             RP_DP_EXITED_ERROR_MSG, result);
     }
 
+    if (retries_exhausted != 0) {
+        rp_dp_dispatch_fatal(handle, result);
+    }
     if (should_dispatch_stopped) {
         rp_dp_dispatch_state(handle, RP_DP_STATE_STOPPED);
     }
@@ -512,40 +840,167 @@ static void *rp_dp_worker_main(void *ctx)
     return NULL;
 }
 
+/*
+ * Invokes a callback generation's destructor over its distinct non-NULL
+ * contexts. The v1 entry points install the same shared context in all three
+ * slots, so the dedup keeps a shared context from being freed more than once.
+ */
+static void rp_dp_free_generation_ctxs(const rp_dp_callbacks_v2_t *callbacks)
+{
+    void *ctxs[3];
+    size_t i;
+    size_t j;
+
+    if (callbacks == NULL || callbacks->free_ctx == NULL) {
+        return;
+    }
+    ctxs[0] = callbacks->log_ctx;
+    ctxs[1] = callbacks->state_ctx;
+    ctxs[2] = callbacks->fatal_ctx;
+    for (i = 0; i < 3; i++) {
+        int duplicate = 0;
+        if (ctxs[i] == NULL) {
+            continue;
+        }
+        for (j = 0; j < i; j++) {
+            if (ctxs[j] == ctxs[i]) {
+                duplicate = 1;
+                break;
+            }
+        }
+        if (duplicate == 0) {
+            callbacks->free_ctx(ctxs[i]);
+        }
+    }
+}
+
+/*
+ * Moves the current callback generation's contexts onto the retired list.
+ * Retired contexts are freed only at destroy because the delivery queue may
+ * still carry callbacks captured against them. Caller holds the global lock.
+ * On allocation failure the context is leaked rather than freed early; a
+ * dangling pointer under a queued callback is the worse failure mode.
+ */
+static void rp_dp_retire_callback_ctxs(struct rp_dp_handle *handle)
+{
+    void *ctxs[3];
+    size_t i;
+    size_t j;
+
+    if (handle->callbacks.free_ctx == NULL) {
+        return;
+    }
+    ctxs[0] = handle->callbacks.log_ctx;
+    ctxs[1] = handle->callbacks.state_ctx;
+    ctxs[2] = handle->callbacks.fatal_ctx;
+    for (i = 0; i < 3; i++) {
+        int duplicate = 0;
+        if (ctxs[i] == NULL) {
+            continue;
+        }
+        for (j = 0; j < i; j++) {
+            if (ctxs[j] == ctxs[i]) {
+                duplicate = 1;
+                break;
+            }
+        }
+        if (duplicate != 0) {
+            continue;
+        }
+        struct rp_dp_retired_ctx *node =
+            (struct rp_dp_retired_ctx *)malloc(sizeof(*node));
+        if (node == NULL) {
+            continue;
+        }
+        rp_dp_mem_note_alloc(RP_DP_ALLOC_RETIRED_CTX);
+        node->ctx = ctxs[i];
+        node->free_fn = handle->callbacks.free_ctx;
+        node->next = handle->retired_ctxs;
+        handle->retired_ctxs = node;
+    }
+}
+
+static void rp_dp_free_retired_ctxs(struct rp_dp_handle *handle)
+{
+    while (handle->retired_ctxs != NULL) {
+        struct rp_dp_retired_ctx *node = handle->retired_ctxs;
+        handle->retired_ctxs = node->next;
+        if (node->free_fn != NULL && node->ctx != NULL) {
+            node->free_fn(node->ctx);
+        }
+        free(node);
+        rp_dp_mem_note_free(RP_DP_ALLOC_RETIRED_CTX);
+    }
+}
+
+/* Widens a v1 callback table into v2 form: the shared user_ctx lands in all
+ * three per-callback slots and no destructor is installed, preserving the v1
+ * contract that the caller owns the context. */
+static rp_dp_callbacks_v2_t rp_dp_callbacks_v2_from_v1(
+    const rp_dp_callbacks_t *callbacks, void *user_ctx)
+{
+    rp_dp_callbacks_v2_t bridged;
+
+    memset(&bridged, 0, sizeof(bridged));
+    if (callbacks != NULL) {
+        bridged.on_log = callbacks->on_log;
+        bridged.on_state = callbacks->on_state;
+        bridged.on_fatal = callbacks->on_fatal;
+    }
+    bridged.log_ctx = user_ctx;
+    bridged.state_ctx = user_ctx;
+    bridged.fatal_ctx = user_ctx;
+    return bridged;
+}
+
 rp_dp_handle_t *rp_dp_create(const char *config_json,
                              const rp_dp_callbacks_t *callbacks,
                              void *user_ctx)
+{
+    rp_dp_callbacks_v2_t bridged = rp_dp_callbacks_v2_from_v1(callbacks, user_ctx);
+    return rp_dp_create_v2(config_json, &bridged);
+}
+
+rp_dp_handle_t *rp_dp_create_v2(const char *config_json,
+                                const rp_dp_callbacks_v2_t *callbacks)
 {
     struct rp_dp_handle *handle =
         (struct rp_dp_handle *)calloc(1, sizeof(struct rp_dp_handle));
     if (handle == NULL) {
+        rp_dp_free_generation_ctxs(callbacks);
         return NULL;
     }
+    rp_dp_mem_note_alloc(RP_DP_ALLOC_HANDLE);
 
     if (callbacks != NULL) {
         handle->callbacks = *callbacks;
     } else {
-        memset(&handle->callbacks, 0, sizeof(rp_dp_callbacks_t));
+        memset(&handle->callbacks, 0, sizeof(rp_dp_callbacks_v2_t));
     }
 
     pthread_once(&rp_dp_thread_keys_once, rp_dp_init_thread_keys);
     if (pthread_mutex_init(&handle->startup_lock, NULL) != 0) {
+        rp_dp_free_generation_ctxs(&handle->callbacks);
         free(handle);
+        rp_dp_mem_note_free(RP_DP_ALLOC_HANDLE);
         return NULL;
     }
     if (pthread_cond_init(&handle->startup_cond, NULL) != 0) {
         pthread_mutex_destroy(&handle->startup_lock);
+        rp_dp_free_generation_ctxs(&handle->callbacks);
         free(handle);
+        rp_dp_mem_note_free(RP_DP_ALLOC_HANDLE);
         return NULL;
     }
     if (rp_dp_callback_queue_start(handle) != 0) {
         pthread_cond_destroy(&handle->startup_cond);
         pthread_mutex_destroy(&handle->startup_lock);
+        rp_dp_free_generation_ctxs(&handle->callbacks);
         free(handle);
+        rp_dp_mem_note_free(RP_DP_ALLOC_HANDLE);
         return NULL;
     }
 
-    handle->user_ctx = user_ctx;
     handle->worker_joinable = 0;
     handle->startup_signaled = 0;
     handle->started = 0;
@@ -564,6 +1019,7 @@ rp_dp_handle_t *rp_dp_create(const char *config_json,
         rp_dp_destroy(handle);
         return NULL;
     }
+    rp_dp_mem_note_alloc(RP_DP_ALLOC_CONFIG);
     handle->config_len = strlen(handle->config_json);
 
     rp_dp_dispatch_state(handle, RP_DP_STATE_CREATED);
@@ -575,26 +1031,26 @@ int32_t rp_dp_start(rp_dp_handle_t *opaque_handle, int32_t tun_fd)
 {
     struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
     if (handle == NULL) {
-        return -1;
+        return RP_DP_ERR_INVALID_ARGUMENT;
     }
     if (rp_dp_reentrant_call_guard() != 0) {
-        return -2;
+        return RP_DP_ERR_REENTRANT_CALL;
     }
     if (tun_fd < 0) {
-        return -3;
+        return RP_DP_ERR_BAD_TUN_FD;
     }
     if (handle->config_json == NULL || handle->config_len == 0) {
-        return -4;
+        return RP_DP_ERR_NO_CONFIG;
     }
     pthread_mutex_lock(&rp_dp_global_lock);
     if (handle->started != 0) {
         uint8_t ready = handle->ready;
         pthread_mutex_unlock(&rp_dp_global_lock);
-        return ready != 0 ? 0 : -6;
+        return ready != 0 ? RP_DP_OK : RP_DP_ERR_START_PENDING;
     }
     if (rp_dp_active_handle != NULL && rp_dp_active_handle != handle) {
         pthread_mutex_unlock(&rp_dp_global_lock);
-        return -5;
+        return RP_DP_ERR_HANDLE_BUSY;
     }
     rp_dp_active_handle = handle;
     handle->tun_fd = tun_fd;
@@ -603,13 +1059,28 @@ int32_t rp_dp_start(rp_dp_handle_t *opaque_handle, int32_t tun_fd)
     handle->ready = 0;
     handle->exited = 0;
     handle->exit_code = 0;
+    handle->restart_attempts = 0;
     handle->startup_signaled = 0;
     pthread_mutex_unlock(&rp_dp_global_lock);
 
+    /*
+     * Join a poll thread left over from a previous run. An engine self-exit
+     * never goes through rp_dp_stop, so without this a stop/start or
+     * exit/start sequence would overwrite worker_thread and leak the old
+     * thread's join slot.
+     */
+    rp_dp_wait_worker_if_needed(handle);
+
     if (rp_dp_is_deterministic_local_mode(handle) && tun_fd == 0) {
         pthread_mutex_lock(&rp_dp_global_lock);
         handle->ready = 1;
+        handle->lifecycle_state = RP_DP_STATE_RUNNING;
+        handle->started_at_ms = rp_dp_monotonic_ms();
+        handle->start_count++;
+        handle->stats_epoch++;
+        uint64_t start_count = handle->start_count;
         pthread_mutex_unlock(&rp_dp_global_lock);
+        rp_dp_publish_epoch_marker(handle, start_count);
         rp_dp_dispatch_state(handle, RP_DP_STATE_RUNNING);
         rp_dp_dispatch_log(handle, RP_DP_RUNNING_MSG);
         return 0;
@@ -623,11 +1094,12 @@ int32_t rp_dp_start(rp_dp_handle_t *opaque_handle, int32_t tun_fd)
         pthread_mutex_unlock(&rp_dp_global_lock);
         rp_dp_clear_active_handle_if_current(handle);
         rp_dp_dispatch_log(handle, "dataplane-worker-create-failed");
-        return -8;
+        return RP_DP_ERR_WORKER_SPAWN;
     }
 
     pthread_mutex_lock(&rp_dp_global_lock);
     handle->worker_joinable = 1;
+    handle->worker_launch_count++;
     pthread_mutex_unlock(&rp_dp_global_lock);
 
     int wait_result = rp_dp_wait_startup(handle, 5);
@@ -642,33 +1114,44 @@ int32_t rp_dp_start(rp_dp_handle_t *opaque_handle, int32_t tun_fd)
         handle->stopping = 0;
         handle->ready = 0;
         handle->exited = 1;
-        handle->exit_code = -6;
+        handle->exit_code = RP_DP_ERR_START_PENDING;
+        handle->stop_count++;
+        handle->last_stop_reason = RP_DP_STOP_REASON_START_TIMEOUT;
+        handle->lifecycle_state = RP_DP_STATE_STOPPED;
         pthread_mutex_unlock(&rp_dp_global_lock);
         rp_dp_clear_active_handle_if_current(handle);
         rp_dp_dispatch_log(handle, "dataplane-start-timeout");
-        return -6;
+        return RP_DP_ERR_START_PENDING;
     }
     pthread_mutex_lock(&rp_dp_global_lock);
     uint8_t ready = handle->ready;
     int32_t exit_code = handle->exit_code;
+    uint64_t start_count = 0;
+    if (ready != 0) {
+        handle->started_at_ms = rp_dp_monotonic_ms();
+        handle->start_count++;
+        handle->stats_epoch++;
+        start_count = handle->start_count;
+    }
     pthread_mutex_unlock(&rp_dp_global_lock);
     if (ready != 0) {
+        rp_dp_publish_epoch_marker(handle, start_count);
         rp_dp_dispatch_log(handle, RP_DP_RUNNING_MSG);
         return 0;
     }
 
     rp_dp_clear_active_handle_if_current(handle);
-    return exit_code == 0 ? -7 : exit_code;
+    return exit_code == 0 ? RP_DP_ERR_ENGINE_EXIT : exit_code;
 }
 
 int32_t rp_dp_stop(rp_dp_handle_t *opaque_handle)
 {
     struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
     if (handle == NULL) {
-        return -1;
+        return RP_DP_ERR_INVALID_ARGUMENT;
     }
     if (rp_dp_reentrant_call_guard() != 0) {
-        return -2;
+        return RP_DP_ERR_REENTRANT_CALL;
     }
     pthread_mutex_lock(&rp_dp_global_lock);
     uint8_t started = handle->started;
@@ -682,6 +1165,9 @@ int32_t rp_dp_stop(rp_dp_handle_t *opaque_handle)
         pthread_mutex_lock(&rp_dp_global_lock);
         handle->started = 0;
         handle->ready = 0;
+        handle->stop_count++;
+        handle->last_stop_reason = RP_DP_STOP_REASON_HOST_STOP;
+        handle->lifecycle_state = RP_DP_STATE_STOPPED;
         pthread_mutex_unlock(&rp_dp_global_lock);
         rp_dp_clear_active_handle_if_current(handle);
         rp_dp_dispatch_state(handle, RP_DP_STATE_STOPPED);
@@ -698,6 +1184,9 @@ int32_t rp_dp_stop(rp_dp_handle_t *opaque_handle)
     handle->started = 0;
     handle->stopping = 0;
     handle->ready = 0;
+    handle->stop_count++;
+    handle->last_stop_reason = RP_DP_STOP_REASON_HOST_STOP;
+    handle->lifecycle_state = RP_DP_STATE_STOPPED;
     pthread_mutex_unlock(&rp_dp_global_lock);
     rp_dp_clear_active_handle_if_current(handle);
 
@@ -707,6 +1196,85 @@ int32_t rp_dp_stop(rp_dp_handle_t *opaque_handle)
     return 0;
 }
 
+int32_t rp_dp_get_state(rp_dp_handle_t *opaque_handle)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    uint32_t state;
+
+    if (handle == NULL) {
+        return RP_DP_ERR_INVALID_ARGUMENT;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    state = handle->lifecycle_state;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return (int32_t)state;
+}
+
+int32_t rp_dp_update_callbacks(rp_dp_handle_t *opaque_handle,
+                               const rp_dp_callbacks_t *callbacks,
+                               void *user_ctx)
+{
+    rp_dp_callbacks_v2_t bridged = rp_dp_callbacks_v2_from_v1(callbacks, user_ctx);
+    return rp_dp_update_callbacks_v2(opaque_handle, &bridged);
+}
+
+int32_t rp_dp_update_callbacks_v2(rp_dp_handle_t *opaque_handle,
+                                  const rp_dp_callbacks_v2_t *callbacks)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+
+    if (handle == NULL) {
+        return RP_DP_ERR_INVALID_ARGUMENT;
+    }
+    if (rp_dp_reentrant_call_guard() != 0) {
+        return RP_DP_ERR_REENTRANT_CALL;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    if (handle->started != 0) {
+        pthread_mutex_unlock(&rp_dp_global_lock);
+        return RP_DP_ERR_STILL_RUNNING;
+    }
+    rp_dp_retire_callback_ctxs(handle);
+    if (callbacks != NULL) {
+        handle->callbacks = *callbacks;
+    } else {
+        memset(&handle->callbacks, 0, sizeof(rp_dp_callbacks_v2_t));
+    }
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return RP_DP_OK;
+}
+
+int32_t rp_dp_on_path_changed(rp_dp_handle_t *opaque_handle,
+                              const rp_dp_path_info_t *info)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    if (handle == NULL || info == NULL) {
+        return -1;
+    }
+    if (rp_dp_reentrant_call_guard() != 0) {
+        return -2;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    uint8_t started = handle->started;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    if (started == 0) {
+        return 0;
+    }
+
+    /*
+     * The vendored tunnel exposes no per-flow control surface, so the bridge's
+     * share of the work is publishing the hint: flows revalidate themselves via
+     * the host relay's Network.framework viability and better-path callbacks,
+     * and the host relay clears its dial failure cache on the same signal.
+     */
+    rp_dp_dispatch_logf(handle, "dataplane-path-changed interface=%u flags=%u",
+                        info->interface_kind, info->flags);
+    return 0;
+}
+
 int32_t rp_dp_destroy(rp_dp_handle_t *opaque_handle)
 {
     struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
@@ -734,13 +1302,24 @@ int32_t rp_dp_destroy(rp_dp_handle_t *opaque_handle)
     pthread_cond_destroy(&handle->startup_cond);
     pthread_mutex_destroy(&handle->startup_lock);
 
+    /*
+     * The delivery queue is drained and joined above, so no callback can run
+     * against a context after its destructor fires here.
+     */
+    assert(handle->callback_queue.head == NULL);
+    rp_dp_free_generation_ctxs(&handle->callbacks);
+    rp_dp_free_retired_ctxs(handle);
+    assert(handle->retired_ctxs == NULL);
+
     if (handle->config_json != NULL) {
         free(handle->config_json);
         handle->config_json = NULL;
         handle->config_len = 0;
+        rp_dp_mem_note_free(RP_DP_ALLOC_CONFIG);
     }
 
     free(handle);
+    rp_dp_mem_note_free(RP_DP_ALLOC_HANDLE);
     return 0;
 }
 
@@ -761,6 +1340,261 @@ int32_t rp_dp_get_stats(rp_dp_handle_t *opaque_handle, rp_dp_stats_t *out_stats)
     return 0;
 }
 
+int32_t rp_dp_reset_stats(rp_dp_handle_t *opaque_handle)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    size_t tx_packets = 0;
+    size_t tx_bytes = 0;
+    size_t rx_packets = 0;
+    size_t rx_bytes = 0;
+
+    if (handle == NULL) {
+        return -1;
+    }
+    if (rp_dp_reentrant_call_guard() != 0) {
+        return -2;
+    }
+
+    hev_socks5_tunnel_stats(&tx_packets, &tx_bytes, &rx_packets, &rx_bytes);
+    pthread_mutex_lock(&rp_dp_global_lock);
+    handle->stats_base.packets_in = (uint64_t)rx_packets;
+    handle->stats_base.bytes_in = (uint64_t)rx_bytes;
+    handle->stats_base.packets_out = (uint64_t)tx_packets;
+    handle->stats_base.bytes_out = (uint64_t)tx_bytes;
+    handle->stats.packets_in = 0;
+    handle->stats.bytes_in = 0;
+    handle->stats.packets_out = 0;
+    handle->stats.bytes_out = 0;
+    handle->stats_epoch++;
+    handle->stats.epoch = handle->stats_epoch;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return 0;
+}
+
+int32_t rp_dp_get_lifecycle_info(rp_dp_handle_t *opaque_handle,
+                                 rp_dp_lifecycle_info_t *out_info)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    if (handle == NULL || out_info == NULL) {
+        return -1;
+    }
+    if (rp_dp_reentrant_call_guard() != 0) {
+        return -2;
+    }
+
+    uint64_t now_ms = rp_dp_monotonic_ms();
+    pthread_mutex_lock(&rp_dp_global_lock);
+    out_info->started_at_ms = handle->started_at_ms;
+    out_info->start_count = handle->start_count;
+    out_info->stop_count = handle->stop_count;
+    out_info->worker_restart_count = handle->engine_restart_count +
+        (handle->worker_launch_count > 1u ? handle->worker_launch_count - 1u : 0u);
+    out_info->is_running = (handle->started != 0 && handle->ready != 0) ? 1u : 0u;
+    out_info->last_stop_reason = handle->last_stop_reason;
+    out_info->last_exit_code = handle->exit_code;
+    out_info->uptime_ms = 0;
+    if (out_info->is_running != 0 && handle->started_at_ms != 0 &&
+        now_ms >= handle->started_at_ms) {
+        out_info->uptime_ms = now_ms - handle->started_at_ms;
+    }
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return 0;
+}
+
+/* Appends one metric when the output array still has room; the running count
+ * always advances so the caller learns the full category size either way. */
+static int32_t rp_dp_metric_emit(rp_dp_metric_entry_t *entries, uint32_t capacity,
+                                 int32_t count, const char *name, uint64_t value)
+{
+    if (entries != NULL && count >= 0 && (uint32_t)count < capacity) {
+        snprintf(entries[count].name, RP_DP_METRIC_NAME_CAPACITY, "%s", name);
+        entries[count].value = value;
+    }
+    return count + 1;
+}
+
+int32_t rp_dp_query_metrics(rp_dp_handle_t *opaque_handle,
+                            uint32_t category,
+                            rp_dp_metric_entry_t *out_entries,
+                            uint32_t capacity,
+                            uint32_t *out_schema_version)
+{
+    int32_t count = 0;
+    int32_t result;
+
+    if (opaque_handle == NULL || (out_entries == NULL && capacity != 0u)) {
+        return RP_DP_ERR_INVALID_ARGUMENT;
+    }
+    if (rp_dp_reentrant_call_guard() != 0) {
+        return RP_DP_ERR_REENTRANT_CALL;
+    }
+    if (out_schema_version != NULL) {
+        *out_schema_version = RP_DP_METRICS_SCHEMA_VERSION;
+    }
+
+    switch (category) {
+    case RP_DP_METRICS_CATEGORY_TRAFFIC: {
+        rp_dp_stats_t stats;
+
+        result = rp_dp_get_stats(opaque_handle, &stats);
+        if (result != 0) {
+            return result;
+        }
+        count = rp_dp_metric_emit(out_entries, capacity, count, "packets_in", stats.packets_in);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "packets_out", stats.packets_out);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "bytes_in", stats.bytes_in);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "bytes_out", stats.bytes_out);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "epoch", stats.epoch);
+        return count;
+    }
+    case RP_DP_METRICS_CATEGORY_LIFECYCLE: {
+        rp_dp_lifecycle_info_t info;
+
+        result = rp_dp_get_lifecycle_info(opaque_handle, &info);
+        if (result != 0) {
+            return result;
+        }
+        count = rp_dp_metric_emit(out_entries, capacity, count, "started_at_ms", info.started_at_ms);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "uptime_ms", info.uptime_ms);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "start_count", info.start_count);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "stop_count", info.stop_count);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "worker_restart_count",
+                                  info.worker_restart_count);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "is_running", info.is_running);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "last_stop_reason",
+                                  info.last_stop_reason);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "last_exit_code",
+                                  (uint64_t)(int64_t)info.last_exit_code);
+        return count;
+    }
+    case RP_DP_METRICS_CATEGORY_ALLOCATIONS: {
+        rp_dp_debug_alloc_counters_t counters;
+
+        rp_dp_debug_allocation_counters(&counters);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "live_handles", counters.live_handles);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "live_config_copies",
+                                  counters.live_config_copies);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "live_callback_tasks",
+                                  counters.live_callback_tasks);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "live_retired_contexts",
+                                  counters.live_retired_contexts);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "total_handles", counters.total_handles);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "total_config_copies",
+                                  counters.total_config_copies);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "total_callback_tasks",
+                                  counters.total_callback_tasks);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "total_retired_contexts",
+                                  counters.total_retired_contexts);
+        return count;
+    }
+    case RP_DP_METRICS_CATEGORY_STACK: {
+        size_t rx_drops = 0;
+        size_t rx_errors = 0;
+        size_t unaddressable = 0;
+        size_t memory_failures = 0;
+
+        hev_socks5_tunnel_stack_stats(&rx_drops, &rx_errors, &unaddressable, &memory_failures);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "rx_drops", (uint64_t)rx_drops);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "rx_errors", (uint64_t)rx_errors);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "unaddressable",
+                                  (uint64_t)unaddressable);
+        count = rp_dp_metric_emit(out_entries, capacity, count, "memory_failures",
+                                  (uint64_t)memory_failures);
+        return count;
+    }
+    default:
+        return RP_DP_ERR_INVALID_ARGUMENT;
+    }
+}
+
+int32_t rp_dp_register_event_ring(rp_dp_handle_t *opaque_handle,
+                                  rp_dp_event_record_t *records,
+                                  uint32_t capacity)
+{
+    return rp_dp_register_event_ring_partitioned(opaque_handle, records, capacity, 0);
+}
+
+int32_t rp_dp_register_event_ring_partitioned(rp_dp_handle_t *opaque_handle,
+                                              rp_dp_event_record_t *records,
+                                              uint32_t capacity,
+                                              uint64_t sequence_base)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    if (handle == NULL || records == NULL || capacity == 0) {
+        return -1;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    handle->event_ring.records = records;
+    handle->event_ring.capacity = capacity;
+    handle->event_ring.next_sequence = 0;
+    handle->event_ring.sequence_base = sequence_base;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return 0;
+}
+
+int32_t rp_dp_unregister_event_ring(rp_dp_handle_t *opaque_handle)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    if (handle == NULL) {
+        return -1;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    handle->event_ring.records = NULL;
+    handle->event_ring.capacity = 0;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return 0;
+}
+
+uint64_t rp_dp_event_ring_sequence(rp_dp_handle_t *opaque_handle)
+{
+    struct rp_dp_handle *handle = (struct rp_dp_handle *)opaque_handle;
+    uint64_t sequence;
+
+    if (handle == NULL) {
+        return 0;
+    }
+
+    pthread_mutex_lock(&rp_dp_global_lock);
+    sequence = handle->event_ring.records != NULL && handle->event_ring.next_sequence != 0
+        ? handle->event_ring.sequence_base + handle->event_ring.next_sequence
+        : 0;
+    pthread_mutex_unlock(&rp_dp_global_lock);
+    return sequence;
+}
+
+int32_t rp_dp_event_record_snapshot(const rp_dp_event_record_t *slot,
+                                    rp_dp_event_record_t *out_record)
+{
+    uint64_t before;
+    uint64_t after;
+    int attempt;
+
+    if (slot == NULL || out_record == NULL) {
+        return -1;
+    }
+
+    /* Two retries absorb a publish racing the copy; a slot rewritten that
+     * often is being lapped and its record is stale anyway. */
+    for (attempt = 0; attempt < 3; attempt++) {
+        before = __atomic_load_n(&slot->sequence, __ATOMIC_ACQUIRE);
+        if (before == 0u) {
+            return -1;
+        }
+        memcpy(out_record, slot, sizeof(*out_record));
+        __atomic_thread_fence(__ATOMIC_ACQUIRE);
+        after = __atomic_load_n(&slot->sequence, __ATOMIC_RELAXED);
+        if (before == after) {
+            /* The memcpy read of the sequence field itself was unordered;
+             * report the validated value. */
+            out_record->sequence = before;
+            return 0;
+        }
+    }
+    return -1;
+}
+
 uint32_t lwip_port_tcp_isn(const void *local_ip,
                            uint16_t local_port,
                            const void *remote_ip,
@@ -0,0 +1,76 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+// Compile-time layout guards for every struct that crosses the C ABI.
+// Decision: the header is hand-maintained, so nothing mechanically ties a
+// field edit there to the implementation or to the Swift mirror types. These
+// asserts pin the exact byte layout the shipped header promises; reordering,
+// widening, or inserting a field fails the build here instead of silently
+// shifting every later field for one side of the boundary.
+// Contract: asserts on fixed-width structs pin absolute sizes and offsets;
+// pointer-bearing structs are pinned in units of sizeof(void *) so the guards
+// hold on any pointer width. Growing a struct is an ABI break and must bump
+// RP_DP_ABI_VERSION alongside the numbers below.
+
+#include <assert.h>
+#include <stddef.h>
+
+#include "rp_dataplane.h"
+
+static_assert(sizeof(rp_dp_version_t) == 4, "rp_dp_version_t layout drifted");
+static_assert(offsetof(rp_dp_version_t, abi_version) == 2, "rp_dp_version_t layout drifted");
+
+static_assert(sizeof(rp_dp_stats_t) == 40, "rp_dp_stats_t layout drifted");
+static_assert(offsetof(rp_dp_stats_t, packets_out) == 8, "rp_dp_stats_t layout drifted");
+static_assert(offsetof(rp_dp_stats_t, bytes_in) == 16, "rp_dp_stats_t layout drifted");
+static_assert(offsetof(rp_dp_stats_t, bytes_out) == 24, "rp_dp_stats_t layout drifted");
+static_assert(offsetof(rp_dp_stats_t, epoch) == 32, "rp_dp_stats_t layout drifted");
+
+static_assert(sizeof(rp_dp_lifecycle_info_t) == 56, "rp_dp_lifecycle_info_t layout drifted");
+static_assert(offsetof(rp_dp_lifecycle_info_t, worker_restart_count) == 32,
+              "rp_dp_lifecycle_info_t layout drifted");
+static_assert(offsetof(rp_dp_lifecycle_info_t, is_running) == 40,
+              "rp_dp_lifecycle_info_t layout drifted");
+static_assert(offsetof(rp_dp_lifecycle_info_t, last_stop_reason) == 44,
+              "rp_dp_lifecycle_info_t layout drifted");
+static_assert(offsetof(rp_dp_lifecycle_info_t, last_exit_code) == 48,
+              "rp_dp_lifecycle_info_t layout drifted");
+
+// The event record additionally promises a 128-byte footprint so rings can be
+// placed in shared memory with a stable stride.
+static_assert(sizeof(rp_dp_event_record_t) == 128, "rp_dp_event_record_t layout drifted");
+static_assert(offsetof(rp_dp_event_record_t, timestamp_ms) == 8,
+              "rp_dp_event_record_t layout drifted");
+static_assert(offsetof(rp_dp_event_record_t, kind) == 16, "rp_dp_event_record_t layout drifted");
+static_assert(offsetof(rp_dp_event_record_t, state) == 20, "rp_dp_event_record_t layout drifted");
+static_assert(offsetof(rp_dp_event_record_t, wall_clock_ms) == 24,
+              "rp_dp_event_record_t layout drifted");
+static_assert(offsetof(rp_dp_event_record_t, message) == 32, "rp_dp_event_record_t layout drifted");
+
+static_assert(sizeof(rp_dp_path_info_t) == 8, "rp_dp_path_info_t layout drifted");
+static_assert(offsetof(rp_dp_path_info_t, flags) == 4, "rp_dp_path_info_t layout drifted");
+
+static_assert(sizeof(rp_dp_callbacks_t) == 3 * sizeof(void *),
+              "rp_dp_callbacks_t layout drifted");
+static_assert(offsetof(rp_dp_callbacks_t, on_state) == sizeof(void *),
+              "rp_dp_callbacks_t layout drifted");
+static_assert(offsetof(rp_dp_callbacks_t, on_fatal) == 2 * sizeof(void *),
+              "rp_dp_callbacks_t layout drifted");
+
+static_assert(sizeof(rp_dp_callbacks_v2_t) == 7 * sizeof(void *),
+              "rp_dp_callbacks_v2_t layout drifted");
+static_assert(offsetof(rp_dp_callbacks_v2_t, on_state) == 2 * sizeof(void *),
+              "rp_dp_callbacks_v2_t layout drifted");
+static_assert(offsetof(rp_dp_callbacks_v2_t, on_fatal) == 4 * sizeof(void *),
+              "rp_dp_callbacks_v2_t layout drifted");
+static_assert(offsetof(rp_dp_callbacks_v2_t, free_ctx) == 6 * sizeof(void *),
+              "rp_dp_callbacks_v2_t layout drifted");
+
+static_assert(sizeof(rp_dp_debug_alloc_counters_t) == 64,
+              "rp_dp_debug_alloc_counters_t layout drifted");
+static_assert(offsetof(rp_dp_debug_alloc_counters_t, total_handles) == 32,
+              "rp_dp_debug_alloc_counters_t layout drifted");
+
+static_assert(sizeof(rp_dp_metric_entry_t) == 56, "rp_dp_metric_entry_t layout drifted");
+static_assert(offsetof(rp_dp_metric_entry_t, value) == 48, "rp_dp_metric_entry_t layout drifted");
@@ -17,7 +17,7 @@ public struct DataplaneVersion: Sendable, Equatable {
         self.abiVersion = abiVersion
     }
 
-    public static let current = DataplaneVersion(apiVersion: 1, abiVersion: 1)
+    public static let current = DataplaneVersion(apiVersion: 9, abiVersion: 4)
 }
 
 /// Coarse dataplane lifecycle state surfaced by the C callback contract.
@@ -25,6 +25,8 @@ public enum DataplaneState: UInt32, Sendable {
     case created = 0
     case running = 1
     case stopped = 2
+    /// The poll worker exhausted automatic restarts; the handle stays restartable.
+    case failed = 3
     case unknown = 999
 
     init(raw: UInt32) {
@@ -38,17 +40,204 @@ public struct DataplaneStats: Sendable, Equatable {
     public let packetsOut: UInt64
     public let bytesIn: UInt64
     public let bytesOut: UInt64
+    /// Counter epoch the snapshot belongs to. Bumps on every engine start and
+    /// every `resetStats()`, so collectors comparing two snapshots with different
+    /// epochs know the counters were rebased and must skip that delta.
+    public let epoch: UInt64
 
     /// - Parameters:
     ///   - packetsIn: Number of inbound packets seen by dataplane.
     ///   - packetsOut: Number of outbound packets emitted by dataplane.
     ///   - bytesIn: Number of inbound bytes seen by dataplane.
     ///   - bytesOut: Number of outbound bytes emitted by dataplane.
-    public init(packetsIn: UInt64, packetsOut: UInt64, bytesIn: UInt64, bytesOut: UInt64) {
+    ///   - epoch: Counter epoch the snapshot belongs to.
+    public init(packetsIn: UInt64, packetsOut: UInt64, bytesIn: UInt64, bytesOut: UInt64, epoch: UInt64 = 0) {
         self.packetsIn = packetsIn
         self.packetsOut = packetsOut
         self.bytesIn = bytesIn
         self.bytesOut = bytesOut
+        self.epoch = epoch
+    }
+}
+
+/// Engine lifecycle snapshot for correlating host-visible reconnects ("VPN keeps
+/// reconnecting") with engine-internal starts, stops, and worker restarts.
+public struct DataplaneLifecycleInfo: Sendable, Equatable {
+    /// Why the engine last stopped, mirroring the C bridge constants.
+    public enum StopReason: UInt32, Sendable {
+        case none = 0
+        case hostStop = 1
+        case engineExit = 2
+        case startTimeout = 3
+        case unknown = 999
+
+        init(raw: UInt32) {
+            self = StopReason(rawValue: raw) ?? .unknown
+        }
+    }
+
+    public let startedAtMs: UInt64
+    public let uptimeMs: UInt64
+    public let startCount: UInt64
+    public let stopCount: UInt64
+    public let workerRestartCount: UInt64
+    public let isRunning: Bool
+    public let lastStopReason: StopReason
+    public let lastExitCode: Int32
+
+    /// - Parameters:
+    ///   - startedAtMs: Monotonic milliseconds of the most recent successful start, 0 if never started.
+    ///   - uptimeMs: Milliseconds since the last start while running, 0 otherwise.
+    ///   - startCount: Total successful engine starts over the handle's lifetime.
+    ///   - stopCount: Total engine stops, whether host-requested or engine-initiated.
+    ///   - workerRestartCount: Poll-thread launches beyond the first.
+    ///   - isRunning: Whether the engine is currently started and ready.
+    ///   - lastStopReason: Why the engine last stopped.
+    ///   - lastExitCode: Exit code from the most recent worker exit.
+    public init(
+        startedAtMs: UInt64,
+        uptimeMs: UInt64,
+        startCount: UInt64,
+        stopCount: UInt64,
+        workerRestartCount: UInt64,
+        isRunning: Bool,
+        lastStopReason: StopReason,
+        lastExitCode: Int32
+    ) {
+        self.startedAtMs = startedAtMs
+        self.uptimeMs = uptimeMs
+        self.startCount = startCount
+        self.stopCount = stopCount
+        self.workerRestartCount = workerRestartCount
+        self.isRunning = isRunning
+        self.lastStopReason = lastStopReason
+        self.lastExitCode = lastExitCode
+    }
+}
+
+/// Per-subsystem snapshot of bridge-owned heap allocations: live counts for leak
+/// audits plus cumulative totals for catching hot-path allocation regressions
+/// that throughput benchmarks miss. Counters are process-global across handles.
+public struct DataplaneAllocationCounters: Sendable, Equatable {
+    public let liveHandles: UInt64
+    public let liveConfigCopies: UInt64
+    public let liveCallbackTasks: UInt64
+    public let liveRetiredContexts: UInt64
+    public let totalHandles: UInt64
+    public let totalConfigCopies: UInt64
+    public let totalCallbackTasks: UInt64
+    public let totalRetiredContexts: UInt64
+
+    /// - Parameters:
+    ///   - liveHandles: Dataplane handles currently allocated.
+    ///   - liveConfigCopies: Config JSON copies currently allocated.
+    ///   - liveCallbackTasks: Callback tasks queued but not yet delivered and freed.
+    ///   - liveRetiredContexts: Superseded callback contexts awaiting destroy.
+    ///   - totalHandles: Handles ever allocated.
+    ///   - totalConfigCopies: Config copies ever allocated.
+    ///   - totalCallbackTasks: Callback tasks ever allocated.
+    ///   - totalRetiredContexts: Callback contexts ever retired.
+    public init(
+        liveHandles: UInt64,
+        liveConfigCopies: UInt64,
+        liveCallbackTasks: UInt64,
+        liveRetiredContexts: UInt64,
+        totalHandles: UInt64,
+        totalConfigCopies: UInt64,
+        totalCallbackTasks: UInt64,
+        totalRetiredContexts: UInt64
+    ) {
+        self.liveHandles = liveHandles
+        self.liveConfigCopies = liveConfigCopies
+        self.liveCallbackTasks = liveCallbackTasks
+        self.liveRetiredContexts = liveRetiredContexts
+        self.totalHandles = totalHandles
+        self.totalConfigCopies = totalConfigCopies
+        self.totalCallbackTasks = totalCallbackTasks
+        self.totalRetiredContexts = totalRetiredContexts
+    }
+
+    /// Total live bridge-owned allocations across all subsystems.
+    public var totalLive: UInt64 {
+        liveHandles + liveConfigCopies + liveCallbackTasks + liveRetiredContexts
+    }
+}
+
+/// Metric category selectable through the generic metrics query, mirroring the
+/// C bridge constants.
+public enum DataplaneMetricsCategory: UInt32, Sendable, CaseIterable {
+    /// Packet/byte counters and the stats epoch.
+    case traffic = 1
+    /// Engine lifecycle counters: uptime, starts, stops, worker restarts.
+    case lifecycle = 2
+    /// Bridge-owned allocation counters, process-global across handles.
+    case allocations = 3
+    /// Network-stack drop and error counters: rx drops, header/checksum errors,
+    /// unroutable packets, memory exhaustion. Process-global and cumulative,
+    /// unlike the epoch-rebased traffic counters.
+    case stack = 4
+}
+
+/// One named counter from the generic metrics query.
+/// Contract: hosts key on `name`, never on array position — the bridge is free
+/// to reorder and append within a schema version.
+public struct DataplaneMetric: Sendable, Equatable {
+    public let name: String
+    public let value: UInt64
+
+    /// - Parameters:
+    ///   - name: Stable ASCII metric identifier, for example `packets_in`.
+    ///   - value: Current counter value.
+    public init(name: String, value: UInt64) {
+        self.name = name
+        self.value = value
+    }
+}
+
+/// Result of one generic metrics query: the category's current name/value pairs
+/// plus the schema version they were produced under.
+public struct DataplaneMetricsSnapshot: Sendable, Equatable {
+    /// Schema version the names belong to. Bumps only when an existing name
+    /// changes meaning or disappears; new metrics appear without a bump.
+    public let schemaVersion: UInt32
+    public let metrics: [DataplaneMetric]
+
+    /// - Parameters:
+    ///   - schemaVersion: Schema version the metric names belong to.
+    ///   - metrics: Name/value pairs in bridge-reported order.
+    public init(schemaVersion: UInt32, metrics: [DataplaneMetric]) {
+        self.schemaVersion = schemaVersion
+        self.metrics = metrics
+    }
+
+    /// Value of the named metric, or `nil` when the bridge does not report it.
+    public subscript(name: String) -> UInt64? {
+        metrics.first { $0.name == name }?.value
+    }
+}
+
+/// Active-path snapshot forwarded to the engine when the device's network path changes.
+public struct DataplanePathInfo: Sendable, Equatable {
+    /// Primary interface carrying the path, mirroring the C bridge constants.
+    public enum InterfaceKind: UInt32, Sendable {
+        case other = 0
+        case wifi = 1
+        case cellular = 2
+        case wired = 3
+    }
+
+    public let interfaceKind: InterfaceKind
+    public let isExpensive: Bool
+    public let isConstrained: Bool
+
+    /// - Parameters:
+    ///   - interfaceKind: Primary interface carrying the new path.
+    ///   - isExpensive: Whether the system marks the path expensive (typically cellular).
+    ///   - isConstrained: Whether the path is under Low Data Mode constraints.
+    public init(interfaceKind: InterfaceKind, isExpensive: Bool = false, isConstrained: Bool = false) {
+        self.interfaceKind = interfaceKind
+        self.isExpensive = isExpensive
+        self.isConstrained = isConstrained
     }
 }
 
@@ -56,27 +245,78 @@ public struct DataplaneStats: Sendable, Equatable {
 public struct DataplaneCallbacks: Sendable {
     public let onLog: @Sendable (String) -> Void
     public let onState: @Sendable (DataplaneState) -> Void
+    public let onFatalError: @Sendable (Int32) -> Void
 
     /// - Parameters:
     ///   - onLog: Callback for dataplane log lines.
     ///   - onState: Callback for dataplane lifecycle state transitions.
+    ///   - onFatalError: Callback fired when the poll task dies and automatic
+    ///     restarts are exhausted; receives the engine exit code.
     public init(
         onLog: @escaping @Sendable (String) -> Void,
-        onState: @escaping @Sendable (DataplaneState) -> Void
+        onState: @escaping @Sendable (DataplaneState) -> Void,
+        onFatalError: @escaping @Sendable (Int32) -> Void = { _ in }
     ) {
         self.onLog = onLog
         self.onState = onState
+        self.onFatalError = onFatalError
     }
 
     public static let noop = DataplaneCallbacks(onLog: { _ in }, onState: { _ in })
 }
 
+/// Protocol form of the dataplane callback hooks for hosts that prefer conforming a type
+/// over wiring closure structs. Every requirement has a no-op default so adopters implement
+/// only the hooks they need.
+/// Contract: methods are invoked on the C bridge callback queue; hop to your own executor
+/// before doing anything heavier than an enqueue.
+public protocol DataplaneObserver: Sendable {
+    /// Receives one dataplane log line.
+    func dataplaneDidLog(_ message: String)
+    /// Receives lifecycle state transitions.
+    func dataplaneStateDidChange(to state: DataplaneState)
+    /// Fired when the poll task dies and automatic restarts are exhausted; receives the
+    /// engine exit code.
+    func dataplaneDidFail(exitCode: Int32)
+}
+
+extension DataplaneObserver {
+    public func dataplaneDidLog(_ message: String) {}
+
+    public func dataplaneStateDidChange(to state: DataplaneState) {}
+
+    public func dataplaneDidFail(exitCode: Int32) {}
+}
+
+extension DataplaneCallbacks {
+    /// Bridges an observer into the closure contract used by the C bridge.
+    /// The resulting callbacks hold the observer strongly for the handle's lifetime.
+    /// - Parameter observer: Observer receiving the bridged callback hooks.
+    public init(observer: some DataplaneObserver) {
+        self.init(
+            onLog: { observer.dataplaneDidLog($0) },
+            onState: { observer.dataplaneStateDidChange(to: $0) },
+            onFatalError: { observer.dataplaneDidFail(exitCode: $0) }
+        )
+    }
+}
+
 /// Errors surfaced by the Swift dataplane bridge.
 public enum DataplaneError: Error, Sendable, Equatable {
     case versionMismatch(expected: DataplaneVersion, actual: DataplaneVersion)
+    /// The runtime ABI handshake rejected the struct sizes or header version this
+    /// module was compiled against; the loaded bridge binary is incompatible.
+    case abiVerificationFailed(code: Int32)
     case createFailed
     case startFailed(code: Int32)
     case stopFailed(code: Int32)
     case statsFailed(code: Int32)
+    case resetStatsFailed(code: Int32)
+    case lifecycleInfoFailed(code: Int32)
+    case metricsQueryFailed(code: Int32)
+    case pathChangeFailed(code: Int32)
+    case eventRingFailed(code: Int32)
+    case stateFailed(code: Int32)
+    case updateCallbacksFailed(code: Int32)
     case destroyed
 }
@@ -0,0 +1,118 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import DataplaneFFICBridge
+import Foundation
+
+/// Kind discriminator for records published into the dataplane event ring.
+public enum DataplaneEventKind: UInt32, Sendable, Equatable {
+    case log = 1
+    case state = 2
+    /// Marks the start of a new engine epoch; the record's timestamp pair
+    /// anchors the monotonic clock to wall time for everything that follows.
+    case epoch = 3
+    case unknown = 999
+
+    init(raw: UInt32) {
+        self = DataplaneEventKind(rawValue: raw) ?? .unknown
+    }
+}
+
+/// One fixed-size telemetry record copied out of the dataplane event ring.
+public struct DataplaneEvent: Sendable, Equatable {
+    public let sequence: UInt64
+    public let timestampMilliseconds: UInt64
+    /// Wall-clock publish timestamp (milliseconds since the Unix epoch). May
+    /// jump on NTP or user clock changes; order within an epoch by
+    /// `timestampMilliseconds` and use this only to map epochs onto real time.
+    public let wallClockMilliseconds: UInt64
+    public let kind: DataplaneEventKind
+    public let state: DataplaneState
+    public let message: String
+
+    /// - Parameters:
+    ///   - sequence: Monotonic per-handle publish sequence, starting one past the
+    ///     registration's sequence base (1 when unpartitioned).
+    ///   - timestampMilliseconds: Monotonic-clock publish timestamp; never jumps
+    ///     but does not advance while the device sleeps.
+    ///   - wallClockMilliseconds: Wall-clock publish timestamp in milliseconds
+    ///     since the Unix epoch.
+    ///   - kind: Record kind discriminator.
+    ///   - state: Lifecycle state carried by state records. Epoch markers reuse
+    ///     the C record's state field for the engine's start count, so read that
+    ///     value from `message` instead of this decoded enum.
+    ///   - message: Inline message text; empty for state records.
+    public init(
+        sequence: UInt64,
+        timestampMilliseconds: UInt64,
+        wallClockMilliseconds: UInt64 = 0,
+        kind: DataplaneEventKind,
+        state: DataplaneState,
+        message: String
+    ) {
+        self.sequence = sequence
+        self.timestampMilliseconds = timestampMilliseconds
+        self.wallClockMilliseconds = wallClockMilliseconds
+        self.kind = kind
+        self.state = state
+        self.message = message
+    }
+}
+
+/// Host-owned fixed-size record ring shared with the C dataplane bridge.
+/// Ownership: the ring owns its record storage for its whole lifetime and must
+/// outlive registration; `DataplaneHandle.registerEventRing` retains the ring
+/// until unregistration or handle destruction so the bridge never observes a
+/// dangling buffer.
+public final class DataplaneEventRing: @unchecked Sendable {
+    public static let defaultCapacity: UInt32 = 256
+
+    let records: UnsafeMutablePointer<rp_dp_event_record_t>
+    public let capacity: UInt32
+
+    /// - Parameter capacity: Number of record slots, clamped to at least 1.
+    public init(capacity: UInt32 = DataplaneEventRing.defaultCapacity) {
+        self.capacity = max(1, capacity)
+        self.records = UnsafeMutablePointer<rp_dp_event_record_t>.allocate(
+            capacity: Int(self.capacity)
+        )
+        self.records.initialize(repeating: rp_dp_event_record_t(), count: Int(self.capacity))
+    }
+
+    deinit {
+        records.deinitialize(count: Int(capacity))
+        records.deallocate()
+    }
+
+    /// Copies all currently published records, dropping slots caught
+    /// mid-overwrite. Each slot is copied through the bridge's seqlock reader
+    /// so the sequence check pairs with the publisher's release ordering
+    /// instead of relying on racy plain loads.
+    /// - Returns: Published records ordered by ascending sequence.
+    public func snapshot() -> [DataplaneEvent] {
+        var events: [DataplaneEvent] = []
+        events.reserveCapacity(Int(capacity))
+        for index in 0..<Int(capacity) {
+            var record = rp_dp_event_record_t()
+            guard rp_dp_event_record_snapshot(records + index, &record) == 0 else {
+                continue
+            }
+            let message = withUnsafeBytes(of: &record.message) { rawBuffer -> String in
+                let bytes = rawBuffer.prefix { $0 != 0 }
+                return String(decoding: bytes, as: UTF8.self)
+            }
+            events.append(
+                DataplaneEvent(
+                    sequence: record.sequence,
+                    timestampMilliseconds: record.timestamp_ms,
+                    wallClockMilliseconds: record.wall_clock_ms,
+                    kind: DataplaneEventKind(raw: record.kind),
+                    state: DataplaneState(raw: record.state),
+                    message: message
+                )
+            )
+        }
+        return events.sorted { $0.sequence < $1.sequence }
+    }
+}
@@ -16,18 +16,17 @@ private final class CallbackBox {
 
 private final class ManagedHandle: @unchecked Sendable {
     let rawHandle: OpaquePointer
-    private let callbackToken: Unmanaged<CallbackBox>
 
-    init(rawHandle: OpaquePointer, callbackToken: Unmanaged<CallbackBox>) {
+    init(rawHandle: OpaquePointer) {
         self.rawHandle = rawHandle
-        self.callbackToken = callbackToken
     }
 
     deinit {
-        let result = rp_dp_destroy(rawHandle)
-        if result == 0 {
-            callbackToken.release()
-        }
+        /// Callback box lifetimes ride on the v2 table's destructor: the bridge
+        /// releases every installed and superseded context after draining its
+        /// delivery queue, including on the asynchronous destroy path, so no
+        /// Swift-side token bookkeeping is needed here.
+        _ = rp_dp_destroy(rawHandle)
     }
 }
 
@@ -48,20 +47,93 @@ private func bridgeStateCallback(state: UInt32, userCtx: UnsafeMutableRawPointer
     box.callbacks.onState(DataplaneState(raw: state))
 }
 
+private func bridgeFatalCallback(exitCode: Int32, userCtx: UnsafeMutableRawPointer?) {
+    guard let userCtx else {
+        return
+    }
+    let box = Unmanaged<CallbackBox>.fromOpaque(userCtx).takeUnretainedValue()
+    box.callbacks.onFatalError(exitCode)
+}
+
+private func bridgeContextRelease(userCtx: UnsafeMutableRawPointer?) {
+    guard let userCtx else {
+        return
+    }
+    Unmanaged<CallbackBox>.fromOpaque(userCtx).release()
+}
+
+/// Builds the v2 callback table for one retained callback box. The box serves
+/// all three callbacks, and `free_ctx` balances the retain when the bridge
+/// frees the handle (or when create fails, per the v2 ownership contract).
+private func makeBridgeCallbacks(token: Unmanaged<CallbackBox>) -> rp_dp_callbacks_v2_t {
+    rp_dp_callbacks_v2_t(
+        on_log: bridgeLogCallback,
+        log_ctx: token.toOpaque(),
+        on_state: bridgeStateCallback,
+        state_ctx: token.toOpaque(),
+        on_fatal: bridgeFatalCallback,
+        fatal_ctx: token.toOpaque(),
+        free_ctx: bridgeContextRelease
+    )
+}
+
 /// Actor wrapper around the C dataplane handle lifecycle and version guard.
 public actor DataplaneHandle {
     private var managedHandle: ManagedHandle?
+    private var eventRing: DataplaneEventRing?
     private let logger: StructuredLogger
+    private let traceRecorder: DataplaneTraceRecorder?
 
     /// Validates runtime dataplane API/ABI compatibility before creating a handle.
+    /// Beyond the version pair, this runs the bridge's ABI handshake with the
+    /// struct sizes this module was compiled against, so a divergent header is
+    /// refused here instead of corrupting fields at the first crossing call.
     /// - Parameter expected: Expected API/ABI contract version.
-    /// - Throws: `DataplaneError.versionMismatch` when runtime versions do not match.
+    /// - Throws: `DataplaneError.versionMismatch` when runtime versions do not
+    ///   match, `DataplaneError.abiVerificationFailed` when the handshake rejects
+    ///   the compiled struct layouts.
     public static func validateCompatibility(expected: DataplaneVersion = .current) throws {
         let version = rp_dp_get_version()
         let actual = DataplaneVersion(apiVersion: version.api_version, abiVersion: version.abi_version)
         guard actual == expected else {
             throw DataplaneError.versionMismatch(expected: expected, actual: actual)
         }
+        let status = rp_dp_verify_abi(
+            UInt32(MemoryLayout<rp_dp_callbacks_v2_t>.size),
+            UInt32(MemoryLayout<rp_dp_event_record_t>.size),
+            UInt32(MemoryLayout<rp_dp_metric_entry_t>.size),
+            rp_dp_version_t(api_version: expected.apiVersion, abi_version: expected.abiVersion)
+        )
+        guard status == 0 else {
+            throw DataplaneError.abiVerificationFailed(code: status)
+        }
+    }
+
+    /// Reads the bridge's count of live bridge-owned heap allocations across all
+    /// handles. Diagnostic surface for teardown audits: with no live handles and a
+    /// drained delivery queue the count is 0, so tests can assert create/destroy
+    /// cycles return to baseline without sampling process RSS.
+    public static func debugLiveAllocations() -> UInt64 {
+        rp_dp_debug_live_allocations()
+    }
+
+    /// Reads the bridge's per-subsystem allocation counters: live counts for leak
+    /// audits plus cumulative totals so tests can pin a hot path's allocation
+    /// behavior (for example, ring publishes allocating nothing) instead of only
+    /// its throughput.
+    public static func debugAllocationCounters() -> DataplaneAllocationCounters {
+        var native = rp_dp_debug_alloc_counters_t()
+        rp_dp_debug_allocation_counters(&native)
+        return DataplaneAllocationCounters(
+            liveHandles: native.live_handles,
+            liveConfigCopies: native.live_config_copies,
+            liveCallbackTasks: native.live_callback_tasks,
+            liveRetiredContexts: native.live_retired_contexts,
+            totalHandles: native.total_handles,
+            totalConfigCopies: native.total_config_copies,
+            totalCallbackTasks: native.total_callback_tasks,
+            totalRetiredContexts: native.total_retired_contexts
+        )
     }
 
     /// Creates a dataplane handle and installs Swift callback bridges.
@@ -70,34 +142,61 @@ public actor DataplaneHandle {
     ///   - callbacks: Swift callback hooks invoked from dataplane callback queue.
     ///   - expectedVersion: Expected API/ABI version contract.
     ///   - logger: Structured logger used for lifecycle failures.
+    ///   - traceRecorder: Optional recorder capturing every FFI call this handle
+    ///     issues, for later deterministic replay.
     /// - Throws: Version mismatch or create failure errors.
     public init(
         configJSON: String,
         callbacks: DataplaneCallbacks = .noop,
         expectedVersion: DataplaneVersion = .current,
-        logger: StructuredLogger
+        logger: StructuredLogger,
+        traceRecorder: DataplaneTraceRecorder? = nil
     ) throws {
         try Self.validateCompatibility(expected: expectedVersion)
 
         self.logger = logger
+        self.traceRecorder = traceRecorder
+        traceRecorder?.record(.create, configJSON: configJSON)
         let callbackBox = CallbackBox(callbacks: callbacks)
-        let callbackToken = Unmanaged.passRetained(callbackBox)
-
-        var bridgeCallbacks = rp_dp_callbacks_t(
-            on_log: bridgeLogCallback,
-            on_state: bridgeStateCallback
-        )
+        var bridgeCallbacks = makeBridgeCallbacks(token: Unmanaged.passRetained(callbackBox))
 
         let handle = configJSON.withCString { rawCString in
-            rp_dp_create(rawCString, &bridgeCallbacks, callbackToken.toOpaque())
+            rp_dp_create_v2(rawCString, &bridgeCallbacks)
         }
 
         guard let handle else {
-            callbackToken.release()
+            // On failure the bridge already invoked free_ctx on the box.
             throw DataplaneError.createFailed
         }
 
-        self.managedHandle = ManagedHandle(rawHandle: handle, callbackToken: callbackToken)
+        self.managedHandle = ManagedHandle(rawHandle: handle)
+    }
+
+    /// Creates a dataplane handle with a protocol-based observer instead of closure callbacks,
+    /// so hosts never touch the C callback plumbing directly.
+    /// - Parameters:
+    ///   - configJSON: Dataplane configuration payload forwarded to the C bridge.
+    ///   - observer: Observer invoked from the dataplane callback queue; retained for the
+    ///     handle's lifetime.
+    ///   - expectedVersion: Expected API/ABI version contract.
+    ///   - logger: Structured logger used for lifecycle failures.
+    ///   - traceRecorder: Optional recorder capturing every FFI call this handle
+    ///     issues, for later deterministic replay.
+    /// - Throws: Version mismatch or create failure errors.
+    public init(
+        configJSON: String,
+        observer: some DataplaneObserver,
+        expectedVersion: DataplaneVersion = .current,
+        logger: StructuredLogger,
+        traceRecorder: DataplaneTraceRecorder? = nil
+    ) throws {
+        try self.init(
+            configJSON: configJSON,
+            callbacks: DataplaneCallbacks(observer: observer),
+            expectedVersion: expectedVersion,
+            logger: logger,
+            traceRecorder: traceRecorder
+        )
     }
 
     /// Starts dataplane processing against the provided tunnel file descriptor.
@@ -107,6 +206,7 @@ public actor DataplaneHandle {
         guard let managedHandle else {
             throw DataplaneError.destroyed
         }
+        traceRecorder?.record(.start, tunFD: tunFD)
         let result = rp_dp_start(managedHandle.rawHandle, tunFD)
         guard result == 0 else {
             await logger.log(
@@ -128,6 +228,7 @@ public actor DataplaneHandle {
         guard let managedHandle else {
             throw DataplaneError.destroyed
         }
+        traceRecorder?.record(.stop)
         let result = rp_dp_stop(managedHandle.rawHandle)
         guard result == 0 else {
             await logger.log(
@@ -143,6 +244,94 @@ public actor DataplaneHandle {
         }
     }
 
+    /// Reads the handle's current lifecycle state.
+    /// States form an explicit machine: created -> running -> stopped (or failed when
+    /// the poll worker exhausted automatic restarts); stopped and failed handles can
+    /// be started again.
+    /// - Returns: Current lifecycle state.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.stateFailed`.
+    public func state() throws -> DataplaneState {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        let result = rp_dp_get_state(managedHandle.rawHandle)
+        guard result >= 0 else {
+            throw DataplaneError.stateFailed(code: result)
+        }
+        return DataplaneState(raw: UInt32(result))
+    }
+
+    /// Replaces the Swift callback hooks used for subsequent dispatches, so a stopped
+    /// or failed handle can be restarted with fresh callbacks.
+    /// Contract: only legal while the engine is not running; callbacks already queued
+    /// on the bridge's delivery thread may still fire with the previous hooks.
+    /// - Parameter callbacks: Replacement callback hooks.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.updateCallbacksFailed`.
+    public func updateCallbacks(_ callbacks: DataplaneCallbacks) async throws {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        let callbackBox = CallbackBox(callbacks: callbacks)
+        let callbackToken = Unmanaged.passRetained(callbackBox)
+        var bridgeCallbacks = makeBridgeCallbacks(token: callbackToken)
+        let result = rp_dp_update_callbacks_v2(managedHandle.rawHandle, &bridgeCallbacks)
+        guard result == 0 else {
+            // On refusal the caller keeps ownership, so balance the retain here.
+            callbackToken.release()
+            await logger.log(
+                level: .error,
+                phase: .relay,
+                category: .dataplane,
+                component: "DataplaneHandle",
+                event: "update-callbacks-failed",
+                errorCode: String(result),
+                message: "Refused to replace dataplane callbacks"
+            )
+            throw DataplaneError.updateCallbacksFailed(code: result)
+        }
+        // The bridge now owns the box; the superseded box is retired and freed
+        // at destroy, after the delivery queue drains.
+    }
+
+    /// Replaces the callback hooks with a protocol-based observer; see `updateCallbacks(_:)`.
+    /// - Parameter observer: Observer receiving subsequent callback dispatches.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.updateCallbacksFailed`.
+    public func updateObserver(_ observer: some DataplaneObserver) async throws {
+        try await updateCallbacks(DataplaneCallbacks(observer: observer))
+    }
+
+    /// Notifies the engine that the device's network path changed (Wi-Fi/cellular handover)
+    /// so established flows are revalidated and path-specific negative dial state is dropped.
+    /// - Parameter info: Snapshot of the new active path.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.pathChangeFailed`.
+    public func notifyPathChanged(_ info: DataplanePathInfo) async throws {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        var flags: UInt32 = 0
+        if info.isExpensive {
+            flags |= UInt32(RP_DP_PATH_FLAG_EXPENSIVE)
+        }
+        if info.isConstrained {
+            flags |= UInt32(RP_DP_PATH_FLAG_CONSTRAINED)
+        }
+        traceRecorder?.record(.pathChanged, interfaceKind: info.interfaceKind.rawValue, pathFlags: flags)
+        var native = rp_dp_path_info_t(interface_kind: info.interfaceKind.rawValue, flags: flags)
+        let result = rp_dp_on_path_changed(managedHandle.rawHandle, &native)
+        guard result == 0 else {
+            await logger.log(
+                level: .error,
+                phase: .relay,
+                category: .dataplane,
+                component: "DataplaneHandle",
+                event: "path-change-failed",
+                errorCode: String(result),
+                message: "Failed to notify dataplane of a network path change"
+            )
+            throw DataplaneError.pathChangeFailed(code: result)
+        }
+    }
+
     /// Reads dataplane packet/byte counters.
     /// - Returns: Current dataplane statistics snapshot.
     /// - Throws: `DataplaneError.destroyed` or `DataplaneError.statsFailed`.
@@ -159,15 +348,154 @@ public actor DataplaneHandle {
             packetsIn: native.packets_in,
             packetsOut: native.packets_out,
             bytesIn: native.bytes_in,
-            bytesOut: native.bytes_out
+            bytesOut: native.bytes_out,
+            epoch: native.epoch
+        )
+    }
+
+    /// Zeroes the packet/byte counters and opens a new stats epoch, so periodic
+    /// collectors can rebase their deltas at a well-defined point.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.resetStatsFailed`.
+    public func resetStats() throws {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        traceRecorder?.record(.resetStats)
+        let result = rp_dp_reset_stats(managedHandle.rawHandle)
+        guard result == 0 else {
+            throw DataplaneError.resetStatsFailed(code: result)
+        }
+    }
+
+    /// Reads engine lifecycle statistics: uptime, start/stop totals, worker restarts,
+    /// and the reason the engine last stopped.
+    /// - Returns: Current lifecycle snapshot.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.lifecycleInfoFailed`.
+    public func lifecycleInfo() throws -> DataplaneLifecycleInfo {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        var native = rp_dp_lifecycle_info_t()
+        let result = rp_dp_get_lifecycle_info(managedHandle.rawHandle, &native)
+        guard result == 0 else {
+            throw DataplaneError.lifecycleInfoFailed(code: result)
+        }
+        return DataplaneLifecycleInfo(
+            startedAtMs: native.started_at_ms,
+            uptimeMs: native.uptime_ms,
+            startCount: native.start_count,
+            stopCount: native.stop_count,
+            workerRestartCount: native.worker_restart_count,
+            isRunning: native.is_running != 0,
+            lastStopReason: DataplaneLifecycleInfo.StopReason(raw: native.last_stop_reason),
+            lastExitCode: native.last_exit_code
+        )
+    }
+
+    /// Queries one metric category as name/value pairs through the generic metrics
+    /// surface, so hosts pick up newly shipped metrics without a bridge type change.
+    /// - Parameter category: Metric category to read.
+    /// - Returns: The category's current metrics plus their schema version.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.metricsQueryFailed`.
+    public func queryMetrics(category: DataplaneMetricsCategory) throws -> DataplaneMetricsSnapshot {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        var schemaVersion: UInt32 = 0
+        let available = rp_dp_query_metrics(managedHandle.rawHandle, category.rawValue, nil, 0, &schemaVersion)
+        guard available >= 0 else {
+            throw DataplaneError.metricsQueryFailed(code: available)
+        }
+        guard available > 0 else {
+            return DataplaneMetricsSnapshot(schemaVersion: schemaVersion, metrics: [])
+        }
+        var native = [rp_dp_metric_entry_t](repeating: rp_dp_metric_entry_t(), count: Int(available))
+        let copied = rp_dp_query_metrics(
+            managedHandle.rawHandle,
+            category.rawValue,
+            &native,
+            UInt32(native.count),
+            &schemaVersion
+        )
+        guard copied >= 0 else {
+            throw DataplaneError.metricsQueryFailed(code: copied)
+        }
+        let metrics = native.prefix(min(Int(copied), native.count)).map { entry in
+            DataplaneMetric(name: Self.metricName(entry.name), value: entry.value)
+        }
+        return DataplaneMetricsSnapshot(schemaVersion: schemaVersion, metrics: Array(metrics))
+    }
+
+    /// Decodes the bridge's fixed-size metric name buffer up to its NUL terminator.
+    private static func metricName<Buffer>(_ buffer: Buffer) -> String {
+        withUnsafeBytes(of: buffer) { raw -> String in
+            let bytes = raw.bindMemory(to: UInt8.self)
+            let length = bytes.firstIndex(of: 0) ?? bytes.count
+            return String(decoding: bytes[..<length], as: UTF8.self)
+        }
+    }
+
+    /// Registers a host-owned event ring the bridge publishes telemetry records into.
+    /// The handle retains the ring until it is unregistered or the handle is destroyed.
+    /// - Parameters:
+    ///   - ring: Ring whose record storage the bridge writes into.
+    ///   - sequenceBase: Offset added to every published sequence number, partitioning the
+    ///     sequence space between engine instances. Hosts running several engines hand each
+    ///     a disjoint base (for example instance N passes `N << 48`) so records merged into
+    ///     one map never carry colliding sequences; the default keeps the historical
+    ///     1-based numbering.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.eventRingFailed`.
+    public func registerEventRing(_ ring: DataplaneEventRing, sequenceBase: UInt64 = 0) throws {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        traceRecorder?.record(.registerEventRing, ringCapacity: ring.capacity, sequenceBase: sequenceBase)
+        let result = rp_dp_register_event_ring_partitioned(
+            managedHandle.rawHandle,
+            ring.records,
+            ring.capacity,
+            sequenceBase
         )
+        guard result == 0 else {
+            throw DataplaneError.eventRingFailed(code: result)
+        }
+        eventRing = ring
+    }
+
+    /// Unregisters the current event ring, if any, and releases the handle's retention of it.
+    /// - Throws: `DataplaneError.destroyed` or `DataplaneError.eventRingFailed`.
+    public func unregisterEventRing() throws {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        traceRecorder?.record(.unregisterEventRing)
+        let result = rp_dp_unregister_event_ring(managedHandle.rawHandle)
+        guard result == 0 else {
+            throw DataplaneError.eventRingFailed(code: result)
+        }
+        eventRing = nil
+    }
+
+    /// Reads the sequence number of the most recently published ring record.
+    /// - Returns: Latest publish sequence, or 0 when no ring is registered.
+    /// - Throws: `DataplaneError.destroyed`.
+    public func eventRingSequence() throws -> UInt64 {
+        guard let managedHandle else {
+            throw DataplaneError.destroyed
+        }
+        return rp_dp_event_ring_sequence(managedHandle.rawHandle)
     }
 
     /// Idempotently destroys the underlying native dataplane handle.
     public func destroy() {
+        if let managedHandle {
+            _ = rp_dp_unregister_event_ring(managedHandle.rawHandle)
+        }
+        eventRing = nil
         guard managedHandle != nil else {
             return
         }
+        traceRecorder?.record(.destroy)
         managedHandle = nil
     }
 }
@@ -0,0 +1,206 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Observability
+
+/// One recorded call crossing the dataplane FFI boundary.
+/// Flat optional-field shape (rather than an associated-value enum) so entries
+/// stay line-decodable JSON and unknown future fields degrade gracefully.
+public struct DataplaneTraceEntry: Codable, Sendable, Equatable {
+    public enum Kind: String, Codable, Sendable {
+        case create
+        case start
+        case stop
+        case pathChanged
+        case resetStats
+        case registerEventRing
+        case unregisterEventRing
+        case destroy
+    }
+
+    /// Milliseconds since the recorder was created, so a replayer can
+    /// optionally reproduce the original call spacing.
+    public let timestampMs: UInt64
+    public let kind: Kind
+    public let configJSON: String?
+    public let tunFD: Int32?
+    public let interfaceKind: UInt32?
+    public let pathFlags: UInt32?
+    public let ringCapacity: UInt32?
+    public let sequenceBase: UInt64?
+
+    public init(
+        timestampMs: UInt64,
+        kind: Kind,
+        configJSON: String? = nil,
+        tunFD: Int32? = nil,
+        interfaceKind: UInt32? = nil,
+        pathFlags: UInt32? = nil,
+        ringCapacity: UInt32? = nil,
+        sequenceBase: UInt64? = nil
+    ) {
+        self.timestampMs = timestampMs
+        self.kind = kind
+        self.configJSON = configJSON
+        self.tunFD = tunFD
+        self.interfaceKind = interfaceKind
+        self.pathFlags = pathFlags
+        self.ringCapacity = ringCapacity
+        self.sequenceBase = sequenceBase
+    }
+}
+
+/// Records every control call the host makes across the FFI boundary, with
+/// timestamps, so a field-reported bug can be replayed locally against a fresh
+/// engine instead of reconstructed from prose.
+/// Attach one recorder per handle via `DataplaneHandle.init(traceRecorder:)`;
+/// the handle records each call as it is issued, whether or not it succeeds,
+/// because failing calls are usually the interesting part of a field trace.
+public final class DataplaneTraceRecorder: @unchecked Sendable {
+    private let lock = NSLock()
+    private var storedEntries: [DataplaneTraceEntry] = []
+    private let startedAt = DispatchTime.now()
+
+    public init() {}
+
+    /// All entries recorded so far, in call order.
+    public var entries: [DataplaneTraceEntry] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedEntries
+    }
+
+    func record(
+        _ kind: DataplaneTraceEntry.Kind,
+        configJSON: String? = nil,
+        tunFD: Int32? = nil,
+        interfaceKind: UInt32? = nil,
+        pathFlags: UInt32? = nil,
+        ringCapacity: UInt32? = nil,
+        sequenceBase: UInt64? = nil
+    ) {
+        let elapsedNanoseconds = DispatchTime.now().uptimeNanoseconds - startedAt.uptimeNanoseconds
+        let entry = DataplaneTraceEntry(
+            timestampMs: elapsedNanoseconds / 1_000_000,
+            kind: kind,
+            configJSON: configJSON,
+            tunFD: tunFD,
+            interfaceKind: interfaceKind,
+            pathFlags: pathFlags,
+            ringCapacity: ringCapacity,
+            sequenceBase: sequenceBase
+        )
+        lock.lock()
+        storedEntries.append(entry)
+        lock.unlock()
+    }
+
+    /// Writes the trace as JSON Lines — one entry per line — so partial traces
+    /// from crashed processes stay loadable up to the last complete line.
+    /// - Parameter url: Destination file URL; an existing file is replaced.
+    public func writeJSONLines(to url: URL) throws {
+        let encoder = JSONEncoder()
+        encoder.outputFormatting = [.sortedKeys]
+        var lines: [String] = []
+        for entry in entries {
+            let data = try encoder.encode(entry)
+            lines.append(String(decoding: data, as: UTF8.self))
+        }
+        try Data((lines.joined(separator: "\n") + "\n").utf8).write(to: url, options: .atomic)
+    }
+
+    /// Loads a JSON Lines trace, skipping blank lines.
+    /// - Parameter url: Trace file written by `writeJSONLines(to:)`.
+    /// - Returns: Entries in recorded order.
+    public static func load(from url: URL) throws -> [DataplaneTraceEntry] {
+        let decoder = JSONDecoder()
+        let contents = try String(contentsOf: url, encoding: .utf8)
+        return try contents.split(separator: "\n").compactMap { line in
+            let trimmed = line.trimmingCharacters(in: .whitespaces)
+            guard !trimmed.isEmpty else {
+                return nil
+            }
+            return try decoder.decode(DataplaneTraceEntry.self, from: Data(trimmed.utf8))
+        }
+    }
+}
+
+/// Summary from one trace replay run.
+public struct DataplaneTraceReplayResult: Sendable, Equatable {
+    /// Calls re-issued against the fresh handle.
+    public let appliedCalls: Int
+    /// Calls that failed during replay, paired with their entry index. A faithful
+    /// reproduction of a field failure often fails the same way, so replay keeps
+    /// going and reports instead of throwing.
+    public let failedCallIndices: [Int]
+
+    public init(appliedCalls: Int, failedCallIndices: [Int]) {
+        self.appliedCalls = appliedCalls
+        self.failedCallIndices = failedCallIndices
+    }
+}
+
+/// Replays a recorded FFI trace against a fresh engine, deterministically
+/// in-order; opt into original call spacing with `respectTiming`.
+public enum DataplaneTraceReplayer {
+    public static func replay(
+        entries: [DataplaneTraceEntry],
+        logger: StructuredLogger,
+        respectTiming: Bool = false
+    ) async throws -> DataplaneTraceReplayResult {
+        var handle: DataplaneHandle?
+        var appliedCalls = 0
+        var failedCallIndices: [Int] = []
+        var previousTimestampMs: UInt64 = 0
+
+        for (index, entry) in entries.enumerated() {
+            if respectTiming, entry.timestampMs > previousTimestampMs {
+                try await Task.sleep(nanoseconds: (entry.timestampMs - previousTimestampMs) * 1_000_000)
+            }
+            previousTimestampMs = entry.timestampMs
+
+            appliedCalls += 1
+            do {
+                switch entry.kind {
+                case .create:
+                    handle = try DataplaneHandle(
+                        configJSON: entry.configJSON ?? "{}",
+                        callbacks: .noop,
+                        logger: logger
+                    )
+                case .start:
+                    try await handle?.start(tunFD: entry.tunFD ?? 0)
+                case .stop:
+                    try await handle?.stop()
+                case .pathChanged:
+                    try await handle?.notifyPathChanged(
+                        DataplanePathInfo(
+                            interfaceKind: DataplanePathInfo.InterfaceKind(rawValue: entry.interfaceKind ?? 0) ?? .other,
+                            isExpensive: (entry.pathFlags ?? 0) & UInt32(RP_DP_PATH_FLAG_EXPENSIVE) != 0,
+                            isConstrained: (entry.pathFlags ?? 0) & UInt32(RP_DP_PATH_FLAG_CONSTRAINED) != 0
+                        )
+                    )
+                case .resetStats:
+                    try await handle?.resetStats()
+                case .registerEventRing:
+                    // The handle retains the ring until unregister or destroy.
+                    let ring = DataplaneEventRing(capacity: entry.ringCapacity ?? DataplaneEventRing.defaultCapacity)
+                    try await handle?.registerEventRing(ring, sequenceBase: entry.sequenceBase ?? 0)
+                case .unregisterEventRing:
+                    try await handle?.unregisterEventRing()
+                case .destroy:
+                    await handle?.destroy()
+                    handle = nil
+                }
+            } catch {
+                failedCallIndices.append(index)
+            }
+        }
+
+        // A trace from a crashed process may end without its destroy call.
+        await handle?.destroy()
+        return DataplaneTraceReplayResult(appliedCalls: appliedCalls, failedCallIndices: failedCallIndices)
+    }
+}
@@ -0,0 +1,125 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import HarnessTunSupport
+
+public enum BenchmarkHarnessError: Error, CustomStringConvertible {
+    case invalidOptions(String)
+    case failed(errno: Int32)
+
+    public var description: String {
+        switch self {
+        case .invalidOptions(let reason):
+            return "Invalid benchmark options: \(reason)"
+        case .failed(let errno):
+            return "Benchmark run failed: errno=\(errno)"
+        }
+    }
+}
+
+public struct BenchmarkOptions: Sendable, Equatable {
+    /// Duration of each load phase (TCP, then UDP).
+    public let durationSeconds: TimeInterval
+    /// Size of each TCP send, controlling syscall rate versus batch size.
+    public let messageBytes: Int
+    /// UDP datagram payload size; defaults to a tunnel-MTU-shaped 1200 bytes.
+    public let udpDatagramBytes: Int
+    /// Address both endpoints bind to. The loopback default measures the host
+    /// socket path the engine relies on; bind a tunnel-routed address to push
+    /// the load through the engine instead.
+    public let bindAddress: String
+
+    public init(
+        durationSeconds: TimeInterval = 3,
+        messageBytes: Int = 65_536,
+        udpDatagramBytes: Int = 1_200,
+        bindAddress: String = "127.0.0.1"
+    ) {
+        self.durationSeconds = durationSeconds
+        self.messageBytes = messageBytes
+        self.udpDatagramBytes = udpDatagramBytes
+        self.bindAddress = bindAddress
+    }
+}
+
+/// Summary of one self-benchmark run: throughput per transport, sender-side TCP
+/// retransmits, UDP delivery counts (the gap is receive-buffer loss), and the
+/// process CPU time the run consumed.
+public struct BenchmarkRunResult: Sendable, Equatable {
+    public let tcpMegabitsPerSecond: Double
+    public let tcpBytesTransferred: UInt64
+    public let tcpRetransmits: UInt64
+    public let udpMegabitsPerSecond: Double
+    public let udpDatagramsSent: UInt64
+    public let udpDatagramsReceived: UInt64
+    public let cpuUserSeconds: Double
+    public let cpuSystemSeconds: Double
+
+    public init(
+        tcpMegabitsPerSecond: Double,
+        tcpBytesTransferred: UInt64,
+        tcpRetransmits: UInt64,
+        udpMegabitsPerSecond: Double,
+        udpDatagramsSent: UInt64,
+        udpDatagramsReceived: UInt64,
+        cpuUserSeconds: Double,
+        cpuSystemSeconds: Double
+    ) {
+        self.tcpMegabitsPerSecond = tcpMegabitsPerSecond
+        self.tcpBytesTransferred = tcpBytesTransferred
+        self.tcpRetransmits = tcpRetransmits
+        self.udpMegabitsPerSecond = udpMegabitsPerSecond
+        self.udpDatagramsSent = udpDatagramsSent
+        self.udpDatagramsReceived = udpDatagramsReceived
+        self.cpuUserSeconds = cpuUserSeconds
+        self.cpuSystemSeconds = cpuSystemSeconds
+    }
+}
+
+/// iperf-style self-benchmark: blasts a TCP and a UDP sender/receiver pair over
+/// local endpoints and reports throughput, retransmits, and CPU usage, giving an
+/// OS-level performance number without external tooling.
+public enum BenchmarkHarness {
+    public static func run(options: BenchmarkOptions) throws -> BenchmarkRunResult {
+        guard options.durationSeconds.isFinite,
+              options.durationSeconds > 0,
+              options.durationSeconds <= 3_600 else {
+            throw BenchmarkHarnessError.invalidOptions("duration must be positive and at most 3600 seconds")
+        }
+        guard (1...4_194_304).contains(options.messageBytes) else {
+            throw BenchmarkHarnessError.invalidOptions("message bytes must be between 1 and 4194304")
+        }
+        guard (1...65_507).contains(options.udpDatagramBytes) else {
+            throw BenchmarkHarnessError.invalidOptions("udp datagram bytes must be between 1 and 65507")
+        }
+
+        var native = rp_harness_bench_result_t()
+        var errnoValue: CInt = 0
+        let result = options.bindAddress.withCString { bindAddressPointer in
+            rp_harness_run_benchmark(
+                bindAddressPointer,
+                options.durationSeconds,
+                CInt(options.messageBytes),
+                CInt(options.udpDatagramBytes),
+                &native,
+                &errnoValue
+            )
+        }
+        guard result == 0 else {
+            throw BenchmarkHarnessError.failed(errno: Int32(errnoValue))
+        }
+
+        return BenchmarkRunResult(
+            tcpMegabitsPerSecond: native.tcp_megabits_per_second,
+            tcpBytesTransferred: native.tcp_bytes_transferred,
+            tcpRetransmits: native.tcp_retransmits,
+            udpMegabitsPerSecond: native.udp_megabits_per_second,
+            udpDatagramsSent: native.udp_datagrams_sent,
+            udpDatagramsReceived: native.udp_datagrams_received,
+            cpuUserSeconds: native.cpu_user_seconds,
+            cpuSystemSeconds: native.cpu_system_seconds
+        )
+    }
+}
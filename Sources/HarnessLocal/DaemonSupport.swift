@@ -0,0 +1,338 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Dispatch
+import Foundation
+import Observability
+import TunnelRuntime
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Failures specific to long-lived daemon runs, reported before the TUN device is touched
+/// so a misconfigured service exits cleanly instead of half-starting.
+public enum DaemonHarnessError: Error, CustomStringConvertible {
+    case pidFileHeld(path: String, pid: Int32)
+    case pidFileUnwritable(path: String)
+    case configFileInvalid(path: String, reason: String)
+
+    public var description: String {
+        switch self {
+        case .pidFileHeld(let path, let pid):
+            return "PID file \(path) is held by live process \(pid)"
+        case .pidFileUnwritable(let path):
+            return "PID file \(path) could not be written"
+        case .configFileInvalid(let path, let reason):
+            return "Daemon config \(path) is invalid: \(reason)"
+        }
+    }
+}
+
+/// Service-lifecycle options for running the TUN harness as a long-lived daemon on a
+/// Linux router: PID file for the init system, optional config file reloaded on SIGHUP,
+/// and size-rotated JSONL logs instead of the in-memory sink.
+public struct DaemonOptions: Sendable, Equatable {
+    public let pidFilePath: String
+    /// Optional JSON config overriding TUN options; re-read on SIGHUP. See
+    /// `TunDaemonConfigFile` for the accepted keys.
+    public let configFilePath: String?
+    /// Directory for rotated JSONL harness logs; `nil` keeps logs in memory.
+    public let logDirectoryPath: String?
+    public let maxLogFileBytes: Int
+    public let maxLogFiles: Int
+
+    /// - Parameters:
+    ///   - pidFilePath: Where the daemon records its PID for the init system.
+    ///   - configFilePath: Optional JSON config file re-read on SIGHUP.
+    ///   - logDirectoryPath: Optional directory for rotated JSONL logs.
+    ///   - maxLogFileBytes: Rotation threshold for the active log file.
+    ///   - maxLogFiles: Maximum retained active/rotated log files.
+    public init(
+        pidFilePath: String = "/tmp/harness-local.pid",
+        configFilePath: String? = nil,
+        logDirectoryPath: String? = nil,
+        maxLogFileBytes: Int = 1_048_576,
+        maxLogFiles: Int = 5
+    ) {
+        self.pidFilePath = pidFilePath
+        self.configFilePath = configFilePath
+        self.logDirectoryPath = logDirectoryPath
+        self.maxLogFileBytes = max(4_096, maxLogFileBytes)
+        self.maxLogFiles = max(1, maxLogFiles)
+    }
+}
+
+/// Summary of one completed daemon run, produced when a termination signal arrives.
+public struct TunDaemonRunResult: Sendable, Equatable {
+    public let interfaceName: String
+    public let reloadCount: Int
+    public let terminationSignal: Int32
+
+    /// - Parameters:
+    ///   - interfaceName: Name of the last TUN interface the daemon served.
+    ///   - reloadCount: Number of SIGHUP-driven restarts performed.
+    ///   - terminationSignal: Signal number that ended the run (SIGTERM or SIGINT).
+    public init(interfaceName: String, reloadCount: Int, terminationSignal: Int32) {
+        self.interfaceName = interfaceName
+        self.reloadCount = reloadCount
+        self.terminationSignal = terminationSignal
+    }
+}
+
+/// On-disk PID file handling with stale-holder recovery: a leftover file from a crashed
+/// run is overwritten, but a file naming a live process refuses startup so two daemons
+/// never fight over one TUN device.
+enum DaemonPidFile {
+    static func acquire(atPath path: String, pid: Int32 = ProcessInfo.processInfo.processIdentifier) throws {
+        if let raw = try? String(contentsOfFile: path, encoding: .utf8),
+           let holder = Int32(raw.trimmingCharacters(in: .whitespacesAndNewlines)),
+           holder > 0,
+           holder != pid,
+           isProcessAlive(holder) {
+            throw DaemonHarnessError.pidFileHeld(path: path, pid: holder)
+        }
+        do {
+            try "\(pid)\n".write(toFile: path, atomically: true, encoding: .utf8)
+        } catch {
+            throw DaemonHarnessError.pidFileUnwritable(path: path)
+        }
+    }
+
+    static func release(atPath path: String) {
+        try? FileManager.default.removeItem(atPath: path)
+    }
+
+    /// Signal 0 probes existence without delivering anything; EPERM still means alive,
+    /// just owned by another user.
+    private static func isProcessAlive(_ pid: Int32) -> Bool {
+        if kill(pid, 0) == 0 {
+            return true
+        }
+        return errno == EPERM
+    }
+}
+
+/// JSON config file for daemon runs. Every key is optional; absent keys keep the value
+/// the daemon was launched with, so a reload only needs to state what changed. Device
+/// identity (interface name, packet-info framing, TAP mode) stays fixed for the daemon's
+/// lifetime — changing it requires a restart.
+public struct TunDaemonConfigFile: Codable, Sendable, Equatable {
+    public var mtu: Int?
+    public var ipv4Address: String?
+    public var ipv6Address: String?
+    public var socksHost: String?
+    public var socksPort: UInt16?
+    public var routerMode: Bool?
+    public var engineLogLevel: String?
+
+    /// Loads and decodes one daemon config file.
+    /// - Parameter url: JSON file location.
+    /// - Returns: Decoded config overlay.
+    /// - Throws: `DaemonHarnessError.configFileInvalid` on read or decode failure.
+    public static func load(from url: URL) throws -> TunDaemonConfigFile {
+        do {
+            let data = try Data(contentsOf: url)
+            return try JSONDecoder().decode(TunDaemonConfigFile.self, from: data)
+        } catch {
+            throw DaemonHarnessError.configFileInvalid(path: url.path, reason: String(describing: error))
+        }
+    }
+
+    /// Overlays this config onto launch-time options, keeping launch values for absent keys.
+    /// - Parameter base: Options the daemon was launched with.
+    /// - Returns: Effective options for the next runtime cycle.
+    public func applying(to base: TunRuntimeOptions) -> TunRuntimeOptions {
+        TunRuntimeOptions(
+            requestedName: base.requestedName,
+            includePacketInfo: base.includePacketInfo,
+            ethernetTap: base.ethernetTap,
+            mtu: mtu ?? base.mtu,
+            ipv4Address: ipv4Address ?? base.ipv4Address,
+            ipv6Address: ipv6Address ?? base.ipv6Address,
+            socksHost: socksHost ?? base.socksHost,
+            socksPort: socksPort ?? base.socksPort,
+            routerMode: routerMode ?? base.routerMode,
+            durationSeconds: base.durationSeconds,
+            engineLogLevel: engineLogLevel ?? base.engineLogLevel
+        )
+    }
+}
+
+/// Bridges POSIX signals into an `AsyncStream` the daemon loop can await. Signals are
+/// ignored at the process level and observed through dispatch sources, the only
+/// async-signal-safe way to run Swift code in response.
+private final class DaemonSignalSource: @unchecked Sendable {
+    let stream: AsyncStream<Int32>
+    private let continuation: AsyncStream<Int32>.Continuation
+    private let sources: [DispatchSourceSignal]
+
+    init(signals: [Int32]) {
+        var streamContinuation: AsyncStream<Int32>.Continuation?
+        self.stream = AsyncStream { streamContinuation = $0 }
+        guard let streamContinuation else {
+            fatalError("AsyncStream init did not provide a continuation")
+        }
+        self.continuation = streamContinuation
+        let queue = DispatchQueue(label: "harness-local.daemon.signals")
+        self.sources = signals.map { number in
+            signal(number, SIG_IGN)
+            let source = DispatchSource.makeSignalSource(signal: number, queue: queue)
+            source.setEventHandler {
+                streamContinuation.yield(number)
+            }
+            source.resume()
+            return source
+        }
+    }
+
+    func cancel() {
+        for source in sources {
+            source.cancel()
+        }
+        continuation.finish()
+    }
+}
+
+public extension HarnessRunner {
+    /// Runs the TUN harness as a long-lived service: write a PID file, serve traffic until
+    /// SIGTERM/SIGINT, and restart the runtime with freshly loaded config on SIGHUP.
+    /// `options.durationSeconds` is ignored — a daemon runs until signaled.
+    /// Decision: a reload that fails to parse keeps the previous config and the daemon
+    /// running; a bad config edit on a remote router must never take the tunnel down.
+    /// - Parameters:
+    ///   - options: Launch-time TUN options; the config file overlays these per cycle.
+    ///   - daemon: Service-lifecycle options (PID file, config file, log rotation).
+    ///   - rootPath: Reserved diagnostics root for harness callers.
+    /// - Returns: Summary of the completed run.
+    func runTunDaemon(
+        options: TunRuntimeOptions,
+        daemon: DaemonOptions,
+        rootPath: URL
+    ) async throws -> TunDaemonRunResult {
+        _ = rootPath
+        guard options.socksPort > 0 else {
+            throw TunHarnessError.invalidSocksPort(options.socksPort)
+        }
+        try DaemonPidFile.acquire(atPath: daemon.pidFilePath)
+        defer {
+            DaemonPidFile.release(atPath: daemon.pidFilePath)
+        }
+
+        let logger = makeDaemonLogger(daemon)
+        var currentOptions = options
+        if let configFilePath = daemon.configFilePath {
+            currentOptions = try TunDaemonConfigFile
+                .load(from: URL(fileURLWithPath: configFilePath))
+                .applying(to: options)
+        }
+
+        let signals = DaemonSignalSource(signals: [SIGHUP, SIGINT, SIGTERM])
+        defer {
+            signals.cancel()
+        }
+        var signalIterator = signals.stream.makeAsyncIterator()
+        var reloadCount = 0
+
+        while true {
+            let endpoint = try TunEngineEndpoint.open(options: currentOptions)
+            let runtime = TunnelRuntime(
+                clock: SystemClock(),
+                runIdGenerator: RandomRunIdGenerator(),
+                randomSource: SystemRandomSource(),
+                logger: logger
+            )
+            let config = TunDataplaneConfig.make(interfaceName: endpoint.interfaceName, options: currentOptions)
+            do {
+                try await runtime.start(configJSON: config, tunFD: endpoint.engineFD)
+            } catch {
+                endpoint.close()
+                throw error
+            }
+            await logger.log(
+                level: .notice,
+                phase: .lifecycle,
+                category: .control,
+                component: "TunDaemon",
+                event: "serving",
+                message: "Daemon serving TUN interface",
+                metadata: [
+                    "interface": endpoint.interfaceName,
+                    "reloads": String(reloadCount)
+                ]
+            )
+
+            // A finished stream only happens at cancel time, so treat it as SIGTERM.
+            let received = await signalIterator.next() ?? SIGTERM
+            try? await runtime.stop()
+            endpoint.close()
+
+            guard received == SIGHUP else {
+                await logger.log(
+                    level: .notice,
+                    phase: .lifecycle,
+                    category: .control,
+                    component: "TunDaemon",
+                    event: "shutdown",
+                    message: "Daemon stopping on termination signal",
+                    metadata: ["signal": String(received)]
+                )
+                return TunDaemonRunResult(
+                    interfaceName: endpoint.interfaceName,
+                    reloadCount: reloadCount,
+                    terminationSignal: received
+                )
+            }
+
+            reloadCount += 1
+            if let configFilePath = daemon.configFilePath {
+                do {
+                    currentOptions = try TunDaemonConfigFile
+                        .load(from: URL(fileURLWithPath: configFilePath))
+                        .applying(to: options)
+                } catch {
+                    await logger.log(
+                        level: .warning,
+                        phase: .lifecycle,
+                        category: .control,
+                        component: "TunDaemon",
+                        event: "reload-config-failed",
+                        errorCode: String(describing: error),
+                        message: "Keeping previous config after failed reload"
+                    )
+                }
+            }
+            await logger.log(
+                level: .notice,
+                phase: .lifecycle,
+                category: .control,
+                component: "TunDaemon",
+                event: "reload",
+                message: "Restarting runtime on SIGHUP",
+                metadata: ["reloads": String(reloadCount)]
+            )
+        }
+    }
+}
+
+/// JSONL file logging with rotation when a log directory is configured, matching the
+/// in-memory default of the one-shot runner otherwise.
+private func makeDaemonLogger(_ daemon: DaemonOptions) -> StructuredLogger {
+    guard let logDirectoryPath = daemon.logDirectoryPath else {
+        return StructuredLogger(sink: InMemoryLogSink())
+    }
+    let sink = JSONLLogSink(
+        rootProvider: HarnessLogRootPathProvider(root: URL(fileURLWithPath: logDirectoryPath, isDirectory: true)),
+        policy: JSONLRotationPolicy(
+            maxBytesPerFile: daemon.maxLogFileBytes,
+            maxFiles: daemon.maxLogFiles,
+            maxTotalBytes: daemon.maxLogFileBytes * daemon.maxLogFiles
+        ),
+        eventQueueLabel: "tun-daemon",
+        filePrefix: "tun-daemon"
+    )
+    return StructuredLogger(sink: sink)
+}
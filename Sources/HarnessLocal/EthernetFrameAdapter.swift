@@ -0,0 +1,353 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// A 48-bit ethernet hardware address.
+public struct EthernetMACAddress: Sendable, Equatable, Hashable, CustomStringConvertible {
+    /// The six address octets in transmission order.
+    public let octets: [UInt8]
+
+    /// - Parameter octets: Exactly six octets; any other length fails.
+    public init?(octets: [UInt8]) {
+        guard octets.count == 6 else {
+            return nil
+        }
+        self.octets = octets
+    }
+
+    /// All-ones broadcast address.
+    public static let broadcast = EthernetMACAddress(octets: [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF])!
+
+    /// Default locally-administered address for the adapter's own interface. The
+    /// locally-administered bit keeps it out of any vendor OUI space.
+    public static let defaultLocal = EthernetMACAddress(octets: [0x02, 0x52, 0x50, 0x00, 0x00, 0x01])!
+
+    /// Whether the group bit is set (broadcast and multicast destinations).
+    public var isGroup: Bool {
+        octets[0] & 0x01 != 0
+    }
+
+    public var description: String {
+        octets.map { String(format: "%02x", $0) }.joined(separator: ":")
+    }
+}
+
+/// Configuration for ethernet-framed operation.
+public struct EthernetFramingOptions: Sendable {
+    /// Hardware address the adapter answers for and stamps on outbound frames.
+    public let localMACAddress: EthernetMACAddress
+    /// IPv4 address literal the ARP responder claims, or `nil` to leave ARP unanswered.
+    public let ipv4Address: String?
+    /// IPv6 address literal the NDP responder claims, or `nil` to leave solicitations unanswered.
+    public let ipv6Address: String?
+
+    /// - Parameters:
+    ///   - localMACAddress: Hardware address of the adapter's own interface.
+    ///   - ipv4Address: IPv4 literal the ARP responder claims.
+    ///   - ipv6Address: IPv6 literal the NDP responder claims.
+    public init(
+        localMACAddress: EthernetMACAddress = .defaultLocal,
+        ipv4Address: String? = nil,
+        ipv6Address: String? = nil
+    ) {
+        self.localMACAddress = localMACAddress
+        self.ipv4Address = ipv4Address
+        self.ipv6Address = ipv6Address
+    }
+}
+
+/// What the adapter decided about one inbound ethernet frame.
+public enum EthernetInboundVerdict: Equatable {
+    /// The frame carried an IP packet for the engine; forward the payload.
+    case ipPacket(family: Int32, payload: Data)
+    /// The frame was a neighbor query the adapter answered; write the reply frame back
+    /// to the ethernet backend instead of forwarding anything.
+    case respond(frame: Data)
+    /// The frame is not for this interface or not parseable; discard it.
+    case drop
+}
+
+/// Monotonic counters describing the adapter's neighbor traffic.
+public struct EthernetFramingStats: Sendable, Equatable {
+    /// ARP replies synthesized for requests naming the local IPv4 address.
+    public let arpRepliesSent: Int
+    /// Neighbor advertisements synthesized for solicitations naming the local IPv6 address.
+    public let neighborAdvertisementsSent: Int
+    /// Frames discarded for any reason (foreign destination, unknown ethertype, truncation).
+    public let droppedFrames: Int
+
+    /// - Parameters:
+    ///   - arpRepliesSent: ARP replies synthesized so far.
+    ///   - neighborAdvertisementsSent: Neighbor advertisements synthesized so far.
+    ///   - droppedFrames: Frames discarded so far.
+    public init(arpRepliesSent: Int, neighborAdvertisementsSent: Int, droppedFrames: Int) {
+        self.arpRepliesSent = arpRepliesSent
+        self.neighborAdvertisementsSent = neighborAdvertisementsSent
+        self.droppedFrames = droppedFrames
+    }
+}
+
+/// Translates between the engine's raw-IP world and ethernet-framed backends: TAP
+/// interfaces and virtualization platforms that deliver layer-2 frames instead of bare
+/// IP packets.
+/// Decision: this is a pure state machine over frames, like `RouterNATTable`, so the
+/// responder logic is deterministic under test; the owner (`EthernetTapPump` for the
+/// harness TAP device) moves frames between this adapter and its descriptor and hands
+/// the extracted IP payloads to the engine.
+/// The built-in ARP and NDP responders answer queries for the configured local
+/// addresses, so the peer's stack can resolve the tunnel without a helper daemon, and
+/// the peer's own hardware address is learned from observed traffic so outbound frames
+/// are unicast as soon as the first frame arrives.
+public struct EthernetFrameAdapter: Sendable {
+    /// Bytes in an ethernet header: destination, source, ethertype.
+    public static let headerLength = 14
+
+    private static let ethertypeIPv4: UInt16 = 0x0800
+    private static let ethertypeARP: UInt16 = 0x0806
+    private static let ethertypeIPv6: UInt16 = 0x86DD
+
+    private let options: EthernetFramingOptions
+    private let localIPv4: [UInt8]?
+    private let localIPv6: [UInt8]?
+    private var learnedPeerMAC: EthernetMACAddress?
+    private var arpRepliesSent = 0
+    private var neighborAdvertisementsSent = 0
+    private var droppedFrames = 0
+
+    /// - Parameter options: Local addresses the responders claim. Literals that do not
+    ///   parse disable the corresponding responder rather than failing construction.
+    public init(options: EthernetFramingOptions) {
+        self.options = options
+        var addr4 = in_addr()
+        if let literal = options.ipv4Address,
+           literal.withCString({ inet_pton(AF_INET, $0, &addr4) }) == 1 {
+            localIPv4 = withUnsafeBytes(of: addr4) { Array($0) }
+        } else {
+            localIPv4 = nil
+        }
+        var addr6 = in6_addr()
+        if let literal = options.ipv6Address,
+           literal.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 {
+            localIPv6 = withUnsafeBytes(of: addr6) { Array($0) }
+        } else {
+            localIPv6 = nil
+        }
+    }
+
+    /// Peer hardware address learned from inbound frames, or `nil` before any frame
+    /// carried a unicast source. Outbound frames broadcast until this resolves.
+    public var peerMACAddress: EthernetMACAddress? {
+        learnedPeerMAC
+    }
+
+    /// Current responder counters.
+    public var stats: EthernetFramingStats {
+        EthernetFramingStats(
+            arpRepliesSent: arpRepliesSent,
+            neighborAdvertisementsSent: neighborAdvertisementsSent,
+            droppedFrames: droppedFrames
+        )
+    }
+
+    /// Classifies one inbound ethernet frame: extracts IP payloads for the engine,
+    /// synthesizes ARP and NDP answers, and drops everything else. Frames addressed to
+    /// a foreign unicast destination are dropped, matching a non-promiscuous interface.
+    public mutating func classifyInbound(frame: Data) -> EthernetInboundVerdict {
+        guard frame.count > Self.headerLength else {
+            droppedFrames += 1
+            return .drop
+        }
+        let bytes = [UInt8](frame)
+        guard let destination = EthernetMACAddress(octets: Array(bytes[0..<6])),
+              let source = EthernetMACAddress(octets: Array(bytes[6..<12])) else {
+            droppedFrames += 1
+            return .drop
+        }
+        guard destination == options.localMACAddress || destination.isGroup else {
+            droppedFrames += 1
+            return .drop
+        }
+        if !source.isGroup {
+            learnedPeerMAC = source
+        }
+        let ethertype = UInt16(bytes[12]) << 8 | UInt16(bytes[13])
+        let payload = Array(bytes[Self.headerLength...])
+        switch ethertype {
+        case Self.ethertypeIPv4:
+            return .ipPacket(family: AF_INET, payload: Data(payload))
+        case Self.ethertypeIPv6:
+            if let advertisement = neighborAdvertisement(forSolicitation: payload, requesterMAC: source) {
+                neighborAdvertisementsSent += 1
+                return .respond(frame: advertisement)
+            }
+            return .ipPacket(family: AF_INET6, payload: Data(payload))
+        case Self.ethertypeARP:
+            if let reply = arpReply(forRequest: payload, requesterMAC: source) {
+                arpRepliesSent += 1
+                return .respond(frame: reply)
+            }
+            // Gratuitous announcements and replies only feed MAC learning above.
+            droppedFrames += 1
+            return .drop
+        default:
+            droppedFrames += 1
+            return .drop
+        }
+    }
+
+    /// Wraps an outbound IP packet in an ethernet header for the backend. The
+    /// destination is the learned peer address, falling back to broadcast before the
+    /// first inbound frame resolves it. Returns `nil` for families the wire cannot carry.
+    public func frameOutbound(packet: Data, family: Int32) -> Data? {
+        let ethertype: UInt16
+        switch family {
+        case AF_INET:
+            ethertype = Self.ethertypeIPv4
+        case AF_INET6:
+            ethertype = Self.ethertypeIPv6
+        default:
+            return nil
+        }
+        let destination = learnedPeerMAC ?? .broadcast
+        var frame = Data(capacity: Self.headerLength + packet.count)
+        frame.append(contentsOf: destination.octets)
+        frame.append(contentsOf: options.localMACAddress.octets)
+        frame.append(UInt8(ethertype >> 8))
+        frame.append(UInt8(ethertype & 0xFF))
+        frame.append(packet)
+        return frame
+    }
+
+    // MARK: - ARP responder
+
+    /// Builds the reply frame for an ARP request naming the local IPv4 address, or
+    /// `nil` when the payload is not such a request.
+    private func arpReply(forRequest payload: [UInt8], requesterMAC: EthernetMACAddress) -> Data? {
+        guard let localIPv4 else {
+            return nil
+        }
+        // Fixed-size IPv4-over-ethernet ARP body: htype, ptype, hlen, plen, oper,
+        // sender hardware/protocol, target hardware/protocol.
+        guard payload.count >= 28,
+              payload[0] == 0x00, payload[1] == 0x01,
+              payload[2] == 0x08, payload[3] == 0x00,
+              payload[4] == 6, payload[5] == 4,
+              payload[6] == 0x00, payload[7] == 0x01 else {
+            return nil
+        }
+        let senderHardware = Array(payload[8..<14])
+        let senderProtocol = Array(payload[14..<18])
+        let targetProtocol = Array(payload[24..<28])
+        guard targetProtocol == localIPv4 else {
+            return nil
+        }
+        var body: [UInt8] = [0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x02]
+        body.append(contentsOf: options.localMACAddress.octets)
+        body.append(contentsOf: localIPv4)
+        body.append(contentsOf: senderHardware)
+        body.append(contentsOf: senderProtocol)
+        var frame = Data(capacity: Self.headerLength + body.count)
+        frame.append(contentsOf: requesterMAC.octets)
+        frame.append(contentsOf: options.localMACAddress.octets)
+        frame.append(contentsOf: [UInt8(Self.ethertypeARP >> 8), UInt8(Self.ethertypeARP & 0xFF)])
+        frame.append(contentsOf: body)
+        return frame
+    }
+
+    // MARK: - NDP responder
+
+    /// Builds the neighbor advertisement frame for a solicitation naming the local
+    /// IPv6 address, or `nil` when the payload is any other IPv6 packet.
+    private func neighborAdvertisement(forSolicitation payload: [UInt8], requesterMAC: EthernetMACAddress) -> Data? {
+        guard let localIPv6 else {
+            return nil
+        }
+        // IPv6 header, then ICMPv6 neighbor solicitation: type 135, code 0, checksum,
+        // reserved, 16-byte target. Solicitations arrive with hop limit 255.
+        guard payload.count >= 40 + 24,
+              payload[0] >> 4 == 6,
+              payload[6] == 58,
+              payload[40] == 135, payload[41] == 0 else {
+            return nil
+        }
+        let solicitationTarget = Array(payload[48..<64])
+        guard solicitationTarget == localIPv6 else {
+            return nil
+        }
+        let sourceAddress = Array(payload[8..<24])
+        // Duplicate-address-detection probes come from the unspecified address; the
+        // adapter holds the address authoritatively, so answer to all-nodes multicast.
+        let unspecified = [UInt8](repeating: 0, count: 16)
+        var replyDestination = sourceAddress
+        var solicitedFlag: UInt8 = 0x40
+        if sourceAddress == unspecified {
+            replyDestination = [UInt8](repeating: 0, count: 16)
+            replyDestination[0] = 0xFF
+            replyDestination[1] = 0x02
+            replyDestination[15] = 0x01
+            solicitedFlag = 0x00
+        }
+
+        // ICMPv6 neighbor advertisement: solicited + override flags, target, then the
+        // target link-layer address option so the peer caches the MAC in one exchange.
+        var icmp: [UInt8] = [136, 0, 0, 0, solicitedFlag | 0x20, 0, 0, 0]
+        icmp.append(contentsOf: localIPv6)
+        icmp.append(contentsOf: [2, 1])
+        icmp.append(contentsOf: options.localMACAddress.octets)
+        let checksum = Self.icmpv6Checksum(
+            source: localIPv6, destination: replyDestination, payload: icmp
+        )
+        icmp[2] = UInt8(checksum >> 8)
+        icmp[3] = UInt8(checksum & 0xFF)
+
+        var header: [UInt8] = [0x60, 0, 0, 0]
+        header.append(contentsOf: [UInt8(icmp.count >> 8), UInt8(icmp.count & 0xFF)])
+        header.append(58)
+        header.append(255)
+        header.append(contentsOf: localIPv6)
+        header.append(contentsOf: replyDestination)
+
+        var frame = Data(capacity: Self.headerLength + header.count + icmp.count)
+        frame.append(contentsOf: requesterMAC.octets)
+        frame.append(contentsOf: options.localMACAddress.octets)
+        frame.append(contentsOf: [UInt8(Self.ethertypeIPv6 >> 8), UInt8(Self.ethertypeIPv6 & 0xFF)])
+        frame.append(contentsOf: header)
+        frame.append(contentsOf: icmp)
+        return frame
+    }
+
+    /// One's-complement checksum over the IPv6 pseudo-header and ICMPv6 payload.
+    private static func icmpv6Checksum(source: [UInt8], destination: [UInt8], payload: [UInt8]) -> UInt16 {
+        var words: [UInt8] = []
+        words.append(contentsOf: source)
+        words.append(contentsOf: destination)
+        let length = UInt32(payload.count)
+        words.append(contentsOf: [
+            UInt8(length >> 24), UInt8((length >> 16) & 0xFF),
+            UInt8((length >> 8) & 0xFF), UInt8(length & 0xFF),
+        ])
+        words.append(contentsOf: [0, 0, 0, 58])
+        words.append(contentsOf: payload)
+        var sum: UInt32 = 0
+        var index = 0
+        while index + 1 < words.count {
+            sum += UInt32(words[index]) << 8 | UInt32(words[index + 1])
+            index += 2
+        }
+        if index < words.count {
+            sum += UInt32(words[index]) << 8
+        }
+        while sum > 0xFFFF {
+            sum = (sum & 0xFFFF) + (sum >> 16)
+        }
+        return ~UInt16(sum & 0xFFFF)
+    }
+}
@@ -0,0 +1,166 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Moves frames between a layer-2 device descriptor and the raw-IP descriptor the
+/// engine consumes, translating with `EthernetFrameAdapter` in both directions.
+/// Decision: the engine reads and writes bare IP packets and knows nothing about
+/// ethernet, so TAP support lives entirely outside it — a datagram socketpair presents
+/// the engine its usual raw-IP fd while this pump encapsulates, decapsulates, and
+/// answers neighbor queries against the device fd. Writes that hit a full buffer drop
+/// the packet, matching kernel TUN/TAP semantics under pressure.
+/// Queue ownership: the adapter and both dispatch sources are only touched on `queue`.
+final class EthernetTapPump: @unchecked Sendable {
+    /// Largest frame one read accepts: an ethernet header plus a maximum IP packet.
+    private static let readBufferBytes = EthernetFrameAdapter.headerLength + 65_535
+    /// Frames moved per wakeup before the loop yields the queue; the level-triggered
+    /// read source re-fires while data remains, so a flood cannot pin the queue.
+    private static let drainFrameBudget = 64
+
+    /// Engine-facing end of the socketpair; hand this to the runtime as its TUN fd.
+    let engineFD: Int32
+
+    private let pumpFD: Int32
+    /// Borrowed from the owning device; the pump never closes it.
+    private let deviceFD: Int32
+    private let queue = DispatchQueue(label: "harness-local.tap-pump")
+    private var adapter: EthernetFrameAdapter
+    private var deviceSource: DispatchSourceRead?
+    private var engineSource: DispatchSourceRead?
+    private var readBuffer = [UInt8](repeating: 0, count: EthernetTapPump.readBufferBytes)
+    private var stopped = false
+
+    /// - Parameters:
+    ///   - deviceFD: Non-blocking descriptor delivering whole ethernet frames per read.
+    ///   - framing: Local addresses the adapter's ARP/NDP responders claim.
+    /// - Throws: `TunHarnessError.unavailable` when the socketpair cannot be created.
+    init(deviceFD: Int32, framing: EthernetFramingOptions) throws {
+        self.deviceFD = deviceFD
+        self.adapter = EthernetFrameAdapter(options: framing)
+
+#if os(Linux)
+        let datagramType = Int32(SOCK_DGRAM.rawValue)
+#else
+        let datagramType = SOCK_DGRAM
+#endif
+        var fds = [Int32](repeating: -1, count: 2)
+        guard socketpair(AF_UNIX, datagramType, 0, &fds) == 0 else {
+            throw TunHarnessError.unavailable(errno: errno)
+        }
+        engineFD = fds[0]
+        pumpFD = fds[1]
+        for fd in fds {
+            let flags = fcntl(fd, F_GETFL, 0)
+            guard flags >= 0, fcntl(fd, F_SETFL, flags | O_NONBLOCK) >= 0 else {
+                let failure = errno
+                close(engineFD)
+                close(pumpFD)
+                throw TunHarnessError.unavailable(errno: failure)
+            }
+        }
+    }
+
+    deinit {
+        stop()
+    }
+
+    /// Starts both directions: device frames toward the engine and engine packets
+    /// toward the device. Idempotent.
+    func start() {
+        queue.sync {
+            guard !stopped, deviceSource == nil else {
+                return
+            }
+            let device = DispatchSource.makeReadSource(fileDescriptor: deviceFD, queue: queue)
+            device.setEventHandler { [weak self] in
+                self?.drainDevice()
+            }
+            deviceSource = device
+
+            let engine = DispatchSource.makeReadSource(fileDescriptor: pumpFD, queue: queue)
+            engine.setEventHandler { [weak self] in
+                self?.drainEngine()
+            }
+            engine.setCancelHandler { [pumpFD] in
+                close(pumpFD)
+            }
+            engineSource = engine
+
+            device.resume()
+            engine.resume()
+        }
+    }
+
+    /// Stops pumping and closes the socketpair. The device fd stays open for its owner.
+    func stop() {
+        queue.sync {
+            guard !stopped else {
+                return
+            }
+            stopped = true
+            deviceSource?.cancel()
+            engineSource?.cancel()
+            deviceSource = nil
+            engineSource = nil
+            close(engineFD)
+        }
+    }
+
+    /// Responder and drop counters accumulated so far.
+    func framingStats() -> EthernetFramingStats {
+        queue.sync {
+            adapter.stats
+        }
+    }
+
+    private func drainDevice() {
+        for _ in 0 ..< Self.drainFrameBudget {
+            let count = readBuffer.withUnsafeMutableBytes { buffer in
+                read(deviceFD, buffer.baseAddress, buffer.count)
+            }
+            guard count > 0 else {
+                return
+            }
+            switch adapter.classifyInbound(frame: Data(readBuffer[0 ..< count])) {
+            case .ipPacket(_, let payload):
+                writeBestEffort(fd: pumpFD, data: payload)
+            case .respond(let frame):
+                writeBestEffort(fd: deviceFD, data: frame)
+            case .drop:
+                break
+            }
+        }
+    }
+
+    private func drainEngine() {
+        for _ in 0 ..< Self.drainFrameBudget {
+            let count = readBuffer.withUnsafeMutableBytes { buffer in
+                read(pumpFD, buffer.baseAddress, buffer.count)
+            }
+            guard count > 0 else {
+                return
+            }
+            let family: Int32 = (readBuffer[0] >> 4) == 6 ? AF_INET6 : AF_INET
+            guard let frame = adapter.frameOutbound(packet: Data(readBuffer[0 ..< count]), family: family) else {
+                continue
+            }
+            writeBestEffort(fd: deviceFD, data: frame)
+        }
+    }
+
+    /// One write attempt; a full buffer or transient error drops the packet like the
+    /// kernel would on an overrun TAP queue.
+    private func writeBestEffort(fd: Int32, data: Data) {
+        _ = data.withUnsafeBytes { buffer in
+            write(fd, buffer.baseAddress, buffer.count)
+        }
+    }
+}
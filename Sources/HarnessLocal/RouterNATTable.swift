@@ -0,0 +1,143 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Limits for the router-mode source NAT table.
+public struct RouterNATOptions: Sendable, Equatable {
+    /// Upper bound on live mappings; allocation past the cap evicts the least recently
+    /// used mapping so a chatty LAN cannot grow the table without bound.
+    public let maxMappings: Int
+    /// Idle seconds after which a mapping is reclaimed.
+    public let mappingTTLSeconds: TimeInterval
+    /// Translated source ports are allocated from this range, mirroring the kernel's
+    /// ephemeral-port convention so SNAT traffic is recognizable in captures.
+    public let portRange: ClosedRange<UInt16>
+
+    public init(
+        maxMappings: Int = 8_192,
+        mappingTTLSeconds: TimeInterval = 120,
+        portRange: ClosedRange<UInt16> = 32_768...60_999
+    ) {
+        self.maxMappings = max(1, maxMappings)
+        self.mappingTTLSeconds = mappingTTLSeconds
+        self.portRange = portRange
+    }
+
+    public static let `default` = RouterNATOptions()
+}
+
+/// One live SNAT mapping: which LAN device a translated source port belongs to.
+public struct RouterNATMapping: Sendable, Equatable {
+    public let clientAddress: String
+    public let clientPort: UInt16
+    public let transport: String
+    public let translatedPort: UInt16
+}
+
+/// Source-NAT table for router mode, where the harness TUN accepts forwarded packets from
+/// other LAN devices with arbitrary source IPs.
+/// Decision: this is a pure state machine — allocation, reverse lookup, and expiry — so it is
+/// deterministic under test; the packet rewrite itself happens where the bytes already flow.
+/// Outbound dials translate (client address, client port) to a harness-owned ephemeral port,
+/// and reverse lookups route reply traffic back to the originating device.
+public struct RouterNATTable: Sendable {
+    private struct Key: Hashable {
+        let clientAddress: String
+        let clientPort: UInt16
+        let transport: String
+    }
+
+    private struct Entry {
+        let translatedPort: UInt16
+        var lastSeen: Date
+    }
+
+    private let options: RouterNATOptions
+    private var entries: [Key: Entry] = [:]
+    private var reverseIndex: [UInt16: Key] = [:]
+    private var nextPortOffset = 0
+
+    public init(options: RouterNATOptions = .default) {
+        self.options = options
+    }
+
+    public var mappingCount: Int {
+        entries.count
+    }
+
+    /// Returns the translated source port for an outbound flow, reusing the live mapping for
+    /// a (client address, client port, transport) tuple so one device keeps a stable identity.
+    public mutating func translateOutbound(
+        clientAddress: String,
+        clientPort: UInt16,
+        transport: String,
+        now: Date
+    ) -> UInt16 {
+        expire(now: now)
+        let key = Key(clientAddress: clientAddress, clientPort: clientPort, transport: transport.lowercased())
+        if var entry = entries[key] {
+            entry.lastSeen = now
+            entries[key] = entry
+            return entry.translatedPort
+        }
+        if entries.count >= options.maxMappings {
+            evictLeastRecentlyUsed()
+        }
+        let port = allocatePort()
+        entries[key] = Entry(translatedPort: port, lastSeen: now)
+        reverseIndex[port] = key
+        return port
+    }
+
+    /// Resolves a translated source port back to the originating LAN device for reply
+    /// traffic, refreshing the mapping's idle clock. Returns `nil` for unknown or expired ports.
+    public mutating func reverseLookup(translatedPort: UInt16, now: Date) -> RouterNATMapping? {
+        expire(now: now)
+        guard let key = reverseIndex[translatedPort], var entry = entries[key] else {
+            return nil
+        }
+        entry.lastSeen = now
+        entries[key] = entry
+        return RouterNATMapping(
+            clientAddress: key.clientAddress,
+            clientPort: key.clientPort,
+            transport: key.transport,
+            translatedPort: translatedPort
+        )
+    }
+
+    private mutating func expire(now: Date) {
+        let cutoff = now.addingTimeInterval(-options.mappingTTLSeconds)
+        for (key, entry) in entries where entry.lastSeen < cutoff {
+            entries.removeValue(forKey: key)
+            reverseIndex.removeValue(forKey: entry.translatedPort)
+        }
+    }
+
+    private mutating func evictLeastRecentlyUsed() {
+        guard let oldest = entries.min(by: { $0.value.lastSeen < $1.value.lastSeen }) else {
+            return
+        }
+        entries.removeValue(forKey: oldest.key)
+        reverseIndex.removeValue(forKey: oldest.value.translatedPort)
+    }
+
+    private mutating func allocatePort() -> UInt16 {
+        let rangeSize = Int(options.portRange.upperBound) - Int(options.portRange.lowerBound) + 1
+        // Linear probe from a rotating offset; the capacity eviction above guarantees a free
+        // port exists whenever the range is at least as large as maxMappings.
+        for probe in 0..<rangeSize {
+            let offset = (nextPortOffset + probe) % rangeSize
+            let candidate = UInt16(Int(options.portRange.lowerBound) + offset)
+            if reverseIndex[candidate] == nil {
+                nextPortOffset = (offset + 1) % rangeSize
+                return candidate
+            }
+        }
+        // Range smaller than the live table: recycle the least recently used port.
+        evictLeastRecentlyUsed()
+        return allocatePort()
+    }
+}
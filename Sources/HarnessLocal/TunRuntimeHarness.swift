@@ -17,6 +17,7 @@ public enum TunHarnessError: Error, CustomStringConvertible {
     case unavailable(errno: Int32)
     case invalidDuration
     case invalidSocksPort(UInt16)
+    case tapPacketInfoUnsupported
 
     public var description: String {
         switch self {
@@ -26,6 +27,8 @@ public enum TunHarnessError: Error, CustomStringConvertible {
             return "TUN harness duration must be positive"
         case .invalidSocksPort(let port):
             return "Invalid SOCKS port \(port)"
+        case .tapPacketInfoUnsupported:
+            return "TAP frames already carry an ethertype; --include-packet-info applies only to TUN devices"
         }
     }
 }
@@ -33,32 +36,44 @@ public enum TunHarnessError: Error, CustomStringConvertible {
 public struct TunRuntimeOptions: Sendable, Equatable {
     public let requestedName: String?
     public let includePacketInfo: Bool
+    /// Opens the device as a layer-2 TAP interface instead of a TUN: `EthernetTapPump`
+    /// handles framing, MAC learning, and ARP/NDP so the engine still exchanges raw IP
+    /// packets. Linux only, like the TUN device itself.
+    public let ethernetTap: Bool
     public let mtu: Int
     public let ipv4Address: String
     public let ipv6Address: String?
     public let socksHost: String
     public let socksPort: UInt16
+    /// Gateway mode for whole-home testing: the TUN accepts forwarded packets from other LAN
+    /// devices with arbitrary source IPs, sessions are source-NATed on dial (see
+    /// `RouterNATTable`), and session limits are raised for multi-device load.
+    public let routerMode: Bool
     public let durationSeconds: TimeInterval
     public let engineLogLevel: String
 
     public init(
         requestedName: String? = nil,
         includePacketInfo: Bool = false,
+        ethernetTap: Bool = false,
         mtu: Int = 1280,
         ipv4Address: String = "10.90.0.2",
         ipv6Address: String? = nil,
         socksHost: String = "127.0.0.1",
         socksPort: UInt16 = 1080,
+        routerMode: Bool = false,
         durationSeconds: TimeInterval = 10,
         engineLogLevel: String = "warn"
     ) {
         self.requestedName = requestedName
         self.includePacketInfo = includePacketInfo
+        self.ethernetTap = ethernetTap
         self.mtu = mtu
         self.ipv4Address = ipv4Address
         self.ipv6Address = ipv6Address
         self.socksHost = socksHost
         self.socksPort = socksPort
+        self.routerMode = routerMode
         self.durationSeconds = durationSeconds
         self.engineLogLevel = engineLogLevel
     }
@@ -89,9 +104,9 @@ public extension HarnessRunner {
             throw TunHarnessError.invalidSocksPort(options.socksPort)
         }
 
-        let device = try TunPacketDevice.open(requestedName: options.requestedName, includePacketInfo: options.includePacketInfo)
+        let endpoint = try TunEngineEndpoint.open(options: options)
         defer {
-            device.close()
+            endpoint.close()
         }
 
         let logger = StructuredLogger(sink: InMemoryLogSink())
@@ -101,15 +116,15 @@ public extension HarnessRunner {
             randomSource: SystemRandomSource(),
             logger: logger
         )
-        let config = TunDataplaneConfig.make(interfaceName: device.interfaceName, options: options)
+        let config = TunDataplaneConfig.make(interfaceName: endpoint.interfaceName, options: options)
 
         do {
-            try await runtime.start(configJSON: config, tunFD: device.fd)
+            try await runtime.start(configJSON: config, tunFD: endpoint.engineFD)
             try await Task.sleep(nanoseconds: runDurationNanoseconds)
             let snapshot = await runtime.currentSnapshot()
             try await runtime.stop()
             return TunHarnessRunResult(
-                interfaceName: device.interfaceName,
+                interfaceName: endpoint.interfaceName,
                 runtimeState: snapshot.state,
                 durationSeconds: options.durationSeconds
             )
@@ -120,7 +135,58 @@ public extension HarnessRunner {
     }
 }
 
-private enum TunDataplaneConfig {
+/// The device the engine attaches to, presented as one packet descriptor: the TUN fd
+/// directly, or a TAP device behind an `EthernetTapPump` translating to raw IP.
+struct TunEngineEndpoint {
+    let device: TunPacketDevice
+    let pump: EthernetTapPump?
+
+    /// Descriptor to hand the runtime as its TUN fd.
+    var engineFD: Int32 {
+        pump?.engineFD ?? device.fd
+    }
+
+    var interfaceName: String {
+        device.interfaceName
+    }
+
+    static func open(options: TunRuntimeOptions) throws -> TunEngineEndpoint {
+        guard !(options.ethernetTap && options.includePacketInfo) else {
+            throw TunHarnessError.tapPacketInfoUnsupported
+        }
+        let device = try TunPacketDevice.open(
+            requestedName: options.requestedName,
+            includePacketInfo: options.includePacketInfo,
+            ethernetTap: options.ethernetTap
+        )
+        guard options.ethernetTap else {
+            return TunEngineEndpoint(device: device, pump: nil)
+        }
+        do {
+            // The pump's responders claim the tunnel addresses so the layer-2 peer can
+            // resolve the interface without a helper daemon on the far side.
+            let pump = try EthernetTapPump(
+                deviceFD: device.fd,
+                framing: EthernetFramingOptions(
+                    ipv4Address: options.ipv4Address,
+                    ipv6Address: options.ipv6Address
+                )
+            )
+            pump.start()
+            return TunEngineEndpoint(device: device, pump: pump)
+        } catch {
+            device.close()
+            throw error
+        }
+    }
+
+    func close() {
+        pump?.stop()
+        device.close()
+    }
+}
+
+enum TunDataplaneConfig {
     static func make(interfaceName: String, options: TunRuntimeOptions) -> String {
         var lines: [String] = []
         lines.append("tunnel:")
@@ -140,7 +206,8 @@ private enum TunDataplaneConfig {
         lines.append("  log-file: stderr")
         lines.append("  log-level: \(normalizedLogLevel(options.engineLogLevel))")
         lines.append("  task-stack-size: 65536")
-        lines.append("  max-session-count: 1024")
+        // Router mode serves a whole LAN, so allow four times the single-device session budget.
+        lines.append("  max-session-count: \(options.routerMode ? 4_096 : 1_024)")
         lines.append("  udp-recv-buffer-size: 131072")
         lines.append("  connect-timeout: 10000")
         lines.append("  tcp-read-write-timeout: 300000")
@@ -157,7 +224,7 @@ private enum TunDataplaneConfig {
     }
 }
 
-private final class TunPacketDevice: @unchecked Sendable {
+final class TunPacketDevice: @unchecked Sendable {
     let fd: Int32
     let interfaceName: String
     private var closed = false
@@ -167,29 +234,36 @@ private final class TunPacketDevice: @unchecked Sendable {
         self.interfaceName = interfaceName
     }
 
-    static func open(requestedName: String?, includePacketInfo: Bool) throws -> TunPacketDevice {
+    static func open(
+        requestedName: String?,
+        includePacketInfo: Bool,
+        ethernetTap: Bool = false
+    ) throws -> TunPacketDevice {
         var errnoValue: CInt = 0
         var nameBuffer = [CChar](repeating: 0, count: 64)
-        let fd: Int32
-        if let requestedName {
-            fd = requestedName.withCString { requestedNamePointer in
-                rp_harness_open_tun(
+        let openDevice: (UnsafePointer<CChar>?) -> Int32 = { requestedNamePointer in
+            if ethernetTap {
+                return rp_harness_open_tap(
                     requestedNamePointer,
-                    includePacketInfo ? 1 : 0,
                     &nameBuffer,
                     nameBuffer.count,
                     &errnoValue
                 )
             }
-        } else {
-            fd = rp_harness_open_tun(
-                nil,
+            return rp_harness_open_tun(
+                requestedNamePointer,
                 includePacketInfo ? 1 : 0,
                 &nameBuffer,
                 nameBuffer.count,
                 &errnoValue
             )
         }
+        let fd: Int32
+        if let requestedName {
+            fd = requestedName.withCString { openDevice($0) }
+        } else {
+            fd = openDevice(nil)
+        }
 
         guard fd >= 0 else {
             throw TunHarnessError.unavailable(errno: Int32(errnoValue))
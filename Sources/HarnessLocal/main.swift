@@ -2,6 +2,7 @@
 // Copyright (c) 2026 Relative Companies, Inc.
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
+import DataplaneFFI
 import Foundation
 import Observability
 
@@ -23,13 +24,21 @@ private enum HarnessCommand {
     case synthetic(URL)
     case pcap(URL, HarnessScenario, PcapReplayOptions)
     case tun(TunRuntimeOptions)
+    case tunDaemon(TunRuntimeOptions, DaemonOptions)
+    case capture(RawCaptureOptions, HarnessScenario)
+    case benchmark(BenchmarkOptions)
+    case replayTrace(URL, respectTiming: Bool)
 }
 
 private let usageText = """
 Usage:
   HarnessLocal <scenario.json>
   HarnessLocal --pcap <capture.pcap> [--max-packets N] [--direction outbound|inbound] [--scenario scenario.json]
-  HarnessLocal --tun [--name rp0] [--duration seconds] [--mtu bytes] [--ipv4 address] [--ipv6 address] [--socks-host host] [--socks-port port] [--include-packet-info] [--log-level warn]
+  HarnessLocal --tun [--name rp0] [--duration seconds] [--mtu bytes] [--ipv4 address] [--ipv6 address] [--socks-host host] [--socks-port port] [--include-packet-info] [--tap] [--router] [--log-level warn]
+               [--daemon [--pid-file path] [--config daemon.json] [--log-dir path] [--log-max-bytes n] [--log-max-files n]]
+  HarnessLocal --capture <interface> [--promiscuous] [--max-packets N] [--duration seconds] [--direction inbound|outbound] [--scenario scenario.json]
+  HarnessLocal --benchmark [--duration seconds] [--message-bytes n] [--udp-bytes n] [--bind address]
+  HarnessLocal --replay-ffi <trace.jsonl> [--respect-timing]
 """
 
 /// CLI entrypoint for deterministic local harness runs.
@@ -74,6 +83,51 @@ private func runHarness() async -> Int32 {
                 "duration": String(result.durationSeconds),
                 "source": "tun"
             ])
+        case .tunDaemon(let options, let daemonOptions):
+            let result = try await runner.runTunDaemon(options: options, daemon: daemonOptions, rootPath: root)
+            print([
+                "interface": result.interfaceName,
+                "reloads": String(result.reloadCount),
+                "signal": String(result.terminationSignal),
+                "source": "tun-daemon"
+            ])
+        case .capture(let options, let scenario):
+            let result = try await runner.run(
+                scenario: scenario,
+                adapter: RawSocketCaptureAdapter(options: options),
+                rootPath: root
+            )
+            print([
+                "scenario": result.scenarioID,
+                "state": result.runtimeState.rawValue,
+                "packets": String(result.packetCount),
+                "source": "capture"
+            ])
+        case .benchmark(let options):
+            let result = try BenchmarkHarness.run(options: options)
+            print([
+                "tcp_mbps": String(format: "%.1f", result.tcpMegabitsPerSecond),
+                "tcp_retransmits": String(result.tcpRetransmits),
+                "udp_mbps": String(format: "%.1f", result.udpMegabitsPerSecond),
+                "udp_sent": String(result.udpDatagramsSent),
+                "udp_received": String(result.udpDatagramsReceived),
+                "cpu_user_s": String(format: "%.3f", result.cpuUserSeconds),
+                "cpu_system_s": String(format: "%.3f", result.cpuSystemSeconds),
+                "source": "benchmark"
+            ])
+        case .replayTrace(let traceURL, let respectTiming):
+            let entries = try DataplaneTraceRecorder.load(from: traceURL)
+            let result = try await DataplaneTraceReplayer.replay(
+                entries: entries,
+                logger: logger,
+                respectTiming: respectTiming
+            )
+            print([
+                "entries": String(entries.count),
+                "applied": String(result.appliedCalls),
+                "failed": result.failedCallIndices.map(String.init).joined(separator: ","),
+                "source": "replay-ffi"
+            ])
         }
 
         return 0
@@ -114,6 +168,17 @@ private func parseCommand(_ args: [String]) throws -> HarnessCommand {
             PcapReplayOptions(maximumPackets: maximumPackets, direction: direction)
         )
     case "--tun":
+        // The engine has no WireGuard datapath; reject the flags loudly instead of
+        // letting the flag scan silently ignore them.
+        if let wireguardFlag = args.first(where: { $0.hasPrefix("--wg-") }) {
+            throw HarnessUsageError.invalidArgument("\(wireguardFlag) (WireGuard upstream is not supported; the engine egresses through the local SOCKS relay)")
+        }
+        // Same story for TLS inspection: the engine config parser has no tls-inspect
+        // section, so nothing would ever terminate TLS. Fail loudly rather than
+        // pretend to honor the flags.
+        if let tlsInspectFlag = args.first(where: { $0.hasPrefix("--tls-inspect-") }) {
+            throw HarnessUsageError.invalidArgument("\(tlsInspectFlag) (TLS inspection is not supported; the engine relays TLS bytes opaquely)")
+        }
         let requestedName = try optionalStringValue(args, flag: "--name")
         let duration = try optionalDoubleValue(args, flag: "--duration") ?? 10
         let mtu = try optionalIntValue(args, flag: "--mtu") ?? 1280
@@ -122,19 +187,72 @@ private func parseCommand(_ args: [String]) throws -> HarnessCommand {
         let socksHost = try optionalStringValue(args, flag: "--socks-host") ?? "127.0.0.1"
         let socksPort = try parseUInt16(args, flag: "--socks-port", defaultValue: 1080)
         let logLevel = try optionalStringValue(args, flag: "--log-level") ?? "warn"
-        return .tun(
-            TunRuntimeOptions(
-                requestedName: requestedName,
-                includePacketInfo: args.contains("--include-packet-info"),
-                mtu: mtu,
-                ipv4Address: ipv4,
-                ipv6Address: ipv6,
-                socksHost: socksHost,
-                socksPort: socksPort,
+        let tunOptions = TunRuntimeOptions(
+            requestedName: requestedName,
+            includePacketInfo: args.contains("--include-packet-info"),
+            ethernetTap: args.contains("--tap"),
+            mtu: mtu,
+            ipv4Address: ipv4,
+            ipv6Address: ipv6,
+            socksHost: socksHost,
+            socksPort: socksPort,
+            routerMode: args.contains("--router"),
+            durationSeconds: duration,
+            engineLogLevel: logLevel
+        )
+        guard args.contains("--daemon") else {
+            return .tun(tunOptions)
+        }
+        return .tunDaemon(
+            tunOptions,
+            DaemonOptions(
+                pidFilePath: try optionalStringValue(args, flag: "--pid-file") ?? "/tmp/harness-local.pid",
+                configFilePath: try optionalStringValue(args, flag: "--config"),
+                logDirectoryPath: try optionalStringValue(args, flag: "--log-dir"),
+                maxLogFileBytes: try optionalIntValue(args, flag: "--log-max-bytes") ?? 1_048_576,
+                maxLogFiles: try optionalIntValue(args, flag: "--log-max-files") ?? 5
+            )
+        )
+    case "--capture":
+        guard args.count >= 2, !args[1].hasPrefix("--") else {
+            throw HarnessUsageError.missingArgument("interface")
+        }
+        let maximumPackets = try optionalIntValue(args, flag: "--max-packets")
+        let duration = try optionalDoubleValue(args, flag: "--duration") ?? 5
+        let direction = try optionalStringValue(args, flag: "--direction") ?? "inbound"
+        let scenarioURL = try optionalStringValue(args, flag: "--scenario").map(URL.init(fileURLWithPath:))
+        let scenario = try scenarioURL.map(HarnessScenario.load(from:)) ?? defaultScenario(id: "live-capture")
+        return .capture(
+            RawCaptureOptions(
+                interfaceName: args[1],
+                promiscuous: args.contains("--promiscuous"),
+                maximumPackets: maximumPackets,
+                durationSeconds: duration,
+                direction: direction
+            ),
+            scenario
+        )
+    case "--benchmark":
+        let duration = try optionalDoubleValue(args, flag: "--duration") ?? 3
+        let messageBytes = try optionalIntValue(args, flag: "--message-bytes") ?? 65_536
+        let udpBytes = try optionalIntValue(args, flag: "--udp-bytes") ?? 1_200
+        let bindAddress = try optionalStringValue(args, flag: "--bind") ?? "127.0.0.1"
+        return .benchmark(
+            BenchmarkOptions(
                 durationSeconds: duration,
-                engineLogLevel: logLevel
+                messageBytes: messageBytes,
+                udpDatagramBytes: udpBytes,
+                bindAddress: bindAddress
             )
         )
+    case "--replay-ffi":
+        guard args.count >= 2 else {
+            throw HarnessUsageError.missingArgument("trace.jsonl")
+        }
+        return .replayTrace(
+            URL(fileURLWithPath: args[1]),
+            respectTiming: args.contains("--respect-timing")
+        )
     default:
         guard !first.hasPrefix("--") else {
             throw HarnessUsageError.invalidArgument(first)
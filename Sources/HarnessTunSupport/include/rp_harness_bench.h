@@ -0,0 +1,45 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+#ifndef RP_HARNESS_BENCH_H
+#define RP_HARNESS_BENCH_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Result of one self-benchmark run: a TCP phase and a UDP phase, each blasting
+ * a loopback sender/receiver pair for the configured duration. */
+typedef struct {
+    double tcp_megabits_per_second;
+    uint64_t tcp_bytes_transferred;
+    /* Sender-side retransmit count read from the kernel after the TCP phase;
+     * 0 when the platform exposes no per-socket retransmit counter. */
+    uint64_t tcp_retransmits;
+    double udp_megabits_per_second;
+    uint64_t udp_datagrams_sent;
+    uint64_t udp_datagrams_received;
+    double cpu_user_seconds;
+    double cpu_system_seconds;
+} rp_harness_bench_result_t;
+
+/* Runs an iperf-style TCP/UDP load between two endpoints bound to
+ * `bind_address` (ephemeral ports) for `duration_seconds` per phase. Bind the
+ * endpoints to a tunnel-routed address to push the load through the engine;
+ * the loopback default measures the host socket path the engine relies on.
+ * Returns 0 on success or -1 with `out_errno` set. */
+int rp_harness_run_benchmark(const char *bind_address,
+                             double duration_seconds,
+                             int message_bytes,
+                             int udp_datagram_bytes,
+                             rp_harness_bench_result_t *out_result,
+                             int *out_errno);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
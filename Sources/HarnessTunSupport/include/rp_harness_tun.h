@@ -18,7 +18,38 @@ int rp_harness_open_tun(const char *requested_name,
                         size_t actual_name_len,
                         int *out_errno);
 
+/* Opens a layer-2 TAP device instead of a TUN: reads and writes move whole ethernet
+ * frames with no packet-info prefix. Linux only; other platforms fail with ENOTSUP.
+ * Returns the device fd, or -1 with *out_errno set. */
+int rp_harness_open_tap(const char *requested_name,
+                        char *actual_name,
+                        size_t actual_name_len,
+                        int *out_errno);
+
+/* Opens a non-blocking AF_PACKET capture socket bound to one interface, observing
+ * real NIC traffic without touching routing tables. Linux only; other platforms
+ * fail with ENOTSUP. Returns the socket fd, or -1 with *out_errno set. */
+int rp_harness_open_packet_capture(const char *interface_name,
+                                   int promiscuous,
+                                   int *out_errno);
+
 ssize_t rp_harness_read_fd(int fd, void *buffer, size_t buffer_len, int *out_errno);
+
+/* Upper bound on packets one rp_harness_read_packets call can return. */
+#define RP_HARNESS_MAX_READ_BATCH 64
+
+/* Drains up to packet_capacity packets in one syscall where the platform supports
+ * batching (recvmmsg on Linux sockets), falling back to a single read() elsewhere
+ * and on non-socket fds such as TUN character devices. `slab` is one preallocated
+ * region of packet_capacity * buffer_len bytes; packet i lands at offset
+ * i * buffer_len and its byte count is written to out_lengths[i]. Returns the
+ * number of packets read, or -1 with *out_errno set (EAGAIN when no data). */
+int rp_harness_read_packets(int fd,
+                            void *slab,
+                            size_t buffer_len,
+                            unsigned int packet_capacity,
+                            unsigned int *out_lengths,
+                            int *out_errno);
 ssize_t rp_harness_write_fd(int fd, const void *buffer, size_t buffer_len, int *out_errno);
 int rp_harness_close_fd(int fd);
 
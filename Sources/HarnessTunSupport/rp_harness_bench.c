@@ -0,0 +1,342 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+#include "rp_harness_bench.h"
+
+#include <arpa/inet.h>
+#include <errno.h>
+#include <netinet/in.h>
+#include <netinet/tcp.h>
+#include <pthread.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/resource.h>
+#include <sys/socket.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+#define RP_BENCH_RECV_BUFFER 65536
+
+static double rp_bench_now_seconds(void)
+{
+    struct timespec now;
+    clock_gettime(CLOCK_MONOTONIC, &now);
+    return (double)now.tv_sec + (double)now.tv_nsec / 1e9;
+}
+
+static void rp_bench_set_errno(int *out_errno, int value)
+{
+    if (out_errno != NULL) {
+        *out_errno = value;
+    }
+}
+
+static int rp_bench_bind_ephemeral(int type, const char *bind_address,
+                                   struct sockaddr_in *out_addr, int *out_errno)
+{
+    int fd = socket(AF_INET, type, 0);
+    if (fd < 0) {
+        rp_bench_set_errno(out_errno, errno);
+        return -1;
+    }
+
+    struct sockaddr_in addr;
+    memset(&addr, 0, sizeof(addr));
+    addr.sin_family = AF_INET;
+    addr.sin_port = 0;
+    if (inet_pton(AF_INET, bind_address, &addr.sin_addr) != 1) {
+        rp_bench_set_errno(out_errno, EINVAL);
+        close(fd);
+        return -1;
+    }
+
+    if (bind(fd, (struct sockaddr *)&addr, sizeof(addr)) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        close(fd);
+        return -1;
+    }
+
+    socklen_t addr_len = sizeof(addr);
+    if (getsockname(fd, (struct sockaddr *)&addr, &addr_len) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        close(fd);
+        return -1;
+    }
+
+    *out_addr = addr;
+    return fd;
+}
+
+/* Reads the sender socket's kernel retransmit counter after the TCP phase so
+ * the benchmark reports loss-recovery work, not just goodput. */
+static uint64_t rp_bench_tcp_retransmits(int fd)
+{
+#if defined(__APPLE__)
+    struct tcp_connection_info info;
+    socklen_t len = sizeof(info);
+    if (getsockopt(fd, IPPROTO_TCP, TCP_CONNECTION_INFO, &info, &len) == 0) {
+        return (uint64_t)info.tcpi_txretransmitpackets;
+    }
+    return 0;
+#elif defined(__linux__)
+    struct tcp_info info;
+    socklen_t len = sizeof(info);
+    if (getsockopt(fd, IPPROTO_TCP, TCP_INFO, &info, &len) == 0) {
+        return (uint64_t)info.tcpi_total_retrans;
+    }
+    return 0;
+#else
+    (void)fd;
+    return 0;
+#endif
+}
+
+struct rp_bench_tcp_receiver {
+    int listen_fd;
+    uint64_t bytes_received;
+};
+
+static void *rp_bench_tcp_receiver_main(void *ctx)
+{
+    struct rp_bench_tcp_receiver *receiver = (struct rp_bench_tcp_receiver *)ctx;
+    char buffer[RP_BENCH_RECV_BUFFER];
+
+    int fd = accept(receiver->listen_fd, NULL, NULL);
+    if (fd < 0) {
+        return NULL;
+    }
+    for (;;) {
+        ssize_t count = recv(fd, buffer, sizeof(buffer), 0);
+        if (count <= 0) {
+            break;
+        }
+        receiver->bytes_received += (uint64_t)count;
+    }
+    close(fd);
+    return NULL;
+}
+
+struct rp_bench_udp_receiver {
+    int fd;
+    uint64_t datagrams_received;
+    uint64_t bytes_received;
+    pthread_mutex_t lock;
+    int stopped;
+};
+
+static void *rp_bench_udp_receiver_main(void *ctx)
+{
+    struct rp_bench_udp_receiver *receiver = (struct rp_bench_udp_receiver *)ctx;
+    char buffer[RP_BENCH_RECV_BUFFER];
+
+    for (;;) {
+        ssize_t count = recv(receiver->fd, buffer, sizeof(buffer), 0);
+        if (count > 0) {
+            receiver->datagrams_received++;
+            receiver->bytes_received += (uint64_t)count;
+            continue;
+        }
+        pthread_mutex_lock(&receiver->lock);
+        int stopped = receiver->stopped;
+        pthread_mutex_unlock(&receiver->lock);
+        if (stopped != 0) {
+            break;
+        }
+    }
+    return NULL;
+}
+
+static int rp_bench_run_tcp(const char *bind_address, double duration_seconds,
+                            int message_bytes, rp_harness_bench_result_t *result,
+                            int *out_errno)
+{
+    struct sockaddr_in addr;
+    int listen_fd = rp_bench_bind_ephemeral(SOCK_STREAM, bind_address, &addr, out_errno);
+    if (listen_fd < 0) {
+        return -1;
+    }
+    if (listen(listen_fd, 1) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        close(listen_fd);
+        return -1;
+    }
+
+    struct rp_bench_tcp_receiver receiver;
+    memset(&receiver, 0, sizeof(receiver));
+    receiver.listen_fd = listen_fd;
+    pthread_t thread;
+    if (pthread_create(&thread, NULL, rp_bench_tcp_receiver_main, &receiver) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        close(listen_fd);
+        return -1;
+    }
+
+    int sender_fd = socket(AF_INET, SOCK_STREAM, 0);
+    if (sender_fd < 0 ||
+        connect(sender_fd, (struct sockaddr *)&addr, sizeof(addr)) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        if (sender_fd >= 0) {
+            close(sender_fd);
+        }
+        close(listen_fd);
+        pthread_join(thread, NULL);
+        return -1;
+    }
+
+    char *message = (char *)calloc((size_t)message_bytes, sizeof(char));
+    if (message == NULL) {
+        rp_bench_set_errno(out_errno, ENOMEM);
+        close(sender_fd);
+        close(listen_fd);
+        pthread_join(thread, NULL);
+        return -1;
+    }
+
+    double started = rp_bench_now_seconds();
+    double deadline = started + duration_seconds;
+    while (rp_bench_now_seconds() < deadline) {
+        ssize_t written = send(sender_fd, message, (size_t)message_bytes, 0);
+        if (written < 0 && errno != EINTR) {
+            break;
+        }
+    }
+    result->tcp_retransmits = rp_bench_tcp_retransmits(sender_fd);
+    shutdown(sender_fd, SHUT_WR);
+    pthread_join(thread, NULL);
+    double elapsed = rp_bench_now_seconds() - started;
+
+    free(message);
+    close(sender_fd);
+    close(listen_fd);
+
+    result->tcp_bytes_transferred = receiver.bytes_received;
+    result->tcp_megabits_per_second =
+        elapsed > 0 ? (double)receiver.bytes_received * 8.0 / elapsed / 1e6 : 0;
+    return 0;
+}
+
+static int rp_bench_run_udp(const char *bind_address, double duration_seconds,
+                            int udp_datagram_bytes,
+                            rp_harness_bench_result_t *result, int *out_errno)
+{
+    struct sockaddr_in addr;
+    int receiver_fd = rp_bench_bind_ephemeral(SOCK_DGRAM, bind_address, &addr, out_errno);
+    if (receiver_fd < 0) {
+        return -1;
+    }
+
+    /* Bounded receive timeout so the receiver thread can observe the stop flag
+     * after the sender finishes instead of blocking forever. */
+    struct timeval timeout;
+    timeout.tv_sec = 0;
+    timeout.tv_usec = 200000;
+    (void)setsockopt(receiver_fd, SOL_SOCKET, SO_RCVTIMEO, &timeout, sizeof(timeout));
+
+    struct rp_bench_udp_receiver receiver;
+    memset(&receiver, 0, sizeof(receiver));
+    receiver.fd = receiver_fd;
+    if (pthread_mutex_init(&receiver.lock, NULL) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        close(receiver_fd);
+        return -1;
+    }
+    pthread_t thread;
+    if (pthread_create(&thread, NULL, rp_bench_udp_receiver_main, &receiver) != 0) {
+        rp_bench_set_errno(out_errno, errno);
+        pthread_mutex_destroy(&receiver.lock);
+        close(receiver_fd);
+        return -1;
+    }
+
+    int sender_fd = socket(AF_INET, SOCK_DGRAM, 0);
+    char *message = sender_fd >= 0
+        ? (char *)calloc((size_t)udp_datagram_bytes, sizeof(char))
+        : NULL;
+    if (sender_fd < 0 || message == NULL) {
+        rp_bench_set_errno(out_errno, sender_fd < 0 ? errno : ENOMEM);
+        pthread_mutex_lock(&receiver.lock);
+        receiver.stopped = 1;
+        pthread_mutex_unlock(&receiver.lock);
+        pthread_join(thread, NULL);
+        pthread_mutex_destroy(&receiver.lock);
+        if (sender_fd >= 0) {
+            close(sender_fd);
+        }
+        close(receiver_fd);
+        return -1;
+    }
+
+    double started = rp_bench_now_seconds();
+    double deadline = started + duration_seconds;
+    uint64_t sent = 0;
+    while (rp_bench_now_seconds() < deadline) {
+        ssize_t written = sendto(sender_fd, message, (size_t)udp_datagram_bytes, 0,
+                                 (struct sockaddr *)&addr, sizeof(addr));
+        if (written > 0) {
+            sent++;
+        } else if (errno == ENOBUFS || errno == EAGAIN) {
+            /* Loopback send buffer pressure; yield instead of spinning. */
+            usleep(100);
+        } else if (errno != EINTR) {
+            break;
+        }
+    }
+    double elapsed = rp_bench_now_seconds() - started;
+
+    pthread_mutex_lock(&receiver.lock);
+    receiver.stopped = 1;
+    pthread_mutex_unlock(&receiver.lock);
+    pthread_join(thread, NULL);
+    pthread_mutex_destroy(&receiver.lock);
+
+    free(message);
+    close(sender_fd);
+    close(receiver_fd);
+
+    result->udp_datagrams_sent = sent;
+    result->udp_datagrams_received = receiver.datagrams_received;
+    result->udp_megabits_per_second =
+        elapsed > 0 ? (double)receiver.bytes_received * 8.0 / elapsed / 1e6 : 0;
+    return 0;
+}
+
+int rp_harness_run_benchmark(const char *bind_address,
+                             double duration_seconds,
+                             int message_bytes,
+                             int udp_datagram_bytes,
+                             rp_harness_bench_result_t *out_result,
+                             int *out_errno)
+{
+    if (bind_address == NULL || out_result == NULL || duration_seconds <= 0 ||
+        message_bytes <= 0 || udp_datagram_bytes <= 0 ||
+        udp_datagram_bytes > 65507) {
+        rp_bench_set_errno(out_errno, EINVAL);
+        return -1;
+    }
+    memset(out_result, 0, sizeof(*out_result));
+    rp_bench_set_errno(out_errno, 0);
+
+    struct rusage usage_before;
+    getrusage(RUSAGE_SELF, &usage_before);
+
+    if (rp_bench_run_tcp(bind_address, duration_seconds, message_bytes,
+                         out_result, out_errno) != 0) {
+        return -1;
+    }
+    if (rp_bench_run_udp(bind_address, duration_seconds, udp_datagram_bytes,
+                         out_result, out_errno) != 0) {
+        return -1;
+    }
+
+    struct rusage usage_after;
+    getrusage(RUSAGE_SELF, &usage_after);
+    out_result->cpu_user_seconds =
+        (double)(usage_after.ru_utime.tv_sec - usage_before.ru_utime.tv_sec) +
+        (double)(usage_after.ru_utime.tv_usec - usage_before.ru_utime.tv_usec) / 1e6;
+    out_result->cpu_system_seconds =
+        (double)(usage_after.ru_stime.tv_sec - usage_before.ru_stime.tv_sec) +
+        (double)(usage_after.ru_stime.tv_usec - usage_before.ru_stime.tv_usec) / 1e6;
+    return 0;
+}
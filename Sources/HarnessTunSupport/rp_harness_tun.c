@@ -2,6 +2,11 @@
 // Copyright (c) 2026 Relative Companies, Inc.
 // Licensed for personal, non-commercial use only. See LICENSE for terms.
 
+#if defined(__linux__)
+/* recvmmsg and struct mmsghdr are GNU extensions. */
+#define _GNU_SOURCE
+#endif
+
 #include "rp_harness_tun.h"
 
 #include <errno.h>
@@ -9,10 +14,14 @@
 #include <unistd.h>
 
 #if defined(__linux__)
+#include <arpa/inet.h>
 #include <fcntl.h>
 #include <linux/if.h>
+#include <linux/if_ether.h>
+#include <linux/if_packet.h>
 #include <linux/if_tun.h>
 #include <sys/ioctl.h>
+#include <sys/socket.h>
 
 #ifndef O_CLOEXEC
 #define O_CLOEXEC 0
@@ -25,11 +34,11 @@ static void rp_set_errno(int *out_errno)
     }
 }
 
-int rp_harness_open_tun(const char *requested_name,
-                        int include_packet_info,
-                        char *actual_name,
-                        size_t actual_name_len,
-                        int *out_errno)
+static int rp_harness_open_tuntap(short interface_flags,
+                                  const char *requested_name,
+                                  char *actual_name,
+                                  size_t actual_name_len,
+                                  int *out_errno)
 {
     int fd = open("/dev/net/tun", O_RDWR | O_CLOEXEC);
     if (fd < 0) {
@@ -39,10 +48,7 @@ int rp_harness_open_tun(const char *requested_name,
 
     struct ifreq request;
     memset(&request, 0, sizeof(request));
-    request.ifr_flags = IFF_TUN;
-    if (!include_packet_info) {
-        request.ifr_flags |= IFF_NO_PI;
-    }
+    request.ifr_flags = interface_flags;
 
     if (requested_name != NULL && requested_name[0] != '\0') {
         size_t name_len = strnlen(requested_name, IFNAMSIZ - 1);
@@ -78,6 +84,89 @@ int rp_harness_open_tun(const char *requested_name,
     return fd;
 }
 
+int rp_harness_open_tun(const char *requested_name,
+                        int include_packet_info,
+                        char *actual_name,
+                        size_t actual_name_len,
+                        int *out_errno)
+{
+    short interface_flags = IFF_TUN;
+    if (!include_packet_info) {
+        interface_flags |= IFF_NO_PI;
+    }
+    return rp_harness_open_tuntap(interface_flags, requested_name,
+                                  actual_name, actual_name_len, out_errno);
+}
+
+int rp_harness_open_tap(const char *requested_name,
+                        char *actual_name,
+                        size_t actual_name_len,
+                        int *out_errno)
+{
+    /* TAP frames carry the ethertype in the ethernet header already, so the
+     * packet-info prefix would be redundant; always open without it. */
+    return rp_harness_open_tuntap(IFF_TAP | IFF_NO_PI, requested_name,
+                                  actual_name, actual_name_len, out_errno);
+}
+
+int rp_harness_open_packet_capture(const char *interface_name,
+                                   int promiscuous,
+                                   int *out_errno)
+{
+    if (interface_name == NULL || interface_name[0] == '\0') {
+        if (out_errno != NULL) {
+            *out_errno = EINVAL;
+        }
+        return -1;
+    }
+
+    int fd = socket(AF_PACKET, SOCK_RAW | SOCK_NONBLOCK | SOCK_CLOEXEC, htons(ETH_P_ALL));
+    if (fd < 0) {
+        rp_set_errno(out_errno);
+        return -1;
+    }
+
+    struct ifreq request;
+    memset(&request, 0, sizeof(request));
+    size_t name_len = strnlen(interface_name, IFNAMSIZ - 1);
+    memcpy(request.ifr_name, interface_name, name_len);
+    request.ifr_name[name_len] = '\0';
+    if (ioctl(fd, SIOCGIFINDEX, &request) < 0) {
+        rp_set_errno(out_errno);
+        close(fd);
+        return -1;
+    }
+
+    struct sockaddr_ll address;
+    memset(&address, 0, sizeof(address));
+    address.sll_family = AF_PACKET;
+    address.sll_protocol = htons(ETH_P_ALL);
+    address.sll_ifindex = request.ifr_ifindex;
+    if (bind(fd, (struct sockaddr *)&address, sizeof(address)) < 0) {
+        rp_set_errno(out_errno);
+        close(fd);
+        return -1;
+    }
+
+    if (promiscuous) {
+        struct packet_mreq membership;
+        memset(&membership, 0, sizeof(membership));
+        membership.mr_ifindex = request.ifr_ifindex;
+        membership.mr_type = PACKET_MR_PROMISC;
+        if (setsockopt(fd, SOL_PACKET, PACKET_ADD_MEMBERSHIP,
+                       &membership, sizeof(membership)) < 0) {
+            rp_set_errno(out_errno);
+            close(fd);
+            return -1;
+        }
+    }
+
+    if (out_errno != NULL) {
+        *out_errno = 0;
+    }
+    return fd;
+}
+
 #else
 
 int rp_harness_open_tun(const char *requested_name,
@@ -97,6 +186,122 @@ int rp_harness_open_tun(const char *requested_name,
     return -1;
 }
 
+int rp_harness_open_tap(const char *requested_name,
+                        char *actual_name,
+                        size_t actual_name_len,
+                        int *out_errno)
+{
+    (void)requested_name;
+    if (actual_name != NULL && actual_name_len > 0) {
+        actual_name[0] = '\0';
+    }
+    if (out_errno != NULL) {
+        *out_errno = ENOTSUP;
+    }
+    return -1;
+}
+
+int rp_harness_open_packet_capture(const char *interface_name,
+                                   int promiscuous,
+                                   int *out_errno)
+{
+    (void)interface_name;
+    (void)promiscuous;
+    if (out_errno != NULL) {
+        *out_errno = ENOTSUP;
+    }
+    return -1;
+}
+
+#endif
+
+#if defined(__linux__)
+
+int rp_harness_read_packets(int fd,
+                            void *slab,
+                            size_t buffer_len,
+                            unsigned int packet_capacity,
+                            unsigned int *out_lengths,
+                            int *out_errno)
+{
+    if (slab == NULL || out_lengths == NULL || buffer_len == 0 || packet_capacity == 0) {
+        if (out_errno != NULL) {
+            *out_errno = EINVAL;
+        }
+        return -1;
+    }
+    if (packet_capacity > RP_HARNESS_MAX_READ_BATCH) {
+        packet_capacity = RP_HARNESS_MAX_READ_BATCH;
+    }
+
+    struct mmsghdr messages[RP_HARNESS_MAX_READ_BATCH];
+    struct iovec vectors[RP_HARNESS_MAX_READ_BATCH];
+    memset(messages, 0, sizeof(struct mmsghdr) * packet_capacity);
+    for (unsigned int i = 0; i < packet_capacity; i++) {
+        vectors[i].iov_base = (char *)slab + (size_t)i * buffer_len;
+        vectors[i].iov_len = buffer_len;
+        messages[i].msg_hdr.msg_iov = &vectors[i];
+        messages[i].msg_hdr.msg_iovlen = 1;
+    }
+
+    int received = recvmmsg(fd, messages, packet_capacity, 0, NULL);
+    if (received >= 0) {
+        for (int i = 0; i < received; i++) {
+            out_lengths[i] = messages[i].msg_len;
+        }
+        if (out_errno != NULL) {
+            *out_errno = 0;
+        }
+        return received;
+    }
+    if (errno != ENOTSOCK) {
+        rp_set_errno(out_errno);
+        return -1;
+    }
+
+    /* TUN character devices are not sockets; one read() still returns one packet. */
+    ssize_t result = read(fd, slab, buffer_len);
+    if (result < 0) {
+        rp_set_errno(out_errno);
+        return -1;
+    }
+    if (out_errno != NULL) {
+        *out_errno = 0;
+    }
+    out_lengths[0] = (unsigned int)result;
+    return result == 0 ? 0 : 1;
+}
+
+#else
+
+int rp_harness_read_packets(int fd,
+                            void *slab,
+                            size_t buffer_len,
+                            unsigned int packet_capacity,
+                            unsigned int *out_lengths,
+                            int *out_errno)
+{
+    if (slab == NULL || out_lengths == NULL || buffer_len == 0 || packet_capacity == 0) {
+        if (out_errno != NULL) {
+            *out_errno = EINVAL;
+        }
+        return -1;
+    }
+
+    ssize_t result = read(fd, slab, buffer_len);
+    if (result < 0) {
+        if (out_errno != NULL) {
+            *out_errno = errno;
+        }
+        return -1;
+    }
+    if (out_errno != NULL) {
+        *out_errno = 0;
+    }
+    out_lengths[0] = (unsigned int)result;
+    return result == 0 ? 0 : 1;
+}
+
 #endif
 
 ssize_t rp_harness_read_fd(int fd, void *buffer, size_t buffer_len, int *out_errno)
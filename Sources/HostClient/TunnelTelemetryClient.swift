@@ -103,6 +103,53 @@ public struct TunnelTelemetryClient: Sendable {
         }
     }
 
+    public func compressedSnapshot(
+        from connection: NEVPNConnection,
+        packetLimit: Int? = nil,
+        includeValidationRecords: Bool? = nil
+    ) async throws -> TunnelTelemetryCompressedSnapshot {
+        guard let session = connection as? NETunnelProviderSession else {
+            throw TunnelTelemetryClientError.invalidSession
+        }
+        return try await compressedSnapshot(
+            from: session,
+            packetLimit: packetLimit,
+            includeValidationRecords: includeValidationRecords
+        )
+    }
+
+    /// Fetches a snapshot as an LZ4 blob suitable for forwarding to a server verbatim.
+    /// Contract: providers that cannot compress answer with a plain snapshot; this method
+    /// compresses that fallback locally so callers always receive the compressed envelope.
+    public func compressedSnapshot(
+        from session: NETunnelProviderSession,
+        packetLimit: Int? = nil,
+        includeValidationRecords: Bool? = nil
+    ) async throws -> TunnelTelemetryCompressedSnapshot {
+        let response = try await send(
+            TunnelTelemetryRequest.snapshot(
+                packetLimit: packetLimit,
+                includeValidationRecords: includeValidationRecords,
+                compressSnapshot: true
+            ),
+            through: session
+        )
+        switch response.kind {
+        case .snapshot:
+            if let compressed = response.compressedSnapshot {
+                return compressed
+            }
+            guard let snapshot = response.snapshot else {
+                throw TunnelTelemetryClientError.providerReturnedNoResponse
+            }
+            return try TunnelTelemetryMessageCodec.compressSnapshot(snapshot)
+        case .failure:
+            throw TunnelTelemetryClientError.providerFailure(response.message ?? "unknown")
+        case .cleared, .flushed:
+            throw TunnelTelemetryClientError.unexpectedResponseKind(response.kind.rawValue)
+        }
+    }
+
     public func clearRecentEvents(from connection: NEVPNConnection) async throws {
         guard let session = connection as? NETunnelProviderSession else {
             throw TunnelTelemetryClientError.invalidSession
@@ -4,6 +4,33 @@
 
 import Foundation
 
+/// Point-in-time snapshot of what a `StructuredLogger` is actually recording, so multi-module
+/// hosts can verify effective logging configuration at runtime instead of trusting bootstrap
+/// parameters that a sink wrapper may have tightened since.
+public struct LoggingState: Sendable, Equatable {
+    /// Lowest severity the logger records; everything below is dropped before the sink.
+    public let minimumLevel: LogLevel
+    /// The severities the logger currently records, in ascending order. Redundant with
+    /// `minimumLevel` but spares callers from re-deriving the severity ordering.
+    public let enabledLevels: [LogLevel]
+    /// Cumulative rate-limit suppression counts per category since construction or the last reset.
+    public let suppressedEventCounts: [LogCategory: Int]
+
+    /// - Parameters:
+    ///   - minimumLevel: Lowest severity the logger records.
+    ///   - enabledLevels: Severities at or above the minimum, ascending.
+    ///   - suppressedEventCounts: Per-category rate-limit suppression counts.
+    public init(
+        minimumLevel: LogLevel,
+        enabledLevels: [LogLevel],
+        suppressedEventCounts: [LogCategory: Int]
+    ) {
+        self.minimumLevel = minimumLevel
+        self.enabledLevels = enabledLevels
+        self.suppressedEventCounts = suppressedEventCounts
+    }
+}
+
 /// Facade used by runtime and analytics to emit structured events consistently.
 public actor StructuredLogger {
     private struct RateLimitState {
@@ -11,28 +38,54 @@ public actor StructuredLogger {
         var suppressedCount: Int
     }
 
+    /// Lock-guarded per-category counters backing `logHotPath(...)` sampling. Lives outside
+    /// the actor so sampling decisions stay synchronous instead of costing an actor hop.
+    private final class SamplingCounters: @unchecked Sendable {
+        private let lock = NSLock()
+        private var countsByCategory: [LogCategory: UInt64] = [:]
+
+        /// Admits the first event of every `ratio`-sized run for `category`.
+        func admitNext(category: LogCategory, ratio: Int) -> Bool {
+            lock.lock()
+            defer { lock.unlock() }
+            let count = countsByCategory[category, default: 0]
+            countsByCategory[category] = count &+ 1
+            return count % UInt64(ratio) == 0
+        }
+    }
+
     private let sink: any LogSink
     private let redactor: EndpointMetadataRedactor
+    private let samplingCounters = SamplingCounters()
     private var rateLimitStates: [String: RateLimitState] = [:]
+    private var suppressedCountsByCategory: [LogCategory: Int] = [:]
 
     /// Lowest severity this logger will record. Events below it are dropped before
     /// envelope construction, metadata redaction, and sink fanout.
     public nonisolated let minimumLevel: LogLevel
 
+    /// Per-category sampling ratios applied by `logHotPath(...)`: a ratio of N keeps one
+    /// event out of every N for that category. Categories without an entry keep every event.
+    /// Ratios are clamped to at least 1 at construction.
+    public nonisolated let hotPathSamplingRatios: [LogCategory: Int]
+
     /// Creates a structured logger with one sink and endpoint metadata redaction policy.
     /// - Parameters:
     ///   - sink: Destination sink implementation (OSLog, JSONL, fanout, etc.).
     ///   - redactor: Redaction policy applied to metadata before serialization.
     ///   - minimumLevel: Lowest severity retained by this logger. Hot paths should pair this
     ///     with `isEnabled(_:)` so suppressed events also skip the `Task` spawn and actor hop.
+    ///   - hotPathSamplingRatios: Per-category 1-in-N ratios for `logHotPath(...)` emission.
     public init(
         sink: any LogSink,
         redactor: EndpointMetadataRedactor = EndpointMetadataRedactor(),
-        minimumLevel: LogLevel = .trace
+        minimumLevel: LogLevel = .trace,
+        hotPathSamplingRatios: [LogCategory: Int] = [:]
     ) {
         self.sink = sink
         self.redactor = redactor
         self.minimumLevel = minimumLevel
+        self.hotPathSamplingRatios = hotPathSamplingRatios.mapValues { max(1, $0) }
     }
 
     /// Returns whether an event at `level` would be recorded by this logger.
@@ -96,6 +149,49 @@ public actor StructuredLogger {
         )
     }
 
+    /// Emits one structured event from a synchronous per-packet or per-frame path without
+    /// paying for a dropped event. The level gate and the category's sampling ratio run
+    /// before `message` and `metadata` are evaluated, so a suppressed trace costs a level
+    /// comparison and a counter bump instead of string formatting and a `Task` spawn.
+    /// Delivery is fire-and-forget on an unstructured task; events may reach the sink out
+    /// of order relative to awaited `log(...)` calls.
+    public nonisolated func logHotPath(
+        level: LogLevel,
+        phase: LogPhase,
+        category: LogCategory,
+        component: String,
+        event: String,
+        connId: String? = nil,
+        flowId: String? = nil,
+        result: String? = nil,
+        errorCode: String? = nil,
+        message: @autoclosure @escaping @Sendable () -> String,
+        metadata: @autoclosure @escaping @Sendable () -> [String: String] = [:]
+    ) {
+        guard isEnabled(level) else {
+            return
+        }
+        if let ratio = hotPathSamplingRatios[category], ratio > 1,
+           !samplingCounters.admitNext(category: category, ratio: ratio) {
+            return
+        }
+        Task {
+            await self.log(
+                level: level,
+                phase: phase,
+                category: category,
+                component: component,
+                event: event,
+                connId: connId,
+                flowId: flowId,
+                result: result,
+                errorCode: errorCode,
+                message: message(),
+                metadata: metadata()
+            )
+        }
+    }
+
     /// Emits at most one event for the same key within the configured interval.
     /// Suppressed duplicate counts are attached to the next emitted event for the key.
     public func logRateLimited(
@@ -147,6 +243,7 @@ public actor StructuredLogger {
                 lastEmittedAt: state.lastEmittedAt,
                 suppressedCount: state.suppressedCount + 1
             )
+            suppressedCountsByCategory[category, default: 0] += 1
             return
         }
 
@@ -176,6 +273,30 @@ public actor StructuredLogger {
         )
     }
 
+    /// Returns cumulative suppressed-event counts per category since construction (or the last reset).
+    /// Decision: counts survive the per-key flush onto the next emitted event, so floods remain
+    /// visible to callers polling stats even when every duplicate was coalesced away.
+    public func suppressedEventCounts() -> [LogCategory: Int] {
+        suppressedCountsByCategory
+    }
+
+    /// Returns a snapshot of the logger's effective configuration and suppression counters.
+    /// Intended for host-side diagnostics ("is trace actually on?") and for attaching the
+    /// current logging posture to support bundles.
+    public func loggingState() -> LoggingState {
+        let allLevels: [LogLevel] = [.trace, .debug, .info, .notice, .warning, .error, .fault]
+        return LoggingState(
+            minimumLevel: minimumLevel,
+            enabledLevels: allLevels.filter { isEnabled($0) },
+            suppressedEventCounts: suppressedCountsByCategory
+        )
+    }
+
+    /// Clears the cumulative suppressed-event counts so callers can measure per-interval rates.
+    public func resetSuppressedEventCounts() {
+        suppressedCountsByCategory = [:]
+    }
+
     private func write(
         timestamp: Date,
         level: LogLevel,
@@ -225,7 +225,8 @@ static void rbpi_fill_udp_metadata(const uint8_t *segment, size_t segment_length
     summary->transport_payload_length = (uint16_t)((size_t)udp_length - RBPI_UDP_HEADER_BYTES);
 
     if (summary->transport_payload_length > 0u &&
-        (summary->source_port == 53u || summary->destination_port == 53u)) {
+        (summary->source_port == 53u || summary->destination_port == 53u ||
+         summary->source_port == 5353u || summary->destination_port == 5353u)) {
         summary->flags |= RBPI_FLAG_MAYBE_DNS;
     }
 
@@ -528,3 +529,219 @@ bool rbpi_parse_packet(const uint8_t *bytes, size_t length, int32_t family_hint,
 
     return true;
 }
+
+static uint32_t rbpi_ones_complement_partial_sum(const uint8_t *bytes, size_t length)
+{
+    uint32_t sum = 0;
+    size_t index = 0;
+
+    while (index + 1u < length) {
+        sum += ((uint32_t)bytes[index] << 8) | (uint32_t)bytes[index + 1u];
+        index += 2u;
+    }
+    if (index < length) {
+        sum += (uint32_t)bytes[index] << 8;
+    }
+
+    return sum;
+}
+
+static uint16_t rbpi_fold_checksum(uint32_t sum)
+{
+    while (sum > 0xffffu) {
+        sum = (sum & 0xffffu) + (sum >> 16);
+    }
+
+    return (uint16_t)sum;
+}
+
+static uint32_t rbpi_pseudo_header_sum(
+    const uint8_t *address_bytes,
+    size_t address_length,
+    uint8_t transport,
+    size_t segment_length)
+{
+    uint32_t sum = rbpi_ones_complement_partial_sum(address_bytes, address_length);
+
+    sum += (uint32_t)transport;
+    sum += (uint32_t)segment_length;
+
+    return sum;
+}
+
+static int32_t rbpi_l4_checksum_failure(const uint8_t *segment, size_t segment_length, uint32_t pseudo_header_sum)
+{
+    uint32_t sum = pseudo_header_sum + rbpi_ones_complement_partial_sum(segment, segment_length);
+
+    return rbpi_fold_checksum(sum) == 0xffffu ? RBPI_STRICT_OK : RBPI_STRICT_CHECKSUM_MISMATCH;
+}
+
+static int32_t rbpi_transport_validation_failure(
+    const uint8_t *segment,
+    size_t segment_length,
+    uint8_t transport,
+    uint32_t pseudo_header_sum,
+    bool udp_zero_checksum_allowed)
+{
+    uint16_t checksum;
+
+    switch (transport) {
+    case 6u:
+        if (segment_length < RBPI_TCP_MIN_HEADER_BYTES) {
+            return RBPI_STRICT_OK;
+        }
+        if ((segment[13] & 0x03u) == 0x03u) {
+            /* SYN and FIN together never occur legitimately. */
+            return RBPI_STRICT_MALFORMED_HEADER;
+        }
+        return rbpi_l4_checksum_failure(segment, segment_length, pseudo_header_sum);
+    case 17u:
+        if (segment_length < RBPI_UDP_HEADER_BYTES) {
+            return RBPI_STRICT_OK;
+        }
+        checksum = rbpi_load_u16(segment + 6);
+        if (checksum == 0u) {
+            /* Legal opt-out over IPv4 (RFC 768); illegal over IPv6 (RFC 8200). */
+            return udp_zero_checksum_allowed ? RBPI_STRICT_OK : RBPI_STRICT_MALFORMED_HEADER;
+        }
+        return rbpi_l4_checksum_failure(segment, segment_length, pseudo_header_sum);
+    default:
+        return RBPI_STRICT_OK;
+    }
+}
+
+static int32_t rbpi_strict_validation_failure_ipv4(const uint8_t *bytes, size_t length)
+{
+    size_t header_length = (size_t)(bytes[0] & 0x0fu) * 4u;
+    size_t total_length;
+    uint16_t fragment_offset;
+
+    if (header_length < RBPI_IPV4_MIN_HEADER_BYTES || length < header_length) {
+        return RBPI_STRICT_OK;
+    }
+    if (rbpi_fold_checksum(rbpi_ones_complement_partial_sum(bytes, header_length)) != 0xffffu) {
+        return RBPI_STRICT_CHECKSUM_MISMATCH;
+    }
+
+    /* Fragments past the first do not carry an L4 header; offset field is bits 3...15 of bytes 6-7. */
+    fragment_offset = (uint16_t)(((uint16_t)(bytes[6] & 0x1fu) << 8) | (uint16_t)bytes[7]);
+    if (fragment_offset != 0u) {
+        return RBPI_STRICT_OK;
+    }
+
+    total_length = ((size_t)bytes[2] << 8) | (size_t)bytes[3];
+    if (total_length < header_length || total_length > length) {
+        return RBPI_STRICT_OK;
+    }
+
+    return rbpi_transport_validation_failure(
+        bytes + header_length,
+        total_length - header_length,
+        bytes[9],
+        rbpi_pseudo_header_sum(bytes + 12, 8u, bytes[9], total_length - header_length),
+        true
+    );
+}
+
+static int32_t rbpi_strict_validation_failure_ipv6(const uint8_t *bytes, size_t length)
+{
+    uint8_t next_header;
+    size_t payload_length;
+
+    if (length < RBPI_IPV6_HEADER_BYTES) {
+        return RBPI_STRICT_OK;
+    }
+
+    /*
+     * Only validate TCP/UDP carried directly after the fixed header; extension-header
+     * chains are rare on this path and pass through unchecked.
+     */
+    next_header = bytes[6];
+    if (next_header != 6u && next_header != 17u) {
+        return RBPI_STRICT_OK;
+    }
+
+    payload_length = ((size_t)bytes[4] << 8) | (size_t)bytes[5];
+    if (payload_length == 0u || RBPI_IPV6_HEADER_BYTES + payload_length > length) {
+        return RBPI_STRICT_OK;
+    }
+
+    return rbpi_transport_validation_failure(
+        bytes + RBPI_IPV6_HEADER_BYTES,
+        payload_length,
+        next_header,
+        rbpi_pseudo_header_sum(bytes + 8, 32u, next_header, payload_length),
+        false
+    );
+}
+
+int32_t rbpi_strict_validation_failure(const uint8_t *bytes, size_t length)
+{
+    if (bytes == NULL || length == 0u) {
+        return RBPI_STRICT_OK;
+    }
+
+    switch ((bytes[0] >> 4) & 0x0fu) {
+    case 4u:
+        return rbpi_strict_validation_failure_ipv4(bytes, length);
+    case 6u:
+        return rbpi_strict_validation_failure_ipv6(bytes, length);
+    default:
+        return RBPI_STRICT_OK;
+    }
+}
+
+bool rbpi_parse_dns_header(const uint8_t *bytes, size_t length, rbpi_dns_header_t *out_header)
+{
+    uint16_t flags;
+
+    if (bytes == NULL || out_header == NULL || length < 12u) {
+        return false;
+    }
+
+    flags = rbpi_load_u16(bytes + 2);
+    out_header->transaction_id = rbpi_load_u16(bytes);
+    out_header->question_count = rbpi_load_u16(bytes + 4);
+    out_header->answer_count = rbpi_load_u16(bytes + 6);
+    out_header->authority_count = rbpi_load_u16(bytes + 8);
+    out_header->additional_count = rbpi_load_u16(bytes + 10);
+    out_header->is_response = (flags & 0x8000u) != 0u ? 1u : 0u;
+    out_header->opcode = (uint8_t)((flags >> 11) & 0x0fu);
+    out_header->is_truncated = (flags & 0x0200u) != 0u ? 1u : 0u;
+    out_header->response_code = (uint8_t)(flags & 0x0fu);
+
+    return true;
+}
+
+int32_t rbpi_quic_datagram_form(const uint8_t *payload, size_t length, uint8_t *out_long_packet_type)
+{
+    rbpi_fast_packet_t summary;
+    uint8_t first_byte;
+
+    if (payload == NULL || length == 0u) {
+        return RBPI_QUIC_FORM_NOT_QUIC;
+    }
+
+    first_byte = payload[0];
+    if ((first_byte & 0x80u) == 0u) {
+        /* Short headers keep only the fixed bit visible; without it this is not QUIC. */
+        if ((first_byte & 0x40u) != 0u) {
+            return RBPI_QUIC_FORM_SHORT_HEADER;
+        }
+        return RBPI_QUIC_FORM_NOT_QUIC;
+    }
+
+    memset(&summary, 0, sizeof(summary));
+    summary.quic_packet_type = RBPI_QUIC_PACKET_TYPE_UNKNOWN;
+    rbpi_fill_quic_metadata(payload, length, &summary);
+    if ((summary.flags & RBPI_FLAG_MAYBE_QUIC_LONG) == 0u) {
+        return RBPI_QUIC_FORM_NOT_QUIC;
+    }
+    if (summary.quic_version == 0u) {
+        return RBPI_QUIC_FORM_VERSION_NEGOTIATION;
+    }
+    if (out_long_packet_type != NULL) {
+        *out_long_packet_type = summary.quic_packet_type;
+    }
+    return RBPI_QUIC_FORM_LONG_HEADER;
+}
@@ -13,6 +13,13 @@
 extern "C" {
 #endif
 
+/*
+ * Freestanding contract: this module depends only on <stdint.h>, <stddef.h>,
+ * <stdbool.h>, and memcpy/memset, so the parsers and checksum code can be
+ * reused and fuzzed outside the tunnel process (harness binaries,
+ * kernel-adjacent tooling) without dragging in the engine.
+ */
+
 enum {
     RBPI_FLAG_HAS_PORTS = 1u << 0,
     RBPI_FLAG_TCP_SYN = 1u << 1,
@@ -76,6 +83,72 @@ typedef struct rbpi_fast_packet_s {
  */
 bool rbpi_parse_packet(const uint8_t *bytes, size_t length, int32_t family_hint, rbpi_fast_packet_t *out_summary);
 
+enum {
+    RBPI_STRICT_OK = 0,
+    RBPI_STRICT_CHECKSUM_MISMATCH = 1,
+    RBPI_STRICT_MALFORMED_HEADER = 2,
+};
+
+/*
+ * Strict-mode header and checksum validation for a raw IPv4/IPv6 packet.
+ *
+ * Checks, in order: IPv4 header checksum, illegal header combinations (TCP
+ * SYN+FIN, zero UDP checksum over IPv6), and the TCP/UDP checksum over the
+ * pseudo-header. Returns the `RBPI_STRICT_*` code for the first failed check;
+ * packets the checks do not apply to (fragments past the first, unsupported
+ * transports, IPv6 extension-header chains) return `RBPI_STRICT_OK`.
+ */
+int32_t rbpi_strict_validation_failure(const uint8_t *bytes, size_t length);
+
+enum {
+    RBPI_QUIC_FORM_NOT_QUIC = 0,
+    RBPI_QUIC_FORM_SHORT_HEADER = 1,
+    RBPI_QUIC_FORM_LONG_HEADER = 2,
+    RBPI_QUIC_FORM_VERSION_NEGOTIATION = 3,
+};
+
+/*
+ * Classifies a UDP datagram payload by QUIC header form.
+ *
+ * Reuses the same long-header reader as `rbpi_parse_packet`, but takes the bare
+ * payload so callers that never see IP headers (the SOCKS relay) can share it.
+ * Short-header classification requires the QUIC fixed bit; payloads without it
+ * return `RBPI_QUIC_FORM_NOT_QUIC`. For long headers `out_long_packet_type`
+ * receives the two type bits (0=Initial, 2=Handshake for v1); it is left
+ * untouched for every other form. Frame contents stay encrypted at every level
+ * this function can observe, so close/drain signals must be inferred by the
+ * caller from header forms alone.
+ */
+int32_t rbpi_quic_datagram_form(const uint8_t *payload, size_t length, uint8_t *out_long_packet_type);
+
+/*
+ * Fixed-size DNS message header summary.
+ *
+ * Like `rbpi_fast_packet_t`, this stays string-free and heap-free: name
+ * decompression and record walking remain in the Swift layer, the core only
+ * extracts the fields needed to route a message.
+ */
+typedef struct rbpi_dns_header_s {
+    uint16_t transaction_id;
+    uint16_t question_count;
+    uint16_t answer_count;
+    uint16_t authority_count;
+    uint16_t additional_count;
+    uint8_t is_response;
+    uint8_t opcode;
+    uint8_t is_truncated;
+    uint8_t response_code;
+} rbpi_dns_header_t;
+
+/*
+ * Parses the 12-byte DNS message header.
+ *
+ * Returns `false` when the buffer is shorter than a DNS header. Section counts
+ * are not validated against the remaining payload; callers walking records must
+ * still bounds-check as they go.
+ */
+bool rbpi_parse_dns_header(const uint8_t *bytes, size_t length, rbpi_dns_header_t *out_header);
+
 #ifdef __cplusplus
 }
 #endif
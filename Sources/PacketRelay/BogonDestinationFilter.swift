@@ -0,0 +1,118 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Darwin
+import Foundation
+
+/// Filter of destination address literals that can never be reached from the public
+/// internet: the unspecified and loopback nets, link-local, TEST-NETs, multicast, and
+/// class E, plus their IPv6 counterparts.
+/// Decision: flows to these addresses previously consumed a full `NWConnection` dial that
+/// was guaranteed to fail; rejecting before the dial answers the client immediately and
+/// keeps garbage destinations out of the dial failure cache. RFC 1918 and ULA space is
+/// deliberately excluded because tunnel clients legitimately reach LAN and site addresses.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class BogonDestinationFilter: @unchecked Sendable {
+    /// Monotonic counts of rejected destinations per address family.
+    public struct DropCounts: Sendable, Equatable {
+        public let ipv4Count: Int
+        public let ipv6Count: Int
+
+        /// - Parameters:
+        ///   - ipv4Count: Rejected IPv4 (and IPv4-mapped IPv6) destinations.
+        ///   - ipv6Count: Rejected IPv6 destinations.
+        public init(ipv4Count: Int, ipv6Count: Int) {
+            self.ipv4Count = ipv4Count
+            self.ipv6Count = ipv6Count
+        }
+    }
+
+    private static let defaultIPv4CIDRs = [
+        "0.0.0.0/8",
+        "127.0.0.0/8",
+        "169.254.0.0/16",
+        "192.0.2.0/24",
+        "198.51.100.0/24",
+        "203.0.113.0/24",
+        "224.0.0.0/4",
+        "240.0.0.0/4",
+    ]
+
+    private static let defaultIPv6CIDRs = [
+        "::/128",
+        "::1/128",
+        "2001:db8::/32",
+        "fe80::/10",
+        "ff00::/8",
+    ]
+
+    private let lock = NSLock()
+    private let ipv4Prefixes: [RelaySourceCIDR]
+    private let ipv6Prefixes: [RelaySourceCIDR]
+    private var ipv4DropCount = 0
+    private var ipv6DropCount = 0
+
+    /// - Parameter additionalCIDRs: Extra reserved prefixes appended to the default set,
+    ///   in `address/prefix` form. Entries that do not parse are ignored.
+    public init(additionalCIDRs: [String] = []) {
+        var ipv4: [RelaySourceCIDR] = []
+        var ipv6: [RelaySourceCIDR] = []
+        for text in Self.defaultIPv4CIDRs + Self.defaultIPv6CIDRs + additionalCIDRs {
+            guard let parsed = RelaySourceCIDR(text) else {
+                continue
+            }
+            if text.contains(":") {
+                ipv6.append(parsed)
+            } else {
+                ipv4.append(parsed)
+            }
+        }
+        ipv4Prefixes = ipv4
+        ipv6Prefixes = ipv6
+    }
+
+    /// Returns the reserved prefix covering the destination, counting the rejection, or
+    /// `nil` when the destination is dialable. Domain names always pass: their resolved
+    /// addresses are unknown until the system resolver runs inside the dial.
+    public func rejectionPrefix(forHost host: String) -> String? {
+        var addr4 = in_addr()
+        if host.withCString({ inet_pton(AF_INET, $0, &addr4) }) == 1 {
+            return rejectionPrefix(forIPv4Literal: host)
+        }
+        var addr6 = in6_addr()
+        guard host.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 else {
+            return nil
+        }
+        let bytes = withUnsafeBytes(of: addr6) { Array($0) }
+        if bytes.prefix(10).allSatisfy({ $0 == 0 }), bytes[10] == 0xff, bytes[11] == 0xff {
+            // IPv4-mapped: classify the embedded address against the IPv4 set.
+            let mapped = "\(bytes[12]).\(bytes[13]).\(bytes[14]).\(bytes[15])"
+            return rejectionPrefix(forIPv4Literal: mapped)
+        }
+        guard let match = ipv6Prefixes.first(where: { $0.contains(host) }) else {
+            return nil
+        }
+        lock.lock()
+        ipv6DropCount += 1
+        lock.unlock()
+        return match.description
+    }
+
+    /// Current rejection counters.
+    public func dropCounts() -> DropCounts {
+        lock.lock()
+        defer { lock.unlock() }
+        return DropCounts(ipv4Count: ipv4DropCount, ipv6Count: ipv6DropCount)
+    }
+
+    private func rejectionPrefix(forIPv4Literal literal: String) -> String? {
+        guard let match = ipv4Prefixes.first(where: { $0.contains(literal) }) else {
+            return nil
+        }
+        lock.lock()
+        ipv4DropCount += 1
+        lock.unlock()
+        return match.description
+    }
+}
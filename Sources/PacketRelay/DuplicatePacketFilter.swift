@@ -0,0 +1,96 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Counters for host packets the duplicate filter inspected and dropped.
+public struct DuplicatePacketFilterStats: Codable, Sendable, Equatable {
+    public private(set) var inspectedPackets: Int
+    public private(set) var duplicatesDropped: Int
+
+    public init(inspectedPackets: Int = 0, duplicatesDropped: Int = 0) {
+        self.inspectedPackets = max(0, inspectedPackets)
+        self.duplicatesDropped = max(0, duplicatesDropped)
+    }
+
+    public var isEmpty: Bool {
+        inspectedPackets == 0 && duplicatesDropped == 0
+    }
+
+    mutating func noteInspected() {
+        inspectedPackets = saturatingAdd(inspectedPackets, 1)
+    }
+
+    mutating func noteDuplicate() {
+        duplicatesDropped = saturatingAdd(duplicatesDropped, 1)
+    }
+
+    private func saturatingAdd(_ lhs: Int, _ rhs: Int) -> Int {
+        let (sum, overflow) = lhs.addingReportingOverflow(rhs)
+        return overflow ? Int.max : sum
+    }
+}
+
+/// Recent-packet duplicate detector for host-delivered packets.
+/// Decision: some packet tunnel providers occasionally hand the same packet to the engine
+/// twice, back-to-back; genuine TCP retransmissions arrive at least one RTO later and
+/// (for IPv4) carry a fresh IP identification field, so a small ring of whole-packet
+/// hashes with a tight time horizon catches provider duplicates without eating real
+/// retransmits. Collisions only suppress a packet when the full 64-bit hashes match
+/// inside the horizon, which is vanishingly rare against tens of entries.
+public struct DuplicatePacketFilter: Sendable, Equatable {
+    public static let defaultWindowSize = 32
+    public static let defaultHorizonSeconds: TimeInterval = 0.05
+
+    private struct Entry: Equatable {
+        var hash: UInt64
+        var seenAt: Date
+    }
+
+    private var entries: [Entry]
+    private var nextSlot = 0
+    private let horizonSeconds: TimeInterval
+    public private(set) var stats = DuplicatePacketFilterStats()
+
+    /// - Parameters:
+    ///   - windowSize: How many recent packet hashes are remembered.
+    ///   - horizonSeconds: How long a remembered hash can still mark a duplicate.
+    public init(
+        windowSize: Int = DuplicatePacketFilter.defaultWindowSize,
+        horizonSeconds: TimeInterval = DuplicatePacketFilter.defaultHorizonSeconds
+    ) {
+        entries = [Entry](
+            repeating: Entry(hash: 0, seenAt: .distantPast),
+            count: max(1, windowSize)
+        )
+        self.horizonSeconds = max(0, horizonSeconds)
+    }
+
+    /// Returns true when `packet` hashes identically to one seen inside the horizon;
+    /// otherwise remembers it and returns false.
+    public mutating func isDuplicate(_ packet: Data, now: Date = Date()) -> Bool {
+        stats.noteInspected()
+        let hash = Self.packetHash(packet)
+        for entry in entries where entry.hash == hash && entry.seenAt != .distantPast {
+            if now.timeIntervalSince(entry.seenAt) <= horizonSeconds {
+                stats.noteDuplicate()
+                return true
+            }
+        }
+        entries[nextSlot] = Entry(hash: hash, seenAt: now)
+        nextSlot = (nextSlot + 1) % entries.count
+        return false
+    }
+
+    /// FNV-1a over the whole packet, so the IP ID (IPv4) and TCP sequence fields both
+    /// participate without parsing headers on the hot path.
+    private static func packetHash(_ packet: Data) -> UInt64 {
+        var hash: UInt64 = 0xCBF2_9CE4_8422_2325
+        for byte in packet {
+            hash ^= UInt64(byte)
+            hash = hash &* 0x0000_0100_0000_01B3
+        }
+        return hash
+    }
+}
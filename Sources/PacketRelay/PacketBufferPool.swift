@@ -0,0 +1,64 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Recycles fixed-size receive buffers so bulk transfers do not allocate per datagram.
+/// Ownership: a buffer leaves the pool raw and comes back through `recycle`; the bridge wraps
+/// buffers in `Data` values whose custom deallocator recycles them, so a buffer returns exactly
+/// when the last reference drops — even when that happens off the bridge queue — and the free
+/// list is capped so a burst cannot pin its high-water mark forever.
+internal final class PacketBufferPool: @unchecked Sendable {
+    let bufferSize: Int
+    private let maxPooledBuffers: Int
+    private let lock = NSLock()
+    private var freeBuffers: [UnsafeMutableRawPointer] = []
+
+    /// - Parameters:
+    ///   - bufferSize: Fixed byte size of every buffer handed out.
+    ///   - maxPooledBuffers: Free-list cap; buffers recycled past it are deallocated.
+    init(bufferSize: Int, maxPooledBuffers: Int) {
+        self.bufferSize = max(1, bufferSize)
+        self.maxPooledBuffers = max(1, maxPooledBuffers)
+    }
+
+    deinit {
+        for buffer in freeBuffers {
+            buffer.deallocate()
+        }
+    }
+
+    /// Returns a pooled buffer, allocating a fresh one when the free list is empty.
+    func acquire() -> UnsafeMutableRawPointer {
+        lock.lock()
+        let buffer = freeBuffers.popLast()
+        lock.unlock()
+        if let buffer {
+            return buffer
+        }
+        return UnsafeMutableRawPointer.allocate(
+            byteCount: bufferSize,
+            alignment: MemoryLayout<UInt64>.alignment
+        )
+    }
+
+    /// Returns one buffer previously handed out by `acquire`.
+    func recycle(_ buffer: UnsafeMutableRawPointer) {
+        lock.lock()
+        if freeBuffers.count < maxPooledBuffers {
+            freeBuffers.append(buffer)
+            lock.unlock()
+            return
+        }
+        lock.unlock()
+        buffer.deallocate()
+    }
+
+    /// Buffers currently sitting in the free list, for diagnostics and tests.
+    var pooledBufferCount: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return freeBuffers.count
+    }
+}
@@ -0,0 +1,158 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Why an outbound dial failed, classified from the failure reason string.
+/// Decision: transport errors surface here as framework-formatted description strings, and
+/// until now they disappeared into per-flow logs; a small substring taxonomy turns them into
+/// countable categories without tying the relay to any one error type's cases.
+public enum RelayDialFailureKind: String, Sendable, Codable, CaseIterable {
+    /// The dial ran out of time (attempt or overall connect timeout).
+    case timeout
+    /// The destination actively refused the connection.
+    case refused
+    /// No route to the destination host or network.
+    case unreachable
+    /// Hostname resolution failed, including named resolvers with no answer.
+    case dnsFailure = "dns-failure"
+    /// Policy rejected the flow before any dial went out.
+    case policyBlocked = "policy-blocked"
+    /// Anything the taxonomy does not recognize; surfaced so gaps stay visible.
+    case other
+
+    /// Classifies a failure reason string by case-insensitive substring.
+    /// Ordering matters: DNS checks run before the generic unreachable check because some
+    /// resolution failures mention unreachable name servers.
+    public static func classify(reason: String) -> RelayDialFailureKind {
+        let lowered = reason.lowercased()
+        if lowered.contains("dns") || lowered.contains("hostname") || lowered.contains("nodename")
+            || lowered.contains("resolve") {
+            return .dnsFailure
+        }
+        if lowered.contains("timed out") || lowered.contains("timeout") {
+            return .timeout
+        }
+        if lowered.contains("refused") {
+            return .refused
+        }
+        if lowered.contains("unreachable") || lowered.contains("no route") {
+            return .unreachable
+        }
+        return .other
+    }
+}
+
+/// Per-destination counters of classified dial failures, exportable as aggregates.
+/// Destinations bucket the same way as the dial-latency heatmap (normalized host, `/24`,
+/// `/48`), so failure rows line up with latency rows in exported views.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class RelayDialFailureCounters: @unchecked Sendable {
+    /// One exportable row: a destination bucket with its failure counts by kind.
+    public struct Entry: Sendable, Equatable, Codable {
+        public let destination: String
+        public let timeouts: Int
+        public let refused: Int
+        public let unreachable: Int
+        public let dnsFailures: Int
+        public let policyBlocked: Int
+        public let other: Int
+        /// Sum across every kind, the export sort key.
+        public let total: Int
+    }
+
+    /// Destination cap guarding against unbounded growth; the least recently failing
+    /// destination is evicted first.
+    private static let maxDestinations = 512
+
+    private struct Bucket {
+        var countsByKind: [RelayDialFailureKind: Int]
+        var lastRecordedAt: Date
+    }
+
+    private let lock = NSLock()
+    private let now: @Sendable () -> Date
+    private var buckets: [String: Bucket] = [:]
+
+    public convenience init() {
+        self.init(now: { Date() })
+    }
+
+    init(now: @escaping @Sendable () -> Date) {
+        self.now = now
+    }
+
+    /// Classifies `reason` and counts the failure against the destination's bucket.
+    public func record(host: String, reason: String) {
+        record(host: host, kind: RelayDialFailureKind.classify(reason: reason))
+    }
+
+    /// Counts an already-classified failure, for call sites that know the kind directly
+    /// (policy blocks, resolver misses) instead of holding a reason string.
+    public func record(host: String, kind: RelayDialFailureKind) {
+        let destination = RelayDialLatencyHeatmap.destinationBucket(for: host)
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        var bucket = buckets[destination] ?? Bucket(countsByKind: [:], lastRecordedAt: reference)
+        bucket.countsByKind[kind, default: 0] += 1
+        bucket.lastRecordedAt = reference
+        if buckets[destination] == nil, buckets.count >= Self.maxDestinations,
+           let oldest = buckets.min(by: { $0.value.lastRecordedAt < $1.value.lastRecordedAt }) {
+            buckets.removeValue(forKey: oldest.key)
+        }
+        buckets[destination] = bucket
+    }
+
+    /// Exports one row per destination, most failures first.
+    public func snapshot() -> [Entry] {
+        lock.lock()
+        let captured = buckets
+        lock.unlock()
+        return captured.map { destination, bucket in
+            Entry(
+                destination: destination,
+                timeouts: bucket.countsByKind[.timeout] ?? 0,
+                refused: bucket.countsByKind[.refused] ?? 0,
+                unreachable: bucket.countsByKind[.unreachable] ?? 0,
+                dnsFailures: bucket.countsByKind[.dnsFailure] ?? 0,
+                policyBlocked: bucket.countsByKind[.policyBlocked] ?? 0,
+                other: bucket.countsByKind[.other] ?? 0,
+                total: bucket.countsByKind.values.reduce(0, +)
+            )
+        }
+        .sorted {
+            ($0.total, $0.destination) > ($1.total, $1.destination)
+        }
+    }
+
+    /// Pre-seeds the counters from previously exported entries so failure totals stay
+    /// continuous across a restart. Counts add onto whatever was already recorded; the
+    /// exported destination strings are already bucketed and are used as keys verbatim.
+    public func seed(entries: [Entry]) {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        for entry in entries {
+            var bucket = buckets[entry.destination] ?? Bucket(countsByKind: [:], lastRecordedAt: reference)
+            bucket.countsByKind[.timeout, default: 0] += max(0, entry.timeouts)
+            bucket.countsByKind[.refused, default: 0] += max(0, entry.refused)
+            bucket.countsByKind[.unreachable, default: 0] += max(0, entry.unreachable)
+            bucket.countsByKind[.dnsFailure, default: 0] += max(0, entry.dnsFailures)
+            bucket.countsByKind[.policyBlocked, default: 0] += max(0, entry.policyBlocked)
+            bucket.countsByKind[.other, default: 0] += max(0, entry.other)
+            if buckets[entry.destination] == nil, buckets.count >= Self.maxDestinations {
+                continue
+            }
+            buckets[entry.destination] = bucket
+        }
+    }
+
+    /// Drops every counter, opening a fresh observation window.
+    public func removeAll() {
+        lock.lock()
+        defer { lock.unlock() }
+        buckets.removeAll()
+    }
+}
@@ -0,0 +1,140 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import TunnelRuntime
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Per-destination aggregate of connection-establishment latency, exportable as percentiles.
+/// Decision: hostnames bucket by normalized host and address literals by network prefix
+/// (/24 for IPv4, /48 for IPv6), so CDN pods behind one subnet land in one row instead of
+/// scattering across hundreds of single-sample entries. Samples are kept as a bounded ring
+/// per destination and percentiles are computed at export time, so recording stays a few
+/// appends under one lock on the dial path.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class RelayDialLatencyHeatmap: @unchecked Sendable {
+    /// One exportable row: a destination bucket with its establishment-latency percentiles.
+    public struct Entry: Sendable, Equatable, Codable {
+        /// Destination bucket: normalized host, `a.b.c.0/24`, or an IPv6 `/48` prefix.
+        public let destination: String
+        /// Samples currently aggregated; bounded by `maxSamplesPerDestination`.
+        public let sampleCount: Int
+        public let p50Milliseconds: Int
+        public let p90Milliseconds: Int
+        public let p99Milliseconds: Int
+        public let maxMilliseconds: Int
+    }
+
+    /// Recent samples kept per destination; older dials age out as new ones arrive.
+    private static let maxSamplesPerDestination = 256
+
+    /// Destination cap guarding against unbounded growth; the least recently dialed
+    /// destination is evicted first.
+    private static let maxDestinations = 512
+
+    private struct Bucket {
+        var samples: [Int]
+        var nextSlot: Int
+        var lastRecordedAt: Date
+    }
+
+    private let lock = NSLock()
+    private let now: @Sendable () -> Date
+    private var buckets: [String: Bucket] = [:]
+
+    public convenience init() {
+        self.init(now: { Date() })
+    }
+
+    init(now: @escaping @Sendable () -> Date) {
+        self.now = now
+    }
+
+    /// Records one establishment latency sample for the destination the dial targeted.
+    /// - Parameters:
+    ///   - host: Destination exactly as requested (hostname or address literal).
+    ///   - milliseconds: Elapsed dial-to-ready time; non-positive samples are dropped.
+    public func record(host: String, milliseconds: Int) {
+        guard milliseconds > 0 else {
+            return
+        }
+        let destination = Self.destinationBucket(for: host)
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        var bucket = buckets[destination] ?? Bucket(samples: [], nextSlot: 0, lastRecordedAt: reference)
+        if bucket.samples.count < Self.maxSamplesPerDestination {
+            bucket.samples.append(milliseconds)
+        } else {
+            bucket.samples[bucket.nextSlot] = milliseconds
+            bucket.nextSlot = (bucket.nextSlot + 1) % Self.maxSamplesPerDestination
+        }
+        bucket.lastRecordedAt = reference
+        if buckets[destination] == nil, buckets.count >= Self.maxDestinations,
+           let oldest = buckets.min(by: { $0.value.lastRecordedAt < $1.value.lastRecordedAt }) {
+            buckets.removeValue(forKey: oldest.key)
+        }
+        buckets[destination] = bucket
+    }
+
+    /// Exports one row per destination, slowest p99 first, so the top of the list is the
+    /// heatmap's hot end. Percentiles use nearest-rank over the retained samples.
+    public func snapshot() -> [Entry] {
+        lock.lock()
+        let captured = buckets
+        lock.unlock()
+        return captured.map { destination, bucket in
+            let sorted = bucket.samples.sorted()
+            return Entry(
+                destination: destination,
+                sampleCount: sorted.count,
+                p50Milliseconds: Self.nearestRank(sorted, percentile: 50),
+                p90Milliseconds: Self.nearestRank(sorted, percentile: 90),
+                p99Milliseconds: Self.nearestRank(sorted, percentile: 99),
+                maxMilliseconds: sorted.last ?? 0
+            )
+        }
+        .sorted {
+            ($0.p99Milliseconds, $0.destination) > ($1.p99Milliseconds, $1.destination)
+        }
+    }
+
+    /// Drops every aggregate, opening a fresh observation window.
+    public func removeAll() {
+        lock.lock()
+        defer { lock.unlock() }
+        buckets.removeAll()
+    }
+
+    /// Maps a requested host to its heatmap bucket: `/24` for IPv4 literals, `/48` for IPv6
+    /// literals, the normalized name for everything else.
+    static func destinationBucket(for host: String) -> String {
+        var addr4 = in_addr()
+        if host.withCString({ inet_pton(AF_INET, $0, &addr4) }) == 1 {
+            let octets = withUnsafeBytes(of: addr4) { Array($0) }
+            return "\(octets[0]).\(octets[1]).\(octets[2]).0/24"
+        }
+        var addr6 = in6_addr()
+        if host.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 {
+            let bytes = withUnsafeBytes(of: addr6) { Array($0) }
+            let groups = stride(from: 0, to: 6, by: 2).map {
+                String(format: "%x", UInt16(bytes[$0]) << 8 | UInt16(bytes[$0 + 1]))
+            }
+            return "\(groups.joined(separator: ":"))::/48"
+        }
+        return HostNormalizer.normalize(host)
+    }
+
+    private static func nearestRank(_ sorted: [Int], percentile: Int) -> Int {
+        guard !sorted.isEmpty else {
+            return 0
+        }
+        let rank = max(1, Int((Double(percentile) / 100 * Double(sorted.count)).rounded(.up)))
+        return sorted[min(rank, sorted.count) - 1]
+    }
+}
@@ -0,0 +1,90 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Caps concurrent outbound TCP dials across every session on one server.
+/// Decision: a connection storm (app relaunch, captive-portal recovery) would otherwise fire
+/// hundreds of `NWConnection` creations in one burst; dials over the cap wait in FIFO arrival
+/// order and start as in-flight dials resolve, so bursts are smoothed instead of rejected and
+/// no flow is starved by later arrivals.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class RelayDialLimiter: @unchecked Sendable {
+    /// Point-in-time queue depths plus cumulative admission counters, exportable so hosts
+    /// can watch dial-storm behaviour.
+    public struct Stats: Codable, Sendable, Equatable {
+        /// Dials currently holding an in-flight slot.
+        public let inFlightCount: Int
+        /// Dials currently waiting for a slot.
+        public let queuedCount: Int
+        /// Deepest the wait queue has ever been.
+        public let peakQueuedCount: Int
+        /// Dials that started immediately because a slot was free.
+        public let admittedImmediatelyCount: Int
+        /// Dials that had to wait in the queue before starting.
+        public let deferredCount: Int
+    }
+
+    /// Default in-flight cap; generous for steady state while keeping a worst-case storm
+    /// to a socket count the platform comfortably absorbs per scheduling burst.
+    public static let defaultMaxInFlightDials = 32
+
+    private let maxInFlightDials: Int
+    private let lock = NSLock()
+    private var inFlightCount = 0
+    private var waitQueue: [@Sendable () -> Void] = []
+    private var peakQueuedCount = 0
+    private var admittedImmediatelyCount = 0
+    private var deferredCount = 0
+
+    public init(maxInFlightDials: Int = RelayDialLimiter.defaultMaxInFlightDials) {
+        self.maxInFlightDials = max(1, maxInFlightDials)
+    }
+
+    /// Runs `dial` immediately when an in-flight slot is free, otherwise queues it FIFO.
+    /// Contract: every dial that runs must balance with exactly one `release()` once it
+    /// resolves (ready, failed, or cancelled), or the slot leaks for the server's lifetime.
+    func admit(_ dial: @escaping @Sendable () -> Void) {
+        lock.lock()
+        if inFlightCount < maxInFlightDials {
+            inFlightCount += 1
+            admittedImmediatelyCount += 1
+            lock.unlock()
+            dial()
+            return
+        }
+        deferredCount += 1
+        waitQueue.append(dial)
+        peakQueuedCount = max(peakQueuedCount, waitQueue.count)
+        lock.unlock()
+    }
+
+    /// Returns a resolved dial's slot and starts the oldest waiting dial, if any. The
+    /// waiter runs on the releasing caller's context; queued dials hop to their own
+    /// session queue before touching connection state.
+    func release() {
+        lock.lock()
+        guard !waitQueue.isEmpty else {
+            inFlightCount = max(0, inFlightCount - 1)
+            lock.unlock()
+            return
+        }
+        let next = waitQueue.removeFirst()
+        lock.unlock()
+        next()
+    }
+
+    /// Exports current depths and cumulative admission counters.
+    public func stats() -> Stats {
+        lock.lock()
+        defer { lock.unlock() }
+        return Stats(
+            inFlightCount: inFlightCount,
+            queuedCount: waitQueue.count,
+            peakQueuedCount: peakQueuedCount,
+            admittedImmediatelyCount: admittedImmediatelyCount,
+            deferredCount: deferredCount
+        )
+    }
+}
@@ -0,0 +1,178 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Darwin
+import Foundation
+
+/// Token-bucket limits on new-flow admission, per client source and server-wide.
+/// Decision: a runaway client or port scanner behind the TUN opens flows far faster than it
+/// uses them, and every accepted session costs a queue and buffer budget; refusing the
+/// connection at accept keeps memory bounded while well-behaved sources never notice.
+/// Buckets refill continuously, so sustained rates are enforced while short bursts up to
+/// the bucket size pass untouched.
+/// Contract: shared across sessions and safe to call from any queue.
+public final class RelayFlowRateLimiter: @unchecked Sendable {
+    /// Sustained new-flow rates and burst allowances. The global bucket backstops the
+    /// per-source buckets for deployments where sources cannot be attributed.
+    public struct Limits: Sendable, Equatable {
+        public let globalFlowsPerSecond: Double
+        public let globalBurst: Int
+        public let perSourceFlowsPerSecond: Double
+        public let perSourceBurst: Int
+        /// When true, IPv6 sources are keyed by their /64 prefix instead of the full
+        /// address. Mobile clients rotate temporary (privacy) addresses within one
+        /// delegated /64, and per-address buckets would hand a rotating client a fresh
+        /// burst per rotation while fragmenting its attribution across entries.
+        public let groupIPv6SourcesBySlash64: Bool
+
+        /// Generous for app traffic; only sustained scanner-like open rates hit them.
+        public static let `default` = Limits(
+            globalFlowsPerSecond: 200,
+            globalBurst: 400,
+            perSourceFlowsPerSecond: 50,
+            perSourceBurst: 100
+        )
+
+        public init(
+            globalFlowsPerSecond: Double,
+            globalBurst: Int,
+            perSourceFlowsPerSecond: Double,
+            perSourceBurst: Int,
+            groupIPv6SourcesBySlash64: Bool = true
+        ) {
+            self.globalFlowsPerSecond = max(1, globalFlowsPerSecond)
+            self.globalBurst = max(1, globalBurst)
+            self.perSourceFlowsPerSecond = max(1, perSourceFlowsPerSecond)
+            self.perSourceBurst = max(1, perSourceBurst)
+            self.groupIPv6SourcesBySlash64 = groupIPv6SourcesBySlash64
+        }
+    }
+
+    /// Admission decision for one new flow; rejections name the exhausted bucket so logs
+    /// can distinguish one abusive source from server-wide pressure.
+    public enum Verdict: String, Sendable, Equatable {
+        case admitted
+        case rejectedBySourceLimit = "rejected-by-source-limit"
+        case rejectedByGlobalLimit = "rejected-by-global-limit"
+    }
+
+    /// Cumulative admission counters plus the live tracked-source count.
+    public struct Stats: Codable, Sendable, Equatable {
+        public let admittedCount: Int
+        public let rejectedBySourceCount: Int
+        public let rejectedByGlobalCount: Int
+        public let trackedSourceCount: Int
+    }
+
+    /// Source cap guarding against unbounded growth; the least recently active source is
+    /// evicted first, which resets its bucket to a full burst.
+    private static let maxTrackedSources = 512
+
+    private struct Bucket {
+        var tokens: Double
+        var lastRefillAt: Date
+    }
+
+    private let limits: Limits
+    private let lock = NSLock()
+    private let now: @Sendable () -> Date
+    private var globalBucket: Bucket
+    private var sourceBuckets: [String: Bucket] = [:]
+    private var admittedCount = 0
+    private var rejectedBySourceCount = 0
+    private var rejectedByGlobalCount = 0
+
+    public convenience init(limits: Limits = .default) {
+        self.init(limits: limits, now: { Date() })
+    }
+
+    init(limits: Limits, now: @escaping @Sendable () -> Date) {
+        self.limits = limits
+        self.now = now
+        self.globalBucket = Bucket(tokens: Double(limits.globalBurst), lastRefillAt: now())
+    }
+
+    /// Decides whether one new flow from `source` may start. Tokens are consumed only on
+    /// admission, so a rejected flood never starves the source's next legitimate open.
+    /// - Parameter source: Client address behind the flow; `nil` (unattributable) flows
+    ///   are checked against the global bucket alone.
+    func admitNewFlow(source: String?) -> Verdict {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+
+        refill(&globalBucket, ratePerSecond: limits.globalFlowsPerSecond, burst: limits.globalBurst, reference: reference)
+        guard globalBucket.tokens >= 1 else {
+            rejectedByGlobalCount += 1
+            return .rejectedByGlobalLimit
+        }
+
+        if let source {
+            let key = Self.sourceKey(source, groupIPv6BySlash64: limits.groupIPv6SourcesBySlash64)
+            var bucket = sourceBuckets[key]
+                ?? Bucket(tokens: Double(limits.perSourceBurst), lastRefillAt: reference)
+            refill(&bucket, ratePerSecond: limits.perSourceFlowsPerSecond, burst: limits.perSourceBurst, reference: reference)
+            guard bucket.tokens >= 1 else {
+                sourceBuckets[key] = bucket
+                rejectedBySourceCount += 1
+                return .rejectedBySourceLimit
+            }
+            bucket.tokens -= 1
+            if sourceBuckets[key] == nil, sourceBuckets.count >= Self.maxTrackedSources,
+               let oldest = sourceBuckets.min(by: { $0.value.lastRefillAt < $1.value.lastRefillAt }) {
+                sourceBuckets.removeValue(forKey: oldest.key)
+            }
+            sourceBuckets[key] = bucket
+        }
+
+        globalBucket.tokens -= 1
+        admittedCount += 1
+        return .admitted
+    }
+
+    /// Exports cumulative admission counters for host-side monitoring.
+    public func stats() -> Stats {
+        lock.lock()
+        defer { lock.unlock() }
+        return Stats(
+            admittedCount: admittedCount,
+            rejectedBySourceCount: rejectedBySourceCount,
+            rejectedByGlobalCount: rejectedByGlobalCount,
+            trackedSourceCount: sourceBuckets.count
+        )
+    }
+
+    /// Bucket key for one source address. IPv6 literals collapse to a `/64` prefix key
+    /// when prefix grouping is on, so every temporary address a device rotates through
+    /// lands in the same bucket; IPv4 literals and anything unparsable (including
+    /// scoped link-local forms) key as the lowercased string itself.
+    static func sourceKey(_ source: String, groupIPv6BySlash64: Bool) -> String {
+        guard groupIPv6BySlash64 else {
+            return source.lowercased()
+        }
+        var addr6 = in6_addr()
+        guard source.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 else {
+            return source.lowercased()
+        }
+        let bytes = withUnsafeBytes(of: addr6) { Array($0) }
+        if bytes.prefix(10).allSatisfy({ $0 == 0 }), bytes[10] == 0xff, bytes[11] == 0xff {
+            // IPv4-mapped addresses carry no rotating interface identifier; keep the
+            // embedded IPv4 address as its own key.
+            return "\(bytes[12]).\(bytes[13]).\(bytes[14]).\(bytes[15])"
+        }
+        let groups = stride(from: 0, to: 8, by: 2).map { index in
+            String(format: "%x", UInt16(bytes[index]) << 8 | UInt16(bytes[index + 1]))
+        }
+        return groups.joined(separator: ":") + "::/64"
+    }
+
+    private func refill(_ bucket: inout Bucket, ratePerSecond: Double, burst: Int, reference: Date) {
+        let elapsed = reference.timeIntervalSince(bucket.lastRefillAt)
+        guard elapsed > 0 else {
+            return
+        }
+        bucket.tokens = min(Double(burst), bucket.tokens + elapsed * ratePerSecond)
+        bucket.lastRefillAt = reference
+    }
+}
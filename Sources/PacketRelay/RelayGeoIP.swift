@@ -0,0 +1,29 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Geo attributes resolved for one destination address.
+public struct RelayGeoIPInfo: Sendable, Equatable {
+    /// ISO 3166-1 alpha-2 country code, uppercased (for example `CN`).
+    public let countryCode: String?
+    /// Autonomous system number, when the database carries ASN data.
+    public let autonomousSystemNumber: UInt32?
+
+    public init(countryCode: String? = nil, autonomousSystemNumber: UInt32? = nil) {
+        self.countryCode = countryCode.map { $0.uppercased() }
+        self.autonomousSystemNumber = autonomousSystemNumber
+    }
+}
+
+/// Host-supplied destination lookup backing `geo:`/`asn:` policy selectors.
+/// Contract: `lookup` runs inline on the relay connection queue at flow admission, so
+/// implementations must answer from memory-mapped or cached data without blocking I/O.
+/// Decision: hosts back this with an MMDB reader loaded from their own database file; the
+/// package defines only the hook so it takes no GeoIP database dependency or license.
+public protocol RelayGeoIPResolver: Sendable {
+    /// Returns geo attributes for a destination, or `nil` when the address is not in the
+    /// database or is a domain name the resolver cannot attribute.
+    func lookup(address: String) -> RelayGeoIPInfo?
+}
@@ -0,0 +1,103 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// One named resolver in the relay's resolver dispatch table.
+/// Decision: the package defines only this synchronous hook, mirroring `RelayPolicyEvaluator`;
+/// hosts back DoH endpoints with their own caching clients so the relay never blocks on a
+/// network round trip inside a connect.
+/// Contract: `resolve` runs inline on the relay connection queue before the outbound dial, so
+/// implementations must answer from local state (a cache or static table) and return `nil`
+/// when they have no answer.
+public protocol RelayHostResolver: Sendable {
+    /// Returns the address to dial for a hostname, or `nil` when the resolver has no answer.
+    func resolve(host: String) -> String?
+}
+
+/// Static hostname-to-address overrides, the simplest `RelayHostResolver`.
+/// Useful for pinning test hosts and for split-horizon names the system resolver gets wrong.
+public struct RelayStaticHostOverrides: RelayHostResolver {
+    private let addressesByHost: [String: String]
+
+    public init(_ addressesByHost: [String: String]) {
+        self.addressesByHost = Dictionary(
+            addressesByHost.map { ($0.key.lowercased(), $0.value) },
+            uniquingKeysWith: { _, last in last }
+        )
+    }
+
+    public func resolve(host: String) -> String? {
+        addressesByHost[host.lowercased()]
+    }
+}
+
+/// How a resolver tag dispatches hostname resolution for one dial.
+public enum RelayHostResolverChoice: Sendable {
+    /// Dial the hostname as requested and let the system resolve it.
+    case system
+    /// Resolve through the named custom resolver before dialing.
+    case custom(any RelayHostResolver)
+}
+
+/// Named resolvers referenced by `resolver=<name>` policy rule parameters.
+/// Decision: tags resolve at connect time like `RelayUpstreamRoutes` outbound tags, so a
+/// compiled policy document stays valid as resolver endpoints rotate. The tag `system` is
+/// built in unless a registry entry shadows it.
+public struct RelayHostResolvers: Sendable {
+    private let resolversByTag: [String: any RelayHostResolver]
+
+    public init(resolvers: [String: any RelayHostResolver] = [:]) {
+        self.resolversByTag = resolvers
+    }
+
+    public func resolver(forTag tag: String) -> RelayHostResolverChoice? {
+        if let resolver = resolversByTag[tag] {
+            return .custom(resolver)
+        }
+        return tag == "system" ? .system : nil
+    }
+
+    public static let none = RelayHostResolvers()
+}
+
+/// Process-global override of the system resolver path, for tests.
+/// Decision: harnesses exercising the relay's default dial path need hostname dials
+/// answered without the real system resolver or network, and threading a resolver
+/// registry through code that normally runs with `.none` would bend every production
+/// call site around a test concern — so this is a single thread-safe global slot the
+/// connection consults only when no named resolver rewrote the dial.
+/// Contract: production code never installs anything here; tests that do must `clear()`
+/// in teardown so later tests see untouched system resolution.
+public final class RelaySystemResolverOverride: @unchecked Sendable {
+    public static let shared = RelaySystemResolverOverride()
+
+    private let lock = NSLock()
+    private var resolver: (any RelayHostResolver)?
+
+    private init() {}
+
+    /// Installs the resolver consulted on the system resolver path, replacing any
+    /// previously installed override.
+    public func install(_ resolver: any RelayHostResolver) {
+        lock.lock()
+        defer { lock.unlock() }
+        self.resolver = resolver
+    }
+
+    /// Removes the override, restoring untouched system resolution.
+    public func clear() {
+        lock.lock()
+        defer { lock.unlock() }
+        resolver = nil
+    }
+
+    /// Answers from the installed override, or `nil` when none is installed or the
+    /// override has no entry for the hostname.
+    public func resolve(host: String) -> String? {
+        lock.lock()
+        defer { lock.unlock() }
+        return resolver?.resolve(host: host)
+    }
+}
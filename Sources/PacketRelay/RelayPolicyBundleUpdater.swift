@@ -0,0 +1,251 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Observability
+#if canImport(CryptoKit)
+import CryptoKit
+#endif
+
+/// Policy evaluator whose backing document can be replaced atomically while sessions
+/// keep evaluating against it.
+/// Decision: sessions hold their evaluator for the server's lifetime, so hot rule
+/// updates need one stable evaluator identity with a swappable document behind it.
+/// Each forwarded call reads the current document under the lock, so a flow admitted
+/// mid-swap sees either the old document or the new one in full — never a mix.
+public final class RelaySwappablePolicy: RelayPolicyEvaluator, @unchecked Sendable {
+    private let lock = NSLock()
+    private var current: (any RelayPolicyEvaluator)?
+    private var storedGeneration = 0
+
+    /// - Parameter initial: Document evaluated until the first install; `nil` admits
+    ///   every flow, matching an empty compiled policy.
+    public init(initial: (any RelayPolicyEvaluator)? = nil) {
+        current = initial
+    }
+
+    /// Count of installs so far, so callers can confirm an update actually applied.
+    public var generation: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedGeneration
+    }
+
+    /// Atomically replaces the backing document for every subsequent evaluation.
+    public func install(_ evaluator: any RelayPolicyEvaluator) {
+        lock.lock()
+        current = evaluator
+        storedGeneration += 1
+        lock.unlock()
+    }
+
+    public func evaluate(_ input: RelayPolicyInput) -> RelayPolicyVerdict {
+        backing()?.evaluate(input) ?? .allow
+    }
+
+    public func shouldInspectClientHello(_ input: RelayPolicyInput) -> Bool {
+        backing()?.shouldInspectClientHello(input) ?? false
+    }
+
+    public func resolverTag(_ input: RelayPolicyInput) -> String? {
+        backing()?.resolverTag(input)
+    }
+
+    public func noDelay(_ input: RelayPolicyInput) -> Bool? {
+        backing()?.noDelay(input)
+    }
+
+    public func dnsResponseDelayMilliseconds(_ input: RelayPolicyInput) -> Int? {
+        backing()?.dnsResponseDelayMilliseconds(input)
+    }
+
+    private func backing() -> (any RelayPolicyEvaluator)? {
+        lock.lock()
+        defer { lock.unlock() }
+        return current
+    }
+}
+
+/// Signed rule bundle envelope fetched by `RelayPolicyBundleUpdater`: the policy
+/// document text plus a detached Ed25519 signature over its UTF-8 bytes.
+public struct RelayPolicyBundle: Codable, Sendable, Equatable {
+    public static let currentSchemaVersion = 1
+
+    /// Envelope schema version; bundles with an unknown version are refused.
+    public let schemaVersion: Int
+    /// Policy document in the `RelayPolicyCompiler` DSL.
+    public let policyText: String
+    /// Base64-encoded Ed25519 signature over the UTF-8 bytes of `policyText`.
+    public let signatureBase64: String
+
+    /// - Parameters:
+    ///   - schemaVersion: Envelope schema version, `currentSchemaVersion` by default.
+    ///   - policyText: Policy document text.
+    ///   - signatureBase64: Base64-encoded Ed25519 signature over the document bytes.
+    public init(
+        schemaVersion: Int = RelayPolicyBundle.currentSchemaVersion,
+        policyText: String,
+        signatureBase64: String
+    ) {
+        self.schemaVersion = schemaVersion
+        self.policyText = policyText
+        self.signatureBase64 = signatureBase64
+    }
+}
+
+/// Fetches a signed rule bundle over HTTPS, verifies its Ed25519 signature against a
+/// caller-provided public key, and atomically installs the compiled document into a
+/// `RelaySwappablePolicy` — so hosts point the engine at a bundle URL instead of each
+/// platform implementing secure blocklist delivery itself.
+/// Decision: the updater ships feature-flagged and disabled, like the DNS fast path;
+/// a host that never opts in keeps a relay with no network-reachable policy input.
+/// Contract: verification happens before compilation, so unsigned or tampered bytes
+/// never reach the policy parser, and a failed update leaves the installed document
+/// untouched.
+public final class RelayPolicyBundleUpdater: @unchecked Sendable {
+    /// Why an update was refused; the installed policy is unchanged in every case.
+    public enum UpdateError: Error, Equatable, CustomStringConvertible {
+        /// The updater was constructed with the feature flag off.
+        case updaterDisabled
+        /// The bundle URL does not use HTTPS.
+        case insecureURL
+        /// The fetched bytes are not a valid bundle envelope.
+        case malformedBundle(String)
+        /// The envelope's schema version is not understood.
+        case unsupportedSchemaVersion(Int)
+        /// The provided public key is not a valid Ed25519 key.
+        case invalidPublicKey
+        /// The signature does not verify against the document bytes.
+        case signatureRejected
+        /// The platform has no Ed25519 implementation available.
+        case signingUnavailable
+        /// The verified document failed to compile.
+        case compileFailed(String)
+
+        public var description: String {
+            switch self {
+            case .updaterDisabled:
+                return "policy bundle updater is not enabled"
+            case .insecureURL:
+                return "policy bundle URL must use https"
+            case .malformedBundle(let detail):
+                return "malformed policy bundle: \(detail)"
+            case .unsupportedSchemaVersion(let version):
+                return "unsupported policy bundle schema version \(version)"
+            case .invalidPublicKey:
+                return "invalid Ed25519 public key"
+            case .signatureRejected:
+                return "policy bundle signature rejected"
+            case .signingUnavailable:
+                return "Ed25519 verification unavailable on this platform"
+            case .compileFailed(let detail):
+                return "verified policy bundle failed to compile: \(detail)"
+            }
+        }
+    }
+
+    private let policy: RelaySwappablePolicy
+    private let logger: StructuredLogger
+    private let isEnabled: Bool
+    private let compileOptions: RelayPolicyCompiler.Options
+    private let fetch: @Sendable (URL) async throws -> Data
+
+    /// - Parameters:
+    ///   - policy: Swappable evaluator verified bundles are installed into.
+    ///   - logger: Structured logger for applied and refused updates.
+    ///   - isEnabled: Feature flag; disabled updaters refuse every fetch so hosts opt
+    ///     in explicitly to network-delivered rules.
+    ///   - compileOptions: Compiler options applied to verified documents.
+    ///   - fetch: Transport override used by tests; defaults to a `URLSession` GET.
+    public init(
+        policy: RelaySwappablePolicy,
+        logger: StructuredLogger,
+        isEnabled: Bool = false,
+        compileOptions: RelayPolicyCompiler.Options = .default,
+        fetch: @escaping @Sendable (URL) async throws -> Data = { url in
+            try await URLSession.shared.data(from: url).0
+        }
+    ) {
+        self.policy = policy
+        self.logger = logger
+        self.isEnabled = isEnabled
+        self.compileOptions = compileOptions
+        self.fetch = fetch
+    }
+
+    /// Fetches the bundle at `url`, verifies it against `publicKey` (raw 32-byte
+    /// Ed25519 representation), and installs the compiled document.
+    /// - Returns: The swappable policy's generation after the install.
+    @discardableResult
+    public func fetchUpdate(from url: URL, publicKey: Data) async throws -> Int {
+        guard isEnabled else {
+            throw UpdateError.updaterDisabled
+        }
+        guard url.scheme?.lowercased() == "https" else {
+            throw UpdateError.insecureURL
+        }
+        let bundleData = try await fetch(url)
+        return try apply(bundleData: bundleData, publicKey: publicKey)
+    }
+
+    /// Verifies and installs already-fetched bundle bytes; the `fetchUpdate` tail, split
+    /// out so hosts with their own delivery channel reuse verification and apply.
+    @discardableResult
+    public func apply(bundleData: Data, publicKey: Data) throws -> Int {
+        guard isEnabled else {
+            throw UpdateError.updaterDisabled
+        }
+        let bundle: RelayPolicyBundle
+        do {
+            bundle = try JSONDecoder().decode(RelayPolicyBundle.self, from: bundleData)
+        } catch {
+            throw UpdateError.malformedBundle("envelope failed to decode")
+        }
+        guard bundle.schemaVersion == RelayPolicyBundle.currentSchemaVersion else {
+            throw UpdateError.unsupportedSchemaVersion(bundle.schemaVersion)
+        }
+        guard let signature = Data(base64Encoded: bundle.signatureBase64) else {
+            throw UpdateError.malformedBundle("signature is not valid base64")
+        }
+        try verify(signature: signature, message: Data(bundle.policyText.utf8), publicKey: publicKey)
+
+        let compiled: CompiledRelayPolicy
+        do {
+            compiled = try RelayPolicyCompiler.compile(bundle.policyText, options: compileOptions)
+        } catch {
+            throw UpdateError.compileFailed(String(describing: error))
+        }
+        policy.install(compiled)
+        let generation = policy.generation
+        Task {
+            await logger.log(
+                level: .notice,
+                phase: .relay,
+                category: .relayTCP,
+                component: "RelayPolicyBundleUpdater",
+                event: "policy-bundle-applied",
+                result: "applied",
+                message: "Installed a verified policy bundle",
+                metadata: [
+                    "rule_count": String(compiled.rules.count),
+                    "generation": String(generation)
+                ]
+            )
+        }
+        return generation
+    }
+
+    private func verify(signature: Data, message: Data, publicKey: Data) throws {
+        #if canImport(CryptoKit)
+        guard let key = try? Curve25519.Signing.PublicKey(rawRepresentation: publicKey) else {
+            throw UpdateError.invalidPublicKey
+        }
+        guard key.isValidSignature(signature, for: message) else {
+            throw UpdateError.signatureRejected
+        }
+        #else
+        throw UpdateError.signingUnavailable
+        #endif
+    }
+}
@@ -0,0 +1,1231 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import TunnelRuntime
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Shaping parameters attached to a compiled `shape` rule.
+/// Decision: the relay enforces the burst cap (per-read chunk ceiling) and, for `rate=` rules,
+/// the pacing rate; latency and jitter are carried through so host-level shapers can honor
+/// them without a DSL change later.
+public struct RelayShapeParameters: Sendable, Equatable {
+    public let latencyMs: Int?
+    public let jitterMs: Int?
+    public let maxBurstBytes: Int
+    /// Target forwarding rate for paced flows, or `nil` for burst-cap-only shaping.
+    public let pacingBytesPerSecond: Int?
+
+    public init(latencyMs: Int? = nil, jitterMs: Int? = nil, maxBurstBytes: Int, pacingBytesPerSecond: Int? = nil) {
+        self.latencyMs = latencyMs
+        self.jitterMs = jitterMs
+        self.maxBurstBytes = max(1, maxBurstBytes)
+        self.pacingBytesPerSecond = pacingBytesPerSecond.map { max(1, $0) }
+    }
+}
+
+/// Built-in semantic selector backed by package heuristics instead of a host pattern.
+public enum RelayBuiltinSelector: Sendable, Equatable {
+    /// Encrypted-DNS destinations: DoT by port 853, DoH by known resolver hosts or addresses on 443.
+    /// Required where policy depends on observing plaintext DNS.
+    case encryptedDNS
+    /// OS-critical probe and clock-sync destinations: NTP by port 123, captive-portal and time
+    /// probes by known hostnames. Exists so experiments can pin an `allow` ahead of broad rules
+    /// without enumerating the endpoints themselves.
+    case osCritical
+    /// BitTorrent-family peer-to-peer traffic, matched by payload signature (peer handshake,
+    /// mainline DHT, uTP) so networks can throttle or block P2P without hostname rules.
+    case p2p
+}
+
+/// Geo-based rule selector resolved against the destination at flow admission time.
+public enum RelayGeoSelector: Sendable, Equatable {
+    /// Matches destinations attributed to an ISO 3166-1 alpha-2 country code.
+    case country(String)
+    /// Matches destinations attributed to an autonomous system number.
+    case asn(UInt32)
+}
+
+/// Precompiled, fully anchored regex host selector.
+/// Decision: patterns are compiled once at policy compile time and capped in size so a hostile
+/// or runaway document cannot turn per-dial matching into a regex denial of service; matching
+/// is case-insensitive and always spans the whole host, mirroring the wildcard selector.
+public struct RelayHostRegex: @unchecked Sendable, Equatable {
+    /// Upper bound on accepted pattern length.
+    public static let maxPatternLength = 256
+
+    public let pattern: String
+    private let regex: NSRegularExpression
+
+    init?(pattern: String) {
+        guard !pattern.isEmpty, pattern.count <= Self.maxPatternLength,
+              let compiled = try? NSRegularExpression(
+                  pattern: "\\A(?:\(pattern))\\z",
+                  options: [.caseInsensitive]
+              ) else {
+            return nil
+        }
+        self.pattern = pattern
+        self.regex = compiled
+    }
+
+    /// Returns `true` when the pattern matches the entire host.
+    func matches(_ host: String) -> Bool {
+        let range = NSRange(host.startIndex..<host.endIndex, in: host)
+        return regex.firstMatch(in: host, options: [], range: range) != nil
+    }
+
+    public static func == (lhs: RelayHostRegex, rhs: RelayHostRegex) -> Bool {
+        lhs.pattern == rhs.pattern
+    }
+}
+
+/// Parsed `src=<cidr>` selector scoping a rule to client devices behind the TUN.
+/// Decision: stored as raw address bytes plus a prefix length so matching is a byte/bit
+/// comparison per admission, with no string parsing on the hot path.
+public struct RelaySourceCIDR: Sendable, Equatable {
+    /// Canonical text form, e.g. `192.168.4.0/24` or `fd00::/8`.
+    public let description: String
+    private let network: [UInt8]
+    private let prefixLength: Int
+
+    /// Parses `address/prefix`; a bare address gets a full-length prefix. Returns `nil` for
+    /// unparseable addresses or out-of-range prefixes.
+    init?(_ text: String) {
+        let parts = text.split(separator: "/", maxSplits: 1)
+        guard let first = parts.first, let address = Self.addressBytes(String(first)) else {
+            return nil
+        }
+        let maxPrefix = address.count * 8
+        var prefix = maxPrefix
+        if parts.count == 2 {
+            guard let parsed = Int(parts[1]), (0...maxPrefix).contains(parsed) else {
+                return nil
+            }
+            prefix = parsed
+        }
+        self.description = text
+        self.network = address
+        self.prefixLength = prefix
+    }
+
+    /// Returns `true` when the address parses to the same family and shares the network prefix.
+    func contains(_ address: String) -> Bool {
+        guard let bytes = Self.addressBytes(address), bytes.count == network.count else {
+            return false
+        }
+        var remaining = prefixLength
+        for index in network.indices {
+            guard remaining > 0 else {
+                return true
+            }
+            if remaining >= 8 {
+                guard bytes[index] == network[index] else {
+                    return false
+                }
+                remaining -= 8
+            } else {
+                let mask = UInt8(0xff << (8 - remaining))
+                return (bytes[index] & mask) == (network[index] & mask)
+            }
+        }
+        return true
+    }
+
+    private static func addressBytes(_ text: String) -> [UInt8]? {
+        // Strip an interface scope suffix such as `%en0` before parsing.
+        let bare = text.split(separator: "%", maxSplits: 1).first.map(String.init) ?? text
+        var addr4 = in_addr()
+        if bare.withCString({ inet_pton(AF_INET, $0, &addr4) }) == 1 {
+            return withUnsafeBytes(of: addr4) { Array($0) }
+        }
+        var addr6 = in6_addr()
+        if bare.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 {
+            return withUnsafeBytes(of: addr6) { Array($0) }
+        }
+        return nil
+    }
+}
+
+/// One compiled policy statement matched against outbound dials in order.
+public struct RelayPolicyRule: Sendable, Equatable {
+    public enum Action: Sendable, Equatable {
+        case allow
+        case block
+        case shape(RelayShapeParameters)
+        /// Route matched flows through the named upstream proxy transport.
+        case route(tag: String)
+        /// Hold DNS responses for matching query names by the given delay. The action is
+        /// orthogonal to admission: it never decides a flow's verdict, so data flows to the
+        /// same hosts keep their full speed while resolution alone slows down.
+        case delayDNS(latencyMs: Int)
+    }
+
+    public let action: Action
+    /// Transport filter (`tcp` or `udp`); `nil` matches any transport.
+    public let transport: String?
+    /// Host pattern: exact name, `*.suffix` (matches the bare domain and subdomains), or `*`.
+    /// `nil` when the rule matches by geo selector instead.
+    public let hostPattern: String?
+    /// Anchored regex selector (`re:<pattern>`); `nil` for all other selector kinds.
+    public let hostRegex: RelayHostRegex?
+    /// Source-device scope (`src=<cidr>`); `nil` matches flows from any client device.
+    public let sourceCIDR: RelaySourceCIDR?
+    /// Destination port filter; `nil` matches any port.
+    public let port: UInt16?
+    /// Geo selector (`geo:CC` or `asn:NNNN`); `nil` for host-pattern rules.
+    public let geoSelector: RelayGeoSelector?
+    /// Built-in selector (`encrypted-dns`, `os-critical`, `class:p2p`); `nil` for host-pattern and geo rules.
+    public let builtinSelector: RelayBuiltinSelector?
+    /// When `true` the rule matches only flows whose ClientHello carried an ECH extension,
+    /// so it never fires at connect time — only on post-inspection re-evaluation.
+    public let requiresECH: Bool
+    /// JA3 fingerprint selector (`ja3:<md5>`); like `requiresECH`, the fingerprint exists only
+    /// after ClientHello inspection, so such rules never fire at connect time.
+    /// `nil` for rules that match by host, geo, or built-in selector.
+    public let ja3Selector: String?
+    /// Named resolver (`resolver=<name>`) hostnames matching this rule resolve through;
+    /// `nil` uses the system resolver.
+    public let resolverTag: String?
+    /// Nagle preference (`nodelay=on|off`) applied to the outbound socket of matching flows:
+    /// `true` disables Nagle for interactive traffic, `false` forces coalescing for bulk
+    /// transfers, and `nil` keeps the platform default.
+    public let noDelay: Bool?
+
+    init(
+        action: Action,
+        transport: String?,
+        hostPattern: String?,
+        port: UInt16?,
+        hostRegex: RelayHostRegex? = nil,
+        sourceCIDR: RelaySourceCIDR? = nil,
+        geoSelector: RelayGeoSelector? = nil,
+        builtinSelector: RelayBuiltinSelector? = nil,
+        requiresECH: Bool = false,
+        ja3Selector: String? = nil,
+        resolverTag: String? = nil,
+        noDelay: Bool? = nil
+    ) {
+        self.action = action
+        self.transport = transport
+        self.hostPattern = hostPattern
+        self.hostRegex = hostRegex
+        self.sourceCIDR = sourceCIDR
+        self.port = port
+        self.geoSelector = geoSelector
+        self.builtinSelector = builtinSelector
+        self.requiresECH = requiresECH
+        self.ja3Selector = ja3Selector
+        self.resolverTag = resolverTag
+        self.noDelay = noDelay
+    }
+
+    func matches(_ input: RelayPolicyInput, geoInfo: RelayGeoIPInfo?) -> Bool {
+        mismatchReason(input, geoInfo: geoInfo) == nil
+    }
+
+    /// Why this rule did not match the input, or `nil` when it matched.
+    /// The strings feed the admission trace, so each names the selector that failed and the
+    /// values compared instead of a bare "no match". Checks run in the same order as `matches`,
+    /// so the reported reason is the first gate the flow fell through.
+    func mismatchReason(_ input: RelayPolicyInput, geoInfo: RelayGeoIPInfo?) -> String? {
+        if requiresECH && !input.echDetected {
+            return "requires ech; flow carries no inspected ClientHello with ECH"
+        }
+        if let transport, transport != input.transport.lowercased() {
+            return "transport is \(input.transport.lowercased()), rule wants \(transport)"
+        }
+        if let port, port != input.port {
+            return "port is \(input.port), rule wants \(port)"
+        }
+        if let sourceCIDR {
+            // Flows without source attribution never match device-scoped rules.
+            guard let sourceAddress = input.sourceAddress else {
+                return "rule is scoped src=\(sourceCIDR.description) and the flow has no source attribution"
+            }
+            guard sourceCIDR.contains(sourceAddress) else {
+                return "source \(sourceAddress) is outside src=\(sourceCIDR.description)"
+            }
+        }
+        if let ja3Selector {
+            guard input.ja3?.lowercased() == ja3Selector else {
+                return "ja3 is \(input.ja3?.lowercased() ?? "absent"), rule wants \(ja3Selector)"
+            }
+            return nil
+        }
+        if let builtinSelector {
+            switch builtinSelector {
+            case .encryptedDNS:
+                guard EncryptedDNSClassifier.classify(
+                    destinationPort: input.port,
+                    serverName: input.host,
+                    destinationAddress: input.host
+                ) != nil else {
+                    return "destination is not a known encrypted-DNS endpoint"
+                }
+                return nil
+            case .osCritical:
+                guard OSCriticalEndpointClassifier.isOSCritical(
+                    destinationPort: input.port,
+                    host: input.host
+                ) else {
+                    return "destination is not a known OS probe or NTP endpoint"
+                }
+                return nil
+            case .p2p:
+                guard P2PTrafficClassifier.isP2P(transport: input.transport, payload: input.firstPayloadSnippet) else {
+                    return "first payload carries no BitTorrent/DHT/uTP signature"
+                }
+                return nil
+            }
+        }
+        if let geoSelector {
+            guard let geoInfo else {
+                return "destination has no geo attribution (no resolver or unattributed address)"
+            }
+            switch geoSelector {
+            case .country(let code):
+                guard geoInfo.countryCode == code else {
+                    return "destination country is \(geoInfo.countryCode ?? "unknown"), rule wants \(code)"
+                }
+                return nil
+            case .asn(let number):
+                guard geoInfo.autonomousSystemNumber == number else {
+                    return "destination ASN is \(geoInfo.autonomousSystemNumber.map(String.init) ?? "unknown"), rule wants \(number)"
+                }
+                return nil
+            }
+        }
+        let normalizedHost = HostNormalizer.normalize(input.host)
+        if let hostRegex {
+            guard hostRegex.matches(normalizedHost) else {
+                return "host '\(normalizedHost)' does not match re:\(hostRegex.pattern)"
+            }
+            return nil
+        }
+        guard let hostPattern else {
+            return "rule has no host selector"
+        }
+        guard Self.hostMatches(pattern: hostPattern, host: normalizedHost) else {
+            return "host '\(normalizedHost)' does not match pattern '\(hostPattern)'"
+        }
+        return nil
+    }
+
+    /// Compact selector text mirroring the DSL form of the rule, for trace and log output.
+    public var selectorDescription: String {
+        var parts: [String] = []
+        if let transport {
+            parts.append(transport)
+        }
+        if requiresECH {
+            parts.append("ech")
+        }
+        if let hostPattern {
+            parts.append(port.map { "\(hostPattern):\($0)" } ?? hostPattern)
+        }
+        if let hostRegex {
+            parts.append("re:\(hostRegex.pattern)")
+        }
+        if let geoSelector {
+            switch geoSelector {
+            case .country(let code):
+                parts.append("geo:\(code)")
+            case .asn(let number):
+                parts.append("asn:\(number)")
+            }
+        }
+        if let builtinSelector {
+            switch builtinSelector {
+            case .encryptedDNS:
+                parts.append("encrypted-dns")
+            case .osCritical:
+                parts.append("os-critical")
+            case .p2p:
+                parts.append("class:p2p")
+            }
+        }
+        if let ja3Selector {
+            parts.append("ja3:\(ja3Selector)")
+        }
+        if let sourceCIDR {
+            parts.append("src=\(sourceCIDR.description)")
+        }
+        return parts.joined(separator: " ")
+    }
+
+    private static func hostMatches(pattern: String, host: String) -> Bool {
+        if pattern == "*" {
+            return true
+        }
+        if pattern.hasPrefix("*.") {
+            let bare = String(pattern.dropFirst(2))
+            return host == bare || host.hasSuffix(".\(bare)")
+        }
+        return host == pattern
+    }
+}
+
+/// Compilation failure carrying the one-based statement number and a human-readable reason.
+public enum RelayPolicyCompileError: Error, Equatable, CustomStringConvertible {
+    case invalidStatement(statement: Int, reason: String)
+
+    public var description: String {
+        switch self {
+        case .invalidStatement(let statement, let reason):
+            return "policy statement \(statement): \(reason)"
+        }
+    }
+}
+
+/// Outcome of a dry-run policy evaluation: which statement won and what it would do.
+public struct RelayPolicyEvaluationResult: Sendable, Equatable {
+    /// One-based statement number of the winning rule in document order, matching the numbers
+    /// used by `RelayPolicyCompileError`; `nil` when no rule matched and the implicit allow applied.
+    public let matchedStatement: Int?
+    public let matchedRule: RelayPolicyRule?
+    public let verdict: RelayPolicyVerdict
+    /// Named resolver the winning rule would route hostname resolution through, if any.
+    public let resolverTag: String?
+
+    public init(
+        matchedStatement: Int?,
+        matchedRule: RelayPolicyRule?,
+        verdict: RelayPolicyVerdict,
+        resolverTag: String?
+    ) {
+        self.matchedStatement = matchedStatement
+        self.matchedRule = matchedRule
+        self.verdict = verdict
+        self.resolverTag = resolverTag
+    }
+}
+
+/// Where the host string a policy decision keyed on came from. Policy inputs in this relay
+/// carry the destination exactly as the client sent it in the SOCKS request; re-evaluations
+/// after ClientHello inspection additionally carry the inspected hello's ECH/JA3 fields.
+public enum RelayPolicyHostSource: String, Sendable {
+    /// The SOCKS request carried an IPv4 address literal.
+    case ipv4Literal = "ipv4-literal"
+    /// The SOCKS request carried an IPv6 address literal.
+    case ipv6Literal = "ipv6-literal"
+    /// The SOCKS request carried a hostname the client resolved (or delegated) via DNS.
+    case clientHostname = "client-hostname"
+    /// Post-inspection re-evaluation; selector fields reflect the inspected ClientHello.
+    case inspectedClientHello = "inspected-client-hello"
+}
+
+/// Full admission trace for one flow: every rule in document order with the reason it did or
+/// did not match. Decision: evaluation is first-match-wins, but the trace walks the whole
+/// document anyway — "why wasn't this blocked?" is usually answered by a matching block rule
+/// shadowed by an earlier statement, which a trace truncated at the winner could not show.
+public struct RelayPolicyTrace: Sendable, Equatable {
+    public struct Step: Sendable, Equatable {
+        /// One-based statement number, matching `explain` and compile diagnostics.
+        public let statement: Int
+        public let rule: RelayPolicyRule
+        public let matched: Bool
+        /// Why the rule matched or did not, naming the selector and the values compared.
+        public let reason: String
+
+        public init(statement: Int, rule: RelayPolicyRule, matched: Bool, reason: String) {
+            self.statement = statement
+            self.rule = rule
+            self.matched = matched
+            self.reason = reason
+        }
+    }
+
+    /// Destination host after the same normalization rule matching applies.
+    public let normalizedHost: String
+    public let hostSource: RelayPolicyHostSource
+    public let steps: [Step]
+    /// Verdict live evaluation returns for the same input (first matching step wins;
+    /// implicit allow when nothing matched).
+    public let verdict: RelayPolicyVerdict
+
+    public init(normalizedHost: String, hostSource: RelayPolicyHostSource, steps: [Step], verdict: RelayPolicyVerdict) {
+        self.normalizedHost = normalizedHost
+        self.hostSource = hostSource
+        self.steps = steps
+        self.verdict = verdict
+    }
+
+    /// One-line rendering for log metadata, one `#statement` entry per rule.
+    public var summary: String {
+        steps.map { step in
+            "#\(step.statement) \(step.matched ? "matched" : "skipped") [\(step.rule.selectorDescription)]: \(step.reason)"
+        }.joined(separator: " | ")
+    }
+}
+
+/// Compiled policy document; conforms to `RelayPolicyEvaluator` with first-match-wins semantics.
+/// Flows matching no rule are allowed, so an empty document is equivalent to no policy.
+public struct CompiledRelayPolicy: RelayPolicyEvaluator, Sendable {
+    public let rules: [RelayPolicyRule]
+    /// Optional destination lookup backing `geo:`/`asn:` selectors. Rules with geo selectors
+    /// never match when no resolver is installed.
+    public let geoIPResolver: (any RelayGeoIPResolver)?
+    /// Non-fatal compile diagnostics, e.g. a wildcard spanning an entire public suffix.
+    /// Warnings never change evaluation; they exist for pre-deployment review UX.
+    public let warnings: [String]
+    /// When `true`, OS-critical destinations (NTP, captive-portal and time probes) are allowed
+    /// before any rule runs, so no document can block, shape, or reroute them. Set through
+    /// `RelayPolicyCompiler.Options.protectOSCriticalEndpoints`.
+    public let protectsOSCriticalEndpoints: Bool
+
+    public init(
+        rules: [RelayPolicyRule],
+        geoIPResolver: (any RelayGeoIPResolver)? = nil,
+        warnings: [String] = [],
+        protectsOSCriticalEndpoints: Bool = false
+    ) {
+        self.rules = rules
+        self.geoIPResolver = geoIPResolver
+        self.warnings = warnings
+        self.protectsOSCriticalEndpoints = protectsOSCriticalEndpoints
+    }
+
+    /// Returns a copy of this policy backed by the given resolver.
+    public func withGeoIPResolver(_ resolver: any RelayGeoIPResolver) -> CompiledRelayPolicy {
+        CompiledRelayPolicy(
+            rules: rules,
+            geoIPResolver: resolver,
+            warnings: warnings,
+            protectsOSCriticalEndpoints: protectsOSCriticalEndpoints
+        )
+    }
+
+    public func evaluate(_ input: RelayPolicyInput) -> RelayPolicyVerdict {
+        if isProtectedOSCriticalFlow(input) {
+            return .allow
+        }
+        // One lookup per admission, and only when the document actually uses geo selectors.
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        for rule in rules where rule.matches(input, geoInfo: geoInfo) {
+            switch rule.action {
+            case .allow:
+                return .allow
+            case .block:
+                return .block
+            case .shape(let parameters):
+                return .shape(
+                    maxBurstBytes: parameters.maxBurstBytes,
+                    pacingBytesPerSecond: parameters.pacingBytesPerSecond
+                )
+            case .route(let tag):
+                return .route(tag: tag)
+            case .delayDNS:
+                // Resolution-timing rules never decide flow admission.
+                continue
+            }
+        }
+        return .allow
+    }
+
+    /// Dry-run evaluation for "test this rule" UX and pre-deployment config validation.
+    /// Runs the same first-match-wins pipeline as `evaluate` without admitting a flow and
+    /// reports which statement would win. Inspection-only selectors (`ech`, `ja3:`) match
+    /// only when the probe input carries those fields, exactly as live re-evaluation would.
+    public func explain(_ input: RelayPolicyInput) -> RelayPolicyEvaluationResult {
+        if isProtectedOSCriticalFlow(input) {
+            // The exemption wins before any statement runs, so there is no winner to report.
+            return RelayPolicyEvaluationResult(matchedStatement: nil, matchedRule: nil, verdict: .allow, resolverTag: nil)
+        }
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        for (index, rule) in rules.enumerated() where rule.matches(input, geoInfo: geoInfo) {
+            let verdict: RelayPolicyVerdict
+            var resolverTag: String?
+            switch rule.action {
+            case .allow:
+                verdict = .allow
+                resolverTag = rule.resolverTag
+            case .block:
+                verdict = .block
+            case .shape(let parameters):
+                verdict = .shape(
+                    maxBurstBytes: parameters.maxBurstBytes,
+                    pacingBytesPerSecond: parameters.pacingBytesPerSecond
+                )
+                resolverTag = rule.resolverTag
+            case .route(let tag):
+                verdict = .route(tag: tag)
+                resolverTag = rule.resolverTag
+            case .delayDNS:
+                // Resolution-timing rules never win admission; keep scanning.
+                continue
+            }
+            return RelayPolicyEvaluationResult(
+                matchedStatement: index + 1,
+                matchedRule: rule,
+                verdict: verdict,
+                resolverTag: resolverTag
+            )
+        }
+        return RelayPolicyEvaluationResult(matchedStatement: nil, matchedRule: nil, verdict: .allow, resolverTag: nil)
+    }
+
+    /// Debug-mode admission trace: checks every rule against the input and records why each
+    /// did or did not match. Unlike `evaluate`, the walk does not stop at the first match, so
+    /// a block rule shadowed by an earlier statement still shows up as matching. Reason
+    /// strings allocate per rule; keep this behind a tracing flag or tooling, off the
+    /// always-on admission path.
+    public func trace(_ input: RelayPolicyInput) -> RelayPolicyTrace {
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        let normalizedHost = HostNormalizer.normalize(input.host)
+        var steps: [RelayPolicyTrace.Step] = []
+        var verdict: RelayPolicyVerdict?
+        for (index, rule) in rules.enumerated() {
+            if let reason = rule.mismatchReason(input, geoInfo: geoInfo) {
+                steps.append(RelayPolicyTrace.Step(statement: index + 1, rule: rule, matched: false, reason: reason))
+                continue
+            }
+            steps.append(RelayPolicyTrace.Step(
+                statement: index + 1,
+                rule: rule,
+                matched: true,
+                reason: "selector matched host '\(normalizedHost)'"
+            ))
+            if verdict == nil {
+                verdict = Self.verdict(for: rule.action)
+            }
+        }
+        // Protected OS-critical flows keep their full step list — "why did my block rule not
+        // fire?" is answered by seeing it match here — but the verdict mirrors live evaluation.
+        let effectiveVerdict: RelayPolicyVerdict = isProtectedOSCriticalFlow(input) ? .allow : (verdict ?? .allow)
+        return RelayPolicyTrace(
+            normalizedHost: normalizedHost,
+            hostSource: Self.hostSource(for: input),
+            steps: steps,
+            verdict: effectiveVerdict
+        )
+    }
+
+    /// Whether the OS-critical exemption decides this input before any rule runs.
+    private func isProtectedOSCriticalFlow(_ input: RelayPolicyInput) -> Bool {
+        protectsOSCriticalEndpoints
+            && OSCriticalEndpointClassifier.isOSCritical(destinationPort: input.port, host: input.host)
+    }
+
+    /// Connection verdict an action produces, or `nil` for resolution-timing actions that
+    /// never decide admission.
+    private static func verdict(for action: RelayPolicyRule.Action) -> RelayPolicyVerdict? {
+        switch action {
+        case .allow:
+            return .allow
+        case .block:
+            return .block
+        case .shape(let parameters):
+            return .shape(
+                maxBurstBytes: parameters.maxBurstBytes,
+                pacingBytesPerSecond: parameters.pacingBytesPerSecond
+            )
+        case .route(let tag):
+            return .route(tag: tag)
+        case .delayDNS:
+            return nil
+        }
+    }
+
+    private static func hostSource(for input: RelayPolicyInput) -> RelayPolicyHostSource {
+        var addr4 = in_addr()
+        if input.host.withCString({ inet_pton(AF_INET, $0, &addr4) }) == 1 {
+            return .ipv4Literal
+        }
+        var addr6 = in6_addr()
+        if input.host.withCString({ inet_pton(AF_INET6, $0, &addr6) }) == 1 {
+            return .ipv6Literal
+        }
+        if input.echDetected || input.ja3 != nil {
+            return .inspectedClientHello
+        }
+        return .clientHostname
+    }
+
+    public func resolverTag(_ input: RelayPolicyInput) -> String? {
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        // First match wins, mirroring `evaluate`; blocked flows never resolve and
+        // resolution-timing rules carry no resolver.
+        for rule in rules where rule.matches(input, geoInfo: geoInfo) {
+            if case .delayDNS = rule.action {
+                continue
+            }
+            if case .block = rule.action {
+                return nil
+            }
+            return rule.resolverTag
+        }
+        return nil
+    }
+
+    public func noDelay(_ input: RelayPolicyInput) -> Bool? {
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        // First match wins, mirroring `evaluate`; blocked flows never dial and
+        // resolution-timing rules carry no socket preference.
+        for rule in rules where rule.matches(input, geoInfo: geoInfo) {
+            if case .delayDNS = rule.action {
+                continue
+            }
+            if case .block = rule.action {
+                return nil
+            }
+            return rule.noDelay
+        }
+        return nil
+    }
+
+    public func dnsResponseDelayMilliseconds(_ input: RelayPolicyInput) -> Int? {
+        // Held resolution breaks captive-portal checks as surely as a blocked dial would,
+        // so the OS-critical exemption covers resolution timing too.
+        if isProtectedOSCriticalFlow(input) {
+            return nil
+        }
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, rules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        // Only delay-dns rules participate, so admission rules never shadow a delay and a
+        // delayed name can still be blocked or shaped when its flows dial.
+        for rule in rules where rule.matches(input, geoInfo: geoInfo) {
+            if case .delayDNS(let latencyMs) = rule.action {
+                return latencyMs
+            }
+        }
+        return nil
+    }
+
+    public func shouldInspectClientHello(_ input: RelayPolicyInput) -> Bool {
+        let inspectionRules = rules.filter { $0.requiresECH || $0.ja3Selector != nil }
+        guard !inspectionRules.isEmpty else {
+            return false
+        }
+        var geoInfo: RelayGeoIPInfo?
+        if let geoIPResolver, inspectionRules.contains(where: { $0.geoSelector != nil }) {
+            geoInfo = geoIPResolver.lookup(address: input.host)
+        }
+        // The real fingerprint is unknown before the hello arrives, so probe each rule with
+        // the inputs that would satisfy its inspection-only selectors.
+        return inspectionRules.contains { rule in
+            let probe = RelayPolicyInput(
+                host: input.host,
+                port: input.port,
+                transport: input.transport,
+                firstPayloadSnippet: input.firstPayloadSnippet,
+                echDetected: true,
+                ja3: rule.ja3Selector,
+                sourceAddress: input.sourceAddress
+            )
+            return rule.matches(probe, geoInfo: geoInfo)
+        }
+    }
+}
+
+/// Compiler for the declarative relay policy DSL.
+/// Grammar (statements separated by `;` or newlines, `#` starts a line comment):
+///     statement := action [transport] [ech] selector [key=value ...]
+///     action    := allow | block | shape | route | delay-dns
+///     transport := tcp | udp
+///     selector  := hostpattern[:port] | re:<pattern> | geo:CC | asn:NNNN | encrypted-dns | os-critical | class:p2p | ja3:<md5>
+/// `shape` accepts `latency=<ms>`, `jitter=<ms>`, and either `burst=<bytes>` or
+/// `rate=<n>bps|kbps|mbps`. A rate converts to a one-second burst allowance and additionally
+/// paces the flow, so bytes spread across the second instead of arriving as one burst.
+/// `re:<pattern>` matches the whole host against a precompiled, case-insensitive regex and is
+/// capped at `RelayHostRegex.maxPatternLength` characters so documents cannot smuggle in
+/// pathological patterns; patterns are anchored implicitly, so `^`/`$` are redundant but legal.
+/// `geo:`/`asn:` selectors are gated behind `Options.geoSelectorsEnabled` and require a
+/// `RelayGeoIPResolver` on the compiled policy to ever match. `encrypted-dns` matches DoT
+/// (port 853) and known public DoH resolvers via `EncryptedDNSClassifier`. `os-critical`
+/// matches NTP (port 123) and known OS probe hosts via `OSCriticalEndpointClassifier`, so an
+/// `allow os-critical` pinned ahead of broad block or shape rules keeps clock sync and
+/// captive-portal checks working; `Options.protectOSCriticalEndpoints` applies the same
+/// exemption without a document change. `class:p2p` matches BitTorrent-family traffic by
+/// payload signature (peer handshake, mainline DHT, uTP) via `P2PTrafficClassifier`, so
+/// `block class:p2p` or a shape rule works without enumerating tracker hostnames; encrypted
+/// peer variants carry no signature and slip through. The `ech` modifier
+/// restricts a rule to flows whose ClientHello carries an Encrypted Client Hello extension;
+/// such rules fire only after the relay has inspected the first client bytes. `ja3:<md5>`
+/// matches the JA3 fingerprint of the inspected ClientHello and likewise never fires at
+/// connect time. `route` takes a required `via=<tag>` naming an upstream proxy transport and is
+/// gated behind `Options.routeActionsEnabled`; unresolved tags fail the dial at connect time.
+/// `delay-dns` takes a required `latency=<ms>` and holds pooled DNS responses for matching
+/// query names that long before delivery; it never admits, blocks, or shapes flows, so data
+/// transfers to the same hosts keep full speed while apps experience slow resolution.
+/// Every non-block rule also accepts `resolver=<name>` naming the `RelayHostResolvers` entry
+/// matching hostnames resolve through; omitting it uses the system resolver. Every rule accepts
+/// `src=<cidr>` scoping it to client devices whose source address falls in the given IPv4/IPv6
+/// network, for standalone/router deployments with multiple devices behind the TUN; flows
+/// without source attribution never match `src=` rules. Non-block rules also accept
+/// `nodelay=on|off`, disabling Nagle on matching flows (interactive SSH, gaming) or forcing
+/// coalescing for bulk flows; omitting it keeps the platform default.
+public enum RelayPolicyCompiler {
+    /// Compile-time feature gates.
+    public struct Options: Sendable, Equatable {
+        /// Enables `geo:CC` and `asn:NNNN` selectors for compliance and geo-fencing documents.
+        public var geoSelectorsEnabled: Bool
+        /// Enables the `route ... via=<tag>` action for split-tunneling through named
+        /// upstream proxy transports.
+        public var routeActionsEnabled: Bool
+        /// Optional public-suffix rules enabling wildcard sanity warnings; `nil` disables the
+        /// check entirely. Pass `.embedded` for the curated seed or a full PSL snapshot loaded
+        /// through `PublicSuffixList(listText:)`.
+        public var publicSuffixList: PublicSuffixList?
+        /// Exempts OS-critical destinations (the `os-critical` category) from every admission
+        /// rule in the document, so policy experiments cannot break clock sync or
+        /// captive-portal checks. Off by default: the exemption is also a bypass, and hosts
+        /// that shape everything deliberately should opt in rather than discover it.
+        public var protectOSCriticalEndpoints: Bool
+
+        public init(
+            geoSelectorsEnabled: Bool = false,
+            routeActionsEnabled: Bool = false,
+            publicSuffixList: PublicSuffixList? = nil,
+            protectOSCriticalEndpoints: Bool = false
+        ) {
+            self.geoSelectorsEnabled = geoSelectorsEnabled
+            self.routeActionsEnabled = routeActionsEnabled
+            self.publicSuffixList = publicSuffixList
+            self.protectOSCriticalEndpoints = protectOSCriticalEndpoints
+        }
+
+        public static let `default` = Options()
+    }
+
+    /// Compiles a whole policy document into an installable evaluator.
+    /// - Throws: `RelayPolicyCompileError` naming the first offending statement.
+    public static func compile(_ text: String, options: Options = .default) throws -> CompiledRelayPolicy {
+        var rules: [RelayPolicyRule] = []
+        var warnings: [String] = []
+        var statementNumber = 0
+
+        let withoutComments = text
+            .split(separator: "\n", omittingEmptySubsequences: false)
+            .map { line -> String in
+                if let hash = line.firstIndex(of: "#") {
+                    return String(line[line.startIndex..<hash])
+                }
+                return String(line)
+            }
+            .joined(separator: "\n")
+
+        for rawStatement in withoutComments.split(whereSeparator: { $0 == ";" || $0 == "\n" }) {
+            let tokens = rawStatement.split(whereSeparator: { $0 == " " || $0 == "\t" }).map(String.init)
+            guard !tokens.isEmpty else {
+                continue
+            }
+            statementNumber += 1
+            let rule = try compileStatement(tokens: tokens, statement: statementNumber, options: options)
+            if let publicSuffixList = options.publicSuffixList,
+               let hostPattern = rule.hostPattern, hostPattern.hasPrefix("*."),
+               publicSuffixList.isPublicSuffix(String(hostPattern.dropFirst(2))) {
+                warnings.append(
+                    "statement \(statementNumber): '\(hostPattern)' spans the entire public suffix "
+                        + "'\(hostPattern.dropFirst(2))'; every registrant under it will match"
+                )
+            }
+            rules.append(rule)
+        }
+
+        return CompiledRelayPolicy(
+            rules: rules,
+            warnings: warnings,
+            protectsOSCriticalEndpoints: options.protectOSCriticalEndpoints
+        )
+    }
+
+    private static func compileStatement(tokens: [String], statement: Int, options: Options) throws -> RelayPolicyRule {
+        var remaining = tokens[...]
+
+        let actionToken = remaining.removeFirst().lowercased()
+        guard actionToken == "allow" || actionToken == "block" || actionToken == "shape" || actionToken == "route"
+            || actionToken == "delay-dns"
+        else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "unknown action '\(actionToken)'; expected allow, block, shape, route, or delay-dns"
+            )
+        }
+        if actionToken == "route", !options.routeActionsEnabled {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "action 'route' requires the routeActionsEnabled compile option"
+            )
+        }
+
+        var transport: String?
+        if let first = remaining.first?.lowercased(), first == "tcp" || first == "udp" {
+            transport = first
+            remaining.removeFirst()
+        }
+
+        var requiresECH = false
+        if remaining.first?.lowercased() == "ech" {
+            requiresECH = true
+            remaining.removeFirst()
+        }
+
+        guard let target = remaining.first else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "missing selector after '\(actionToken)'"
+            )
+        }
+        remaining.removeFirst()
+
+        let hostPattern: String?
+        let port: UInt16?
+        let geoSelector: RelayGeoSelector?
+        let builtinSelector: RelayBuiltinSelector?
+        var ja3Selector: String?
+        var hostRegex: RelayHostRegex?
+        if target.lowercased() == "encrypted-dns" {
+            builtinSelector = .encryptedDNS
+            hostPattern = nil
+            port = nil
+            geoSelector = nil
+        } else if target.lowercased() == "os-critical" {
+            builtinSelector = .osCritical
+            hostPattern = nil
+            port = nil
+            geoSelector = nil
+        } else if target.lowercased().hasPrefix("class:") {
+            guard target.lowercased() == "class:p2p" else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "unknown traffic class '\(target)'; the only supported class selector is class:p2p"
+                )
+            }
+            builtinSelector = .p2p
+            hostPattern = nil
+            port = nil
+            geoSelector = nil
+        } else if target.lowercased().hasPrefix("re:") {
+            guard let compiled = RelayHostRegex(pattern: String(target.dropFirst(3))) else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "regex selector '\(target)' must be a valid pattern of at most \(RelayHostRegex.maxPatternLength) characters"
+                )
+            }
+            hostRegex = compiled
+            hostPattern = nil
+            port = nil
+            geoSelector = nil
+            builtinSelector = nil
+        } else if target.lowercased().hasPrefix("ja3:") {
+            let digest = String(target.dropFirst(4)).lowercased()
+            guard digest.count == 32, digest.allSatisfy(\.isHexDigit) else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "ja3 selector '\(target)' must use a 32-character hex MD5 digest"
+                )
+            }
+            ja3Selector = digest
+            hostPattern = nil
+            port = nil
+            geoSelector = nil
+            builtinSelector = nil
+        } else if target.lowercased().hasPrefix("geo:") || target.lowercased().hasPrefix("asn:") {
+            guard options.geoSelectorsEnabled else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "selector '\(target)' requires the geoSelectorsEnabled compile option"
+                )
+            }
+            geoSelector = try parseGeoSelector(target, statement: statement)
+            hostPattern = nil
+            port = nil
+            builtinSelector = nil
+        } else {
+            let parsed = try parseTarget(target, statement: statement)
+            hostPattern = parsed.host
+            port = parsed.port
+            geoSelector = nil
+            builtinSelector = nil
+        }
+
+        var latencyMs: Int?
+        var jitterMs: Int?
+        var burstBytes: Int?
+        var pacingBytesPerSecond: Int?
+        var routeTag: String?
+        var resolverTag: String?
+        var sourceCIDR: RelaySourceCIDR?
+        var noDelay: Bool?
+        for token in remaining {
+            let pair = token.split(separator: "=", maxSplits: 1)
+            guard pair.count == 2 else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "expected key=value parameter, found '\(token)'"
+                )
+            }
+            let key = pair[0].lowercased()
+            let value = String(pair[1])
+            if key == "src" {
+                guard let parsed = RelaySourceCIDR(value) else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "src parameter '\(value)' must be an IPv4/IPv6 address or CIDR, such as src=192.168.4.0/24"
+                    )
+                }
+                sourceCIDR = parsed
+                continue
+            }
+            if key == "nodelay" {
+                guard actionToken != "block" else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "block rules never dial and take no nodelay parameter"
+                    )
+                }
+                guard actionToken != "delay-dns" else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "delay-dns rules act on resolution timing and take no nodelay parameter"
+                    )
+                }
+                switch value.lowercased() {
+                case "on", "true":
+                    noDelay = true
+                case "off", "false":
+                    noDelay = false
+                default:
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "nodelay parameter must be on or off, found '\(value)'"
+                    )
+                }
+                continue
+            }
+            if key == "resolver" {
+                guard actionToken != "block" else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "block rules never resolve and take no resolver parameter"
+                    )
+                }
+                guard actionToken != "delay-dns" else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "delay-dns rules act on resolution timing and take no resolver parameter"
+                    )
+                }
+                guard !value.isEmpty else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "resolver parameter requires a name, such as resolver=doh-primary"
+                    )
+                }
+                resolverTag = value
+                continue
+            }
+            if actionToken == "delay-dns" {
+                guard key == "latency" else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "delay-dns rules take a required latency=<ms> parameter, found '\(token)'"
+                    )
+                }
+                latencyMs = try parsePositiveInt(value, key: key, statement: statement)
+                continue
+            }
+            if actionToken == "route" {
+                guard key == "via", !value.isEmpty else {
+                    throw RelayPolicyCompileError.invalidStatement(
+                        statement: statement,
+                        reason: "route rules take via=<tag> and optional resolver=<name> parameters, found '\(token)'"
+                    )
+                }
+                routeTag = value
+                continue
+            }
+            guard actionToken == "shape" else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "parameter '\(token)' is only valid on shape rules"
+                )
+            }
+            switch key {
+            case "latency":
+                latencyMs = try parsePositiveInt(value, key: key, statement: statement)
+            case "jitter":
+                jitterMs = try parsePositiveInt(value, key: key, statement: statement)
+            case "burst":
+                burstBytes = try parsePositiveInt(value, key: key, statement: statement)
+            case "rate":
+                // A rate is both the one-second burst allowance and a pacing target, so the
+                // relay can spread the allowance across the second instead of releasing it whole.
+                burstBytes = try parseRateBytesPerSecond(value, statement: statement)
+                pacingBytesPerSecond = burstBytes
+            default:
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "unknown shape parameter '\(key)'; expected latency, jitter, burst, or rate"
+                )
+            }
+        }
+
+        let action: RelayPolicyRule.Action
+        switch actionToken {
+        case "allow":
+            action = .allow
+        case "block":
+            action = .block
+        case "route":
+            guard let routeTag else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "route rules require via=<tag> naming an upstream transport"
+                )
+            }
+            action = .route(tag: routeTag)
+        case "delay-dns":
+            guard let latencyMs else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "delay-dns rules require latency=<ms> naming the response hold time"
+                )
+            }
+            action = .delayDNS(latencyMs: latencyMs)
+        default:
+            guard let burstBytes else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "shape rules require burst=<bytes> or rate=<n>bps|kbps|mbps"
+                )
+            }
+            action = .shape(RelayShapeParameters(
+                latencyMs: latencyMs,
+                jitterMs: jitterMs,
+                maxBurstBytes: burstBytes,
+                pacingBytesPerSecond: pacingBytesPerSecond
+            ))
+        }
+
+        return RelayPolicyRule(
+            action: action,
+            transport: transport,
+            hostPattern: hostPattern.map(HostNormalizer.normalize),
+            port: port,
+            hostRegex: hostRegex,
+            sourceCIDR: sourceCIDR,
+            geoSelector: geoSelector,
+            builtinSelector: builtinSelector,
+            requiresECH: requiresECH,
+            ja3Selector: ja3Selector,
+            resolverTag: resolverTag,
+            noDelay: noDelay
+        )
+    }
+
+    private static func parseGeoSelector(_ target: String, statement: Int) throws -> RelayGeoSelector {
+        let parts = target.split(separator: ":", maxSplits: 1)
+        let kind = parts[0].lowercased()
+        let value = parts.count == 2 ? String(parts[1]) : ""
+        switch kind {
+        case "geo":
+            guard value.count == 2, value.allSatisfy(\.isLetter) else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "geo selector '\(target)' must use a two-letter country code such as geo:CN"
+                )
+            }
+            return .country(value.uppercased())
+        default:
+            guard let number = UInt32(value) else {
+                throw RelayPolicyCompileError.invalidStatement(
+                    statement: statement,
+                    reason: "asn selector '\(target)' must use a numeric autonomous system number such as asn:13335"
+                )
+            }
+            return .asn(number)
+        }
+    }
+
+    private static func parseTarget(_ target: String, statement: Int) throws -> (host: String, port: UInt16?) {
+        // IPv6 literals contain colons; only treat the final colon as a port separator when the
+        // remainder is all digits, so `2001:db8::1` stays a bare host.
+        guard let lastColon = target.lastIndex(of: ":") else {
+            return (target, nil)
+        }
+        let portText = target[target.index(after: lastColon)...]
+        guard !portText.isEmpty, portText.allSatisfy(\.isNumber) else {
+            return (target, nil)
+        }
+        guard let port = UInt16(portText) else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "port '\(portText)' must be in 0...65535"
+            )
+        }
+        let host = String(target[target.startIndex..<lastColon])
+        guard !host.isEmpty else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "missing host pattern before ':\(portText)'"
+            )
+        }
+        return (host, port)
+    }
+
+    private static func parsePositiveInt(_ value: String, key: String, statement: Int) throws -> Int {
+        guard let parsed = Int(value), parsed > 0 else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "\(key) '\(value)' must be a positive integer"
+            )
+        }
+        return parsed
+    }
+
+    private static func parseRateBytesPerSecond(_ value: String, statement: Int) throws -> Int {
+        let lowered = value.lowercased()
+        let multiplierBits: Int
+        let digits: Substring
+        if lowered.hasSuffix("mbps") {
+            multiplierBits = 1_000_000
+            digits = lowered.dropLast(4)
+        } else if lowered.hasSuffix("kbps") {
+            multiplierBits = 1_000
+            digits = lowered.dropLast(4)
+        } else if lowered.hasSuffix("bps") {
+            multiplierBits = 1
+            digits = lowered.dropLast(3)
+        } else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "rate '\(value)' must end in bps, kbps, or mbps"
+            )
+        }
+        guard let magnitude = Int(digits), magnitude > 0 else {
+            throw RelayPolicyCompileError.invalidStatement(
+                statement: statement,
+                reason: "rate '\(value)' must be a positive integer with a bps, kbps, or mbps unit"
+            )
+        }
+        let bitsPerSecond = magnitude.multipliedReportingOverflow(by: multiplierBits)
+        guard !bitsPerSecond.overflow else {
+            return Int.max
+        }
+        return max(1, bitsPerSecond.partialValue / 8)
+    }
+}
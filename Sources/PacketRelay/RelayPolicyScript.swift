@@ -0,0 +1,121 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Verdict returned by a relay policy evaluator for one outbound connection.
+public enum RelayPolicyVerdict: Sendable, Equatable {
+    /// Proceed with the connection unchanged.
+    case allow
+    /// Reject the connection before dialing; the client receives a ruleset-denied SOCKS reply.
+    case block
+    /// Proceed, but cap each outbound read chunk to bound relay burst size for this session.
+    /// When `pacingBytesPerSecond` is set the relay also paces shaped reads, spreading
+    /// forwarded bytes evenly across each second instead of releasing rate-sized bursts
+    /// back to back.
+    case shape(maxBurstBytes: Int, pacingBytesPerSecond: Int?)
+    /// Proceed, but dial through the named upstream proxy transport instead of directly.
+    /// The relay resolves the tag against its installed `RelayUpstreamRoutes`.
+    case route(tag: String)
+
+    /// Burst-cap-only shaping, for call sites predating rate pacing.
+    public static func shape(maxBurstBytes: Int) -> RelayPolicyVerdict {
+        .shape(maxBurstBytes: maxBurstBytes, pacingBytesPerSecond: nil)
+    }
+}
+
+/// Inputs handed to a policy evaluator before the relay dials an outbound connection.
+public struct RelayPolicyInput: Sendable, Equatable {
+    /// Destination host exactly as requested (IPv4/IPv6 literal or domain name).
+    public let host: String
+    public let port: UInt16
+    /// Transport label, currently `tcp` for SOCKS CONNECT sessions.
+    public let transport: String
+    /// Leading client bytes buffered before the dial, if any arrived ahead of the connect reply.
+    /// Contract: may be empty; evaluators must not treat absence as meaningful.
+    public let firstPayloadSnippet: Data
+    /// Whether the client's ClientHello carried an Encrypted Client Hello extension.
+    /// Contract: `false` at connect time; only re-evaluations after ClientHello inspection set it.
+    public let echDetected: Bool
+    /// JA3 fingerprint of the client's ClientHello.
+    /// Contract: `nil` at connect time; only re-evaluations after ClientHello inspection set it.
+    public let ja3: String?
+    /// Address of the client device that originated the flow, for standalone/router deployments
+    /// where multiple devices share the tunnel.
+    /// Contract: `nil` when the relay cannot attribute a source device (for example the
+    /// single-client loopback path); rules scoped by `src=` never match such flows.
+    public let sourceAddress: String?
+
+    public init(
+        host: String,
+        port: UInt16,
+        transport: String,
+        firstPayloadSnippet: Data,
+        echDetected: Bool = false,
+        ja3: String? = nil,
+        sourceAddress: String? = nil
+    ) {
+        self.host = host
+        self.port = port
+        self.transport = transport
+        self.firstPayloadSnippet = firstPayloadSnippet
+        self.echDetected = echDetected
+        self.ja3 = ja3
+        self.sourceAddress = sourceAddress
+    }
+}
+
+/// Extension point for dynamic, server-pushed relay policy.
+/// Decision: the package defines only this synchronous hook; hosts can back it with an embedded
+/// interpreter (for example a WASM runtime evaluating downloaded policy modules) so policy logic
+/// can change without shipping a new binary, and the package takes no interpreter dependency.
+/// Contract: `evaluate` runs inline on the relay connection queue before the outbound dial, so
+/// implementations must avoid blocking I/O, sleeps, and long CPU work.
+public protocol RelayPolicyEvaluator: Sendable {
+    func evaluate(_ input: RelayPolicyInput) -> RelayPolicyVerdict
+
+    /// Whether the relay should hold the first client bytes of an allowed connection and
+    /// re-evaluate with `echDetected` set once the ClientHello has been read. Hostname rules
+    /// cannot see through ECH, so this is the hook that lets a policy react to its presence.
+    /// Contract: runs on the relay connection queue under the same constraints as `evaluate`.
+    func shouldInspectClientHello(_ input: RelayPolicyInput) -> Bool
+
+    /// Names the resolver the relay should use for this flow's hostname, or `nil` for the
+    /// system resolver. The relay resolves the tag against its installed `RelayHostResolvers`.
+    /// Contract: runs on the relay connection queue under the same constraints as `evaluate`.
+    func resolverTag(_ input: RelayPolicyInput) -> String?
+
+    /// Nagle preference for this flow's outbound socket: `true` disables Nagle, `false`
+    /// forces coalescing, `nil` keeps the platform default.
+    /// Contract: runs on the relay connection queue under the same constraints as `evaluate`.
+    func noDelay(_ input: RelayPolicyInput) -> Bool?
+
+    /// Milliseconds the relay should hold DNS responses for the queried name in `input.host`,
+    /// or `nil` to deliver them immediately. Delays apply only to resolution, so transfer
+    /// measurements over already-resolved flows are unaffected.
+    /// Contract: runs on the DNS pool queue under the same constraints as `evaluate`.
+    func dnsResponseDelayMilliseconds(_ input: RelayPolicyInput) -> Int?
+}
+
+public extension RelayPolicyEvaluator {
+    /// Default: never inspect; connections forward their first bytes immediately.
+    func shouldInspectClientHello(_ input: RelayPolicyInput) -> Bool {
+        false
+    }
+
+    /// Default: every flow resolves through the system resolver.
+    func resolverTag(_ input: RelayPolicyInput) -> String? {
+        nil
+    }
+
+    /// Default: outbound sockets keep the platform's Nagle behavior.
+    func noDelay(_ input: RelayPolicyInput) -> Bool? {
+        nil
+    }
+
+    /// Default: DNS responses are delivered as soon as the resolver answers.
+    func dnsResponseDelayMilliseconds(_ input: RelayPolicyInput) -> Int? {
+        nil
+    }
+}
@@ -0,0 +1,213 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// ALG-style registry of data flows announced on recognized control flows, so policy can
+/// pre-authorize them instead of breaking protocols that negotiate dynamic ports.
+/// FTP announces its data connection in PORT commands and PASV replies; SIP carries media
+/// endpoints in SDP bodies. A blocking policy document that admits the control flow would
+/// otherwise silently kill the transfer or the call when the dynamic port fails to match
+/// any allow rule.
+/// Decision: expectations are one-shot and short-lived — each announcement authorizes
+/// exactly one dial within a small window, so a stale or spoofed announcement can never
+/// become a standing hole in the policy.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class RelaySecondaryFlowPredictor: @unchecked Sendable {
+    /// Default lifetime of one expectation; clients dial announced endpoints promptly, and a
+    /// transfer that starts later than this deserves a fresh announcement.
+    public static let defaultTTL: TimeInterval = 30
+
+    /// Control ports whose payloads are inspected for announcements.
+    static let ftpControlPort: UInt16 = 21
+    static let sipControlPort: UInt16 = 5_060
+
+    /// Expectation cap guarding against a chatty or hostile control flow flooding the table.
+    private static let maxExpectations = 256
+
+    /// Announcement parsing only ever looks at the leading bytes of one relayed chunk; an
+    /// FTP line or the SDP section of an INVITE fits, and anything longer is body traffic.
+    private static let inspectionByteLimit = 2_048
+
+    /// Which endpoint of the control flow produced the observed payload.
+    public enum PayloadDirection: Sendable {
+        case clientToServer
+        case serverToClient
+    }
+
+    private struct Destination: Hashable {
+        let host: String
+        let port: UInt16
+    }
+
+    private let lock = NSLock()
+    private let ttl: TimeInterval
+    private let now: @Sendable () -> Date
+    private var expiryByDestination: [Destination: Date] = [:]
+    private var arrivalOrder: [Destination] = []
+
+    public convenience init(ttl: TimeInterval = RelaySecondaryFlowPredictor.defaultTTL) {
+        self.init(ttl: ttl, now: { Date() })
+    }
+
+    init(ttl: TimeInterval, now: @escaping @Sendable () -> Date) {
+        self.ttl = max(0, ttl)
+        self.now = now
+    }
+
+    /// Whether a flow to this port is a control flow whose payloads are worth inspecting.
+    public static func isRecognizedControlPort(_ port: UInt16) -> Bool {
+        port == ftpControlPort || port == sipControlPort
+    }
+
+    /// Inspects one relayed control-flow chunk for data-flow announcements and registers
+    /// the endpoints it names.
+    /// - Parameters:
+    ///   - controlHost: Destination host of the control flow, used when an announcement
+    ///     names the unspecified address.
+    ///   - controlPort: Destination port of the control flow; selects the parser.
+    ///   - direction: Which endpoint produced the chunk.
+    ///   - payload: The relayed bytes as they passed through the session.
+    public func observeControlPayload(
+        controlHost: String,
+        controlPort: UInt16,
+        direction: PayloadDirection,
+        payload: Data
+    ) {
+        guard !payload.isEmpty else {
+            return
+        }
+        guard let text = String(data: payload.prefix(Self.inspectionByteLimit), encoding: .ascii) else {
+            return
+        }
+        switch controlPort {
+        case Self.ftpControlPort:
+            registerAll(Self.ftpAnnouncements(in: text, direction: direction, controlHost: controlHost))
+        case Self.sipControlPort:
+            registerAll(Self.sdpAnnouncements(in: text))
+        default:
+            return
+        }
+    }
+
+    /// Consumes one expectation for the destination, returning whether the dial was
+    /// announced on a control flow. A hit removes the expectation so each announcement
+    /// authorizes one flow.
+    public func consume(host: String, port: UInt16) -> Bool {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        let key = Destination(host: host.lowercased(), port: port)
+        guard let expiry = expiryByDestination[key] else {
+            return false
+        }
+        expiryByDestination.removeValue(forKey: key)
+        arrivalOrder.removeAll { $0 == key }
+        return expiry > reference
+    }
+
+    /// Live expectation count, for tests and diagnostics.
+    public var expectationCount: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return expiryByDestination.count
+    }
+
+    private func registerAll(_ destinations: [(host: String, port: UInt16)]) {
+        guard !destinations.isEmpty else {
+            return
+        }
+        let expiry = now().addingTimeInterval(ttl)
+        lock.lock()
+        defer { lock.unlock() }
+        for destination in destinations {
+            let key = Destination(host: destination.host.lowercased(), port: destination.port)
+            if expiryByDestination[key] == nil {
+                arrivalOrder.append(key)
+            }
+            expiryByDestination[key] = expiry
+            while expiryByDestination.count > Self.maxExpectations, !arrivalOrder.isEmpty {
+                expiryByDestination.removeValue(forKey: arrivalOrder.removeFirst())
+            }
+        }
+    }
+
+    /// Parses FTP announcements: active-mode `PORT h1,h2,h3,h4,p1,p2` from the client and
+    /// passive-mode `227 ... (h1,h2,h3,h4,p1,p2)` from the server. A PASV reply naming the
+    /// unspecified address falls back to the control flow's host, which some NAT-unaware
+    /// servers rely on.
+    private static func ftpAnnouncements(
+        in text: String,
+        direction: PayloadDirection,
+        controlHost: String
+    ) -> [(host: String, port: UInt16)] {
+        var announcements: [(host: String, port: UInt16)] = []
+        for line in text.split(separator: "\r\n", omittingEmptySubsequences: true) {
+            switch direction {
+            case .clientToServer:
+                guard line.uppercased().hasPrefix("PORT ") else {
+                    continue
+                }
+                if let endpoint = parseCommaEndpoint(line.dropFirst(5), controlHost: controlHost) {
+                    announcements.append(endpoint)
+                }
+            case .serverToClient:
+                guard line.hasPrefix("227"), let open = line.firstIndex(of: "("),
+                      let close = line.lastIndex(of: ")"), open < close else {
+                    continue
+                }
+                if let endpoint = parseCommaEndpoint(line[line.index(after: open) ..< close], controlHost: controlHost) {
+                    announcements.append(endpoint)
+                }
+            }
+        }
+        return announcements
+    }
+
+    /// Parses the `h1,h2,h3,h4,p1,p2` endpoint form shared by PORT and PASV.
+    private static func parseCommaEndpoint(
+        _ fragment: Substring,
+        controlHost: String
+    ) -> (host: String, port: UInt16)? {
+        let fields = fragment.split(separator: ",").compactMap { Int($0.trimmingCharacters(in: .whitespaces)) }
+        guard fields.count == 6, fields.allSatisfy({ (0 ... 255).contains($0) }) else {
+            return nil
+        }
+        let port = fields[4] * 256 + fields[5]
+        guard port > 0, port <= 65_535 else {
+            return nil
+        }
+        let host = fields[0 ..< 4].map(String.init).joined(separator: ".")
+        return (host: host == "0.0.0.0" ? controlHost : host, port: UInt16(port))
+    }
+
+    /// Parses SDP media announcements from a SIP message: the session- or media-level
+    /// `c=IN IP4 <addr>` connection line supplies the host and each `m=<media> <port> ...`
+    /// line supplies a port. The RTCP companion port (media port + 1) is registered too,
+    /// matching the default pairing when no `a=rtcp:` attribute overrides it.
+    private static func sdpAnnouncements(in text: String) -> [(host: String, port: UInt16)] {
+        var announcements: [(host: String, port: UInt16)] = []
+        var connectionAddress: String?
+        for rawLine in text.split(whereSeparator: { $0 == "\r" || $0 == "\n" }) {
+            let line = rawLine.trimmingCharacters(in: .whitespaces)
+            if line.hasPrefix("c=IN IP4 ") || line.hasPrefix("c=IN IP6 ") {
+                let address = line.dropFirst(9).trimmingCharacters(in: .whitespaces)
+                if !address.isEmpty {
+                    connectionAddress = address
+                }
+                continue
+            }
+            guard line.hasPrefix("m="), let connectionAddress else {
+                continue
+            }
+            let fields = line.dropFirst(2).split(separator: " ")
+            guard fields.count >= 2, let port = Int(fields[1]), port > 0, port <= 65_534 else {
+                continue
+            }
+            announcements.append((host: connectionAddress, port: UInt16(port)))
+            announcements.append((host: connectionAddress, port: UInt16(port + 1)))
+        }
+        return announcements
+    }
+}
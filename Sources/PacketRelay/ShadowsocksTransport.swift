@@ -0,0 +1,370 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+#if canImport(CryptoKit)
+import CryptoKit
+#endif
+
+/// AEAD ciphers supported for Shadowsocks upstream transports.
+public enum ShadowsocksCipher: String, Sendable, Equatable {
+    case chacha20Poly1305 = "chacha20-ietf-poly1305"
+    case aes256GCM = "aes-256-gcm"
+
+    var keyLength: Int { 32 }
+    var saltLength: Int { 32 }
+    var tagLength: Int { 16 }
+}
+
+/// One configured Shadowsocks server reachable as a named upstream route.
+public struct ShadowsocksServerConfig: Sendable, Equatable {
+    public let host: String
+    public let port: UInt16
+    public let password: String
+    public let cipher: ShadowsocksCipher
+
+    public init(host: String, port: UInt16, password: String, cipher: ShadowsocksCipher = .chacha20Poly1305) {
+        self.host = host
+        self.port = port
+        self.password = password
+        self.cipher = cipher
+    }
+
+    /// Master key via OpenSSL-compatible EVP_BytesToKey (MD5 chaining), the standard
+    /// Shadowsocks password derivation.
+    func masterKey() -> Data {
+#if canImport(CryptoKit)
+        let passwordBytes = Data(password.utf8)
+        var key = Data()
+        var previous = Data()
+        while key.count < cipher.keyLength {
+            previous = Data(Insecure.MD5.hash(data: previous + passwordBytes))
+            key.append(previous)
+        }
+        return key.prefix(cipher.keyLength)
+#else
+        return Data()
+#endif
+    }
+}
+
+/// One named outbound in the relay routing table.
+public enum RelayOutbound: Sendable, Equatable {
+    /// Dial the destination directly through the provider.
+    case direct
+    /// Dial the configured Shadowsocks server and wrap the flow in its AEAD stream.
+    case shadowsocks(ShadowsocksServerConfig)
+    /// Refuse the dial; matched flows receive a ruleset-denied reply.
+    case block
+}
+
+/// Named outbounds referenced by `route ... via=<tag>` policy rules.
+/// Decision: the relay resolves tags at connect time rather than compile time so the same
+/// compiled policy document can be reused as servers rotate. The tags `direct` and `block`
+/// are built in unless a registry entry shadows them.
+public struct RelayUpstreamRoutes: Sendable {
+    private let outboundsByTag: [String: RelayOutbound]
+
+    public init(outbounds: [String: RelayOutbound]) {
+        self.outboundsByTag = outbounds
+    }
+
+    public init(shadowsocks: [String: ShadowsocksServerConfig] = [:]) {
+        self.outboundsByTag = shadowsocks.mapValues { .shadowsocks($0) }
+    }
+
+    public func outbound(forTag tag: String) -> RelayOutbound? {
+        if let outbound = outboundsByTag[tag] {
+            return outbound
+        }
+        switch tag {
+        case "direct":
+            return .direct
+        case "block":
+            return .block
+        default:
+            return nil
+        }
+    }
+
+    public static let none = RelayUpstreamRoutes()
+}
+
+enum ShadowsocksStreamError: Error, Equatable {
+    case cryptoUnavailable
+    case malformedStream
+}
+
+/// Encrypting half of one Shadowsocks AEAD stream session.
+/// Contract: the caller emits `salt` exactly once, before the first sealed chunk. Each chunk is
+/// `[2-byte length][tag][payload][tag]` sealed with a little-endian nonce counter per the
+/// Shadowsocks AEAD spec; payloads above 0x3FFF bytes are split transparently.
+struct ShadowsocksStreamEncryptor {
+    private static let maxChunkPayloadBytes = 0x3FFF
+
+    let salt: Data
+    private let cipher: ShadowsocksCipher
+#if canImport(CryptoKit)
+    private let subkey: SymmetricKey
+#endif
+    private var nonceCounter: UInt64 = 0
+
+    init(masterKey: Data, cipher: ShadowsocksCipher, salt: Data? = nil) {
+        self.cipher = cipher
+        let sessionSalt = salt ?? Data((0 ..< cipher.saltLength).map { _ in UInt8.random(in: .min ... .max) })
+        self.salt = sessionSalt
+#if canImport(CryptoKit)
+        self.subkey = ShadowsocksSubkey.derive(masterKey: masterKey, salt: sessionSalt, cipher: cipher)
+#endif
+    }
+
+    mutating func sealChunks(_ plaintext: Data) throws -> Data {
+        guard !plaintext.isEmpty else {
+            return Data()
+        }
+        var output = Data()
+        var offset = plaintext.startIndex
+        repeat {
+            let end = plaintext.index(offset, offsetBy: Self.maxChunkPayloadBytes, limitedBy: plaintext.endIndex) ?? plaintext.endIndex
+            let chunk = plaintext[offset ..< end]
+            let length = UInt16(chunk.count)
+            output.append(try seal(Data([UInt8(length >> 8), UInt8(length & 0xFF)])))
+            output.append(try seal(Data(chunk)))
+            offset = end
+        } while offset < plaintext.endIndex
+        return output
+    }
+
+    private mutating func seal(_ plaintext: Data) throws -> Data {
+#if canImport(CryptoKit)
+        let nonce = ShadowsocksSubkey.nonceData(counter: nonceCounter)
+        nonceCounter &+= 1
+        switch cipher {
+        case .chacha20Poly1305:
+            let box = try ChaChaPoly.seal(plaintext, using: subkey, nonce: ChaChaPoly.Nonce(data: nonce))
+            return box.ciphertext + box.tag
+        case .aes256GCM:
+            let box = try AES.GCM.seal(plaintext, using: subkey, nonce: AES.GCM.Nonce(data: nonce))
+            return box.ciphertext + box.tag
+        }
+#else
+        throw ShadowsocksStreamError.cryptoUnavailable
+#endif
+    }
+}
+
+/// Decrypting half of one Shadowsocks AEAD stream session.
+/// Stateful: buffers partial chunks across `feed` calls and consumes the peer salt first.
+struct ShadowsocksStreamDecryptor {
+    private let masterKey: Data
+    private let cipher: ShadowsocksCipher
+#if canImport(CryptoKit)
+    private var subkey: SymmetricKey?
+#endif
+    private var nonceCounter: UInt64 = 0
+    private var buffer = Data()
+
+    init(masterKey: Data, cipher: ShadowsocksCipher) {
+        self.masterKey = masterKey
+        self.cipher = cipher
+    }
+
+    /// Appends ciphertext and returns every complete plaintext chunk now available.
+    mutating func feed(_ data: Data) throws -> Data {
+#if canImport(CryptoKit)
+        buffer.append(data)
+        if subkey == nil {
+            guard buffer.count >= cipher.saltLength else {
+                return Data()
+            }
+            let salt = buffer.prefix(cipher.saltLength)
+            buffer.removeFirst(cipher.saltLength)
+            subkey = ShadowsocksSubkey.derive(masterKey: masterKey, salt: Data(salt), cipher: cipher)
+        }
+        guard let subkey else {
+            return Data()
+        }
+
+        var plaintext = Data()
+        while true {
+            let lengthChunkBytes = 2 + cipher.tagLength
+            guard buffer.count >= lengthChunkBytes else {
+                break
+            }
+            let lengthPlain = try open(Data(buffer.prefix(lengthChunkBytes)), subkey: subkey, counter: nonceCounter)
+            guard lengthPlain.count == 2 else {
+                throw ShadowsocksStreamError.malformedStream
+            }
+            let payloadLength = Int(lengthPlain[lengthPlain.startIndex]) << 8 | Int(lengthPlain[lengthPlain.index(after: lengthPlain.startIndex)])
+            guard payloadLength > 0, payloadLength <= 0x3FFF else {
+                throw ShadowsocksStreamError.malformedStream
+            }
+            let totalChunkBytes = lengthChunkBytes + payloadLength + cipher.tagLength
+            guard buffer.count >= totalChunkBytes else {
+                break
+            }
+            let payloadCipher = buffer.dropFirst(lengthChunkBytes).prefix(payloadLength + cipher.tagLength)
+            plaintext.append(try open(Data(payloadCipher), subkey: subkey, counter: nonceCounter + 1))
+            nonceCounter &+= 2
+            buffer.removeFirst(totalChunkBytes)
+        }
+        return plaintext
+#else
+        throw ShadowsocksStreamError.cryptoUnavailable
+#endif
+    }
+
+#if canImport(CryptoKit)
+    private func open(_ ciphertextAndTag: Data, subkey: SymmetricKey, counter: UInt64) throws -> Data {
+        guard ciphertextAndTag.count >= cipher.tagLength else {
+            throw ShadowsocksStreamError.malformedStream
+        }
+        let ciphertext = ciphertextAndTag.dropLast(cipher.tagLength)
+        let tag = ciphertextAndTag.suffix(cipher.tagLength)
+        let nonce = ShadowsocksSubkey.nonceData(counter: counter)
+        do {
+            switch cipher {
+            case .chacha20Poly1305:
+                let box = try ChaChaPoly.SealedBox(nonce: ChaChaPoly.Nonce(data: nonce), ciphertext: ciphertext, tag: tag)
+                return try ChaChaPoly.open(box, using: subkey)
+            case .aes256GCM:
+                let box = try AES.GCM.SealedBox(nonce: AES.GCM.Nonce(data: nonce), ciphertext: ciphertext, tag: tag)
+                return try AES.GCM.open(box, using: subkey)
+            }
+        } catch {
+            throw ShadowsocksStreamError.malformedStream
+        }
+    }
+#endif
+}
+
+#if canImport(CryptoKit)
+private enum ShadowsocksSubkey {
+    /// Session subkey per the AEAD spec: HKDF-SHA1(master, salt, "ss-subkey").
+    static func derive(masterKey: Data, salt: Data, cipher: ShadowsocksCipher) -> SymmetricKey {
+        HKDF<Insecure.SHA1>.deriveKey(
+            inputKeyMaterial: SymmetricKey(data: masterKey),
+            salt: salt,
+            info: Data("ss-subkey".utf8),
+            outputByteCount: cipher.keyLength
+        )
+    }
+
+    /// 12-byte little-endian nonce counter.
+    static func nonceData(counter: UInt64) -> Data {
+        var nonce = Data(repeating: 0, count: 12)
+        var value = counter
+        for index in 0 ..< 8 {
+            nonce[index] = UInt8(value & 0xFF)
+            value >>= 8
+        }
+        return nonce
+    }
+}
+#endif
+
+/// `Socks5TCPOutbound` adapter that wraps a TCP flow in a Shadowsocks AEAD stream.
+/// The inner outbound is dialed at the Shadowsocks server, not the destination; the
+/// destination travels in the first encrypted chunk as a SOCKS-style address header.
+final class ShadowsocksTCPOutbound: @unchecked Sendable, Socks5TCPOutbound {
+    private let inner: any Socks5TCPOutbound
+    private let lock = NSLock()
+    private var encryptor: ShadowsocksStreamEncryptor
+    private var decryptor: ShadowsocksStreamDecryptor
+    private var sentSalt = false
+    private let addressHeader: Data
+
+    /// - Returns: `nil` when the destination cannot be encoded as an address header.
+    init?(inner: any Socks5TCPOutbound, config: ShadowsocksServerConfig, destination: Socks5Address, destinationPort: UInt16) {
+        guard let header = Socks5Codec.encodeAddressPort(destination, port: destinationPort) else {
+            return nil
+        }
+        self.inner = inner
+        let masterKey = config.masterKey()
+        self.encryptor = ShadowsocksStreamEncryptor(masterKey: masterKey, cipher: config.cipher)
+        self.decryptor = ShadowsocksStreamDecryptor(masterKey: masterKey, cipher: config.cipher)
+        self.addressHeader = Data(header)
+    }
+
+    func waitUntilReady(completionHandler: @escaping @Sendable (Result<Void, Error>) -> Void) {
+        inner.waitUntilReady(completionHandler: completionHandler)
+    }
+
+    func write(_ data: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        lock.lock()
+        let framed: Data
+        do {
+            var output = Data()
+            if !sentSalt {
+                sentSalt = true
+                output.append(encryptor.salt)
+                output.append(try encryptor.sealChunks(addressHeader))
+            }
+            output.append(try encryptor.sealChunks(data))
+            framed = output
+        } catch {
+            lock.unlock()
+            completionHandler(error)
+            return
+        }
+        lock.unlock()
+        inner.write(framed, completionHandler: completionHandler)
+    }
+
+    func readMinimumLength(_ minimumLength: Int, maximumLength: Int, completionHandler: @escaping @Sendable (Data?, Error?) -> Void) {
+        readLoop(minimumLength: minimumLength, maximumLength: maximumLength, accumulated: Data(), completionHandler: completionHandler)
+    }
+
+    private func readLoop(
+        minimumLength: Int,
+        maximumLength: Int,
+        accumulated: Data,
+        completionHandler: @escaping @Sendable (Data?, Error?) -> Void
+    ) {
+        inner.readMinimumLength(1, maximumLength: maximumLength) { [weak self] data, error in
+            guard let self else {
+                completionHandler(nil, error)
+                return
+            }
+            if let error {
+                completionHandler(accumulated.isEmpty ? nil : accumulated, error)
+                return
+            }
+            guard let data, !data.isEmpty else {
+                // EOF: surface whatever decrypted cleanly; a truncated trailing chunk is dropped.
+                completionHandler(accumulated.isEmpty ? data : accumulated, nil)
+                return
+            }
+            let plaintext: Data
+            do {
+                self.lock.lock()
+                plaintext = try self.decryptor.feed(data)
+                self.lock.unlock()
+            } catch {
+                self.lock.unlock()
+                completionHandler(nil, error)
+                return
+            }
+            let total = accumulated + plaintext
+            if total.count >= minimumLength {
+                completionHandler(total, nil)
+            } else {
+                self.readLoop(
+                    minimumLength: minimumLength,
+                    maximumLength: maximumLength,
+                    accumulated: total,
+                    completionHandler: completionHandler
+                )
+            }
+        }
+    }
+
+    func finishWriting(completionHandler: @escaping @Sendable (Error?) -> Void) {
+        inner.finishWriting(completionHandler: completionHandler)
+    }
+
+    func cancel() {
+        inner.cancel()
+    }
+}
@@ -275,6 +275,12 @@ public enum Socks5Codec {
         return data
     }
 
+    /// SOCKS-style `ATYP + address + port` encoding, shared with upstream proxy transports
+    /// whose wire formats reuse the SOCKS address header (for example Shadowsocks).
+    static func encodeAddressPort(_ address: Socks5Address, port: UInt16) -> [UInt8]? {
+        validatedAddressPortBytes(address, port: port)
+    }
+
     private static func validatedAddressPortBytes(_ address: Socks5Address, port: UInt16) -> [UInt8]? {
         switch address {
         case .ipv4(let value):
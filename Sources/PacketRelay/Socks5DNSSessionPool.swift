@@ -0,0 +1,433 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@preconcurrency import NetworkExtension
+import Observability
+
+/// Shared pool of outbound UDP sessions to DNS resolvers.
+/// Decision: each UDP ASSOCIATE otherwise dials the resolver through its own relay, so a
+/// bursty resolver costs one host dial per client source port. Port-53 queries are instead
+/// multiplexed over a few pooled sessions per resolver, and responses are routed back to
+/// the issuing association by DNS transaction ID and question name. The name is part of
+/// the claim key because 16-bit transaction IDs collide under load; ID alone would let a
+/// later claim overwrite an earlier pending one and misroute its response.
+/// Queue ownership: all pool state is serialized on the pool's private queue. `send` may be
+/// called from any relay queue; `deliver` closures are invoked from the pool queue and must
+/// hop back to their own queue.
+final class Socks5DNSSessionPool: @unchecked Sendable {
+    struct Configuration: Sendable {
+        /// Pooled sessions dialed per resolver; queries spread across them by transaction ID.
+        let sessionsPerResolver: Int
+        /// Distinct resolvers tracked before the least-recently-used one is evicted.
+        let maxResolvers: Int
+        /// Seconds an unanswered response claim stays routable before it is dropped.
+        let claimTimeoutSeconds: TimeInterval
+        /// Seconds a pooled session may stay idle before it is reaped.
+        let idleTimeoutSeconds: TimeInterval
+        /// When enabled, responses for one resolver deliver in receive order even when a
+        /// `delay-dns` hold sits in front: later responses queue behind the held one
+        /// instead of overtaking it. Off by default so delayed names never add latency to
+        /// unrelated queries on the same resolver.
+        let preserveDeliveryOrder: Bool
+
+        /// - Parameters:
+        ///   - sessionsPerResolver: Sessions per resolver; values below 1 are clamped to 1.
+        ///   - maxResolvers: Resolver cap; values below 1 are clamped to 1.
+        ///   - claimTimeoutSeconds: Claim lifetime for unanswered queries.
+        ///   - idleTimeoutSeconds: Idle lifetime for pooled sessions.
+        ///   - preserveDeliveryOrder: Per-resolver in-order delivery across delayed and
+        ///     direct responses.
+        init(
+            sessionsPerResolver: Int = 2,
+            maxResolvers: Int = 8,
+            claimTimeoutSeconds: TimeInterval = 10,
+            idleTimeoutSeconds: TimeInterval = 60,
+            preserveDeliveryOrder: Bool = false
+        ) {
+            self.sessionsPerResolver = max(1, sessionsPerResolver)
+            self.maxResolvers = max(1, maxResolvers)
+            self.claimTimeoutSeconds = claimTimeoutSeconds
+            self.idleTimeoutSeconds = idleTimeoutSeconds
+            self.preserveDeliveryOrder = preserveDeliveryOrder
+        }
+    }
+
+    private struct ResolverKey: Hashable, Sendable {
+        let address: Socks5Address
+        let port: UInt16
+    }
+
+    private struct SlotKey: Hashable, Sendable {
+        let resolver: ResolverKey
+        let slot: Int
+    }
+
+    private struct ClaimKey: Hashable, Sendable {
+        let resolver: ResolverKey
+        let transactionID: UInt16
+        /// First question name, lowercased; `nil` when unparsable. Responses echo the
+        /// query's question section verbatim, so both sides parse to the same value.
+        let queryName: String?
+    }
+
+    private struct Claim {
+        let deliver: @Sendable (Data) -> Void
+        let expiresAt: Date
+        /// Artificial hold applied to the matched response before delivery; 0 delivers
+        /// immediately. Set by `delay-dns` policy rules matching the query name.
+        let deliveryDelayMilliseconds: Int
+    }
+
+    private struct SessionEntry {
+        let session: Socks5UDPSession
+        var lastUsedAt: Date
+    }
+
+    private let configuration: Configuration
+    private let logger: StructuredLogger
+    private let queue: DispatchQueue
+    private let policyEvaluator: (any RelayPolicyEvaluator)?
+    private let nowProvider: @Sendable () -> Date
+
+    /// One response waiting its turn in a resolver's ordered delivery queue.
+    private struct OrderedDelivery {
+        let deliver: @Sendable (Data) -> Void
+        let datagram: Data
+        let dueAt: Date
+    }
+
+    private var sessions: [SlotKey: SessionEntry] = [:]
+    private var claims: [ClaimKey: Claim] = [:]
+    private var expiredClaimCountStored = 0
+    private var orderedDeliveries: [ResolverKey: [OrderedDelivery]] = [:]
+    private var orderedDrainScheduled: Set<ResolverKey> = []
+
+    /// - Parameters:
+    ///   - configuration: Pool sizing and timeout knobs.
+    ///   - logger: Structured logger for pool events.
+    ///   - policyEvaluator: Optional policy hook consulted per query for `delay-dns` response
+    ///     holds; queries still go out immediately, only delivery is delayed.
+    ///   - nowProvider: Time source used for claim expiry and idle reaping.
+    init(
+        configuration: Configuration = Configuration(),
+        logger: StructuredLogger,
+        policyEvaluator: (any RelayPolicyEvaluator)? = nil,
+        nowProvider: @escaping @Sendable () -> Date = { Date() }
+    ) {
+        self.configuration = configuration
+        self.logger = logger
+        self.queue = DispatchQueue(label: "com.vpnbridge.socks.dns-pool")
+        self.policyEvaluator = policyEvaluator
+        self.nowProvider = nowProvider
+    }
+
+    var activeSessionCount: Int {
+        queue.sync { sessions.count }
+    }
+
+    var pendingClaimCount: Int {
+        queue.sync { claims.count }
+    }
+
+    var expiredClaimCount: Int {
+        queue.sync { expiredClaimCountStored }
+    }
+
+    /// Sends DNS queries to `address:port` over pooled sessions, registering one response
+    /// claim per transaction ID and question name. Queries shorter than a DNS header are
+    /// dropped.
+    /// - Parameters:
+    ///   - queries: Raw DNS query payloads from one relay drain pass.
+    ///   - address: Resolver address.
+    ///   - port: Resolver port (53 in practice; the pool does not enforce it).
+    ///   - provider: Session provider used when a pooled session must be dialed.
+    ///   - deliver: Receives each matched response payload; invoked on the pool queue.
+    func send(
+        queries: [Data],
+        to address: Socks5Address,
+        port: UInt16,
+        provider: Socks5ConnectionProvider,
+        deliver: @escaping @Sendable (Data) -> Void
+    ) {
+        guard !queries.isEmpty else {
+            return
+        }
+        queue.async { [self] in
+            let now = nowProvider()
+            sweepExpiredClaims(now: now)
+            reapIdleSessions(now: now)
+
+            let resolver = ResolverKey(address: address, port: port)
+            var grouped: [Int: [Data]] = [:]
+            for query in queries {
+                guard let transactionID = Self.transactionID(of: query) else {
+                    continue
+                }
+                let slot = Int(transactionID) % configuration.sessionsPerResolver
+                let queryName = Self.queryName(of: query)
+                claims[ClaimKey(resolver: resolver, transactionID: transactionID, queryName: queryName)] = Claim(
+                    deliver: deliver,
+                    expiresAt: now.addingTimeInterval(configuration.claimTimeoutSeconds),
+                    deliveryDelayMilliseconds: responseDelayMilliseconds(for: queryName, port: port)
+                )
+                grouped[slot, default: []].append(query)
+            }
+
+            for (slot, datagrams) in grouped {
+                let key = SlotKey(resolver: resolver, slot: slot)
+                let session = sessionEntry(for: key, provider: provider, now: now).session
+                session.writeDatagrams(datagrams) { [weak self] error in
+                    guard let self, let error else { return }
+                    self.queue.async {
+                        guard self.sessions[key]?.session === session else { return }
+                        self.removeSession(for: key)
+                        let logger = self.logger
+                        Task {
+                            await logger.log(
+                                level: .error,
+                                phase: .relay,
+                                category: .relayUDP,
+                                component: "Socks5DNSSessionPool",
+                                event: "write-failed",
+                                errorCode: String(describing: error),
+                                message: "Pooled DNS session write failed; session dropped",
+                                metadata: Self.resolverMetadata(for: key.resolver)
+                            )
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cancels all pooled sessions and drops outstanding claims.
+    func stop() {
+        queue.sync {
+            sessions.values.forEach { $0.session.cancel() }
+            sessions.removeAll()
+            claims.removeAll()
+            orderedDeliveries.removeAll()
+            orderedDrainScheduled.removeAll()
+        }
+    }
+
+    private func sessionEntry(for key: SlotKey, provider: Socks5ConnectionProvider, now: Date) -> SessionEntry {
+        if var entry = sessions[key] {
+            entry.lastUsedAt = now
+            sessions[key] = entry
+            return entry
+        }
+
+        evictOldestResolverIfNeeded(keeping: key.resolver)
+
+        let hostString: String
+        switch key.resolver.address {
+        case .ipv4(let value), .ipv6(let value), .domain(let value):
+            hostString = value
+        }
+        let endpoint = NWHostEndpoint(hostname: hostString, port: String(key.resolver.port))
+        let session = provider.makeUDPSession(to: endpoint)
+        session.eventHandler = { [weak self, weak session] event in
+            guard let self, let session else { return }
+            self.queue.async {
+                guard self.sessions[key]?.session === session else { return }
+                if case .failed = event {
+                    self.removeSession(for: key)
+                }
+            }
+        }
+        session.setReadHandler({ [weak self, weak session] datagram, error in
+            guard let self, let session else { return }
+            self.queue.async {
+                guard self.sessions[key]?.session === session else { return }
+                if error != nil {
+                    self.removeSession(for: key)
+                    return
+                }
+                guard let datagram, let transactionID = Self.transactionID(of: datagram) else {
+                    return
+                }
+                let claimKey = ClaimKey(
+                    resolver: key.resolver,
+                    transactionID: transactionID,
+                    queryName: Self.queryName(of: datagram)
+                )
+                guard let claim = self.claims.removeValue(forKey: claimKey) else {
+                    return
+                }
+                self.markSessionUsed(for: key)
+                if self.configuration.preserveDeliveryOrder {
+                    self.enqueueOrderedDelivery(datagram, claim: claim, resolver: key.resolver)
+                    return
+                }
+                guard claim.deliveryDelayMilliseconds > 0 else {
+                    claim.deliver(datagram)
+                    return
+                }
+                // delay-dns hold: the claim is already consumed, so duplicate responses
+                // stay dropped and the claim timeout never races the artificial delay.
+                self.queue.asyncAfter(deadline: .now() + .milliseconds(claim.deliveryDelayMilliseconds)) {
+                    claim.deliver(datagram)
+                }
+            }
+        })
+
+        let entry = SessionEntry(session: session, lastUsedAt: now)
+        sessions[key] = entry
+        return entry
+    }
+
+    /// Appends one matched response to its resolver's ordered queue and drains what is due.
+    /// A held response parks everything behind it, so relative order within the resolver
+    /// survives `delay-dns` holds at the cost of the hold also delaying later responses.
+    private func enqueueOrderedDelivery(_ datagram: Data, claim: Claim, resolver: ResolverKey) {
+        let dueAt = nowProvider().addingTimeInterval(TimeInterval(claim.deliveryDelayMilliseconds) / 1_000)
+        orderedDeliveries[resolver, default: []].append(
+            OrderedDelivery(deliver: claim.deliver, datagram: datagram, dueAt: dueAt)
+        )
+        drainOrderedDeliveries(for: resolver)
+    }
+
+    private func drainOrderedDeliveries(for resolver: ResolverKey) {
+        guard !orderedDrainScheduled.contains(resolver) else {
+            return
+        }
+        while let head = orderedDeliveries[resolver]?.first {
+            let remaining = head.dueAt.timeIntervalSince(nowProvider())
+            guard remaining > 0 else {
+                orderedDeliveries[resolver]?.removeFirst()
+                head.deliver(head.datagram)
+                continue
+            }
+            orderedDrainScheduled.insert(resolver)
+            queue.asyncAfter(deadline: .now() + remaining) { [weak self] in
+                guard let self else { return }
+                self.orderedDrainScheduled.remove(resolver)
+                self.drainOrderedDeliveries(for: resolver)
+            }
+            return
+        }
+        orderedDeliveries[resolver] = nil
+    }
+
+    private func evictOldestResolverIfNeeded(keeping resolver: ResolverKey) {
+        var lastUsedByResolver: [ResolverKey: Date] = [:]
+        for (key, entry) in sessions {
+            lastUsedByResolver[key.resolver] = max(lastUsedByResolver[key.resolver] ?? .distantPast, entry.lastUsedAt)
+        }
+        guard lastUsedByResolver[resolver] == nil,
+              lastUsedByResolver.count >= configuration.maxResolvers,
+              let oldest = lastUsedByResolver.min(by: { $0.value < $1.value })?.key
+        else {
+            return
+        }
+        for key in sessions.keys where key.resolver == oldest {
+            removeSession(for: key)
+        }
+        claims = claims.filter { $0.key.resolver != oldest }
+    }
+
+    private func markSessionUsed(for key: SlotKey) {
+        guard var entry = sessions[key] else {
+            return
+        }
+        entry.lastUsedAt = nowProvider()
+        sessions[key] = entry
+    }
+
+    private func removeSession(for key: SlotKey) {
+        guard let entry = sessions.removeValue(forKey: key) else {
+            return
+        }
+        entry.session.cancel()
+    }
+
+    private func reapIdleSessions(now: Date) {
+        let expiredKeys = sessions.compactMap { key, entry in
+            now.timeIntervalSince(entry.lastUsedAt) > configuration.idleTimeoutSeconds ? key : nil
+        }
+        for key in expiredKeys {
+            removeSession(for: key)
+        }
+    }
+
+    private func sweepExpiredClaims(now: Date) {
+        let expired = claims.filter { $0.value.expiresAt < now }
+        guard !expired.isEmpty else {
+            return
+        }
+        for key in expired.keys {
+            claims.removeValue(forKey: key)
+        }
+        expiredClaimCountStored += expired.count
+    }
+
+    /// Milliseconds a `delay-dns` policy rule holds this query's response, or 0 when no
+    /// evaluator is installed, the query name is unparsable, or no rule matches.
+    private func responseDelayMilliseconds(for queryName: String?, port: UInt16) -> Int {
+        guard let policyEvaluator, let name = queryName else {
+            return 0
+        }
+        // Pool queries carry no source attribution, so `src=` scoped delay rules never match.
+        let input = RelayPolicyInput(host: name, port: port, transport: "udp", firstPayloadSnippet: Data())
+        return max(0, policyEvaluator.dnsResponseDelayMilliseconds(input) ?? 0)
+    }
+
+    /// Extracts the first question name from a DNS query, lowercased and without a trailing
+    /// dot. Returns `nil` for messages without a question or with compressed or malformed
+    /// names; question sections in queries are never compressed in practice.
+    private static func queryName(of datagram: Data) -> String? {
+        guard datagram.count >= 12 else {
+            return nil
+        }
+        let start = datagram.startIndex
+        let questionCount = UInt16(datagram[start + 4]) << 8 | UInt16(datagram[start + 5])
+        guard questionCount >= 1 else {
+            return nil
+        }
+        var labels: [String] = []
+        var index = start + 12
+        var nameLength = 0
+        while index < datagram.endIndex {
+            let labelLength = Int(datagram[index])
+            if labelLength == 0 {
+                return labels.isEmpty ? nil : labels.joined(separator: ".")
+            }
+            // Lengths above 63 are compression pointers or malformed; both bail out.
+            guard labelLength <= 63 else {
+                return nil
+            }
+            index += 1
+            nameLength += labelLength + 1
+            guard nameLength <= 255, datagram.endIndex - index >= labelLength else {
+                return nil
+            }
+            labels.append(String(decoding: datagram[index..<(index + labelLength)], as: UTF8.self).lowercased())
+            index += labelLength
+        }
+        return nil
+    }
+
+    private static func transactionID(of datagram: Data) -> UInt16? {
+        guard datagram.count >= 12 else {
+            return nil
+        }
+        let start = datagram.startIndex
+        return UInt16(datagram[start]) << 8 | UInt16(datagram[start + 1])
+    }
+
+    private static func resolverMetadata(for resolver: ResolverKey) -> [String: String] {
+        let host: String
+        switch resolver.address {
+        case .ipv4(let value), .ipv6(let value), .domain(let value):
+            host = value
+        }
+        return [
+            "destination_host": host,
+            "destination_port": String(resolver.port),
+            "destination_transport": "udp"
+        ]
+    }
+}
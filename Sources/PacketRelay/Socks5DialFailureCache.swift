@@ -0,0 +1,199 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Short-lived negative cache of recent outbound dial failures, keyed by destination.
+/// Decision: during an outage every flow to an unreachable host would otherwise consume a
+/// full dial attempt and a socket; suppressed flows fail immediately and only one probe dial
+/// goes out per TTL window. The TTL is deliberately short so recovered hosts are retried
+/// within seconds.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class Socks5DialFailureCache: @unchecked Sendable {
+    /// Default suppression window after a failed dial.
+    public static let defaultTTL: TimeInterval = 5
+
+    /// Entry cap guarding against unbounded growth when many destinations fail at once.
+    private static let maxEntries = 512
+
+    /// Backoff doublings applied to the retry-after hint before it stops growing.
+    private static let maxRetryAfterDoublings = 6
+
+    /// Retry-after hints never exceed this, however long a destination keeps failing.
+    private static let maxRetryAfterMilliseconds = 60_000
+
+    /// One persistable suppression entry with its remaining lifetime. Codable so hosts can
+    /// carry the negative cache across a short extension restart and keep failing fast
+    /// against destinations that were unreachable moments earlier.
+    public struct ExportEntry: Codable, Sendable, Equatable {
+        public let host: String
+        public let port: UInt16
+        /// Suppression lifetime the entry had left at export time; imports honor it so a
+        /// persisted failure never suppresses longer than the live cache would have.
+        public let remainingSuppressionMilliseconds: Int
+        /// Failure streak carried over so retry-after hints keep compounding after restart.
+        public let consecutiveFailures: Int
+
+        public init(host: String, port: UInt16, remainingSuppressionMilliseconds: Int, consecutiveFailures: Int) {
+            self.host = host
+            self.port = port
+            self.remainingSuppressionMilliseconds = max(0, remainingSuppressionMilliseconds)
+            self.consecutiveFailures = max(1, consecutiveFailures)
+        }
+    }
+
+    private struct Destination: Hashable {
+        let host: String
+        let port: UInt16
+    }
+
+    private struct Entry {
+        var expiry: Date
+        /// Failures recorded since the destination last dialed successfully or aged out;
+        /// feeds the exponential retry-after hint.
+        var consecutiveFailures: Int
+    }
+
+    private let lock = NSLock()
+    private let ttl: TimeInterval
+    private let now: @Sendable () -> Date
+    private var entryByDestination: [Destination: Entry] = [:]
+
+    public convenience init(ttl: TimeInterval = Socks5DialFailureCache.defaultTTL) {
+        self.init(ttl: ttl, now: { Date() })
+    }
+
+    init(ttl: TimeInterval, now: @escaping @Sendable () -> Date) {
+        self.ttl = max(0, ttl)
+        self.now = now
+    }
+
+    /// Records a failed dial so flows to the same destination fail fast until the TTL lapses.
+    public func recordFailure(host: String, port: UInt16) {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        let key = Destination(host: host.lowercased(), port: port)
+        // Read the streak before pruning so back-to-back failures keep compounding even
+        // when each one lands after the previous suppression window lapsed.
+        let streak = entryByDestination[key]?.consecutiveFailures ?? 0
+        pruneExpiredLocked()
+        if entryByDestination.count >= Self.maxEntries,
+           let earliest = entryByDestination.min(by: { $0.value.expiry < $1.value.expiry }) {
+            entryByDestination.removeValue(forKey: earliest.key)
+        }
+        entryByDestination[key] = Entry(
+            expiry: reference.addingTimeInterval(ttl),
+            consecutiveFailures: streak + 1
+        )
+    }
+
+    /// Clears the destination after a successful dial so recovery is observed immediately.
+    public func recordSuccess(host: String, port: UInt16) {
+        lock.lock()
+        defer { lock.unlock() }
+        entryByDestination.removeValue(forKey: Destination(host: host.lowercased(), port: port))
+    }
+
+    /// Drops every entry. Called on network path changes: failures observed on the old
+    /// path say nothing about reachability on the new one.
+    public func removeAll() {
+        lock.lock()
+        defer { lock.unlock() }
+        entryByDestination.removeAll()
+    }
+
+    /// Exports every live suppression with its remaining lifetime, in deterministic order,
+    /// so the host can persist the cache across a restart.
+    public func exportSnapshot() -> [ExportEntry] {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        return entryByDestination.compactMap { key, entry -> ExportEntry? in
+            let remaining = entry.expiry.timeIntervalSince(reference)
+            guard remaining > 0 else {
+                return nil
+            }
+            return ExportEntry(
+                host: key.host,
+                port: key.port,
+                remainingSuppressionMilliseconds: Int((remaining * 1_000).rounded()),
+                consecutiveFailures: entry.consecutiveFailures
+            )
+        }
+        .sorted { ($0.host, $0.port) < ($1.host, $1.port) }
+    }
+
+    /// Pre-seeds the cache from a previously exported snapshot, returning how many entries
+    /// were accepted. Remaining lifetimes are clamped to this cache's TTL, entries whose
+    /// lifetime already lapsed are skipped, and a live entry always wins over a seed.
+    @discardableResult
+    public func importEntries(_ imported: [ExportEntry]) -> Int {
+        let reference = now()
+        lock.lock()
+        defer { lock.unlock() }
+        pruneExpiredLocked()
+        var accepted = 0
+        for entry in imported {
+            guard entry.remainingSuppressionMilliseconds > 0 else {
+                continue
+            }
+            let key = Destination(host: entry.host.lowercased(), port: entry.port)
+            guard entryByDestination[key] == nil else {
+                continue
+            }
+            if entryByDestination.count >= Self.maxEntries {
+                break
+            }
+            let remaining = min(Double(entry.remainingSuppressionMilliseconds) / 1_000, ttl)
+            entryByDestination[key] = Entry(
+                expiry: reference.addingTimeInterval(remaining),
+                consecutiveFailures: entry.consecutiveFailures
+            )
+            accepted += 1
+        }
+        return accepted
+    }
+
+    /// Whether flows to the destination should fail fast instead of dialing.
+    public func isSuppressed(host: String, port: UInt16) -> Bool {
+        let key = Destination(host: host.lowercased(), port: port)
+        lock.lock()
+        defer { lock.unlock() }
+        guard let entry = entryByDestination[key] else {
+            return false
+        }
+        guard entry.expiry > now() else {
+            entryByDestination.removeValue(forKey: key)
+            return false
+        }
+        return true
+    }
+
+    /// Suggested client wait before re-dialing the destination, or `nil` when no live
+    /// failure state exists. The base is the remaining suppression window, doubled per
+    /// consecutive failure, then jittered to 80–120% so clients that failed together do
+    /// not re-dial together, and finally capped.
+    public func retryAfterMilliseconds(host: String, port: UInt16) -> Int? {
+        let key = Destination(host: host.lowercased(), port: port)
+        lock.lock()
+        defer { lock.unlock() }
+        guard let entry = entryByDestination[key] else {
+            return nil
+        }
+        let remaining = entry.expiry.timeIntervalSince(now())
+        guard remaining > 0 else {
+            return nil
+        }
+        let doublings = min(max(0, entry.consecutiveFailures - 1), Self.maxRetryAfterDoublings)
+        let scaled = remaining * 1_000 * pow(2, Double(doublings))
+        let jittered = scaled * Double.random(in: 0.8...1.2)
+        return max(1, min(Int(jittered.rounded()), Self.maxRetryAfterMilliseconds))
+    }
+
+    private func pruneExpiredLocked() {
+        let reference = now()
+        entryByDestination = entryByDestination.filter { $0.value.expiry > reference }
+    }
+}
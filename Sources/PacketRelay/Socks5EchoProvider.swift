@@ -0,0 +1,228 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Network
+@preconcurrency import NetworkExtension
+import Observability
+
+/// Loopback echo dial provider: every TCP dial and UDP session is handled
+/// internally by an echo implementation instead of the network, so the full
+/// relay path — handshake, CONNECT, the bidirectional pump, and UDP framing —
+/// can be exercised in CI with zero host or network involvement.
+/// Decision: echo rather than discard, because echoing proves both pump
+/// directions move the same bytes; a sink would leave the inbound-to-outbound
+/// half of the relay unverified.
+final class Socks5EchoConnectionProvider: @unchecked Sendable, Socks5FullConnectionProvider {
+    private let lock = NSLock()
+    private var storedTCPOutbounds: [Socks5EchoTCPOutbound] = []
+    private var storedUDPSessions: [Socks5EchoUDPSession] = []
+
+    init() {}
+
+    /// Echo TCP connections created so far, in dial order.
+    var tcpOutbounds: [Socks5EchoTCPOutbound] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedTCPOutbounds
+    }
+
+    /// Echo UDP sessions created so far, in creation order.
+    var udpSessions: [Socks5EchoUDPSession] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedUDPSessions
+    }
+
+    func makeTCPConnection(
+        to _: NWHostEndpoint,
+        enableTLS _: Bool,
+        tlsParameters _: NWTLSParameters?,
+        delegate _: (any NWTCPConnectionAuthenticationDelegate)?
+    ) -> Socks5TCPOutbound {
+        let outbound = Socks5EchoTCPOutbound()
+        lock.lock()
+        storedTCPOutbounds.append(outbound)
+        lock.unlock()
+        return outbound
+    }
+
+    func makeUDPSession(to endpoint: NWHostEndpoint) -> Socks5UDPSession {
+        let session = Socks5EchoUDPSession(endpoint: endpoint)
+        lock.lock()
+        storedUDPSessions.append(session)
+        lock.unlock()
+        return session
+    }
+}
+
+/// TCP outbound that is immediately ready and echoes every written byte back
+/// through pending reads, honoring the same minimum/maximum length and
+/// end-of-stream semantics as the Network.framework adapter.
+final class Socks5EchoTCPOutbound: @unchecked Sendable, Socks5TCPOutbound {
+    private struct PendingRead {
+        let minimumLength: Int
+        let maximumLength: Int
+        let completion: @Sendable (Data?, Error?) -> Void
+    }
+
+    private let lock = NSLock()
+    private var buffer = Data()
+    private var pendingReads: [PendingRead] = []
+    private var finished = false
+    private var storedCancelled = false
+    private var storedEchoedByteCount = 0
+
+    /// Total bytes written into the echo, for throughput assertions.
+    var echoedByteCount: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedEchoedByteCount
+    }
+
+    var cancelled: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedCancelled
+    }
+
+    func waitUntilReady(completionHandler: @escaping @Sendable (Result<Void, Error>) -> Void) {
+        completionHandler(.success(()))
+    }
+
+    func readMinimumLength(
+        _ minimumLength: Int,
+        maximumLength: Int,
+        completionHandler: @escaping @Sendable (Data?, Error?) -> Void
+    ) {
+        lock.lock()
+        pendingReads.append(
+            PendingRead(minimumLength: minimumLength, maximumLength: maximumLength, completion: completionHandler)
+        )
+        let deliveries = drainLocked()
+        lock.unlock()
+        fire(deliveries)
+    }
+
+    func write(_ data: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        lock.lock()
+        buffer.append(data)
+        storedEchoedByteCount += data.count
+        let deliveries = drainLocked()
+        lock.unlock()
+        completionHandler(nil)
+        fire(deliveries)
+    }
+
+    func finishWriting(completionHandler: @escaping @Sendable (Error?) -> Void) {
+        lock.lock()
+        finished = true
+        let deliveries = drainLocked()
+        lock.unlock()
+        completionHandler(nil)
+        fire(deliveries)
+    }
+
+    func cancel() {
+        lock.lock()
+        storedCancelled = true
+        let deliveries = drainLocked()
+        lock.unlock()
+        fire(deliveries)
+    }
+
+    /// Resolves every pending read the buffer can satisfy. A read completes when
+    /// the buffer reaches its minimum length, or with the remaining bytes (then
+    /// nil for end-of-stream) once the stream finished or was cancelled.
+    private func drainLocked() -> [(@Sendable (Data?, Error?) -> Void, Data?)] {
+        var deliveries: [(@Sendable (Data?, Error?) -> Void, Data?)] = []
+        while let next = pendingReads.first {
+            let terminal = finished || storedCancelled
+            if buffer.isEmpty {
+                guard terminal else {
+                    break
+                }
+                pendingReads.removeFirst()
+                deliveries.append((next.completion, nil))
+                continue
+            }
+            guard buffer.count >= next.minimumLength || terminal else {
+                break
+            }
+            pendingReads.removeFirst()
+            let count = min(buffer.count, max(1, next.maximumLength))
+            let chunk = Data(buffer.prefix(count))
+            buffer.removeFirst(count)
+            deliveries.append((next.completion, chunk))
+        }
+        return deliveries
+    }
+
+    private func fire(_ deliveries: [(@Sendable (Data?, Error?) -> Void, Data?)]) {
+        for (completion, data) in deliveries {
+            completion(data, nil)
+        }
+    }
+}
+
+/// UDP session that reflects every datagram back through the read handler,
+/// preserving per-datagram boundaries.
+final class Socks5EchoUDPSession: @unchecked Sendable, Socks5UDPSession {
+    let endpoint: NWHostEndpoint
+
+    private let lock = NSLock()
+    private var readHandler: (@Sendable (Data?, Error?) -> Void)?
+    private var storedCancelled = false
+    private var storedEchoedDatagramCount = 0
+    private var storedEventHandler: ((Socks5UDPSessionEvent) -> Void)?
+
+    /// Total datagrams reflected, for correctness assertions.
+    var echoedDatagramCount: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedEchoedDatagramCount
+    }
+
+    var eventHandler: ((Socks5UDPSessionEvent) -> Void)? {
+        get {
+            lock.lock()
+            defer { lock.unlock() }
+            return storedEventHandler
+        }
+        set {
+            lock.lock()
+            storedEventHandler = newValue
+            lock.unlock()
+        }
+    }
+
+    init(endpoint: NWHostEndpoint) {
+        self.endpoint = endpoint
+    }
+
+    func setReadHandler(_ handler: @escaping @Sendable (Data?, Error?) -> Void) {
+        lock.lock()
+        readHandler = handler
+        lock.unlock()
+    }
+
+    func writeDatagram(_ datagram: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        lock.lock()
+        let handler = storedCancelled ? nil : readHandler
+        if handler != nil {
+            storedEchoedDatagramCount += 1
+        }
+        lock.unlock()
+        completionHandler(nil)
+        handler?(datagram, nil)
+    }
+
+    func restart() {}
+
+    func cancel() {
+        lock.lock()
+        storedCancelled = true
+        lock.unlock()
+    }
+}
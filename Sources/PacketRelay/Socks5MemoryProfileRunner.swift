@@ -0,0 +1,491 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Network
+@preconcurrency import NetworkExtension
+import Observability
+
+/// Knobs for the iOS memory-profile scenario.
+/// Decision: the defaults model the constrained extension profile — a bounded shared
+/// budget, 200 concurrent shaped flows, telemetry sampling on — and the peak threshold
+/// leaves headroom above the expected peak so drift trips the scenario before it trips
+/// the extension's jetsam limit.
+public struct Socks5MemoryProfileOptions: Sendable {
+    /// Concurrent SOCKS sessions the scenario keeps open.
+    public let flowCount: Int
+    /// Client payload bytes each flow buffers toward a stalled outbound.
+    public let clientChunkBytes: Int
+    /// Remote payload bytes each shaped flow delivers toward the client.
+    public let remoteChunkBytes: Int
+    /// Shaping burst cap applied to every flow; keeps the shaped ledger path engaged.
+    public let shapedBurstBytes: Int
+    /// Bounded budget the scenario runs under.
+    public let bufferLimits: Socks5BufferLimits
+    /// Ceiling on the peak bytes the shared ledger may hold at any sample point.
+    public let maxPeakTrackedBytes: Int
+
+    public init(
+        flowCount: Int = 200,
+        clientChunkBytes: Int = 4_096,
+        remoteChunkBytes: Int = 512,
+        shapedBurstBytes: Int = 1_024,
+        bufferLimits: Socks5BufferLimits = Socks5BufferLimits(
+            maxBufferedBytesPerFlow: 16 * 1024,
+            maxBufferedBytesPerServer: 1_024 * 1024,
+            maxShapedBytesPerServer: 256 * 1024
+        ),
+        maxPeakTrackedBytes: Int = 900 * 1024
+    ) {
+        self.flowCount = max(1, flowCount)
+        self.clientChunkBytes = max(1, clientChunkBytes)
+        self.remoteChunkBytes = max(1, remoteChunkBytes)
+        self.shapedBurstBytes = max(1, shapedBurstBytes)
+        self.bufferLimits = bufferLimits
+        self.maxPeakTrackedBytes = max(1, maxPeakTrackedBytes)
+    }
+}
+
+/// Result of one memory-profile run: ledger peaks, residuals after teardown, and the
+/// pass verdict with the first failed check's detail.
+public struct Socks5MemoryProfileReport: Equatable, Sendable {
+    public let startedAt: Date
+    public let completedAt: Date
+    public let flowCount: Int
+    /// Remote payload bytes delivered to clients across every flow.
+    public let deliveredBytes: Int
+    /// Highest combined buffered-plus-shaped ledger bytes observed at any sample point.
+    public let peakTrackedBytes: Int
+    public let peakBufferedBytes: Int
+    public let peakShapedBytes: Int
+    /// Ledger bytes still held after every session stopped; non-zero means a leak.
+    public let residualTrackedBytes: Int
+    /// Flow telemetry snapshots collected while the load was held.
+    public let telemetrySampleCount: Int
+    /// `nil` when every check held; otherwise the first failed check.
+    public let failureDetail: String?
+
+    public var passed: Bool {
+        failureDetail == nil
+    }
+
+    public var summaryText: String {
+        passed
+            ? "PASS · \(flowCount) flows · peak \(peakTrackedBytes) bytes"
+            : "FAIL · \(failureDetail ?? "unknown")"
+    }
+}
+
+/// Simulated iOS memory-profile scenario: holds the configured number of concurrent
+/// shaped flows against a bounded budget — outbound writes stalled so client payload
+/// pins buffered bytes, then shaped remote deliveries — with flow telemetry sampled
+/// throughout, and checks that peak tracked memory stays under the threshold and that
+/// teardown returns every reserved byte.
+/// Decision: the checks run against the buffer ledger, the relay's instrumented
+/// allocator, rather than process-resident memory — RSS in a shared test process is
+/// dominated by allocations the relay does not own, while the ledger bounds exactly
+/// what the extension's memory limit constrains.
+public final class Socks5MemoryProfileRunner: @unchecked Sendable {
+    public init() {}
+
+    public func run(options: Socks5MemoryProfileOptions = Socks5MemoryProfileOptions()) -> Socks5MemoryProfileReport {
+        let startedAt = Date()
+        let queue = DispatchQueue(label: "com.vpnbridge.memory-profile")
+        let ledger = Socks5BufferLedger(
+            capacity: options.bufferLimits.maxBufferedBytesPerServer,
+            shapedCapacity: options.bufferLimits.maxShapedBytesPerServer
+        )
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        var flows: [ProfileFlow] = []
+        flows.reserveCapacity(options.flowCount)
+
+        var peakTrackedBytes = 0
+        var telemetrySampleCount = 0
+        func sampleLedger() {
+            let usage = ledger.usage
+            peakTrackedBytes = max(peakTrackedBytes, usage.bufferedBytes + usage.shapedBytes)
+        }
+
+        var failureDetail: String?
+        do {
+            for index in 0..<options.flowCount {
+                let flow = ProfileFlow(
+                    host: "flow-\(index).memory-profile.example",
+                    queue: queue,
+                    logger: logger,
+                    ledger: ledger,
+                    options: options
+                )
+                try flow.openAndHandshake()
+                flows.append(flow)
+            }
+
+            // Phase 1: every client pushes one chunk while its outbound write stalls,
+            // pinning buffered bytes across the whole flow population.
+            for flow in flows {
+                flow.pushClientChunk()
+                sampleLedger()
+            }
+            for flow in flows {
+                telemetrySampleCount += flow.sampleTelemetry() ? 1 : 0
+            }
+            let usageUnderLoad = ledger.usage
+            try require(
+                usageUnderLoad.bufferedBytes == options.flowCount * options.clientChunkBytes,
+                "expected \(options.flowCount * options.clientChunkBytes) buffered bytes under load, ledger held \(usageUnderLoad.bufferedBytes)"
+            )
+
+            // Phase 2: outbounds drain, returning the buffered reservations.
+            for flow in flows {
+                flow.releaseOutboundWrites()
+                sampleLedger()
+            }
+
+            // Phase 3: each shaped flow delivers one remote chunk through the shaped ledger.
+            for flow in flows {
+                flow.deliverRemoteChunk()
+                sampleLedger()
+            }
+            let expectedDelivered = options.flowCount * options.remoteChunkBytes
+            try waitFor(timeoutSeconds: 5.0) {
+                flows.reduce(0) { $0 + $1.deliveredPayloadBytes } >= expectedDelivered ? true : nil
+            }
+        } catch {
+            failureDetail = error.localizedDescription
+        }
+
+        let deliveredBytes = flows.reduce(0) { $0 + $1.deliveredPayloadBytes }
+        for flow in flows {
+            flow.stop()
+        }
+        let finalUsage = ledger.usage
+        let refusals = ledger.refusals
+
+        if failureDetail == nil {
+            do {
+                try require(
+                    peakTrackedBytes <= options.maxPeakTrackedBytes,
+                    "peak tracked bytes \(peakTrackedBytes) exceeded the \(options.maxPeakTrackedBytes)-byte threshold"
+                )
+                try require(
+                    refusals.isEmpty,
+                    "budget refused \(refusals.totalRefusalCount) admissions during a profile sized to fit"
+                )
+                try require(
+                    finalUsage.bufferedBytes + finalUsage.shapedBytes == 0,
+                    "ledger still held \(finalUsage.bufferedBytes + finalUsage.shapedBytes) bytes after teardown"
+                )
+                try require(
+                    deliveredBytes == options.flowCount * options.remoteChunkBytes,
+                    "delivered \(deliveredBytes) bytes, expected \(options.flowCount * options.remoteChunkBytes)"
+                )
+            } catch {
+                failureDetail = error.localizedDescription
+            }
+        }
+
+        return Socks5MemoryProfileReport(
+            startedAt: startedAt,
+            completedAt: Date(),
+            flowCount: options.flowCount,
+            deliveredBytes: deliveredBytes,
+            peakTrackedBytes: peakTrackedBytes,
+            peakBufferedBytes: finalUsage.peakBufferedBytes,
+            peakShapedBytes: finalUsage.peakShapedBytes,
+            residualTrackedBytes: finalUsage.bufferedBytes + finalUsage.shapedBytes,
+            telemetrySampleCount: telemetrySampleCount,
+            failureDetail: failureDetail
+        )
+    }
+
+    private func require(_ condition: Bool, _ message: String) throws {
+        if !condition {
+            throw MemoryProfileError(message)
+        }
+    }
+}
+
+/// One simulated session: in-process client and stallable outbound around a real
+/// `Socks5Connection` sharing the scenario's ledger.
+private final class ProfileFlow {
+    private let host: String
+    private let queue: DispatchQueue
+    private let options: Socks5MemoryProfileOptions
+    private let inbound = ProfileInboundConnection()
+    private let outbound = ProfileTCPOutbound()
+    private let connection: Socks5Connection
+
+    init(
+        host: String,
+        queue: DispatchQueue,
+        logger: StructuredLogger,
+        ledger: Socks5BufferLedger,
+        options: Socks5MemoryProfileOptions
+    ) {
+        self.host = host
+        self.queue = queue
+        self.options = options
+        self.connection = Socks5Connection(
+            connection: inbound,
+            provider: ProfileProvider(outbound: outbound),
+            queue: queue,
+            mtu: 1_500,
+            logger: logger,
+            policyEvaluator: ProfileShapeEvaluator(maxBurstBytes: options.shapedBurstBytes),
+            bufferLimits: options.bufferLimits,
+            bufferLedger: ledger
+        )
+    }
+
+    var deliveredPayloadBytes: Int {
+        inbound.deliveredPayloadBytes
+    }
+
+    func openAndHandshake() throws {
+        queue.sync {
+            connection.start()
+            inbound.push(Data([0x05, 0x01, 0x00]))
+            inbound.push(Self.connectRequest(host: host, port: 443))
+        }
+        try waitFor(timeoutSeconds: 1.0) {
+            inbound.handshakeComplete ? true : nil
+        }
+    }
+
+    func pushClientChunk() {
+        queue.sync {
+            inbound.push(Data(repeating: 0x61, count: options.clientChunkBytes))
+        }
+    }
+
+    func releaseOutboundWrites() {
+        queue.sync {
+            outbound.completePendingWrites()
+        }
+    }
+
+    func deliverRemoteChunk() {
+        queue.sync {
+            outbound.queueRead(Data(repeating: 0x62, count: options.remoteChunkBytes))
+        }
+    }
+
+    /// Collects one flow telemetry snapshot under load; returns whether the flow was
+    /// still in its proxy state when sampled.
+    func sampleTelemetry() -> Bool {
+        connection.flowInfo().state == "tcp-proxy"
+    }
+
+    func stop() {
+        connection.stop()
+    }
+
+    private static func connectRequest(host: String, port: UInt16) -> Data {
+        let hostBytes = Array(host.utf8)
+        return Data(
+            [0x05, 0x01, 0x00, 0x03, UInt8(hostBytes.count)] +
+            hostBytes +
+            [UInt8((port >> 8) & 0xFF), UInt8(port & 0xFF)]
+        )
+    }
+}
+
+private struct ProfileShapeEvaluator: RelayPolicyEvaluator {
+    let maxBurstBytes: Int
+
+    func evaluate(_ input: RelayPolicyInput) -> RelayPolicyVerdict {
+        .shape(maxBurstBytes: maxBurstBytes)
+    }
+}
+
+private final class ProfileProvider: @unchecked Sendable, Socks5FullConnectionProvider {
+    private let outbound: ProfileTCPOutbound
+
+    init(outbound: ProfileTCPOutbound) {
+        self.outbound = outbound
+    }
+
+    func makeTCPConnection(
+        to _: NWHostEndpoint,
+        enableTLS _: Bool,
+        tlsParameters _: NWTLSParameters?,
+        delegate _: (any NWTCPConnectionAuthenticationDelegate)?
+    ) -> Socks5TCPOutbound {
+        outbound
+    }
+
+    func makeUDPSession(to endpoint: NWHostEndpoint) -> Socks5UDPSession {
+        ProfileUDPSession(endpoint: endpoint)
+    }
+}
+
+/// Outbound that connects immediately, stalls write completions until released, and
+/// delivers queued reads respecting the relay's shaped read cap.
+private final class ProfileTCPOutbound: @unchecked Sendable, Socks5TCPOutbound {
+    private let lock = NSLock()
+    private var pendingWriteCompletions: [(@Sendable (Error?) -> Void)] = []
+    private var pendingRead: (maximumLength: Int, handler: @Sendable (Data?, Error?) -> Void)?
+    private var queuedReadData = Data()
+
+    func waitUntilReady(completionHandler: @escaping @Sendable (Result<Void, Error>) -> Void) {
+        completionHandler(.success(()))
+    }
+
+    func readMinimumLength(
+        _: Int,
+        maximumLength: Int,
+        completionHandler: @escaping @Sendable (Data?, Error?) -> Void
+    ) {
+        lock.lock()
+        pendingRead = (maximumLength, completionHandler)
+        lock.unlock()
+        deliverQueuedReadIfPossible()
+    }
+
+    func write(_ data: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        _ = data
+        lock.lock()
+        pendingWriteCompletions.append(completionHandler)
+        lock.unlock()
+    }
+
+    func finishWriting(completionHandler: @escaping @Sendable (Error?) -> Void) {
+        completionHandler(nil)
+    }
+
+    func cancel() {}
+
+    func completePendingWrites() {
+        lock.lock()
+        let completions = pendingWriteCompletions
+        pendingWriteCompletions.removeAll(keepingCapacity: false)
+        lock.unlock()
+        for completion in completions {
+            completion(nil)
+        }
+    }
+
+    func queueRead(_ data: Data) {
+        lock.lock()
+        queuedReadData.append(data)
+        lock.unlock()
+        deliverQueuedReadIfPossible()
+    }
+
+    private func deliverQueuedReadIfPossible() {
+        lock.lock()
+        guard let read = pendingRead, !queuedReadData.isEmpty else {
+            lock.unlock()
+            return
+        }
+        let sliceLength = min(read.maximumLength, queuedReadData.count)
+        let slice = queuedReadData.prefix(sliceLength)
+        queuedReadData.removeFirst(sliceLength)
+        pendingRead = nil
+        lock.unlock()
+        read.handler(Data(slice), nil)
+    }
+}
+
+private final class ProfileUDPSession: @unchecked Sendable, Socks5UDPSession {
+    let endpoint: NWHostEndpoint
+
+    init(endpoint: NWHostEndpoint) {
+        self.endpoint = endpoint
+    }
+
+    func setReadHandler(_: @escaping @Sendable (Data?, Error?) -> Void) {}
+
+    func writeDatagram(_: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        completionHandler(nil)
+    }
+
+    func cancel() {}
+}
+
+/// In-process client end: receives are armed by the connection and fed by `push`,
+/// sends complete immediately, and payload bytes after the two handshake replies count
+/// toward delivery.
+private final class ProfileInboundConnection: @unchecked Sendable, Socks5InboundConnection {
+    var stateUpdateHandler: (@Sendable (NWConnection.State) -> Void)?
+
+    private let lock = NSLock()
+    private var pendingReceives: [(@Sendable (Data?, NWConnection.ContentContext?, Bool, NWError?) -> Void)] = []
+    private var handshakeSendsRemaining = 2
+    private var storedDeliveredPayloadBytes = 0
+
+    var deliveredPayloadBytes: Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedDeliveredPayloadBytes
+    }
+
+    var handshakeComplete: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return handshakeSendsRemaining == 0
+    }
+
+    func start(queue _: DispatchQueue) {}
+
+    func receive(
+        minimumIncompleteLength _: Int,
+        maximumLength _: Int,
+        completion: @escaping @Sendable (Data?, NWConnection.ContentContext?, Bool, NWError?) -> Void
+    ) {
+        lock.lock()
+        pendingReceives.append(completion)
+        lock.unlock()
+    }
+
+    func send(content: Data?, completion: NWConnection.SendCompletion) {
+        lock.lock()
+        if handshakeSendsRemaining > 0 {
+            handshakeSendsRemaining -= 1
+        } else {
+            storedDeliveredPayloadBytes += content?.count ?? 0
+        }
+        lock.unlock()
+        if case .contentProcessed(let handler) = completion {
+            handler(nil)
+        }
+    }
+
+    func cancel() {}
+
+    func push(_ data: Data) {
+        lock.lock()
+        guard !pendingReceives.isEmpty else {
+            lock.unlock()
+            return
+        }
+        let completion = pendingReceives.removeFirst()
+        lock.unlock()
+        completion(data, nil, false, nil)
+    }
+}
+
+private struct MemoryProfileError: LocalizedError {
+    let message: String
+
+    init(_ message: String) {
+        self.message = message
+    }
+
+    var errorDescription: String? {
+        message
+    }
+}
+
+private func waitFor<T>(timeoutSeconds: TimeInterval, pollIntervalSeconds: TimeInterval = 0.01, _ body: () -> T?) throws -> T {
+    let deadline = Date().addingTimeInterval(timeoutSeconds)
+    let ticker = DispatchSemaphore(value: 0)
+    while Date() < deadline {
+        if let value = body() {
+            return value
+        }
+        _ = ticker.wait(timeout: .now() + pollIntervalSeconds)
+    }
+    throw MemoryProfileError("timed out waiting for memory-profile state")
+}
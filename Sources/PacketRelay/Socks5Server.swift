@@ -6,6 +6,7 @@ import Foundation
 import Network
 @preconcurrency import NetworkExtension
 import Observability
+import TunnelRuntime
 
 private func interfaceTypeName(_ type: Network.NWInterface.InterfaceType) -> String {
     switch type {
@@ -124,6 +125,21 @@ protocol Socks5InboundConnection: AnyObject {
     func receive(minimumIncompleteLength: Int, maximumLength: Int, completion: @escaping @Sendable (Data?, NWConnection.ContentContext?, Bool, NWError?) -> Void)
     func send(content: Data?, completion: NWConnection.SendCompletion)
     func cancel()
+    /// Aborts the connection without an orderly shutdown, for send-failure policies that
+    /// propagate a remote reset as a reset. Transports without an abortive close fall
+    /// back to `cancel()`.
+    func forceCancel()
+    /// Address of the client device behind the connection, feeding `src=` policy rules in
+    /// standalone/router deployments; `nil` when the transport cannot attribute one.
+    var remoteAddressDescription: String? { get }
+}
+
+extension Socks5InboundConnection {
+    func forceCancel() {
+        cancel()
+    }
+
+    var remoteAddressDescription: String? { nil }
 }
 
 /// Abstraction over outbound TCP channel used for SOCKS CONNECT.
@@ -220,6 +236,333 @@ public struct Socks5TCPPathSettings: Sendable {
     public static let `default` = Socks5TCPPathSettings()
 }
 
+/// Byte budgets bounding relay-side buffering of client payloads awaiting a slow outbound.
+/// Decision: the per-flow cap preserves the historical fixed limit; the per-server cap bounds
+/// the aggregate so one burst fanned across many sessions cannot multiply into an
+/// unpredictable memory peak.
+public struct Socks5BufferLimits: Sendable {
+    /// Cap on bytes buffered for one session before its connection is closed.
+    public let maxBufferedBytesPerFlow: Int
+    /// Cap on bytes buffered across every session of one server.
+    public let maxBufferedBytesPerServer: Int
+    /// Cap on undelivered shaped payload bytes held across every rate-shaped session.
+    /// The per-flow read cap bounds one shaped flow; this bounds what many shaped flows
+    /// stalled behind slow clients can pin at once.
+    public let maxShapedBytesPerServer: Int
+
+    public init(
+        maxBufferedBytesPerFlow: Int = 256 * 1024,
+        maxBufferedBytesPerServer: Int = 4 * 1024 * 1024,
+        maxShapedBytesPerServer: Int = 2 * 1024 * 1024
+    ) {
+        self.maxBufferedBytesPerFlow = max(1, maxBufferedBytesPerFlow)
+        self.maxBufferedBytesPerServer = max(self.maxBufferedBytesPerFlow, maxBufferedBytesPerServer)
+        self.maxShapedBytesPerServer = max(1, maxShapedBytesPerServer)
+    }
+
+    public static let `default` = Socks5BufferLimits()
+}
+
+/// Sizing for relayed chunks handed to `NWConnection.send` toward the client.
+/// Decision: the proxy otherwise forwards whatever one outbound read yields — sometimes a
+/// one-byte fragment, sometimes the full read cap — so hosts tuning for throughput can ask
+/// the transport to coalesce tiny fragments and split huge reads into well-sized writes.
+public struct Socks5ChunkSizing: Sendable, Equatable {
+    /// Bytes an outbound read waits to accumulate before delivering; `1` forwards fragments
+    /// as they arrive. A shorter final fragment still delivers when the stream ends.
+    public let minCoalescedReadBytes: Int
+    /// Ceiling on one forwarded chunk. Shaped-flow pacing slices below this still apply.
+    public let maxChunkBytes: Int
+
+    public init(
+        minCoalescedReadBytes: Int = 1,
+        maxChunkBytes: Int = 65_535
+    ) {
+        self.maxChunkBytes = min(max(1, maxChunkBytes), 65_535)
+        self.minCoalescedReadBytes = min(max(1, minCoalescedReadBytes), self.maxChunkBytes)
+    }
+
+    public static let `default` = Socks5ChunkSizing()
+}
+
+/// Adaptive ceiling on one forwarded chunk toward the client, tuned per flow to how
+/// fast the client actually consumes delivered bytes.
+/// Decision: a slow client used to leave the proxy reading from the remote at full cap
+/// while the backlog queued in the transport's send buffer. Timing each delivery's
+/// completion gives a per-flow consumption rate; halving the read window when
+/// consumption falls behind bounds queued bytes to roughly one window, so a slow
+/// upstream earns a small window instead of megabytes of engine-held buffer.
+public struct Socks5ReceiveWindowTuner: Sendable, Equatable {
+    /// Floor the window never shrinks below, so even a crawling client makes progress.
+    public static let minWindowBytes = 4_096
+    /// Consumption below this rate shrinks the window.
+    public static let slowConsumptionBytesPerSecond = 131_072
+    /// Consumption at or above this rate grows the window back toward its ceiling.
+    public static let fastConsumptionBytesPerSecond = 1_048_576
+
+    /// Current per-read window toward the client, in bytes.
+    public private(set) var windowBytes: Int
+    private let maxWindowBytes: Int
+
+    /// - Parameter maxWindowBytes: Ceiling the window starts at and recovers toward,
+    ///   normally the flow's configured `Socks5ChunkSizing.maxChunkBytes`.
+    public init(maxWindowBytes: Int = 65_535) {
+        self.maxWindowBytes = max(Self.minWindowBytes, maxWindowBytes)
+        windowBytes = self.maxWindowBytes
+    }
+
+    /// Records one completed delivery toward the client: `byteCount` bytes whose send
+    /// completion arrived `seconds` after the write was issued. Instantaneous
+    /// completions count as fast; rates between the two thresholds hold the window.
+    public mutating func recordDelivery(byteCount: Int, seconds: TimeInterval) {
+        guard byteCount > 0 else {
+            return
+        }
+        if seconds > 0 {
+            let rate = TimeInterval(byteCount) / seconds
+            if rate < TimeInterval(Self.slowConsumptionBytesPerSecond) {
+                windowBytes = max(Self.minWindowBytes, windowBytes / 2)
+                return
+            }
+            guard rate >= TimeInterval(Self.fastConsumptionBytesPerSecond) else {
+                return
+            }
+        }
+        windowBytes = min(maxWindowBytes, windowBytes * 2)
+    }
+}
+
+/// Client-facing close behavior when a write toward the remote fails mid-flow.
+public enum Socks5SendFailureBehavior: String, Sendable, Codable {
+    /// Abort the client connection without an orderly shutdown — the reset-equivalent
+    /// signal, so the app sees the failure as abrupt rather than a clean end of stream.
+    case reset
+    /// Close the client connection in order — the clean-shutdown signal, and the
+    /// behavior every failure received before the mapping table existed.
+    case close
+    /// Signal nothing; the flow stops forwarding and the half-close abort deadline
+    /// reaps the session if the client never gives up on its own.
+    case silent
+}
+
+/// What kind of failure the remote write reported, classified from the error string.
+/// Decision: transport errors arrive as framework-formatted description strings, so
+/// classification is a small substring taxonomy like `RelayDialFailureKind.classify`
+/// rather than a match on any one error type's cases.
+public enum Socks5SendFailureKind: String, Sendable, CaseIterable {
+    /// The remote reset the connection (connection reset, broken pipe).
+    case connectionReset = "connection-reset"
+    /// The remote became unreachable (network or host unreachable, no route).
+    case unreachable
+    /// The write ran out of time.
+    case timeout
+    /// Anything the taxonomy does not recognize.
+    case other
+
+    /// Classifies a failure reason string by case-insensitive substring.
+    public static func classify(reason: String) -> Socks5SendFailureKind {
+        let lowered = reason.lowercased()
+        if lowered.contains("reset") || lowered.contains("broken pipe") {
+            return .connectionReset
+        }
+        if lowered.contains("unreachable") || lowered.contains("no route") {
+            return .unreachable
+        }
+        if lowered.contains("timed out") || lowered.contains("timeout") {
+            return .timeout
+        }
+        return .other
+    }
+}
+
+/// Configurable mapping from classified send failures to the client-facing close
+/// behavior, so a remote reset can surface to the app as a reset while an unreachable
+/// network still closes cleanly — instead of every failure collapsing into one signal.
+public struct Socks5SendFailurePolicy: Sendable {
+    /// Behavior per classified failure kind; kinds without an entry use `fallback`.
+    public let behaviors: [Socks5SendFailureKind: Socks5SendFailureBehavior]
+    /// Behavior for kinds the table does not name.
+    public let fallback: Socks5SendFailureBehavior
+
+    /// - Parameters:
+    ///   - behaviors: Behavior per classified failure kind. The default propagates
+    ///     remote resets as resets and leaves everything else on the fallback.
+    ///   - fallback: Behavior for unmapped kinds, the pre-table clean close by default.
+    public init(
+        behaviors: [Socks5SendFailureKind: Socks5SendFailureBehavior] = [.connectionReset: .reset],
+        fallback: Socks5SendFailureBehavior = .close
+    ) {
+        self.behaviors = behaviors
+        self.fallback = fallback
+    }
+
+    public static let `default` = Socks5SendFailurePolicy()
+
+    /// Resolves the behavior for one failure reason string.
+    public func behavior(forReason reason: String) -> Socks5SendFailureBehavior {
+        behaviors[Socks5SendFailureKind.classify(reason: reason)] ?? fallback
+    }
+}
+
+/// Point-in-time aggregate buffer usage for one server.
+public struct Socks5BufferUsage: Sendable, Equatable {
+    public let bufferedBytes: Int
+    public let peakBufferedBytes: Int
+    /// Undelivered shaped payload bytes currently held across rate-shaped sessions.
+    public let shapedBytes: Int
+    public let peakShapedBytes: Int
+    /// Sessions closed to free shaped budget since the server started.
+    public let shapedEvictionCount: Int
+
+    public init(
+        bufferedBytes: Int,
+        peakBufferedBytes: Int,
+        shapedBytes: Int = 0,
+        peakShapedBytes: Int = 0,
+        shapedEvictionCount: Int = 0
+    ) {
+        self.bufferedBytes = bufferedBytes
+        self.peakBufferedBytes = peakBufferedBytes
+        self.shapedBytes = shapedBytes
+        self.peakShapedBytes = peakShapedBytes
+        self.shapedEvictionCount = shapedEvictionCount
+    }
+}
+
+/// Aggregate of shared-budget admission refusals, attributed to the destination host the
+/// refused session was serving.
+/// Decision: attribution is per destination rather than per session so operators can tell
+/// an undersized budget (refusals spread across many hosts) apart from a single misbehaving
+/// destination absorbing the whole cap.
+public struct Socks5BudgetRefusalReport: Sendable, Equatable {
+    /// Bound on distinct destinations tracked; refusals past it count as untracked so the
+    /// report itself stays within a fixed memory footprint.
+    public static let maxTrackedDestinations = 64
+
+    /// Refusals across all destinations, tracked or not.
+    public let totalRefusalCount: Int
+    /// Refusal count per destination host; sessions refused before a CONNECT resolves
+    /// appear under `unknown`.
+    public let refusalsByDestination: [String: Int]
+    /// Refusals attributed to destinations beyond the tracking bound.
+    public let untrackedRefusalCount: Int
+
+    public init(
+        totalRefusalCount: Int,
+        refusalsByDestination: [String: Int],
+        untrackedRefusalCount: Int = 0
+    ) {
+        self.totalRefusalCount = totalRefusalCount
+        self.refusalsByDestination = refusalsByDestination
+        self.untrackedRefusalCount = untrackedRefusalCount
+    }
+
+    public var isEmpty: Bool {
+        totalRefusalCount == 0
+    }
+}
+
+/// Shared ledger of buffered bytes across one server's sessions.
+/// Contract: sessions reserve before appending to their flow buffer and release as the
+/// buffer drains or the session closes, so `usage` never undercounts held memory.
+final class Socks5BufferLedger: @unchecked Sendable {
+    private let lock = NSLock()
+    private let capacity: Int
+    private let shapedCapacity: Int
+    private var bufferedBytes = 0
+    private var peakBufferedBytes = 0
+    private var shapedBytes = 0
+    private var peakShapedBytes = 0
+    private var shapedEvictionCount = 0
+    private var refusalsByDestination: [String: Int] = [:]
+    private var untrackedRefusalCount = 0
+    private var totalRefusalCount = 0
+
+    init(capacity: Int, shapedCapacity: Int = Socks5BufferLimits.default.maxShapedBytesPerServer) {
+        self.capacity = capacity
+        self.shapedCapacity = shapedCapacity
+    }
+
+    func reserve(_ byteCount: Int) -> Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        guard bufferedBytes + byteCount <= capacity else {
+            return false
+        }
+        bufferedBytes += byteCount
+        peakBufferedBytes = max(peakBufferedBytes, bufferedBytes)
+        return true
+    }
+
+    func release(_ byteCount: Int) {
+        lock.lock()
+        defer { lock.unlock() }
+        bufferedBytes = max(0, bufferedBytes - byteCount)
+    }
+
+    /// Reserves shaped budget for one undelivered shaped payload slice.
+    func reserveShaped(_ byteCount: Int) -> Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        guard shapedBytes + byteCount <= shapedCapacity else {
+            return false
+        }
+        shapedBytes += byteCount
+        peakShapedBytes = max(peakShapedBytes, shapedBytes)
+        return true
+    }
+
+    func releaseShaped(_ byteCount: Int) {
+        lock.lock()
+        defer { lock.unlock() }
+        shapedBytes = max(0, shapedBytes - byteCount)
+    }
+
+    /// Counts one session closed to bring shaped usage back under the global cap.
+    func recordShapedEviction() {
+        lock.lock()
+        defer { lock.unlock() }
+        shapedEvictionCount += 1
+    }
+
+    /// Counts one admission refusal against the destination being served, so the refusal
+    /// remains attributable after the refused session closes.
+    func recordRefusal(destination: String?) {
+        lock.lock()
+        defer { lock.unlock() }
+        totalRefusalCount += 1
+        let key = destination ?? "unknown"
+        if refusalsByDestination[key] != nil
+            || refusalsByDestination.count < Socks5BudgetRefusalReport.maxTrackedDestinations {
+            refusalsByDestination[key, default: 0] += 1
+        } else {
+            untrackedRefusalCount += 1
+        }
+    }
+
+    var refusals: Socks5BudgetRefusalReport {
+        lock.lock()
+        defer { lock.unlock() }
+        return Socks5BudgetRefusalReport(
+            totalRefusalCount: totalRefusalCount,
+            refusalsByDestination: refusalsByDestination,
+            untrackedRefusalCount: untrackedRefusalCount
+        )
+    }
+
+    var usage: Socks5BufferUsage {
+        lock.lock()
+        defer { lock.unlock() }
+        return Socks5BufferUsage(
+            bufferedBytes: bufferedBytes,
+            peakBufferedBytes: peakBufferedBytes,
+            shapedBytes: shapedBytes,
+            peakShapedBytes: peakShapedBytes,
+            shapedEvictionCount: shapedEvictionCount
+        )
+    }
+}
+
 /// Extended outbound provider for SOCKS TCP + UDP backends.
 protocol Socks5FullConnectionProvider: Socks5ConnectionProvider {
     func makeTCPConnection(
@@ -228,6 +571,29 @@ protocol Socks5FullConnectionProvider: Socks5ConnectionProvider {
         tlsParameters: NWTLSParameters?,
         delegate: NWTCPConnectionAuthenticationDelegate?
     ) -> Socks5TCPOutbound
+
+    /// Policy-aware variant carrying the winning rule's Nagle preference for the new socket.
+    func makeTCPConnection(
+        to endpoint: NWHostEndpoint,
+        enableTLS: Bool,
+        tlsParameters: NWTLSParameters?,
+        delegate: NWTCPConnectionAuthenticationDelegate?,
+        noDelay: Bool?
+    ) -> Socks5TCPOutbound
+}
+
+extension Socks5FullConnectionProvider {
+    /// Default: drop the preference so providers without socket-option control keep working.
+    func makeTCPConnection(
+        to endpoint: NWHostEndpoint,
+        enableTLS: Bool,
+        tlsParameters: NWTLSParameters?,
+        delegate: NWTCPConnectionAuthenticationDelegate?,
+        noDelay: Bool?
+    ) -> Socks5TCPOutbound {
+        _ = noDelay
+        return makeTCPConnection(to: endpoint, enableTLS: enableTLS, tlsParameters: tlsParameters, delegate: delegate)
+    }
 }
 
 enum TCPOutboundEvent: Sendable {
@@ -278,6 +644,18 @@ final class SocksInboundNWConnectionAdapter: @unchecked Sendable, Socks5InboundC
     func cancel() {
         connection.cancel()
     }
+
+    func forceCancel() {
+        // Docs: https://developer.apple.com/documentation/network/nwconnection/forcecancel()
+        connection.forceCancel()
+    }
+
+    var remoteAddressDescription: String? {
+        guard case .hostPort(let host, _) = connection.endpoint else {
+            return nil
+        }
+        return "\(host)"
+    }
 }
 
 final class NWConnectionTCPAdapter: @unchecked Sendable, Socks5PathAwareTCPOutbound {
@@ -1178,6 +1556,19 @@ final class NWConnectionUDPSessionAdapter: @unchecked Sendable, Socks5UDPSession
         })
     }
 
+    func writeDatagrams(_ datagrams: [Data], completionHandler: @escaping @Sendable (Error?) -> Void) {
+        guard datagrams.count > 1 else {
+            datagrams.forEach { writeDatagram($0, completionHandler: completionHandler) }
+            return
+        }
+        // Docs: https://developer.apple.com/documentation/network/nwconnection/batch(_:)
+        connection.batch {
+            for datagram in datagrams {
+                writeDatagram(datagram, completionHandler: completionHandler)
+            }
+        }
+    }
+
     func restart() {
         connection.restart()
     }
@@ -1350,14 +1741,26 @@ final class PacketTunnelProviderAdapter: @unchecked Sendable, Socks5FullConnecti
     ) -> Socks5TCPOutbound {
         _ = tlsParameters
         _ = delegate
-        return makeNWConnection(to: endpoint, enableTLS: enableTLS)
+        return makeNWConnection(to: endpoint, enableTLS: enableTLS, noDelay: nil)
+    }
+
+    func makeTCPConnection(
+        to endpoint: NWHostEndpoint,
+        enableTLS: Bool,
+        tlsParameters: NWTLSParameters?,
+        delegate: NWTCPConnectionAuthenticationDelegate?,
+        noDelay: Bool?
+    ) -> Socks5TCPOutbound {
+        _ = tlsParameters
+        _ = delegate
+        return makeNWConnection(to: endpoint, enableTLS: enableTLS, noDelay: noDelay)
     }
 
     func makeUDPSession(to endpoint: NWHostEndpoint) -> Socks5UDPSession {
         makeNWUDPSession(to: endpoint)
     }
 
-    private func makeNWConnection(to endpoint: NWHostEndpoint, enableTLS: Bool) -> Socks5TCPOutbound {
+    private func makeNWConnection(to endpoint: NWHostEndpoint, enableTLS: Bool, noDelay: Bool?) -> Socks5TCPOutbound {
         guard let portValue = UInt16(endpoint.port),
               let port = NWEndpoint.Port(rawValue: portValue)
         else {
@@ -1375,7 +1778,7 @@ final class PacketTunnelProviderAdapter: @unchecked Sendable, Socks5FullConnecti
                 tls: enableTLS
             )
         ) { _ in
-            return self.makeSingleNWConnection(host: endpoint.hostname, port: port, enableTLS: enableTLS)
+            return self.makeSingleNWConnection(host: endpoint.hostname, port: port, enableTLS: enableTLS, noDelay: noDelay)
         }
     }
 
@@ -1408,11 +1811,14 @@ final class PacketTunnelProviderAdapter: @unchecked Sendable, Socks5FullConnecti
         )
     }
 
-    private func makeSingleNWConnection(host: String, port: Network.NWEndpoint.Port, enableTLS: Bool) -> Socks5PathAwareTCPOutbound {
+    private func makeSingleNWConnection(host: String, port: Network.NWEndpoint.Port, enableTLS: Bool, noDelay: Bool?) -> Socks5PathAwareTCPOutbound {
         let parameters = enableTLS ? NWParameters.tls : NWParameters.tcp
         if let multipathServiceType = tcpPathSettings.multipathServiceType {
             parameters.multipathServiceType = multipathServiceType
         }
+        if let noDelay, let tcpOptions = parameters.defaultProtocolStack.transportProtocol as? NWProtocolTCP.Options {
+            tcpOptions.noDelay = noDelay
+        }
         if #available(iOS 18.0, macOS 15.0, *) {
             // Docs: https://developer.apple.com/documentation/networkextension/nepackettunnelprovider
             if let virtualInterface = provider.virtualInterface {
@@ -1527,6 +1933,74 @@ public enum Socks5ServerError: Error {
     case invalidPort
 }
 
+/// Codable envelope of the relay's learned per-destination state: live dial suppressions
+/// and classified failure counts. Hosts serialize it (JSON via `Codable`) when the tunnel
+/// stops and hand it back after a restart, so a short extension relaunch keeps failing
+/// fast against destinations that were unreachable moments earlier and keeps exported
+/// failure totals continuous. Observed DNS associations persist separately through the
+/// analytics worker's export API.
+public struct Socks5ServerPersistedState: Codable, Sendable, Equatable {
+    /// Bumped whenever the layout changes; restore ignores unknown versions so a stale
+    /// persisted blob can never corrupt a newer relay.
+    public static let currentSchemaVersion = 1
+
+    public let schemaVersion: Int
+    public let dialFailures: [Socks5DialFailureCache.ExportEntry]
+    public let dialFailureCounts: [RelayDialFailureCounters.Entry]
+
+    public init(
+        schemaVersion: Int = Socks5ServerPersistedState.currentSchemaVersion,
+        dialFailures: [Socks5DialFailureCache.ExportEntry],
+        dialFailureCounts: [RelayDialFailureCounters.Entry]
+    ) {
+        self.schemaVersion = schemaVersion
+        self.dialFailures = dialFailures
+        self.dialFailureCounts = dialFailureCounts
+    }
+}
+
+/// Point-in-time debug view of one active SOCKS session, exported through
+/// `Socks5Server.flowSnapshot()` so stuck flows can be diagnosed in production.
+public struct Socks5FlowInfo: Codable, Sendable, Equatable {
+    /// Stable session state name: `greeting`, `request`, `dial-pending`, `connecting-tcp`,
+    /// `tcp-proxy`, `udp-proxy`, `udp-forward`, or `blocked-tls-drain`.
+    public let state: String
+    /// Destination host once a CONNECT resolves; `nil` during the handshake phases.
+    public let destinationHost: String?
+    /// Destination port once a CONNECT resolves; `nil` during the handshake phases.
+    public let destinationPort: String?
+    /// Client payload bytes buffered toward the outbound side (send-queue occupancy).
+    public let bufferedClientBytes: Int
+    /// Undelivered rate-shaped bytes reserved toward the client (receive-queue occupancy).
+    public let undeliveredShapedBytes: Int
+    /// Whether the flow is currently rate-shaped.
+    public let isRateShaped: Bool
+    /// Total pacing delay this flow has earned ahead of outbound re-reads, in milliseconds.
+    /// Zero for unshaped and unpaced flows, so shaping impact can be measured directly
+    /// instead of inferred from packet timing.
+    public let shapedDelayTotalMilliseconds: Int
+    /// High-water mark of undelivered shaped bytes reserved toward the client.
+    public let peakUndeliveredShapedBytes: Int
+    /// Chunks never forwarded because shaped budget could not be reserved; the flow closes
+    /// at the first such drop, so any non-zero value marks a shaping casualty.
+    public let shapedDroppedChunkCount: Int
+    /// Autotuned per-read window toward the client, in bytes. Starts at the flow's chunk
+    /// ceiling and shrinks while the client consumes deliveries slower than the tuner's
+    /// thresholds, so a small value identifies a slow-draining client.
+    public let receiveWindowBytes: Int
+    /// Whether the host paused delivery toward the client on this flow via
+    /// `Socks5Server.setFlowPaused(flowCookie:paused:)`.
+    public let isFlowPaused: Bool
+    /// Whether a write toward the client is in flight, during which outbound reads stay
+    /// paused — a flow stuck here is waiting on the client to drain (backpressure retry).
+    public let isAwaitingClientDrain: Bool
+    /// Opaque cookie the host's `flowCookieProvider` attached at dial time, echoed here and
+    /// in the flow's structured log events so hosts can key their own per-flow maps without
+    /// re-deriving an identity from destination tuples. `nil` before the dial or when no
+    /// provider is installed.
+    public let flowCookie: UInt64?
+}
+
 /// Local SOCKS5 server that handles CONNECT and UDP ASSOCIATE from the dataplane.
 /// Queue ownership: listener state and `connections` map are mutated on `queue`.
 public final class Socks5Server: @unchecked Sendable {
@@ -1539,22 +2013,87 @@ public final class Socks5Server: @unchecked Sendable {
     private let mtu: Int
     private let makeConnectionQueue: @Sendable () -> DispatchQueue
     private let providerFactory: @Sendable (DispatchQueue) -> Socks5FullConnectionProvider
+    private let policyEvaluator: (any RelayPolicyEvaluator)?
+    private let logPolicyRuleTraces: Bool
+    private let upstreamRoutes: RelayUpstreamRoutes
+    private let hostResolvers: RelayHostResolvers
+    private let dialFailureCache: Socks5DialFailureCache
+    private let dialLatencyHeatmap: RelayDialLatencyHeatmap
+    private let dialFailureCounters: RelayDialFailureCounters
+    private let dialLimiter: RelayDialLimiter
+    private let flowRateLimiter: RelayFlowRateLimiter
+    private let bogonFilter: BogonDestinationFilter?
+    private let loopGuard: TunnelLoopGuard?
+    private let dnsSessionPool: Socks5DNSSessionPool?
+    private let bufferLimits: Socks5BufferLimits
+    private let chunkSizing: Socks5ChunkSizing
+    private let bufferLedger: Socks5BufferLedger
+    private let sendTLSAlertOnPolicyBlock: Bool
+    private let sendFailurePolicy: Socks5SendFailurePolicy
+    private let secondaryFlowPredictor: RelaySecondaryFlowPredictor?
+    private let flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)?
     private let queueSpecificKey = DispatchSpecificKey<UInt8>()
 
     private var listener: NWListener?
     private var connections: [ObjectIdentifier: Socks5Connection] = [:]
+    private var halfCloseAbortCount = 0
 
     /// - Parameters:
     ///   - provider: Outbound connection provider implementation.
     ///   - queue: Serial queue used for listener + connection events.
     ///   - mtu: MTU hint forwarded to UDP relay handlers.
     ///   - logger: Structured logger for server lifecycle and failures.
-    init(provider: Socks5FullConnectionProvider, queue: DispatchQueue, mtu: Int, logger: StructuredLogger) {
+    init(
+        provider: Socks5FullConnectionProvider,
+        queue: DispatchQueue,
+        mtu: Int,
+        logger: StructuredLogger,
+        policyEvaluator: (any RelayPolicyEvaluator)? = nil,
+        logPolicyRuleTraces: Bool = false,
+        upstreamRoutes: RelayUpstreamRoutes = .none,
+        hostResolvers: RelayHostResolvers = .none,
+        dialFailureCache: Socks5DialFailureCache = Socks5DialFailureCache(),
+        dialLatencyHeatmap: RelayDialLatencyHeatmap = RelayDialLatencyHeatmap(),
+        dialFailureCounters: RelayDialFailureCounters = RelayDialFailureCounters(),
+        dialLimiter: RelayDialLimiter = RelayDialLimiter(),
+        flowRateLimiter: RelayFlowRateLimiter = RelayFlowRateLimiter(),
+        bogonFilter: BogonDestinationFilter? = nil,
+        loopGuard: TunnelLoopGuard? = nil,
+        dnsSessionPool: Socks5DNSSessionPool? = nil,
+        bufferLimits: Socks5BufferLimits = .default,
+        chunkSizing: Socks5ChunkSizing = .default,
+        sendTLSAlertOnPolicyBlock: Bool = false,
+        sendFailurePolicy: Socks5SendFailurePolicy = .default,
+        secondaryFlowPredictor: RelaySecondaryFlowPredictor? = nil,
+        flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)? = nil
+    ) {
         self.providerFactory = { _ in provider }
         self.makeConnectionQueue = { queue }
         self.queue = queue
         self.mtu = mtu
         self.logger = logger
+        self.policyEvaluator = policyEvaluator
+        self.logPolicyRuleTraces = logPolicyRuleTraces
+        self.upstreamRoutes = upstreamRoutes
+        self.hostResolvers = hostResolvers
+        self.dialFailureCache = dialFailureCache
+        self.dialLatencyHeatmap = dialLatencyHeatmap
+        self.dialFailureCounters = dialFailureCounters
+        self.dialLimiter = dialLimiter
+        self.flowRateLimiter = flowRateLimiter
+        self.bogonFilter = bogonFilter
+        self.loopGuard = loopGuard
+        self.dnsSessionPool = dnsSessionPool
+        self.bufferLimits = bufferLimits
+        self.chunkSizing = chunkSizing
+        self.bufferLedger = Socks5BufferLedger(
+            capacity: bufferLimits.maxBufferedBytesPerServer,
+            shapedCapacity: bufferLimits.maxShapedBytesPerServer
+        )
+        self.sendTLSAlertOnPolicyBlock = sendTLSAlertOnPolicyBlock
+        self.sendFailurePolicy = sendFailurePolicy
+        self.secondaryFlowPredictor = secondaryFlowPredictor
+        self.flowCookieProvider = flowCookieProvider
         self.queue.setSpecific(key: queueSpecificKey, value: 1)
     }
 
@@ -1563,13 +2102,53 @@ public final class Socks5Server: @unchecked Sendable {
         mtu: Int,
         logger: StructuredLogger,
         makeConnectionQueue: @escaping @Sendable () -> DispatchQueue,
-        providerFactory: @escaping @Sendable (DispatchQueue) -> Socks5FullConnectionProvider
+        providerFactory: @escaping @Sendable (DispatchQueue) -> Socks5FullConnectionProvider,
+        policyEvaluator: (any RelayPolicyEvaluator)?,
+        logPolicyRuleTraces: Bool,
+        upstreamRoutes: RelayUpstreamRoutes,
+        hostResolvers: RelayHostResolvers,
+        dialFailureCache: Socks5DialFailureCache,
+        dialLatencyHeatmap: RelayDialLatencyHeatmap,
+        dialFailureCounters: RelayDialFailureCounters,
+        dialLimiter: RelayDialLimiter,
+        flowRateLimiter: RelayFlowRateLimiter,
+        bogonFilter: BogonDestinationFilter?,
+        loopGuard: TunnelLoopGuard?,
+        dnsSessionPool: Socks5DNSSessionPool?,
+        bufferLimits: Socks5BufferLimits,
+        chunkSizing: Socks5ChunkSizing,
+        sendTLSAlertOnPolicyBlock: Bool,
+        sendFailurePolicy: Socks5SendFailurePolicy,
+        secondaryFlowPredictor: RelaySecondaryFlowPredictor?,
+        flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)?
     ) {
         self.queue = queue
         self.mtu = mtu
         self.logger = logger
         self.makeConnectionQueue = makeConnectionQueue
         self.providerFactory = providerFactory
+        self.policyEvaluator = policyEvaluator
+        self.logPolicyRuleTraces = logPolicyRuleTraces
+        self.upstreamRoutes = upstreamRoutes
+        self.hostResolvers = hostResolvers
+        self.dialFailureCache = dialFailureCache
+        self.dialLatencyHeatmap = dialLatencyHeatmap
+        self.dialFailureCounters = dialFailureCounters
+        self.dialLimiter = dialLimiter
+        self.flowRateLimiter = flowRateLimiter
+        self.bogonFilter = bogonFilter
+        self.loopGuard = loopGuard
+        self.dnsSessionPool = dnsSessionPool
+        self.bufferLimits = bufferLimits
+        self.chunkSizing = chunkSizing
+        self.bufferLedger = Socks5BufferLedger(
+            capacity: bufferLimits.maxBufferedBytesPerServer,
+            shapedCapacity: bufferLimits.maxShapedBytesPerServer
+        )
+        self.sendTLSAlertOnPolicyBlock = sendTLSAlertOnPolicyBlock
+        self.sendFailurePolicy = sendFailurePolicy
+        self.secondaryFlowPredictor = secondaryFlowPredictor
+        self.flowCookieProvider = flowCookieProvider
         self.queue.setSpecific(key: queueSpecificKey, value: 1)
     }
 
@@ -1579,12 +2158,62 @@ public final class Socks5Server: @unchecked Sendable {
     ///   - queue: Serial queue for listener + connection events.
     ///   - mtu: MTU hint used by UDP relay.
     ///   - logger: Structured logger.
+    ///   - policyEvaluator: Optional host-supplied policy hook consulted before each outbound dial.
+    ///   - logPolicyRuleTraces: Debug mode that logs a rule-by-rule admission trace per flow
+    ///     when the evaluator is a compiled policy document; off for production noise reasons.
+    ///   - upstreamRoutes: Named upstream proxy transports resolvable by `route` policy verdicts.
+    ///   - hostResolvers: Named resolvers resolvable by `resolver=` policy rule parameters.
+    ///   - dialFailureCache: Negative cache that fails flows fast after recent dial failures.
+    ///   - dialLatencyHeatmap: Per-destination aggregate of dial-to-ready latency, exportable
+    ///     as percentiles through `dialLatencySnapshot()`.
+    ///   - dialFailureCounters: Per-destination counts of classified dial failures, exportable
+    ///     through `dialFailureSnapshot()`.
+    ///   - dialLimiter: Cap on concurrent outbound dials shared by every session; over-cap
+    ///     CONNECTs wait FIFO for a slot, with depths exportable through `dialQueueStats()`.
+    ///   - flowRateLimiter: Token-bucket caps on the new-flow accept rate, per client source
+    ///     and server-wide; over-rate connections are reset before a session is allocated,
+    ///     with counters exportable through `flowAdmissionStats()`.
+    ///   - bogonFilter: Optional filter rejecting unroutable destination literals before any dial.
+    ///   - loopGuard: Optional guard rejecting flows destined to the tunnel's own addresses.
+    ///   - enableDNSFastPath: When enabled, port-53 datagrams from every UDP ASSOCIATE
+    ///     multiplex over a shared per-resolver session pool keyed by DNS transaction ID,
+    ///     instead of dialing the resolver once per client source port.
+    ///   - bufferLimits: Per-flow and per-server caps on buffered client payload bytes.
+    ///   - chunkSizing: Min-coalescing and max-chunk bounds on relayed reads so writes toward
+    ///     the client are well-sized instead of mirroring whatever each outbound read yields.
+    ///   - sendTLSAlertOnPolicyBlock: When enabled, policy-blocked CONNECTs are accepted long enough
+    ///     to read the TLS ClientHello and answer with a fatal alert instead of a bare reset.
+    ///   - sendFailurePolicy: Mapping from classified mid-flow send failures (reset,
+    ///     unreachable, timeout) to the client-facing close behavior.
+    ///   - secondaryFlowPredictor: Optional ALG registry shared across sessions; control-flow
+    ///     payloads (FTP, SIP) feed it and announced data flows dial past blocking rules.
+    ///   - flowCookieProvider: Optional host hook called once per flow at dial time with
+    ///     (host, port, transport); the opaque cookie it returns is echoed in the flow's
+    ///     structured log events and `flowSnapshot()` entries until close.
     public convenience init(
         provider: NEPacketTunnelProvider,
         queue: DispatchQueue,
         mtu: Int,
         logger: StructuredLogger,
-        tcpPathSettings: Socks5TCPPathSettings = .default
+        tcpPathSettings: Socks5TCPPathSettings = .default,
+        policyEvaluator: (any RelayPolicyEvaluator)? = nil,
+        logPolicyRuleTraces: Bool = false,
+        upstreamRoutes: RelayUpstreamRoutes = .none,
+        hostResolvers: RelayHostResolvers = .none,
+        dialFailureCache: Socks5DialFailureCache = Socks5DialFailureCache(),
+        dialLatencyHeatmap: RelayDialLatencyHeatmap = RelayDialLatencyHeatmap(),
+        dialFailureCounters: RelayDialFailureCounters = RelayDialFailureCounters(),
+        dialLimiter: RelayDialLimiter = RelayDialLimiter(),
+        flowRateLimiter: RelayFlowRateLimiter = RelayFlowRateLimiter(),
+        bogonFilter: BogonDestinationFilter? = nil,
+        loopGuard: TunnelLoopGuard? = nil,
+        enableDNSFastPath: Bool = false,
+        bufferLimits: Socks5BufferLimits = .default,
+        chunkSizing: Socks5ChunkSizing = .default,
+        sendTLSAlertOnPolicyBlock: Bool = false,
+        sendFailurePolicy: Socks5SendFailurePolicy = .default,
+        secondaryFlowPredictor: RelaySecondaryFlowPredictor? = nil,
+        flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)? = nil
     ) {
         let connectionQueueLabelPrefix = queue.label.isEmpty ? "com.vpnbridge.tunnel.relay.session" : "\(queue.label).session"
         self.init(
@@ -1601,7 +2230,27 @@ public final class Socks5Server: @unchecked Sendable {
                     logger: logger,
                     tcpPathSettings: tcpPathSettings
                 )
-            }
+            },
+            policyEvaluator: policyEvaluator,
+            logPolicyRuleTraces: logPolicyRuleTraces,
+            upstreamRoutes: upstreamRoutes,
+            hostResolvers: hostResolvers,
+            dialFailureCache: dialFailureCache,
+            dialLatencyHeatmap: dialLatencyHeatmap,
+            dialFailureCounters: dialFailureCounters,
+            dialLimiter: dialLimiter,
+            flowRateLimiter: flowRateLimiter,
+            bogonFilter: bogonFilter,
+            loopGuard: loopGuard,
+            dnsSessionPool: enableDNSFastPath
+                ? Socks5DNSSessionPool(logger: logger, policyEvaluator: policyEvaluator)
+                : nil,
+            bufferLimits: bufferLimits,
+            chunkSizing: chunkSizing,
+            sendTLSAlertOnPolicyBlock: sendTLSAlertOnPolicyBlock,
+            sendFailurePolicy: sendFailurePolicy,
+            secondaryFlowPredictor: secondaryFlowPredictor,
+            flowCookieProvider: flowCookieProvider
         )
     }
 
@@ -1614,6 +2263,153 @@ public final class Socks5Server: @unchecked Sendable {
         startListener(port: initialPort, remainingAttempts: 3, completion: completion)
     }
 
+    /// Current and peak buffered client payload bytes across all sessions.
+    public func bufferUsage() -> Socks5BufferUsage {
+        bufferLedger.usage
+    }
+
+    /// Per-destination tally of sessions the shared buffer budget refused, so an undersized
+    /// cap can be told apart from one destination consuming it.
+    public func budgetRefusals() -> Socks5BudgetRefusalReport {
+        bufferLedger.refusals
+    }
+
+    /// Exports per-destination connection-establishment latency percentiles, slowest p99
+    /// first, so operations can see which services are slow through the tunnel.
+    public func dialLatencySnapshot() -> [RelayDialLatencyHeatmap.Entry] {
+        dialLatencyHeatmap.snapshot()
+    }
+
+    /// Exports per-destination classified dial-failure counts, most failures first.
+    public func dialFailureSnapshot() -> [RelayDialFailureCounters.Entry] {
+        dialFailureCounters.snapshot()
+    }
+
+    /// Exports the dial limiter's current queue depths and cumulative admission counters.
+    public func dialQueueStats() -> RelayDialLimiter.Stats {
+        dialLimiter.stats()
+    }
+
+    /// Exports new-flow admission counters: flows admitted and flows reset because a
+    /// per-source or server-wide accept-rate bucket was empty.
+    public func flowAdmissionStats() -> RelayFlowRateLimiter.Stats {
+        flowRateLimiter.stats()
+    }
+
+    /// Count of sessions forcibly aborted by the half-close safety timeout since start,
+    /// each one a socket pair reclaimed from a server that never finished its side.
+    public func halfCloseAbortTotal() -> Int {
+        var count = 0
+        performOnQueue {
+            count = self.halfCloseAbortCount
+        }
+        return count
+    }
+
+    /// Lists every active session with its state-machine phase, buffer occupancy, and
+    /// backpressure flags. Each session is read synchronously on its own queue so one
+    /// entry's fields are mutually consistent.
+    public func flowSnapshot() -> [Socks5FlowInfo] {
+        var sessions: [Socks5Connection] = []
+        performOnQueue {
+            sessions = Array(self.connections.values)
+        }
+        return sessions.map { $0.flowInfo() }
+    }
+
+    /// Injects `data` toward the network on the active flow carrying `flowCookie`, as if
+    /// the client had sent it — protocol probes and keepalives without crafting raw IP
+    /// packets, complementing the existing network-to-client receive path. The payload
+    /// routes through the flow's normal client buffer, so it never interleaves with an
+    /// in-flight client write and the usual per-flow and per-server buffer caps apply.
+    /// - Returns: Whether an active TCP proxy flow with that cookie accepted the payload.
+    @discardableResult
+    public func injectClientPayload(flowCookie: UInt64, data: Data) -> Bool {
+        var target: Socks5Connection?
+        performOnQueue {
+            target = self.connections.values.first { $0.flowCookie == flowCookie }
+        }
+        guard let target else {
+            return false
+        }
+        return target.injectClientPayload(data)
+    }
+
+    /// Pauses or resumes delivery toward the client on the active flow carrying
+    /// `flowCookie`. While paused the flow stops reading from the remote, so transport
+    /// flow control closes the window toward it — precise per-connection backpressure
+    /// when the host's own send queues fill, instead of global buffering or drops.
+    /// - Returns: Whether an active TCP proxy flow with that cookie applied the change.
+    @discardableResult
+    public func setFlowPaused(flowCookie: UInt64, paused: Bool) -> Bool {
+        var target: Socks5Connection?
+        performOnQueue {
+            target = self.connections.values.first { $0.flowCookie == flowCookie }
+        }
+        guard let target else {
+            return false
+        }
+        return target.setFlowPaused(paused)
+    }
+
+    /// Exports the relay's learned per-destination state for host-side persistence.
+    public func persistedStateSnapshot() -> Socks5ServerPersistedState {
+        Socks5ServerPersistedState(
+            dialFailures: dialFailureCache.exportSnapshot(),
+            dialFailureCounts: dialFailureCounters.snapshot()
+        )
+    }
+
+    /// Restores a previously exported state envelope, typically right after construction
+    /// when the extension restarts. Returns whether the envelope was applied; envelopes
+    /// with an unknown schema version are ignored. Suppression lifetimes are honored and
+    /// clamped on import, and anything this session already observed wins over the seed.
+    @discardableResult
+    public func restorePersistedState(_ state: Socks5ServerPersistedState) -> Bool {
+        guard state.schemaVersion == Socks5ServerPersistedState.currentSchemaVersion else {
+            return false
+        }
+        dialFailureCache.importEntries(state.dialFailures)
+        dialFailureCounters.seed(entries: state.dialFailureCounts)
+        return true
+    }
+
+    /// Closes the longest-shaped session other than `requester` to free global shaped budget.
+    /// Decision: a TCP stream cannot drop payload bytes, so "evicting the oldest shaped
+    /// payload" means retiring the session that has held shaped budget the longest; the
+    /// requester is spared so the flow that exposed the pressure keeps its slice.
+    private func evictOldestShapedSession(excluding requester: Socks5Connection) -> Bool {
+        let victim = connections.values
+            .filter { $0 !== requester }
+            .compactMap { session in session.shapedSince.map { (session: session, shapedSince: $0) } }
+            .min { $0.shapedSince < $1.shapedSince }?
+            .session
+        guard let victim else {
+            return false
+        }
+        bufferLedger.recordShapedEviction()
+        victim.stop(reason: .bufferLimitExceeded, message: "shaped-budget-evicted")
+        return true
+    }
+
+    /// Tells the relay the device's network path changed (for example Wi-Fi to cellular).
+    /// Established flows already revalidate themselves through per-connection viability and
+    /// better-path callbacks; this entry point drops the negative dial cache, whose failures
+    /// were observed on the old path and would otherwise suppress reachable destinations.
+    public func notifyPathChanged() {
+        dialFailureCache.removeAll()
+        Task {
+            await logger.log(
+                level: .notice,
+                phase: .relay,
+                category: .relayTCP,
+                component: "Socks5Server",
+                event: "path-changed",
+                message: "Cleared dial failure cache after network path change"
+            )
+        }
+    }
+
     /// Stops listener and all active SOCKS sessions.
     public func stop() {
         performOnQueue {
@@ -1622,6 +2418,7 @@ public final class Socks5Server: @unchecked Sendable {
             let sessions = Array(self.connections.values)
             self.connections.removeAll()
             sessions.forEach { $0.stop() }
+            self.dnsSessionPool?.stop()
         }
     }
 
@@ -1739,6 +2536,28 @@ public final class Socks5Server: @unchecked Sendable {
 
         listener.newConnectionHandler = { [weak self] connection in
             guard let self else { return }
+            let inbound = SocksInboundNWConnectionAdapter(connection)
+            // A runaway client opens flows faster than it can use them; resetting the
+            // connection before any session state is allocated keeps floods cheap.
+            let flowVerdict = self.flowRateLimiter.admitNewFlow(source: inbound.remoteAddressDescription)
+            guard flowVerdict == .admitted else {
+                connection.cancel()
+                Task {
+                    await self.logger.log(
+                        level: .warning,
+                        phase: .relay,
+                        category: .relayTCP,
+                        component: "Socks5Server",
+                        event: "connection-rate-limited",
+                        result: flowVerdict.rawValue,
+                        message: "Reset inbound SOCKS5 connection because the new-flow rate limit is exceeded",
+                        metadata: [
+                            "source": inbound.remoteAddressDescription ?? "unknown"
+                        ]
+                    )
+                }
+                return
+            }
             guard self.connections.count < ServerPolicy.maxConnections else {
                 connection.cancel()
                 Task {
@@ -1760,17 +2579,44 @@ public final class Socks5Server: @unchecked Sendable {
             }
             let connectionQueue = self.makeConnectionQueue()
             let session = Socks5Connection(
-                connection: SocksInboundNWConnectionAdapter(connection),
+                connection: inbound,
                 provider: self.providerFactory(connectionQueue),
                 queue: connectionQueue,
                 mtu: self.mtu,
-                logger: self.logger
+                logger: self.logger,
+                policyEvaluator: self.policyEvaluator,
+                logPolicyRuleTraces: self.logPolicyRuleTraces,
+                upstreamRoutes: self.upstreamRoutes,
+                hostResolvers: self.hostResolvers,
+                dialFailureCache: self.dialFailureCache,
+                dialLatencyHeatmap: self.dialLatencyHeatmap,
+                dialFailureCounters: self.dialFailureCounters,
+                dialLimiter: self.dialLimiter,
+                bogonFilter: self.bogonFilter,
+                loopGuard: self.loopGuard,
+                dnsSessionPool: self.dnsSessionPool,
+                bufferLimits: self.bufferLimits,
+                chunkSizing: self.chunkSizing,
+                bufferLedger: self.bufferLedger,
+                sendTLSAlertOnPolicyBlock: self.sendTLSAlertOnPolicyBlock,
+                sendFailurePolicy: self.sendFailurePolicy,
+                secondaryFlowPredictor: self.secondaryFlowPredictor,
+                flowCookieProvider: self.flowCookieProvider
             )
             session.onClose = { [weak self] in
                 self?.performOnQueue {
                     self?.connections.removeValue(forKey: ObjectIdentifier(connection))
                 }
             }
+            session.onCloseWithReason = { [weak self] reason, _, _ in
+                guard reason == .halfCloseTimeout else { return }
+                self?.performOnQueue {
+                    self?.halfCloseAbortCount += 1
+                }
+            }
+            session.evictOldestShapedSession = { [weak self] requester in
+                self?.evictOldestShapedSession(excluding: requester) ?? false
+            }
             self.connections[ObjectIdentifier(connection)] = session
             session.start()
         }
@@ -2103,20 +2949,56 @@ final class Socks5TCPForwardUDPRelay: @unchecked Sendable {
     }
 }
 
+/// Stable close-reason code surfaced alongside the free-form event text when a SOCKS session ends.
+/// Contract: hosts branch retry/give-up/report decisions on this enum, never on log strings; unknown
+/// future cases should be treated as non-retryable.
+public enum Socks5CloseReason: String, Codable, Sendable, Equatable {
+    case clientFinished
+    case clientCancelled
+    case clientFailed
+    case remoteFinished
+    case remoteFailed
+    case dialFailed
+    /// The dial was suppressed by the negative failure cache after a recent failed dial.
+    case dialSuppressed
+    case requestRejected
+    case protocolError
+    case bufferLimitExceeded
+    /// The flow sat half-closed past the safety timeout and was forcibly aborted so its
+    /// socket pair could be reclaimed.
+    case halfCloseTimeout
+    case sessionTeardown
+}
+
 /// Per-client SOCKS connection state machine.
 /// Invariant: transitions are serialized by callbacks running on `queue`.
 final class Socks5Connection: @unchecked Sendable {
     private enum ConnectionPolicy {
-        static let maxBufferedBytes = 256 * 1024
+        static let policySnippetBytes = 64
+        static let maxOutboundReadBytes = 65_535
+        /// Upper bound on bytes drained while waiting for a blocked flow's ClientHello.
+        static let maxClientHelloProbeBytes = 16 * 1024
+        /// Slices a paced flow's per-second allowance is split into, so shaped bytes reach
+        /// the client spread across the second instead of as one rate-sized burst.
+        static let pacedReadSlicesPerSecond = 8
+        /// Seconds a proxied flow may stay half-closed — client finished and the write side
+        /// toward the server finished — awaiting the server's own EOF before it is forcibly
+        /// aborted. The reverse half-close cannot leak here: a server EOF tears the whole
+        /// session down immediately.
+        static let halfCloseTimeoutSeconds: TimeInterval = 60
     }
 
     private enum State {
         case greeting
         case request
+        /// CONNECT admitted but waiting for a dial-limiter slot; client bytes buffer meanwhile.
+        case dialPending
         case connectingTCP(Socks5TCPOutbound)
         case tcpProxy(Socks5TCPOutbound)
         case udpProxy(Socks5UDPRelayProtocol)
         case udpForward(Socks5TCPForwardUDPRelay)
+        /// Policy-blocked CONNECT being drained far enough to answer with a TLS alert.
+        case blockedTLSDrain
     }
 
     private let logger: StructuredLogger
@@ -2125,9 +3007,32 @@ final class Socks5Connection: @unchecked Sendable {
     private let queue: DispatchQueue
     private let queueSpecificKey = DispatchSpecificKey<UInt8>()
     private let mtu: Int
-    private let udpRelayFactory: (Socks5ConnectionProvider, DispatchQueue, Int, StructuredLogger) throws -> Socks5UDPRelayProtocol
+    private let policyEvaluator: (any RelayPolicyEvaluator)?
+    private let logPolicyRuleTraces: Bool
+    private let upstreamRoutes: RelayUpstreamRoutes
+    private let hostResolvers: RelayHostResolvers
+    private let dialFailureCache: Socks5DialFailureCache
+    private let dialLatencyHeatmap: RelayDialLatencyHeatmap?
+    private let dialFailureCounters: RelayDialFailureCounters?
+    private let dialLimiter: RelayDialLimiter?
+    private let bogonFilter: BogonDestinationFilter?
+    private let loopGuard: TunnelLoopGuard?
+    private let dnsSessionPool: Socks5DNSSessionPool?
+    private let bufferLimits: Socks5BufferLimits
+    private let chunkSizing: Socks5ChunkSizing
+    private let bufferLedger: Socks5BufferLedger
+    private let sendTLSAlertOnPolicyBlock: Bool
+    private let sendFailurePolicy: Socks5SendFailurePolicy
+    private let secondaryFlowPredictor: RelaySecondaryFlowPredictor?
+    private let flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)?
+    private let udpRelayFactory: (Socks5ConnectionProvider, DispatchQueue, Int, StructuredLogger, Socks5DNSSessionPool?) throws -> Socks5UDPRelayProtocol
+
+    /// Per-flow read window autotuned to the client's measured consumption rate.
+    private var receiveWindowTuner: Socks5ReceiveWindowTuner
 
     private var buffer = Data()
+    /// Bytes currently reserved in the shared server ledger for this session's buffer.
+    private var ledgeredBufferBytes = 0
     private var state: State = .greeting
     private var isClosed = false
     private var inboundReceiveArmed = false
@@ -2136,11 +3041,53 @@ final class Socks5Connection: @unchecked Sendable {
     private var outboundFinishInFlight = false
     private var outboundWriteFinished = false
     private var inboundStreamComplete = false
+    /// Forced-abort deadline for half-closed flows; tests shorten it to avoid real waits.
+    var halfCloseTimeoutSeconds: TimeInterval = ConnectionPolicy.halfCloseTimeoutSeconds
     private var inboundSendInFlight = false
+    /// Whether the host paused delivery toward the client; outbound reads stay unarmed
+    /// until resume so transport flow control backs pressure up to the remote.
+    private var isFlowPaused = false
+    /// Whether close should abort the client connection instead of shutting down in
+    /// order, set when the send-failure policy maps the failure to `.reset`.
+    private var abortClientOnClose = false
+    /// Whether a `.silent` send failure is waiting out the half-close deadline; both
+    /// directions stop arming so the client sees nothing until the reap.
+    private var silentClosePending = false
     private var udpForwardReplyInFlight = false
+    /// Whether this session currently holds an in-flight slot in the shared dial limiter.
+    private var holdsDialSlot = false
+    private var shapedReadCapBytes: Int?
+    private var shapedPacingBytesPerSecond: Int?
+    /// When the flow became rate-shaped; eviction under the global shaped cap retires the
+    /// session that has been shaped the longest.
+    private(set) var shapedSince: Date?
+    /// Shaped bytes currently reserved in the shared ledger for this session's undelivered slice.
+    private var ledgeredShapedBytes = 0
+    /// High-water mark of `ledgeredShapedBytes` over the session's lifetime.
+    private var peakLedgeredShapedBytes = 0
+    /// Accumulated pacing delay inserted ahead of outbound re-reads, in milliseconds.
+    private var shapedDelayTotalMilliseconds = 0
+    /// Chunks never forwarded because shaped budget could not be reserved.
+    private var shapedDroppedChunkCount = 0
+    private var pendingClientHelloInspection: RelayPolicyInput?
+    /// Set when this session proxies a recognized ALG control flow (FTP, SIP); relayed
+    /// payloads are then mirrored into the secondary-flow predictor.
+    private var algControlFlow: (host: String, port: UInt16)?
     private var activeTCPDestinationMetadata: [String: String] = [:]
+    /// Opaque host cookie attached when the outbound dial starts; `nil` before the dial or
+    /// when no provider is installed. Echoed in flow telemetry and log metadata until close.
+    private(set) var flowCookie: UInt64?
 
     var onClose: (() -> Void)?
+    /// v2 close callback carrying the stable reason code, the triggering event name, and,
+    /// for dial-failure closes, a retry-after hint in milliseconds derived from the failure
+    /// cache's backoff state. The hint is jittered so clients that failed together do not
+    /// re-dial together; every other close passes `nil`.
+    /// Decision: the legacy `onClose` hook stays for existing call sites; both fire exactly once.
+    var onCloseWithReason: ((Socks5CloseReason, String?, Int?) -> Void)?
+    /// Server hook that closes another shaped session to free global shaped budget; returns
+    /// whether anything was evicted. The requester passes itself so it is never the victim.
+    var evictOldestShapedSession: ((Socks5Connection) -> Bool)?
 
     /// - Parameters:
     ///   - connection: Accepted inbound SOCKS connection.
@@ -2148,6 +3095,34 @@ final class Socks5Connection: @unchecked Sendable {
     ///   - queue: Queue for callback-driven state transitions.
     ///   - mtu: MTU hint passed into UDP relay.
     ///   - logger: Structured logger for connection lifecycle.
+    ///   - policyEvaluator: Optional policy hook consulted before each outbound dial.
+    ///   - logPolicyRuleTraces: Debug mode that logs a rule-by-rule admission trace for each
+    ///     flow when the evaluator is a compiled policy document.
+    ///   - upstreamRoutes: Named upstream proxy transports resolvable by `route` policy verdicts.
+    ///   - hostResolvers: Named resolvers resolvable by `resolver=` policy rule parameters.
+    ///   - dialFailureCache: Negative cache that fails flows fast after recent dial failures.
+    ///   - dialLatencyHeatmap: Optional shared aggregate this session reports dial-to-ready
+    ///     latency into, keyed by destination.
+    ///   - dialFailureCounters: Optional shared counters this session reports classified
+    ///     dial failures into, keyed by destination.
+    ///   - dialLimiter: Optional shared cap on concurrent outbound dials; over-cap CONNECTs
+    ///     wait FIFO for a slot instead of dialing immediately.
+    ///   - bogonFilter: Optional filter rejecting unroutable destination literals before any dial.
+    ///   - loopGuard: Optional guard rejecting flows destined to the tunnel's own addresses.
+    ///   - dnsSessionPool: Optional shared resolver pool handed to UDP relays so port-53
+    ///     traffic multiplexes over a few pooled host dials.
+    ///   - bufferLimits: Per-flow and per-server caps on buffered client payload bytes.
+    ///   - chunkSizing: Min-coalescing and max-chunk bounds applied to outbound reads so
+    ///     writes toward the client are well-sized.
+    ///   - bufferLedger: Shared cross-session ledger; standalone connections get a private one.
+    ///   - sendTLSAlertOnPolicyBlock: When enabled, blocked CONNECTs drain the ClientHello and
+    ///     answer with a fatal TLS alert before closing.
+    ///   - sendFailurePolicy: Mapping from classified mid-flow send failures to the
+    ///     client-facing close behavior.
+    ///   - secondaryFlowPredictor: Optional shared ALG registry; this session feeds it from
+    ///     recognized control flows and consults it before the policy evaluator.
+    ///   - flowCookieProvider: Optional host hook called once at dial time with (host, port,
+    ///     transport); the returned opaque cookie rides in the flow's telemetry and logs.
     ///   - udpRelayFactory: Factory override used by tests.
     init(
         connection: Socks5InboundConnection,
@@ -2155,8 +3130,26 @@ final class Socks5Connection: @unchecked Sendable {
         queue: DispatchQueue,
         mtu: Int,
         logger: StructuredLogger,
-        udpRelayFactory: @escaping (Socks5ConnectionProvider, DispatchQueue, Int, StructuredLogger) throws -> Socks5UDPRelayProtocol = {
-            try Socks5UDPRelay(provider: $0, queue: $1, mtu: $2, logger: $3)
+        policyEvaluator: (any RelayPolicyEvaluator)? = nil,
+        logPolicyRuleTraces: Bool = false,
+        upstreamRoutes: RelayUpstreamRoutes = .none,
+        hostResolvers: RelayHostResolvers = .none,
+        dialFailureCache: Socks5DialFailureCache = Socks5DialFailureCache(),
+        dialLatencyHeatmap: RelayDialLatencyHeatmap? = nil,
+        dialFailureCounters: RelayDialFailureCounters? = nil,
+        dialLimiter: RelayDialLimiter? = nil,
+        bogonFilter: BogonDestinationFilter? = nil,
+        loopGuard: TunnelLoopGuard? = nil,
+        dnsSessionPool: Socks5DNSSessionPool? = nil,
+        bufferLimits: Socks5BufferLimits = .default,
+        chunkSizing: Socks5ChunkSizing = .default,
+        bufferLedger: Socks5BufferLedger? = nil,
+        sendTLSAlertOnPolicyBlock: Bool = false,
+        sendFailurePolicy: Socks5SendFailurePolicy = .default,
+        secondaryFlowPredictor: RelaySecondaryFlowPredictor? = nil,
+        flowCookieProvider: (@Sendable (String, UInt16, String) -> UInt64)? = nil,
+        udpRelayFactory: @escaping (Socks5ConnectionProvider, DispatchQueue, Int, StructuredLogger, Socks5DNSSessionPool?) throws -> Socks5UDPRelayProtocol = {
+            try Socks5UDPRelay(provider: $0, queue: $1, mtu: $2, logger: $3, dnsSessionPool: $4)
         }
     ) {
         self.connection = connection
@@ -2164,6 +3157,28 @@ final class Socks5Connection: @unchecked Sendable {
         self.queue = queue
         self.mtu = mtu
         self.logger = logger
+        self.policyEvaluator = policyEvaluator
+        self.logPolicyRuleTraces = logPolicyRuleTraces
+        self.upstreamRoutes = upstreamRoutes
+        self.hostResolvers = hostResolvers
+        self.dialFailureCache = dialFailureCache
+        self.dialLatencyHeatmap = dialLatencyHeatmap
+        self.dialFailureCounters = dialFailureCounters
+        self.dialLimiter = dialLimiter
+        self.bogonFilter = bogonFilter
+        self.loopGuard = loopGuard
+        self.dnsSessionPool = dnsSessionPool
+        self.bufferLimits = bufferLimits
+        self.chunkSizing = chunkSizing
+        self.receiveWindowTuner = Socks5ReceiveWindowTuner(maxWindowBytes: chunkSizing.maxChunkBytes)
+        self.bufferLedger = bufferLedger ?? Socks5BufferLedger(
+            capacity: bufferLimits.maxBufferedBytesPerServer,
+            shapedCapacity: bufferLimits.maxShapedBytesPerServer
+        )
+        self.sendTLSAlertOnPolicyBlock = sendTLSAlertOnPolicyBlock
+        self.sendFailurePolicy = sendFailurePolicy
+        self.secondaryFlowPredictor = secondaryFlowPredictor
+        self.flowCookieProvider = flowCookieProvider
         self.udpRelayFactory = udpRelayFactory
         self.queue.setSpecific(key: queueSpecificKey, value: 1)
     }
@@ -2185,9 +3200,9 @@ final class Socks5Connection: @unchecked Sendable {
                         message: "SOCKS5 inbound connection failed"
                     )
                 }
-                self.stop()
+                self.stop(reason: .clientFailed, message: "connection-failed")
             case .cancelled:
-                self.stop()
+                self.stop(reason: .clientCancelled, message: "connection-cancelled")
             default:
                 break
             }
@@ -2197,13 +3212,18 @@ final class Socks5Connection: @unchecked Sendable {
     }
 
     /// Idempotently closes this connection and any outbound resources.
-    func stop() {
+    /// - Parameters:
+    ///   - reason: Stable close-reason code passed to the v2 close callback.
+    ///   - message: Optional triggering event name mirroring the structured log event.
+    ///   - retryAfterMilliseconds: Optional re-dial hint passed to the v2 close callback;
+    ///     set by dial-failure closes, `nil` everywhere else.
+    func stop(reason: Socks5CloseReason = .sessionTeardown, message: String? = nil, retryAfterMilliseconds: Int? = nil) {
         runOnQueue {
-            self.stopOnQueue()
+            self.stopOnQueue(reason: reason, message: message, retryAfterMilliseconds: retryAfterMilliseconds)
         }
     }
 
-    private func stopOnQueue() {
+    private func stopOnQueue(reason: Socks5CloseReason, message: String?, retryAfterMilliseconds: Int?) {
         guard !isClosed else { return }
         isClosed = true
         switch state {
@@ -2218,10 +3238,32 @@ final class Socks5Connection: @unchecked Sendable {
         default:
             break
         }
-        connection.cancel()
+        if abortClientOnClose {
+            connection.forceCancel()
+        } else {
+            connection.cancel()
+        }
+        releaseDialSlotIfNeeded()
+        if ledgeredBufferBytes > 0 {
+            bufferLedger.release(ledgeredBufferBytes)
+            ledgeredBufferBytes = 0
+        }
+        if ledgeredShapedBytes > 0 {
+            bufferLedger.releaseShaped(ledgeredShapedBytes)
+            ledgeredShapedBytes = 0
+        }
+        onCloseWithReason?(reason, message, retryAfterMilliseconds)
         onClose?()
     }
 
+    /// Returns this session's dial slot to the shared limiter exactly once, whether the
+    /// dial resolved or the session was torn down while still connecting.
+    private func releaseDialSlotIfNeeded() {
+        guard holdsDialSlot else { return }
+        holdsDialSlot = false
+        dialLimiter?.release()
+    }
+
     private func armInboundReceiveIfNeeded() {
         guard !inboundReceiveArmed, shouldReadInbound else {
             return
@@ -2240,7 +3282,8 @@ final class Socks5Connection: @unchecked Sendable {
                     // no outbound write is in flight, so byte ordering is preserved.
                     if case .tcpProxy(let outbound) = self.state,
                        self.buffer.isEmpty,
-                       !self.outboundWriteInFlight {
+                       !self.outboundWriteInFlight,
+                       self.pendingClientHelloInspection == nil {
                         self.outboundWriteInFlight = true
                         self.forwardToOutbound(data, outbound: outbound)
                     } else {
@@ -2253,7 +3296,7 @@ final class Socks5Connection: @unchecked Sendable {
                 }
                 if let error {
                     self.logInboundReadFailure(error)
-                    self.stop()
+                    self.stop(reason: .clientFailed, message: "inbound-read-failed")
                     return
                 }
                 if isComplete {
@@ -2272,7 +3315,7 @@ final class Socks5Connection: @unchecked Sendable {
     }
 
     private var shouldReadInbound: Bool {
-        guard !inboundStreamComplete else {
+        guard !inboundStreamComplete, !silentClosePending else {
             return false
         }
         switch state {
@@ -2300,7 +3343,7 @@ final class Socks5Connection: @unchecked Sendable {
                         message: "Closing SOCKS5 connection after non-SOCKS5 greeting version"
                     )
                 }
-                stop()
+                stop(reason: .protocolError, message: "malformed-greeting")
                 return
             }
             guard let methods = Socks5Codec.parseGreeting(&buffer) else { return }
@@ -2311,11 +3354,11 @@ final class Socks5Connection: @unchecked Sendable {
                     guard !self.isClosed else { return }
                     if let error {
                         self.logInboundWriteFailure(error, event: "greeting-write-failed", message: "SOCKS5 greeting reply write failed")
-                        self.stop()
+                        self.stop(reason: .clientFailed, message: "greeting-write-failed")
                         return
                     }
                     if method == 0xFF {
-                        self.stop()
+                        self.stop(reason: .protocolError, message: "no-acceptable-auth-method")
                     }
                 }
             })
@@ -2331,7 +3374,7 @@ final class Socks5Connection: @unchecked Sendable {
             }
             guard let request = Socks5Codec.parseRequest(&buffer) else { return }
             handleRequest(request)
-        case .connectingTCP:
+        case .dialPending, .connectingTCP:
             // RFC 1928 requires the server reply before payload forwarding.
             // Any pipelined client bytes are buffered until the outbound channel is ready.
             return
@@ -2340,6 +3383,9 @@ final class Socks5Connection: @unchecked Sendable {
                 finishOutboundWritingIfNeeded(outbound)
                 return
             }
+            guard resolvePendingClientHelloInspection() else {
+                return
+            }
 
             let payload = buffer
             buffer.removeAll(keepingCapacity: false)
@@ -2362,14 +3408,22 @@ final class Socks5Connection: @unchecked Sendable {
                         message: "Closing SOCKS5 TCP-carried UDP stream after invalid frame"
                     )
                 }
-                stop()
+                stop(reason: .protocolError, message: "udp-forward-parse-failed")
                 return
             }
+        case .blockedTLSDrain:
+            guard !buffer.isEmpty else { return }
+            processBlockedTLSDrainBuffer()
         }
     }
 
     private func admitInboundBufferBytes(_ byteCount: Int) -> Bool {
-        let remainingCapacity = max(0, ConnectionPolicy.maxBufferedBytes - buffer.count)
+        // Return drained capacity to the shared ledger before reserving more.
+        if buffer.count < ledgeredBufferBytes {
+            bufferLedger.release(ledgeredBufferBytes - buffer.count)
+            ledgeredBufferBytes = buffer.count
+        }
+        let remainingCapacity = max(0, bufferLimits.maxBufferedBytesPerFlow - buffer.count)
         guard byteCount <= remainingCapacity else {
             Task {
                 await logger.log(
@@ -2383,26 +3437,56 @@ final class Socks5Connection: @unchecked Sendable {
                     metadata: [
                         "buffered_bytes": String(buffer.count),
                         "incoming_bytes": String(byteCount),
-                        "max_buffered_bytes": String(ConnectionPolicy.maxBufferedBytes)
+                        "max_buffered_bytes": String(bufferLimits.maxBufferedBytesPerFlow)
                     ]
                 )
             }
-            stop()
+            stop(reason: .bufferLimitExceeded, message: "inbound-buffer-limit-reached")
             return false
         }
-        return true
-    }
-
-    private func handleRequest(_ request: Socks5Request) {
-        switch request.command {
-        case .connect:
-            startTCPProxy(request)
-        case .udpAssociate:
-            startUDPRelay()
-        case .udpForward:
-            startTCPForwardUDPRelay()
-        case .bind:
-            sendFailure(replyCode: 0x07)
+        guard bufferLedger.reserve(byteCount) else {
+            let usage = bufferLedger.usage
+            let destinationHost = activeTCPDestinationMetadata["destination_host"]
+            bufferLedger.recordRefusal(destination: destinationHost)
+            Task {
+                await logger.log(
+                    level: .warning,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "server-buffer-limit-reached",
+                    result: "closed",
+                    message: "Closing SOCKS5 connection because aggregate buffering exceeded the per-server cap",
+                    metadata: [
+                        "buffered_bytes": String(buffer.count),
+                        "incoming_bytes": String(byteCount),
+                        "server_buffered_bytes": String(usage.bufferedBytes),
+                        "server_peak_buffered_bytes": String(usage.peakBufferedBytes),
+                        "max_server_buffered_bytes": String(bufferLimits.maxBufferedBytesPerServer),
+                        "server_shaped_bytes": String(usage.shapedBytes),
+                        "server_peak_shaped_bytes": String(usage.peakShapedBytes),
+                        "server_shaped_evictions": String(usage.shapedEvictionCount),
+                        "destination_host": destinationHost ?? "unknown"
+                    ]
+                )
+            }
+            stop(reason: .bufferLimitExceeded, message: "server-buffer-limit-reached")
+            return false
+        }
+        ledgeredBufferBytes += byteCount
+        return true
+    }
+
+    private func handleRequest(_ request: Socks5Request) {
+        switch request.command {
+        case .connect:
+            startTCPProxy(request)
+        case .udpAssociate:
+            startUDPRelay()
+        case .udpForward:
+            startTCPForwardUDPRelay()
+        case .bind:
+            sendFailure(replyCode: 0x07)
         }
     }
 
@@ -2413,28 +3497,361 @@ final class Socks5Connection: @unchecked Sendable {
             host = value
         }
 
-        let endpoint = NWHostEndpoint(hostname: host, port: String(request.port))
-        let outbound = provider.makeTCPConnection(to: endpoint, enableTLS: false, tlsParameters: nil, delegate: nil)
+        // ALG pre-authorization: a data flow announced moments ago on a recognized control
+        // flow dials without consulting the policy document, so blocking rules cannot break
+        // an FTP transfer or SIP media that negotiated a dynamic port.
+        var preauthorizedSecondaryFlow = false
+        if let secondaryFlowPredictor, secondaryFlowPredictor.consume(host: host, port: request.port) {
+            preauthorizedSecondaryFlow = true
+            Task {
+                await self.logger.log(
+                    level: .info,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "connect-preauthorized-secondary-flow",
+                    message: "SOCKS5 outbound connect admitted as a control-flow-announced data flow",
+                    metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                )
+            }
+        }
+
+        var routeConfig: ShadowsocksServerConfig?
+        // Outbound name carried in dial telemetry; default flows dial directly.
+        var outboundLabel = "direct"
+        // Hostname the direct dial targets; a named resolver may rewrite it below.
+        var dialHost = host
+        // Resolver name carried in resolve telemetry; default flows use the system resolver.
+        var resolverLabel = "system"
+        // Winning rule's Nagle preference for the outbound socket; nil keeps the platform default.
+        var noDelayPreference: Bool?
+        if let policyEvaluator, !preauthorizedSecondaryFlow {
+            let input = RelayPolicyInput(
+                host: host,
+                port: request.port,
+                transport: "tcp",
+                firstPayloadSnippet: Data(buffer.prefix(ConnectionPolicy.policySnippetBytes)),
+                sourceAddress: connection.remoteAddressDescription
+            )
+            emitPolicyRuleTraceIfEnabled(input)
+            switch policyEvaluator.evaluate(input) {
+            case .allow:
+                break
+            case .route(let tag):
+                switch upstreamRoutes.outbound(forTag: tag) {
+                case .direct:
+                    outboundLabel = tag
+                case .shadowsocks(let config):
+                    routeConfig = config
+                    outboundLabel = tag
+                case .block:
+                    dialFailureCounters?.record(host: host, kind: .policyBlocked)
+                    let metadata = relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                        .merging(["outbound": tag]) { _, new in new }
+                    Task {
+                        await self.logger.log(
+                            level: .notice,
+                            phase: .relay,
+                            category: .relayTCP,
+                            component: "Socks5Connection",
+                            event: "connect-blocked-by-route",
+                            result: "blocked",
+                            message: "SOCKS5 outbound connect routed to a block outbound",
+                            metadata: metadata
+                        )
+                    }
+                    if sendTLSAlertOnPolicyBlock {
+                        startBlockedTLSDrain(metadata: metadata)
+                        return
+                    }
+                    // 0x02: connection not allowed by ruleset.
+                    sendFailure(replyCode: 0x02, closeReason: .requestRejected)
+                    return
+                case nil:
+                    Task {
+                        await self.logger.log(
+                            level: .error,
+                            phase: .relay,
+                            category: .relayTCP,
+                            component: "Socks5Connection",
+                            event: "connect-route-unresolved",
+                            result: "failed",
+                            message: "SOCKS5 route verdict named an outbound with no registry entry",
+                            metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                                .merging(["route_tag": tag]) { _, new in new }
+                        )
+                    }
+                    sendFailure(replyCode: 0x01, closeReason: .requestRejected)
+                    return
+                }
+            case .block:
+                let metadata = relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                Task {
+                    await self.logger.log(
+                        level: .notice,
+                        phase: .relay,
+                        category: .relayTCP,
+                        component: "Socks5Connection",
+                        event: "connect-blocked-by-policy",
+                        result: "blocked",
+                        message: "SOCKS5 outbound connect blocked by policy evaluator",
+                        metadata: metadata
+                    )
+                }
+                dialFailureCounters?.record(host: host, kind: .policyBlocked)
+                if sendTLSAlertOnPolicyBlock {
+                    startBlockedTLSDrain(metadata: metadata)
+                    return
+                }
+                // 0x02: connection not allowed by ruleset.
+                sendFailure(replyCode: 0x02, closeReason: .requestRejected)
+                return
+            case .shape(let maxBurstBytes, let pacingBytesPerSecond):
+                shapedReadCapBytes = min(max(1, maxBurstBytes), ConnectionPolicy.maxOutboundReadBytes)
+                shapedPacingBytesPerSecond = pacingBytesPerSecond.map { max(1, $0) }
+                shapedSince = Date()
+            }
+            if let tag = policyEvaluator.resolverTag(input) {
+                switch hostResolvers.resolver(forTag: tag) {
+                case .system:
+                    resolverLabel = tag
+                case .custom(let resolver):
+                    guard let resolved = resolver.resolve(host: host) else {
+                        Task {
+                            await self.logger.log(
+                                level: .error,
+                                phase: .relay,
+                                category: .relayTCP,
+                                component: "Socks5Connection",
+                                event: "connect-resolve-failed",
+                                result: "failed",
+                                message: "SOCKS5 connect failed because the selected resolver had no answer",
+                                metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                                    .merging(["resolver": tag]) { _, new in new }
+                            )
+                        }
+                        dialFailureCounters?.record(host: host, kind: .dnsFailure)
+                        // 0x04: host unreachable.
+                        sendFailure(replyCode: 0x04, closeReason: .dialFailed)
+                        return
+                    }
+                    dialHost = resolved
+                    resolverLabel = tag
+                case nil:
+                    Task {
+                        await self.logger.log(
+                            level: .error,
+                            phase: .relay,
+                            category: .relayTCP,
+                            component: "Socks5Connection",
+                            event: "connect-resolver-unresolved",
+                            result: "failed",
+                            message: "SOCKS5 policy rule named a resolver with no registry entry",
+                            metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                                .merging(["resolver_tag": tag]) { _, new in new }
+                        )
+                    }
+                    sendFailure(replyCode: 0x01, closeReason: .requestRejected)
+                    return
+                }
+            }
+            noDelayPreference = policyEvaluator.noDelay(input)
+            if policyEvaluator.shouldInspectClientHello(input) {
+                pendingClientHelloInspection = input
+            }
+        }
+        // Test-only escape hatch: when no named resolver rewrote the dial, a
+        // process-global override may answer in place of the system resolver.
+        if dialHost == host, let overridden = RelaySystemResolverOverride.shared.resolve(host: dialHost) {
+            dialHost = overridden
+            resolverLabel = "system-override"
+        }
+        if secondaryFlowPredictor != nil, RelaySecondaryFlowPredictor.isRecognizedControlPort(request.port) {
+            algControlFlow = (host: host, port: request.port)
+        }
+
+        if let loopGuard, loopGuard.isSelfDestination(host: dialHost) {
+            let droppedFlows = loopGuard.droppedFlows()
+            Task {
+                await self.logger.log(
+                    level: .warning,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "connect-rejected-self-traffic",
+                    result: "rejected",
+                    message: "SOCKS5 connect rejected because the destination is the tunnel's own address; a route is looping tunnel traffic back into the TUN",
+                    metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                        .merging(["loop_drop_count": String(droppedFlows)]) { _, new in new }
+                )
+            }
+            // 0x02: connection not allowed by ruleset.
+            sendFailure(replyCode: 0x02, closeReason: .requestRejected)
+            return
+        }
+
+        // Checked after policy so a named resolver's rewrite is classified, not just the literal.
+        if let bogonFilter, let prefix = bogonFilter.rejectionPrefix(forHost: dialHost) {
+            let counts = bogonFilter.dropCounts()
+            Task {
+                await self.logger.log(
+                    level: .notice,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "connect-rejected-bogon-destination",
+                    result: "rejected",
+                    message: "SOCKS5 connect rejected because the destination sits in a reserved, unroutable prefix",
+                    metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                        .merging([
+                            "reserved_prefix": prefix,
+                            "bogon_drop_count_ipv4": String(counts.ipv4Count),
+                            "bogon_drop_count_ipv6": String(counts.ipv6Count)
+                        ]) { _, new in new }
+                )
+            }
+            // 0x04: host unreachable.
+            sendFailure(replyCode: 0x04, closeReason: .requestRejected)
+            return
+        }
+
+        if dialFailureCache.isSuppressed(host: host, port: request.port) {
+            Task {
+                await self.logger.log(
+                    level: .notice,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "connect-suppressed-by-failure-cache",
+                    result: "failed",
+                    message: "SOCKS5 connect failed fast because the destination recently failed to dial",
+                    metadata: relayDestinationMetadata(host: host, port: String(request.port), transport: "tcp")
+                )
+            }
+            sendFailure(
+                replyCode: 0x05,
+                closeReason: .dialSuppressed,
+                retryAfterMilliseconds: dialFailureCache.retryAfterMilliseconds(host: host, port: request.port)
+            )
+            return
+        }
+
+        guard let dialLimiter else {
+            startOutboundDial(
+                host: host,
+                dialHost: dialHost,
+                request: request,
+                routeConfig: routeConfig,
+                noDelayPreference: noDelayPreference,
+                outboundLabel: outboundLabel,
+                resolverLabel: resolverLabel
+            )
+            return
+        }
+        // Storm smoothing: the whole dial, including NWConnection creation, waits for an
+        // in-flight slot so a burst of CONNECTs cannot hit the platform connection layer
+        // all at once. Client bytes pipelined meanwhile stay buffered.
+        state = .dialPending
+        dialLimiter.admit { [weak self] in
+            guard let self else {
+                dialLimiter.release()
+                return
+            }
+            self.runOnQueue {
+                guard !self.isClosed else {
+                    dialLimiter.release()
+                    return
+                }
+                self.holdsDialSlot = true
+                self.startOutboundDial(
+                    host: host,
+                    dialHost: dialHost,
+                    request: request,
+                    routeConfig: routeConfig,
+                    noDelayPreference: noDelayPreference,
+                    outboundLabel: outboundLabel,
+                    resolverLabel: resolverLabel
+                )
+            }
+        }
+    }
+
+    /// Creates the outbound channel and drives it to ready; split from `startTCPProxy` so
+    /// the dial limiter can defer the whole creation, not just the readiness wait.
+    private func startOutboundDial(
+        host: String,
+        dialHost: String,
+        request: Socks5Request,
+        routeConfig: ShadowsocksServerConfig?,
+        noDelayPreference: Bool?,
+        outboundLabel: String,
+        resolverLabel: String
+    ) {
+        // Routed flows dial the proxy server; the destination rides in the encrypted header.
+        let endpoint = routeConfig.map { NWHostEndpoint(hostname: $0.host, port: String($0.port)) }
+            ?? NWHostEndpoint(hostname: dialHost, port: String(request.port))
+        let dialed = provider.makeTCPConnection(
+            to: endpoint,
+            enableTLS: false,
+            tlsParameters: nil,
+            delegate: nil,
+            noDelay: noDelayPreference
+        )
+        let outbound: Socks5TCPOutbound
+        if let routeConfig {
+            guard let wrapped = ShadowsocksTCPOutbound(
+                inner: dialed,
+                config: routeConfig,
+                destination: request.address,
+                destinationPort: request.port
+            ) else {
+                dialed.cancel()
+                sendFailure(replyCode: 0x01, closeReason: .requestRejected)
+                return
+            }
+            outbound = wrapped
+        } else {
+            outbound = dialed
+        }
+        // Cookie attaches once the dial actually starts, so rejected and suppressed
+        // CONNECTs never consume a host-side identity.
+        if flowCookie == nil, let flowCookieProvider {
+            flowCookie = flowCookieProvider(host, request.port, "tcp")
+        }
         activeTCPDestinationMetadata = relayDestinationMetadata(
             host: host,
             port: String(request.port),
             transport: "tcp"
         )
+        activeTCPDestinationMetadata["outbound"] = outboundLabel
+        activeTCPDestinationMetadata["resolver"] = resolverLabel
+        if dialHost != host {
+            activeTCPDestinationMetadata["resolved_host"] = dialHost
+        }
+        if let flowCookie {
+            activeTCPDestinationMetadata["flow_cookie"] = String(flowCookie)
+        }
 
         state = .connectingTCP(outbound)
+        let dialStartedAt = Date()
         outbound.waitUntilReady { [weak self] result in
             guard let self else { return }
             self.runOnQueue {
+                self.releaseDialSlotIfNeeded()
                 guard !self.isClosed else { return }
                 switch result {
                 case .success:
+                    self.dialFailureCache.recordSuccess(host: host, port: request.port)
+                    if let latencyMs = elapsedMilliseconds(since: dialStartedAt) {
+                        self.dialLatencyHeatmap?.record(host: host, milliseconds: latencyMs)
+                    }
                     guard case .connectingTCP(let activeOutbound) = self.state,
                           activeOutbound === outbound else {
                         outbound.cancel()
                         return
                     }
                     guard let reply = Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0) else {
-                        self.stop()
+                        self.stop(reason: .protocolError, message: "reply-encode-failed")
                         return
                     }
                     self.state = .tcpProxy(outbound)
@@ -2450,7 +3867,7 @@ final class Socks5Connection: @unchecked Sendable {
                                         event: "connect-reply-write-failed",
                                         message: "SOCKS5 connect success reply write failed"
                                     )
-                                    self.stop()
+                                    self.stop(reason: .clientFailed, message: "connect-reply-write-failed")
                                     return
                                 }
                                 self.armOutboundReadIfNeeded(outbound)
@@ -2460,6 +3877,8 @@ final class Socks5Connection: @unchecked Sendable {
                         }
                     )
                 case .failure(let error):
+                    self.dialFailureCache.recordFailure(host: host, port: request.port)
+                    self.dialFailureCounters?.record(host: host, reason: error.localizedDescription)
                     Task {
                         await self.logger.log(
                             level: .error,
@@ -2473,18 +3892,207 @@ final class Socks5Connection: @unchecked Sendable {
                                 "destination_host": host,
                                 "destination_port": String(request.port),
                                 "destination_host_kind": endpointHostKind(host),
-                                "destination_transport": "tcp"
+                                "destination_transport": "tcp",
+                                "outbound": outboundLabel,
+                                "resolver": resolverLabel
                             ]
                         )
                     }
-                    self.sendFailure(replyCode: 0x05)
+                    self.sendFailure(
+                        replyCode: 0x05,
+                        closeReason: .dialFailed,
+                        retryAfterMilliseconds: self.dialFailureCache.retryAfterMilliseconds(host: host, port: request.port)
+                    )
                 }
             }
         }
     }
 
+    /// Accepts a policy-blocked CONNECT so the client's first TLS flight can be read.
+    /// Decision: the success reply here is a deliberate lie — the outbound dial never happens.
+    /// Draining the ClientHello lets the relay answer with a fatal `access_denied` alert, which
+    /// clients report as an explicit refusal instead of the opaque error a mid-handshake reset
+    /// produces. Streams that turn out not to be TLS are reset without an alert.
+    private func startBlockedTLSDrain(metadata: [String: String]) {
+        guard let reply = Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0) else {
+            stop(reason: .protocolError, message: "reply-encode-failed")
+            return
+        }
+        activeTCPDestinationMetadata = metadata
+        state = .blockedTLSDrain
+        connection.send(
+            content: reply,
+            completion: .contentProcessed { [weak self] error in
+                guard let self else { return }
+                self.runOnQueue {
+                    guard !self.isClosed else { return }
+                    if let error {
+                        self.logInboundWriteFailure(
+                            error,
+                            event: "blocked-tls-reply-write-failed",
+                            message: "SOCKS5 blocked-connect success reply write failed"
+                        )
+                        self.stop(reason: .clientFailed, message: "blocked-tls-reply-write-failed")
+                        return
+                    }
+                    self.processBuffer()
+                    self.armInboundReceiveIfNeeded()
+                }
+            }
+        )
+    }
+
+    private func processBlockedTLSDrainBuffer() {
+        switch TLSHandshakeReset.parseClientHello(buffer) {
+        case .needsMoreData:
+            guard buffer.count <= ConnectionPolicy.maxClientHelloProbeBytes else {
+                stop(reason: .requestRejected, message: "blocked-tls-probe-overflow")
+                return
+            }
+        case .notTLS:
+            stop(reason: .requestRejected, message: "blocked-connect-not-tls")
+        case .clientHello(let serverName, _):
+            var metadata = activeTCPDestinationMetadata
+            if let serverName {
+                metadata["tls_server_name"] = serverName
+            }
+            Task {
+                await self.logger.log(
+                    level: .notice,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "blocked-tls-alert-sent",
+                    result: "blocked",
+                    message: "SOCKS5 blocked TLS flow answered with fatal access_denied alert",
+                    metadata: metadata
+                )
+            }
+            connection.send(
+                content: TLSHandshakeReset.makeFatalAlert(.accessDenied),
+                completion: .contentProcessed { [weak self] _ in
+                    guard let self else { return }
+                    self.runOnQueue {
+                        guard !self.isClosed else { return }
+                        self.stop(reason: .requestRejected, message: "blocked-tls-alert-sent")
+                    }
+                }
+            )
+        }
+    }
+
+    /// Returns whether buffered client bytes may be forwarded to the outbound connection.
+    /// Decision: `ech` and `ja3:` policy rules cannot fire at connect time, so when the evaluator
+    /// asks for inspection the relay holds the client's first bytes until the ClientHello parses,
+    /// then re-evaluates with `echDetected` and the JA3 fingerprint set. Anything that does not
+    /// block on re-evaluation — non-TLS streams, oversized pre-TLS chatter, allowed hellos —
+    /// resumes forwarding unchanged.
+    private func resolvePendingClientHelloInspection() -> Bool {
+        guard let input = pendingClientHelloInspection else {
+            return true
+        }
+        switch TLSHandshakeReset.parseClientHello(buffer) {
+        case .needsMoreData:
+            guard buffer.count <= ConnectionPolicy.maxClientHelloProbeBytes else {
+                pendingClientHelloInspection = nil
+                return true
+            }
+            return false
+        case .notTLS:
+            pendingClientHelloInspection = nil
+            return true
+        case .clientHello(let serverName, let hasECH):
+            pendingClientHelloInspection = nil
+            guard let policyEvaluator else {
+                return true
+            }
+            let fingerprint = TLSFingerprinter.fingerprint(record: buffer, transport: .tcp)
+            let reevaluation = RelayPolicyInput(
+                host: input.host,
+                port: input.port,
+                transport: input.transport,
+                firstPayloadSnippet: Data(buffer.prefix(ConnectionPolicy.policySnippetBytes)),
+                echDetected: hasECH,
+                ja3: fingerprint?.ja3,
+                sourceAddress: input.sourceAddress
+            )
+            emitPolicyRuleTraceIfEnabled(reevaluation)
+            guard case .block = policyEvaluator.evaluate(reevaluation) else {
+                return true
+            }
+            var metadata = activeTCPDestinationMetadata
+            if let serverName {
+                metadata["tls_server_name"] = serverName
+            }
+            metadata["ech"] = hasECH ? "true" : "false"
+            if let fingerprint {
+                metadata["ja3"] = fingerprint.ja3
+            }
+            Task {
+                await self.logger.log(
+                    level: .notice,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "connect-blocked-after-inspection",
+                    result: "blocked",
+                    message: "SOCKS5 flow blocked after ClientHello inspection",
+                    metadata: metadata
+                )
+            }
+            if sendTLSAlertOnPolicyBlock {
+                connection.send(
+                    content: TLSHandshakeReset.makeFatalAlert(.accessDenied),
+                    completion: .contentProcessed { [weak self] _ in
+                        guard let self else { return }
+                        self.runOnQueue {
+                            guard !self.isClosed else { return }
+                            self.stop(reason: .requestRejected, message: "connect-blocked-after-inspection")
+                        }
+                    }
+                )
+            } else {
+                stop(reason: .requestRejected, message: "connect-blocked-after-inspection")
+            }
+            return false
+        }
+    }
+
+    /// Logs the rule-by-rule admission trace when trace mode is on and the installed
+    /// evaluator is a compiled policy document. Host-defined evaluators expose no rule
+    /// list, so flows through them emit nothing. Emission goes through the logger's
+    /// hot-path entry point so the rule walk and metadata formatting only run for flows
+    /// the level gate and the category's sampling ratio actually keep.
+    private func emitPolicyRuleTraceIfEnabled(_ input: RelayPolicyInput) {
+        guard logPolicyRuleTraces, let compiled = policyEvaluator as? CompiledRelayPolicy else {
+            return
+        }
+        logger.logHotPath(
+            level: .debug,
+            phase: .relay,
+            category: .relayTCP,
+            component: "Socks5Connection",
+            event: "policy-rule-trace",
+            message: "SOCKS5 policy admission rule trace",
+            metadata: Self.policyRuleTraceMetadata(compiled: compiled, input: input)
+        )
+    }
+
+    private static func policyRuleTraceMetadata(
+        compiled: CompiledRelayPolicy,
+        input: RelayPolicyInput
+    ) -> [String: String] {
+        let trace = compiled.trace(input)
+        return relayDestinationMetadata(host: input.host, port: String(input.port), transport: input.transport)
+            .merging([
+                "normalized_host": trace.normalizedHost,
+                "host_source": trace.hostSource.rawValue,
+                "rule_trace": trace.summary
+            ]) { _, new in new }
+    }
+
     private func armOutboundReadIfNeeded(_ outbound: Socks5TCPOutbound) {
-        guard !outboundReadArmed, !inboundSendInFlight else {
+        guard !outboundReadArmed, !inboundSendInFlight, !isFlowPaused, !silentClosePending else {
             return
         }
         guard case .tcpProxy(let activeOutbound) = state,
@@ -2494,7 +4102,11 @@ final class Socks5Connection: @unchecked Sendable {
         }
 
         outboundReadArmed = true
-        outbound.readMinimumLength(1, maximumLength: 65_535) { [weak self] data, error in
+        let maximumLength = outboundReadCapBytes
+        outbound.readMinimumLength(
+            min(chunkSizing.minCoalescedReadBytes, maximumLength),
+            maximumLength: maximumLength
+        ) { [weak self] data, error in
             guard let self else { return }
             self.runOnQueue {
                 guard !self.isClosed else { return }
@@ -2505,10 +4117,13 @@ final class Socks5Connection: @unchecked Sendable {
                     return
                 } else if let error {
                     self.logOutboundReadError(error)
-                    self.stop()
+                    self.stop(
+                        reason: Self.isBenignOutboundReadClose(error) ? .remoteFinished : .remoteFailed,
+                        message: "outbound-read-failed"
+                    )
                     return
                 } else if data == nil {
-                    self.stop()
+                    self.stop(reason: .remoteFinished, message: "outbound-read-eof")
                     return
                 }
 
@@ -2517,6 +4132,41 @@ final class Socks5Connection: @unchecked Sendable {
         }
     }
 
+    /// Per-read ceiling for the active flow. Paced flows read in slices well below the burst
+    /// cap so forwarded bytes spread across the second instead of arriving as one rate-sized
+    /// burst per read cycle, and the autotuned receive window clamps every variant so a slow
+    /// client shrinks reads from the remote instead of growing a queued backlog.
+    private var outboundReadCapBytes: Int {
+        let tunedWindow = receiveWindowTuner.windowBytes
+        guard let cap = shapedReadCapBytes else {
+            return min(ConnectionPolicy.maxOutboundReadBytes, chunkSizing.maxChunkBytes, tunedWindow)
+        }
+        guard let rate = shapedPacingBytesPerSecond else {
+            return min(cap, chunkSizing.maxChunkBytes, tunedWindow)
+        }
+        return min(
+            cap, chunkSizing.maxChunkBytes, tunedWindow,
+            max(1, rate / ConnectionPolicy.pacedReadSlicesPerSecond)
+        )
+    }
+
+    /// Re-arms the outbound read after a forwarded chunk, inserting the pacing delay the chunk
+    /// earned when the flow is rate-paced. The delay is proportional to bytes forwarded, so the
+    /// flow averages the configured rate with evenly spaced slices rather than alternating full
+    /// bursts and idle gaps.
+    private func scheduleOutboundReadAfterForwarding(byteCount: Int, outbound: Socks5TCPOutbound) {
+        guard let rate = shapedPacingBytesPerSecond, byteCount > 0 else {
+            armOutboundReadIfNeeded(outbound)
+            return
+        }
+        let delay = TimeInterval(byteCount) / TimeInterval(rate)
+        shapedDelayTotalMilliseconds += Int((delay * 1_000).rounded())
+        queue.asyncAfter(deadline: .now() + delay) { [weak self] in
+            guard let self, !self.isClosed else { return }
+            self.armOutboundReadIfNeeded(outbound)
+        }
+    }
+
     private func logOutboundReadError(_ error: any Error) {
         let benignRemoteClose = Self.isBenignOutboundReadClose(error)
         let metadata = activeTCPDestinationMetadata
@@ -2552,24 +4202,21 @@ final class Socks5Connection: @unchecked Sendable {
     }
 
     private func forwardToOutbound(_ data: Data, outbound: Socks5TCPOutbound) {
+        if let algControlFlow, let secondaryFlowPredictor {
+            secondaryFlowPredictor.observeControlPayload(
+                controlHost: algControlFlow.host,
+                controlPort: algControlFlow.port,
+                direction: .clientToServer,
+                payload: data
+            )
+        }
         outbound.write(data) { [weak self] error in
             guard let self else { return }
             self.runOnQueue {
                 guard !self.isClosed else { return }
                 self.outboundWriteInFlight = false
                 if let error {
-                    Task {
-                        await self.logger.log(
-                            level: .error,
-                            phase: .relay,
-                            category: .relayTCP,
-                            component: "Socks5Connection",
-                            event: "outbound-write-failed",
-                            errorCode: error.localizedDescription,
-                            message: "SOCKS5 outbound write failed"
-                        )
-                    }
-                    self.stop()
+                    self.handleOutboundWriteFailure(error)
                     return
                 }
 
@@ -2580,6 +4227,49 @@ final class Socks5Connection: @unchecked Sendable {
         }
     }
 
+    /// Applies the send-failure policy to one failed write toward the remote: resets
+    /// abort the client without an orderly shutdown, closes tear down cleanly, and
+    /// silent failures park the flow until the half-close deadline reaps it.
+    private func handleOutboundWriteFailure(_ error: any Error) {
+        let reason = error.localizedDescription
+        let behavior = sendFailurePolicy.behavior(forReason: reason)
+        Task {
+            await logger.log(
+                level: .error,
+                phase: .relay,
+                category: .relayTCP,
+                component: "Socks5Connection",
+                event: "outbound-write-failed",
+                errorCode: reason,
+                message: "SOCKS5 outbound write failed",
+                metadata: [
+                    "failure_kind": Socks5SendFailureKind.classify(reason: reason).rawValue,
+                    "close_behavior": behavior.rawValue
+                ]
+            )
+        }
+        switch behavior {
+        case .close:
+            stop(reason: .remoteFailed, message: "outbound-write-failed")
+        case .reset:
+            abortClientOnClose = true
+            stop(reason: .remoteFailed, message: "outbound-write-failed")
+        case .silent:
+            guard !silentClosePending else {
+                return
+            }
+            silentClosePending = true
+            queue.asyncAfter(deadline: .now() + halfCloseTimeoutSeconds) { [weak self] in
+                guard let self, !self.isClosed else { return }
+                self.stopOnQueue(
+                    reason: .remoteFailed,
+                    message: "outbound-write-failed-silent",
+                    retryAfterMilliseconds: nil
+                )
+            }
+        }
+    }
+
     private func handleInboundStreamComplete() {
         inboundStreamComplete = true
         switch state {
@@ -2588,7 +4278,7 @@ final class Socks5Connection: @unchecked Sendable {
         case .tcpProxy(let outbound):
             finishOutboundWritingIfNeeded(outbound)
         default:
-            stop()
+            stop(reason: .clientFinished, message: "inbound-stream-complete")
         }
     }
 
@@ -2625,23 +4315,66 @@ final class Socks5Connection: @unchecked Sendable {
                             message: "SOCKS5 outbound TCP write-side finish failed"
                         )
                     }
-                    self.stop()
+                    self.stop(reason: .remoteFailed, message: "outbound-finish-write-failed")
                     return
                 }
 
                 self.outboundWriteFinished = true
+                self.scheduleHalfCloseAbort()
                 self.armOutboundReadIfNeeded(outbound)
             }
         }
     }
 
+    /// Arms the forced-abort deadline once a flow is fully half-closed. Without it, a
+    /// server that never finishes its side after the client's FIN would hold this
+    /// session — and its socket pair — until tunnel teardown.
+    private func scheduleHalfCloseAbort() {
+        queue.asyncAfter(deadline: .now() + halfCloseTimeoutSeconds) { [weak self] in
+            guard let self, !self.isClosed, self.outboundWriteFinished else { return }
+            let metadata = self.activeTCPDestinationMetadata
+            Task {
+                await self.logger.log(
+                    level: .warning,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "half-close-timeout",
+                    result: "closed",
+                    message: "Forcibly aborted a half-closed SOCKS5 flow whose server never finished",
+                    metadata: metadata
+                )
+            }
+            self.stopOnQueue(reason: .halfCloseTimeout, message: "half-close-timeout", retryAfterMilliseconds: nil)
+        }
+    }
+
     private func forwardToInbound(_ data: Data, outbound: Socks5TCPOutbound) {
+        if let algControlFlow, let secondaryFlowPredictor {
+            secondaryFlowPredictor.observeControlPayload(
+                controlHost: algControlFlow.host,
+                controlPort: algControlFlow.port,
+                direction: .serverToClient,
+                payload: data
+            )
+        }
+        let byteCount = data.count
+        if shapedSince != nil, !reserveShapedBytes(byteCount) {
+            shapedDroppedChunkCount += 1
+            return
+        }
         inboundSendInFlight = true
+        let sendStartedAt = Date()
         connection.send(content: data, completion: .contentProcessed { [weak self] error in
             guard let self else { return }
             self.runOnQueue {
                 guard !self.isClosed else { return }
                 self.inboundSendInFlight = false
+                self.receiveWindowTuner.recordDelivery(
+                    byteCount: byteCount,
+                    seconds: Date().timeIntervalSince(sendStartedAt)
+                )
+                self.releaseShapedBytes(byteCount)
                 if let error {
                     Task {
                         await self.logger.log(
@@ -2654,15 +4387,60 @@ final class Socks5Connection: @unchecked Sendable {
                             message: "SOCKS5 inbound write failed"
                         )
                     }
-                    self.stop()
+                    self.stop(reason: .clientFailed, message: "inbound-write-failed")
                     return
                 }
 
-                self.armOutboundReadIfNeeded(outbound)
+                self.scheduleOutboundReadAfterForwarding(byteCount: byteCount, outbound: outbound)
             }
         })
     }
 
+    /// Reserves global shaped budget for one undelivered slice, evicting the longest-shaped
+    /// other session while the ledger is full. Returns `false` after closing this session
+    /// when no budget can be freed: the global cap is a memory bound, so the newest slice is
+    /// not allowed to push aggregate shaped usage past it.
+    private func reserveShapedBytes(_ byteCount: Int) -> Bool {
+        var reserved = bufferLedger.reserveShaped(byteCount)
+        while !reserved, evictOldestShapedSession?(self) == true {
+            reserved = bufferLedger.reserveShaped(byteCount)
+        }
+        if !reserved {
+            bufferLedger.recordShapedEviction()
+            let destinationHost = activeTCPDestinationMetadata["destination_host"]
+            bufferLedger.recordRefusal(destination: destinationHost)
+            Task {
+                await logger.log(
+                    level: .warning,
+                    phase: .relay,
+                    category: .relayTCP,
+                    component: "Socks5Connection",
+                    event: "shaped-budget-exceeded",
+                    message: "SOCKS5 shaped flow closed at the global shaped-bytes cap",
+                    metadata: [
+                        "slice_bytes": String(byteCount),
+                        "destination_host": destinationHost ?? "unknown"
+                    ]
+                )
+            }
+            stop(reason: .bufferLimitExceeded, message: "shaped-budget-exceeded")
+        }
+        if reserved {
+            ledgeredShapedBytes += byteCount
+            peakLedgeredShapedBytes = max(peakLedgeredShapedBytes, ledgeredShapedBytes)
+        }
+        return reserved
+    }
+
+    private func releaseShapedBytes(_ byteCount: Int) {
+        let release = min(byteCount, ledgeredShapedBytes)
+        guard release > 0 else {
+            return
+        }
+        bufferLedger.releaseShaped(release)
+        ledgeredShapedBytes -= release
+    }
+
     private func logInboundReadFailure(_ error: Error) {
         Task {
             await logger.log(
@@ -2699,13 +4477,112 @@ final class Socks5Connection: @unchecked Sendable {
         }
     }
 
+    /// Injects host-crafted payload toward the network as if the client had sent it.
+    /// Contract: only an established TCP proxy flow accepts it; the payload takes the
+    /// same forward path as client bytes, including buffering behind an in-flight
+    /// outbound write and the shared buffer ledger's caps.
+    func injectClientPayload(_ data: Data) -> Bool {
+        if DispatchQueue.getSpecific(key: queueSpecificKey) != nil {
+            return injectClientPayloadOnQueue(data)
+        }
+        return queue.sync { injectClientPayloadOnQueue(data) }
+    }
+
+    private func injectClientPayloadOnQueue(_ data: Data) -> Bool {
+        guard !isClosed, !data.isEmpty, case .tcpProxy(let outbound) = state else {
+            return false
+        }
+        if buffer.isEmpty, !outboundWriteInFlight, pendingClientHelloInspection == nil {
+            outboundWriteInFlight = true
+            forwardToOutbound(data, outbound: outbound)
+            return true
+        }
+        guard admitInboundBufferBytes(data.count) else {
+            return false
+        }
+        buffer.append(data)
+        processBuffer()
+        return true
+    }
+
+    /// Pauses or resumes delivery toward the client on this flow.
+    /// Contract: pausing stops re-arming outbound reads — a chunk already in flight still
+    /// forwards, then remote bytes back up in the transport and TCP flow control closes
+    /// the window toward the remote instead of this session buffering or dropping.
+    func setFlowPaused(_ paused: Bool) -> Bool {
+        if DispatchQueue.getSpecific(key: queueSpecificKey) != nil {
+            return setFlowPausedOnQueue(paused)
+        }
+        return queue.sync { setFlowPausedOnQueue(paused) }
+    }
+
+    private func setFlowPausedOnQueue(_ paused: Bool) -> Bool {
+        guard !isClosed, case .tcpProxy(let outbound) = state else {
+            return false
+        }
+        guard paused != isFlowPaused else {
+            return true
+        }
+        isFlowPaused = paused
+        if !paused {
+            armOutboundReadIfNeeded(outbound)
+        }
+        return true
+    }
+
+    /// Point-in-time debug view of this session for `Socks5Server.flowSnapshot()`.
+    /// Contract: reads synchronously on the session queue so the fields are consistent.
+    func flowInfo() -> Socks5FlowInfo {
+        if DispatchQueue.getSpecific(key: queueSpecificKey) != nil {
+            return makeFlowInfo()
+        }
+        return queue.sync { makeFlowInfo() }
+    }
+
+    private func makeFlowInfo() -> Socks5FlowInfo {
+        let stateName: String
+        switch state {
+        case .greeting:
+            stateName = "greeting"
+        case .request:
+            stateName = "request"
+        case .dialPending:
+            stateName = "dial-pending"
+        case .connectingTCP:
+            stateName = "connecting-tcp"
+        case .tcpProxy:
+            stateName = "tcp-proxy"
+        case .udpProxy:
+            stateName = "udp-proxy"
+        case .udpForward:
+            stateName = "udp-forward"
+        case .blockedTLSDrain:
+            stateName = "blocked-tls-drain"
+        }
+        return Socks5FlowInfo(
+            state: stateName,
+            destinationHost: activeTCPDestinationMetadata["destination_host"],
+            destinationPort: activeTCPDestinationMetadata["destination_port"],
+            bufferedClientBytes: buffer.count,
+            undeliveredShapedBytes: ledgeredShapedBytes,
+            isRateShaped: shapedSince != nil,
+            shapedDelayTotalMilliseconds: shapedDelayTotalMilliseconds,
+            peakUndeliveredShapedBytes: peakLedgeredShapedBytes,
+            shapedDroppedChunkCount: shapedDroppedChunkCount,
+            receiveWindowBytes: receiveWindowTuner.windowBytes,
+            isFlowPaused: isFlowPaused,
+            isAwaitingClientDrain: inboundSendInFlight,
+            flowCookie: flowCookie
+        )
+    }
+
     private func startUDPRelay() {
         do {
-            let relay = try udpRelayFactory(provider, queue, mtu, logger)
+            let relay = try udpRelayFactory(provider, queue, mtu, logger, dnsSessionPool)
             relay.start()
             guard let reply = Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("127.0.0.1"), bindPort: relay.port) else {
                 relay.stop()
-                stop()
+                stop(reason: .protocolError, message: "reply-encode-failed")
                 return
             }
             state = .udpProxy(relay)
@@ -2720,7 +4597,7 @@ final class Socks5Connection: @unchecked Sendable {
                             event: "udp-associate-reply-write-failed",
                             message: "SOCKS5 UDP ASSOCIATE success reply write failed"
                         )
-                        self.stop()
+                        self.stop(reason: .clientFailed, message: "udp-associate-reply-write-failed")
                     }
                 }
             )
@@ -2758,7 +4635,7 @@ final class Socks5Connection: @unchecked Sendable {
                                 event: "udp-forward-write-failed",
                                 message: "SOCKS5 TCP-carried UDP response write failed"
                             )
-                            self.stop()
+                            self.stop(reason: .clientFailed, message: "udp-forward-write-failed")
                         }
                     })
                 }
@@ -2767,7 +4644,7 @@ final class Socks5Connection: @unchecked Sendable {
 
         guard let reply = Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0) else {
             relay.stop()
-            stop()
+            stop(reason: .protocolError, message: "reply-encode-failed")
             return
         }
 
@@ -2786,7 +4663,7 @@ final class Socks5Connection: @unchecked Sendable {
                             event: "udp-forward-reply-write-failed",
                             message: "SOCKS5 TCP-carried UDP success reply write failed"
                         )
-                        self.stop()
+                        self.stop(reason: .clientFailed, message: "udp-forward-reply-write-failed")
                         return
                     }
                     self.processBuffer()
@@ -2796,9 +4673,9 @@ final class Socks5Connection: @unchecked Sendable {
         )
     }
 
-    private func sendFailure(replyCode: UInt8 = 0x01) {
+    private func sendFailure(replyCode: UInt8 = 0x01, closeReason: Socks5CloseReason = .requestRejected, retryAfterMilliseconds: Int? = nil) {
         guard let reply = Socks5Codec.buildReply(code: replyCode, bindAddress: .ipv4("0.0.0.0"), bindPort: 0) else {
-            stop()
+            stop(reason: .protocolError, message: "reply-encode-failed")
             return
         }
         connection.send(
@@ -2814,7 +4691,7 @@ final class Socks5Connection: @unchecked Sendable {
                             message: "SOCKS5 failure reply write failed"
                         )
                     }
-                    self.stop()
+                    self.stop(reason: closeReason, message: "failure-reply-sent", retryAfterMilliseconds: retryAfterMilliseconds)
                 }
             }
         )
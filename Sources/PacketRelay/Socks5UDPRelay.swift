@@ -7,6 +7,7 @@ import Foundation
 import Network
 @preconcurrency import NetworkExtension
 import Observability
+import PacketIntelligenceCore
 
 /// Minimal lifecycle for per-connection UDP relay helpers.
 protocol Socks5UDPRelayProtocol: AnyObject {
@@ -20,10 +21,24 @@ protocol Socks5UDPSession: AnyObject, Sendable {
     var eventHandler: ((Socks5UDPSessionEvent) -> Void)? { get set }
     func setReadHandler(_ handler: @escaping @Sendable (Data?, Error?) -> Void)
     func writeDatagram(_ datagram: Data, completionHandler: @escaping @Sendable (Error?) -> Void)
+    /// Writes several datagrams to the same destination in one call so backends can coalesce
+    /// them into a single send pass.
+    /// Contract: `completionHandler` fires once per datagram, exactly as if each had been
+    /// written through `writeDatagram`.
+    func writeDatagrams(_ datagrams: [Data], completionHandler: @escaping @Sendable (Error?) -> Void)
     func restart()
     func cancel()
 }
 
+extension Socks5UDPSession {
+    /// Default: forward each datagram individually so backends without batch support keep working.
+    func writeDatagrams(_ datagrams: [Data], completionHandler: @escaping @Sendable (Error?) -> Void) {
+        for datagram in datagrams {
+            writeDatagram(datagram, completionHandler: completionHandler)
+        }
+    }
+}
+
 enum Socks5UDPSessionEvent: Sendable {
     case ready
     case waiting
@@ -44,6 +59,14 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
     private enum SessionPolicy {
         static let maxSessions = 256
         static let idleTimeoutSeconds: TimeInterval = 60
+        /// QUIC flows with a completed handshake carry their own keepalive/idle negotiation,
+        /// so the relay keeps them alive well past the generic UDP timeout.
+        static let quicEstablishedIdleTimeoutSeconds: TimeInterval = 180
+        /// QUIC flows that never left the handshake (including handshake-time closes, which
+        /// the relay cannot read) are cleaned up faster than generic UDP flows.
+        static let quicHandshakeTimeoutSeconds: TimeInterval = 30
+        /// Draining QUIC flows only need to outlive in-flight datagrams.
+        static let quicDrainingTimeoutSeconds: TimeInterval = 5
         static let idleReapIntervalSeconds: TimeInterval = 10
         static let usageQueueCompactionThreshold = 128
         static let pmtuReplacementThreshold = 3
@@ -56,6 +79,29 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         let port: UInt16
     }
 
+    /// Per-session QUIC lifecycle hints inferred from header forms on port-443 flows.
+    /// Decision: CONNECTION_CLOSE frames ride encrypted payloads at every level the relay
+    /// can observe, so close is never parsed directly. Handshake completion (short headers
+    /// seen in both directions) extends the idle timeout, flows stuck in the handshake are
+    /// reaped early, and a version-negotiation response marks the flow draining.
+    private struct QUICFlowState: Sendable {
+        var sawLongHeader = false
+        var sawOutboundShortHeader = false
+        var sawInboundShortHeader = false
+        var isDraining = false
+
+        var isEstablished: Bool {
+            sawOutboundShortHeader && sawInboundShortHeader
+        }
+    }
+
+    private enum QUICDatagramForm {
+        case notQUIC
+        case shortHeader
+        case longHeader
+        case versionNegotiation
+    }
+
     private struct SessionEntry: Sendable {
         struct PMTUFeedback: Sendable {
             var latestObservedMaximumDatagramSize: Int?
@@ -70,6 +116,9 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         var lastUsedSequence: UInt64
         var needsReplacement: Bool
         var pmtuFeedback: PMTUFeedback
+        var quic = QUICFlowState()
+        /// Requests awaiting a response on a single-transaction flow; unused elsewhere.
+        var pendingSingleTransactionRequests = 0
     }
 
     private struct SessionUsageStamp {
@@ -86,6 +135,8 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
     private let provider: Socks5ConnectionProvider
     private let queue: DispatchQueue
     private let mtu: Int
+    private let dnsSessionPool: Socks5DNSSessionPool?
+    private let singleTransactionPorts: Set<UInt16>
     private let nowProvider: @Sendable () -> Date
     private let queueSpecificKey = DispatchSpecificKey<UUID>()
     private let queueSpecificValue = UUID()
@@ -108,18 +159,29 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
     ///   - queue: Serial queue for socket I/O and state.
     ///   - mtu: Max expected datagram size.
     ///   - logger: Structured logger for relay events.
+    ///   - dnsSessionPool: Optional shared resolver pool; when set, port-53 datagrams
+    ///     multiplex over pooled sessions instead of per-association dials.
+    ///   - singleTransactionPorts: Destination ports treated as single-transaction
+    ///     exchanges — DNS and NTP by default. Flows to these ports close as soon as every
+    ///     outstanding request has drawn a response instead of idling out, so one-shot
+    ///     lookups stop holding sessions. Port 53 only takes this path when no shared
+    ///     resolver pool is configured; the pool manages its own session lifecycle.
     ///   - nowProvider: Time source used for bounded UDP session eviction.
     init(
         provider: Socks5ConnectionProvider,
         queue: DispatchQueue,
         mtu: Int,
         logger: StructuredLogger,
+        dnsSessionPool: Socks5DNSSessionPool? = nil,
+        singleTransactionPorts: Set<UInt16> = [53, 123],
         nowProvider: @escaping @Sendable () -> Date = { Date() }
     ) throws {
         self.provider = provider
         self.queue = queue
         self.mtu = max(256, mtu)
         self.logger = logger
+        self.dnsSessionPool = dnsSessionPool
+        self.singleTransactionPorts = singleTransactionPorts
         self.nowProvider = nowProvider
         queue.setSpecific(key: queueSpecificKey, value: queueSpecificValue)
         try openSocket()
@@ -187,8 +249,29 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         }
     }
 
+    /// Datagrams drained per read wakeup before the loop yields the queue and re-arms
+    /// itself, so session dial and write callbacks sharing the queue are not starved by
+    /// one sustained burst.
+    static let drainDatagramBudget = 256
+
     private func drainReadable() {
+        // Decision: datagrams read in one wakeup are grouped per destination and flushed with one
+        // batched write per session, so QUIC-style bursts cost one session crossing per
+        // destination instead of one per datagram.
+        var pendingDatagrams: [SessionKey: [Data]] = [:]
+        var pendingOrder: [SessionKey] = []
+        var budgetRemaining = Self.drainDatagramBudget
         while true {
+            guard budgetRemaining > 0 else {
+                // Flush what this pass gathered, then re-wake behind queued callbacks to
+                // keep draining the backlog.
+                performAsyncOnQueue { [weak self] in
+                    guard let self, self.readSource != nil else { return }
+                    self.drainReadable()
+                }
+                break
+            }
+            budgetRemaining -= 1
             var addr = sockaddr_storage()
             var addrLen = socklen_t(MemoryLayout<sockaddr_storage>.size)
             let bytes = recvfrom(socketFD, &receiveBuffer, receiveBuffer.count, 0, withUnsafeMutablePointer(to: &addr) {
@@ -225,12 +308,54 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
                 continue
             }
 
-            let now = nowProvider()
-            reapIdleSessionsIfNeeded(now: now)
             let key = SessionKey(address: packet.address, port: packet.port)
+            if pendingDatagrams[key] == nil {
+                pendingOrder.append(key)
+            }
+            pendingDatagrams[key, default: []].append(packet.payload)
+        }
+        flushPendingDatagrams(pendingDatagrams, order: pendingOrder)
+    }
+
+    private func flushPendingDatagrams(_ pending: [SessionKey: [Data]], order: [SessionKey]) {
+        guard !order.isEmpty else {
+            return
+        }
+        let now = nowProvider()
+        reapIdleSessionsIfNeeded(now: now)
+        for key in order {
+            guard let datagrams = pending[key] else {
+                continue
+            }
+            if key.port == 53, let dnsSessionPool {
+                // DNS fast path: resolver queries ride the shared pool instead of dialing a
+                // per-association session, so bursty resolvers stop churning host dials.
+                dnsSessionPool.send(
+                    queries: datagrams,
+                    to: key.address,
+                    port: key.port,
+                    provider: provider
+                ) { [weak self] response in
+                    guard let self,
+                          let packet = Socks5Codec.buildUDPPacket(
+                              address: key.address,
+                              port: key.port,
+                              payload: response
+                          )
+                    else {
+                        return
+                    }
+                    self.performAsyncOnQueue {
+                        self.sendToClient(packet)
+                    }
+                }
+                continue
+            }
             let entry = sessionEntry(for: key, now: now)
+            noteSingleTransactionRequests(datagrams.count, for: key)
+            noteOutboundQUICDatagrams(datagrams, for: key)
             let session = entry.session
-            entry.session.writeDatagram(packet.payload) { [weak self] error in
+            entry.session.writeDatagrams(datagrams) { [weak self] error in
                 guard let self, let error else { return }
                 self.performOnQueue {
                     guard self.isCurrentSession(session, for: key) else { return }
@@ -283,7 +408,7 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         dispatchPrecondition(condition: .onQueue(queue))
 
         let expiredKeys = sessions.compactMap { key, entry in
-            now.timeIntervalSince(entry.lastUsedAt) > SessionPolicy.idleTimeoutSeconds ? key : nil
+            now.timeIntervalSince(entry.lastUsedAt) > Self.idleTimeout(for: entry) ? key : nil
         }
 
         for key in expiredKeys {
@@ -291,6 +416,73 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         }
     }
 
+    private static func idleTimeout(for entry: SessionEntry) -> TimeInterval {
+        if entry.quic.isDraining {
+            return SessionPolicy.quicDrainingTimeoutSeconds
+        }
+        if entry.quic.isEstablished {
+            return SessionPolicy.quicEstablishedIdleTimeoutSeconds
+        }
+        if entry.quic.sawLongHeader {
+            return SessionPolicy.quicHandshakeTimeoutSeconds
+        }
+        return SessionPolicy.idleTimeoutSeconds
+    }
+
+    private func noteOutboundQUICDatagrams(_ datagrams: [Data], for key: SessionKey) {
+        // The C core only treats UDP:443 as a QUIC candidate path; mirror that here.
+        guard key.port == 443, var entry = sessions[key] else {
+            return
+        }
+        for datagram in datagrams {
+            switch Self.quicDatagramForm(datagram) {
+            case .longHeader:
+                entry.quic.sawLongHeader = true
+            case .shortHeader:
+                entry.quic.sawOutboundShortHeader = true
+            case .versionNegotiation, .notQUIC:
+                break
+            }
+        }
+        sessions[key] = entry
+    }
+
+    private func noteInboundQUICDatagram(_ datagram: Data, for key: SessionKey) {
+        guard key.port == 443, var entry = sessions[key] else {
+            return
+        }
+        switch Self.quicDatagramForm(datagram) {
+        case .longHeader:
+            entry.quic.sawLongHeader = true
+        case .shortHeader:
+            entry.quic.sawInboundShortHeader = true
+        case .versionNegotiation:
+            entry.quic.isDraining = true
+        case .notQUIC:
+            break
+        }
+        sessions[key] = entry
+    }
+
+    private static func quicDatagramForm(_ payload: Data) -> QUICDatagramForm {
+        let form = payload.withUnsafeBytes { rawBuffer -> Int32 in
+            guard let baseAddress = rawBuffer.baseAddress?.assumingMemoryBound(to: UInt8.self) else {
+                return Int32(RBPI_QUIC_FORM_NOT_QUIC)
+            }
+            return rbpi_quic_datagram_form(baseAddress, rawBuffer.count, nil)
+        }
+        switch form {
+        case Int32(RBPI_QUIC_FORM_SHORT_HEADER):
+            return .shortHeader
+        case Int32(RBPI_QUIC_FORM_LONG_HEADER):
+            return .longHeader
+        case Int32(RBPI_QUIC_FORM_VERSION_NEGOTIATION):
+            return .versionNegotiation
+        default:
+            return .notQUIC
+        }
+    }
+
     private func sessionEntry(for key: SessionKey, now: Date) -> SessionEntry {
         if let entry = sessions[key], !entry.needsReplacement {
             return markSessionUsed(for: key, at: now) ?? entry
@@ -377,10 +569,12 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
                 guard self.markSessionUsed(for: key, at: self.nowProvider()) != nil else {
                     return
                 }
+                self.noteInboundQUICDatagram(datagram, for: key)
                 guard let response = Socks5Codec.buildUDPPacket(address: key.address, port: key.port, payload: datagram) else {
                     return
                 }
                 self.sendToClient(response)
+                self.finishSingleTransactionExchangeIfNeeded(for: key)
             }
         })
 
@@ -533,6 +727,33 @@ final class Socks5UDPRelay: @unchecked Sendable, Socks5UDPRelayProtocol {
         return metadata
     }
 
+    /// Records requests now in flight on a single-transaction flow.
+    private func noteSingleTransactionRequests(_ count: Int, for key: SessionKey) {
+        guard singleTransactionPorts.contains(key.port), var entry = sessions[key] else {
+            return
+        }
+        entry.pendingSingleTransactionRequests = Self.saturatingAdd(entry.pendingSingleTransactionRequests, count)
+        sessions[key] = entry
+    }
+
+    /// Closes a single-transaction flow once its last outstanding request has been
+    /// answered, instead of leaving the session to the generic idle timeout.
+    /// Decision: responses are matched by count, not payload — transaction IDs are
+    /// protocol-specific, and for request/response ports an equal number of answers
+    /// means the exchange the client started is over. Retransmissions only raise the
+    /// count, so a retried lookup still closes on its final answer.
+    private func finishSingleTransactionExchangeIfNeeded(for key: SessionKey) {
+        guard singleTransactionPorts.contains(key.port), var entry = sessions[key] else {
+            return
+        }
+        entry.pendingSingleTransactionRequests = max(0, entry.pendingSingleTransactionRequests - 1)
+        guard entry.pendingSingleTransactionRequests == 0 else {
+            sessions[key] = entry
+            return
+        }
+        removeSession(for: key)
+    }
+
     private func clearReplacementNeed(for key: SessionKey) {
         guard var entry = sessions[key] else {
             return
@@ -0,0 +1,177 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// TLS alert descriptions the relay can send before resetting a blocked flow.
+/// Contract: values are RFC 8446 AlertDescription codes and are sent at fatal level.
+enum TLSAlertDescription: UInt8, Sendable {
+    /// Sent for policy-blocked flows; clients surface this as an explicit server refusal.
+    case accessDenied = 49
+    /// Alternative for name-based blocks; some clients render this as a clearer DNS-style error.
+    case unrecognizedName = 112
+}
+
+/// Minimal TLS ClientHello reader used when a blocked flow is drained far enough to name its SNI.
+/// Decision: this is not a TLS stack — it parses exactly enough of the first flight to recover the
+/// server name for diagnostics and to confirm the stream is TLS before an alert is worth sending.
+/// Anything malformed or non-TLS reports `.notTLS` and the caller falls back to a plain reset.
+enum TLSHandshakeReset {
+    enum ClientHelloParseResult: Equatable {
+        /// The buffer is a valid TLS prefix but the first handshake record is incomplete.
+        case needsMoreData
+        /// The buffer does not start with a TLS handshake record.
+        case notTLS
+        /// A complete ClientHello, with the SNI host when the client sent one and whether the
+        /// hello carried an Encrypted Client Hello extension (in which case any SNI is a cover name).
+        case clientHello(serverName: String?, hasECH: Bool)
+    }
+
+    private static let handshakeContentType: UInt8 = 0x16
+    private static let clientHelloHandshakeType: UInt8 = 0x01
+    private static let serverNameExtensionType: UInt16 = 0
+    private static let echExtensionType: UInt16 = 0xfe0d
+    private static let hostNameType: UInt8 = 0
+
+    /// Builds a two-byte fatal alert record (level 2) wrapped in a TLS 1.2 record header.
+    /// Middleboxes and pre-1.3 clients accept the 0x0303 legacy record version.
+    static func makeFatalAlert(_ description: TLSAlertDescription) -> Data {
+        Data([0x15, 0x03, 0x03, 0x00, 0x02, 0x02, description.rawValue])
+    }
+
+    /// Parses the leading ClientHello in `data`, reassembling it across TLS records.
+    /// Decision: large hellos (ECH payloads, post-quantum key shares) fragment across
+    /// records and TCP segments; collecting handshake bytes until the declared length is
+    /// reached keeps the sniffer from being defeated by wherever the segmentation fell.
+    /// Callers keep appending bytes while this reports `.needsMoreData`.
+    static func parseClientHello(_ data: Data) -> ClientHelloParseResult {
+        let bytes = [UInt8](data)
+        guard bytes.count >= 5 else {
+            return looksLikeTLSPrefix(bytes) ? .needsMoreData : .notTLS
+        }
+        guard bytes[0] == handshakeContentType, bytes[1] == 0x03 else {
+            return .notTLS
+        }
+
+        // Coalesce consecutive handshake records until the hello's declared length arrives.
+        var handshake: [UInt8] = []
+        var handshakeLength: Int?
+        var recordCursor = 0
+        while handshakeLength.map({ handshake.count < $0 + 4 }) ?? true {
+            guard recordCursor + 5 <= bytes.count else {
+                return .needsMoreData
+            }
+            guard bytes[recordCursor] == handshakeContentType, bytes[recordCursor + 1] == 0x03 else {
+                return .notTLS
+            }
+            let recordLength = Int(bytes[recordCursor + 3]) << 8 | Int(bytes[recordCursor + 4])
+            guard recordLength >= 1 else {
+                return .notTLS
+            }
+            guard recordCursor + 5 + recordLength <= bytes.count else {
+                return .needsMoreData
+            }
+            handshake.append(contentsOf: bytes[(recordCursor + 5) ..< (recordCursor + 5 + recordLength)])
+            recordCursor += 5 + recordLength
+            if handshakeLength == nil, handshake.count >= 4 {
+                guard handshake[0] == clientHelloHandshakeType else {
+                    return .notTLS
+                }
+                handshakeLength = Int(handshake[1]) << 16 | Int(handshake[2]) << 8 | Int(handshake[3])
+            }
+        }
+        guard let declaredLength = handshakeLength else {
+            return .needsMoreData
+        }
+
+        return parseClientHelloHandshake(handshake, handshakeLength: declaredLength)
+    }
+
+    private static func parseClientHelloHandshake(_ bytes: [UInt8], handshakeLength: Int) -> ClientHelloParseResult {
+        var cursor = 4
+        let handshakeEnd = cursor + handshakeLength
+
+        // client_version(2) + random(32)
+        cursor += 34
+        guard cursor < handshakeEnd else {
+            return .notTLS
+        }
+        // session_id
+        let sessionIDLength = Int(bytes[cursor])
+        cursor += 1 + sessionIDLength
+        guard cursor + 2 <= handshakeEnd else {
+            return .notTLS
+        }
+        // cipher_suites
+        let cipherSuitesLength = Int(bytes[cursor]) << 8 | Int(bytes[cursor + 1])
+        cursor += 2 + cipherSuitesLength
+        guard cursor < handshakeEnd else {
+            return .notTLS
+        }
+        // compression_methods
+        let compressionLength = Int(bytes[cursor])
+        cursor += 1 + compressionLength
+        guard cursor + 2 <= handshakeEnd else {
+            // Extensions are optional; a hello without them is still a valid ClientHello.
+            return cursor == handshakeEnd ? .clientHello(serverName: nil, hasECH: false) : .notTLS
+        }
+
+        let extensionsLength = Int(bytes[cursor]) << 8 | Int(bytes[cursor + 1])
+        cursor += 2
+        let extensionsEnd = cursor + extensionsLength
+        guard extensionsEnd <= handshakeEnd else {
+            return .notTLS
+        }
+        var serverName: String?
+        var hasECH = false
+        while cursor + 4 <= extensionsEnd {
+            let extensionType = UInt16(bytes[cursor]) << 8 | UInt16(bytes[cursor + 1])
+            let extensionLength = Int(bytes[cursor + 2]) << 8 | Int(bytes[cursor + 3])
+            cursor += 4
+            guard cursor + extensionLength <= extensionsEnd else {
+                return .notTLS
+            }
+            if extensionType == serverNameExtensionType {
+                serverName = parseServerNameList(bytes, start: cursor, end: cursor + extensionLength)
+            } else if extensionType == echExtensionType {
+                hasECH = true
+            }
+            cursor += extensionLength
+        }
+        return .clientHello(serverName: serverName, hasECH: hasECH)
+    }
+
+    private static func looksLikeTLSPrefix(_ bytes: [UInt8]) -> Bool {
+        if bytes.count >= 1, bytes[0] != handshakeContentType {
+            return false
+        }
+        if bytes.count >= 2, bytes[1] != 0x03 {
+            return false
+        }
+        return true
+    }
+
+    private static func parseServerNameList(_ bytes: [UInt8], start: Int, end: Int) -> String? {
+        var cursor = start
+        guard cursor + 2 <= end else {
+            return nil
+        }
+        let listLength = Int(bytes[cursor]) << 8 | Int(bytes[cursor + 1])
+        cursor += 2
+        let listEnd = min(cursor + listLength, end)
+        while cursor + 3 <= listEnd {
+            let nameType = bytes[cursor]
+            let nameLength = Int(bytes[cursor + 1]) << 8 | Int(bytes[cursor + 2])
+            cursor += 3
+            guard cursor + nameLength <= listEnd else {
+                return nil
+            }
+            if nameType == hostNameType {
+                return String(bytes: bytes[cursor ..< cursor + nameLength], encoding: .utf8)
+            }
+            cursor += nameLength
+        }
+        return nil
+    }
+}
@@ -16,8 +16,58 @@ public enum BridgeWriteResult: Sendable, Equatable {
     case failed(errorCode: Int32)
 }
 
+/// Direction of a host-injected raw frame relative to the bridge.
+public enum BridgeInjectionDirection: Sendable, Equatable {
+    /// Toward the dataplane engine, as if the packet had arrived from `NEPacketTunnelFlow`.
+    case towardEngine
+    /// Toward the host's read loop, as if the engine had emitted the packet.
+    case towardHost
+}
+
+/// Failure-history-driven pacing for the bridge's write-drain loop.
+/// Decision: repeated `EAGAIN` stalls mean the engine side is consuming slowly; halving the
+/// per-poll batch and spacing out re-arms keeps the bridge from re-offering the full flush
+/// buffer at every writable edge, which burns CPU without moving bytes. Clean drains earn
+/// the batch size back so a transient stall does not throttle steady state.
+struct BridgeDrainPacer: Equatable {
+    static let minBatchFrames = 4
+    static let maxBatchFrames = 256
+    /// Stalls in a row before the write source is parked between polls.
+    static let stallsBeforeSpacing = 2
+    static let baseSpacingMilliseconds = 2
+    static let maxSpacingMilliseconds = 20
+
+    /// Frames the drain loop may write in one poll.
+    private(set) var batchFrames = BridgeDrainPacer.maxBatchFrames
+    private(set) var consecutiveStalls = 0
+
+    /// Records one poll that ended in `EAGAIN` with frames still queued.
+    mutating func recordStall() {
+        consecutiveStalls += 1
+        batchFrames = max(Self.minBatchFrames, batchFrames / 2)
+    }
+
+    /// Records one poll that drained the queue without stalling.
+    mutating func recordDrained() {
+        consecutiveStalls = 0
+        batchFrames = min(Self.maxBatchFrames, batchFrames * 2)
+    }
+
+    /// Milliseconds to keep the write source parked before the next drain attempt, or
+    /// `nil` while the stall streak is short enough to stay armed.
+    var spacingMilliseconds: Int? {
+        guard consecutiveStalls >= Self.stallsBeforeSpacing else {
+            return nil
+        }
+        return min(Self.maxSpacingMilliseconds, Self.baseSpacingMilliseconds * consecutiveStalls)
+    }
+}
+
 /// Bridges NE packetFlow data into a file descriptor pair consumed by the dataplane engine.
 /// Queue ownership: read/write sources and pending write state are only touched on `queue`.
+/// Decision: queued outbound frames hash by flow across several queues drained round-robin,
+/// so one bulk flow that saturates its queue delays only itself while interactive flows keep
+/// moving; a single FIFO would head-of-line block everything behind the flood.
 public final class TunSocketBridge: @unchecked Sendable {
     private enum PacketSizing {
         static let frameHeaderBytes = MemoryLayout<UInt32>.size
@@ -25,6 +75,17 @@ public final class TunSocketBridge: @unchecked Sendable {
         static let maxBridgeFrameBytes = frameHeaderBytes + maxIPPacketBytes
     }
 
+    /// Outbound queue fan-out. Small on purpose: queues exist to separate a flooding flow
+    /// from everyone else, not to bucket every flow uniquely.
+    static let outboundQueueCount = 4
+
+    /// Frames drained per read wakeup before the loop yields the queue and re-arms itself.
+    /// Decision: a sustained flood otherwise keeps one dispatch-source event running for
+    /// the whole burst, so writes, injections, and stats calls sharing the serial queue
+    /// wait behind it; a budget with an async re-wake lets that queued work interleave
+    /// while the socket stays hot.
+    static let drainFrameBudget = 256
+
     private let logger: StructuredLogger
     private let mtu: Int
     private let queue: DispatchQueue
@@ -34,15 +95,39 @@ public final class TunSocketBridge: @unchecked Sendable {
     private let appFD: Int32
     public let engineFD: Int32
 
+    /// One queued outbound frame. The family header is written from a scratch word at drain time
+    /// and the payload stays the caller's copy-on-write `Data`, so queueing never clones payload bytes.
+    private struct PendingFrame {
+        let family: Int32
+        let packet: Data
+
+        var byteCount: Int {
+            PacketSizing.frameHeaderBytes + packet.count
+        }
+    }
+
     private var readSource: DispatchSourceRead?
     private var writeSource: DispatchSourceWrite?
     private var writeSourceActive = false
-    private var pendingWrites: ArraySlice<Data> = []
+    private var pendingWrites: [ArraySlice<PendingFrame>]
+    private var pendingBytesPerQueue: [Int]
     private var pendingBytes = 0
+    /// Queue the round-robin drain visits next; a stall pins it so the stalled frame
+    /// retries first and frame order within a flow is preserved.
+    private var drainCursor = 0
     private var backpressureSignals: UInt64 = 0
+    private var drainPacer = BridgeDrainPacer()
+    private var spacedDrainScheduled = false
     private let maxPendingBytes: Int
+    private let maxPendingBytesPerQueue: Int
     private let backpressureThreshold: Int
-    private var readBuffer: [UInt8]
+    private let receiveBufferPool: PacketBufferPool
+    // Scratch batch arrays reused across drain polls. Handing them to the handler shares storage
+    // copy-on-write, so `removeAll(keepingCapacity:)` reuses the allocation whenever the handler
+    // finished with the batch synchronously and only reallocates when it kept a reference.
+    private var drainPacketsScratch: [Data] = []
+    private var drainFamiliesScratch: [Int32] = []
+    private var duplicateFilter: DuplicatePacketFilter?
     private var isStopped = false
 
     public var onBackpressureRelieved: (@Sendable () -> Void)?
@@ -51,16 +136,31 @@ public final class TunSocketBridge: @unchecked Sendable {
     ///   - mtu: Expected tunnel MTU used to size buffers.
     ///   - queue: Serial queue for socket read/write dispatch sources.
     ///   - logger: Structured logger for bridge errors and backpressure events.
-    public init(mtu: Int, queue: DispatchQueue, logger: StructuredLogger) throws {
+    ///   - duplicateFilter: When provided, host packets hashing identically to one seen
+    ///     inside the filter's window are silently dropped instead of injected twice;
+    ///     `nil` (the default) writes every packet through unfiltered.
+    public init(
+        mtu: Int,
+        queue: DispatchQueue,
+        logger: StructuredLogger,
+        duplicateFilter: DuplicatePacketFilter? = nil
+    ) throws {
         self.logger = logger
+        self.duplicateFilter = duplicateFilter
         self.mtu = min(max(256, mtu), 65_535)
         self.queue = queue
         queue.setSpecific(key: queueSpecificKey, value: queueSpecificValue)
         self.maxPendingBytes = max(4_194_304, self.mtu * 1024)
+        self.maxPendingBytesPerQueue = maxPendingBytes / Self.outboundQueueCount
         self.backpressureThreshold = maxPendingBytes * 3 / 4
+        self.pendingWrites = Array(repeating: ArraySlice<PendingFrame>(), count: Self.outboundQueueCount)
+        self.pendingBytesPerQueue = Array(repeating: 0, count: Self.outboundQueueCount)
         // Apple NEPacketTunnelFlow read/write APIs move full IP packets; the configured MTU is an interface policy,
         // not a safe receive-buffer ceiling for dataplane recovery paths.
-        self.readBuffer = [UInt8](repeating: 0, count: PacketSizing.maxBridgeFrameBytes)
+        self.receiveBufferPool = PacketBufferPool(
+            bufferSize: PacketSizing.maxBridgeFrameBytes,
+            maxPooledBuffers: 64
+        )
 
         var fds = [Int32](repeating: 0, count: 2)
         let result = socketpair(AF_UNIX, SOCK_DGRAM, 0, &fds)
@@ -144,6 +244,12 @@ public final class TunSocketBridge: @unchecked Sendable {
             return .failed(errorCode: EBADF)
         }
 
+        // Dropped duplicates report `.accepted`: from the host's view the packet was
+        // delivered, it just is not injected into the engine a second time.
+        if duplicateFilter?.isDuplicate(packet) == true {
+            return .accepted
+        }
+
         var family: Int32 = ipVersionHint
         if family != AF_INET && family != AF_INET6 {
             family = packet.first.map { (($0 >> 4) & 0x0F) == 6 ? AF_INET6 : AF_INET } ?? AF_INET
@@ -153,13 +259,13 @@ public final class TunSocketBridge: @unchecked Sendable {
             return .failed(errorCode: EMSGSIZE)
         }
 
-        if pendingWrites.isEmpty {
+        if pendingBytes == 0 {
             let result = writePacketImmediate(packet, family: family)
             if result == expectedLength {
                 return .accepted
             }
             if result < 0 && (errno == EAGAIN || errno == EWOULDBLOCK || errno == ENOBUFS) {
-                return enqueueWrite(framedPacket(packet, family: family, expectedLength: expectedLength))
+                return enqueueWrite(PendingFrame(family: family, packet: packet))
             }
             let errorCode = Int32(errno)
             Task {
@@ -176,10 +282,70 @@ public final class TunSocketBridge: @unchecked Sendable {
             return .failed(errorCode: errorCode)
         }
 
-        return enqueueWrite(framedPacket(packet, family: family, expectedLength: expectedLength))
+        return enqueueWrite(PendingFrame(family: family, packet: packet))
+    }
+
+    @discardableResult
+    /// Validates and injects one raw IP frame in either direction, for test tooling and
+    /// host-side protocol helpers (keepalive or neighbor-discovery style emulation)
+    /// that would otherwise have to hand-roll the bridge's frame format.
+    /// Validation pins the version nibble to a coherent family and requires at least the
+    /// fixed IP header for that family; rejected frames are logged and never written.
+    /// Injection toward the host is fire-and-forget: a full socket drops the frame with
+    /// `.backpressured` instead of queueing, since injected frames are droppable probes.
+    /// - Parameters:
+    ///   - packet: Full raw IP packet to inject.
+    ///   - direction: Which side of the bridge receives the frame.
+    /// - Returns: Bridge acceptance, saturation, or terminal failure status.
+    public func injectFrame(_ packet: Data, direction: BridgeInjectionDirection) -> BridgeWriteResult {
+        guard let family = Self.validatedInjectionFamily(of: packet) else {
+            Task {
+                await logger.log(
+                    level: .warning,
+                    phase: .relay,
+                    category: .control,
+                    component: "TunSocketBridge",
+                    event: "inject-frame-invalid",
+                    result: "rejected",
+                    message: "Refused to inject a frame that is not a structurally valid IP packet",
+                    metadata: ["packet_bytes": String(packet.count), "direction": Self.injectionDirectionLabel(direction)]
+                )
+            }
+            return .failed(errorCode: EINVAL)
+        }
+        Task {
+            await logger.log(
+                level: .debug,
+                phase: .relay,
+                category: .control,
+                component: "TunSocketBridge",
+                event: "inject-frame",
+                message: "Injecting host-crafted frame into the bridge",
+                metadata: [
+                    "packet_bytes": String(packet.count),
+                    "family": String(family),
+                    "direction": Self.injectionDirectionLabel(direction)
+                ]
+            )
+        }
+        switch direction {
+        case .towardEngine:
+            return writePacket(packet, ipVersionHint: family)
+        case .towardHost:
+            return injectFrameTowardHost(packet, family: family)
+        }
     }
 
     /// Returns whether queued bytes have crossed the backpressure threshold.
+    /// Returns the duplicate filter's counters, or `nil` when filtering is disabled.
+    public func duplicateFilterStats() -> DuplicatePacketFilterStats? {
+        var stats: DuplicatePacketFilterStats?
+        performOnQueue {
+            stats = duplicateFilter?.stats
+        }
+        return stats
+    }
+
     public func isBackpressured() -> Bool {
         var result = false
         performOnQueue {
@@ -223,7 +389,10 @@ public final class TunSocketBridge: @unchecked Sendable {
             }
             writeSourceActive = false
 
-            pendingWrites.removeAll(keepingCapacity: false)
+            for index in pendingWrites.indices {
+                pendingWrites[index].removeAll(keepingCapacity: false)
+            }
+            pendingBytesPerQueue = Array(repeating: 0, count: Self.outboundQueueCount)
             pendingBytes = 0
             onBackpressureRelieved = nil
         }
@@ -232,22 +401,26 @@ public final class TunSocketBridge: @unchecked Sendable {
 
     private func drainReadable(handler: @escaping @Sendable ([Data], [Int32]) -> Void) {
         let bufferSize = PacketSizing.maxBridgeFrameBytes
-        if readBuffer.count != bufferSize {
-            readBuffer = [UInt8](repeating: 0, count: bufferSize)
-        }
-
         let batchLimit = 32
-        var packets: [Data] = []
-        var families: [Int32] = []
-        packets.reserveCapacity(batchLimit)
-        families.reserveCapacity(batchLimit)
+        drainPacketsScratch.removeAll(keepingCapacity: true)
+        drainFamiliesScratch.removeAll(keepingCapacity: true)
+        drainPacketsScratch.reserveCapacity(batchLimit)
+        drainFamiliesScratch.reserveCapacity(batchLimit)
+        var budgetRemaining = Self.drainFrameBudget
+        var yieldedWithBacklog = false
 
         while true {
-            let bytesRead = readBuffer.withUnsafeMutableBufferPointer { bufferPtr -> Int in
-                guard let base = bufferPtr.baseAddress else { return -1 }
-                return recv(appFD, base, bufferPtr.count, 0)
+            guard budgetRemaining > 0 else {
+                yieldedWithBacklog = true
+                break
             }
+            budgetRemaining -= 1
+            // Each datagram lands in a pooled slab that travels to the handler inside `Data`
+            // without another copy; the deallocator recycles the slab when the last reference drops.
+            let slab = receiveBufferPool.acquire()
+            let bytesRead = recv(appFD, slab, bufferSize, 0)
             if bytesRead < 0 {
+                receiveBufferPool.recycle(slab)
                 if errno == EAGAIN || errno == EWOULDBLOCK {
                     break
                 }
@@ -264,38 +437,74 @@ public final class TunSocketBridge: @unchecked Sendable {
                 }
                 break
             }
-            guard bytesRead > 0 else { break }
-            guard bytesRead > MemoryLayout<UInt32>.size else { continue }
+            guard bytesRead > 0 else {
+                receiveBufferPool.recycle(slab)
+                break
+            }
+            let headerSize = PacketSizing.frameHeaderBytes
+            guard bytesRead > headerSize else {
+                receiveBufferPool.recycle(slab)
+                continue
+            }
 
-            let headerSize = MemoryLayout<UInt32>.size
-            let payloadRange = headerSize ..< bytesRead
-            let payload = Data(readBuffer[payloadRange])
-            let familyRaw = (UInt32(readBuffer[0]) << 24)
-                | (UInt32(readBuffer[1]) << 16)
-                | (UInt32(readBuffer[2]) << 8)
-                | UInt32(readBuffer[3])
+            let headerBytes = slab.assumingMemoryBound(to: UInt8.self)
+            let familyRaw = (UInt32(headerBytes[0]) << 24)
+                | (UInt32(headerBytes[1]) << 16)
+                | (UInt32(headerBytes[2]) << 8)
+                | UInt32(headerBytes[3])
+            let pool = receiveBufferPool
+            let payload = Data(
+                bytesNoCopy: slab.advanced(by: headerSize),
+                count: bytesRead - headerSize,
+                deallocator: .custom { pointer, _ in
+                    // The payload starts one frame header into the slab; rewind to the base
+                    // allocation before recycling.
+                    pool.recycle(pointer.advanced(by: -PacketSizing.frameHeaderBytes))
+                }
+            )
             var family = Int32(familyRaw)
             if family != AF_INET && family != AF_INET6 {
                 family = payload.first.map { (($0 >> 4) & 0x0F) == 6 ? AF_INET6 : AF_INET } ?? AF_INET
             }
 
-            packets.append(payload)
-            families.append(family)
-            if packets.count >= batchLimit {
-                handler(packets, families)
-                packets.removeAll(keepingCapacity: true)
-                families.removeAll(keepingCapacity: true)
+            drainPacketsScratch.append(payload)
+            drainFamiliesScratch.append(family)
+            if drainPacketsScratch.count >= batchLimit {
+                handler(drainPacketsScratch, drainFamiliesScratch)
+                drainPacketsScratch.removeAll(keepingCapacity: true)
+                drainFamiliesScratch.removeAll(keepingCapacity: true)
             }
         }
 
-        if !packets.isEmpty {
-            handler(packets, families)
+        if !drainPacketsScratch.isEmpty {
+            handler(drainPacketsScratch, drainFamiliesScratch)
+            drainPacketsScratch.removeAll(keepingCapacity: true)
+            drainFamiliesScratch.removeAll(keepingCapacity: true)
+        }
+
+        if yieldedWithBacklog {
+            // The socket may still hold frames; re-wake behind whatever else queued while
+            // this pass ran so the backlog keeps draining without waiting on the source.
+            queue.async { [weak self] in
+                guard let self else { return }
+                self.lifecycleLock.lock()
+                let stopped = self.isStopped
+                self.lifecycleLock.unlock()
+                guard !stopped else { return }
+                self.drainReadable(handler: handler)
+            }
         }
     }
 
-    private func enqueueWrite(_ data: Data) -> BridgeWriteResult {
-        let remainingCapacity = max(0, maxPendingBytes - pendingBytes)
-        if data.count > remainingCapacity {
+    private func enqueueWrite(_ frame: PendingFrame) -> BridgeWriteResult {
+        let queueIndex = Self.flowQueueIndex(packet: frame.packet, family: frame.family)
+        // A flooding flow exhausts its own queue's budget first, so its backpressure
+        // signal fires while the other queues still accept interactive flows.
+        let remainingCapacity = max(
+            0,
+            min(maxPendingBytes - pendingBytes, maxPendingBytesPerQueue - pendingBytesPerQueue[queueIndex])
+        )
+        if frame.byteCount > remainingCapacity {
             backpressureSignals &+= 1
             if backpressureSignals == 1 || backpressureSignals % 100 == 0 {
                 Task {
@@ -309,6 +518,8 @@ public final class TunSocketBridge: @unchecked Sendable {
                         message: "Bridge write queue is saturated",
                         metadata: [
                             "signals": String(backpressureSignals),
+                            "queue_index": String(queueIndex),
+                            "queue_pending_bytes": String(pendingBytesPerQueue[queueIndex]),
                             "pending_bytes": String(pendingBytes),
                             "max_pending_bytes": String(maxPendingBytes)
                         ]
@@ -317,30 +528,42 @@ public final class TunSocketBridge: @unchecked Sendable {
             }
             return .backpressured
         }
-        pendingWrites.append(data)
-        pendingBytes += data.count
+        pendingWrites[queueIndex].append(frame)
+        pendingBytesPerQueue[queueIndex] += frame.byteCount
+        pendingBytes += frame.byteCount
         startWriteSourceIfNeeded()
         return .accepted
     }
 
     private func drainWritable() {
         let wasBackpressured = pendingBytes >= backpressureThreshold
+        var framesWritten = 0
+        var stalled = false
 
-        while let next = pendingWrites.first {
-            let result = next.withUnsafeBytes { ptr -> ssize_t in
-                guard let base = ptr.baseAddress else { return -1 }
-                return write(appFD, base, next.count)
+        // One frame per non-empty queue per rotation: a bulk flow's backlog drains one
+        // frame for every frame of each waiting interactive flow instead of ahead of them.
+        while framesWritten < drainPacer.batchFrames, let queueIndex = nextNonEmptyQueue() {
+            guard let next = pendingWrites[queueIndex].first else {
+                break
             }
-            if result == next.count {
-                pendingWrites.removeFirst()
-                pendingBytes -= next.count
+            let result = writePacketImmediate(next.packet, family: next.family)
+            if result == next.byteCount {
+                pendingWrites[queueIndex].removeFirst()
+                pendingBytesPerQueue[queueIndex] -= next.byteCount
+                pendingBytes -= next.byteCount
+                framesWritten += 1
+                drainCursor = (queueIndex + 1) % Self.outboundQueueCount
                 continue
             }
             if result < 0 && (errno == EAGAIN || errno == EWOULDBLOCK || errno == ENOBUFS) {
+                stalled = true
+                drainCursor = queueIndex
                 break
             }
-            pendingWrites.removeFirst()
-            pendingBytes -= next.count
+            pendingWrites[queueIndex].removeFirst()
+            pendingBytesPerQueue[queueIndex] -= next.byteCount
+            pendingBytes -= next.byteCount
+            drainCursor = (queueIndex + 1) % Self.outboundQueueCount
             Task {
                 await logger.log(
                     level: .error,
@@ -354,9 +577,15 @@ public final class TunSocketBridge: @unchecked Sendable {
             }
         }
 
-        if pendingWrites.isEmpty {
+        if stalled {
+            drainPacer.recordStall()
+            scheduleSpacedDrainIfNeeded()
+        } else if pendingBytes == 0 {
+            drainPacer.recordDrained()
             stopWriteSourceIfNeeded()
-            pendingWrites.removeAll(keepingCapacity: false)
+            for index in pendingWrites.indices {
+                pendingWrites[index].removeAll(keepingCapacity: false)
+            }
         }
 
         let isBackpressured = pendingBytes >= backpressureThreshold
@@ -365,6 +594,177 @@ public final class TunSocketBridge: @unchecked Sendable {
         }
     }
 
+    /// Next queue the round-robin drain should service, starting at the cursor, or `nil`
+    /// when every queue is empty.
+    private func nextNonEmptyQueue() -> Int? {
+        for offset in 0 ..< Self.outboundQueueCount {
+            let index = (drainCursor + offset) % Self.outboundQueueCount
+            if !pendingWrites[index].isEmpty {
+                return index
+            }
+        }
+        return nil
+    }
+
+    /// Maps a packet onto an outbound queue by hashing its flow tuple: addresses and
+    /// protocol, plus TCP/UDP ports when the transport header is present. Frames of one
+    /// flow always land on one queue, preserving per-flow ordering; packets too short
+    /// to carry the tuple fall back to queue 0.
+    static func flowQueueIndex(
+        packet: Data,
+        family: Int32,
+        queueCount: Int = TunSocketBridge.outboundQueueCount
+    ) -> Int {
+        guard queueCount > 1 else {
+            return 0
+        }
+        let header = [UInt8](packet.prefix(64))
+        var hash: UInt64 = 0xcbf2_9ce4_8422_2325
+        func mix(_ byte: UInt8) {
+            hash = (hash ^ UInt64(byte)) &* 0x0000_0100_0000_01b3
+        }
+        if family == AF_INET6 {
+            guard header.count >= 40 else {
+                return 0
+            }
+            for index in 8 ..< 40 {
+                mix(header[index])
+            }
+            let nextHeader = header[6]
+            mix(nextHeader)
+            if nextHeader == 6 || nextHeader == 17, header.count >= 44 {
+                for index in 40 ..< 44 {
+                    mix(header[index])
+                }
+            }
+        } else {
+            guard header.count >= 20 else {
+                return 0
+            }
+            for index in 12 ..< 20 {
+                mix(header[index])
+            }
+            let transportProtocol = header[9]
+            mix(transportProtocol)
+            let headerLength = Int(header[0] & 0x0F) * 4
+            if transportProtocol == 6 || transportProtocol == 17,
+               headerLength >= 20, header.count >= headerLength + 4 {
+                for index in headerLength ..< headerLength + 4 {
+                    mix(header[index])
+                }
+            }
+        }
+        return Int(hash % UInt64(queueCount))
+    }
+
+    /// Writes one injected frame onto the engine-side descriptor so it arrives through the
+    /// host's read loop exactly like an engine-emitted packet.
+    private func injectFrameTowardHost(_ packet: Data, family: Int32) -> BridgeWriteResult {
+        lifecycleLock.lock()
+        let stopped = isStopped
+        lifecycleLock.unlock()
+        guard !stopped else {
+            return .failed(errorCode: EBADF)
+        }
+        guard let expectedLength = frameLength(for: packet) else {
+            return .failed(errorCode: EMSGSIZE)
+        }
+
+        var header = UInt32(family).bigEndian
+        let result = withUnsafeBytes(of: &header) { headerPtr -> Int in
+            packet.withUnsafeBytes { packetPtr -> Int in
+                var iov = [
+                    iovec(
+                        iov_base: UnsafeMutableRawPointer(mutating: headerPtr.baseAddress),
+                        iov_len: headerPtr.count
+                    ),
+                    iovec(
+                        iov_base: UnsafeMutableRawPointer(mutating: packetPtr.baseAddress),
+                        iov_len: packetPtr.count
+                    )
+                ]
+                return writev(engineFD, &iov, Int32(iov.count))
+            }
+        }
+        if result == expectedLength {
+            return .accepted
+        }
+        if result < 0 && (errno == EAGAIN || errno == EWOULDBLOCK || errno == ENOBUFS) {
+            return .backpressured
+        }
+        return .failed(errorCode: Int32(errno))
+    }
+
+    /// Minimal structural validation for injected frames: the version nibble must name a
+    /// family and the packet must carry at least that family's fixed IP header (including
+    /// the declared IPv4 header length). Returns the address family or `nil`.
+    static func validatedInjectionFamily(of packet: Data) -> Int32? {
+        guard let first = packet.first else {
+            return nil
+        }
+        switch (first >> 4) & 0x0F {
+        case 4:
+            let headerLength = Int(first & 0x0F) * 4
+            guard headerLength >= 20, packet.count >= headerLength else {
+                return nil
+            }
+            return AF_INET
+        case 6:
+            guard packet.count >= 40 else {
+                return nil
+            }
+            return AF_INET6
+        default:
+            return nil
+        }
+    }
+
+    private static func injectionDirectionLabel(_ direction: BridgeInjectionDirection) -> String {
+        switch direction {
+        case .towardEngine:
+            return "toward-engine"
+        case .towardHost:
+            return "toward-host"
+        }
+    }
+
+    /// Parks the write source between stalled polls so the engine gets real time to drain
+    /// instead of the bridge retrying at every writable edge of a nearly-full buffer.
+    private func scheduleSpacedDrainIfNeeded() {
+        guard let spacingMilliseconds = drainPacer.spacingMilliseconds, !spacedDrainScheduled else {
+            return
+        }
+        stopWriteSourceIfNeeded()
+        spacedDrainScheduled = true
+        if drainPacer.consecutiveStalls == BridgeDrainPacer.stallsBeforeSpacing {
+            Task {
+                await logger.log(
+                    level: .notice,
+                    phase: .relay,
+                    category: .control,
+                    component: "TunSocketBridge",
+                    event: "write-drain-paced",
+                    result: "retry",
+                    message: "Bridge write drain is pacing after repeated stalls",
+                    metadata: [
+                        "batch_frames": String(drainPacer.batchFrames),
+                        "spacing_ms": String(spacingMilliseconds),
+                        "pending_bytes": String(pendingBytes)
+                    ]
+                )
+            }
+        }
+        queue.asyncAfter(deadline: .now() + .milliseconds(spacingMilliseconds)) { [weak self] in
+            guard let self else { return }
+            self.spacedDrainScheduled = false
+            self.lifecycleLock.lock()
+            let stopped = self.isStopped
+            self.lifecycleLock.unlock()
+            guard !stopped, self.pendingBytes > 0 else { return }
+            self.startWriteSourceIfNeeded()
+        }
+    }
+
     private func startWriteSourceIfNeeded() {
         guard let writeSource, !writeSourceActive else { return }
         writeSource.resume()
@@ -421,16 +821,6 @@ public final class TunSocketBridge: @unchecked Sendable {
         }
     }
 
-    private func framedPacket(_ packet: Data, family: Int32, expectedLength: Int) -> Data {
-        var header = UInt32(family).bigEndian
-        var buffer = Data(capacity: expectedLength)
-        withUnsafeBytes(of: &header) { headerPtr in
-            buffer.append(headerPtr.bindMemory(to: UInt8.self))
-        }
-        buffer.append(packet)
-        return buffer
-    }
-
     private func frameLength(for packet: Data) -> Int? {
         let (length, overflow) = MemoryLayout<UInt32>.size.addingReportingOverflow(packet.count)
         guard !overflow, length <= PacketSizing.maxBridgeFrameBytes else {
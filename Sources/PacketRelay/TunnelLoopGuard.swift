@@ -0,0 +1,59 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Guard against routing loops where a misconfigured route sends the tunnel's own upstream
+/// traffic back into the TUN: flows destined to the engine's gateway address or the host's
+/// tunnel endpoint are rejected before any dial, because dialing them would re-enter the
+/// tunnel and spiral.
+/// Contract: shared across sessions and safe to call from any session queue.
+public final class TunnelLoopGuard: @unchecked Sendable {
+    private let lock = NSLock()
+    private let literalAddresses: Set<String>
+    private let parsedAddresses: [RelaySourceCIDR]
+    private var droppedFlowCount = 0
+
+    /// - Parameter selfAddresses: Address literals owned by the tunnel itself — the TUN
+    ///   interface addresses, the gateway/router address, and the tunnel's remote endpoint.
+    ///   Empty entries are ignored. IPv6 literals match across textual variants.
+    public init(selfAddresses: [String]) {
+        var literals: Set<String> = []
+        var parsed: [RelaySourceCIDR] = []
+        for address in selfAddresses {
+            let normalized = address.lowercased()
+            guard !normalized.isEmpty else {
+                continue
+            }
+            literals.insert(normalized)
+            // A bare address parses to a full-length prefix, giving exact matching that is
+            // robust to IPv6 zero-compression differences.
+            if let cidr = RelaySourceCIDR(normalized) {
+                parsed.append(cidr)
+            }
+        }
+        literalAddresses = literals
+        parsedAddresses = parsed
+    }
+
+    /// Whether the destination is one of the tunnel's own addresses. Matches count toward
+    /// the dropped-flow counter.
+    public func isSelfDestination(host: String) -> Bool {
+        let normalized = host.lowercased()
+        guard literalAddresses.contains(normalized) || parsedAddresses.contains(where: { $0.contains(normalized) }) else {
+            return false
+        }
+        lock.lock()
+        droppedFlowCount += 1
+        lock.unlock()
+        return true
+    }
+
+    /// Total flows rejected because they targeted the tunnel's own addresses.
+    public func droppedFlows() -> Int {
+        lock.lock()
+        defer { lock.unlock() }
+        return droppedFlowCount
+    }
+}
@@ -0,0 +1,115 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// One client-facing CIDR accepted as a packet source on the tunnel interface.
+public struct ClientSubnet: Sendable, Equatable {
+    public let cidr: String
+    let addressBytes: [UInt8]
+    let prefixLength: Int
+
+    /// Parses an IPv4 or IPv6 CIDR string such as `10.0.0.0/24` or `fd00:1::/64`.
+    public init?(cidr: String) {
+        let parts = cidr.split(separator: "/", maxSplits: 1).map(String.init)
+        guard parts.count == 2, let prefixLength = Int(parts[1]), prefixLength >= 0 else {
+            return nil
+        }
+
+        var ipv4 = in_addr()
+        if parts[0].withCString({ inet_pton(AF_INET, $0, &ipv4) }) == 1 {
+            guard prefixLength <= 32 else {
+                return nil
+            }
+            self.cidr = cidr
+            self.addressBytes = withUnsafeBytes(of: ipv4) { Array($0) }
+            self.prefixLength = prefixLength
+            return
+        }
+
+        var ipv6 = in6_addr()
+        if parts[0].withCString({ inet_pton(AF_INET6, $0, &ipv6) }) == 1 {
+            guard prefixLength <= 128 else {
+                return nil
+            }
+            self.cidr = cidr
+            self.addressBytes = withUnsafeBytes(of: ipv6) { Array($0) }
+            self.prefixLength = prefixLength
+            return
+        }
+
+        return nil
+    }
+
+    func contains(sourceBytes: ArraySlice<UInt8>) -> Bool {
+        guard sourceBytes.count == addressBytes.count else {
+            return false
+        }
+        var remaining = prefixLength
+        var sourceIndex = sourceBytes.startIndex
+        for byte in addressBytes {
+            guard remaining > 0 else {
+                return true
+            }
+            let mask: UInt8 = remaining >= 8 ? 0xff : ~(0xff >> remaining)
+            guard (sourceBytes[sourceIndex] & mask) == (byte & mask) else {
+                return false
+            }
+            remaining -= 8
+            sourceIndex = sourceBytes.index(after: sourceIndex)
+        }
+        return true
+    }
+}
+
+/// Source-address policy for packets read from the tunnel interface.
+/// Decision: an empty subnet list keeps today's accept-any behavior so existing
+/// profiles are unaffected; deployments with NAT'd or multi-subnet clients opt in
+/// by listing every CIDR their traffic can legitimately arrive from.
+public struct ClientSubnetPolicy: Sendable, Equatable {
+    public let subnets: [ClientSubnet]
+
+    public init(subnets: [ClientSubnet] = []) {
+        self.subnets = subnets
+    }
+
+    public static let acceptAll = ClientSubnetPolicy()
+
+    public var isEnabled: Bool {
+        !subnets.isEmpty
+    }
+
+    /// Returns `true` when the packet's IP source address falls inside a configured subnet.
+    /// Malformed or truncated packets are rejected whenever the policy is enabled.
+    public func allows(packet: Data) -> Bool {
+        guard isEnabled else {
+            return true
+        }
+        guard let version = packet.first.map({ ($0 >> 4) & 0x0f }) else {
+            return false
+        }
+        let bytes = [UInt8](packet)
+        let sourceBytes: ArraySlice<UInt8>
+        switch version {
+        case 4:
+            guard bytes.count >= 20 else {
+                return false
+            }
+            sourceBytes = bytes[12..<16]
+        case 6:
+            guard bytes.count >= 40 else {
+                return false
+            }
+            sourceBytes = bytes[8..<24]
+        default:
+            return false
+        }
+        return subnets.contains { $0.contains(sourceBytes: sourceBytes) }
+    }
+}
@@ -0,0 +1,218 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Analytics
+import Foundation
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Diagnostic-error policy for packets read from the tunnel interface.
+/// Decision: the engine forwards at the IP layer without ever decrementing TTL, so
+/// traceroute probes aimed through the tunnel would expire invisibly and UDP sent to
+/// the gateway's own virtual address would wait out its timeout. The responder stands
+/// in for the gateway hop: it answers TTL/hop-limit expiry with Time Exceeded, UDP to
+/// the gateway itself with port unreachable, and subnet-policy rejections with an
+/// administratively-prohibited unreachable. Profiles carry no IPv6 gateway address,
+/// so IPv6 packets only get answers when a future profile field supplies one.
+public struct OutboundICMPResponder: Sendable, Equatable {
+    /// Traceroute-probe detection and virtual-path bounds.
+    /// Decision: the relay delivers datagrams straight to their destination, so from the
+    /// client's viewpoint the tunnel really is a two-hop path — gateway, then destination.
+    /// Probes that outlive the virtual path are answered as the destination would answer;
+    /// probe detection sticks to the reserved traceroute port range so ordinary UDP is
+    /// never faked. Synthetic middle hops (longer virtual paths) answer from
+    /// documentation-range addresses that can never collide with real traffic.
+    private enum TraceroutePolicy {
+        static let udpProbePorts: ClosedRange<UInt16> = 33_434...33_534
+        static let defaultVirtualHopCount = 2
+        static let maxVirtualHopCount = 30
+    }
+
+    let gatewayIPv4: [UInt8]
+    let gatewayIPv6: [UInt8]
+    let virtualHopCount: Int
+
+    /// Parses the gateway addresses the responder answers from; an address that does
+    /// not parse disables responses for that IP version, keeping the silent-drop
+    /// behavior existing profiles rely on. `virtualHopCount` is the path length the
+    /// tunnel advertises to traceroute probes, clamped to keep hop addresses valid.
+    public init(
+        ipv4Gateway: String? = nil,
+        ipv6Gateway: String? = nil,
+        virtualHopCount: Int = TraceroutePolicy.defaultVirtualHopCount
+    ) {
+        var ipv4 = in_addr()
+        if let ipv4Gateway, ipv4Gateway.withCString({ inet_pton(AF_INET, $0, &ipv4) }) == 1 {
+            self.gatewayIPv4 = withUnsafeBytes(of: ipv4) { Array($0) }
+        } else {
+            self.gatewayIPv4 = []
+        }
+        var ipv6 = in6_addr()
+        if let ipv6Gateway, ipv6Gateway.withCString({ inet_pton(AF_INET6, $0, &ipv6) }) == 1 {
+            self.gatewayIPv6 = withUnsafeBytes(of: ipv6) { Array($0) }
+        } else {
+            self.gatewayIPv6 = []
+        }
+        self.virtualHopCount = max(2, min(virtualHopCount, TraceroutePolicy.maxVirtualHopCount))
+    }
+
+    public static let disabled = OutboundICMPResponder()
+
+    public var isEnabled: Bool {
+        !gatewayIPv4.isEmpty || !gatewayIPv6.isEmpty
+    }
+
+    /// Returns the ICMP error owed to an outbound packet, or `nil` when the packet
+    /// should be forwarded unanswered. ICMP error offenders never get replies (RFC
+    /// 1122 forbids errors about errors) though echo probes still do, so ICMP-based
+    /// traceroute works; non-initial IPv4 fragments are likewise exempt. Traceroute
+    /// probes walk the virtual path: middle hops answer Time Exceeded from synthetic
+    /// addresses and UDP probes that outlive the path terminate with the destination's
+    /// port unreachable, so a trace completes instead of trailing off in timeouts.
+    public func response(forOutboundPacket packet: Data) -> Data? {
+        let bytes = [UInt8](packet)
+        guard let first = bytes.first else {
+            return nil
+        }
+        switch (first >> 4) & 0x0f {
+        case 4:
+            guard bytes.count >= 20, !gatewayIPv4.isEmpty else {
+                return nil
+            }
+            guard (UInt16(bytes[6]) << 8 | UInt16(bytes[7])) & 0x1fff == 0 else {
+                return nil
+            }
+            let headerLength = Int(bytes[0] & 0x0f) * 4
+            if bytes[9] == 1 {
+                guard headerLength >= 20, bytes.count > headerLength,
+                      Self.informationalICMPv4Types.contains(bytes[headerLength]) else {
+                    return nil
+                }
+            }
+            let destinationIsGateway = bytes[16..<20].elementsEqual(gatewayIPv4)
+            if bytes[8] <= 1, !destinationIsGateway {
+                return ICMPErrorFrames.makeTimeExceeded(expiredPacket: packet, gatewayAddress: gatewayIPv4)
+            }
+            if destinationIsGateway, bytes[9] == 17, headerLength >= 20, bytes.count >= headerLength + 8,
+               !isExemptGatewayPort(UInt16(bytes[headerLength + 2]) << 8 | UInt16(bytes[headerLength + 3])) {
+                return ICMPErrorFrames.makePortUnreachable(undeliverablePacket: packet)
+            }
+            if !destinationIsGateway, isIPv4TracerouteProbe(bytes, headerLength: headerLength) {
+                let ttl = Int(bytes[8])
+                if ttl < virtualHopCount {
+                    return ICMPErrorFrames.makeTimeExceeded(
+                        expiredPacket: packet,
+                        gatewayAddress: Self.syntheticIPv4Hop(ttl)
+                    )
+                }
+                if bytes[9] == 17 {
+                    return ICMPErrorFrames.makePortUnreachable(undeliverablePacket: packet)
+                }
+            }
+            return nil
+        case 6:
+            guard bytes.count >= 40, !gatewayIPv6.isEmpty else {
+                return nil
+            }
+            if bytes[6] == 58 {
+                // ICMPv6 informational types occupy 128 and above.
+                guard bytes.count > 40, bytes[40] >= 128 else {
+                    return nil
+                }
+            }
+            let destinationIsGateway = bytes[24..<40].elementsEqual(gatewayIPv6)
+            if bytes[7] <= 1, !destinationIsGateway {
+                return ICMPErrorFrames.makeTimeExceeded(expiredPacket: packet, gatewayAddress: gatewayIPv6)
+            }
+            if destinationIsGateway, bytes[6] == 17, bytes.count >= 48,
+               !isExemptGatewayPort(UInt16(bytes[42]) << 8 | UInt16(bytes[43])) {
+                return ICMPErrorFrames.makePortUnreachable(undeliverablePacket: packet)
+            }
+            if !destinationIsGateway, isIPv6TracerouteProbe(bytes) {
+                let hopLimit = Int(bytes[7])
+                if hopLimit < virtualHopCount {
+                    return ICMPErrorFrames.makeTimeExceeded(
+                        expiredPacket: packet,
+                        gatewayAddress: Self.syntheticIPv6Hop(hopLimit)
+                    )
+                }
+                if bytes[6] == 17 {
+                    return ICMPErrorFrames.makePortUnreachable(undeliverablePacket: packet)
+                }
+            }
+            return nil
+        default:
+            return nil
+        }
+    }
+
+    /// Returns the administratively-prohibited unreachable owed to a packet a policy
+    /// refused, or `nil` when no gateway address exists for the packet's IP version.
+    public func adminProhibitedResponse(forRejectedPacket packet: Data) -> Data? {
+        guard let first = packet.first else {
+            return nil
+        }
+        switch (first >> 4) & 0x0f {
+        case 4 where !gatewayIPv4.isEmpty:
+            return ICMPErrorFrames.makeAdminProhibited(rejectedPacket: packet, gatewayAddress: gatewayIPv4)
+        case 6 where !gatewayIPv6.isEmpty:
+            return ICMPErrorFrames.makeAdminProhibited(rejectedPacket: packet, gatewayAddress: gatewayIPv6)
+        default:
+            return nil
+        }
+    }
+
+    /// ICMPv4 types that may elicit errors: echo, timestamp, and address-mask probes.
+    private static let informationalICMPv4Types: Set<UInt8> = [0, 8, 13, 14, 17, 18]
+
+    /// Traceroute probes are UDP to the reserved probe port range or an echo request.
+    private func isIPv4TracerouteProbe(_ bytes: [UInt8], headerLength: Int) -> Bool {
+        guard headerLength >= 20, bytes.count >= headerLength + 4 else {
+            return false
+        }
+        switch bytes[9] {
+        case 17:
+            let port = UInt16(bytes[headerLength + 2]) << 8 | UInt16(bytes[headerLength + 3])
+            return TraceroutePolicy.udpProbePorts.contains(port)
+        case 1:
+            return bytes[headerLength] == 8
+        default:
+            return false
+        }
+    }
+
+    private func isIPv6TracerouteProbe(_ bytes: [UInt8]) -> Bool {
+        switch bytes[6] {
+        case 17:
+            guard bytes.count >= 44 else {
+                return false
+            }
+            let port = UInt16(bytes[42]) << 8 | UInt16(bytes[43])
+            return TraceroutePolicy.udpProbePorts.contains(port)
+        case 58:
+            return bytes.count > 40 && bytes[40] == 128
+        default:
+            return false
+        }
+    }
+
+    /// Synthetic middle-hop addresses in the IPv4 documentation range (RFC 5737).
+    private static func syntheticIPv4Hop(_ hop: Int) -> [UInt8] {
+        [198, 51, 100, UInt8(truncatingIfNeeded: hop)]
+    }
+
+    /// Synthetic middle-hop addresses in the IPv6 documentation prefix (RFC 3849).
+    private static func syntheticIPv6Hop(_ hop: Int) -> [UInt8] {
+        [0x20, 0x01, 0x0d, 0xb8] + [UInt8](repeating: 0, count: 11) + [UInt8(truncatingIfNeeded: hop)]
+    }
+
+    /// DNS aimed at the gateway is legitimately served by resolver settings that name
+    /// the router address, so port 53 is never answered with an unreachable.
+    private func isExemptGatewayPort(_ port: UInt16) -> Bool {
+        port == 53
+    }
+}
@@ -0,0 +1,218 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// pf-style normalization pass for outbound packets before they reach the dataplane stack.
+/// Decision: an app on the device controls every header byte it writes into the TUN, so
+/// crafted ambiguities — predictable IP IDs, overlapping fragments, reserved TCP bits,
+/// near-zero TTLs — land directly on the engine's TCP/IP implementation. Scrubbing
+/// normalizes those fields the way pf's `scrub` does so the engine only ever sees one
+/// canonical interpretation of each packet. Packets the scrubber cannot read pass through
+/// untouched; the invalid-packet counters already account for malformed traffic.
+public struct PacketScrubber: Sendable {
+    /// Normalization knobs, clamped so a bad configuration cannot disable delivery.
+    public struct Policy: Sendable, Equatable {
+        /// Replaces the IP ID of unfragmented IPv4 packets with a random value.
+        public let randomizeIPv4ID: Bool
+        /// Raises TTL/hop limit to this floor so single-hop expiry tricks cannot make the
+        /// engine and a downstream observer disagree about delivery. Zero disables the floor.
+        public let minimumTTL: UInt8
+        /// Clears the reserved TCP header bits (including the NS bit) that no negotiated
+        /// option in this stack uses.
+        public let clearReservedTCPFlags: Bool
+
+        public init(
+            randomizeIPv4ID: Bool = true,
+            minimumTTL: UInt8 = 8,
+            clearReservedTCPFlags: Bool = true
+        ) {
+            self.randomizeIPv4ID = randomizeIPv4ID
+            self.minimumTTL = minimumTTL
+            self.clearReservedTCPFlags = clearReservedTCPFlags
+        }
+
+        public static let `default` = Policy()
+    }
+
+    /// Outcome of one scrub: the (possibly rewritten) packet, or a drop for fragments
+    /// that overlap bytes an earlier fragment of the same train already supplied.
+    public enum Verdict: Equatable {
+        case pass(Data)
+        case drop
+    }
+
+    /// Bound on concurrently tracked fragment trains; oldest trains are evicted first.
+    private static let maxTrackedFragmentTrains = 256
+
+    public let isEnabled: Bool
+    private let policy: Policy
+    private let makeIPID: @Sendable () -> UInt16
+    private var fragmentRangesByTrain: [String: [Range<Int>]] = [:]
+    private var fragmentTrainArrivalOrder: [String] = []
+
+    /// Creates an enabled scrubber with the default policy and random IP IDs.
+    public init(policy: Policy = .default) {
+        self.init(enabled: true, policy: policy, makeIPID: { UInt16.random(in: 0...UInt16.max) })
+    }
+
+    /// Internal initializer with ID injection so tests can assert rewritten headers.
+    init(enabled: Bool, policy: Policy = .default, makeIPID: @escaping @Sendable () -> UInt16) {
+        self.isEnabled = enabled
+        self.policy = policy
+        self.makeIPID = makeIPID
+    }
+
+    public static let disabled = PacketScrubber(
+        enabled: false,
+        makeIPID: { UInt16.random(in: 0...UInt16.max) }
+    )
+
+    /// Normalizes one outbound packet. Mutating because overlap detection tracks the
+    /// byte ranges each in-flight fragment train has already covered.
+    public mutating func scrub(_ packet: Data) -> Verdict {
+        guard isEnabled, let first = packet.first else {
+            return .pass(packet)
+        }
+        switch (first >> 4) & 0x0f {
+        case 4:
+            return scrubIPv4(packet)
+        case 6:
+            return scrubIPv6(packet)
+        default:
+            return .pass(packet)
+        }
+    }
+
+    private mutating func scrubIPv4(_ packet: Data) -> Verdict {
+        var bytes = [UInt8](packet)
+        let headerLength = Int(bytes[0] & 0x0f) * 4
+        guard bytes.count >= 20, headerLength >= 20, bytes.count >= headerLength else {
+            return .pass(packet)
+        }
+
+        let fragmentField = UInt16(bytes[6]) << 8 | UInt16(bytes[7])
+        let fragmentOffset = Int(fragmentField & 0x1fff) * 8
+        let moreFragments = fragmentField & 0x2000 != 0
+        if fragmentOffset != 0 || moreFragments {
+            let payloadLength = max(0, bytes.count - headerLength)
+            guard admitFragment(
+                train: ipv4FragmentTrainKey(bytes),
+                range: fragmentOffset ..< fragmentOffset + payloadLength,
+                isLast: !moreFragments
+            ) else {
+                return .drop
+            }
+        }
+
+        var headerChecksum = UInt16(bytes[10]) << 8 | UInt16(bytes[11])
+
+        if policy.minimumTTL > 0, bytes[8] > 0, bytes[8] < policy.minimumTTL {
+            let oldWord = UInt16(bytes[8]) << 8 | UInt16(bytes[9])
+            bytes[8] = policy.minimumTTL
+            let newWord = UInt16(bytes[8]) << 8 | UInt16(bytes[9])
+            headerChecksum = Self.adjustChecksum(headerChecksum, old: oldWord, new: newWord)
+        }
+
+        if policy.randomizeIPv4ID, fragmentOffset == 0, !moreFragments {
+            let oldID = UInt16(bytes[4]) << 8 | UInt16(bytes[5])
+            let newID = makeIPID()
+            bytes[4] = UInt8(newID >> 8)
+            bytes[5] = UInt8(newID & 0xff)
+            headerChecksum = Self.adjustChecksum(headerChecksum, old: oldID, new: newID)
+        }
+
+        bytes[10] = UInt8(headerChecksum >> 8)
+        bytes[11] = UInt8(headerChecksum & 0xff)
+
+        if policy.clearReservedTCPFlags, bytes[9] == 6, fragmentOffset == 0,
+           bytes.count >= headerLength + 20 {
+            clearReservedTCPBits(&bytes, tcpOffset: headerLength)
+        }
+
+        return .pass(Data(bytes))
+    }
+
+    private mutating func scrubIPv6(_ packet: Data) -> Verdict {
+        var bytes = [UInt8](packet)
+        guard bytes.count >= 40 else {
+            return .pass(packet)
+        }
+
+        if policy.minimumTTL > 0, bytes[7] > 0, bytes[7] < policy.minimumTTL {
+            bytes[7] = policy.minimumTTL
+        }
+
+        // Extension-header chains are left to the engine's own validation; only the
+        // common direct-TCP shape is normalized here.
+        if policy.clearReservedTCPFlags, bytes[6] == 6, bytes.count >= 40 + 20 {
+            clearReservedTCPBits(&bytes, tcpOffset: 40)
+        }
+
+        return .pass(Data(bytes))
+    }
+
+    /// Clears the four reserved bits that share the data-offset byte, keeping the TCP
+    /// checksum consistent with the rewritten header.
+    private func clearReservedTCPBits(_ bytes: inout [UInt8], tcpOffset: Int) {
+        let reservedBits = bytes[tcpOffset + 12] & 0x0f
+        guard reservedBits != 0 else {
+            return
+        }
+        let oldWord = UInt16(bytes[tcpOffset + 12]) << 8 | UInt16(bytes[tcpOffset + 13])
+        bytes[tcpOffset + 12] &= 0xf0
+        let newWord = UInt16(bytes[tcpOffset + 12]) << 8 | UInt16(bytes[tcpOffset + 13])
+        let checksumOffset = tcpOffset + 16
+        guard bytes.count >= checksumOffset + 2 else {
+            return
+        }
+        let oldChecksum = UInt16(bytes[checksumOffset]) << 8 | UInt16(bytes[checksumOffset + 1])
+        let newChecksum = Self.adjustChecksum(oldChecksum, old: oldWord, new: newWord)
+        bytes[checksumOffset] = UInt8(newChecksum >> 8)
+        bytes[checksumOffset + 1] = UInt8(newChecksum & 0xff)
+    }
+
+    /// Admits a fragment when its byte range extends the train without overlapping what
+    /// earlier fragments already delivered. The last fragment retires the train's state.
+    private mutating func admitFragment(train: String, range: Range<Int>, isLast: Bool) -> Bool {
+        var ranges = fragmentRangesByTrain[train] ?? []
+        if !range.isEmpty, ranges.contains(where: { $0.overlaps(range) }) {
+            return false
+        }
+        if isLast {
+            if fragmentRangesByTrain.removeValue(forKey: train) != nil {
+                fragmentTrainArrivalOrder.removeAll { $0 == train }
+            }
+            return true
+        }
+        if fragmentRangesByTrain[train] == nil {
+            fragmentTrainArrivalOrder.append(train)
+            if fragmentTrainArrivalOrder.count > Self.maxTrackedFragmentTrains {
+                let evicted = fragmentTrainArrivalOrder.removeFirst()
+                fragmentRangesByTrain.removeValue(forKey: evicted)
+            }
+        }
+        ranges.append(range)
+        fragmentRangesByTrain[train] = ranges
+        return true
+    }
+
+    private func ipv4FragmentTrainKey(_ bytes: [UInt8]) -> String {
+        let id = UInt16(bytes[4]) << 8 | UInt16(bytes[5])
+        let source = bytes[12..<16].map(String.init).joined(separator: ".")
+        let destination = bytes[16..<20].map(String.init).joined(separator: ".")
+        return "\(source)>\(destination)/\(bytes[9])#\(id)"
+    }
+
+    /// RFC 1624 incremental checksum update for one rewritten 16-bit header word.
+    private static func adjustChecksum(_ checksum: UInt16, old: UInt16, new: UInt16) -> UInt16 {
+        var sum = UInt32(~checksum & 0xffff)
+        sum &+= UInt32(~old & 0xffff)
+        sum &+= UInt32(new)
+        while sum > 0xffff {
+            sum = (sum & 0xffff) &+ (sum >> 16)
+        }
+        return ~UInt16(sum) & 0xffff
+    }
+}
@@ -25,6 +25,22 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         static let maxPacketLimit = 96
     }
 
+    /// Caps on one synchronous burst of bridge writes.
+    /// Decision: a large `readPackets` batch is written in bounded slices with continuation
+    /// rescheduled onto the I/O queue, so one bursty download cannot hold a queue iteration
+    /// for an arbitrary duration.
+    public struct OutboundWriteSliceLimits: Sendable {
+        public let maxPackets: Int
+        public let maxBytes: Int
+
+        public init(maxPackets: Int = 64, maxBytes: Int = 256 * 1024) {
+            self.maxPackets = max(1, maxPackets)
+            self.maxBytes = max(1, maxBytes)
+        }
+
+        public static let `default` = OutboundWriteSliceLimits()
+    }
+
     /// Provider-owned backlog retained when the bridge is saturated but not failed.
     fileprivate struct PendingOutboundBatch {
         let packets: [Data]
@@ -43,6 +59,15 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         }
     }
 
+    /// Cumulative outbound slice accounting surfaced in health samples.
+    /// Utilization contrasts packets written against the slice cap so deployments can
+    /// tell whether the configured `OutboundWriteSliceLimits` bound bursts or idles.
+    fileprivate struct OutboundSliceStats {
+        var slicesWritten = 0
+        var fullSlices = 0
+        var packetsWritten = 0
+    }
+
     /// Mutable provider state shared between startup/shutdown tasks and packet I/O callbacks.
     /// Access invariant: every read/write goes through `withState(_:)`.
     private struct ProviderState {
@@ -56,9 +81,18 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         var cumulativeInboundPackets = 0
         var cumulativeInboundBytes = 0
         var lastHealthSampleAt: Date?
+        var clientSubnetPolicy = ClientSubnetPolicy.acceptAll
+        var outboundICMPResponder = OutboundICMPResponder.disabled
+        var packetScrubber = PacketScrubber.disabled
+        var cumulativeRejectedSourcePackets = 0
+        var cumulativeScrubDroppedPackets = 0
+        var cumulativeICMPErrorResponses = 0
+        var cumulativeOversizedInboundPackets = 0
+        var interfaceMTU = 0
         var waitingForBackpressureRelief = false
         var isStopping = false
         var pendingOutbound: [PendingOutboundBatch] = []
+        var outboundSliceStats = OutboundSliceStats()
         var startupTask: Task<Void, Never>?
         var startupGeneration: UInt64 = 0
         var activeStartupID: UInt64?
@@ -119,6 +153,15 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
 
     private let signposts = SignpostSupport(subsystem: "com.vpnbridge.tunnel", category: "control")
 
+    /// Per-slice caps applied to outbound bridge writes.
+    /// Contract: set before `startTunnel`; the packet I/O queue reads it without locking.
+    public var outboundWriteSliceLimits = OutboundWriteSliceLimits.default
+
+    /// Optional out-of-band tap receiving copies of selected frames for external analysis
+    /// tooling, independent of the telemetry event path.
+    /// Contract: set before `startTunnel`; the packet I/O queue reads it without locking.
+    public var packetMirror: PacketMirror?
+
     public override init() {
         let bootstrapLogger = StructuredLogger(
             sink: FanoutLogSink(
@@ -167,6 +210,13 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
             completion.call(error)
             return
         }
+        withState { state in
+            state.clientSubnetPolicy = ClientSubnetPolicy(subnets: profile.clientSubnets)
+            // Profiles define no IPv6 gateway address, so only IPv4 offenders get answers today.
+            state.outboundICMPResponder = OutboundICMPResponder(ipv4Gateway: profile.ipv4Router)
+            state.packetScrubber = profile.packetScrubbingEnabled ? PacketScrubber() : .disabled
+            state.interfaceMTU = profile.mtu
+        }
         let supersededComponents = takeCleanupSnapshot(markStopping: false)
         let startupID = beginStartup()
 
@@ -276,6 +326,8 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                         "ipv6_settings_installed": String(settings.ipv6Settings != nil),
                         "preflight_path_supports_ipv6": String(defaultPath.supportsIPv6),
                         "tcp_multipath_handover_enabled": String(profile.tcpMultipathHandoverEnabled),
+                        "dns_fast_path_enabled": String(profile.dnsFastPathEnabled),
+                        "packet_scrubbing_enabled": String(profile.packetScrubbingEnabled),
                         "tcp_waiting_restart_enabled": "true",
                         "tcp_waiting_restart_max": "1",
                         "udp_waiting_replacement_enabled": "true",
@@ -320,6 +372,13 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
             let snapshot = takeCleanupSnapshot(markStopping: true)
             snapshot.startupTask?.cancel()
             if let telemetryWorker = snapshot.telemetryWorker {
+                // Abort live flows toward the client before the interface disappears, so apps
+                // see an immediate reset/unreachable instead of waiting out their own timeouts.
+                let teardown = await telemetryWorker.makeFlowTeardownFrames()
+                if !teardown.packets.isEmpty {
+                    let protocols = teardown.families.map(Self.protocolNumber(for:))
+                    _ = packetFlow.writePackets(teardown.packets, withProtocols: protocols)
+                }
                 await telemetryWorker.stopAndWait()
             }
             if let telemetryWorker = snapshot.startupTelemetryWorker {
@@ -438,6 +497,87 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
     private func handleOutboundPackets(_ packets: [Data], protocols: [NSNumber]) {
         dispatchPrecondition(condition: .onQueue(ioQueue))
 
+        var packets = packets
+        var protocols = protocols
+        let (subnetPolicy, icmpResponder, scrubberEnabled) = withState {
+            ($0.clientSubnetPolicy, $0.outboundICMPResponder, $0.packetScrubber.isEnabled)
+        }
+        if scrubberEnabled {
+            scrubOutboundPackets(&packets, protocols: &protocols)
+            guard !packets.isEmpty else {
+                return
+            }
+        }
+        if subnetPolicy.isEnabled {
+            var admittedPackets: [Data] = []
+            var admittedProtocols: [NSNumber] = []
+            admittedPackets.reserveCapacity(packets.count)
+            admittedProtocols.reserveCapacity(protocols.count)
+            var rejectedCount = 0
+            var rejectionResponses: [Data] = []
+            for (index, packet) in packets.enumerated() {
+                guard subnetPolicy.allows(packet: packet) else {
+                    rejectedCount += 1
+                    if let response = icmpResponder.adminProhibitedResponse(forRejectedPacket: packet) {
+                        rejectionResponses.append(response)
+                    }
+                    continue
+                }
+                admittedPackets.append(packet)
+                if protocols.indices.contains(index) {
+                    admittedProtocols.append(protocols[index])
+                }
+            }
+            if rejectedCount > 0 {
+                let (logger, shouldLogFirstRejection) = withState { state -> (StructuredLogger, Bool) in
+                    let wasZero = state.cumulativeRejectedSourcePackets == 0
+                    state.cumulativeRejectedSourcePackets = Self.saturatingAdd(state.cumulativeRejectedSourcePackets, rejectedCount)
+                    return (state.logger, wasZero)
+                }
+                if shouldLogFirstRejection {
+                    Task {
+                        await logger.log(
+                            level: .warning,
+                            phase: .packetIn,
+                            category: .control,
+                            component: "PacketTunnelProviderShell",
+                            event: "outbound-source-rejected",
+                            message: "Dropped outbound packets whose source address is outside the configured client subnets",
+                            metadata: [
+                                "rejected_packets": String(rejectedCount)
+                            ]
+                        )
+                    }
+                }
+            }
+            writeICMPErrorResponses(rejectionResponses)
+            packets = admittedPackets
+            protocols = admittedProtocols
+            guard !packets.isEmpty else {
+                return
+            }
+        }
+
+        if icmpResponder.isEnabled {
+            var answeredIndices: [Int] = []
+            var responses: [Data] = []
+            for (index, packet) in packets.enumerated() {
+                if let response = icmpResponder.response(forOutboundPacket: packet) {
+                    answeredIndices.append(index)
+                    responses.append(response)
+                }
+            }
+            if !responses.isEmpty {
+                writeICMPErrorResponses(responses)
+                let answered = Set(answeredIndices)
+                packets = packets.enumerated().filter { !answered.contains($0.offset) }.map(\.element)
+                protocols = protocols.enumerated().filter { !answered.contains($0.offset) }.map(\.element)
+                guard !packets.isEmpty else {
+                    return
+                }
+            }
+        }
+
         let packetCount = packets.count
         let byteCount = Self.saturatingByteCount(packets)
         let snapshot = withState { state -> (logger: StructuredLogger, bridge: TunSocketBridge?, telemetryWorker: PacketTelemetryWorker?, isStopping: Bool) in
@@ -493,11 +633,19 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                     ]
                 )
             }
+        case .sliced(let pendingBatch):
+            withState { state in
+                state.pendingOutbound.append(pendingBatch)
+            }
+            ioQueue.async { [weak self] in
+                self?.continuePendingOutboundDrain()
+            }
         case .failed(let errorCode):
             failTunnelForBridgeWrite(errorCode: errorCode, logger: snapshot.logger)
         }
 
         emitHealthSampleIfNeeded(trigger: "outbound", logger: snapshot.logger)
+        packetMirror?.offer(packets: packets, direction: .outbound)
 
         guard let telemetryWorker = snapshot.telemetryWorker else {
             return
@@ -511,42 +659,153 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         )
     }
 
+    /// Runs the pf-style normalization pass over one outbound batch. Rewritten packets
+    /// replace the originals in place; overlapping fragments are removed along with
+    /// their protocol entries. Runs under `withState` because overlap detection keeps
+    /// per-fragment-train state inside the scrubber.
+    private func scrubOutboundPackets(_ packets: inout [Data], protocols: inout [NSNumber]) {
+        dispatchPrecondition(condition: .onQueue(ioQueue))
+        var scrubbedPackets: [Data] = []
+        var scrubbedProtocols: [NSNumber] = []
+        scrubbedPackets.reserveCapacity(packets.count)
+        scrubbedProtocols.reserveCapacity(protocols.count)
+        var droppedCount = 0
+        let inputPackets = packets
+        let inputProtocols = protocols
+        let (logger, shouldLogFirstDrop) = withState { state -> (StructuredLogger, Bool) in
+            for (index, packet) in inputPackets.enumerated() {
+                switch state.packetScrubber.scrub(packet) {
+                case .pass(let scrubbed):
+                    scrubbedPackets.append(scrubbed)
+                    if inputProtocols.indices.contains(index) {
+                        scrubbedProtocols.append(inputProtocols[index])
+                    }
+                case .drop:
+                    droppedCount += 1
+                }
+            }
+            let wasZero = state.cumulativeScrubDroppedPackets == 0
+            if droppedCount > 0 {
+                state.cumulativeScrubDroppedPackets = Self.saturatingAdd(state.cumulativeScrubDroppedPackets, droppedCount)
+            }
+            return (state.logger, wasZero && droppedCount > 0)
+        }
+        if shouldLogFirstDrop {
+            let dropped = droppedCount
+            Task {
+                await logger.log(
+                    level: .warning,
+                    phase: .packetIn,
+                    category: .control,
+                    component: "PacketTunnelProviderShell",
+                    event: "outbound-scrub-dropped",
+                    message: "Dropped outbound fragments that overlap bytes an earlier fragment already supplied",
+                    metadata: [
+                        "dropped_packets": String(dropped)
+                    ]
+                )
+            }
+        }
+        packets = scrubbedPackets
+        protocols = scrubbedProtocols
+    }
+
+    /// Writes synthesized ICMP error frames back to the device.
+    /// Best effort: a refused write drops the diagnostics instead of failing the
+    /// tunnel, leaving the offender no worse off than the silent drop it replaced.
+    private func writeICMPErrorResponses(_ responses: [Data]) {
+        dispatchPrecondition(condition: .onQueue(ioQueue))
+        guard !responses.isEmpty else {
+            return
+        }
+        let protocols = responses.map { response in
+            Self.protocolNumber(
+                for: response.first.map {
+                    (($0 >> 4) & 0x0F) == 6 ? Int32(AF_INET6) : Int32(AF_INET)
+                } ?? Int32(AF_INET)
+            )
+        }
+        // Docs: https://developer.apple.com/documentation/networkextension/nepackettunnelflow/writepackets(_:withprotocols:)
+        _ = packetFlow.writePackets(responses, withProtocols: protocols)
+        let (logger, shouldLogFirstResponse) = withState { state -> (StructuredLogger, Bool) in
+            let wasZero = state.cumulativeICMPErrorResponses == 0
+            state.cumulativeICMPErrorResponses = Self.saturatingAdd(state.cumulativeICMPErrorResponses, responses.count)
+            return (state.logger, wasZero)
+        }
+        if shouldLogFirstResponse {
+            Task {
+                await logger.log(
+                    level: .info,
+                    phase: .packetOut,
+                    category: .control,
+                    component: "PacketTunnelProviderShell",
+                    event: "icmp-error-responded",
+                    message: "Answered undeliverable outbound packets with synthesized ICMP errors",
+                    metadata: [
+                        "response_packets": String(responses.count)
+                    ]
+                )
+            }
+        }
+    }
+
     /// Handles inbound packets flowing dataplane -> device.
     /// - Parameters:
     ///   - packets: Raw IP packets read from bridge.
     ///   - families: Address family values aligned by index with `packets`.
-    private func handleInboundPackets(_ packets: [Data], families: [Int32]) {
+    private func handleInboundPackets(_ rawPackets: [Data], families rawFamilies: [Int32]) {
         dispatchPrecondition(condition: .onQueue(ioQueue))
 
-        let packetCount = packets.count
-        let byteCount = Self.saturatingByteCount(packets)
-        let snapshot = withState { state -> (logger: StructuredLogger, telemetryWorker: PacketTelemetryWorker?, isStopping: Bool) in
-            if !state.isStopping, !packets.isEmpty {
+        let packetCount = rawPackets.count
+        let byteCount = Self.saturatingByteCount(rawPackets)
+        let snapshot = withState { state -> (logger: StructuredLogger, telemetryWorker: PacketTelemetryWorker?, isStopping: Bool, bridge: TunSocketBridge?, interfaceMTU: Int) in
+            if !state.isStopping, !rawPackets.isEmpty {
                 state.cumulativeInboundPackets = Self.saturatingAdd(state.cumulativeInboundPackets, packetCount)
                 state.cumulativeInboundBytes = Self.saturatingAdd(state.cumulativeInboundBytes, byteCount)
             }
             return (
                 logger: state.logger,
                 telemetryWorker: state.telemetryWorker,
-                isStopping: state.isStopping
+                isStopping: state.isStopping,
+                bridge: state.tunBridge,
+                interfaceMTU: state.interfaceMTU
             )
         }
-        guard !snapshot.isStopping, !packets.isEmpty else {
+        guard !snapshot.isStopping, !rawPackets.isEmpty else {
             return
         }
 
+        var packets = rawPackets
+        var families = rawFamilies
+        // Delivering a frame larger than the interface MTU would hand the device a packet
+        // its stack may truncate or discard unpredictably. Rejecting it with Packet Too Big
+        // toward the sender lets path MTU discovery shrink the flow instead.
+        if snapshot.interfaceMTU > 0, rawPackets.contains(where: { $0.count > snapshot.interfaceMTU }) {
+            rejectOversizedInboundPackets(
+                &packets,
+                families: &families,
+                mtu: snapshot.interfaceMTU,
+                bridge: snapshot.bridge,
+                logger: snapshot.logger
+            )
+            guard !packets.isEmpty else {
+                return
+            }
+        }
+
         var protocols: [NSNumber] = []
         protocols.reserveCapacity(packets.count)
 
         for (index, packet) in packets.enumerated() {
+            let family: Int32
             if families.indices.contains(index) {
-                protocols.append(NSNumber(value: families[index]))
+                family = families[index]
             } else {
-                let inferred: Int32 = packet.first.map {
+                family = packet.first.map {
                     (($0 >> 4) & 0x0F) == 6 ? Int32(AF_INET6) : Int32(AF_INET)
                 } ?? Int32(AF_INET)
-                protocols.append(NSNumber(value: inferred))
             }
+            protocols.append(Self.protocolNumber(for: family))
         }
 
         // Docs: https://developer.apple.com/documentation/networkextension/nepackettunnelflow/writepackets(_:withprotocols:)
@@ -568,6 +827,7 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         }
 
         emitHealthSampleIfNeeded(trigger: "inbound", logger: snapshot.logger)
+        packetMirror?.offer(packets: packets, direction: .inbound)
 
         guard let telemetryWorker = snapshot.telemetryWorker else {
             return
@@ -581,6 +841,75 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         )
     }
 
+    /// Removes inbound packets larger than the interface MTU and answers each with a
+    /// Fragmentation Needed / Packet Too Big carrying the MTU, written back through the
+    /// bridge so the dataplane routes the error to the oversized frame's sender.
+    /// Best effort like `writeICMPErrorResponses`: an offender the builder cannot quote
+    /// is still dropped and counted, just unanswered.
+    private func rejectOversizedInboundPackets(
+        _ packets: inout [Data],
+        families: inout [Int32],
+        mtu: Int,
+        bridge: TunSocketBridge?,
+        logger: StructuredLogger
+    ) {
+        dispatchPrecondition(condition: .onQueue(ioQueue))
+
+        let familiesAligned = families.count == packets.count
+        var kept: [Data] = []
+        kept.reserveCapacity(packets.count)
+        var keptFamilies: [Int32] = []
+        keptFamilies.reserveCapacity(packets.count)
+        var rejectedCount = 0
+
+        for (index, packet) in packets.enumerated() {
+            guard packet.count > mtu else {
+                kept.append(packet)
+                if familiesAligned {
+                    keptFamilies.append(families[index])
+                }
+                continue
+            }
+            rejectedCount += 1
+            if let bridge, let response = ICMPErrorFrames.makePacketTooBig(oversizedPacket: packet, mtu: mtu) {
+                let family = response.first.map {
+                    (($0 >> 4) & 0x0F) == 6 ? Int32(AF_INET6) : Int32(AF_INET)
+                } ?? Int32(AF_INET)
+                _ = bridge.writePacket(response, ipVersionHint: family)
+            }
+        }
+
+        packets = kept
+        families = familiesAligned ? keptFamilies : families
+        guard rejectedCount > 0 else {
+            return
+        }
+
+        let shouldLogFirstRejection = withState { state -> Bool in
+            let wasZero = state.cumulativeOversizedInboundPackets == 0
+            state.cumulativeOversizedInboundPackets = Self.saturatingAdd(state.cumulativeOversizedInboundPackets, rejectedCount)
+            return wasZero
+        }
+        if shouldLogFirstRejection {
+            let droppedCount = rejectedCount
+            Task {
+                await logger.log(
+                    level: .notice,
+                    phase: .packetOut,
+                    category: .control,
+                    component: "PacketTunnelProviderShell",
+                    event: "inbound-packet-too-big",
+                    result: "rejected",
+                    message: "Rejected inbound packets larger than the interface MTU and answered with Packet Too Big",
+                    metadata: [
+                        "mtu": String(mtu),
+                        "rejected_packets": String(droppedCount)
+                    ]
+                )
+            }
+        }
+    }
+
     /// Resumes packet read loop after backpressure drops below threshold.
     private func resumePacketReadLoopIfNeeded() {
         ioQueue.async { [weak self] in
@@ -620,12 +949,22 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
             betterPathRetryMinimumElapsed: 0.75,
             multipathServiceType: profile.tcpMultipathHandoverEnabled ? .handover : nil
         )
+        // Flows targeting the tunnel's own addresses would re-enter the TUN and loop; the
+        // guard rejects them before any dial.
+        let loopGuard = TunnelLoopGuard(selfAddresses: [
+            profile.ipv4Address,
+            profile.ipv4Router,
+            profile.ipv6Enabled ? profile.ipv6Address : "",
+            profile.tunnelRemoteAddress,
+        ])
         let server = Socks5Server(
             provider: self,
             queue: relayQueue,
             mtu: profile.mtu,
             logger: logger,
-            tcpPathSettings: tcpPathSettings
+            tcpPathSettings: tcpPathSettings,
+            loopGuard: loopGuard,
+            enableDNSFastPath: profile.dnsFastPathEnabled
         )
         return try await withCheckedThrowingContinuation { continuation in
             server.start(port: profile.engineSocksPort) { result in
@@ -717,7 +1056,8 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                 flowTTLSeconds: 60
             ),
             signatureClassifier: classifier,
-            addressScopeClassifier: AddressScopeClassifier(prefixes: profile.addressScopePrefixes)
+            addressScopeClassifier: AddressScopeClassifier(prefixes: profile.addressScopePrefixes),
+            validationMode: profile.packetValidationMode
         )
 
         return PacketTelemetryWorker(
@@ -779,7 +1119,14 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                 } else {
                     telemetrySnapshot = .empty
                 }
-                response = .snapshot(telemetrySnapshot)
+                if request.compressSnapshot == true,
+                   let compressed = try? TunnelTelemetryMessageCodec.compressSnapshot(telemetrySnapshot) {
+                    response = .compressedSnapshot(compressed)
+                } else {
+                    // Compression is best effort: a host that asked for it must still accept a
+                    // plain snapshot, so unavailability never fails the query.
+                    response = .snapshot(telemetrySnapshot)
+                }
 
             case .clearRecentEvents:
                 if let telemetryWorker = snapshot.telemetryWorker {
@@ -834,6 +1181,7 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                 .replacingOccurrences(of: "${SOCKS_PORT}", with: String(socksPort))
                 .replacingOccurrences(of: "${MTU}", with: String(profile.mtu))
                 .replacingOccurrences(of: "${IPV4}", with: profile.ipv4Address)
+                .replacingOccurrences(of: "${IPV4_ROUTER}", with: profile.ipv4Router)
                 .replacingOccurrences(of: "${IPV6}", with: profile.ipv6Address)
         }
 
@@ -1083,6 +1431,22 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         }
     }
 
+    // Shared boxed family numbers: inbound emission appends one NSNumber per packet, and boxing a
+    // fresh object per frame is measurable allocator churn at bulk-download batch rates.
+    private static let ipv4ProtocolNumber = NSNumber(value: Int32(AF_INET))
+    private static let ipv6ProtocolNumber = NSNumber(value: Int32(AF_INET6))
+
+    private static func protocolNumber(for family: Int32) -> NSNumber {
+        switch family {
+        case Int32(AF_INET6):
+            return ipv6ProtocolNumber
+        case Int32(AF_INET):
+            return ipv4ProtocolNumber
+        default:
+            return NSNumber(value: family)
+        }
+    }
+
     private static func saturatingByteCount(_ packets: [Data]) -> Int {
         packets.reduce(0) { total, packet in
             saturatingAdd(total, packet.count)
@@ -1324,6 +1688,11 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
             }
             let bridgeBackpressured = state.tunBridge?.isBackpressured() ?? false
             let telemetrySnapshot = state.telemetryWorker?.snapshot()
+            let sliceStats = state.outboundSliceStats
+            let sliceCapacity = Double(sliceStats.slicesWritten) * Double(self.outboundWriteSliceLimits.maxPackets)
+            let sliceUtilizationPercent = sliceCapacity > 0
+                ? Int((Double(sliceStats.packetsWritten) * 100 / sliceCapacity).rounded())
+                : 0
 
             return (
                 true,
@@ -1335,6 +1704,9 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                     "inbound_bytes_total": String(state.cumulativeInboundBytes),
                     "pending_outbound_packets": String(pendingPackets),
                     "pending_outbound_bytes": String(pendingBytes),
+                    "outbound_slices_written": String(sliceStats.slicesWritten),
+                    "outbound_full_slices": String(sliceStats.fullSlices),
+                    "outbound_slice_utilization_pct": String(sliceUtilizationPercent),
                     "bridge_backpressured": String(bridgeBackpressured),
                     "waiting_for_backpressure_relief": String(state.waitingForBackpressureRelief),
                     "packet_batches_accepted": String(telemetrySnapshot?.acceptedBatches ?? 0),
@@ -1367,17 +1739,35 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         }
     }
 
-    /// Attempts to write one queued packet batch into the bridge until it completes, saturates, or fails.
+    /// Attempts to write one queued packet batch into the bridge until it completes a slice,
+    /// saturates, or fails. At most `outboundWriteSliceLimits` packets/bytes are written per
+    /// call; the remainder continues on a later queue iteration.
     /// Preconditions: caller runs on `ioQueue` so batch ordering remains deterministic.
     private func writePendingBatch(_ batch: PendingOutboundBatch, bridge: TunSocketBridge) -> PendingBatchWriteResult {
         dispatchPrecondition(condition: .onQueue(ioQueue))
 
+        let limits = outboundWriteSliceLimits
         var nextIndex = batch.nextIndex
+        var slicePackets = 0
+        var sliceBytes = 0
         while nextIndex < batch.packets.count {
+            guard slicePackets < limits.maxPackets, sliceBytes < limits.maxBytes else {
+                recordOutboundSlice(packetsWritten: slicePackets, hitSliceCap: true)
+                return .sliced(
+                    PendingOutboundBatch(
+                        packets: batch.packets,
+                        families: batch.families,
+                        nextIndex: nextIndex
+                    )
+                )
+            }
             switch bridge.writePacket(batch.packets[nextIndex], ipVersionHint: batch.families[nextIndex]) {
             case .accepted:
+                slicePackets += 1
+                sliceBytes = Self.saturatingAdd(sliceBytes, batch.packets[nextIndex].count)
                 nextIndex += 1
             case .backpressured:
+                recordOutboundSlice(packetsWritten: slicePackets, hitSliceCap: false)
                 return .backpressured(
                     PendingOutboundBatch(
                         packets: batch.packets,
@@ -1390,10 +1780,27 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
             }
         }
 
+        recordOutboundSlice(packetsWritten: slicePackets, hitSliceCap: false)
         return .complete
     }
 
+    /// Accumulates one drain slice into the utilization counters.
+    private func recordOutboundSlice(packetsWritten: Int, hitSliceCap: Bool) {
+        guard packetsWritten > 0 else {
+            return
+        }
+        withState { state in
+            state.outboundSliceStats.slicesWritten = Self.saturatingAdd(state.outboundSliceStats.slicesWritten, 1)
+            state.outboundSliceStats.packetsWritten = Self.saturatingAdd(state.outboundSliceStats.packetsWritten, packetsWritten)
+            if hitSliceCap {
+                state.outboundSliceStats.fullSlices = Self.saturatingAdd(state.outboundSliceStats.fullSlices, 1)
+            }
+        }
+    }
+
     /// Drains provider-owned pending outbound batches after the bridge signals relief.
+    /// Sliced batches rotate to the back of the queue so every queued batch makes
+    /// progress under sustained load instead of draining strictly front-to-back.
     /// - Returns: Whether draining progressed or hit a terminal bridge failure.
     private func drainPendingOutboundIfPossible() -> PendingDrainOutcome {
         dispatchPrecondition(condition: .onQueue(ioQueue))
@@ -1437,6 +1844,19 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
                     state.waitingForBackpressureRelief = true
                 }
                 return .progressed
+            case .sliced(let updatedBatch):
+                withState { state in
+                    guard !state.pendingOutbound.isEmpty else { return }
+                    // Round-robin: a sliced batch rotates to the back so one large
+                    // burst cannot starve batches queued behind it. IP imposes no
+                    // cross-packet ordering, and packets within a batch stay ordered.
+                    state.pendingOutbound.removeFirst()
+                    state.pendingOutbound.append(updatedBatch)
+                }
+                ioQueue.async { [weak self] in
+                    self?.continuePendingOutboundDrain()
+                }
+                return .progressed
             case .failed(let errorCode):
                 withState { state in
                     state.pendingOutbound.removeAll(keepingCapacity: false)
@@ -1448,6 +1868,19 @@ open class PacketTunnelProviderShell: NEPacketTunnelProvider {
         }
     }
 
+    /// Continues a sliced outbound batch on a fresh queue iteration so one large
+    /// `readPackets` burst cannot monopolize the packet I/O queue.
+    private func continuePendingOutboundDrain() {
+        dispatchPrecondition(condition: .onQueue(ioQueue))
+
+        switch drainPendingOutboundIfPossible() {
+        case .progressed:
+            resumeReadLoopAfterWriteIfPossible()
+        case .failed:
+            break
+        }
+    }
+
     /// Starts the next `readPackets` cycle only when there is no local backlog and the bridge can accept more data.
     private func resumeReadLoopAfterWriteIfPossible() {
         dispatchPrecondition(condition: .onQueue(ioQueue))
@@ -1678,6 +2111,7 @@ extension PacketTunnelProviderShell: @unchecked Sendable {}
 private enum PendingBatchWriteResult {
     case complete
     case backpressured(PacketTunnelProviderShell.PendingOutboundBatch)
+    case sliced(PacketTunnelProviderShell.PendingOutboundBatch)
     case failed(errorCode: Int32)
 }
 
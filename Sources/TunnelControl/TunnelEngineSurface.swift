@@ -0,0 +1,39 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Analytics
+import Observability
+import PacketRelay
+
+/// Documented stable surface for hosts embedding the tunnel engine.
+/// Decision: embedders kept reaching into individual targets for the handful of types a
+/// provider actually needs, which couples them to module layout that moves between
+/// releases. Everything aliased here is the supported contract — these names keep their
+/// shape across refactors, while the underlying module paths stay free to reorganize.
+/// `import TunnelControl` alone is enough to configure, run, and observe the engine.
+public enum TunnelEngine {
+    /// Provider-side engine shell; subclass it from the NE packet tunnel extension.
+    public typealias Shell = PacketTunnelProviderShell
+    /// Declarative engine configuration carried in provider configuration dictionaries.
+    public typealias Profile = TunnelProfile
+    /// Encoder/decoder between `Profile` and NE provider configuration dictionaries.
+    public typealias ProfileManager = TunnelProfileManager
+
+    /// Policy evaluator whose backing document can be hot-swapped while flows run.
+    public typealias Policy = RelaySwappablePolicy
+    /// Compiler for the text policy DSL (`allow`, `block`, `shape`, `route`, `delay-dns`).
+    public typealias PolicyCompiler = RelayPolicyCompiler
+    /// Signed over-the-air policy bundle fetcher feeding a `Policy`.
+    public typealias PolicyUpdater = RelayPolicyBundleUpdater
+
+    /// Aggregating worker the engine feeds and hosts poll for telemetry.
+    public typealias TelemetryWorker = PacketTelemetryWorker
+    /// One polled aggregate telemetry snapshot.
+    public typealias TelemetrySnapshot = TunnelTelemetrySnapshot
+    /// Per-second dashboard series (throughput, flows, DNS, blocks).
+    public typealias TelemetrySeries = TunnelTelemetrySeries
+
+    /// Structured logger every engine component reports through.
+    public typealias Logger = StructuredLogger
+}
@@ -46,7 +46,7 @@ public enum TunnelNetworkSettingsFactory {
                 addresses: [profile.ipv6Address],
                 networkPrefixLengths: [NSNumber(value: profile.ipv6PrefixLength)]
             )
-            ipv6.includedRoutes = [NEIPv6Route.default()]
+            ipv6.includedRoutes = profile.ipv6RouteStrategy.includedRoutes
             settings.ipv6Settings = ipv6
         }
 
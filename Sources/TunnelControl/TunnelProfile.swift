@@ -22,17 +22,30 @@ public struct TunnelProfile: Sendable, Equatable {
     public let ipv6Enabled: Bool
     /// Enables `NWParameters.MultipathServiceType.handover` for outbound TCP connects.
     public let tcpMultipathHandoverEnabled: Bool
+    /// Routes port-53 UDP queries through a shared per-resolver session pool keyed by DNS
+    /// transaction ID instead of dialing the resolver once per client source port.
+    public let dnsFastPathEnabled: Bool
+    /// Normalizes outbound packets pf-`scrub` style (random IPv4 IDs, overlapping-fragment
+    /// drops, cleared reserved TCP bits, a TTL floor) before they reach the dataplane.
+    public let packetScrubbingEnabled: Bool
     public let ipv4Address: String
     public let ipv4SubnetMask: String
     public let ipv4Router: String
     public let ipv4RouteStrategy: TunnelIPv4RouteStrategy
     public let ipv6Address: String
     public let ipv6PrefixLength: Int
+    public let ipv6RouteStrategy: TunnelIPv6RouteStrategy
+    /// Client CIDRs accepted as packet sources on the tunnel interface. Empty means accept any source.
+    public let clientSubnets: [ClientSubnet]
     /// Controls which DNS settings are installed on the tunnel interface.
     public let dnsStrategy: TunnelDNSStrategy
     public let engineSocksPort: UInt16
     public let engineLogLevel: String
     public let telemetryEnabled: Bool
+    /// Strictness of checksum and header validation in the analytics pipeline. Permissive by
+    /// default because the host TUN stack validates checksums before packets reach the extension;
+    /// strict mode rejects and counts bad-checksum packets and illegal header combinations.
+    public let packetValidationMode: PacketValidationMode
     public let liveTapEnabled: Bool
     public let liveTapIncludeFlowSlices: Bool
     public let liveTapIncludePacketCues: Bool
@@ -60,18 +73,24 @@ public struct TunnelProfile: Sendable, Equatable {
     ///   - mtuStrategy: Controls how interface MTU settings are applied. Defaults to `.fixed(mtu)` for backward compatibility.
     ///   - ipv6Enabled: Controls whether IPv6 settings are installed.
     ///   - tcpMultipathHandoverEnabled: Enables multipath handover for outbound TCP connections.
+    ///   - dnsFastPathEnabled: Multiplexes port-53 queries over a shared resolver session pool.
+    ///   - packetScrubbingEnabled: Normalizes outbound packets before dataplane ingestion.
     ///   - ipv4Address: Assigned IPv4 address.
     ///   - ipv4SubnetMask: Assigned IPv4 subnet mask.
     ///   - ipv4Router: Default IPv4 router.
     ///   - ipv4RouteStrategy: Controls which IPv4 routes are installed on the tunnel interface.
     ///   - ipv6Address: Assigned IPv6 address.
     ///   - ipv6PrefixLength: IPv6 prefix length.
+    ///   - ipv6RouteStrategy: Controls which IPv6 routes are installed on the tunnel interface.
+    ///   - clientSubnets: Client CIDRs accepted as packet sources; empty accepts any source.
     ///   - dnsServers: DNS servers used by direct callers when `dnsStrategy` is not supplied.
     ///   - dnsStrategy: Controls which DNS settings are installed. Direct callers default to cleartext DNS over
     ///     `dnsServers`; provider-configuration decoding defaults to `TunnelDNSStrategy.recommendedDefault`.
     ///   - engineSocksPort: Local SOCKS server listen port.
     ///   - engineLogLevel: Dataplane log level hint.
     ///   - telemetryEnabled: Enables sparse analytics and detector execution inside the tunnel extension.
+    ///   - packetValidationMode: Strictness of checksum and header validation in the analytics
+    ///     pipeline; strict-mode rejections are surfaced through the invalid-packet counters.
     ///   - liveTapEnabled: Enables the live rolling packet tap used for foreground snapshots. This is a
     ///     lean app-facing debug/read surface, not a guarantee that every detector-grade sparse record kind
     ///     will be published to the containing app.
@@ -94,17 +113,22 @@ public struct TunnelProfile: Sendable, Equatable {
         mtuStrategy: TunnelMTUStrategy? = nil,
         ipv6Enabled: Bool,
         tcpMultipathHandoverEnabled: Bool,
+        dnsFastPathEnabled: Bool = false,
+        packetScrubbingEnabled: Bool = false,
         ipv4Address: String,
         ipv4SubnetMask: String,
         ipv4Router: String,
         ipv4RouteStrategy: TunnelIPv4RouteStrategy? = nil,
         ipv6Address: String,
         ipv6PrefixLength: Int,
+        ipv6RouteStrategy: TunnelIPv6RouteStrategy? = nil,
+        clientSubnets: [ClientSubnet] = [],
         dnsServers: [String],
         dnsStrategy: TunnelDNSStrategy? = nil,
         engineSocksPort: UInt16,
         engineLogLevel: String,
         telemetryEnabled: Bool,
+        packetValidationMode: PacketValidationMode = .permissive,
         liveTapEnabled: Bool,
         liveTapIncludeFlowSlices: Bool,
         liveTapIncludePacketCues: Bool = false,
@@ -132,16 +156,21 @@ public struct TunnelProfile: Sendable, Equatable {
         }
         self.ipv6Enabled = ipv6Enabled
         self.tcpMultipathHandoverEnabled = tcpMultipathHandoverEnabled
+        self.dnsFastPathEnabled = dnsFastPathEnabled
+        self.packetScrubbingEnabled = packetScrubbingEnabled
         self.ipv4Address = ipv4Address
         self.ipv4SubnetMask = ipv4SubnetMask
         self.ipv4Router = ipv4Router
         self.ipv4RouteStrategy = (ipv4RouteStrategy ?? .defaultRoute).normalized()
         self.ipv6Address = ipv6Address
         self.ipv6PrefixLength = ipv6PrefixLength
+        self.ipv6RouteStrategy = (ipv6RouteStrategy ?? .defaultRoute).normalized()
+        self.clientSubnets = clientSubnets
         self.dnsStrategy = dnsStrategy ?? .cleartext(servers: dnsServers)
         self.engineSocksPort = engineSocksPort
         self.engineLogLevel = engineLogLevel
         self.telemetryEnabled = telemetryEnabled
+        self.packetValidationMode = packetValidationMode
         self.liveTapEnabled = liveTapEnabled
         self.liveTapIncludeFlowSlices = liveTapIncludeFlowSlices
         self.liveTapIncludePacketCues = liveTapIncludePacketCues
@@ -172,6 +201,7 @@ public struct TunnelProfile: Sendable, Equatable {
         let mtuValue = configuredMTU ?? mtuStrategy.bufferMTUHint
         let dnsStrategy = dnsStrategy(from: providerConfiguration)
         let ipv4RouteStrategy = ipv4RouteStrategy(from: providerConfiguration)
+        let ipv6RouteStrategy = ipv6RouteStrategy(from: providerConfiguration)
         let packetCuePolicy = packetCuePolicy(from: providerConfiguration)
         let addressScopePrefixes = addressScopePrefixes(from: providerConfiguration)
         let richPacketLogPolicy = richPacketLogPolicy(from: providerConfiguration)
@@ -183,17 +213,24 @@ public struct TunnelProfile: Sendable, Equatable {
             mtuStrategy: mtuStrategy,
             ipv6Enabled: bool(providerConfiguration[TunnelProviderConfigurationKey.ipv6Enabled], default: true),
             tcpMultipathHandoverEnabled: bool(providerConfiguration[TunnelProviderConfigurationKey.tcpMultipathHandoverEnabled], default: false),
+            dnsFastPathEnabled: bool(providerConfiguration[TunnelProviderConfigurationKey.dnsFastPathEnabled], default: false),
+            packetScrubbingEnabled: bool(providerConfiguration[TunnelProviderConfigurationKey.packetScrubbingEnabled], default: false),
             ipv4Address: providerConfiguration[TunnelProviderConfigurationKey.ipv4Address] as? String ?? "10.0.0.2",
             ipv4SubnetMask: providerConfiguration[TunnelProviderConfigurationKey.ipv4SubnetMask] as? String ?? "255.255.255.0",
             ipv4Router: providerConfiguration[TunnelProviderConfigurationKey.ipv4Router] as? String ?? "10.0.0.1",
             ipv4RouteStrategy: ipv4RouteStrategy,
             ipv6Address: providerConfiguration[TunnelProviderConfigurationKey.ipv6Address] as? String ?? "fd00:1::2",
             ipv6PrefixLength: int(providerConfiguration[TunnelProviderConfigurationKey.ipv6PrefixLength], default: 64),
+            ipv6RouteStrategy: ipv6RouteStrategy,
+            clientSubnets: (stringArray(providerConfiguration[TunnelProviderConfigurationKey.clientSubnets]) ?? []).compactMap(ClientSubnet.init(cidr:)),
             dnsServers: dnsStrategy.servers,
             dnsStrategy: dnsStrategy,
             engineSocksPort: uint16AllowingZero(providerConfiguration[TunnelProviderConfigurationKey.engineSocksPort], default: 1080),
             engineLogLevel: providerConfiguration[TunnelProviderConfigurationKey.engineLogLevel] as? String ?? "warn",
             telemetryEnabled: bool(providerConfiguration[TunnelProviderConfigurationKey.telemetryEnabled], default: true),
+            packetValidationMode: PacketValidationMode(
+                rawValue: providerConfiguration[TunnelProviderConfigurationKey.packetValidationMode] as? String ?? ""
+            ) ?? .permissive,
             liveTapEnabled: bool(providerConfiguration[TunnelProviderConfigurationKey.liveTapEnabled], default: false),
             liveTapIncludeFlowSlices: bool(providerConfiguration[TunnelProviderConfigurationKey.liveTapIncludeFlowSlices], default: false),
             liveTapIncludePacketCues: bool(providerConfiguration[TunnelProviderConfigurationKey.liveTapIncludePacketCues], default: false),
@@ -281,6 +318,8 @@ public struct TunnelProfile: Sendable, Equatable {
             throw TunnelProfileValidationError.invalidValue(key: TunnelProviderConfigurationKey.ipv4Router, reason: "must be a valid IPv4 address")
         }
         try validateIPv4RouteStrategy(profile.ipv4RouteStrategy, rawValue: providerConfiguration[TunnelProviderConfigurationKey.ipv4IncludedRoutes])
+        try validateIPv6RouteStrategy(profile.ipv6RouteStrategy, rawValue: providerConfiguration[TunnelProviderConfigurationKey.ipv6IncludedRoutes])
+        try validateClientSubnets(providerConfiguration[TunnelProviderConfigurationKey.clientSubnets])
         guard profile.ipv6PrefixLength > 0, profile.ipv6PrefixLength <= 128 else {
             throw TunnelProfileValidationError.invalidValue(key: TunnelProviderConfigurationKey.ipv6PrefixLength, reason: "must be in 1...128")
         }
@@ -635,7 +674,7 @@ public struct TunnelProfile: Sendable, Equatable {
         guard let rawRoutes = providerConfiguration[TunnelProviderConfigurationKey.ipv4IncludedRoutes] else {
             return .defaultRoute
         }
-        guard let routeDictionaries = ipv4RouteDictionaries(from: rawRoutes) else {
+        guard let routeDictionaries = routeDictionaries(from: rawRoutes) else {
             return .defaultRoute
         }
 
@@ -649,11 +688,29 @@ public struct TunnelProfile: Sendable, Equatable {
         return routes.isEmpty ? .defaultRoute : .includedRoutes(routes)
     }
 
+    private static func ipv6RouteStrategy(from providerConfiguration: [String: Any]) -> TunnelIPv6RouteStrategy {
+        guard let rawRoutes = providerConfiguration[TunnelProviderConfigurationKey.ipv6IncludedRoutes] else {
+            return .defaultRoute
+        }
+        guard let routeDictionaries = routeDictionaries(from: rawRoutes) else {
+            return .defaultRoute
+        }
+
+        let routes = routeDictionaries.compactMap { route -> TunnelIPv6Route? in
+            guard let destinationAddress = string(route["destinationAddress"]),
+                  let prefixLength = exactInt(route["prefixLength"]) else {
+                return nil
+            }
+            return TunnelIPv6Route(destinationAddress: destinationAddress, prefixLength: prefixLength)
+        }
+        return routes.isEmpty ? .defaultRoute : .includedRoutes(routes)
+    }
+
     private static func validateIPv4RouteStrategy(_ strategy: TunnelIPv4RouteStrategy, rawValue: Any?) throws {
         guard let rawValue else {
             return
         }
-        guard let routeDictionaries = ipv4RouteDictionaries(from: rawValue), !routeDictionaries.isEmpty else {
+        guard let routeDictionaries = routeDictionaries(from: rawValue), !routeDictionaries.isEmpty else {
             throw TunnelProfileValidationError.invalidValue(
                 key: TunnelProviderConfigurationKey.ipv4IncludedRoutes,
                 reason: "must be a non-empty array of IPv4 route dictionaries"
@@ -691,7 +748,67 @@ public struct TunnelProfile: Sendable, Equatable {
         }
     }
 
-    private static func ipv4RouteDictionaries(from value: Any?) -> [[String: Any]]? {
+    private static func validateClientSubnets(_ value: Any?) throws {
+        guard let value else {
+            return
+        }
+        guard let cidrs = stringArray(value) else {
+            throw TunnelProfileValidationError.invalidValue(
+                key: TunnelProviderConfigurationKey.clientSubnets,
+                reason: "must be an array of CIDR strings"
+            )
+        }
+        for cidr in cidrs where ClientSubnet(cidr: cidr) == nil {
+            throw TunnelProfileValidationError.invalidValue(
+                key: TunnelProviderConfigurationKey.clientSubnets,
+                reason: "must contain only valid IPv4 or IPv6 CIDRs"
+            )
+        }
+    }
+
+    private static func validateIPv6RouteStrategy(_ strategy: TunnelIPv6RouteStrategy, rawValue: Any?) throws {
+        guard let rawValue else {
+            return
+        }
+        guard let routeDictionaries = routeDictionaries(from: rawValue), !routeDictionaries.isEmpty else {
+            throw TunnelProfileValidationError.invalidValue(
+                key: TunnelProviderConfigurationKey.ipv6IncludedRoutes,
+                reason: "must be a non-empty array of IPv6 route dictionaries"
+            )
+        }
+
+        let routes: [TunnelIPv6Route]
+        switch strategy {
+        case .defaultRoute:
+            routes = []
+        case .includedRoutes(let includedRoutes):
+            routes = includedRoutes
+        }
+
+        guard routes.count == routeDictionaries.count else {
+            throw TunnelProfileValidationError.invalidValue(
+                key: TunnelProviderConfigurationKey.ipv6IncludedRoutes,
+                reason: "each route must include a destinationAddress string and an integer prefixLength"
+            )
+        }
+
+        for route in routes {
+            guard isValidIPv6Address(route.destinationAddress) else {
+                throw TunnelProfileValidationError.invalidValue(
+                    key: TunnelProviderConfigurationKey.ipv6IncludedRoutes,
+                    reason: "destinationAddress must be a valid IPv6 address"
+                )
+            }
+            guard route.prefixLength > 0, route.prefixLength <= 128 else {
+                throw TunnelProfileValidationError.invalidValue(
+                    key: TunnelProviderConfigurationKey.ipv6IncludedRoutes,
+                    reason: "prefixLength must be in 1...128"
+                )
+            }
+        }
+    }
+
+    private static func routeDictionaries(from value: Any?) -> [[String: Any]]? {
         if let routes = value as? [[String: Any]] {
             return routes
         }
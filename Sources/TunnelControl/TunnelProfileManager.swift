@@ -35,11 +35,14 @@ public enum TunnelProfileManager {
             TunnelProviderConfigurationKey.mtu: profile.mtu,
             TunnelProviderConfigurationKey.ipv6Enabled: profile.ipv6Enabled,
             TunnelProviderConfigurationKey.tcpMultipathHandoverEnabled: profile.tcpMultipathHandoverEnabled,
+            TunnelProviderConfigurationKey.dnsFastPathEnabled: profile.dnsFastPathEnabled,
+            TunnelProviderConfigurationKey.packetScrubbingEnabled: profile.packetScrubbingEnabled,
             TunnelProviderConfigurationKey.ipv4Address: profile.ipv4Address,
             TunnelProviderConfigurationKey.ipv4SubnetMask: profile.ipv4SubnetMask,
             TunnelProviderConfigurationKey.ipv4Router: profile.ipv4Router,
             TunnelProviderConfigurationKey.ipv6Address: profile.ipv6Address,
             TunnelProviderConfigurationKey.ipv6PrefixLength: profile.ipv6PrefixLength,
+            TunnelProviderConfigurationKey.clientSubnets: profile.clientSubnets.map(\.cidr),
             TunnelProviderConfigurationKey.dnsServers: profile.dnsServers,
             TunnelProviderConfigurationKey.engineSocksPort: Int(profile.engineSocksPort),
             TunnelProviderConfigurationKey.engineLogLevel: profile.engineLogLevel,
@@ -67,6 +70,9 @@ public enum TunnelProfileManager {
         for (key, value) in profile.ipv4RouteStrategy.providerConfiguration {
             configuration[key] = value
         }
+        for (key, value) in profile.ipv6RouteStrategy.providerConfiguration {
+            configuration[key] = value
+        }
         configuration[TunnelProviderConfigurationKey.dnsStrategy] = profile.dnsStrategy.providerConfiguration
         proto.providerConfiguration = configuration
         manager.protocolConfiguration = proto
@@ -12,17 +12,22 @@ enum TunnelProviderConfigurationKey {
     static let tunnelOverheadBytes = "tunnelOverheadBytes"
     static let ipv6Enabled = "ipv6Enabled"
     static let tcpMultipathHandoverEnabled = "tcpMultipathHandoverEnabled"
+    static let dnsFastPathEnabled = "dnsFastPathEnabled"
+    static let packetScrubbingEnabled = "packetScrubbingEnabled"
     static let ipv4Address = "ipv4Address"
     static let ipv4SubnetMask = "ipv4SubnetMask"
     static let ipv4Router = "ipv4Router"
     static let ipv4IncludedRoutes = "ipv4IncludedRoutes"
     static let ipv6Address = "ipv6Address"
     static let ipv6PrefixLength = "ipv6PrefixLength"
+    static let ipv6IncludedRoutes = "ipv6IncludedRoutes"
+    static let clientSubnets = "clientSubnets"
     static let dnsServers = "dnsServers"
     static let dnsStrategy = "dnsStrategy"
     static let engineSocksPort = "engineSocksPort"
     static let engineLogLevel = "engineLogLevel"
     static let telemetryEnabled = "telemetryEnabled"
+    static let packetValidationMode = "packetValidationMode"
     static let liveTapEnabled = "liveTapEnabled"
     static let liveTapIncludeFlowSlices = "liveTapIncludeFlowSlices"
     static let liveTapIncludePacketCues = "liveTapIncludePacketCues"
@@ -48,17 +53,22 @@ enum TunnelProviderConfigurationKey {
         tunnelOverheadBytes,
         ipv6Enabled,
         tcpMultipathHandoverEnabled,
+        dnsFastPathEnabled,
+        packetScrubbingEnabled,
         ipv4Address,
         ipv4SubnetMask,
         ipv4Router,
         ipv4IncludedRoutes,
         ipv6Address,
         ipv6PrefixLength,
+        ipv6IncludedRoutes,
+        clientSubnets,
         dnsServers,
         dnsStrategy,
         engineSocksPort,
         engineLogLevel,
         telemetryEnabled,
+        packetValidationMode,
         liveTapEnabled,
         liveTapIncludeFlowSlices,
         liveTapIncludePacketCues,
@@ -62,3 +62,61 @@ public enum TunnelIPv4RouteStrategy: Sendable, Equatable {
         }
     }
 }
+
+/// One IPv6 route installed on the packet-tunnel interface.
+public struct TunnelIPv6Route: Sendable, Equatable {
+    public let destinationAddress: String
+    public let prefixLength: Int
+
+    public init(destinationAddress: String, prefixLength: Int) {
+        self.destinationAddress = destinationAddress
+        self.prefixLength = prefixLength
+    }
+}
+
+/// IPv6 routing policy for the packet tunnel.
+public enum TunnelIPv6RouteStrategy: Sendable, Equatable {
+    /// Routes all IPv6 traffic through the tunnel.
+    case defaultRoute
+    /// Routes only the specified IPv6 destinations through the tunnel.
+    case includedRoutes([TunnelIPv6Route])
+
+    public static let defaultFullTunnel = TunnelIPv6RouteStrategy.defaultRoute
+
+    var providerConfiguration: [String: Any] {
+        switch self {
+        case .defaultRoute:
+            return [:]
+        case .includedRoutes(let routes):
+            let encodedRoutes = routes.map { route -> [String: Any] in
+                [
+                    "destinationAddress": route.destinationAddress,
+                    "prefixLength": route.prefixLength
+                ]
+            }
+            return [TunnelProviderConfigurationKey.ipv6IncludedRoutes: encodedRoutes]
+        }
+    }
+
+    var includedRoutes: [NEIPv6Route] {
+        switch self {
+        case .defaultRoute:
+            return [NEIPv6Route.default()]
+        case .includedRoutes(let routes):
+            return routes.map { route in
+                NEIPv6Route(destinationAddress: route.destinationAddress, networkPrefixLength: NSNumber(value: route.prefixLength))
+            }
+        }
+    }
+
+    func normalized() -> TunnelIPv6RouteStrategy {
+        switch self {
+        case .defaultRoute:
+            return .defaultRoute
+        case .includedRoutes(let routes) where routes.isEmpty:
+            return .defaultRoute
+        case .includedRoutes:
+            return self
+        }
+    }
+}
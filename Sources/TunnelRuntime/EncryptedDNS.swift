@@ -0,0 +1,108 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Encrypted-DNS transport flavor attached to flows and policy decisions.
+public enum EncryptedDNSKind: String, Codable, Sendable, Equatable {
+    /// DNS-over-HTTPS, identified by a known public resolver hostname or address on 443.
+    case doh
+    /// DNS-over-TLS (and DNS-over-QUIC), identified by destination port 853.
+    case dot
+}
+
+/// Shared heuristics for spotting encrypted-DNS destinations.
+/// Decision: lives in TunnelRuntime so both the analytics pipeline (flow tagging) and the relay
+/// (policy enforcement) classify identically without a cross-module dependency.
+/// Contract: the host list is a best-effort catalog of major public resolvers, not an exhaustive
+/// census; deployments needing stricter coverage should layer their own policy rules on top.
+public enum EncryptedDNSClassifier {
+    public static let dotPort: UInt16 = 853
+    private static let httpsPort: UInt16 = 443
+
+    /// Well-known public DoH endpoint hostnames, matched exactly or as a `.suffix`.
+    public static let knownDoHResolverHosts: Set<String> = [
+        "dns.google",
+        "cloudflare-dns.com",
+        "one.one.one.one",
+        "dns.quad9.net",
+        "dns.nextdns.io",
+        "doh.opendns.com",
+        "dns.adguard-dns.com",
+        "doh.cleanbrowsing.org"
+    ]
+
+    /// Well-known public DoH resolver addresses (IPv4 and IPv6 literals, lowercased).
+    public static let knownDoHResolverAddresses: Set<String> = [
+        "8.8.8.8",
+        "8.8.4.4",
+        "1.1.1.1",
+        "1.0.0.1",
+        "9.9.9.9",
+        "149.112.112.112",
+        "2001:4860:4860::8888",
+        "2001:4860:4860::8844",
+        "2606:4700:4700::1111",
+        "2606:4700:4700::1001",
+        "2620:fe::fe",
+        "2620:fe::9"
+    ]
+
+    /// Known DoH IPv4 resolvers as host-order integers for numeric fast paths.
+    public static let knownDoHResolverIPv4Addresses: Set<UInt32> = Set(
+        knownDoHResolverAddresses.compactMap(parseIPv4)
+    )
+
+    /// Classifies one destination, preferring the strongest available evidence.
+    /// - Parameters:
+    ///   - destinationPort: Destination port of the flow or dial.
+    ///   - serverName: TLS server name or requested hostname, when known.
+    ///   - destinationAddress: Destination address literal, when known.
+    public static func classify(
+        destinationPort: UInt16?,
+        serverName: String?,
+        destinationAddress: String?
+    ) -> EncryptedDNSKind? {
+        guard let destinationPort else {
+            return nil
+        }
+        if destinationPort == dotPort {
+            return .dot
+        }
+        guard destinationPort == httpsPort else {
+            return nil
+        }
+        if let serverName, isKnownDoHResolverHost(serverName) {
+            return .doh
+        }
+        if let destinationAddress, knownDoHResolverAddresses.contains(destinationAddress.lowercased()) {
+            return .doh
+        }
+        return nil
+    }
+
+    /// Returns whether a hostname names a known DoH resolver, including subdomains.
+    public static func isKnownDoHResolverHost(_ host: String) -> Bool {
+        let normalized = host.lowercased()
+        if knownDoHResolverHosts.contains(normalized) {
+            return true
+        }
+        return knownDoHResolverHosts.contains { normalized.hasSuffix(".\($0)") }
+    }
+
+    private static func parseIPv4(_ literal: String) -> UInt32? {
+        let octets = literal.split(separator: ".")
+        guard octets.count == 4 else {
+            return nil
+        }
+        var value: UInt32 = 0
+        for octet in octets {
+            guard let parsed = UInt32(octet), parsed <= 255 else {
+                return nil
+            }
+            value = (value << 8) | parsed
+        }
+        return value
+    }
+}
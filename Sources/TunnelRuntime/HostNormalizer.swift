@@ -0,0 +1,115 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Canonical hostname form shared by rule installation and match-time comparison.
+/// Decision: rules and observed hosts are both folded to the lowercased, root-dot-stripped,
+/// IDNA (`xn--`) representation, so a rule written as `bücher.example` matches the Punycode
+/// form DNS and SNI actually carry — and vice versa — without every matcher re-implementing
+/// the folding.
+public enum HostNormalizer {
+    /// Returns the canonical comparison form of a hostname: lowercased, trailing root dot
+    /// stripped, and non-ASCII labels encoded to their `xn--` Punycode form. ASCII hosts
+    /// (including already-encoded `xn--` labels and wildcard `*` labels) pass through with
+    /// only case/dot folding, so normalization is idempotent.
+    public static func normalize(_ host: String) -> String {
+        var folded = host.lowercased()
+        if folded.hasSuffix(".") {
+            folded.removeLast()
+        }
+        guard !folded.unicodeScalars.allSatisfy(\.isASCII) else {
+            return folded
+        }
+        return folded
+            .split(separator: ".", omittingEmptySubsequences: false)
+            .map { encodeLabel(String($0)) }
+            .joined(separator: ".")
+    }
+
+    private static func encodeLabel(_ label: String) -> String {
+        guard !label.unicodeScalars.allSatisfy(\.isASCII) else {
+            return label
+        }
+        guard let encoded = punycode(label) else {
+            return label
+        }
+        return "xn--" + encoded
+    }
+
+    /// RFC 3492 Punycode encoding of one label. Returns `nil` on overflow, in which case the
+    /// caller keeps the unencoded label and matching degrades to exact-unicode comparison.
+    private static func punycode(_ label: String) -> String? {
+        let scalars = label.unicodeScalars.map { Int($0.value) }
+        var output: [Character] = scalars.compactMap { value in
+            value < 128 ? Character(UnicodeScalar(UInt8(value))) : nil
+        }
+        let basicLength = output.count
+        var handled = basicLength
+        if basicLength > 0 {
+            output.append("-")
+        }
+
+        var n = 128
+        var delta = 0
+        var bias = 72
+        while handled < scalars.count {
+            guard let m = scalars.lazy.filter({ $0 >= n }).min() else {
+                return nil
+            }
+            let (advanced, overflow) = delta.addingReportingOverflow((m - n) * (handled + 1))
+            guard !overflow else {
+                return nil
+            }
+            delta = advanced
+            n = m
+            for value in scalars {
+                if value < n {
+                    delta += 1
+                    guard delta > 0 else {
+                        return nil
+                    }
+                }
+                guard value == n else {
+                    continue
+                }
+                var q = delta
+                var k = 36
+                while true {
+                    let t = k <= bias ? 1 : (k >= bias + 26 ? 26 : k - bias)
+                    if q < t {
+                        break
+                    }
+                    output.append(encodeDigit(t + (q - t) % (36 - t)))
+                    q = (q - t) / (36 - t)
+                    k += 36
+                }
+                output.append(encodeDigit(q))
+                bias = adapt(delta: delta, numPoints: handled + 1, firstTime: handled == basicLength)
+                delta = 0
+                handled += 1
+            }
+            delta += 1
+            n += 1
+        }
+        return String(output)
+    }
+
+    private static func adapt(delta: Int, numPoints: Int, firstTime: Bool) -> Int {
+        var delta = firstTime ? delta / 700 : delta / 2
+        delta += delta / numPoints
+        var k = 0
+        while delta > ((36 - 1) * 26) / 2 {
+            delta /= 36 - 1
+            k += 36
+        }
+        return k + (36 * delta) / (delta + 38)
+    }
+
+    private static func encodeDigit(_ digit: Int) -> Character {
+        digit < 26
+            ? Character(UnicodeScalar(UInt8(digit + 97)))
+            : Character(UnicodeScalar(UInt8(digit - 26 + 48)))
+    }
+}
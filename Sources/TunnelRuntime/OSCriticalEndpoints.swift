@@ -0,0 +1,49 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Shared heuristics for spotting OS-critical probe and clock-sync destinations.
+/// Decision: lives in TunnelRuntime beside `EncryptedDNSClassifier` so the relay's policy
+/// engine and any host-side tooling recognize the same endpoints without a cross-module
+/// dependency. Breaking these flows has outsized blast radius — a blocked captive-portal
+/// probe makes the OS declare the network broken, and blocked NTP lets the clock drift
+/// until TLS validation starts failing everywhere.
+/// Contract: the host list is a best-effort catalog of the probes Apple platforms depend
+/// on, not an exhaustive census; deployments fronting other device families should layer
+/// their own `allow` rules on top.
+public enum OSCriticalEndpointClassifier {
+    /// NTP destination port. Clock sync qualifies by port alone: devices sync against
+    /// arbitrary pool servers, so no host catalog could cover them.
+    public static let ntpPort: UInt16 = 123
+
+    /// OS probe hostnames, matched exactly or as a `.suffix`.
+    public static let knownProbeHosts: Set<String> = [
+        "time.apple.com",
+        "captive.apple.com"
+    ]
+
+    /// Classifies one destination as OS-critical by NTP port or known probe hostname.
+    /// - Parameters:
+    ///   - destinationPort: Destination port of the flow or dial.
+    ///   - host: Requested hostname or address literal, when known.
+    public static func isOSCritical(destinationPort: UInt16?, host: String?) -> Bool {
+        if destinationPort == ntpPort {
+            return true
+        }
+        if let host, isKnownProbeHost(host) {
+            return true
+        }
+        return false
+    }
+
+    /// Returns whether a hostname names a known OS probe endpoint, including subdomains.
+    public static func isKnownProbeHost(_ host: String) -> Bool {
+        let normalized = host.lowercased()
+        if knownProbeHosts.contains(normalized) {
+            return true
+        }
+        return knownProbeHosts.contains { normalized.hasSuffix(".\($0)") }
+    }
+}
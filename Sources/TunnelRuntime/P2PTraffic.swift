@@ -0,0 +1,78 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Shared payload signatures for spotting BitTorrent-family peer-to-peer traffic.
+/// Decision: lives in TunnelRuntime beside the encrypted-DNS heuristics so the relay's
+/// `class:p2p` policy selector and any analytics tagging classify identically without a
+/// cross-module dependency.
+/// Contract: these are positive signatures for the plaintext protocol variants; encrypted
+/// peer connections (MSE/obfuscated) carry no stable signature and are not detected, so a
+/// blocking deployment should treat `class:p2p` as best-effort rather than airtight.
+public enum P2PTrafficClassifier {
+    /// Fixed 19-byte protocol identifier that opens every plaintext BitTorrent peer handshake,
+    /// preceded on the wire by its length byte.
+    private static let bitTorrentProtocolIdentifier = Data("BitTorrent protocol".utf8)
+
+    /// Classifies one leading payload as peer-to-peer traffic.
+    /// - Parameters:
+    ///   - transport: Transport label (`tcp` or `udp`); DHT and uTP are UDP-only signatures.
+    ///   - payload: Leading bytes of the flow's first payload. May be empty, in which case
+    ///     nothing matches.
+    public static func isP2P(transport: String, payload: Data) -> Bool {
+        if isBitTorrentHandshake(payload) {
+            return true
+        }
+        guard transport.lowercased() == "udp" else {
+            return false
+        }
+        return isDHTMessage(payload) || isUTPPacket(payload)
+    }
+
+    /// Matches the plaintext peer-wire handshake: `0x13` followed by "BitTorrent protocol".
+    public static func isBitTorrentHandshake(_ payload: Data) -> Bool {
+        guard payload.count >= 1 + bitTorrentProtocolIdentifier.count else {
+            return false
+        }
+        guard payload[payload.startIndex] == 0x13 else {
+            return false
+        }
+        let identifierStart = payload.index(after: payload.startIndex)
+        let identifierEnd = payload.index(identifierStart, offsetBy: bitTorrentProtocolIdentifier.count)
+        return payload[identifierStart ..< identifierEnd].elementsEqual(bitTorrentProtocolIdentifier)
+    }
+
+    /// Matches a bencoded mainline-DHT message: a dictionary opening with a one-character
+    /// key and carrying the mandatory `1:y` message-type entry in its leading bytes.
+    public static func isDHTMessage(_ payload: Data) -> Bool {
+        guard payload.count >= 8, payload[payload.startIndex] == UInt8(ascii: "d") else {
+            return false
+        }
+        guard let text = String(data: payload.prefix(128), encoding: .ascii) else {
+            return false
+        }
+        return text.hasPrefix("d1:") && text.contains("1:y1:")
+    }
+
+    /// Matches a uTP (BEP 29) packet header: version 1, a known packet type, and the fixed
+    /// 20-byte header present. Deliberately conservative — a random datagram passes this
+    /// shape check roughly once in several thousand, and only flows already selected by a
+    /// `class:p2p` rule pay for it.
+    public static func isUTPPacket(_ payload: Data) -> Bool {
+        guard payload.count >= 20 else {
+            return false
+        }
+        let versionAndType = payload[payload.startIndex]
+        let version = versionAndType & 0x0F
+        let packetType = versionAndType >> 4
+        // Types 0...4: ST_DATA, ST_FIN, ST_STATE, ST_RESET, ST_SYN.
+        guard version == 1, packetType <= 4 else {
+            return false
+        }
+        // The extension byte is 0 (none) or 1 (selective ACK) in practice.
+        let extensionByte = payload[payload.index(after: payload.startIndex)]
+        return extensionByte <= 1
+    }
+}
@@ -0,0 +1,121 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+
+/// Public-suffix rules for registrable-domain ("same-site") grouping and wildcard sanity checks.
+/// Decision: the list is an opt-in install rather than an always-on dependency — consumers that
+/// never group by site keep the zero-cost curated heuristics they have today, and consumers that
+/// do opt in choose between the embedded seed and a full publicsuffix.org snapshot they load
+/// themselves. Parsing follows the PSL format: one rule per line, `//` comments, `*.` wildcard
+/// rules, and `!` exception rules that re-admit a label under a wildcard suffix.
+public struct PublicSuffixList: Sendable, Equatable {
+    /// Exact suffix rules, e.g. `co.uk`.
+    private let exactRules: Set<String>
+    /// Parents of `*.` wildcard rules, e.g. `ck` for `*.ck`.
+    private let wildcardParents: Set<String>
+    /// Exception rules stripped of `!`, e.g. `www.ck`; these are registrable despite a wildcard.
+    private let exceptionRules: Set<String>
+
+    /// Parses PSL-format text. Unparseable lines are skipped rather than rejected so a
+    /// truncated or partially customized list degrades to fewer rules, never to a failure.
+    public init(listText: String) {
+        var exact: Set<String> = []
+        var wildcards: Set<String> = []
+        var exceptions: Set<String> = []
+        for rawLine in listText.split(separator: "\n", omittingEmptySubsequences: true) {
+            let line = rawLine.trimmingCharacters(in: .whitespaces)
+            guard !line.isEmpty, !line.hasPrefix("//") else {
+                continue
+            }
+            if line.hasPrefix("!") {
+                exceptions.insert(HostNormalizer.normalize(String(line.dropFirst())))
+            } else if line.hasPrefix("*.") {
+                wildcards.insert(HostNormalizer.normalize(String(line.dropFirst(2))))
+            } else {
+                exact.insert(HostNormalizer.normalize(line))
+            }
+        }
+        self.exactRules = exact
+        self.wildcardParents = wildcards
+        self.exceptionRules = exceptions
+    }
+
+    /// Curated seed covering the multi-part ccTLD suffixes this package's heuristics already
+    /// special-case, plus the wildcard/exception shapes needed for correct PSL semantics.
+    /// Small enough to ship as source; hosts needing exhaustive coverage load the full
+    /// publicsuffix.org list through `init(listText:)`.
+    public static let embedded = PublicSuffixList(listText: """
+        ac.uk
+        co.uk
+        gov.uk
+        org.uk
+        co.jp
+        co.kr
+        co.in
+        co.nz
+        com.au
+        net.au
+        org.au
+        com.br
+        com.mx
+        com.ar
+        com.cn
+        com.hk
+        com.tw
+        com.my
+        com.sg
+        com.tr
+        com.sa
+        *.ck
+        !www.ck
+        """)
+
+    /// Returns `true` when the host itself is a public suffix (so `*.<host>` spans every
+    /// registrant under it).
+    public func isPublicSuffix(_ host: String) -> Bool {
+        let normalized = HostNormalizer.normalize(host)
+        guard !normalized.isEmpty, !exceptionRules.contains(normalized) else {
+            return false
+        }
+        if exactRules.contains(normalized) || wildcardParents.contains(normalized) {
+            return true
+        }
+        // A wildcard rule makes every direct child of its parent a suffix too.
+        if let dot = normalized.firstIndex(of: "."),
+           wildcardParents.contains(String(normalized[normalized.index(after: dot)...])) {
+            return true
+        }
+        // Single labels fall under the implicit `*` rule of the PSL algorithm.
+        return !normalized.contains(".")
+    }
+
+    /// Returns the registrable domain (public suffix plus one label), or `nil` when the host
+    /// is itself a public suffix or an empty string.
+    public func registrableDomain(of host: String) -> String? {
+        let normalized = HostNormalizer.normalize(host)
+        guard !normalized.isEmpty else {
+            return nil
+        }
+        let labels = normalized.split(separator: ".").map(String.init)
+        // Walk candidate suffixes longest-first; the longest tail that is a public suffix
+        // plus one more label is the registrable domain.
+        for start in labels.indices {
+            let candidate = labels[start...].joined(separator: ".")
+            if exceptionRules.contains(candidate) {
+                return candidate
+            }
+            if isPublicSuffix(candidate) {
+                guard start > 0 else {
+                    return nil
+                }
+                return labels[(start - 1)...].joined(separator: ".")
+            }
+        }
+        guard labels.count >= 2 else {
+            return nil
+        }
+        return labels.suffix(2).joined(separator: ".")
+    }
+}
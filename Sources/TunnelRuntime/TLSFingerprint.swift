@@ -0,0 +1,362 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+#if canImport(CryptoKit)
+import CryptoKit
+#endif
+
+/// Transport the ClientHello was observed on; JA4 encodes it as the fingerprint's first character.
+public enum TLSFingerprintTransport: String, Sendable, Equatable {
+    case tcp = "t"
+    case quic = "q"
+}
+
+/// Passive fingerprints computed from one observed ClientHello.
+public struct TLSClientHelloFingerprint: Sendable, Equatable {
+    /// Classic JA3: MD5 hex of `version,ciphers,extensions,groups,pointFormats` (GREASE removed).
+    public let ja3: String
+    /// JA4 composite: `{t|q}{ver}{d|i}{ciphers}{extensions}{alpn}_{cipherHash}_{extensionHash}`.
+    public let ja4: String
+
+    public init(ja3: String, ja4: String) {
+        self.ja3 = ja3
+        self.ja4 = ja4
+    }
+}
+
+/// Randomization features of one observed ClientHello, for aggregate bot-mitigation
+/// telemetry: GREASE usage, cipher ordering quirks, and session-resumption offers.
+/// Contract: features only — no server name, fingerprint hash, or raw handshake bytes —
+/// so counting them in telemetry never exports handshake contents.
+public struct TLSClientHelloVariation: Sendable, Equatable {
+    /// Count of GREASE values (RFC 8701) across cipher suites, extension types,
+    /// supported groups, and supported versions.
+    public let greaseValueCount: Int
+    /// True when a TLS 1.3 suite (0x1301...0x1305) is offered after a non-GREASE legacy
+    /// suite; mainstream stacks list the 1.3 suites first.
+    public let hasUnusualCipherOrder: Bool
+    /// True when the hello offers resumption: a non-empty legacy session ID, a session
+    /// ticket extension, or a pre-shared key offer.
+    public let offersSessionResumption: Bool
+
+    public init(greaseValueCount: Int, hasUnusualCipherOrder: Bool, offersSessionResumption: Bool) {
+        self.greaseValueCount = greaseValueCount
+        self.hasUnusualCipherOrder = hasUnusualCipherOrder
+        self.offersSessionResumption = offersSessionResumption
+    }
+}
+
+/// Passive TLS client fingerprinter (JA3 and JA4) over raw ClientHello bytes.
+/// Decision: lives in TunnelRuntime so the analytics pipeline (flow telemetry) and the relay
+/// (policy selectors) compute identical fingerprints without a cross-module dependency.
+/// Contract: a non-`nil` result means the buffer parsed as a complete ClientHello; malformed or
+/// truncated input returns `nil` rather than a partial fingerprint.
+public enum TLSFingerprinter {
+    private static let sniExtension: UInt16 = 0
+    private static let supportedGroupsExtension: UInt16 = 10
+    private static let pointFormatsExtension: UInt16 = 11
+    private static let signatureAlgorithmsExtension: UInt16 = 13
+    private static let alpnExtension: UInt16 = 16
+    private static let sessionTicketExtension: UInt16 = 35
+    private static let preSharedKeyExtension: UInt16 = 41
+    private static let supportedVersionsExtension: UInt16 = 43
+
+    /// Fingerprints a ClientHello wrapped in a TLS record layer (the TCP first-flight form).
+    /// - Parameters:
+    ///   - data: Buffer whose TLS record starts at `offset`.
+    ///   - offset: Byte offset of the record's content-type byte within `data`.
+    ///   - transport: Transport label encoded into JA4.
+    public static func fingerprint(
+        record data: Data,
+        at offset: Int = 0,
+        transport: TLSFingerprintTransport = .tcp
+    ) -> TLSClientHelloFingerprint? {
+        guard let handshake = recordPayload(data, at: offset) else {
+            return nil
+        }
+        return fingerprint(handshakeBytes: handshake, transport: transport)
+    }
+
+    /// Fingerprints a bare handshake message (the QUIC CRYPTO-stream form, no record layer).
+    public static func fingerprint(handshake data: Data, transport: TLSFingerprintTransport) -> TLSClientHelloFingerprint? {
+        fingerprint(handshakeBytes: [UInt8](data), transport: transport)
+    }
+
+    /// Extracts variation features from a ClientHello wrapped in a TLS record layer.
+    /// Same parse and `nil` contract as `fingerprint(record:at:transport:)`.
+    public static func variation(record data: Data, at offset: Int = 0) -> TLSClientHelloVariation? {
+        guard let handshake = recordPayload(data, at: offset) else {
+            return nil
+        }
+        return variation(handshakeBytes: handshake)
+    }
+
+    /// Extracts variation features from a bare handshake message (no record layer).
+    public static func variation(handshake data: Data) -> TLSClientHelloVariation? {
+        variation(handshakeBytes: [UInt8](data))
+    }
+
+    private static func recordPayload(_ data: Data, at offset: Int) -> [UInt8]? {
+        let bytes = [UInt8](data)
+        guard bytes.count >= offset + 5, bytes[offset] == 0x16, bytes[offset + 1] == 0x03 else {
+            return nil
+        }
+        let recordLength = Int(bytes[offset + 3]) << 8 | Int(bytes[offset + 4])
+        guard bytes.count >= offset + 5 + recordLength else {
+            return nil
+        }
+        return Array(bytes[(offset + 5) ..< (offset + 5 + recordLength)])
+    }
+
+    private struct ClientHelloShape {
+        var legacyVersion: UInt16 = 0
+        var ciphers: [UInt16] = []
+        var extensionTypes: [UInt16] = []
+        var groups: [UInt16] = []
+        var pointFormats: [UInt8] = []
+        var signatureAlgorithms: [UInt16] = []
+        var supportedVersions: [UInt16] = []
+        var firstALPNProtocol: String?
+        var sniPresent = false
+        var sessionIDLength = 0
+    }
+
+    private static func fingerprint(handshakeBytes bytes: [UInt8], transport: TLSFingerprintTransport) -> TLSClientHelloFingerprint? {
+        guard let shape = parseClientHello(bytes) else {
+            return nil
+        }
+        guard let ja3 = ja3(for: shape), let ja4 = ja4(for: shape, transport: transport) else {
+            return nil
+        }
+        return TLSClientHelloFingerprint(ja3: ja3, ja4: ja4)
+    }
+
+    private static func variation(handshakeBytes bytes: [UInt8]) -> TLSClientHelloVariation? {
+        guard let shape = parseClientHello(bytes) else {
+            return nil
+        }
+        let greaseValueCount = [shape.ciphers, shape.extensionTypes, shape.groups, shape.supportedVersions]
+            .reduce(0) { $0 + $1.filter(isGREASE).count }
+        let offersResumption = shape.sessionIDLength > 0
+            || shape.extensionTypes.contains(sessionTicketExtension)
+            || shape.extensionTypes.contains(preSharedKeyExtension)
+        return TLSClientHelloVariation(
+            greaseValueCount: greaseValueCount,
+            hasUnusualCipherOrder: hasUnusualCipherOrder(shape.ciphers),
+            offersSessionResumption: offersResumption
+        )
+    }
+
+    private static func hasUnusualCipherOrder(_ ciphers: [UInt16]) -> Bool {
+        var sawLegacySuite = false
+        for cipher in ciphers where !isGREASE(cipher) {
+            if (0x1301 ... 0x1305).contains(cipher) {
+                if sawLegacySuite {
+                    return true
+                }
+            } else {
+                sawLegacySuite = true
+            }
+        }
+        return false
+    }
+
+    private static func parseClientHello(_ bytes: [UInt8]) -> ClientHelloShape? {
+        guard bytes.count >= 4, bytes[0] == 0x01 else {
+            return nil
+        }
+        let handshakeLength = Int(bytes[1]) << 16 | Int(bytes[2]) << 8 | Int(bytes[3])
+        let handshakeEnd = 4 + handshakeLength
+        guard handshakeEnd <= bytes.count else {
+            return nil
+        }
+
+        var shape = ClientHelloShape()
+        var cursor = 4
+        guard cursor + 34 < handshakeEnd else {
+            return nil
+        }
+        shape.legacyVersion = UInt16(bytes[cursor]) << 8 | UInt16(bytes[cursor + 1])
+        cursor += 34
+
+        shape.sessionIDLength = Int(bytes[cursor])
+        cursor += 1 + shape.sessionIDLength
+        guard cursor + 2 <= handshakeEnd else {
+            return nil
+        }
+
+        let cipherSuitesLength = Int(bytes[cursor]) << 8 | Int(bytes[cursor + 1])
+        cursor += 2
+        guard cursor + cipherSuitesLength <= handshakeEnd, cipherSuitesLength % 2 == 0 else {
+            return nil
+        }
+        var cipherCursor = cursor
+        while cipherCursor + 2 <= cursor + cipherSuitesLength {
+            shape.ciphers.append(UInt16(bytes[cipherCursor]) << 8 | UInt16(bytes[cipherCursor + 1]))
+            cipherCursor += 2
+        }
+        cursor += cipherSuitesLength
+        guard cursor < handshakeEnd else {
+            return nil
+        }
+
+        let compressionLength = Int(bytes[cursor])
+        cursor += 1 + compressionLength
+        guard cursor + 2 <= handshakeEnd else {
+            // A hello without extensions is fingerprintable, just sparse.
+            return cursor == handshakeEnd ? shape : nil
+        }
+
+        let extensionsLength = Int(bytes[cursor]) << 8 | Int(bytes[cursor + 1])
+        cursor += 2
+        let extensionsEnd = cursor + extensionsLength
+        guard extensionsEnd <= handshakeEnd else {
+            return nil
+        }
+        while cursor + 4 <= extensionsEnd {
+            let extensionType = UInt16(bytes[cursor]) << 8 | UInt16(bytes[cursor + 1])
+            let extensionLength = Int(bytes[cursor + 2]) << 8 | Int(bytes[cursor + 3])
+            cursor += 4
+            guard cursor + extensionLength <= extensionsEnd else {
+                return nil
+            }
+            shape.extensionTypes.append(extensionType)
+            let body = Array(bytes[cursor ..< cursor + extensionLength])
+            switch extensionType {
+            case sniExtension:
+                shape.sniPresent = true
+            case supportedGroupsExtension:
+                shape.groups = parseUInt16List(body, listLengthBytes: 2)
+            case pointFormatsExtension:
+                if let length = body.first, body.count >= 1 + Int(length) {
+                    shape.pointFormats = Array(body[1 ..< 1 + Int(length)])
+                }
+            case signatureAlgorithmsExtension:
+                shape.signatureAlgorithms = parseUInt16List(body, listLengthBytes: 2)
+            case supportedVersionsExtension:
+                shape.supportedVersions = parseUInt16List(body, listLengthBytes: 1)
+            case alpnExtension:
+                shape.firstALPNProtocol = parseFirstALPNProtocol(body)
+            default:
+                break
+            }
+            cursor += extensionLength
+        }
+        return shape
+    }
+
+    private static func parseUInt16List(_ body: [UInt8], listLengthBytes: Int) -> [UInt16] {
+        guard body.count >= listLengthBytes else {
+            return []
+        }
+        let listLength = listLengthBytes == 1
+            ? Int(body[0])
+            : Int(body[0]) << 8 | Int(body[1])
+        let end = min(body.count, listLengthBytes + listLength)
+        var values: [UInt16] = []
+        var cursor = listLengthBytes
+        while cursor + 2 <= end {
+            values.append(UInt16(body[cursor]) << 8 | UInt16(body[cursor + 1]))
+            cursor += 2
+        }
+        return values
+    }
+
+    private static func parseFirstALPNProtocol(_ body: [UInt8]) -> String? {
+        guard body.count >= 3 else {
+            return nil
+        }
+        let nameLength = Int(body[2])
+        guard body.count >= 3 + nameLength, nameLength > 0 else {
+            return nil
+        }
+        return String(bytes: body[3 ..< 3 + nameLength], encoding: .utf8)
+    }
+
+    /// GREASE values (RFC 8701) are random per-connection and must not affect fingerprints.
+    private static func isGREASE(_ value: UInt16) -> Bool {
+        (value & 0xFF) == (value >> 8) && (value & 0x0F) == 0x0A
+    }
+
+    private static func ja3(for shape: ClientHelloShape) -> String? {
+        let ciphers = shape.ciphers.filter { !isGREASE($0) }.map(String.init)
+        let extensions = shape.extensionTypes.filter { !isGREASE($0) }.map(String.init)
+        let groups = shape.groups.filter { !isGREASE($0) }.map(String.init)
+        let formats = shape.pointFormats.map(String.init)
+        let ja3String = [
+            String(shape.legacyVersion),
+            ciphers.joined(separator: "-"),
+            extensions.joined(separator: "-"),
+            groups.joined(separator: "-"),
+            formats.joined(separator: "-")
+        ].joined(separator: ",")
+        return md5Hex(ja3String)
+    }
+
+    private static func ja4(for shape: ClientHelloShape, transport: TLSFingerprintTransport) -> String? {
+        let version = shape.supportedVersions.filter { !isGREASE($0) }.max() ?? shape.legacyVersion
+        let versionLabel: String
+        switch version {
+        case 0x0304: versionLabel = "13"
+        case 0x0303: versionLabel = "12"
+        case 0x0302: versionLabel = "11"
+        default: versionLabel = "10"
+        }
+
+        let ciphers = shape.ciphers.filter { !isGREASE($0) }
+        let extensions = shape.extensionTypes.filter { !isGREASE($0) }
+        let alpn: String
+        if let protocolName = shape.firstALPNProtocol, let first = protocolName.first, let last = protocolName.last {
+            alpn = "\(first)\(last)"
+        } else {
+            alpn = "00"
+        }
+        let header = transport.rawValue
+            + versionLabel
+            + (shape.sniPresent ? "d" : "i")
+            + String(format: "%02d", min(ciphers.count, 99))
+            + String(format: "%02d", min(extensions.count, 99))
+            + alpn
+
+        let sortedCiphers = ciphers.sorted().map { String(format: "%04x", $0) }.joined(separator: ",")
+        let hashedExtensions = extensions
+            .filter { $0 != sniExtension && $0 != alpnExtension }
+            .sorted()
+            .map { String(format: "%04x", $0) }
+            .joined(separator: ",")
+        let signatureAlgorithms = shape.signatureAlgorithms
+            .filter { !isGREASE($0) }
+            .map { String(format: "%04x", $0) }
+            .joined(separator: ",")
+        let extensionInput = signatureAlgorithms.isEmpty
+            ? hashedExtensions
+            : hashedExtensions + "_" + signatureAlgorithms
+
+        guard let cipherHash = sha256Prefix12(sortedCiphers),
+              let extensionHash = sha256Prefix12(extensionInput) else {
+            return nil
+        }
+        return header + "_" + cipherHash + "_" + extensionHash
+    }
+
+    private static func md5Hex(_ input: String) -> String? {
+#if canImport(CryptoKit)
+        Insecure.MD5.hash(data: Data(input.utf8)).map { String(format: "%02x", $0) }.joined()
+#else
+        nil
+#endif
+    }
+
+    private static func sha256Prefix12(_ input: String) -> String? {
+        guard !input.isEmpty else {
+            return "000000000000"
+        }
+#if canImport(CryptoKit)
+        return String(SHA256.hash(data: Data(input.utf8)).map { String(format: "%02x", $0) }.joined().prefix(12))
+#else
+        return nil
+#endif
+    }
+}
@@ -6,6 +6,60 @@ import DataplaneFFI
 import Foundation
 import Observability
 
+/// Default dataplane observer that forwards bridge callbacks into the structured log.
+/// Decision: the runtime talks to `DataplaneObserver` only, so harnesses and tests can
+/// substitute richer mocks without touching any callback plumbing.
+public final class LoggingDataplaneObserver: DataplaneObserver {
+    private let logger: StructuredLogger
+
+    /// - Parameter logger: Structured logger receiving dataplane callback events.
+    public init(logger: StructuredLogger) {
+        self.logger = logger
+    }
+
+    public func dataplaneDidLog(_ message: String) {
+        // Engine log lines arrive once per frame at debug verbosity; route them through the
+        // hot-path entry point so a configured `.dataplane` sampling ratio can thin them out.
+        logger.logHotPath(
+            level: .debug,
+            phase: .relay,
+            category: .dataplane,
+            component: "DataplaneCallback",
+            event: "log",
+            message: message
+        )
+    }
+
+    public func dataplaneStateDidChange(to state: DataplaneState) {
+        guard logger.isEnabled(.debug) else { return }
+        Task { [logger] in
+            await logger.log(
+                level: .debug,
+                phase: .relay,
+                category: .dataplane,
+                component: "DataplaneCallback",
+                event: "state",
+                result: "\(state.rawValue)",
+                message: "Dataplane state callback"
+            )
+        }
+    }
+
+    public func dataplaneDidFail(exitCode: Int32) {
+        Task { [logger] in
+            await logger.log(
+                level: .error,
+                phase: .relay,
+                category: .dataplane,
+                component: "DataplaneCallback",
+                event: "fatal-error",
+                errorCode: String(exitCode),
+                message: "Dataplane poll task died and exhausted automatic restarts"
+            )
+        }
+    }
+}
+
 /// Actor-owned runtime state machine that avoids NetworkExtension dependencies.
 public actor TunnelRuntime {
     private let clock: any Clock
@@ -13,6 +67,7 @@ public actor TunnelRuntime {
     private let randomSource: any RandomSource
     private let logger: StructuredLogger
     private let snapshotSink: (any RuntimeSnapshotSink)?
+    private let dataplaneObserver: any DataplaneObserver
 
     private var state: RuntimeState = .idle
     private var dataplane: DataplaneHandle?
@@ -29,18 +84,22 @@ public actor TunnelRuntime {
     ///   - randomSource: Random source used to generate session IDs.
     ///   - logger: Structured logger for runtime/dataplane lifecycle events.
     ///   - snapshotSink: Optional sink that receives runtime snapshots.
+    ///   - dataplaneObserver: Observer receiving dataplane bridge callbacks. Defaults to
+    ///     forwarding them into the structured log.
     public init(
         clock: any Clock,
         runIdGenerator: any RunIdGenerator,
         randomSource: any RandomSource,
         logger: StructuredLogger,
-        snapshotSink: (any RuntimeSnapshotSink)? = nil
+        snapshotSink: (any RuntimeSnapshotSink)? = nil,
+        dataplaneObserver: (any DataplaneObserver)? = nil
     ) {
         self.clock = clock
         self.runIdGenerator = runIdGenerator
         self.randomSource = randomSource
         self.logger = logger
         self.snapshotSink = snapshotSink
+        self.dataplaneObserver = dataplaneObserver ?? LoggingDataplaneObserver(logger: logger)
     }
 
     /// Starts the runtime and dataplane.
@@ -73,40 +132,10 @@ public actor TunnelRuntime {
             message: "Starting runtime"
         )
 
-        let callbacks = DataplaneCallbacks(
-            onLog: { [logger] value in
-                guard logger.isEnabled(.debug) else { return }
-                Task {
-                    await logger.log(
-                        level: .debug,
-                        phase: .relay,
-                        category: .dataplane,
-                        component: "DataplaneCallback",
-                        event: "log",
-                        message: value
-                    )
-                }
-            },
-            onState: { [logger] state in
-                guard logger.isEnabled(.debug) else { return }
-                Task {
-                    await logger.log(
-                        level: .debug,
-                        phase: .relay,
-                        category: .dataplane,
-                        component: "DataplaneCallback",
-                        event: "state",
-                        result: "\(state.rawValue)",
-                        message: "Dataplane state callback"
-                    )
-                }
-            }
-        )
-
         do {
             let handle = try DataplaneHandle(
                 configJSON: configJSON,
-                callbacks: callbacks,
+                observer: dataplaneObserver,
                 logger: logger
             )
             dataplane = handle
@@ -1551,6 +1551,75 @@ final class AnalyticsTests: XCTestCase {
         XCTAssertTrue(exportedValidationRecords.contains { $0["kind"] as? String == PacketSampleKind.packetCue.rawValue && $0["timestampMs"] != nil })
     }
 
+    /// Verifies a snapshot survives the compressed app-message path: compress, ship through the
+    /// response envelope, decompress, and recover the exact plain payload.
+    func testCompressedSnapshotRoundTripsThroughResponseEnvelope() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let worker = PacketTelemetryWorker(
+            pipeline: pipeline,
+            detectors: [],
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        let packet = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: Array(repeating: 0x17, count: 35)
+            )
+        )
+
+        XCTAssertTrue(worker.submit(packets: [packet], families: [], direction: .outbound).accepted)
+        await worker.flushAndWait()
+        let snapshot = await worker.recentSnapshot(limit: 10, includeValidationRecords: false)
+        await worker.stopAndWait()
+
+        let compressed = try TunnelTelemetryMessageCodec.compressSnapshot(snapshot)
+        XCTAssertEqual(compressed.algorithm, "lz4")
+        XCTAssertEqual(compressed.sampleCount, snapshot.samples.count)
+        XCTAssertGreaterThan(compressed.uncompressedByteCount, 0)
+
+        let responseData = try TunnelTelemetryMessageCodec.encodeResponse(.compressedSnapshot(compressed))
+        let decodedResponse = try TunnelTelemetryMessageCodec.decodeResponse(responseData)
+        XCTAssertEqual(decodedResponse.kind, .snapshot)
+        XCTAssertNil(decodedResponse.snapshot)
+        let decodedEnvelope = try XCTUnwrap(decodedResponse.compressedSnapshot)
+        XCTAssertEqual(decodedEnvelope, compressed)
+
+        let recovered = try TunnelTelemetryMessageCodec.decompressSnapshot(decodedEnvelope)
+        XCTAssertEqual(recovered, snapshot)
+    }
+
+    /// Verifies a tampered compressed envelope fails with a telemetry-specific error instead of
+    /// decoding garbage.
+    func testDecompressSnapshotRejectsTamperedEnvelope() throws {
+        let compressed = try TunnelTelemetryMessageCodec.compressSnapshot(.empty)
+
+        let foreignAlgorithm = TunnelTelemetryCompressedSnapshot(
+            algorithm: "zstd",
+            sampleCount: compressed.sampleCount,
+            uncompressedByteCount: compressed.uncompressedByteCount,
+            payload: compressed.payload
+        )
+        XCTAssertThrowsError(try TunnelTelemetryMessageCodec.decompressSnapshot(foreignAlgorithm))
+
+        let wrongSize = TunnelTelemetryCompressedSnapshot(
+            algorithm: compressed.algorithm,
+            sampleCount: compressed.sampleCount,
+            uncompressedByteCount: compressed.uncompressedByteCount + 1,
+            payload: compressed.payload
+        )
+        XCTAssertThrowsError(try TunnelTelemetryMessageCodec.decompressSnapshot(wrongSize))
+    }
+
     /// Verifies DNS answers can be reused to attribute later hostless UDP/443 traffic and derive service-family hints.
     func testPacketAnalyticsPipelineEmitsDNSAssociationAndServiceAttributionWhenRequested() async throws {
         let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
@@ -1560,6 +1629,15 @@ final class AnalyticsTests: XCTestCase {
             signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
         )
 
+        let dnsQuery = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [8, 8, 8, 8],
+                sourcePort: 53_000,
+                destinationPort: 53,
+                payload: makeDNSQueryPayload(queryName: "video.example.com")
+            )
+        )
         let dnsResponse = Data(
             makeIPv4UDPPacket(
                 sourceAddress: [8, 8, 8, 8],
@@ -1593,6 +1671,12 @@ final class AnalyticsTests: XCTestCase {
             emitActivitySamples: false
         )
 
+        _ = await pipeline.ingest(
+            packets: [dnsQuery],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
         _ = await pipeline.ingest(
             packets: [dnsResponse],
             families: [],
@@ -1623,6 +1707,162 @@ final class AnalyticsTests: XCTestCase {
         XCTAssertEqual(flowOpen.associationSource, .dnsAnswer)
         XCTAssertEqual(flowOpen.serviceFamily, "example.com")
         XCTAssertNotNil(flowOpen.serviceFamilyConfidence)
+
+        let integrity = await pipeline.dnsIntegrityCountersSnapshot()
+        XCTAssertEqual(integrity.matchedResponseCount, 1)
+        XCTAssertEqual(integrity.spoofCandidateCount, 0)
+
+        let resolverStats = await pipeline.dnsResolverStatsSnapshot()
+        XCTAssertEqual(resolverStats.count, 1)
+        XCTAssertEqual(resolverStats.first?.resolverAddress, "8.8.8.8")
+        XCTAssertEqual(resolverStats.first?.queryCount, 1)
+        XCTAssertEqual(resolverStats.first?.matchedResponseCount, 1)
+    }
+
+    /// Verifies a response with no matching query never updates the association cache and is counted
+    /// as a potential spoofing attempt instead.
+    func testPacketAnalyticsPipelineRejectsUnsolicitedDNSResponsesAsSpoofCandidates() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+
+        let unsolicitedResponse = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [8, 8, 8, 8],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 53,
+                destinationPort: 53_000,
+                payload: makeDNSResponsePayload(
+                    queryName: "spoofed.example.com",
+                    answerIPv4: [1, 1, 1, 1]
+                )
+            )
+        )
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: true,
+            maxMetadataProbesPerBatch: 2,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            emitDNSAssociationFields: true,
+            emitLineageFields: false,
+            emitPathRegimeFields: false,
+            emitServiceAttributionFields: false,
+            includeHostHints: false,
+            includeQUICIdentity: false,
+            activitySampleMinimumPackets: 1_024,
+            activitySampleMinimumBytes: 16 * 1_024 * 1_024,
+            activitySampleMinimumInterval: 60,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        _ = await pipeline.ingest(
+            packets: [unsolicitedResponse],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+
+        await clock.advance(by: 0.1)
+        let mediaPacket = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 53_001,
+                destinationPort: 443,
+                payload: Array(repeating: 0xc0, count: 64)
+            )
+        )
+        let records = await pipeline.ingest(
+            packets: [mediaPacket],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+
+        let flowOpen = try XCTUnwrap(records.first(where: { $0.kind == .flowOpen }))
+        XCTAssertNil(flowOpen.associatedDomain)
+
+        let integrity = await pipeline.dnsIntegrityCountersSnapshot()
+        XCTAssertEqual(integrity.matchedResponseCount, 0)
+        XCTAssertEqual(integrity.spoofCandidateCount, 1)
+    }
+
+    /// Verifies reverse IP-to-hostname lookup surfaces the most recent name observed resolving to
+    /// an address and expires with the association TTL.
+    func testPacketAnalyticsPipelineAnswersReverseHostnameLookups() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+
+        let dnsQuery = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [8, 8, 8, 8],
+                sourcePort: 53_000,
+                destinationPort: 53,
+                payload: makeDNSQueryPayload(queryName: "video.example.com")
+            )
+        )
+        let dnsResponse = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [8, 8, 8, 8],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 53,
+                destinationPort: 53_000,
+                payload: makeDNSResponsePayload(
+                    queryName: "video.example.com",
+                    answerIPv4: [1, 1, 1, 1]
+                )
+            )
+        )
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: true,
+            maxMetadataProbesPerBatch: 2,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            emitDNSAssociationFields: true,
+            emitLineageFields: false,
+            emitPathRegimeFields: false,
+            emitServiceAttributionFields: false,
+            includeHostHints: false,
+            includeQUICIdentity: false,
+            activitySampleMinimumPackets: 1_024,
+            activitySampleMinimumBytes: 16 * 1_024 * 1_024,
+            activitySampleMinimumInterval: 60,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        _ = await pipeline.ingest(packets: [dnsQuery], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [dnsResponse], families: [], direction: .inbound, policy: policy)
+
+        await clock.advance(by: 0.25)
+        let answerAddress = try XCTUnwrap(IPAddress(string: "1.1.1.1"))
+        let association = try XCTUnwrap(await pipeline.hostnameAssociation(for: answerAddress))
+        XCTAssertEqual(association.address, "1.1.1.1")
+        XCTAssertEqual(association.hostname, "video.example.com")
+        XCTAssertEqual(association.ageMs, 250)
+
+        let unknownAddress = try XCTUnwrap(IPAddress(string: "9.9.9.9"))
+        let unknown = await pipeline.hostnameAssociation(for: unknownAddress)
+        XCTAssertNil(unknown)
+
+        await clock.advance(by: 61)
+        let expired = await pipeline.hostnameAssociation(for: answerAddress)
+        XCTAssertNil(expired)
     }
 
     /// Verifies DNS answers and DNS association survive all the way into the app-facing live tap snapshot.
@@ -1672,6 +1912,15 @@ final class AnalyticsTests: XCTestCase {
             includeFlowSlicesInLiveTap: false
         )
 
+        let dnsQuery = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [8, 8, 8, 8],
+                sourcePort: 53_000,
+                destinationPort: 53,
+                payload: makeDNSQueryPayload(queryName: "video.example.com")
+            )
+        )
         let dnsResponse = Data(
             makeIPv4UDPPacket(
                 sourceAddress: [8, 8, 8, 8],
@@ -1685,6 +1934,8 @@ final class AnalyticsTests: XCTestCase {
             )
         )
 
+        XCTAssertTrue(worker.submit(packets: [dnsQuery], families: [], direction: .outbound).accepted)
+        await worker.flushAndWait()
         XCTAssertTrue(worker.submit(packets: [dnsResponse], families: [], direction: .inbound).accepted)
         await worker.flushAndWait()
 
@@ -2513,6 +2764,25 @@ final class AnalyticsTests: XCTestCase {
         )
     }
 
+    private func makeDNSQueryPayload(queryName: String) -> [UInt8] {
+        var payload: [UInt8] = [
+            0x12, 0x34,
+            0x01, 0x00,
+            0x00, 0x01,
+            0x00, 0x00,
+            0x00, 0x00,
+            0x00, 0x00
+        ]
+
+        for label in queryName.split(separator: ".") {
+            payload.append(UInt8(label.count))
+            payload.append(contentsOf: label.utf8)
+        }
+        payload.append(0x00)
+        payload.append(contentsOf: [0x00, 0x01, 0x00, 0x01])
+        return payload
+    }
+
     private func makeDNSResponsePayload(queryName: String, answerIPv4: [UInt8]) -> [UInt8] {
         var payload: [UInt8] = [
             0x12, 0x34,
@@ -0,0 +1,116 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Batch-size and processing-latency histogram tests.
+final class BatchExecutionHistogramTests: XCTestCase {
+    /// Verifies batches land in the inclusive packet-count and duration buckets that contain them.
+    func testRecordAssignsInclusiveBuckets() {
+        var histograms = BatchExecutionHistograms()
+        histograms.record(packetCount: 1, durationMicroseconds: 50)
+        histograms.record(packetCount: 2, durationMicroseconds: 51)
+        histograms.record(packetCount: 128, durationMicroseconds: 10_000)
+        histograms.record(packetCount: 500, durationMicroseconds: 40_000)
+
+        XCTAssertEqual(histograms.packetCountBucketCounts[0], 1)
+        XCTAssertEqual(histograms.packetCountBucketCounts[1], 1)
+        XCTAssertEqual(
+            histograms.packetCountBucketCounts[BatchExecutionHistograms.packetCountUpperBounds.count - 1],
+            1
+        )
+        XCTAssertEqual(
+            histograms.packetCountBucketCounts[BatchExecutionHistograms.packetCountUpperBounds.count],
+            1
+        )
+        XCTAssertEqual(histograms.durationBucketCounts[0], 1)
+        XCTAssertEqual(histograms.durationBucketCounts[1], 1)
+        XCTAssertEqual(
+            histograms.durationBucketCounts[BatchExecutionHistograms.durationUpperBoundsMicroseconds.count - 1],
+            1
+        )
+        XCTAssertEqual(
+            histograms.durationBucketCounts[BatchExecutionHistograms.durationUpperBoundsMicroseconds.count],
+            1
+        )
+        XCTAssertEqual(histograms.batchCount, 4)
+        XCTAssertEqual(histograms.totalPackets, 1 + 2 + 128 + 500)
+        XCTAssertEqual(histograms.totalMicroseconds, 50 + 51 + 10_000 + 40_000)
+        XCTAssertEqual(histograms.maxMicroseconds, 40_000)
+    }
+
+    /// Verifies empty batches are ignored and negative durations are clamped to zero.
+    func testRecordIgnoresEmptyBatchesAndClampsNegativeDurations() {
+        var histograms = BatchExecutionHistograms()
+        histograms.record(packetCount: 0, durationMicroseconds: 200)
+        XCTAssertTrue(histograms.isEmpty)
+
+        histograms.record(packetCount: 3, durationMicroseconds: -5)
+        XCTAssertEqual(histograms.batchCount, 1)
+        XCTAssertEqual(histograms.durationBucketCounts[0], 1)
+        XCTAssertEqual(histograms.totalMicroseconds, 0)
+        XCTAssertEqual(histograms.maxMicroseconds, 0)
+    }
+
+    /// Verifies the worker accumulates per-batch histograms visible through its snapshot.
+    func testWorkerSnapshotReportsBatchExecution() async {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let pipeline = PacketAnalyticsPipeline(
+            clock: SystemClock(),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: logger)
+        )
+        let worker = PacketTelemetryWorker(pipeline: pipeline, logger: logger)
+        let packet = Data(makeIPv4UDPPacket(sourcePort: 50_000, destinationPort: 53, payload: [0x01, 0x02]))
+        _ = worker.submit(packets: [packet, packet], families: [], direction: .outbound)
+        await worker.flushAndWait()
+
+        let snapshot = worker.snapshot()
+        XCTAssertEqual(snapshot.batchExecution.batchCount, 1)
+        XCTAssertEqual(snapshot.batchExecution.totalPackets, 2)
+        await worker.stopAndWait()
+    }
+
+    /// Verifies the histograms survive an encode/decode round trip.
+    func testBatchExecutionHistogramsRoundTrip() throws {
+        var histograms = BatchExecutionHistograms()
+        histograms.record(packetCount: 16, durationMicroseconds: 800)
+
+        let data = try JSONEncoder().encode(histograms)
+        let decoded = try JSONDecoder().decode(BatchExecutionHistograms.self, from: data)
+        XCTAssertEqual(decoded, histograms)
+    }
+
+    private func makeIPv4UDPPacket(
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 8 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 17
+        packet[12..<16] = [10, 0, 0, 2][0..<4]
+        packet[16..<20] = [203, 0, 113, 9][0..<4]
+
+        let udpOffset = 20
+        let udpLength = 8 + payload.count
+        packet[udpOffset] = UInt8(sourcePort >> 8)
+        packet[udpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[udpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[udpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[udpOffset + 4] = UInt8(udpLength >> 8)
+        packet[udpOffset + 5] = UInt8(udpLength & 0xff)
+        if !payload.isEmpty {
+            packet[(udpOffset + 8)...] = payload[0...]
+        }
+        return packet
+    }
+}
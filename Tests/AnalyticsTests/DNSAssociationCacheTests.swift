@@ -0,0 +1,172 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// DNS association cache bounds, eviction accounting, and recency tests.
+final class DNSAssociationCacheTests: XCTestCase {
+    /// Verifies expired entries are swept on the batch cadence and counted as TTL evictions.
+    func testSweepDropsExpiredEntriesAndCountsThem() {
+        var cache = DNSAssociationCache()
+        let storedAt = Date(timeIntervalSinceReferenceDate: 0)
+
+        let disposition = cache.record(
+            metadata: makeResponseMetadata(queryName: "video.example.com", answer: [1, 1, 1, 1]),
+            classification: nil,
+            queryMatched: true,
+            now: storedAt
+        )
+        XCTAssertEqual(disposition, .recorded)
+        XCTAssertEqual(cache.statsSnapshot().entryCount, 1)
+
+        cache.sweepExpired(now: storedAt.addingTimeInterval(61))
+
+        let stats = cache.statsSnapshot()
+        XCTAssertEqual(stats.entryCount, 0)
+        XCTAssertEqual(stats.expiredEvictionCount, 1)
+        XCTAssertEqual(stats.overflowEvictionCount, 0)
+    }
+
+    /// Verifies the global cap evicts least-recently-used entries and that a lookup refreshes
+    /// an entry's recency so it survives the overflow trim.
+    func testOverflowEvictsLeastRecentlyUsedEntries() {
+        var cache = DNSAssociationCache()
+        let now = Date(timeIntervalSinceReferenceDate: 0)
+
+        for index in 0..<4_096 {
+            let answer: [UInt8] = [10, 0, UInt8(index >> 8), UInt8(index & 0xff)]
+            _ = cache.record(
+                metadata: makeResponseMetadata(queryName: "host-\(index).example.com", answer: answer),
+                classification: nil,
+                queryMatched: true,
+                now: now
+            )
+        }
+        XCTAssertEqual(cache.statsSnapshot().entryCount, 4_096)
+
+        let firstAddress = IPAddress(bytes: Data([10, 0, 0, 0]))!
+        XCTAssertNotNil(cache.lookupHostname(for: firstAddress, now: now))
+
+        _ = cache.record(
+            metadata: makeResponseMetadata(queryName: "overflow.example.com", answer: [10, 16, 0, 0]),
+            classification: nil,
+            queryMatched: true,
+            now: now
+        )
+
+        let stats = cache.statsSnapshot()
+        XCTAssertEqual(stats.entryCount, 4_096)
+        XCTAssertEqual(stats.overflowEvictionCount, 1)
+        XCTAssertNotNil(cache.lookupHostname(for: firstAddress, now: now))
+        XCTAssertNil(cache.lookupHostname(for: IPAddress(bytes: Data([10, 0, 0, 1]))!, now: now))
+    }
+
+    /// Verifies an exported snapshot carries remaining lifetimes and can pre-seed a fresh
+    /// cache after a restart without granting entries a new full TTL.
+    func testExportImportRoundTripHonorsRemainingTTL() {
+        var cache = DNSAssociationCache()
+        let storedAt = Date(timeIntervalSinceReferenceDate: 0)
+        _ = cache.record(
+            metadata: makeResponseMetadata(queryName: "video.example.com", answer: [1, 1, 1, 1]),
+            classification: nil,
+            queryMatched: true,
+            now: storedAt
+        )
+
+        let exported = cache.exportSnapshot(now: storedAt.addingTimeInterval(20))
+        XCTAssertEqual(exported.count, 1)
+        XCTAssertEqual(exported.first?.address, "1.1.1.1")
+        XCTAssertEqual(exported.first?.hostname, "video.example.com")
+        XCTAssertEqual(exported.first?.associatedDomain, "example.com")
+        XCTAssertEqual(exported.first?.remainingTTLMilliseconds, 40_000)
+
+        var restarted = DNSAssociationCache()
+        let rebootAt = Date(timeIntervalSinceReferenceDate: 1_000)
+        XCTAssertEqual(restarted.importEntries(exported, now: rebootAt), 1)
+
+        let address = IPAddress(string: "1.1.1.1")!
+        XCTAssertEqual(restarted.lookupHostname(for: address, now: rebootAt)?.hostname, "video.example.com")
+        // The seed expires when its remaining lifetime runs out, not one full TTL later.
+        XCTAssertNotNil(restarted.lookupHostname(for: address, now: rebootAt.addingTimeInterval(39)))
+        XCTAssertNil(restarted.lookupHostname(for: address, now: rebootAt.addingTimeInterval(41)))
+    }
+
+    /// Verifies import skips unusable entries, clamps oversized lifetimes to the cache TTL,
+    /// and never overwrites an association this session already observed.
+    func testImportSkipsStaleEntriesAndKeepsLiveObservations() {
+        var cache = DNSAssociationCache()
+        let now = Date(timeIntervalSinceReferenceDate: 0)
+        _ = cache.record(
+            metadata: makeResponseMetadata(queryName: "live.example.com", answer: [1, 1, 1, 1]),
+            classification: nil,
+            queryMatched: true,
+            now: now
+        )
+
+        let accepted = cache.importEntries([
+            DNSAssociationExportEntry(
+                address: "1.1.1.1",
+                hostname: "persisted.example.com",
+                associatedDomain: "example.com",
+                remainingTTLMilliseconds: 30_000,
+                confidence: 0.9
+            ),
+            DNSAssociationExportEntry(
+                address: "not-an-address",
+                hostname: nil,
+                associatedDomain: "example.com",
+                remainingTTLMilliseconds: 30_000,
+                confidence: 0.9
+            ),
+            DNSAssociationExportEntry(
+                address: "2.2.2.2",
+                hostname: nil,
+                associatedDomain: "example.com",
+                remainingTTLMilliseconds: 0,
+                confidence: 0.9
+            ),
+            DNSAssociationExportEntry(
+                address: "3.3.3.3",
+                hostname: "seeded.example.com",
+                associatedDomain: "example.com",
+                remainingTTLMilliseconds: 120_000,
+                confidence: 0.9
+            )
+        ], now: now)
+
+        XCTAssertEqual(accepted, 1)
+        XCTAssertEqual(
+            cache.lookupHostname(for: IPAddress(string: "1.1.1.1")!, now: now)?.hostname,
+            "live.example.com"
+        )
+        // The oversized lifetime clamps to the cache TTL instead of extending it.
+        XCTAssertNotNil(cache.lookupHostname(for: IPAddress(string: "3.3.3.3")!, now: now.addingTimeInterval(59)))
+        XCTAssertNil(cache.lookupHostname(for: IPAddress(string: "3.3.3.3")!, now: now.addingTimeInterval(61)))
+    }
+
+    private func makeResponseMetadata(queryName: String, answer: [UInt8]) -> PacketMetadata {
+        PacketMetadata(
+            ipVersion: .v4,
+            transport: .udp,
+            srcAddress: IPAddress(bytes: Data([8, 8, 8, 8]))!,
+            dstAddress: IPAddress(bytes: Data([10, 0, 0, 2]))!,
+            srcPort: 53,
+            dstPort: 53_000,
+            length: 64,
+            dnsQueryName: queryName,
+            dnsCname: nil,
+            dnsAnswerAddresses: [IPAddress(bytes: Data(answer))!],
+            dnsTransactionId: 0x1234,
+            dnsIsResponse: true,
+            registrableDomain: "example.com",
+            tlsServerName: nil,
+            quicVersion: nil,
+            quicPacketType: nil,
+            quicDestinationConnectionId: nil,
+            quicSourceConnectionId: nil
+        )
+    }
+}
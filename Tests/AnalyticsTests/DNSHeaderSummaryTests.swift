@@ -0,0 +1,61 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// Tests for the C-core DNS message header summary.
+final class DNSHeaderSummaryTests: XCTestCase {
+    /// Verifies all header fields are decoded from a standard response header.
+    func testParsesResponseHeaderFields() throws {
+        // ID 0xabcd, QR=1, opcode 0, TC=1, RCODE 3 (NXDOMAIN), counts 1/2/3/4.
+        let message = Data([
+            0xab, 0xcd,
+            0x82, 0x03,
+            0x00, 0x01,
+            0x00, 0x02,
+            0x00, 0x03,
+            0x00, 0x04
+        ])
+
+        let header = try XCTUnwrap(DNSHeaderSummary(message: message))
+        XCTAssertEqual(header.transactionID, 0xabcd)
+        XCTAssertTrue(header.isResponse)
+        XCTAssertEqual(header.opcode, 0)
+        XCTAssertTrue(header.isTruncated)
+        XCTAssertEqual(header.responseCode, 3)
+        XCTAssertEqual(header.questionCount, 1)
+        XCTAssertEqual(header.answerCount, 2)
+        XCTAssertEqual(header.authorityCount, 3)
+        XCTAssertEqual(header.additionalCount, 4)
+    }
+
+    /// Verifies a query header reports no response bit and the payload offset is honored.
+    func testParsesQueryHeaderAtPayloadOffset() throws {
+        let prefix = Data(repeating: 0xff, count: 8)
+        let message = prefix + Data([
+            0x00, 0x2a,
+            0x01, 0x00,
+            0x00, 0x01,
+            0x00, 0x00,
+            0x00, 0x00,
+            0x00, 0x00
+        ])
+
+        let header = try XCTUnwrap(DNSHeaderSummary(message: message, payloadOffset: prefix.count))
+        XCTAssertEqual(header.transactionID, 0x2a)
+        XCTAssertFalse(header.isResponse)
+        XCTAssertFalse(header.isTruncated)
+        XCTAssertEqual(header.questionCount, 1)
+        XCTAssertEqual(header.answerCount, 0)
+    }
+
+    /// Verifies truncated buffers and out-of-range offsets fail the parse instead of reading past the end.
+    func testRejectsShortBuffersAndBadOffsets() {
+        XCTAssertNil(DNSHeaderSummary(message: Data(repeating: 0, count: 11)))
+        XCTAssertNil(DNSHeaderSummary(message: Data(repeating: 0, count: 16), payloadOffset: 5))
+        XCTAssertNil(DNSHeaderSummary(message: Data(repeating: 0, count: 16), payloadOffset: 20))
+        XCTAssertNil(DNSHeaderSummary(message: Data(), payloadOffset: 0))
+    }
+}
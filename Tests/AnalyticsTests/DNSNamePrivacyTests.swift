@@ -0,0 +1,51 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// DNS name privacy policy bounds and hashing contract tests.
+final class DNSNamePrivacyTests: XCTestCase {
+    /// Verifies the default policy passes names through untouched.
+    func testPlaintextPolicyPassesNamesThrough() {
+        let policy = DNSNamePrivacyPolicy.plaintext
+        XCTAssertEqual(policy.apply(to: "cdn.example.com"), "cdn.example.com")
+        XCTAssertNil(policy.apply(to: nil))
+        XCTAssertEqual(policy.apply(to: ""), "")
+    }
+
+    /// Verifies truncation respects the configured UTF-8 byte bound without splitting scalars.
+    func testTruncatedPolicyBoundsNameLength() {
+        let policy = DNSNamePrivacyPolicy(mode: .truncated, maxNameLength: 16)
+        let longName = String(repeating: "a", count: 40) + ".example.com"
+        let truncated = policy.apply(to: longName)
+        XCTAssertEqual(truncated, String(repeating: "a", count: 16))
+
+        let multiByte = String(repeating: "ü", count: 20)
+        let truncatedMultiByte = DNSNamePrivacyPolicy.truncated(multiByte, maxUTF8Bytes: 15)
+        XCTAssertLessThanOrEqual(truncatedMultiByte.utf8.count, 15)
+        XCTAssertTrue(multiByte.hasPrefix(truncatedMultiByte))
+
+        XCTAssertEqual(policy.apply(to: "short.example"), "short.example")
+    }
+
+    /// Verifies hashed tokens are stable in-process, name-specific, and never plaintext.
+    func testHashedPolicyEmitsStableOpaqueTokens() {
+        let policy = DNSNamePrivacyPolicy.hashed
+        let first = policy.apply(to: "video.example.com")
+        let second = policy.apply(to: "video.example.com")
+        let other = policy.apply(to: "tracker.example.net")
+
+        XCTAssertEqual(first, second)
+        XCTAssertNotEqual(first, other)
+        XCTAssertEqual(first?.hasPrefix("dns#"), true)
+        XCTAssertEqual(first?.contains("example"), false)
+    }
+
+    /// Verifies the policy clamps out-of-range length configuration.
+    func testPolicyClampsMaxNameLength() {
+        XCTAssertEqual(DNSNamePrivacyPolicy(mode: .truncated, maxNameLength: 1).maxNameLength, 16)
+        XCTAssertEqual(DNSNamePrivacyPolicy(mode: .truncated, maxNameLength: 1_000_000).maxNameLength, 1_024)
+    }
+}
@@ -0,0 +1,136 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// DNS transaction matching, resolver latency, and timeout accounting tests.
+final class DNSTransactionTrackerTests: XCTestCase {
+    /// Verifies a response matching transaction ID, qname, and resolver records its latency.
+    func testMatchedResponseRecordsResolverLatency() {
+        var tracker = DNSTransactionTracker()
+        let askedAt = Date(timeIntervalSinceReferenceDate: 0)
+
+        tracker.noteQuery(
+            metadata: makeQueryMetadata(transactionId: 0x1234, queryName: "video.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt
+        )
+        let match = tracker.matchResponse(
+            metadata: makeResponseMetadata(transactionId: 0x1234, queryName: "VIDEO.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt.addingTimeInterval(0.05)
+        )
+
+        XCTAssertEqual(match, DNSTransactionMatch(resolverAddress: "8.8.8.8", latencyMs: 50))
+        let stats = tracker.statsSnapshot()
+        XCTAssertEqual(stats.count, 1)
+        XCTAssertEqual(stats[0].resolverAddress, "8.8.8.8")
+        XCTAssertEqual(stats[0].queryCount, 1)
+        XCTAssertEqual(stats[0].matchedResponseCount, 1)
+        XCTAssertEqual(stats[0].timeoutCount, 0)
+        XCTAssertEqual(stats[0].totalLatencyMs, 50)
+        XCTAssertEqual(stats[0].maxLatencyMs, 50)
+        XCTAssertEqual(stats[0].averageLatencyMs, 50)
+    }
+
+    /// Verifies a response from an address the query was never sent to does not match.
+    func testResponseFromDifferentResolverDoesNotMatch() {
+        var tracker = DNSTransactionTracker()
+        let askedAt = Date(timeIntervalSinceReferenceDate: 0)
+
+        tracker.noteQuery(
+            metadata: makeQueryMetadata(transactionId: 0x1234, queryName: "video.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt
+        )
+        let match = tracker.matchResponse(
+            metadata: makeResponseMetadata(transactionId: 0x1234, queryName: "video.example.com", resolver: [9, 9, 9, 9]),
+            now: askedAt.addingTimeInterval(0.01)
+        )
+
+        XCTAssertNil(match)
+    }
+
+    /// Verifies a mismatched transaction ID or qname never matches.
+    func testMatchRequiresSameTransactionIdAndQname() {
+        var tracker = DNSTransactionTracker()
+        let askedAt = Date(timeIntervalSinceReferenceDate: 0)
+
+        tracker.noteQuery(
+            metadata: makeQueryMetadata(transactionId: 0x1234, queryName: "video.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt
+        )
+
+        XCTAssertNil(
+            tracker.matchResponse(
+                metadata: makeResponseMetadata(transactionId: 0x9999, queryName: "video.example.com", resolver: [8, 8, 8, 8]),
+                now: askedAt.addingTimeInterval(0.01)
+            )
+        )
+        XCTAssertNil(
+            tracker.matchResponse(
+                metadata: makeResponseMetadata(transactionId: 0x1234, queryName: "other.example.com", resolver: [8, 8, 8, 8]),
+                now: askedAt.addingTimeInterval(0.01)
+            )
+        )
+    }
+
+    /// Verifies an unanswered query is charged to its resolver as a timeout once the window lapses.
+    func testUnansweredQueryCountsAsTimeout() {
+        var tracker = DNSTransactionTracker()
+        let askedAt = Date(timeIntervalSinceReferenceDate: 0)
+
+        tracker.noteQuery(
+            metadata: makeQueryMetadata(transactionId: 0x1234, queryName: "video.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt
+        )
+        tracker.noteQuery(
+            metadata: makeQueryMetadata(transactionId: 0x5678, queryName: "late.example.com", resolver: [8, 8, 8, 8]),
+            now: askedAt.addingTimeInterval(6)
+        )
+
+        let stats = tracker.statsSnapshot()
+        XCTAssertEqual(stats.count, 1)
+        XCTAssertEqual(stats[0].queryCount, 2)
+        XCTAssertEqual(stats[0].timeoutCount, 1)
+        XCTAssertEqual(stats[0].matchedResponseCount, 0)
+    }
+
+    private func makeQueryMetadata(transactionId: UInt16, queryName: String, resolver: [UInt8]) -> PacketMetadata {
+        makeMetadata(transactionId: transactionId, queryName: queryName, resolver: resolver, isResponse: false)
+    }
+
+    private func makeResponseMetadata(transactionId: UInt16, queryName: String, resolver: [UInt8]) -> PacketMetadata {
+        makeMetadata(transactionId: transactionId, queryName: queryName, resolver: resolver, isResponse: true)
+    }
+
+    private func makeMetadata(
+        transactionId: UInt16,
+        queryName: String,
+        resolver: [UInt8],
+        isResponse: Bool
+    ) -> PacketMetadata {
+        let client = IPAddress(bytes: Data([10, 0, 0, 2]))!
+        let resolverAddress = IPAddress(bytes: Data(resolver))!
+        return PacketMetadata(
+            ipVersion: .v4,
+            transport: .udp,
+            srcAddress: isResponse ? resolverAddress : client,
+            dstAddress: isResponse ? client : resolverAddress,
+            srcPort: isResponse ? 53 : 53_000,
+            dstPort: isResponse ? 53_000 : 53,
+            length: 64,
+            dnsQueryName: queryName,
+            dnsCname: nil,
+            dnsAnswerAddresses: nil,
+            dnsTransactionId: transactionId,
+            dnsIsResponse: isResponse,
+            registrableDomain: "example.com",
+            tlsServerName: nil,
+            quicVersion: nil,
+            quicPacketType: nil,
+            quicDestinationConnectionId: nil,
+            quicSourceConnectionId: nil
+        )
+    }
+}
@@ -0,0 +1,27 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import TunnelRuntime
+import XCTest
+
+/// Registrable-domain grouping tests for the curated heuristic and the optional PSL override.
+final class DomainNormalizerTests: XCTestCase {
+    /// Verifies an installed public-suffix list replaces the curated heuristic for same-site
+    /// grouping and that uninstalling restores it.
+    func testInstalledPublicSuffixListOverridesHeuristic() {
+        defer { DomainNormalizer.installPublicSuffixList(nil) }
+
+        XCTAssertEqual(DomainNormalizer.registrableDomain(from: "tenant.hosted.example"), "hosted.example")
+
+        DomainNormalizer.installPublicSuffixList(PublicSuffixList(listText: "*.hosted.example"))
+        XCTAssertEqual(
+            DomainNormalizer.registrableDomain(from: "deep.tenant.hosted.example"),
+            "deep.tenant.hosted.example"
+        )
+
+        DomainNormalizer.installPublicSuffixList(nil)
+        XCTAssertEqual(DomainNormalizer.registrableDomain(from: "tenant.hosted.example"), "hosted.example")
+    }
+}
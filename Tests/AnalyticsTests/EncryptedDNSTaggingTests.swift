@@ -0,0 +1,124 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Encrypted-DNS (DoH/DoT) flow tagging tests.
+final class EncryptedDNSTaggingTests: XCTestCase {
+    /// Verifies flows to port 853 are classed as encrypted DNS rather than generic TLS.
+    func testDoTPortTagsFlowAsEncryptedDNS() async throws {
+        let pipeline = makePipeline()
+        let packet = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 853,
+                tcpFlags: 0x18,
+                payload: [0x16, 0x03, 0x01, 0x00, 0x05]
+            )
+        )
+
+        _ = await pipeline.ingest(packets: [packet], families: [], direction: .outbound, policy: makeEmissionPolicy())
+
+        let report = await pipeline.usageAccountingReport()
+        XCTAssertEqual(report.buckets.map(\.category), [FlowProtocolClass.encryptedDNS.rawValue])
+    }
+
+    /// Verifies 443 flows to a known DoH resolver address are classed as encrypted DNS
+    /// while ordinary 443 flows stay in the tls bucket.
+    func testKnownDoHResolverAddressTagsFlow() async throws {
+        let pipeline = makePipeline()
+        let dohPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [8, 8, 8, 8],
+                sourcePort: 50_001,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        let tlsPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 5],
+                sourcePort: 50_002,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+
+        _ = await pipeline.ingest(
+            packets: [dohPacket, tlsPacket],
+            families: [],
+            direction: .outbound,
+            policy: makeEmissionPolicy()
+        )
+
+        let report = await pipeline.usageAccountingReport()
+        XCTAssertEqual(
+            report.buckets.map(\.category).sorted(),
+            [FlowProtocolClass.encryptedDNS.rawValue, FlowProtocolClass.tls.rawValue]
+        )
+    }
+
+    private func makePipeline() -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 3_600)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,163 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Parsing and counter tests for unusual-but-legal IP header constructs.
+final class ExoticHeaderTests: XCTestCase {
+    /// Verifies an IPv4 packet carrying a router-alert option (IHL 6) parses with correct ports.
+    func testIPv4RouterAlertOptionParses() {
+        let packet = Data(makeIPv4TCPPacketWithRouterAlert(sourcePort: 50_000, destinationPort: 443))
+
+        let metadata = PacketParser.parse(packet, ipVersionHint: nil)
+        XCTAssertEqual(metadata?.ipVersion, .v4)
+        XCTAssertEqual(metadata?.transport, .tcp)
+        XCTAssertEqual(metadata?.srcPort, 50_000)
+        XCTAssertEqual(metadata?.dstPort, 443)
+    }
+
+    /// Verifies the C fast path reads transport fields past IPv4 options instead of
+    /// misreading the option bytes as the TCP header.
+    func testFastPathParsesPortsPastIPv4Options() {
+        let packet = Data(makeIPv4TCPPacketWithRouterAlert(sourcePort: 50_000, destinationPort: 443))
+
+        let summary = FastPacketSummary(data: packet, ipVersionHint: nil)
+        XCTAssertEqual(summary?.sourcePort, 50_000)
+        XCTAssertEqual(summary?.destinationPort, 443)
+        XCTAssertEqual(summary?.transportPayloadOffset, 24 + 20)
+    }
+
+    /// Verifies an IPv6 packet whose transport sits behind a hop-by-hop extension header parses.
+    func testIPv6HopByHopExtensionHeaderParses() {
+        let packet = Data(makeIPv6TCPPacket(sourcePort: 50_000, destinationPort: 443, hopByHop: true))
+
+        let metadata = PacketParser.parse(packet, ipVersionHint: nil)
+        XCTAssertEqual(metadata?.ipVersion, .v6)
+        XCTAssertEqual(metadata?.transport, .tcp)
+        XCTAssertEqual(metadata?.srcPort, 50_000)
+        XCTAssertEqual(metadata?.dstPort, 443)
+    }
+
+    /// Verifies the RFC 2675 jumbogram encoding (zero payload-length field) is tolerated:
+    /// the parser falls back to the buffer length instead of rejecting the packet.
+    func testIPv6ZeroPayloadLengthParses() {
+        var bytes = makeIPv6TCPPacket(sourcePort: 50_000, destinationPort: 443, hopByHop: false)
+        bytes[4] = 0
+        bytes[5] = 0
+        let packet = Data(bytes)
+
+        let metadata = PacketParser.parse(packet, ipVersionHint: nil)
+        XCTAssertEqual(metadata?.ipVersion, .v6)
+        XCTAssertEqual(metadata?.srcPort, 50_000)
+        XCTAssertEqual(metadata?.dstPort, 443)
+
+        let summary = FastPacketSummary(data: packet, ipVersionHint: nil)
+        XCTAssertEqual(summary?.sourcePort, 50_000)
+        XCTAssertEqual(summary?.packetLength, packet.count)
+    }
+
+    /// Verifies ingest tallies exotic header features per kind while plain packets stay uncounted.
+    func testIngestCountsExoticHeaderFeatures() async throws {
+        let pipeline = makePipeline()
+        let optionsPacket = Data(makeIPv4TCPPacketWithRouterAlert(sourcePort: 50_000, destinationPort: 443))
+        let extensionPacket = Data(makeIPv6TCPPacket(sourcePort: 50_001, destinationPort: 443, hopByHop: true))
+        var jumboBytes = makeIPv6TCPPacket(sourcePort: 50_002, destinationPort: 443, hopByHop: false)
+        jumboBytes[4] = 0
+        jumboBytes[5] = 0
+        let plainPacket = Data(makeIPv6TCPPacket(sourcePort: 50_003, destinationPort: 443, hopByHop: false))
+
+        _ = await pipeline.ingest(
+            packets: [optionsPacket, extensionPacket, Data(jumboBytes), plainPacket],
+            families: [],
+            direction: .outbound,
+            policy: makeEmissionPolicy()
+        )
+
+        let counters = await pipeline.exoticHeaderCountersSnapshot()
+        XCTAssertEqual(counters.ipv4OptionsCount, 1)
+        XCTAssertEqual(counters.ipv6ExtensionHeaderCount, 1)
+        XCTAssertEqual(counters.ipv6JumboPayloadCount, 1)
+        XCTAssertFalse(counters.isEmpty)
+
+        let invalid = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertTrue(invalid.isEmpty)
+    }
+
+    private func makePipeline() -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    /// IPv4 + TCP with a four-byte router-alert option, so IHL is 6 words.
+    private func makeIPv4TCPPacketWithRouterAlert(sourcePort: UInt16, destinationPort: UInt16) -> [UInt8] {
+        let headerLength = 24
+        var packet = [UInt8](repeating: 0, count: headerLength + 20 + 4)
+        packet[0] = 0x46
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = [10, 0, 0, 2][0...]
+        packet[16..<20] = [1, 1, 1, 1][0...]
+        packet[20..<24] = [0x94, 0x04, 0x00, 0x00][0...]
+
+        packet[headerLength] = UInt8(sourcePort >> 8)
+        packet[headerLength + 1] = UInt8(sourcePort & 0xff)
+        packet[headerLength + 2] = UInt8(destinationPort >> 8)
+        packet[headerLength + 3] = UInt8(destinationPort & 0xff)
+        packet[headerLength + 12] = 0x50
+        packet[headerLength + 13] = 0x18
+        return packet
+    }
+
+    /// IPv6 + TCP, optionally behind one minimal hop-by-hop extension header.
+    private func makeIPv6TCPPacket(sourcePort: UInt16, destinationPort: UInt16, hopByHop: Bool) -> [UInt8] {
+        let extensionLength = hopByHop ? 8 : 0
+        let payloadLength = extensionLength + 20 + 4
+        var packet = [UInt8](repeating: 0, count: 40 + payloadLength)
+        packet[0] = 0x60
+        packet[4] = UInt8(payloadLength >> 8)
+        packet[5] = UInt8(payloadLength & 0xff)
+        packet[6] = hopByHop ? 0 : 6
+        packet[7] = 64
+        packet[23] = 2
+        packet[39] = 1
+
+        var tcpOffset = 40
+        if hopByHop {
+            packet[40] = 6 // next header: TCP
+            packet[41] = 0 // header extends one 8-byte unit
+            tcpOffset = 48
+        }
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = 0x18
+        return packet
+    }
+}
@@ -0,0 +1,56 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// Bounded per-flow breadcrumb ring tests.
+final class FlowBreadcrumbTests: XCTestCase {
+    /// Verifies a flow's ring keeps only its most recent events once the per-flow cap is hit.
+    func testPerFlowRingDropsOldestPastCap() {
+        var log = FlowBreadcrumbLog()
+        let start = Date(timeIntervalSince1970: 0)
+
+        log.record(flowHash: 0xabc, event: .opened, now: start)
+        for index in 0..<FlowBreadcrumbLog.Policy.maxEventsPerFlow {
+            log.record(flowHash: 0xabc, event: .metadataUpdated, now: start.addingTimeInterval(Double(index + 1)))
+        }
+        log.record(flowHash: 0xabc, event: .closed, closeReason: .tcpRst, now: start.addingTimeInterval(60))
+
+        let breadcrumbs = log.breadcrumbs(forFlowHash: 0xabc)
+        XCTAssertEqual(breadcrumbs.count, FlowBreadcrumbLog.Policy.maxEventsPerFlow)
+        XCTAssertNil(breadcrumbs.first(where: { $0.event == .opened }))
+        XCTAssertEqual(breadcrumbs.last?.event, .closed)
+        XCTAssertEqual(breadcrumbs.last?.closeReason, .tcpRst)
+    }
+
+    /// Verifies breadcrumbs survive a close event so failed connections stay inspectable.
+    func testBreadcrumbsRemainReadableAfterClose() {
+        var log = FlowBreadcrumbLog()
+        let start = Date(timeIntervalSince1970: 0)
+
+        log.record(flowHash: 7, event: .opened, now: start)
+        log.record(flowHash: 7, event: .closed, closeReason: .idleEviction, now: start.addingTimeInterval(121))
+
+        let breadcrumbs = log.breadcrumbs(forFlowHash: 7)
+        XCTAssertEqual(breadcrumbs.map(\.event), [.opened, .closed])
+        XCTAssertEqual(breadcrumbs.last?.closeReason, .idleEviction)
+        XCTAssertEqual(log.breadcrumbs(forFlowHash: 8), [])
+    }
+
+    /// Verifies the oldest flow's history is evicted when the tracked-flow cap overflows.
+    func testOverflowEvictsOldestFlowHistory() {
+        var log = FlowBreadcrumbLog()
+        let start = Date(timeIntervalSince1970: 0)
+
+        for flowHash in 0..<UInt64(FlowBreadcrumbLog.Policy.maxTrackedFlows + 1) {
+            log.record(flowHash: flowHash, event: .opened, now: start)
+        }
+
+        XCTAssertEqual(log.trackedFlowCount, FlowBreadcrumbLog.Policy.maxTrackedFlows)
+        XCTAssertEqual(log.breadcrumbs(forFlowHash: 0), [])
+        XCTAssertEqual(log.breadcrumbs(forFlowHash: 1).map(\.event), [.opened])
+    }
+}
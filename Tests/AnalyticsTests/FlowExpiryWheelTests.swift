@@ -0,0 +1,54 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// Timing-wheel slot and drain behaviour tests for flow expiry scheduling.
+final class FlowExpiryWheelTests: XCTestCase {
+    /// Verifies drained candidates come only from slots whose deadline has lapsed.
+    func testDrainReturnsOnlyLapsedSlots() {
+        var wheel = FlowExpiryWheel(slotSeconds: 15)
+        let start = Date(timeIntervalSinceReferenceDate: 0)
+        let earlyFlow = FlowKey(src: "10.0.0.2:1", dst: "1.1.1.1:443", proto: "udp")
+        let lateFlow = FlowKey(src: "10.0.0.2:2", dst: "1.1.1.1:443", proto: "udp")
+
+        wheel.schedule(earlyFlow, deadline: start.addingTimeInterval(30))
+        wheel.schedule(lateFlow, deadline: start.addingTimeInterval(120))
+        XCTAssertEqual(wheel.pendingCount, 2)
+
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(10)), [])
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(45)), [earlyFlow])
+        XCTAssertEqual(wheel.pendingCount, 1)
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(130)), [lateFlow])
+        XCTAssertEqual(wheel.pendingCount, 0)
+    }
+
+    /// Verifies a rescheduled flow is reported again once its pushed-out deadline lapses.
+    func testRescheduledFlowExpiresAtNewDeadline() {
+        var wheel = FlowExpiryWheel(slotSeconds: 15)
+        let start = Date(timeIntervalSinceReferenceDate: 0)
+        let flow = FlowKey(src: "10.0.0.2:1", dst: "1.1.1.1:443", proto: "tcp")
+
+        wheel.schedule(flow, deadline: start.addingTimeInterval(30))
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(45)), [flow])
+
+        wheel.schedule(flow, deadline: start.addingTimeInterval(90))
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(60)), [])
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(120)), [flow])
+    }
+
+    /// Verifies scheduling the same flow into one slot twice yields a single candidate.
+    func testDuplicateScheduleInOneSlotCollapses() {
+        var wheel = FlowExpiryWheel(slotSeconds: 15)
+        let start = Date(timeIntervalSinceReferenceDate: 0)
+        let flow = FlowKey(src: "10.0.0.2:1", dst: "1.1.1.1:443", proto: "udp")
+
+        wheel.schedule(flow, deadline: start.addingTimeInterval(20))
+        wheel.schedule(flow, deadline: start.addingTimeInterval(21))
+        XCTAssertEqual(wheel.pendingCount, 1)
+        XCTAssertEqual(wheel.drainExpiredCandidates(now: start.addingTimeInterval(60)), [flow])
+    }
+}
@@ -0,0 +1,236 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Flow-interceptor hook tests covering open, payload, and close callbacks.
+final class FlowInterceptorTests: XCTestCase {
+    /// Pipeline-owned recorder; hooks run inline on the actor so no synchronization is needed.
+    private final class RecordingInterceptor: FlowInterceptor {
+        let identifier = "test.recording"
+        var events: [String] = []
+
+        func flowOpened(flow: FlowKey) {
+            events.append("open")
+        }
+
+        func clientPayload(flow: FlowKey, payloadLength: Int) {
+            events.append("client:\(payloadLength)")
+        }
+
+        func remotePayload(flow: FlowKey, payloadLength: Int) {
+            events.append("remote:\(payloadLength)")
+        }
+
+        func flowClosed(flow: FlowKey, reason: FlowCloseReason) {
+            events.append("close:\(reason.rawValue)")
+        }
+    }
+
+    /// Partial conformer relying on the protocol's default no-op hooks.
+    private final class CloseOnlyInterceptor: FlowInterceptor {
+        let identifier = "test.close-only"
+        var closedCount = 0
+
+        func flowClosed(flow: FlowKey, reason: FlowCloseReason) {
+            closedCount += 1
+        }
+    }
+
+    /// Verifies interceptors observe flow open, directional payloads, and close in order.
+    func testInterceptorObservesFlowLifecycle() async throws {
+        let interceptor = RecordingInterceptor()
+        let pipeline = makePipeline(interceptors: [interceptor])
+        let policy = makeEmissionPolicy()
+
+        let outboundPayload = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        let inboundPayload = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [1, 1, 1, 1],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 443,
+                destinationPort: 50_000,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x02, 0xff]
+            )
+        )
+        let reset = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x04,
+                payload: []
+            )
+        )
+
+        _ = await pipeline.ingest(packets: [outboundPayload], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [inboundPayload], families: [], direction: .inbound, policy: policy)
+        _ = await pipeline.ingest(packets: [reset], families: [], direction: .outbound, policy: policy)
+
+        XCTAssertEqual(interceptor.events.first, "open")
+        XCTAssertTrue(interceptor.events.contains("client:5"))
+        XCTAssertTrue(interceptor.events.contains("remote:6"))
+        XCTAssertEqual(interceptor.events.last, "close:tcpRst")
+    }
+
+    /// Verifies partial conformances compile against the default hooks and still see closes.
+    func testDefaultHooksAllowPartialConformance() async throws {
+        let interceptor = CloseOnlyInterceptor()
+        let pipeline = makePipeline(interceptors: [interceptor])
+        let policy = makeEmissionPolicy()
+
+        let reset = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_001,
+                destinationPort: 443,
+                tcpFlags: 0x04,
+                payload: []
+            )
+        )
+        _ = await pipeline.ingest(packets: [reset], families: [], direction: .outbound, policy: policy)
+
+        XCTAssertEqual(interceptor.closedCount, 1)
+    }
+
+    /// Blocks each callback on a semaphore so tests can hold the executor queue busy.
+    private final class GatedInterceptor: FlowInterceptor {
+        let identifier = "test.gated"
+        let gate = DispatchSemaphore(value: 0)
+        var openedCount = 0
+
+        func flowOpened(flow: FlowKey) {
+            gate.wait()
+            openedCount += 1
+        }
+    }
+
+    /// Verifies the isolated wrapper replays events to the wrapped interceptor in arrival order.
+    func testIsolatedInterceptorPreservesEventOrder() throws {
+        let recording = RecordingInterceptor()
+        let isolated = IsolatedFlowInterceptor(wrapping: recording)
+        let flow = FlowKey(src: "10.0.0.2:50000", dst: "1.1.1.1:443", proto: "tcp")
+
+        isolated.flowOpened(flow: flow)
+        isolated.clientPayload(flow: flow, payloadLength: 5)
+        isolated.remotePayload(flow: flow, payloadLength: 6)
+        isolated.flowClosed(flow: flow, reason: .tcpRst)
+        isolated.drainAndWait()
+
+        XCTAssertEqual(recording.events, ["open", "client:5", "remote:6", "close:tcpRst"])
+        XCTAssertEqual(isolated.stats().executedEventCount, 4)
+        XCTAssertEqual(isolated.stats().droppedEventCount, 0)
+    }
+
+    /// Verifies a full hand-off queue drops new events instead of blocking the caller.
+    func testIsolatedInterceptorDropsWhenHandOffIsFull() throws {
+        let gated = GatedInterceptor()
+        let isolated = IsolatedFlowInterceptor(wrapping: gated, maxPendingEvents: 1)
+        let flow = FlowKey(src: "10.0.0.2:50001", dst: "1.1.1.1:443", proto: "tcp")
+
+        isolated.flowOpened(flow: flow)
+        isolated.flowOpened(flow: flow)
+        isolated.flowOpened(flow: flow)
+        gated.gate.signal()
+        isolated.drainAndWait()
+
+        let stats = isolated.stats()
+        XCTAssertEqual(stats.executedEventCount, 1)
+        XCTAssertEqual(stats.droppedEventCount, 2)
+        XCTAssertEqual(stats.pendingEventCount, 0)
+        XCTAssertEqual(stats.peakPendingEventCount, 1)
+        XCTAssertEqual(gated.openedCount, 1)
+    }
+
+    /// Verifies callback wall time is accumulated separately from the caller's path.
+    func testIsolatedInterceptorMeasuresCallbackLatency() throws {
+        final class SlowInterceptor: FlowInterceptor {
+            let identifier = "test.slow"
+            func flowOpened(flow: FlowKey) {
+                Thread.sleep(forTimeInterval: 0.02)
+            }
+        }
+
+        let isolated = IsolatedFlowInterceptor(wrapping: SlowInterceptor())
+        let flow = FlowKey(src: "10.0.0.2:50002", dst: "8.8.8.8:53", proto: "udp")
+
+        isolated.flowOpened(flow: flow)
+        isolated.drainAndWait()
+
+        let stats = isolated.stats()
+        XCTAssertEqual(stats.executedEventCount, 1)
+        XCTAssertGreaterThanOrEqual(stats.maxCallbackMilliseconds, 15)
+        XCTAssertGreaterThanOrEqual(stats.totalCallbackMilliseconds, stats.maxCallbackMilliseconds)
+    }
+
+    private func makePipeline(interceptors: [any FlowInterceptor]) -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink())),
+            flowInterceptors: interceptors
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,207 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Flow-pinning rule matching and pruning-exemption tests.
+final class FlowPinningTests: XCTestCase {
+    /// Verifies pin rules match on destination port and case-insensitive host suffix.
+    func testPinRuleMatchesPortAndHostSuffix() {
+        let portRule = FlowPinRule(destinationPort: 5_223)
+        XCTAssertTrue(portRule.matches(destinationPort: 5_223, hostCandidates: []))
+        XCTAssertFalse(portRule.matches(destinationPort: 443, hostCandidates: []))
+
+        let hostRule = FlowPinRule(hostSuffix: "Push.Example.com")
+        XCTAssertTrue(hostRule.matches(destinationPort: nil, hostCandidates: ["courier.PUSH.example.com"]))
+        XCTAssertTrue(hostRule.matches(destinationPort: nil, hostCandidates: ["push.example.com"]))
+        XCTAssertFalse(hostRule.matches(destinationPort: nil, hostCandidates: ["push.example.com.evil.net"]))
+
+        XCTAssertFalse(FlowPinRule().matches(destinationPort: 443, hostCandidates: ["push.example.com"]))
+    }
+
+    /// Verifies unicode and punycode host forms fold together in pin-rule suffix matching.
+    func testPinRuleNormalizesIDNHostForms() {
+        let unicodeRule = FlowPinRule(hostSuffix: "bücher.example")
+        XCTAssertEqual(unicodeRule.hostSuffix, "xn--bcher-kva.example")
+        XCTAssertTrue(unicodeRule.matches(destinationPort: nil, hostCandidates: ["shop.xn--bcher-kva.example"]))
+
+        let punycodeRule = FlowPinRule(hostSuffix: "xn--bcher-kva.example")
+        XCTAssertTrue(punycodeRule.matches(destinationPort: nil, hostCandidates: ["shop.Bücher.Example."]))
+    }
+
+    /// Verifies a transaction's adds and removes resolve against the live policy in one swap.
+    func testTransactionAppliesBatchedEditsAtomically() {
+        let voipRule = FlowPinRule(destinationPort: 5_223)
+        let pushRule = FlowPinRule(hostSuffix: "push.example.com")
+        let legacyRule = FlowPinRule(hostSuffix: "legacy.example.com")
+
+        var transaction = FlowPinningTransaction()
+        transaction.add(pushRule)
+        transaction.add(pushRule)
+        transaction.remove(legacyRule)
+
+        let next = transaction.applying(to: FlowPinningPolicy(rules: [voipRule, legacyRule], maxPinnedFlows: 32))
+        XCTAssertEqual(next.rules, [voipRule, pushRule])
+        XCTAssertEqual(next.maxPinnedFlows, 32)
+
+        var capOnly = FlowPinningTransaction()
+        XCTAssertTrue(capOnly.isEmpty)
+        capOnly.maxPinnedFlows = 8
+        XCTAssertFalse(capOnly.isEmpty)
+        XCTAssertEqual(capOnly.applying(to: next).maxPinnedFlows, 8)
+        XCTAssertEqual(capOnly.applying(to: next).rules, next.rules)
+    }
+
+    /// Verifies pinned flows survive the idle TTL sweep while unpinned flows are closed.
+    func testPinnedFlowSurvivesIdleEviction() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        await pipeline.updateFlowPinning(FlowPinningPolicy(rules: [FlowPinRule(destinationPort: 5_223)]))
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let pinnedPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [17, 0, 0, 1],
+                sourcePort: 50_000,
+                destinationPort: 5_223,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        let idlePacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_001,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        _ = await pipeline.ingest(packets: [pinnedPacket, idlePacket], families: [], direction: .outbound, policy: policy)
+
+        // Idle both flows past the TTL, then ingest fresh traffic to trigger the sweep.
+        await clock.advance(by: 180)
+        let freshPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [9, 9, 9, 9],
+                sourcePort: 50_002,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        let records = await pipeline.ingest(packets: [freshPacket], families: [], direction: .outbound, policy: policy)
+
+        let closedPorts = records.filter { $0.kind == .flowClose }.compactMap(\.destinationPort)
+        XCTAssertEqual(closedPorts, [443])
+        XCTAssertFalse(closedPorts.contains(5_223))
+    }
+
+    /// Verifies updating the pinning policy unpins flows that no longer match any rule.
+    func testPolicyUpdateUnpinsFlows() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        await pipeline.updateFlowPinning(FlowPinningPolicy(rules: [FlowPinRule(destinationPort: 5_223)]))
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let pinnedPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [17, 0, 0, 1],
+                sourcePort: 50_000,
+                destinationPort: 5_223,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        _ = await pipeline.ingest(packets: [pinnedPacket], families: [], direction: .outbound, policy: policy)
+
+        await pipeline.updateFlowPinning(.disabled)
+        await clock.advance(by: 180)
+        let freshPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [9, 9, 9, 9],
+                sourcePort: 50_002,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        let records = await pipeline.ingest(packets: [freshPacket], families: [], direction: .outbound, policy: policy)
+
+        let closedPorts = records.filter { $0.kind == .flowClose }.compactMap(\.destinationPort)
+        XCTAssertEqual(closedPorts, [5_223])
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
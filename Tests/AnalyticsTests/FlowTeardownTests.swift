@@ -0,0 +1,216 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Flow teardown frame synthesis tests.
+final class FlowTeardownTests: XCTestCase {
+    /// Verifies sequence bookkeeping comes from acknowledged segments only and advances
+    /// past payload and FIN bytes.
+    func testParseTCPStateRequiresAcknowledgmentAndTracksNextSequence() throws {
+        let pureSYN = makeIPv4TCPPacket(tcpFlags: 0x02, sequence: 100, acknowledgment: 0, payload: [])
+        XCTAssertNil(FlowTeardownFrames.parseTCPState(packet: Data(pureSYN)))
+
+        let data = makeIPv4TCPPacket(tcpFlags: 0x18, sequence: 1_000, acknowledgment: 9_000, payload: [1, 2, 3, 4])
+        let state = try XCTUnwrap(FlowTeardownFrames.parseTCPState(packet: Data(data)))
+        XCTAssertEqual(state.clientExpectedSequence, 9_000)
+        XCTAssertEqual(state.clientNextSequence, 1_004)
+
+        let fin = makeIPv4TCPPacket(tcpFlags: 0x11, sequence: 1_004, acknowledgment: 9_000, payload: [])
+        let finState = try XCTUnwrap(FlowTeardownFrames.parseTCPState(packet: Data(fin)))
+        XCTAssertEqual(finState.clientNextSequence, 1_005)
+    }
+
+    /// Verifies the forged reset is a remote-to-client RST|ACK that survives strict header
+    /// and checksum validation.
+    func testTCPResetAddressingFlagsAndChecksums() async throws {
+        let state = FlowTeardownFrames.TCPState(clientExpectedSequence: 9_000, clientNextSequence: 1_004)
+        let frame = try XCTUnwrap(
+            FlowTeardownFrames.makeTCPReset(
+                clientAddress: [10, 0, 0, 2],
+                clientPort: 50_000,
+                remoteAddress: [1, 1, 1, 1],
+                remotePort: 443,
+                state: state
+            )
+        )
+
+        let summary = try XCTUnwrap(FastPacketSummary(data: frame, ipVersionHint: nil))
+        XCTAssertTrue(summary.hasTCPRST)
+        XCTAssertTrue(summary.hasTCPACK)
+        XCTAssertEqual(summary.sourcePort, 443)
+        XCTAssertEqual(summary.destinationPort, 50_000)
+        XCTAssertEqual(summary.destinationAddressLow, 0x0a00_0002)
+
+        let strictPipeline = makePipeline(validationMode: .strict)
+        _ = await strictPipeline.ingest(packets: [frame], families: [], direction: .inbound, policy: makePolicy())
+        let counters = await strictPipeline.invalidPacketCountersSnapshot()
+        XCTAssertTrue(counters.isEmpty)
+    }
+
+    /// Verifies the ICMP port-unreachable quotes the original client-to-remote headers so the
+    /// client stack can match the error to its socket.
+    func testUDPUnreachableQuotesOriginalHeaders() throws {
+        let frame = try XCTUnwrap(
+            FlowTeardownFrames.makeUDPUnreachable(
+                clientAddress: [10, 0, 0, 2],
+                clientPort: 50_000,
+                remoteAddress: [8, 8, 8, 8],
+                remotePort: 53
+            )
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(bytes[9], 1)
+        XCTAssertEqual(Array(bytes[12..<16]), [8, 8, 8, 8])
+        XCTAssertEqual(Array(bytes[16..<20]), [10, 0, 0, 2])
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 3)
+
+        let embedded = Array(bytes[28...])
+        XCTAssertEqual(embedded[9], 17)
+        XCTAssertEqual(Array(embedded[12..<16]), [10, 0, 0, 2])
+        XCTAssertEqual(Array(embedded[16..<20]), [8, 8, 8, 8])
+        XCTAssertEqual(Int(embedded[20]) << 8 | Int(embedded[21]), 50_000)
+        XCTAssertEqual(Int(embedded[22]) << 8 | Int(embedded[23]), 53)
+    }
+
+    /// Verifies the pipeline synthesizes one frame per live flow — an RST for the established
+    /// TCP flow, an unreachable for the UDP flow — and drops closed flows from the set.
+    func testPipelineBuildsTeardownFramesForLiveFlowsOnly() async throws {
+        let pipeline = makePipeline(validationMode: .permissive)
+        let policy = makePolicy()
+
+        let tcpData = makeIPv4TCPPacket(tcpFlags: 0x18, sequence: 1_000, acknowledgment: 9_000, payload: [1, 2, 3, 4])
+        let udpQuery = makeIPv4UDPPacket(payload: [0, 1, 2, 3])
+        _ = await pipeline.ingest(packets: [Data(tcpData), Data(udpQuery)], families: [], direction: .outbound, policy: policy)
+
+        let frames = await pipeline.makeTeardownFrames()
+        XCTAssertEqual(frames.packets.count, 2)
+        XCTAssertEqual(frames.families.count, 2)
+        let summaries = frames.packets.compactMap { FastPacketSummary(data: $0, ipVersionHint: nil) }
+        XCTAssertTrue(summaries.contains { $0.hasTCPRST && $0.destinationPort == 50_000 })
+        XCTAssertTrue(summaries.contains { $0.transportProtocolNumber == 1 })
+
+        let finOut = makeIPv4TCPPacket(tcpFlags: 0x11, sequence: 1_004, acknowledgment: 9_000, payload: [])
+        let finIn = makeIPv4TCPPacket(
+            tcpFlags: 0x11,
+            sequence: 9_000,
+            acknowledgment: 1_005,
+            payload: [],
+            reversed: true
+        )
+        _ = await pipeline.ingest(packets: [Data(finOut)], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [Data(finIn)], families: [], direction: .inbound, policy: policy)
+
+        let afterClose = await pipeline.makeTeardownFrames()
+        XCTAssertEqual(afterClose.packets.count, 1)
+        let remaining = try XCTUnwrap(FastPacketSummary(data: afterClose.packets[0], ipVersionHint: nil))
+        XCTAssertEqual(remaining.transportProtocolNumber, 1)
+    }
+
+    /// Verifies flows opened by unacknowledged TCP traffic produce no reset: without an
+    /// acknowledged sequence a blind RST would be discarded anyway.
+    func testUnestablishedTCPFlowsProduceNoFrames() async throws {
+        let pipeline = makePipeline(validationMode: .permissive)
+        let syn = makeIPv4TCPPacket(tcpFlags: 0x02, sequence: 100, acknowledgment: 0, payload: [])
+        _ = await pipeline.ingest(packets: [Data(syn)], families: [], direction: .outbound, policy: makePolicy())
+
+        let frames = await pipeline.makeTeardownFrames()
+        XCTAssertTrue(frames.packets.isEmpty)
+    }
+
+    private func makePipeline(validationMode: PacketValidationMode) -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink())),
+            validationMode: validationMode
+        )
+    }
+
+    private func makePolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        tcpFlags: UInt8,
+        sequence: UInt32,
+        acknowledgment: UInt32,
+        payload: [UInt8],
+        reversed: Bool = false
+    ) -> [UInt8] {
+        let sourceAddress: [UInt8] = reversed ? [1, 1, 1, 1] : [10, 0, 0, 2]
+        let destinationAddress: [UInt8] = reversed ? [10, 0, 0, 2] : [1, 1, 1, 1]
+        let sourcePort: UInt16 = reversed ? 443 : 50_000
+        let destinationPort: UInt16 = reversed ? 50_000 : 443
+
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 4] = UInt8(truncatingIfNeeded: sequence >> 24)
+        packet[tcpOffset + 5] = UInt8(truncatingIfNeeded: sequence >> 16)
+        packet[tcpOffset + 6] = UInt8(truncatingIfNeeded: sequence >> 8)
+        packet[tcpOffset + 7] = UInt8(truncatingIfNeeded: sequence)
+        packet[tcpOffset + 8] = UInt8(truncatingIfNeeded: acknowledgment >> 24)
+        packet[tcpOffset + 9] = UInt8(truncatingIfNeeded: acknowledgment >> 16)
+        packet[tcpOffset + 10] = UInt8(truncatingIfNeeded: acknowledgment >> 8)
+        packet[tcpOffset + 11] = UInt8(truncatingIfNeeded: acknowledgment)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+
+    private func makeIPv4UDPPacket(payload: [UInt8]) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 8 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 17
+        packet[12..<16] = [10, 0, 0, 2][0..<4]
+        packet[16..<20] = [8, 8, 8, 8][0..<4]
+
+        let udpOffset = 20
+        packet[udpOffset] = UInt8(50_000 >> 8)
+        packet[udpOffset + 1] = UInt8(50_000 & 0xff)
+        packet[udpOffset + 2] = 0
+        packet[udpOffset + 3] = 53
+        packet[udpOffset + 4] = UInt8((8 + payload.count) >> 8)
+        packet[udpOffset + 5] = UInt8((8 + payload.count) & 0xff)
+        if !payload.isEmpty {
+            packet[(udpOffset + 8)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,161 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// HTTP metadata extraction tests: message parsing and exchange timing through the pipeline.
+final class HTTPMetadataTests: XCTestCase {
+    /// Verifies a request line parses with method, target, version, and Host header.
+    func testParserExtractsRequestLineAndHost() {
+        let payload = Data("GET /v1/status HTTP/1.1\r\nHost: api.example.com\r\nAccept: */*\r\n\r\n".utf8)
+
+        XCTAssertEqual(
+            HTTPMetadataExtractor.parse(payload: payload),
+            .request(method: "GET", target: "/v1/status", version: "1.1", host: "api.example.com")
+        )
+    }
+
+    /// Verifies a status line parses with its version and status code.
+    func testParserExtractsStatusLine() {
+        let payload = Data("HTTP/1.1 204 No Content\r\nServer: test\r\n\r\n".utf8)
+
+        XCTAssertEqual(HTTPMetadataExtractor.parse(payload: payload), .response(version: "1.1", statusCode: 204, upgrade: nil))
+    }
+
+    /// Verifies the HTTP/2 connection preface is recognized.
+    func testParserRecognizesHTTP2Preface() {
+        let payload = Data("PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n".utf8)
+
+        XCTAssertEqual(HTTPMetadataExtractor.parse(payload: payload), .http2Preface)
+    }
+
+    /// Verifies non-HTTP payloads and mid-stream continuation segments parse to nothing.
+    func testParserRejectsNonHTTPPayloads() {
+        XCTAssertNil(HTTPMetadataExtractor.parse(payload: Data([0x16, 0x03, 0x01, 0x00, 0x05])))
+        XCTAssertNil(HTTPMetadataExtractor.parse(payload: Data("<html>continuation bytes".utf8)))
+        XCTAssertNil(HTTPMetadataExtractor.parse(payload: Data()))
+    }
+
+    /// Verifies a port-80 request and its reverse-direction response complete one
+    /// exchange with request-to-response timing from the pipeline clock.
+    func testPipelineRecordsExchangeWithTiming() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy()
+
+        let request = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 80,
+                tcpFlags: 0x18,
+                payload: Array("GET /index.html HTTP/1.1\r\nHost: example.com\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [request], families: [], direction: .outbound, policy: policy)
+
+        await clock.advance(by: 0.05)
+        let response = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [203, 0, 113, 9],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 80,
+                destinationPort: 50_000,
+                tcpFlags: 0x18,
+                payload: Array("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [response], families: [], direction: .inbound, policy: policy)
+
+        let exchanges = await pipeline.httpExchanges()
+        XCTAssertEqual(exchanges.count, 1)
+        let exchange = try XCTUnwrap(exchanges.first)
+        XCTAssertEqual(exchange.method, "GET")
+        XCTAssertEqual(exchange.target, "/index.html")
+        XCTAssertEqual(exchange.host, "example.com")
+        XCTAssertEqual(exchange.statusCode, 200)
+        XCTAssertEqual(exchange.responseLatencyMs, 50)
+    }
+
+    /// Verifies HTTP parsing stays off without the emission-policy opt-in.
+    func testPipelineSkipsParsingWithoutPolicyOptIn() async {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy(emitHTTPMetadata: false)
+
+        let request = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 80,
+                tcpFlags: 0x18,
+                payload: Array("GET / HTTP/1.1\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [request], families: [], direction: .outbound, policy: policy)
+
+        let exchanges = await pipeline.httpExchanges()
+        XCTAssertTrue(exchanges.isEmpty)
+    }
+
+    private func makePipeline(clock: DeterministicClock) -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy(emitHTTPMetadata: Bool = true) -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false,
+            emitHTTPMetadata: emitHTTPMetadata
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
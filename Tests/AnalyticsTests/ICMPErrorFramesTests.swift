@@ -0,0 +1,250 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// ICMP error frame synthesis tests.
+final class ICMPErrorFramesTests: XCTestCase {
+    /// Verifies Time Exceeded is sourced from the gateway, addressed to the offender's
+    /// source, and quotes the offending header plus eight payload bytes.
+    func testTimeExceededQuotesOffendingHeaders() throws {
+        let offender = makeIPv4UDPPacket(
+            ttl: 1,
+            source: [10, 0, 0, 2],
+            destination: [8, 8, 8, 8],
+            sourcePort: 50_000,
+            destinationPort: 33_434,
+            payload: [0xde, 0xad, 0xbe, 0xef, 1, 2, 3, 4, 5, 6]
+        )
+        let frame = try XCTUnwrap(
+            ICMPErrorFrames.makeTimeExceeded(expiredPacket: Data(offender), gatewayAddress: [10, 0, 0, 1])
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(bytes[9], 1)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 1])
+        XCTAssertEqual(Array(bytes[16..<20]), [10, 0, 0, 2])
+        XCTAssertEqual(bytes[20], 11)
+        XCTAssertEqual(bytes[21], 0)
+        XCTAssertEqual(Array(bytes[28...]), Array(offender[0..<28]))
+    }
+
+    /// Verifies the administratively-prohibited variant carries type 3 code 13 and is
+    /// otherwise shaped like the other gateway-sourced errors.
+    func testAdminProhibitedUsesCode13() throws {
+        let offender = makeIPv4UDPPacket(
+            ttl: 64,
+            source: [192, 168, 1, 5],
+            destination: [8, 8, 8, 8],
+            sourcePort: 40_000,
+            destinationPort: 443,
+            payload: [1, 2, 3]
+        )
+        let frame = try XCTUnwrap(
+            ICMPErrorFrames.makeAdminProhibited(rejectedPacket: Data(offender), gatewayAddress: [10, 0, 0, 1])
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 13)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 1])
+        XCTAssertEqual(Array(bytes[16..<20]), [192, 168, 1, 5])
+    }
+
+    /// Verifies port unreachable replies from the offender's own destination address,
+    /// matching what a real host with a closed port would send.
+    func testPortUnreachableRepliesFromDestination() throws {
+        let offender = makeIPv4UDPPacket(
+            ttl: 64,
+            source: [10, 0, 0, 2],
+            destination: [10, 0, 0, 1],
+            sourcePort: 50_000,
+            destinationPort: 4_500,
+            payload: [9, 9]
+        )
+        let frame = try XCTUnwrap(ICMPErrorFrames.makePortUnreachable(undeliverablePacket: Data(offender)))
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 1])
+        XCTAssertEqual(Array(bytes[16..<20]), [10, 0, 0, 2])
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 3)
+        let embedded = Array(bytes[28...])
+        XCTAssertEqual(Int(embedded[22]) << 8 | Int(embedded[23]), 4_500)
+    }
+
+    /// Verifies the IPv6 variant carries a next-header of 58, ICMPv6 type 3 code 0, a
+    /// verifiable pseudo-header checksum, and a 48-byte quote of the offender.
+    func testIPv6TimeExceededAddressingAndChecksum() throws {
+        let gateway: [UInt8] = [0xfd, 0x00, 0, 1] + [UInt8](repeating: 0, count: 11) + [1]
+        let source: [UInt8] = [0xfd, 0x00, 0, 1] + [UInt8](repeating: 0, count: 11) + [2]
+        let destination: [UInt8] = [0x20, 0x01, 0x0d, 0xb8] + [UInt8](repeating: 0, count: 11) + [9]
+        let offender = makeIPv6UDPPacket(
+            hopLimit: 1,
+            source: source,
+            destination: destination,
+            payload: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]
+        )
+        let frame = try XCTUnwrap(
+            ICMPErrorFrames.makeTimeExceeded(expiredPacket: Data(offender), gatewayAddress: gateway)
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual((bytes[0] >> 4) & 0x0f, 6)
+        XCTAssertEqual(bytes[6], 58)
+        XCTAssertEqual(Array(bytes[8..<24]), gateway)
+        XCTAssertEqual(Array(bytes[24..<40]), source)
+        XCTAssertEqual(bytes[40], 3)
+        XCTAssertEqual(bytes[41], 0)
+        XCTAssertEqual(Array(bytes[48...]), Array(offender[0..<48]))
+
+        var message = Array(bytes[40...])
+        message[2] = 0
+        message[3] = 0
+        let expected = FlowTeardownFrames.transportChecksum(
+            source: gateway,
+            destination: source,
+            protocolNumber: 58,
+            payload: message
+        )
+        XCTAssertEqual(UInt16(bytes[42]) << 8 | UInt16(bytes[43]), expected)
+    }
+
+    /// Verifies Fragmentation Needed carries type 3 code 4, the next-hop MTU field,
+    /// and replies from the oversized packet's own destination toward its source.
+    func testPacketTooBigCarriesNextHopMTU() throws {
+        let offender = makeIPv4UDPPacket(
+            ttl: 64,
+            source: [8, 8, 8, 8],
+            destination: [10, 0, 0, 2],
+            sourcePort: 443,
+            destinationPort: 50_000,
+            payload: [UInt8](repeating: 0xAA, count: 64)
+        )
+        let frame = try XCTUnwrap(
+            ICMPErrorFrames.makePacketTooBig(oversizedPacket: Data(offender), mtu: 1_400)
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 2])
+        XCTAssertEqual(Array(bytes[16..<20]), [8, 8, 8, 8])
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 4)
+        XCTAssertEqual(Int(bytes[26]) << 8 | Int(bytes[27]), 1_400)
+        XCTAssertEqual(Array(bytes[28...]), Array(offender[0..<28]))
+    }
+
+    /// Verifies the IPv6 Packet Too Big variant encodes the MTU as a 32-bit field
+    /// with a verifiable pseudo-header checksum.
+    func testIPv6PacketTooBigEncodesMTU() throws {
+        let source: [UInt8] = [0x20, 0x01, 0x0d, 0xb8] + [UInt8](repeating: 0, count: 11) + [9]
+        let destination: [UInt8] = [0xfd, 0x00, 0, 1] + [UInt8](repeating: 0, count: 11) + [2]
+        let offender = makeIPv6UDPPacket(
+            hopLimit: 64,
+            source: source,
+            destination: destination,
+            payload: [UInt8](repeating: 0xBB, count: 64)
+        )
+        let frame = try XCTUnwrap(
+            ICMPErrorFrames.makePacketTooBig(oversizedPacket: Data(offender), mtu: 1_280)
+        )
+
+        let bytes = [UInt8](frame)
+        XCTAssertEqual(bytes[6], 58)
+        XCTAssertEqual(Array(bytes[8..<24]), destination)
+        XCTAssertEqual(Array(bytes[24..<40]), source)
+        XCTAssertEqual(bytes[40], 2)
+        XCTAssertEqual(bytes[41], 0)
+        let mtuField = UInt32(bytes[44]) << 24 | UInt32(bytes[45]) << 16 | UInt32(bytes[46]) << 8 | UInt32(bytes[47])
+        XCTAssertEqual(mtuField, 1_280)
+        XCTAssertEqual(Array(bytes[48...]), Array(offender[0..<48]))
+
+        var message = Array(bytes[40...])
+        message[2] = 0
+        message[3] = 0
+        let expected = FlowTeardownFrames.transportChecksum(
+            source: destination,
+            destination: source,
+            protocolNumber: 58,
+            payload: message
+        )
+        XCTAssertEqual(UInt16(bytes[42]) << 8 | UInt16(bytes[43]), expected)
+    }
+
+    /// Verifies malformed offenders and gateway addresses of the wrong IP version
+    /// yield no frame instead of a corrupt one.
+    func testMalformedInputsProduceNoFrame() {
+        XCTAssertNil(ICMPErrorFrames.makeTimeExceeded(expiredPacket: Data(), gatewayAddress: [10, 0, 0, 1]))
+        XCTAssertNil(
+            ICMPErrorFrames.makeTimeExceeded(expiredPacket: Data([0x45, 0x00]), gatewayAddress: [10, 0, 0, 1])
+        )
+        let offender = makeIPv4UDPPacket(
+            ttl: 1,
+            source: [10, 0, 0, 2],
+            destination: [8, 8, 8, 8],
+            sourcePort: 50_000,
+            destinationPort: 33_434,
+            payload: []
+        )
+        XCTAssertNil(
+            ICMPErrorFrames.makeTimeExceeded(
+                expiredPacket: Data(offender),
+                gatewayAddress: [UInt8](repeating: 0, count: 16)
+            )
+        )
+        XCTAssertNil(ICMPErrorFrames.makePortUnreachable(undeliverablePacket: Data([0x05, 0x00])))
+    }
+
+    private func makeIPv4UDPPacket(
+        ttl: UInt8,
+        source: [UInt8],
+        destination: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var bytes = [UInt8](repeating: 0, count: 28)
+        bytes[0] = 0x45
+        let totalLength = 28 + payload.count
+        bytes[2] = UInt8(truncatingIfNeeded: totalLength >> 8)
+        bytes[3] = UInt8(truncatingIfNeeded: totalLength)
+        bytes[8] = ttl
+        bytes[9] = 17
+        bytes.replaceSubrange(12..<16, with: source)
+        bytes.replaceSubrange(16..<20, with: destination)
+        bytes[20] = UInt8(truncatingIfNeeded: sourcePort >> 8)
+        bytes[21] = UInt8(truncatingIfNeeded: sourcePort)
+        bytes[22] = UInt8(truncatingIfNeeded: destinationPort >> 8)
+        bytes[23] = UInt8(truncatingIfNeeded: destinationPort)
+        let udpLength = 8 + payload.count
+        bytes[24] = UInt8(truncatingIfNeeded: udpLength >> 8)
+        bytes[25] = UInt8(truncatingIfNeeded: udpLength)
+        return bytes + payload
+    }
+
+    private func makeIPv6UDPPacket(
+        hopLimit: UInt8,
+        source: [UInt8],
+        destination: [UInt8],
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var bytes = [UInt8](repeating: 0, count: 48)
+        bytes[0] = 0x60
+        let payloadLength = 8 + payload.count
+        bytes[4] = UInt8(truncatingIfNeeded: payloadLength >> 8)
+        bytes[5] = UInt8(truncatingIfNeeded: payloadLength)
+        bytes[6] = 17
+        bytes[7] = hopLimit
+        bytes.replaceSubrange(8..<24, with: source)
+        bytes.replaceSubrange(24..<40, with: destination)
+        bytes[40] = 0xc3
+        bytes[41] = 0x50
+        bytes[42] = 0x82
+        bytes[43] = 0x9a
+        bytes[44] = UInt8(truncatingIfNeeded: payloadLength >> 8)
+        bytes[45] = UInt8(truncatingIfNeeded: payloadLength)
+        return bytes + payload
+    }
+}
@@ -0,0 +1,276 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Direction-aware invalid-packet counter tests.
+final class InvalidPacketCounterTests: XCTestCase {
+    /// Verifies unparseable packets increment per-direction and per-reason counters.
+    func testIngestCountsUnparseablePacketsByDirectionAndReason() async throws {
+        let pipeline = makePipeline()
+        let policy = makeEmissionPolicy()
+
+        let truncatedIPv4 = Data([0x45, 0x00, 0x00, 0x14])
+        let truncatedIPv6 = Data([0x60] + [UInt8](repeating: 0, count: 10))
+        let unknownVersion = Data([0x50] + [UInt8](repeating: 0, count: 30))
+
+        _ = await pipeline.ingest(
+            packets: [truncatedIPv4, unknownVersion],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+        _ = await pipeline.ingest(
+            packets: [truncatedIPv6],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+
+        let counters = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertEqual(counters.outboundCount, 2)
+        XCTAssertEqual(counters.inboundCount, 1)
+        XCTAssertEqual(counters.truncatedCount, 2)
+        XCTAssertEqual(counters.unsupportedVersionCount, 1)
+        XCTAssertEqual(counters.malformedHeaderCount, 0)
+        XCTAssertEqual(counters.totalCount, 3)
+    }
+
+    /// Verifies valid packets leave the counters untouched.
+    func testValidPacketsDoNotIncrementCounters() async throws {
+        let pipeline = makePipeline()
+        let validPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+
+        _ = await pipeline.ingest(packets: [validPacket], families: [], direction: .outbound, policy: makeEmissionPolicy())
+
+        let counters = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertTrue(counters.isEmpty)
+    }
+
+    /// Verifies the reset API clears counters so callers can measure per-interval rates.
+    func testResetClearsCounters() async throws {
+        let pipeline = makePipeline()
+        _ = await pipeline.ingest(packets: [Data()], families: [], direction: .outbound, policy: makeEmissionPolicy())
+
+        let before = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertEqual(before.truncatedCount, 1)
+
+        await pipeline.resetInvalidPacketCounters()
+
+        let after = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertTrue(after.isEmpty)
+    }
+
+    /// Verifies permissive mode stays the default: the test helper emits a zero header
+    /// checksum and the packet still flows through without touching the mismatch counter.
+    func testPermissiveModeIsDefaultAndForwardsBadChecksums() async throws {
+        let pipeline = makePipeline()
+        let zeroChecksumPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+
+        _ = await pipeline.ingest(packets: [zeroChecksumPacket], families: [], direction: .outbound, policy: makeEmissionPolicy())
+
+        let counters = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertEqual(counters.checksumMismatchCount, 0)
+        XCTAssertTrue(counters.isEmpty)
+    }
+
+    /// Verifies strict mode drops packets whose IPv4 header or TCP checksum does not verify and
+    /// counts them, while packets with correct checksums pass through untouched.
+    func testStrictModeCountsAndDropsChecksumMismatches() async throws {
+        let pipeline = makePipeline(validationMode: .strict)
+        let policy = makeEmissionPolicy()
+        var packet = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x18,
+            payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+        )
+        writeValidChecksums(into: &packet)
+        let validPacket = Data(packet)
+        packet[10] ^= 0xff
+        let badHeaderChecksum = Data(packet)
+        packet[10] ^= 0xff
+        packet[packet.count - 1] ^= 0xff
+        let badTCPChecksum = Data(packet)
+
+        _ = await pipeline.ingest(
+            packets: [validPacket, badHeaderChecksum, badTCPChecksum],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+
+        let counters = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertEqual(counters.checksumMismatchCount, 2)
+        XCTAssertEqual(counters.outboundCount, 2)
+        XCTAssertEqual(counters.truncatedCount, 0)
+    }
+
+    /// Verifies strict mode rejects illegal header combinations: TCP SYN+FIN and a zero UDP
+    /// checksum over IPv6, both counted as malformed headers.
+    func testStrictModeRejectsIllegalHeaderCombos() async throws {
+        let pipeline = makePipeline(validationMode: .strict)
+        var synFin = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x03,
+            payload: []
+        )
+        writeValidChecksums(into: &synFin)
+        let zeroChecksumUDPv6 = makeIPv6UDPPacket(payload: [0xde, 0xad])
+
+        _ = await pipeline.ingest(
+            packets: [Data(synFin), Data(zeroChecksumUDPv6)],
+            families: [],
+            direction: .inbound,
+            policy: makeEmissionPolicy()
+        )
+
+        let counters = await pipeline.invalidPacketCountersSnapshot()
+        XCTAssertEqual(counters.malformedHeaderCount, 2)
+        XCTAssertEqual(counters.inboundCount, 2)
+        XCTAssertEqual(counters.checksumMismatchCount, 0)
+    }
+
+    private func makePipeline(validationMode: PacketValidationMode = .permissive) -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink())),
+            validationMode: validationMode
+        )
+    }
+
+    /// Fills in the TCP checksum (over the IPv4 pseudo-header) and then the IPv4 header checksum.
+    private func writeValidChecksums(into packet: inout [UInt8]) {
+        packet[30] = 0
+        packet[31] = 0
+        var tcpSum = onesComplementSum(packet[12..<20])
+        tcpSum &+= 6
+        tcpSum &+= UInt32(packet.count - 20)
+        tcpSum &+= onesComplementSum(packet[20...])
+        let tcpChecksum = ~UInt16(fold(tcpSum))
+        packet[30] = UInt8(tcpChecksum >> 8)
+        packet[31] = UInt8(tcpChecksum & 0xff)
+
+        packet[10] = 0
+        packet[11] = 0
+        let headerChecksum = ~UInt16(fold(onesComplementSum(packet[0..<20])))
+        packet[10] = UInt8(headerChecksum >> 8)
+        packet[11] = UInt8(headerChecksum & 0xff)
+    }
+
+    private func onesComplementSum(_ bytes: ArraySlice<UInt8>) -> UInt32 {
+        var sum: UInt32 = 0
+        var index = bytes.startIndex
+        while index < bytes.endIndex {
+            let low = index + 1 < bytes.endIndex ? UInt32(bytes[index + 1]) : 0
+            sum &+= UInt32(bytes[index]) << 8 | low
+            index += 2
+        }
+        return sum
+    }
+
+    private func fold(_ sum: UInt32) -> UInt32 {
+        var folded = sum
+        while folded > 0xffff {
+            folded = (folded & 0xffff) &+ (folded >> 16)
+        }
+        return folded
+    }
+
+    private func makeIPv6UDPPacket(payload: [UInt8]) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 40 + 8 + payload.count)
+        packet[0] = 0x60
+        packet[4] = UInt8((8 + payload.count) >> 8)
+        packet[5] = UInt8((8 + payload.count) & 0xff)
+        packet[6] = 17
+        packet[7] = 64
+        packet[23] = 2
+        packet[39] = 1
+
+        let udpOffset = 40
+        packet[udpOffset] = UInt8(51_000 >> 8)
+        packet[udpOffset + 1] = UInt8(51_000 & 0xff)
+        packet[udpOffset + 2] = UInt8(53 >> 8)
+        packet[udpOffset + 3] = UInt8(53 & 0xff)
+        packet[udpOffset + 4] = UInt8((8 + payload.count) >> 8)
+        packet[udpOffset + 5] = UInt8((8 + payload.count) & 0xff)
+        if !payload.isEmpty {
+            packet[(udpOffset + 8)...] = payload[0...]
+        }
+        return packet
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,47 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// Cadence tests for the pipeline's periodic-upkeep scheduler.
+final class MaintenanceSchedulerTests: XCTestCase {
+    /// Verifies every registered task is due on the first poll so idle components get swept promptly.
+    func testAllRegisteredTasksAreDueOnFirstPoll() {
+        var scheduler = MaintenanceScheduler()
+        scheduler.register(.flowContextSweep, every: 15)
+        scheduler.register(.dnsAssociationSweep, every: 10)
+        scheduler.register(.dnsTransactionSweep, every: 1)
+
+        let due = scheduler.dueTasks(now: Date(timeIntervalSinceReferenceDate: 0))
+        XCTAssertEqual(due, [.flowContextSweep, .dnsAssociationSweep, .dnsTransactionSweep])
+    }
+
+    /// Verifies each task fires on its own cadence independent of how often the scheduler is polled.
+    func testTasksFireAtIndependentCadences() {
+        var scheduler = MaintenanceScheduler()
+        scheduler.register(.flowContextSweep, every: 15)
+        scheduler.register(.dnsTransactionSweep, every: 1)
+        let start = Date(timeIntervalSinceReferenceDate: 0)
+        _ = scheduler.dueTasks(now: start)
+
+        XCTAssertEqual(scheduler.dueTasks(now: start.addingTimeInterval(0.5)), [])
+        XCTAssertEqual(scheduler.dueTasks(now: start.addingTimeInterval(1)), [.dnsTransactionSweep])
+        XCTAssertEqual(scheduler.dueTasks(now: start.addingTimeInterval(2)), [.dnsTransactionSweep])
+        XCTAssertEqual(
+            scheduler.dueTasks(now: start.addingTimeInterval(15)),
+            [.flowContextSweep, .dnsTransactionSweep]
+        )
+    }
+
+    /// Verifies unregistered tasks never fire and a non-positive interval is rejected.
+    func testUnregisteredAndInvalidTasksNeverFire() {
+        var scheduler = MaintenanceScheduler()
+        scheduler.register(.dnsAssociationSweep, every: 0)
+
+        XCTAssertEqual(scheduler.dueTasks(now: Date(timeIntervalSinceReferenceDate: 0)), [])
+        XCTAssertEqual(scheduler.dueTasks(now: Date(timeIntervalSinceReferenceDate: 1_000)), [])
+    }
+}
@@ -0,0 +1,147 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Multi-TUN tagging tests: identical 5-tuples on different interfaces stay distinct flows.
+final class MultiTunInterfaceTests: XCTestCase {
+    /// Verifies the same 5-tuple ingested from two interfaces opens two flows and the
+    /// emitted records carry each interface id.
+    func testSameTupleOnTwoInterfacesTracksAsTwoFlows() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let packet = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [1, 1, 1, 1],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+
+        let firstRecords = await pipeline.ingest(
+            packets: [packet],
+            families: [],
+            direction: .outbound,
+            policy: policy,
+            runtimeContext: .init(pathRegime: nil, interfaceId: 1)
+        )
+        let firstOpen = try XCTUnwrap(firstRecords.first(where: { $0.kind == .flowOpen }))
+        XCTAssertEqual(firstOpen.interfaceId, 1)
+
+        let secondRecords = await pipeline.ingest(
+            packets: [packet],
+            families: [],
+            direction: .outbound,
+            policy: policy,
+            runtimeContext: .init(pathRegime: nil, interfaceId: 2)
+        )
+        let secondOpen = try XCTUnwrap(secondRecords.first(where: { $0.kind == .flowOpen }))
+        XCTAssertEqual(secondOpen.interfaceId, 2)
+
+        // Re-ingesting on the first interface reuses its existing flow: no new flowOpen.
+        let thirdRecords = await pipeline.ingest(
+            packets: [packet],
+            families: [],
+            direction: .outbound,
+            policy: policy,
+            runtimeContext: .init(pathRegime: nil, interfaceId: 1)
+        )
+        XCTAssertNil(thirdRecords.first(where: { $0.kind == .flowOpen }))
+    }
+
+    /// Verifies untagged ingest leaves keys and records interface-free, so single-interface
+    /// hosts see no change.
+    func testUntaggedIngestHasNilInterfaceId() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let packet = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [9, 9, 9, 9],
+                sourcePort: 50_001,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+
+        let records = await pipeline.ingest(packets: [packet], families: [], direction: .outbound, policy: policy)
+        let flowOpen = try XCTUnwrap(records.first(where: { $0.kind == .flowOpen }))
+        XCTAssertNil(flowOpen.interfaceId)
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
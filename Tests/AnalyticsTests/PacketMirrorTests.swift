@@ -0,0 +1,92 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// Packet mirror sampling, eligibility, and rate-cap tests.
+final class PacketMirrorTests: XCTestCase {
+    private final class FrameCollector: @unchecked Sendable {
+        private let lock = NSLock()
+        private var storedFrames: [(Data, PacketDirection)] = []
+
+        func append(_ packet: Data, _ direction: PacketDirection) {
+            lock.lock()
+            storedFrames.append((packet, direction))
+            lock.unlock()
+        }
+
+        var frames: [(Data, PacketDirection)] {
+            lock.lock()
+            defer { lock.unlock() }
+            return storedFrames
+        }
+    }
+
+    /// Verifies the sampling stride mirrors one of every N eligible frames.
+    func testSamplingStrideMirrorsEveryNthFrame() {
+        let collector = FrameCollector()
+        let mirror = PacketMirror(
+            configuration: .init(sampleEvery: 3),
+            handler: { packet, direction in collector.append(packet, direction) }
+        )
+
+        let packets = (0 ..< 9).map { Data([UInt8($0)]) }
+        mirror.offer(packets: packets, direction: .outbound)
+
+        XCTAssertEqual(collector.frames.map { $0.0 }, [Data([2]), Data([5]), Data([8])])
+        XCTAssertEqual(mirror.droppedFrameCount(), 0)
+    }
+
+    /// Verifies the per-second frame cap drops excess frames and counts them, and that a new
+    /// window admits frames again.
+    func testPacketRateCapDropsExcessUntilWindowRolls() {
+        let collector = FrameCollector()
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let mirror = PacketMirror(
+            configuration: .init(maxPacketsPerSecond: 2),
+            handler: { packet, direction in collector.append(packet, direction) },
+            nowProvider: { currentTime }
+        )
+
+        mirror.offer(packets: [Data([1]), Data([2]), Data([3])], direction: .inbound)
+        XCTAssertEqual(collector.frames.count, 2)
+        XCTAssertEqual(mirror.droppedFrameCount(), 1)
+
+        currentTime = currentTime.addingTimeInterval(1.5)
+        mirror.offer(packets: [Data([4])], direction: .inbound)
+        XCTAssertEqual(collector.frames.count, 3)
+    }
+
+    /// Verifies the byte cap limits mirrored volume independently of the frame cap.
+    func testByteRateCapDropsOversizedVolume() {
+        let collector = FrameCollector()
+        let mirror = PacketMirror(
+            configuration: .init(maxPacketsPerSecond: 100, maxBytesPerSecond: 1_000),
+            handler: { packet, direction in collector.append(packet, direction) }
+        )
+
+        let frame = Data(repeating: 0xaa, count: 400)
+        mirror.offer(packets: [frame, frame, frame], direction: .outbound)
+
+        XCTAssertEqual(collector.frames.count, 2)
+        XCTAssertEqual(mirror.droppedFrameCount(), 1)
+    }
+
+    /// Verifies the eligibility predicate scopes mirroring to rule-selected traffic without
+    /// consuming sampling or rate budget for skipped frames.
+    func testEligibilityPredicateFiltersFrames() {
+        let collector = FrameCollector()
+        let mirror = PacketMirror(
+            isEligible: { packet, _ in packet.first == 0x45 },
+            handler: { packet, direction in collector.append(packet, direction) }
+        )
+
+        mirror.offer(packets: [Data([0x60, 0x00]), Data([0x45, 0x00]), Data([0x60, 0x01])], direction: .outbound)
+
+        XCTAssertEqual(collector.frames.count, 1)
+        XCTAssertEqual(collector.frames.first?.0, Data([0x45, 0x00]))
+        XCTAssertEqual(mirror.droppedFrameCount(), 0)
+    }
+}
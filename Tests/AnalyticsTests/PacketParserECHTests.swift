@@ -0,0 +1,106 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// ECH (Encrypted Client Hello) detection tests for the TLS ClientHello parser.
+final class PacketParserECHTests: XCTestCase {
+    /// Verifies a ClientHello carrying the ECH extension sets the flag and still surfaces
+    /// the cover SNI the client sent alongside it.
+    func testECHExtensionSetsFlagAlongsideCoverSNI() throws {
+        let packet = Self.makeIPv4TCPPacket(
+            payload: Self.clientHello(serverName: "cover.example", includeECH: true)
+        )
+        let metadata = try XCTUnwrap(PacketParser.parse(packet, ipVersionHint: nil))
+        XCTAssertEqual(metadata.tlsECH, true)
+        XCTAssertEqual(metadata.tlsServerName, "cover.example")
+    }
+
+    /// Verifies a parsed ClientHello without the extension reports `false`, not `nil`,
+    /// so consumers can distinguish "no ECH" from "no hello observed".
+    func testHelloWithoutECHReportsFalse() throws {
+        let packet = Self.makeIPv4TCPPacket(
+            payload: Self.clientHello(serverName: "plain.example", includeECH: false)
+        )
+        let metadata = try XCTUnwrap(PacketParser.parse(packet, ipVersionHint: nil))
+        XCTAssertEqual(metadata.tlsECH, false)
+        XCTAssertEqual(metadata.tlsServerName, "plain.example")
+    }
+
+    /// Verifies a parsed hello also carries JA3/JA4 fingerprints in its metadata.
+    func testParsedHelloCarriesFingerprints() throws {
+        let packet = Self.makeIPv4TCPPacket(
+            payload: Self.clientHello(serverName: "plain.example", includeECH: false)
+        )
+        let metadata = try XCTUnwrap(PacketParser.parse(packet, ipVersionHint: nil))
+        let ja3 = try XCTUnwrap(metadata.tlsJA3)
+        XCTAssertEqual(ja3.count, 32)
+        XCTAssertTrue(ja3.allSatisfy(\.isHexDigit))
+        // TCP transport, legacy 1.2 version, SNI present.
+        XCTAssertEqual(try XCTUnwrap(metadata.tlsJA4).prefix(4), "t12d")
+    }
+
+    /// Verifies non-handshake TCP payloads leave the flag unset.
+    func testNonHelloPayloadLeavesFlagUnset() throws {
+        let packet = Self.makeIPv4TCPPacket(payload: Data([0x17, 0x03, 0x03, 0x00, 0x01, 0x00]))
+        let metadata = try XCTUnwrap(PacketParser.parse(packet, ipVersionHint: nil))
+        XCTAssertNil(metadata.tlsECH)
+        XCTAssertNil(metadata.tlsServerName)
+    }
+
+    private static func clientHello(serverName: String, includeECH: Bool) -> Data {
+        var extensions = Data()
+        let name = Array(serverName.utf8)
+        let listLength = name.count + 3
+        let sniLength = listLength + 2
+        extensions.append(contentsOf: [0x00, 0x00, UInt8(sniLength >> 8), UInt8(sniLength & 0xFF)])
+        extensions.append(contentsOf: [UInt8(listLength >> 8), UInt8(listLength & 0xFF), 0x00])
+        extensions.append(contentsOf: [UInt8(name.count >> 8), UInt8(name.count & 0xFF)])
+        extensions.append(contentsOf: name)
+        if includeECH {
+            extensions.append(contentsOf: [0xFE, 0x0D, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00])
+        }
+
+        var body = Data([0x03, 0x03])
+        body.append(Data(repeating: 0, count: 32))
+        body.append(0x00)
+        body.append(contentsOf: [0x00, 0x02, 0x13, 0x01])
+        body.append(contentsOf: [0x01, 0x00])
+        body.append(contentsOf: [UInt8(extensions.count >> 8), UInt8(extensions.count & 0xFF)])
+        body.append(extensions)
+
+        var handshake = Data([0x01, UInt8(body.count >> 16), UInt8((body.count >> 8) & 0xFF), UInt8(body.count & 0xFF)])
+        handshake.append(body)
+        var record = Data([0x16, 0x03, 0x01, UInt8(handshake.count >> 8), UInt8(handshake.count & 0xFF)])
+        record.append(handshake)
+        return record
+    }
+
+    private static func makeIPv4TCPPacket(payload: Data) -> Data {
+        let totalLength = 20 + 20 + payload.count
+
+        var packet = Data(capacity: totalLength)
+        packet.append(0x45)
+        packet.append(0x00)
+        packet.append(UInt8((totalLength >> 8) & 0xFF))
+        packet.append(UInt8(totalLength & 0xFF))
+        packet.append(contentsOf: [0x00, 0x00, 0x00, 0x00])
+        packet.append(0x40)
+        packet.append(6)
+        packet.append(contentsOf: [0x00, 0x00])
+        packet.append(contentsOf: [10, 0, 0, 2])
+        packet.append(contentsOf: [93, 184, 216, 34])
+        packet.append(contentsOf: [0xC3, 0x50]) // source port 50000
+        packet.append(contentsOf: [0x01, 0xBB]) // destination port 443
+        packet.append(contentsOf: [0x00, 0x00, 0x00, 0x00])
+        packet.append(contentsOf: [0x00, 0x00, 0x00, 0x00])
+        packet.append(0x50)
+        packet.append(0x18)
+        packet.append(contentsOf: [0x00, 0x00, 0x00, 0x00, 0x00, 0x00])
+        packet.append(payload)
+        return packet
+    }
+}
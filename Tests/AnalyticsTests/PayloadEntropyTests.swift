@@ -0,0 +1,130 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Per-flow payload entropy estimation tests.
+final class PayloadEntropyTests: XCTestCase {
+    /// Verifies the estimator separates uniformly distributed bytes from constant bytes.
+    func testEstimatorDistinguishesUniformFromConstantBytes() throws {
+        var uniform = PayloadEntropyEstimator()
+        uniform.observe(Data((0 ..< 256).map { UInt8($0) })[...])
+        XCTAssertEqual(try XCTUnwrap(uniform.bitsPerByte), 8.0, accuracy: 0.001)
+
+        var constant = PayloadEntropyEstimator()
+        constant.observe(Data(repeating: 0x41, count: 256)[...])
+        XCTAssertEqual(try XCTUnwrap(constant.bitsPerByte), 0.0, accuracy: 0.001)
+    }
+
+    /// Verifies no estimate is reported below the minimum sample and sampling stops at the cap.
+    func testEstimatorSampleBounds() {
+        var estimator = PayloadEntropyEstimator()
+        estimator.observe(Data(repeating: 0x00, count: PayloadEntropyEstimator.minimumSampleBytes - 1)[...])
+        XCTAssertNil(estimator.bitsPerByte)
+
+        estimator.observe(Data(repeating: 0x00, count: PayloadEntropyEstimator.sampleLimit * 2)[...])
+        XCTAssertEqual(estimator.sampledByteCount, PayloadEntropyEstimator.sampleLimit)
+        XCTAssertFalse(estimator.wantsMoreBytes)
+    }
+
+    /// Verifies flow records report per-direction entropy: a high-entropy client payload and a
+    /// constant remote payload land on the flow-close summary.
+    func testPipelineReportsPerDirectionEntropyOnFlowClose() async throws {
+        let pipeline = PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let outboundPayload = (0 ..< 128).map { UInt8($0) }
+        let inboundPayload = [UInt8](repeating: 0x41, count: 128)
+        let dataOut = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x18,
+            payload: outboundPayload
+        )
+        let dataIn = makeIPv4TCPPacket(
+            sourceAddress: [1, 1, 1, 1],
+            destinationAddress: [10, 0, 0, 2],
+            sourcePort: 443,
+            destinationPort: 50_000,
+            tcpFlags: 0x18,
+            payload: inboundPayload
+        )
+        let finOut = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x11,
+            payload: []
+        )
+        let finIn = makeIPv4TCPPacket(
+            sourceAddress: [1, 1, 1, 1],
+            destinationAddress: [10, 0, 0, 2],
+            sourcePort: 443,
+            destinationPort: 50_000,
+            tcpFlags: 0x11,
+            payload: []
+        )
+
+        _ = await pipeline.ingest(packets: [Data(dataOut)], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [Data(dataIn)], families: [], direction: .inbound, policy: policy)
+        _ = await pipeline.ingest(packets: [Data(finOut)], families: [], direction: .outbound, policy: policy)
+        let records = await pipeline.ingest(packets: [Data(finIn)], families: [], direction: .inbound, policy: policy)
+
+        let close = try XCTUnwrap(records.first(where: { $0.kind == .flowClose }))
+        XCTAssertEqual(try XCTUnwrap(close.payloadEntropyOut), 7.0, accuracy: 0.001)
+        XCTAssertEqual(try XCTUnwrap(close.payloadEntropyIn), 0.0, accuracy: 0.001)
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,146 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// First-client-payload preview capture tests.
+final class PayloadPreviewTests: XCTestCase {
+    /// Verifies previews stay off by default: records carry no payload bytes.
+    func testPreviewDisabledByDefault() async throws {
+        let pipeline = makePipeline()
+        let policy = makePolicy(firstPayloadPreviewBytes: 0)
+
+        let dataOut = makeIPv4TCPPacket(payload: Array("GET / HTTP/1.1".utf8), outbound: true)
+        _ = await pipeline.ingest(packets: [Data(dataOut)], families: [], direction: .outbound, policy: policy)
+        let records = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: [], outbound: true, tcpFlags: 0x11))],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+
+        XCTAssertTrue(records.allSatisfy { $0.firstPayloadPreview == nil })
+    }
+
+    /// Verifies an opted-in policy captures only the first client payload's leading bytes
+    /// and reports them base64-encoded on subsequent flow records.
+    func testPreviewCapturesFirstClientPayloadPrefixOnce() async throws {
+        let pipeline = makePipeline()
+        let policy = makePolicy(firstPayloadPreviewBytes: 8)
+
+        let firstPayload = Array("GET / HTTP/1.1".utf8)
+        let secondPayload = Array("Host: example.com".utf8)
+        _ = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: firstPayload, outbound: true))],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+        _ = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: secondPayload, outbound: true))],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+        _ = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: [], outbound: true, tcpFlags: 0x11))],
+            families: [],
+            direction: .outbound,
+            policy: policy
+        )
+        let records = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: [], outbound: false, tcpFlags: 0x11))],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+
+        let close = try XCTUnwrap(records.first(where: { $0.kind == .flowClose }))
+        let preview = try XCTUnwrap(close.firstPayloadPreview)
+        XCTAssertEqual(Data(base64Encoded: preview), Data(firstPayload.prefix(8)))
+    }
+
+    /// Verifies remote payloads never seed a preview: only client bytes are captured.
+    func testPreviewIgnoresInboundPayloads() async throws {
+        let pipeline = makePipeline()
+        let policy = makePolicy(firstPayloadPreviewBytes: 8)
+
+        _ = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: Array("HTTP/1.1 200 OK".utf8), outbound: false))],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+        let records = await pipeline.ingest(
+            packets: [Data(makeIPv4TCPPacket(payload: [], outbound: false))],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+
+        XCTAssertTrue(records.allSatisfy { $0.firstPayloadPreview == nil })
+    }
+
+    /// Verifies the policy clamps oversized preview requests to the 64-byte ceiling.
+    func testPolicyClampsPreviewLength() {
+        XCTAssertEqual(makePolicy(firstPayloadPreviewBytes: 4_096).firstPayloadPreviewBytes, 64)
+        XCTAssertEqual(makePolicy(firstPayloadPreviewBytes: -1).firstPayloadPreviewBytes, 0)
+    }
+
+    private func makePipeline() -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makePolicy(firstPayloadPreviewBytes: Int) -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false,
+            firstPayloadPreviewBytes: firstPayloadPreviewBytes
+        )
+    }
+
+    private func makeIPv4TCPPacket(payload: [UInt8], outbound: Bool, tcpFlags: UInt8 = 0x18) -> [UInt8] {
+        let sourceAddress: [UInt8] = outbound ? [10, 0, 0, 2] : [1, 1, 1, 1]
+        let destinationAddress: [UInt8] = outbound ? [1, 1, 1, 1] : [10, 0, 0, 2]
+        let sourcePort: UInt16 = outbound ? 50_000 : 443
+        let destinationPort: UInt16 = outbound ? 443 : 50_000
+
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,60 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// Payload-size histogram bucketing and per-class aggregation tests.
+final class PayloadSizeHistogramTests: XCTestCase {
+    /// Verifies observations land in the bucket whose inclusive upper bound contains them.
+    func testRecordAssignsInclusiveBuckets() {
+        var histogram = PayloadSizeHistogram()
+        histogram.record(payloadLength: 1)
+        histogram.record(payloadLength: 64)
+        histogram.record(payloadLength: 65)
+        histogram.record(payloadLength: 1_500)
+        histogram.record(payloadLength: 9_000)
+
+        XCTAssertEqual(histogram.bucketCounts[0], 2)
+        XCTAssertEqual(histogram.bucketCounts[1], 1)
+        XCTAssertEqual(histogram.bucketCounts[PayloadSizeHistogram.bucketUpperBounds.count - 1], 1)
+        XCTAssertEqual(histogram.bucketCounts[PayloadSizeHistogram.bucketUpperBounds.count], 1)
+        XCTAssertEqual(histogram.packetCount, 5)
+        XCTAssertEqual(histogram.payloadBytes, 1 + 64 + 65 + 1_500 + 9_000)
+    }
+
+    /// Verifies zero-length and negative observations are ignored.
+    func testRecordIgnoresEmptyPayloads() {
+        var histogram = PayloadSizeHistogram()
+        histogram.record(payloadLength: 0)
+        histogram.record(payloadLength: -4)
+        XCTAssertTrue(histogram.isEmpty)
+    }
+
+    /// Verifies per-class routing keeps one independent histogram per protocol class.
+    func testFlowClassHistogramsRoutePerClass() {
+        var histograms = FlowClassPayloadHistograms()
+        histograms.record(protocolClass: .dns, payloadLength: 80)
+        histograms.record(protocolClass: .tls, payloadLength: 1_200)
+        histograms.record(protocolClass: .quic, payloadLength: 1_252)
+        histograms.record(protocolClass: .other, payloadLength: 40)
+        histograms.record(protocolClass: .dns, payloadLength: 120)
+
+        XCTAssertEqual(histograms[.dns].packetCount, 2)
+        XCTAssertEqual(histograms[.tls].packetCount, 1)
+        XCTAssertEqual(histograms[.quic].packetCount, 1)
+        XCTAssertEqual(histograms[.other].packetCount, 1)
+        XCTAssertFalse(histograms.isEmpty)
+    }
+
+    /// Verifies the snapshot shape survives an encode/decode round trip.
+    func testFlowClassHistogramsRoundTrip() throws {
+        var histograms = FlowClassPayloadHistograms()
+        histograms.record(protocolClass: .tls, payloadLength: 700)
+
+        let data = try JSONEncoder().encode(histograms)
+        let decoded = try JSONDecoder().decode(FlowClassPayloadHistograms.self, from: data)
+        XCTAssertEqual(decoded, histograms)
+    }
+}
@@ -0,0 +1,241 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Automatic anomaly diagnostic snapshot tests.
+final class PipelineDiagnosticTests: XCTestCase {
+    /// Verifies an outbound TCP flow with no reply past the handshake threshold captures a
+    /// snapshot carrying the stuck flow and its breadcrumbs.
+    func testStuckHandshakeCapturesSnapshotWithBreadcrumbs() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy()
+
+        let synPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x02,
+                payload: []
+            )
+        )
+        _ = await pipeline.ingest(packets: [synPacket], families: [], direction: .outbound, policy: policy)
+
+        // Past the stuck-handshake threshold but inside the idle TTL; fresh traffic on a
+        // different flow drives the maintenance sweep that runs the anomaly check.
+        await clock.advance(by: 70)
+        let freshPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [9, 9, 9, 9],
+                sourcePort: 50_001,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        _ = await pipeline.ingest(packets: [freshPacket], families: [], direction: .outbound, policy: policy)
+
+        let snapshots = await pipeline.diagnosticSnapshots().filter { $0.trigger == .stuckHandshake }
+        XCTAssertEqual(snapshots.count, 1)
+        // Maintenance runs at batch start, before the fresh flow's context exists.
+        let snapshot = try XCTUnwrap(snapshots.first)
+        XCTAssertEqual(snapshot.trackedFlowCount, 1)
+        XCTAssertEqual(snapshot.flows.count, 1)
+        let stuckFlow = try XCTUnwrap(snapshot.flows.first)
+        XCTAssertEqual(stuckFlow.inboundPacketCount, 0)
+        XCTAssertGreaterThan(stuckFlow.outboundPacketCount, 0)
+        XCTAssertEqual(stuckFlow.breadcrumbs.first?.event, .opened)
+    }
+
+    /// Verifies a reply packet clears the stuck-handshake shape, so healthy handshakes
+    /// never capture snapshots.
+    func testRepliedFlowDoesNotCountAsStuck() async {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy()
+
+        let synPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x02,
+                payload: []
+            )
+        )
+        let synAckPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [203, 0, 113, 9],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 443,
+                destinationPort: 50_000,
+                tcpFlags: 0x12,
+                payload: []
+            )
+        )
+        _ = await pipeline.ingest(packets: [synPacket], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [synAckPacket], families: [], direction: .inbound, policy: policy)
+
+        await clock.advance(by: 70)
+        let keepAliveData = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17]
+            )
+        )
+        _ = await pipeline.ingest(packets: [keepAliveData], families: [], direction: .outbound, policy: policy)
+
+        let snapshots = await pipeline.diagnosticSnapshots()
+        XCTAssertTrue(snapshots.allSatisfy { $0.trigger != .stuckHandshake })
+    }
+
+    /// Verifies blowing the flow-table budget captures one bounded snapshot of the
+    /// overloaded table before overflow eviction runs.
+    func testFlowTableOverflowCapturesBoundedSnapshot() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy()
+
+        var packets: [Data] = []
+        for index in 0..<2_100 {
+            packets.append(
+                Data(
+                    makeIPv4TCPPacket(
+                        sourceAddress: [10, 0, 0, 2],
+                        destinationAddress: [203, 0, UInt8(113 + index % 2), UInt8(index % 250 + 1)],
+                        sourcePort: UInt16(10_000 + index / 250),
+                        destinationPort: 443,
+                        tcpFlags: 0x18,
+                        payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+                    )
+                )
+            )
+        }
+        _ = await pipeline.ingest(packets: packets, families: [], direction: .outbound, policy: policy)
+
+        let snapshots = await pipeline.diagnosticSnapshots().filter { $0.trigger == .flowTableOverflow }
+        XCTAssertEqual(snapshots.count, 1)
+        let snapshot = try XCTUnwrap(snapshots.first)
+        XCTAssertGreaterThan(snapshot.trackedFlowCount, 2_048)
+        XCTAssertEqual(snapshot.flows.count, PipelineDiagnosticBuffer.Policy.maxFlowsPerSnapshot)
+    }
+
+    /// Verifies clearing drops captured snapshots while the per-trigger cooldown keeps a
+    /// persistent anomaly from being recaptured immediately.
+    func testClearKeepsCooldownAgainstImmediateRecapture() async {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = makePipeline(clock: clock)
+        let policy = makeEmissionPolicy()
+
+        let synPacket = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x02,
+                payload: []
+            )
+        )
+        _ = await pipeline.ingest(packets: [synPacket], families: [], direction: .outbound, policy: policy)
+
+        await clock.advance(by: 70)
+        _ = await pipeline.ingest(packets: [triggerPacket(sourcePort: 50_001)], families: [], direction: .outbound, policy: policy)
+        var snapshots = await pipeline.diagnosticSnapshots()
+        XCTAssertEqual(snapshots.filter { $0.trigger == .stuckHandshake }.count, 1)
+
+        await pipeline.clearDiagnosticSnapshots()
+
+        // Inside the cooldown the persistent anomaly must not be recaptured.
+        await clock.advance(by: 16)
+        _ = await pipeline.ingest(packets: [triggerPacket(sourcePort: 50_002)], families: [], direction: .outbound, policy: policy)
+        snapshots = await pipeline.diagnosticSnapshots()
+        XCTAssertTrue(snapshots.filter { $0.trigger == .stuckHandshake }.isEmpty)
+
+        // Once the cooldown elapses the still-present anomaly is captured again.
+        await clock.advance(by: 16)
+        _ = await pipeline.ingest(packets: [triggerPacket(sourcePort: 50_003)], families: [], direction: .outbound, policy: policy)
+        snapshots = await pipeline.diagnosticSnapshots()
+        XCTAssertEqual(snapshots.filter { $0.trigger == .stuckHandshake }.count, 1)
+    }
+
+    private func makePipeline(clock: DeterministicClock) -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    private func triggerPacket(sourcePort: UInt16) -> Data {
+        Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [9, 9, 9, 9],
+                sourcePort: sourcePort,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,81 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import XCTest
+
+/// Policy change audit trail tests.
+final class PolicyAuditLogTests: XCTestCase {
+    /// Verifies a replacement is recorded as per-rule removed/installed diffs with attribution.
+    func testReplacementRecordsRuleLevelDiff() {
+        var log = PolicyAuditLog()
+        let now = Date(timeIntervalSince1970: 1_000)
+        let voipRule = FlowPinRule(destinationPort: 5_223)
+        let pushRule = FlowPinRule(hostSuffix: "push.apple.com")
+
+        log.recordReplacement(
+            from: .disabled,
+            to: FlowPinningPolicy(rules: [voipRule]),
+            source: "mdm-agent",
+            now: now
+        )
+        log.recordReplacement(
+            from: FlowPinningPolicy(rules: [voipRule]),
+            to: FlowPinningPolicy(rules: [voipRule, pushRule]),
+            source: "app-settings",
+            now: now.addingTimeInterval(60)
+        )
+
+        let entries = log.snapshot()
+        XCTAssertEqual(entries.count, 2)
+        XCTAssertEqual(entries[0].change, .installed)
+        XCTAssertEqual(entries[0].destinationPort, 5_223)
+        XCTAssertEqual(entries[0].source, "mdm-agent")
+        XCTAssertEqual(entries[1].change, .installed)
+        XCTAssertEqual(entries[1].hostSuffix, "push.apple.com")
+        XCTAssertEqual(entries[1].source, "app-settings")
+        XCTAssertEqual(entries[1].timestamp, now.addingTimeInterval(60))
+    }
+
+    /// Verifies removing a rule is audited and unchanged rules produce no entries.
+    func testRuleRemovalIsAuditedWithoutNoiseForUnchangedRules() {
+        var log = PolicyAuditLog()
+        let now = Date(timeIntervalSince1970: 0)
+        let keptRule = FlowPinRule(destinationPort: 443, hostSuffix: "example.com")
+        let droppedRule = FlowPinRule(hostSuffix: "legacy.example.com")
+
+        log.recordReplacement(
+            from: FlowPinningPolicy(rules: [keptRule, droppedRule]),
+            to: FlowPinningPolicy(rules: [keptRule]),
+            source: "host",
+            now: now
+        )
+
+        let entries = log.snapshot()
+        XCTAssertEqual(entries.count, 1)
+        XCTAssertEqual(entries[0].change, .removed)
+        XCTAssertEqual(entries[0].hostSuffix, "legacy.example.com")
+    }
+
+    /// Verifies the buffer stays bounded by dropping its oldest entries.
+    func testAuditBufferDropsOldestPastCap() {
+        var log = PolicyAuditLog()
+        let now = Date(timeIntervalSince1970: 0)
+
+        for port in 0..<(PolicyAuditLog.Policy.maxEntries + 10) {
+            log.recordReplacement(
+                from: .disabled,
+                to: FlowPinningPolicy(rules: [FlowPinRule(destinationPort: UInt16(port + 1))]),
+                source: "host",
+                now: now.addingTimeInterval(Double(port))
+            )
+        }
+
+        let entries = log.snapshot()
+        XCTAssertEqual(entries.count, PolicyAuditLog.Policy.maxEntries)
+        XCTAssertEqual(entries.first?.destinationPort, 11)
+        XCTAssertEqual(entries.last?.destinationPort, UInt16(PolicyAuditLog.Policy.maxEntries + 10))
+    }
+}
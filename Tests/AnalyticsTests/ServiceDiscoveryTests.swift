@@ -0,0 +1,246 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Foundation
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// mDNS DNS-SD parsing and service-discovery catalog tests.
+final class ServiceDiscoveryTests: XCTestCase {
+    /// Verifies the parser extracts PTR, SRV, and TXT records from an mDNS announcement.
+    func testPacketParserExtractsDNSSDRecordsFromMDNSResponse() throws {
+        let packet = makeIPv4UDPPacket(
+            sourceAddress: [192, 168, 1, 40],
+            destinationAddress: [224, 0, 0, 251],
+            sourcePort: 5_353,
+            destinationPort: 5_353,
+            payload: makeMDNSAnnouncementPayload()
+        )
+
+        let metadata = try XCTUnwrap(PacketParser.parse(Data(packet), ipVersionHint: nil))
+        let records = try XCTUnwrap(metadata.dnsServiceRecords)
+        XCTAssertEqual(records.count, 3)
+        XCTAssertEqual(
+            records[0],
+            DNSServiceRecord(kind: .pointer, name: "_airplay._tcp.local", target: "Den._airplay._tcp.local")
+        )
+        XCTAssertEqual(
+            records[1],
+            DNSServiceRecord(kind: .service, name: "Den._airplay._tcp.local", target: "den-tv.local", port: 7_000)
+        )
+        XCTAssertEqual(
+            records[2],
+            DNSServiceRecord(kind: .text, name: "Den._airplay._tcp.local", textEntries: ["model=TV"])
+        )
+    }
+
+    /// Verifies unicast DNS responses without DNS-SD names carry no service records.
+    func testPacketParserLeavesServiceRecordsNilForPlainDNS() throws {
+        var payload: [UInt8] = [
+            0x12, 0x34, 0x81, 0x80,
+            0x00, 0x01, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x00,
+        ]
+        payload += encodeDNSName("example.com")
+        payload += [0x00, 0x01, 0x00, 0x01]
+        payload += encodeDNSName("example.com")
+        payload += [0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x04, 93, 184, 216, 34]
+
+        let packet = makeIPv4UDPPacket(
+            sourceAddress: [8, 8, 8, 8],
+            destinationAddress: [10, 0, 0, 2],
+            sourcePort: 53,
+            destinationPort: 53_000,
+            payload: payload
+        )
+
+        let metadata = try XCTUnwrap(PacketParser.parse(Data(packet), ipVersionHint: nil))
+        XCTAssertEqual(metadata.dnsAnswerAddresses?.count, 1)
+        XCTAssertNil(metadata.dnsServiceRecords)
+    }
+
+    /// Verifies PTR, SRV, and TXT records for the same instance merge into one catalog entry.
+    func testCatalogMergesRecordsForOneInstance() {
+        var catalog = ServiceDiscoveryCatalog()
+        let now = Date(timeIntervalSinceReferenceDate: 100)
+
+        catalog.record(
+            serviceRecords: [
+                DNSServiceRecord(kind: .pointer, name: "_airplay._tcp.local", target: "Den._airplay._tcp.local"),
+                DNSServiceRecord(kind: .service, name: "Den._airplay._tcp.local", target: "den-tv.local", port: 7_000),
+                DNSServiceRecord(kind: .text, name: "Den._airplay._tcp.local", textEntries: ["model=TV"]),
+            ],
+            advertiserAddress: "192.168.1.40",
+            now: now
+        )
+
+        let services = catalog.snapshot()
+        XCTAssertEqual(services.count, 1)
+        XCTAssertEqual(services[0].serviceType, "_airplay._tcp.local")
+        XCTAssertEqual(services[0].instanceName, "Den._airplay._tcp.local")
+        XCTAssertEqual(services[0].targetHost, "den-tv.local")
+        XCTAssertEqual(services[0].port, 7_000)
+        XCTAssertEqual(services[0].textEntries, ["model=TV"])
+        XCTAssertEqual(services[0].advertiserAddress, "192.168.1.40")
+        XCTAssertEqual(services[0].lastSeenAt, now)
+    }
+
+    /// Verifies a later announcement refreshes the existing entry instead of duplicating it.
+    func testCatalogRefreshesEntryOnRepeatedAnnouncement() {
+        var catalog = ServiceDiscoveryCatalog()
+        let firstSeenAt = Date(timeIntervalSinceReferenceDate: 100)
+
+        catalog.record(
+            serviceRecords: [
+                DNSServiceRecord(kind: .service, name: "Den._airplay._tcp.local", target: "den-tv.local", port: 7_000),
+            ],
+            advertiserAddress: "192.168.1.40",
+            now: firstSeenAt
+        )
+        catalog.record(
+            serviceRecords: [
+                DNSServiceRecord(kind: .service, name: "den._airplay._tcp.local", target: "den-tv.local", port: 7_001),
+            ],
+            advertiserAddress: "192.168.1.41",
+            now: firstSeenAt.addingTimeInterval(30)
+        )
+
+        let services = catalog.snapshot()
+        XCTAssertEqual(services.count, 1)
+        XCTAssertEqual(services[0].port, 7_001)
+        XCTAssertEqual(services[0].advertiserAddress, "192.168.1.41")
+        XCTAssertEqual(services[0].lastSeenAt, firstSeenAt.addingTimeInterval(30))
+    }
+
+    /// Verifies an mDNS announcement flows into the pipeline's discovered-services snapshot
+    /// without being counted as a spoofed unicast DNS response.
+    func testPipelineCatalogsMDNSAnnouncementsWithoutSpoofAccounting() async {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: true,
+            maxMetadataProbesPerBatch: 2,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            emitDNSAssociationFields: true,
+            emitLineageFields: false,
+            emitPathRegimeFields: false,
+            emitServiceAttributionFields: false,
+            includeHostHints: false,
+            includeQUICIdentity: false,
+            activitySampleMinimumPackets: 1_024,
+            activitySampleMinimumBytes: 16 * 1_024 * 1_024,
+            activitySampleMinimumInterval: 60,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let announcement = Data(
+            makeIPv4UDPPacket(
+                sourceAddress: [192, 168, 1, 40],
+                destinationAddress: [224, 0, 0, 251],
+                sourcePort: 5_353,
+                destinationPort: 5_353,
+                payload: makeMDNSAnnouncementPayload()
+            )
+        )
+        _ = await pipeline.ingest(
+            packets: [announcement],
+            families: [],
+            direction: .inbound,
+            policy: policy
+        )
+
+        let services = await pipeline.discoveredServicesSnapshot()
+        XCTAssertEqual(services.count, 1)
+        XCTAssertEqual(services[0].instanceName, "Den._airplay._tcp.local")
+        XCTAssertEqual(services[0].port, 7_000)
+
+        let integrity = await pipeline.dnsIntegrityCountersSnapshot()
+        XCTAssertEqual(integrity.spoofCandidateCount, 0)
+        let resolverStats = await pipeline.dnsResolverStatsSnapshot()
+        XCTAssertTrue(resolverStats.isEmpty)
+    }
+
+    /// One mDNS response advertising `Den._airplay._tcp.local` via PTR, SRV, and TXT records.
+    private func makeMDNSAnnouncementPayload() -> [UInt8] {
+        let serviceType = encodeDNSName("_airplay._tcp.local")
+        let instanceName = encodeDNSName("Den._airplay._tcp.local")
+        let targetHost = encodeDNSName("den-tv.local")
+
+        var payload: [UInt8] = [
+            0x00, 0x00, 0x84, 0x00,
+            0x00, 0x00, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x02,
+        ]
+        // Answer: PTR _airplay._tcp.local -> Den._airplay._tcp.local
+        payload += serviceType
+        payload += [0x00, 0x0c, 0x80, 0x01, 0x00, 0x00, 0x11, 0x94]
+        payload += [UInt8(instanceName.count >> 8), UInt8(instanceName.count & 0xff)]
+        payload += instanceName
+        // Additional: SRV Den._airplay._tcp.local -> den-tv.local:7000
+        payload += instanceName
+        payload += [0x00, 0x21, 0x80, 0x01, 0x00, 0x00, 0x00, 0x78]
+        let srvLength = 6 + targetHost.count
+        payload += [UInt8(srvLength >> 8), UInt8(srvLength & 0xff)]
+        payload += [0x00, 0x00, 0x00, 0x00, UInt8(7_000 >> 8), UInt8(7_000 & 0xff)]
+        payload += targetHost
+        // Additional: TXT Den._airplay._tcp.local -> model=TV
+        payload += instanceName
+        payload += [0x00, 0x10, 0x80, 0x01, 0x00, 0x00, 0x11, 0x94]
+        let txtEntry = Array("model=TV".utf8)
+        payload += [0x00, UInt8(txtEntry.count + 1), UInt8(txtEntry.count)]
+        payload += txtEntry
+        return payload
+    }
+
+    private func encodeDNSName(_ name: String) -> [UInt8] {
+        var encoded: [UInt8] = []
+        for label in name.split(separator: ".") {
+            let bytes = Array(label.utf8)
+            encoded.append(UInt8(bytes.count))
+            encoded += bytes
+        }
+        encoded.append(0)
+        return encoded
+    }
+
+    private func makeIPv4UDPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 8 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 17
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let udpOffset = 20
+        let udpLength = 8 + payload.count
+        packet[udpOffset] = UInt8(sourcePort >> 8)
+        packet[udpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[udpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[udpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[udpOffset + 4] = UInt8(udpLength >> 8)
+        packet[udpOffset + 5] = UInt8(udpLength & 0xff)
+        if !payload.isEmpty {
+            packet[(udpOffset + 8)...] = payload[0...]
+        }
+        return packet
+    }
+}
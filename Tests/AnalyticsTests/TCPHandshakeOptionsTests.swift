@@ -0,0 +1,159 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// TCP handshake option extraction and per-flow negotiation reporting tests.
+final class TCPHandshakeOptionsTests: XCTestCase {
+    /// Verifies the parser extracts timestamps, SACK-permitted, window scale, and MSS from a SYN.
+    func testParseExtractsOptionsFromSYN() throws {
+        let packet = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x02,
+            options: Self.fullOptionSet
+        )
+
+        let options = try XCTUnwrap(TCPHandshakeOptions.parse(packet: Data(packet)))
+        XCTAssertTrue(options.timestamps)
+        XCTAssertTrue(options.sackPermitted)
+        XCTAssertEqual(options.windowScale, 7)
+        XCTAssertEqual(options.maximumSegmentSize, 1_460)
+    }
+
+    /// Verifies negotiation is the intersection of both sides: options one side omits are off,
+    /// the effective MSS is the smaller offer, and window scale requires both sides.
+    func testNegotiationIntersectsBothSides() {
+        let offered = TCPHandshakeOptions(timestamps: true, sackPermitted: true, windowScale: 7, maximumSegmentSize: 1_460)
+        let answered = TCPHandshakeOptions(timestamps: false, sackPermitted: true, windowScale: 8, maximumSegmentSize: 1_400)
+
+        let negotiated = TCPNegotiatedOptions(offered: offered, answered: answered)
+        XCTAssertFalse(negotiated.timestampsEnabled)
+        XCTAssertTrue(negotiated.selectiveAckEnabled)
+        XCTAssertEqual(negotiated.windowScale, 8)
+        XCTAssertEqual(negotiated.maximumSegmentSize, 1_400)
+
+        let noScaleAnswer = TCPHandshakeOptions(timestamps: true, sackPermitted: false, windowScale: nil, maximumSegmentSize: nil)
+        let partial = TCPNegotiatedOptions(offered: offered, answered: noScaleAnswer)
+        XCTAssertNil(partial.windowScale)
+        XCTAssertEqual(partial.maximumSegmentSize, 1_460)
+    }
+
+    /// Verifies a flow's records report the negotiated set after the pipeline observes both the
+    /// outbound SYN and the inbound SYN-ACK.
+    func testPipelineReportsNegotiatedOptionsOnFlowClose() async throws {
+        let pipeline = PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: true,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let syn = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x02,
+            options: Self.fullOptionSet
+        )
+        let synAck = makeIPv4TCPPacket(
+            sourceAddress: [1, 1, 1, 1],
+            destinationAddress: [10, 0, 0, 2],
+            sourcePort: 443,
+            destinationPort: 50_000,
+            tcpFlags: 0x12,
+            options: [2, 4, 0x05, 0x78, 4, 2, 1, 1]
+        )
+        let finOut = makeIPv4TCPPacket(
+            sourceAddress: [10, 0, 0, 2],
+            destinationAddress: [1, 1, 1, 1],
+            sourcePort: 50_000,
+            destinationPort: 443,
+            tcpFlags: 0x11,
+            options: []
+        )
+        let finIn = makeIPv4TCPPacket(
+            sourceAddress: [1, 1, 1, 1],
+            destinationAddress: [10, 0, 0, 2],
+            sourcePort: 443,
+            destinationPort: 50_000,
+            tcpFlags: 0x11,
+            options: []
+        )
+
+        _ = await pipeline.ingest(packets: [Data(syn)], families: [], direction: .outbound, policy: policy)
+        _ = await pipeline.ingest(packets: [Data(synAck)], families: [], direction: .inbound, policy: policy)
+        _ = await pipeline.ingest(packets: [Data(finOut)], families: [], direction: .outbound, policy: policy)
+        let records = await pipeline.ingest(packets: [Data(finIn)], families: [], direction: .inbound, policy: policy)
+
+        let close = try XCTUnwrap(records.first(where: { $0.kind == .flowClose }))
+        let negotiated = try XCTUnwrap(close.tcpNegotiatedOptions)
+        XCTAssertFalse(negotiated.timestampsEnabled)
+        XCTAssertTrue(negotiated.selectiveAckEnabled)
+        XCTAssertNil(negotiated.windowScale)
+        XCTAssertEqual(negotiated.maximumSegmentSize, 1_400)
+    }
+
+    /// MSS 1460, SACK-permitted, window scale 7, and timestamps, padded with no-ops.
+    private static let fullOptionSet: [UInt8] = [
+        2, 4, 0x05, 0xb4,
+        4, 2,
+        3, 3, 7,
+        1,
+        8, 10, 0, 0, 0, 1, 0, 0, 0, 0,
+    ]
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        options: [UInt8]
+    ) -> [UInt8] {
+        var paddedOptions = options
+        while paddedOptions.count % 4 != 0 {
+            paddedOptions.append(0)
+        }
+        let tcpHeaderLength = 20 + paddedOptions.count
+        var packet = [UInt8](repeating: 0, count: 20 + tcpHeaderLength)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = UInt8((tcpHeaderLength / 4) << 4)
+        packet[tcpOffset + 13] = tcpFlags
+        if !paddedOptions.isEmpty {
+            packet[(tcpOffset + 20)...] = paddedOptions[0...]
+        }
+        return packet
+    }
+}
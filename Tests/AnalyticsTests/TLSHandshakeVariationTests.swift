@@ -0,0 +1,159 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Aggregate ClientHello randomization counter tests.
+final class TLSHandshakeVariationTests: XCTestCase {
+    /// Verifies the counters tally each feature independently and track the observed total.
+    func testCountersTallyVariationFeatures() {
+        var counters = TLSHandshakeVariationCounters()
+        XCTAssertTrue(counters.isEmpty)
+
+        counters.record(variation: TLSClientHelloVariation(
+            greaseValueCount: 4, hasUnusualCipherOrder: false, offersSessionResumption: false
+        ))
+        counters.record(variation: TLSClientHelloVariation(
+            greaseValueCount: 0, hasUnusualCipherOrder: true, offersSessionResumption: true
+        ))
+        counters.record(variation: TLSClientHelloVariation(
+            greaseValueCount: 0, hasUnusualCipherOrder: false, offersSessionResumption: false
+        ))
+
+        XCTAssertEqual(counters.observedHelloCount, 3)
+        XCTAssertEqual(counters.greaseHelloCount, 1)
+        XCTAssertEqual(counters.unusualCipherOrderCount, 1)
+        XCTAssertEqual(counters.sessionResumptionCount, 1)
+        XCTAssertFalse(counters.isEmpty)
+    }
+
+    /// Verifies negative initializer inputs clamp to zero, like the other counter structs.
+    func testInitializerClampsNegativeCounts() {
+        let counters = TLSHandshakeVariationCounters(
+            observedHelloCount: -1,
+            greaseHelloCount: -2,
+            unusualCipherOrderCount: -3,
+            sessionResumptionCount: -4
+        )
+        XCTAssertTrue(counters.isEmpty)
+    }
+
+    /// Verifies ingest extracts variation features from ClientHello candidates on the
+    /// fast path while non-candidate traffic leaves the counters untouched.
+    func testIngestCountsClientHelloVariations() async throws {
+        let pipeline = makePipeline()
+        let greased = makeHTTPSPacket(
+            sourcePort: 50_000,
+            payload: Self.clientHelloRecord(ciphers: [0x0A0A, 0x1301], sessionIDLength: 0)
+        )
+        let reordered = makeHTTPSPacket(
+            sourcePort: 50_001,
+            payload: Self.clientHelloRecord(ciphers: [0xC02F, 0x1301], sessionIDLength: 32)
+        )
+        let plain = makeHTTPSPacket(
+            sourcePort: 50_002,
+            payload: Self.clientHelloRecord(ciphers: [0x1301, 0x1302], sessionIDLength: 0)
+        )
+        let nonTLS = makeHTTPSPacket(sourcePort: 50_003, payload: Data([0x47, 0x45, 0x54, 0x20]))
+
+        _ = await pipeline.ingest(
+            packets: [greased, reordered, plain, nonTLS],
+            families: [],
+            direction: .outbound,
+            policy: makeEmissionPolicy()
+        )
+
+        let counters = await pipeline.tlsHandshakeVariationCountersSnapshot()
+        XCTAssertEqual(counters.observedHelloCount, 3)
+        XCTAssertEqual(counters.greaseHelloCount, 1)
+        XCTAssertEqual(counters.unusualCipherOrderCount, 1)
+        XCTAssertEqual(counters.sessionResumptionCount, 1)
+    }
+
+    /// Verifies the counters survive a snapshot round trip through the wire encoding.
+    func testCountersCodableRoundTrip() throws {
+        var counters = TLSHandshakeVariationCounters()
+        counters.record(variation: TLSClientHelloVariation(
+            greaseValueCount: 2, hasUnusualCipherOrder: true, offersSessionResumption: true
+        ))
+
+        let decoded = try JSONDecoder().decode(
+            TLSHandshakeVariationCounters.self,
+            from: JSONEncoder().encode(counters)
+        )
+        XCTAssertEqual(decoded, counters)
+    }
+
+    private func makePipeline() -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    /// IPv4 + TCP to port 443 carrying `payload` as the transport payload.
+    private func makeHTTPSPacket(sourcePort: UInt16, payload: Data) -> Data {
+        var packet = [UInt8](repeating: 0, count: 40 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = [10, 0, 0, 2][0...]
+        packet[16..<20] = [1, 1, 1, 1][0...]
+
+        packet[20] = UInt8(sourcePort >> 8)
+        packet[21] = UInt8(sourcePort & 0xff)
+        packet[22] = 0x01
+        packet[23] = 0xBB
+        packet[32] = 0x50
+        packet[33] = 0x18
+        packet.replaceSubrange(40..., with: payload)
+        return Data(packet)
+    }
+
+    /// Minimal extension-free ClientHello record: enough for the variation parser while
+    /// staying far smaller than a browser hello.
+    private static func clientHelloRecord(ciphers: [UInt16], sessionIDLength: Int) -> Data {
+        var body = Data([0x03, 0x03])
+        body.append(Data(repeating: 0x42, count: 32))
+        body.append(UInt8(sessionIDLength))
+        body.append(Data(repeating: 0x24, count: sessionIDLength))
+        let cipherBytes = ciphers.count * 2
+        body.append(contentsOf: [UInt8(cipherBytes >> 8), UInt8(cipherBytes & 0xFF)])
+        for cipher in ciphers {
+            body.append(contentsOf: [UInt8(cipher >> 8), UInt8(cipher & 0xFF)])
+        }
+        body.append(contentsOf: [0x01, 0x00])
+
+        var handshake = Data([0x01, 0x00])
+        handshake.append(contentsOf: [UInt8(body.count >> 8), UInt8(body.count & 0xFF)])
+        handshake.append(body)
+
+        var record = Data([0x16, 0x03, 0x01])
+        record.append(contentsOf: [UInt8(handshake.count >> 8), UInt8(handshake.count & 0xFF)])
+        record.append(handshake)
+        return record
+    }
+}
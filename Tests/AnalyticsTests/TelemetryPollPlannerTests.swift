@@ -0,0 +1,103 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// Adaptive snapshot-poll interval selection tests.
+final class TelemetryPollPlannerTests: XCTestCase {
+    /// Verifies an idle tunnel is polled at the ceiling so a foreground app stops waking
+    /// the provider for nothing.
+    func testIdleTunnelChoosesCeiling() {
+        var planner = TelemetryPollPlanner()
+        let start = Date(timeIntervalSince1970: 0)
+
+        let first = planner.nextIntervalSeconds(now: start, activeFlowCount: 0, cumulativePacketCount: 0)
+        XCTAssertEqual(first, planner.configuration.maximumIntervalSeconds)
+
+        let second = planner.nextIntervalSeconds(
+            now: start.addingTimeInterval(30),
+            activeFlowCount: 0,
+            cumulativePacketCount: 0
+        )
+        XCTAssertEqual(second, planner.configuration.maximumIntervalSeconds)
+        XCTAssertEqual(planner.lastChosenIntervalSeconds, second)
+    }
+
+    /// Verifies a flow count at or past the busy threshold pins the interval to the floor
+    /// even with no packet-rate history yet.
+    func testBusyFlowCountChoosesFloor() {
+        var planner = TelemetryPollPlanner(
+            configuration: TelemetryPollPlanner.Configuration(busyFlowCount: 8)
+        )
+        let interval = planner.nextIntervalSeconds(
+            now: Date(timeIntervalSince1970: 0),
+            activeFlowCount: 8,
+            cumulativePacketCount: 0
+        )
+        XCTAssertEqual(interval, planner.configuration.minimumIntervalSeconds)
+    }
+
+    /// Verifies the packet rate between consecutive calls drives the interval down even
+    /// when only a handful of flows carry the traffic.
+    func testRecentPacketRateChoosesFloor() {
+        var planner = TelemetryPollPlanner(
+            configuration: TelemetryPollPlanner.Configuration(busyPacketsPerSecond: 100)
+        )
+        let start = Date(timeIntervalSince1970: 0)
+        _ = planner.nextIntervalSeconds(now: start, activeFlowCount: 1, cumulativePacketCount: 0)
+
+        let interval = planner.nextIntervalSeconds(
+            now: start.addingTimeInterval(10),
+            activeFlowCount: 1,
+            cumulativePacketCount: 2_000
+        )
+        XCTAssertEqual(interval, planner.configuration.minimumIntervalSeconds)
+    }
+
+    /// Verifies partial load lands between the floor and the ceiling, scaled linearly.
+    func testIntermediateLoadInterpolates() {
+        var planner = TelemetryPollPlanner(
+            configuration: TelemetryPollPlanner.Configuration(
+                minimumIntervalSeconds: 2,
+                maximumIntervalSeconds: 22,
+                busyFlowCount: 10
+            )
+        )
+        let interval = planner.nextIntervalSeconds(
+            now: Date(timeIntervalSince1970: 0),
+            activeFlowCount: 5,
+            cumulativePacketCount: 0
+        )
+        XCTAssertEqual(interval, 12, accuracy: 0.001)
+    }
+
+    /// Verifies a counter reset (worker restart) reads as zero rate instead of going negative.
+    func testBackwardsPacketCounterReadsAsIdle() {
+        var planner = TelemetryPollPlanner()
+        let start = Date(timeIntervalSince1970: 0)
+        _ = planner.nextIntervalSeconds(now: start, activeFlowCount: 0, cumulativePacketCount: 50_000)
+
+        let interval = planner.nextIntervalSeconds(
+            now: start.addingTimeInterval(5),
+            activeFlowCount: 0,
+            cumulativePacketCount: 100
+        )
+        XCTAssertEqual(interval, planner.configuration.maximumIntervalSeconds)
+    }
+
+    /// Verifies configuration clamping keeps the bounds ordered and positive.
+    func testConfigurationClampsBounds() {
+        let configuration = TelemetryPollPlanner.Configuration(
+            minimumIntervalSeconds: -1,
+            maximumIntervalSeconds: -5,
+            busyFlowCount: 0,
+            busyPacketsPerSecond: 0
+        )
+        XCTAssertEqual(configuration.minimumIntervalSeconds, 0.1)
+        XCTAssertEqual(configuration.maximumIntervalSeconds, 0.1)
+        XCTAssertEqual(configuration.busyFlowCount, 1)
+        XCTAssertEqual(configuration.busyPacketsPerSecond, 1)
+    }
+}
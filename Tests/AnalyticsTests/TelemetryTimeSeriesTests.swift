@@ -0,0 +1,125 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Per-second dashboard series recorder and snapshot tests.
+final class TelemetryTimeSeriesTests: XCTestCase {
+    /// Verifies byte and query totals accumulate within a second while the flow count
+    /// keeps the highest sample, and idle seconds come back as zero buckets.
+    func testRecorderBucketsBySecondWithZeroGaps() {
+        var recorder = TelemetrySeriesRecorder()
+        let base = Date(timeIntervalSince1970: 1_000)
+        recorder.record(inboundBytes: 100, activeFlowCount: 3, dnsQueryCount: 1, now: base)
+        recorder.record(outboundBytes: 50, activeFlowCount: 2, now: base.addingTimeInterval(0.4))
+        recorder.record(blockedFlowCount: 1, now: base.addingTimeInterval(2))
+
+        let series = recorder.series(windowSeconds: 3, now: base.addingTimeInterval(2))
+        XCTAssertEqual(series.buckets.count, 3)
+        XCTAssertEqual(series.buckets[0].bucketStart, base)
+        XCTAssertEqual(series.buckets[0].inboundBytes, 100)
+        XCTAssertEqual(series.buckets[0].outboundBytes, 50)
+        XCTAssertEqual(series.buckets[0].activeFlowCount, 3)
+        XCTAssertEqual(series.buckets[0].dnsQueryCount, 1)
+        XCTAssertEqual(series.buckets[1], TunnelTelemetrySeries.Bucket(
+            bucketStart: base.addingTimeInterval(1),
+            inboundBytes: 0, outboundBytes: 0, activeFlowCount: 0, dnsQueryCount: 0, blockedFlowCount: 0
+        ))
+        XCTAssertEqual(series.buckets[2].blockedFlowCount, 1)
+        XCTAssertFalse(series.isEmpty)
+    }
+
+    /// Verifies the window clamps to the ring depth and cells from a previous lap of the
+    /// ring read as empty seconds rather than stale data.
+    func testRecorderWindowClampAndRingReuse() {
+        var recorder = TelemetrySeriesRecorder()
+        let base = Date(timeIntervalSince1970: 5_000)
+        recorder.record(inboundBytes: 10, now: base)
+
+        let clamped = recorder.series(windowSeconds: 10_000, now: base)
+        XCTAssertEqual(clamped.buckets.count, TelemetrySeriesRecorder.capacitySeconds)
+        XCTAssertEqual(clamped.buckets.last?.inboundBytes, 10)
+
+        // One full lap later the same cell index belongs to a new second.
+        let lapLater = base.addingTimeInterval(TimeInterval(TelemetrySeriesRecorder.capacitySeconds))
+        let reused = recorder.series(windowSeconds: 1, now: lapLater)
+        XCTAssertEqual(reused.buckets.count, 1)
+        XCTAssertEqual(reused.buckets[0].inboundBytes, 0)
+        XCTAssertTrue(reused.isEmpty)
+    }
+
+    /// Verifies ingest feeds throughput, DNS queries, and flow counts into the series and
+    /// worker-reported blocks land in the same buckets.
+    func testPipelineMaintainsSeriesFromIngest() async throws {
+        let startTime = Date(timeIntervalSince1970: 0)
+        let pipeline = PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: startTime),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let tcpPacket = Data(makeIPv4Packet(protocolNumber: 6, destinationPort: 443))
+        let dnsPacket = Data(makeIPv4Packet(protocolNumber: 17, destinationPort: 53))
+
+        _ = await pipeline.ingest(
+            packets: [tcpPacket, dnsPacket],
+            families: [],
+            direction: .outbound,
+            policy: makeEmissionPolicy()
+        )
+        await pipeline.noteBlockedFlows(2)
+
+        let series = await pipeline.telemetrySeries(windowSeconds: 1)
+        XCTAssertEqual(series.buckets.count, 1)
+        let bucket = try XCTUnwrap(series.buckets.first)
+        XCTAssertEqual(bucket.outboundBytes, tcpPacket.count + dnsPacket.count)
+        XCTAssertEqual(bucket.inboundBytes, 0)
+        XCTAssertEqual(bucket.dnsQueryCount, 1)
+        XCTAssertEqual(bucket.blockedFlowCount, 2)
+        XCTAssertGreaterThan(bucket.activeFlowCount, 0)
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+    }
+
+    /// IPv4 packet with a minimal TCP or UDP header to the given destination port.
+    private func makeIPv4Packet(protocolNumber: UInt8, destinationPort: UInt16) -> [UInt8] {
+        let transportLength = protocolNumber == 6 ? 20 : 8
+        var packet = [UInt8](repeating: 0, count: 20 + transportLength + 4)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = protocolNumber
+        packet[12..<16] = [10, 0, 0, 2][0...]
+        packet[16..<20] = [1, 1, 1, 1][0...]
+        packet[20] = 0xC3
+        packet[21] = 0x50
+        packet[22] = UInt8(destinationPort >> 8)
+        packet[23] = UInt8(destinationPort & 0xff)
+        if protocolNumber == 6 {
+            packet[32] = 0x50
+            packet[33] = 0x18
+        } else {
+            packet[24] = 0
+            packet[25] = UInt8(transportLength + 4)
+        }
+        return packet
+    }
+}
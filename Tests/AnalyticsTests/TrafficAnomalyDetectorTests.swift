@@ -0,0 +1,242 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// Built-in anomaly heuristic threshold and cooldown tests.
+final class TrafficAnomalyDetectorTests: XCTestCase {
+    /// Verifies the DNS flood detector fires once per window and honors its cooldown.
+    func testDNSQueryFloodFiresOnceAboveThreshold() {
+        let detector = DNSQueryFloodDetector(
+            policy: DNSQueryFloodDetector.Policy(windowSeconds: 10, queryThreshold: 10, cooldownSeconds: 60)
+        )
+        let base = Date(timeIntervalSince1970: 1_000)
+        let records = (0..<20).map { index in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(Double(index) * 0.1),
+                flowHash: UInt64(index),
+                destinationPort: 53,
+                dnsQueryName: "host\(index).example.com"
+            )
+        }
+
+        let events = detector.ingest(DetectorRecordCollection(records))
+        XCTAssertEqual(events.count, 1)
+        XCTAssertEqual(events.first?.detectorIdentifier, "dns-query-flood")
+        XCTAssertEqual(events.first?.signal, "query-flood")
+    }
+
+    /// Verifies sparse DNS traffic below the threshold never fires.
+    func testDNSQueryFloodIgnoresSparseQueries() {
+        let detector = DNSQueryFloodDetector(
+            policy: DNSQueryFloodDetector.Policy(windowSeconds: 5, queryThreshold: 10, cooldownSeconds: 60)
+        )
+        let base = Date(timeIntervalSince1970: 1_000)
+        let records = (0..<20).map { index in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(Double(index) * 2),
+                flowHash: UInt64(index),
+                destinationPort: 53,
+                dnsQueryName: "host.example.com"
+            )
+        }
+
+        XCTAssertTrue(detector.ingest(DetectorRecordCollection(records)).isEmpty)
+    }
+
+    /// Verifies the connection-rate detector keys spikes per host.
+    func testConnectionRateSpikeIsPerHost() {
+        let detector = ConnectionRateSpikeDetector(
+            policy: ConnectionRateSpikeDetector.Policy(windowSeconds: 10, openThreshold: 5, cooldownSeconds: 60)
+        )
+        let base = Date(timeIntervalSince1970: 2_000)
+        var records: [PacketSampleStream.PacketStreamRecord] = []
+        for index in 0..<6 {
+            records.append(
+                makeRecord(
+                    kind: .flowOpen,
+                    timestamp: base.addingTimeInterval(Double(index) * 0.5),
+                    flowHash: UInt64(index),
+                    tlsServerName: "burst.example.com"
+                )
+            )
+            records.append(
+                makeRecord(
+                    kind: .flowOpen,
+                    timestamp: base.addingTimeInterval(Double(index) * 0.5),
+                    flowHash: UInt64(100 + index),
+                    tlsServerName: "quiet\(index).example.net"
+                )
+            )
+        }
+
+        let events = detector.ingest(DetectorRecordCollection(records))
+        XCTAssertEqual(events.count, 1)
+        XCTAssertEqual(events.first?.target, "burst.example.com")
+    }
+
+    /// Verifies fan-out across many destination ports from one source raises a scan event
+    /// that names the scanning source.
+    func testPortScanFanOutFlagsScanningSource() {
+        let detector = PortScanFanOutDetector(
+            policy: PortScanFanOutDetector.Policy(windowSeconds: 10, distinctDestinationThreshold: 10, cooldownSeconds: 60)
+        )
+        let base = Date(timeIntervalSince1970: 5_000)
+        let records = (0..<12).map { index in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(Double(index) * 0.2),
+                flowHash: UInt64(index),
+                destinationPort: UInt16(1_000 + index),
+                sourceAddress: "10.0.0.9",
+                destinationAddress: "192.0.2.10"
+            )
+        }
+
+        let events = detector.ingest(DetectorRecordCollection(records))
+        XCTAssertEqual(events.count, 1)
+        XCTAssertEqual(events.first?.detectorIdentifier, "port-scan-fan-out")
+        XCTAssertEqual(events.first?.target, "10.0.0.9")
+    }
+
+    /// Verifies repeated opens toward one destination never look like a scan.
+    func testPortScanFanOutIgnoresRepeatedDestination() {
+        let detector = PortScanFanOutDetector(
+            policy: PortScanFanOutDetector.Policy(windowSeconds: 10, distinctDestinationThreshold: 10, cooldownSeconds: 60)
+        )
+        let base = Date(timeIntervalSince1970: 6_000)
+        let records = (0..<40).map { index in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(Double(index) * 0.1),
+                flowHash: UInt64(index),
+                sourceAddress: "10.0.0.9",
+                destinationAddress: "192.0.2.10"
+            )
+        }
+
+        XCTAssertTrue(detector.ingest(DetectorRecordCollection(records)).isEmpty)
+    }
+
+    /// Verifies regular flow-open cadence toward one host raises a beaconing event.
+    func testBeaconingDetectorFlagsRegularCadence() {
+        let detector = BeaconingIntervalDetector(
+            policy: BeaconingIntervalDetector.Policy(
+                minimumIntervalSeconds: 5,
+                maximumIntervalSeconds: 600,
+                minimumObservations: 4,
+                jitterTolerance: 0.15,
+                cooldownSeconds: 600
+            )
+        )
+        let base = Date(timeIntervalSince1970: 3_000)
+        let records = (0..<6).map { index in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(Double(index) * 30),
+                flowHash: UInt64(index),
+                tlsServerName: "c2.example.org"
+            )
+        }
+
+        let events = detector.ingest(DetectorRecordCollection(records))
+        XCTAssertEqual(events.count, 1)
+        XCTAssertEqual(events.first?.detectorIdentifier, "beaconing-interval")
+        XCTAssertEqual(events.first?.target, "c2.example.org")
+        XCTAssertEqual(events.first?.durationMs, 30_000)
+    }
+
+    /// Verifies irregular cadence toward one host stays quiet.
+    func testBeaconingDetectorIgnoresJitteryCadence() {
+        let detector = BeaconingIntervalDetector(
+            policy: BeaconingIntervalDetector.Policy(
+                minimumIntervalSeconds: 5,
+                maximumIntervalSeconds: 600,
+                minimumObservations: 4,
+                jitterTolerance: 0.1,
+                cooldownSeconds: 600
+            )
+        )
+        let base = Date(timeIntervalSince1970: 4_000)
+        let offsets: [TimeInterval] = [0, 12, 80, 95, 240, 251]
+        let records = offsets.enumerated().map { index, offset in
+            makeRecord(
+                kind: .flowOpen,
+                timestamp: base.addingTimeInterval(offset),
+                flowHash: UInt64(index),
+                tlsServerName: "browse.example.com"
+            )
+        }
+
+        XCTAssertTrue(detector.ingest(DetectorRecordCollection(records)).isEmpty)
+    }
+
+    private func makeRecord(
+        kind: PacketSampleKind,
+        timestamp: Date,
+        flowHash: UInt64,
+        destinationPort: UInt16 = 443,
+        dnsQueryName: String? = nil,
+        tlsServerName: String? = nil,
+        sourceAddress: String? = nil,
+        destinationAddress: String? = nil
+    ) -> PacketSampleStream.PacketStreamRecord {
+        PacketSampleStream.PacketStreamRecord(
+            kind: kind,
+            timestamp: timestamp,
+            direction: PacketDirection.outbound.rawValue,
+            bytes: 120,
+            packetCount: 1,
+            flowPacketCount: 1,
+            flowByteCount: 120,
+            protocolHint: dnsQueryName == nil ? "tcp" : "udp",
+            ipVersion: 4,
+            transportProtocolNumber: dnsQueryName == nil ? 6 : 17,
+            sourcePort: 50_000,
+            destinationPort: destinationPort,
+            flowHash: flowHash,
+            textFlowId: nil,
+            sourceAddressLength: nil,
+            sourceAddressHigh: nil,
+            sourceAddressLow: nil,
+            destinationAddressLength: nil,
+            destinationAddressHigh: nil,
+            destinationAddressLow: nil,
+            textSourceAddress: sourceAddress,
+            textDestinationAddress: destinationAddress,
+            registrableDomain: nil,
+            dnsQueryName: dnsQueryName,
+            dnsCname: nil,
+            dnsAnswerAddresses: nil,
+            tlsServerName: tlsServerName,
+            quicVersion: nil,
+            quicPacketType: nil,
+            quicDestinationConnectionId: nil,
+            quicSourceConnectionId: nil,
+            classification: nil,
+            closeReason: nil,
+            largePacketCount: nil,
+            smallPacketCount: nil,
+            udpPacketCount: nil,
+            tcpPacketCount: nil,
+            quicInitialCount: nil,
+            tcpSynCount: nil,
+            tcpFinCount: nil,
+            tcpRstCount: nil,
+            burstDurationMs: nil,
+            burstPacketCount: nil,
+            leadingBytes200ms: nil,
+            leadingPackets200ms: nil,
+            leadingBytes600ms: nil,
+            leadingPackets600ms: nil,
+            burstLargePacketCount: nil,
+            burstUdpPacketCount: nil,
+            burstTcpPacketCount: nil,
+            burstQuicInitialCount: nil
+        )
+    }
+}
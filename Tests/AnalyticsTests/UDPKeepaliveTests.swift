@@ -0,0 +1,65 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import XCTest
+
+/// UDP NAT keepalive classification tests.
+final class UDPKeepaliveTests: XCTestCase {
+    /// Verifies zero-length UDP datagrams classify as keepalives.
+    func testZeroLengthDatagramIsKeepalive() throws {
+        let packet = Data(makeIPv4UDPPacket(sourcePort: 50_000, destinationPort: 3_478, payload: []))
+        let summary = try XCTUnwrap(FastPacketSummary(data: packet, ipVersionHint: nil))
+        XCTAssertTrue(PacketAnalyticsPipeline.isUDPNATKeepalive(summary: summary, packet: packet))
+    }
+
+    /// Verifies STUN binding indications classify as keepalives.
+    func testStunBindingIndicationIsKeepalive() throws {
+        var stun: [UInt8] = [0x00, 0x11, 0x00, 0x00, 0x21, 0x12, 0xa4, 0x42]
+        stun.append(contentsOf: [UInt8](repeating: 0, count: 12))
+        let packet = Data(makeIPv4UDPPacket(sourcePort: 50_000, destinationPort: 3_478, payload: stun))
+        let summary = try XCTUnwrap(FastPacketSummary(data: packet, ipVersionHint: nil))
+        XCTAssertTrue(PacketAnalyticsPipeline.isUDPNATKeepalive(summary: summary, packet: packet))
+    }
+
+    /// Verifies ordinary UDP payloads are never tagged as keepalives.
+    func testPayloadDatagramsAreNotKeepalives() throws {
+        let dnsLike = [UInt8](repeating: 0xab, count: 32)
+        let packet = Data(makeIPv4UDPPacket(sourcePort: 50_000, destinationPort: 53, payload: dnsLike))
+        let summary = try XCTUnwrap(FastPacketSummary(data: packet, ipVersionHint: nil))
+        XCTAssertFalse(PacketAnalyticsPipeline.isUDPNATKeepalive(summary: summary, packet: packet))
+
+        let shortPayload = Data(makeIPv4UDPPacket(sourcePort: 50_000, destinationPort: 4_500, payload: [0xff]))
+        let shortSummary = try XCTUnwrap(FastPacketSummary(data: shortPayload, ipVersionHint: nil))
+        XCTAssertFalse(PacketAnalyticsPipeline.isUDPNATKeepalive(summary: shortSummary, packet: shortPayload))
+    }
+
+    private func makeIPv4UDPPacket(
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 8 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 17
+        packet[12..<16] = [10, 0, 0, 2][0..<4]
+        packet[16..<20] = [203, 0, 113, 9][0..<4]
+
+        let udpOffset = 20
+        let udpLength = 8 + payload.count
+        packet[udpOffset] = UInt8(sourcePort >> 8)
+        packet[udpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[udpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[udpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[udpOffset + 4] = UInt8(udpLength >> 8)
+        packet[udpOffset + 5] = UInt8(udpLength & 0xff)
+        if !payload.isEmpty {
+            packet[(udpOffset + 8)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,128 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// Hourly per-category usage accounting tests.
+final class UsageAccountingTests: XCTestCase {
+    /// Verifies bytes accumulate into hour-by-category buckets with per-direction totals.
+    func testAccountantBucketsBytesPerHourAndCategory() {
+        var accountant = UsageAccountant()
+        let hourStart = Date(timeIntervalSince1970: 3_600)
+
+        accountant.record(category: "tls", direction: .outbound, byteCount: 100, now: hourStart.addingTimeInterval(10))
+        accountant.record(category: "tls", direction: .inbound, byteCount: 400, now: hourStart.addingTimeInterval(20))
+        accountant.record(category: "dns", direction: .outbound, byteCount: 60, now: hourStart.addingTimeInterval(30))
+        accountant.record(category: "tls", direction: .outbound, byteCount: 50, now: hourStart.addingTimeInterval(3_700))
+
+        let report = accountant.report()
+        XCTAssertEqual(report.buckets.count, 3)
+        XCTAssertEqual(report.buckets[0].hourStart, hourStart)
+        XCTAssertEqual(report.buckets[0].category, "dns")
+        XCTAssertEqual(report.buckets[0].outboundBytes, 60)
+        XCTAssertEqual(report.buckets[1].category, "tls")
+        XCTAssertEqual(report.buckets[1].outboundBytes, 100)
+        XCTAssertEqual(report.buckets[1].inboundBytes, 400)
+        XCTAssertEqual(report.buckets[1].packetCount, 2)
+        XCTAssertEqual(report.buckets[2].hourStart, hourStart.addingTimeInterval(3_600))
+        XCTAssertEqual(report.buckets[2].outboundBytes, 50)
+
+        accountant.reset()
+        XCTAssertTrue(accountant.report().isEmpty)
+    }
+
+    /// Verifies the bucket cap drops whole oldest hours instead of recent cells.
+    func testAccountantEvictsOldestHourAtCapacity() {
+        var accountant = UsageAccountant()
+        let base = Date(timeIntervalSince1970: 0)
+
+        for index in 0..<UsageAccountant.maxTrackedBuckets {
+            accountant.record(category: "cat-\(index)", direction: .outbound, byteCount: 1, now: base)
+        }
+        accountant.record(category: "fresh", direction: .outbound, byteCount: 1, now: base.addingTimeInterval(3_600))
+
+        let report = accountant.report()
+        XCTAssertEqual(report.buckets.count, 1)
+        XCTAssertEqual(report.buckets[0].category, "fresh")
+    }
+
+    /// Verifies the pipeline attributes ingested packet bytes to a category bucket.
+    func testPipelineAccumulatesUsageForIngestedPackets() async throws {
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 7_200))
+        let pipeline = PacketAnalyticsPipeline(
+            clock: clock,
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+        let policy = PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false
+        )
+
+        let packet = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 5],
+                sourcePort: 50_000,
+                destinationPort: 443,
+                tcpFlags: 0x18,
+                payload: [0x17, 0x03, 0x03, 0x00, 0x01]
+            )
+        )
+        _ = await pipeline.ingest(packets: [packet], families: [], direction: .outbound, policy: policy)
+
+        let report = await pipeline.usageAccountingReport()
+        XCTAssertEqual(report.buckets.count, 1)
+        XCTAssertEqual(report.buckets[0].category, FlowProtocolClass.tls.rawValue)
+        XCTAssertEqual(report.buckets[0].hourStart, Date(timeIntervalSince1970: 7_200))
+        XCTAssertEqual(report.buckets[0].outboundBytes, packet.count)
+        XCTAssertEqual(report.buckets[0].packetCount, 1)
+
+        await pipeline.resetUsageAccounting()
+        let cleared = await pipeline.usageAccountingReport()
+        XCTAssertTrue(cleared.isEmpty)
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
@@ -0,0 +1,155 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import Analytics
+import Observability
+import TunnelRuntime
+import XCTest
+
+/// WebSocket upgrade tests: 101 detection and protocol-class tagging of upgraded flows.
+final class WebSocketTaggingTests: XCTestCase {
+    /// Verifies a 101 response parses with its Upgrade header value attached.
+    func testParserAttachesUpgradeHeaderTo101() {
+        let payload = Data("HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n".utf8)
+
+        XCTAssertEqual(
+            HTTPMetadataExtractor.parse(payload: payload),
+            .response(version: "1.1", statusCode: 101, upgrade: "websocket")
+        )
+    }
+
+    /// Verifies traffic after a completed websocket upgrade accounts under the websocket
+    /// protocol class instead of the port-derived one.
+    func testUpgradedFlowTagsSubsequentTrafficAsWebSocket() async {
+        let pipeline = makePipeline()
+        let policy = makeEmissionPolicy()
+
+        let upgradeRequest = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_000,
+                destinationPort: 80,
+                tcpFlags: 0x18,
+                payload: Array("GET /socket HTTP/1.1\r\nHost: push.example.com\r\nUpgrade: websocket\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [upgradeRequest], families: [], direction: .outbound, policy: policy)
+
+        let upgradeResponse = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [203, 0, 113, 9],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 80,
+                destinationPort: 50_000,
+                tcpFlags: 0x18,
+                payload: Array("HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [upgradeResponse], families: [], direction: .inbound, policy: policy)
+
+        let pushFrame = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [203, 0, 113, 9],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 80,
+                destinationPort: 50_000,
+                tcpFlags: 0x18,
+                payload: [0x81, 0x02, 0x68, 0x69]
+            )
+        )
+        _ = await pipeline.ingest(packets: [pushFrame], families: [], direction: .inbound, policy: policy)
+
+        let report = await pipeline.usageAccountingReport()
+        XCTAssertTrue(report.buckets.map(\.category).contains(FlowProtocolClass.websocket.rawValue))
+    }
+
+    /// Verifies a 101 upgrading to something other than websocket leaves the flow untagged.
+    func testNonWebSocketUpgradeDoesNotTagFlow() async {
+        let pipeline = makePipeline()
+        let policy = makeEmissionPolicy()
+
+        let request = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [10, 0, 0, 2],
+                destinationAddress: [203, 0, 113, 9],
+                sourcePort: 50_001,
+                destinationPort: 80,
+                tcpFlags: 0x18,
+                payload: Array("GET / HTTP/1.1\r\nHost: example.com\r\nUpgrade: h2c\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [request], families: [], direction: .outbound, policy: policy)
+
+        let response = Data(
+            makeIPv4TCPPacket(
+                sourceAddress: [203, 0, 113, 9],
+                destinationAddress: [10, 0, 0, 2],
+                sourcePort: 80,
+                destinationPort: 50_001,
+                tcpFlags: 0x18,
+                payload: Array("HTTP/1.1 101 Switching Protocols\r\nUpgrade: h2c\r\n\r\n".utf8)
+            )
+        )
+        _ = await pipeline.ingest(packets: [response], families: [], direction: .inbound, policy: policy)
+
+        let report = await pipeline.usageAccountingReport()
+        XCTAssertFalse(report.buckets.map(\.category).contains(FlowProtocolClass.websocket.rawValue))
+    }
+
+    private func makePipeline() -> PacketAnalyticsPipeline {
+        PacketAnalyticsPipeline(
+            clock: DeterministicClock(startTime: Date(timeIntervalSince1970: 0)),
+            burstTracker: BurstTracker(thresholdMs: 350),
+            signatureClassifier: SignatureClassifier(logger: StructuredLogger(sink: InMemoryLogSink()))
+        )
+    }
+
+    private func makeEmissionPolicy() -> PacketAnalyticsPipeline.EmissionPolicy {
+        PacketAnalyticsPipeline.EmissionPolicy(
+            allowDeepMetadata: false,
+            maxMetadataProbesPerBatch: 0,
+            emitFlowSlices: false,
+            flowSliceIntervalMs: 250,
+            emitFlowCloseEvents: false,
+            emitBurstShapeCounters: false,
+            activitySampleMinimumPackets: 1_000,
+            activitySampleMinimumBytes: 1_000_000,
+            activitySampleMinimumInterval: 600,
+            emitBurstEvents: false,
+            emitActivitySamples: false,
+            emitHTTPMetadata: true
+        )
+    }
+
+    private func makeIPv4TCPPacket(
+        sourceAddress: [UInt8],
+        destinationAddress: [UInt8],
+        sourcePort: UInt16,
+        destinationPort: UInt16,
+        tcpFlags: UInt8,
+        payload: [UInt8]
+    ) -> [UInt8] {
+        var packet = [UInt8](repeating: 0, count: 20 + 20 + payload.count)
+        packet[0] = 0x45
+        packet[2] = UInt8(packet.count >> 8)
+        packet[3] = UInt8(packet.count & 0xff)
+        packet[8] = 64
+        packet[9] = 6
+        packet[12..<16] = sourceAddress[0..<4]
+        packet[16..<20] = destinationAddress[0..<4]
+
+        let tcpOffset = 20
+        packet[tcpOffset] = UInt8(sourcePort >> 8)
+        packet[tcpOffset + 1] = UInt8(sourcePort & 0xff)
+        packet[tcpOffset + 2] = UInt8(destinationPort >> 8)
+        packet[tcpOffset + 3] = UInt8(destinationPort & 0xff)
+        packet[tcpOffset + 12] = 0x50
+        packet[tcpOffset + 13] = tcpFlags
+        if !payload.isEmpty {
+            packet[(tcpOffset + 20)...] = payload[0...]
+        }
+        return packet
+    }
+}
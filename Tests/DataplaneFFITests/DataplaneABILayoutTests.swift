@@ -0,0 +1,81 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import DataplaneFFICBridge
+import XCTest
+
+// Docs: https://developer.apple.com/documentation/xctest/xctestcase
+/// Layout tests for the structs crossing the C ABI. The C side pins the same
+/// numbers with static_asserts in rp_dataplane_layout_checks.c; this file
+/// verifies the header Swift actually imported agrees, so a stale or divergent
+/// copy of rp_dataplane.h can never silently shift fields between the sides.
+final class DataplaneABILayoutTests: XCTestCase {
+    /// Verifies the fixed-width counter structs keep their promised byte layout.
+    func testCounterStructLayoutsMatchShippedHeader() {
+        XCTAssertEqual(MemoryLayout<rp_dp_version_t>.size, 4)
+        XCTAssertEqual(MemoryLayout<rp_dp_stats_t>.size, 40)
+        XCTAssertEqual(MemoryLayout<rp_dp_stats_t>.offset(of: \.epoch), 32)
+        XCTAssertEqual(MemoryLayout<rp_dp_lifecycle_info_t>.size, 56)
+        XCTAssertEqual(MemoryLayout<rp_dp_lifecycle_info_t>.offset(of: \.is_running), 40)
+        XCTAssertEqual(MemoryLayout<rp_dp_lifecycle_info_t>.offset(of: \.last_exit_code), 48)
+        XCTAssertEqual(MemoryLayout<rp_dp_debug_alloc_counters_t>.size, 64)
+        XCTAssertEqual(MemoryLayout<rp_dp_debug_alloc_counters_t>.offset(of: \.total_handles), 32)
+    }
+
+    /// Verifies the event record keeps the 128-byte stride shared rings rely on.
+    func testEventRecordKeepsSharedMemoryStride() {
+        XCTAssertEqual(MemoryLayout<rp_dp_event_record_t>.size, 128)
+        XCTAssertEqual(MemoryLayout<rp_dp_event_record_t>.stride, 128)
+        XCTAssertEqual(MemoryLayout<rp_dp_event_record_t>.offset(of: \.kind), 16)
+        XCTAssertEqual(MemoryLayout<rp_dp_event_record_t>.offset(of: \.wall_clock_ms), 24)
+        XCTAssertEqual(MemoryLayout<rp_dp_event_record_t>.offset(of: \.message), 32)
+    }
+
+    /// Verifies the metric entry's inline name buffer precedes its value at a fixed offset.
+    func testMetricEntryLayoutMatchesShippedHeader() {
+        XCTAssertEqual(MemoryLayout<rp_dp_metric_entry_t>.size, 56)
+        XCTAssertEqual(MemoryLayout<rp_dp_metric_entry_t>.offset(of: \.value), 48)
+    }
+
+    /// Verifies the runtime ABI handshake accepts the layouts this module compiled against.
+    func testAbiHandshakeAcceptsCompiledLayouts() {
+        let status = rp_dp_verify_abi(
+            UInt32(MemoryLayout<rp_dp_callbacks_v2_t>.size),
+            UInt32(MemoryLayout<rp_dp_event_record_t>.size),
+            UInt32(MemoryLayout<rp_dp_metric_entry_t>.size),
+            rp_dp_get_version()
+        )
+        XCTAssertEqual(status, RP_DP_OK)
+    }
+
+    /// Verifies the handshake refuses a host compiled against a drifted struct size.
+    func testAbiHandshakeRejectsDriftedEventRecordSize() {
+        let status = rp_dp_verify_abi(
+            UInt32(MemoryLayout<rp_dp_callbacks_v2_t>.size),
+            UInt32(MemoryLayout<rp_dp_event_record_t>.size) + 8,
+            UInt32(MemoryLayout<rp_dp_metric_entry_t>.size),
+            rp_dp_get_version()
+        )
+        XCTAssertEqual(status, RP_DP_ERR_ABI_MISMATCH)
+    }
+
+    /// Verifies the handshake refuses a host compiled against a different header version.
+    func testAbiHandshakeRejectsForeignHeaderVersion() {
+        let status = rp_dp_verify_abi(
+            UInt32(MemoryLayout<rp_dp_callbacks_v2_t>.size),
+            UInt32(MemoryLayout<rp_dp_event_record_t>.size),
+            UInt32(MemoryLayout<rp_dp_metric_entry_t>.size),
+            rp_dp_version_t(api_version: 99, abi_version: 99)
+        )
+        XCTAssertEqual(status, RP_DP_ERR_ABI_MISMATCH)
+    }
+
+    /// Verifies the callback tables stay pointer-packed with no hidden padding.
+    func testCallbackTablesStayPointerPacked() {
+        let pointer = MemoryLayout<UnsafeRawPointer>.size
+        XCTAssertEqual(MemoryLayout<rp_dp_callbacks_t>.size, 3 * pointer)
+        XCTAssertEqual(MemoryLayout<rp_dp_callbacks_v2_t>.size, 7 * pointer)
+        XCTAssertEqual(MemoryLayout<rp_dp_callbacks_v2_t>.offset(of: \.free_ctx), 6 * pointer)
+    }
+}
@@ -43,6 +43,369 @@ final class DataplaneFFITests: XCTestCase {
         try await handle.stop()
         await handle.destroy()
     }
+
+    /// Verifies resets zero the counters and every start or reset opens a new stats epoch.
+    func testStatsEpochAdvancesAcrossResetAndRestart() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        try await handle.start(tunFD: 0)
+        let first = try await handle.stats()
+        XCTAssertEqual(first.epoch, 1)
+
+        try await handle.resetStats()
+        let afterReset = try await handle.stats()
+        XCTAssertEqual(afterReset.epoch, 2)
+        XCTAssertEqual(afterReset.bytesIn, 0)
+        XCTAssertEqual(afterReset.bytesOut, 0)
+
+        try await handle.stop()
+        try await handle.start(tunFD: 0)
+        let afterRestart = try await handle.stats()
+        XCTAssertEqual(afterRestart.epoch, 3)
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies lifecycle counters track start/stop cycles and the last stop reason.
+    func testLifecycleInfoTracksStartStopCycle() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+
+        let created = try await handle.lifecycleInfo()
+        XCTAssertEqual(created.startCount, 0)
+        XCTAssertFalse(created.isRunning)
+        XCTAssertEqual(created.lastStopReason, .none)
+
+        try await handle.start(tunFD: 0)
+        let running = try await handle.lifecycleInfo()
+        XCTAssertEqual(running.startCount, 1)
+        XCTAssertTrue(running.isRunning)
+        XCTAssertNotEqual(running.startedAtMs, 0)
+
+        try await handle.stop()
+        let stopped = try await handle.lifecycleInfo()
+        XCTAssertEqual(stopped.stopCount, 1)
+        XCTAssertFalse(stopped.isRunning)
+        XCTAssertEqual(stopped.uptimeMs, 0)
+        XCTAssertEqual(stopped.lastStopReason, .hostStop)
+        await handle.destroy()
+    }
+
+    /// Verifies the generic metrics query reports each category by name under schema version 1.
+    func testMetricsQueryReportsNamedCountersPerCategory() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        try await handle.start(tunFD: 0)
+
+        let traffic = try await handle.queryMetrics(category: .traffic)
+        XCTAssertEqual(traffic.schemaVersion, 1)
+        XCTAssertEqual(traffic["packets_in"], 0)
+        XCTAssertEqual(traffic["bytes_out"], 0)
+        XCTAssertEqual(traffic["epoch"], 1)
+
+        let lifecycle = try await handle.queryMetrics(category: .lifecycle)
+        XCTAssertEqual(lifecycle.schemaVersion, 1)
+        XCTAssertEqual(lifecycle["start_count"], 1)
+        XCTAssertEqual(lifecycle["is_running"], 1)
+
+        let allocations = try await handle.queryMetrics(category: .allocations)
+        XCTAssertEqual(allocations.schemaVersion, 1)
+        XCTAssertNotNil(allocations["live_handles"])
+        XCTAssertNotNil(allocations["total_callback_tasks"])
+
+        let stack = try await handle.queryMetrics(category: .stack)
+        XCTAssertEqual(stack.schemaVersion, 1)
+        XCTAssertEqual(stack["rx_drops"], 0)
+        XCTAssertEqual(stack["rx_errors"], 0)
+        XCTAssertEqual(stack["unaddressable"], 0)
+        XCTAssertEqual(stack["memory_failures"], 0)
+
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies metrics name/value pairs agree with the dedicated stats accessor.
+    func testMetricsQueryMatchesStatsSnapshot() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        try await handle.start(tunFD: 0)
+
+        let stats = try await handle.stats()
+        let traffic = try await handle.queryMetrics(category: .traffic)
+        XCTAssertEqual(traffic["packets_in"], stats.packetsIn)
+        XCTAssertEqual(traffic["packets_out"], stats.packetsOut)
+        XCTAssertEqual(traffic["bytes_in"], stats.bytesIn)
+        XCTAssertEqual(traffic["bytes_out"], stats.bytesOut)
+        XCTAssertEqual(traffic["epoch"], stats.epoch)
+
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies path-change notifications reach a running engine and land in the event ring.
+    func testPathChangeNotificationPublishesEvent() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        let ring = DataplaneEventRing(capacity: 8)
+        try await handle.registerEventRing(ring)
+        try await handle.start(tunFD: 0)
+
+        try await handle.notifyPathChanged(
+            DataplanePathInfo(interfaceKind: .cellular, isExpensive: true)
+        )
+
+        let events = ring.snapshot()
+        XCTAssertTrue(events.contains { $0.kind == .log && $0.message == "dataplane-path-changed interface=2 flags=1" })
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies lifecycle events land in a registered ring with monotonic sequences.
+    func testEventRingReceivesLifecycleRecords() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        let ring = DataplaneEventRing(capacity: 8)
+        try await handle.registerEventRing(ring)
+
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+
+        let events = ring.snapshot()
+        XCTAssertFalse(events.isEmpty)
+        XCTAssertEqual(events.map(\.sequence), events.map(\.sequence).sorted())
+        XCTAssertTrue(events.contains { $0.kind == .state && $0.state == .running })
+        XCTAssertTrue(events.contains { $0.kind == .log && !$0.message.isEmpty })
+        let latest = try await handle.eventRingSequence()
+        XCTAssertEqual(latest, events.last?.sequence)
+
+        try await handle.unregisterEventRing()
+        let afterUnregister = try await handle.eventRingSequence()
+        XCTAssertEqual(afterUnregister, 0)
+        await handle.destroy()
+    }
+
+    /// Verifies every successful start opens an epoch marker carrying the start count,
+    /// and that ring records carry both monotonic and wall-clock timestamps.
+    func testEventRingPublishesEpochMarkerPerStart() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        let ring = DataplaneEventRing(capacity: 32)
+        try await handle.registerEventRing(ring)
+
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+
+        let markers = ring.snapshot().filter { $0.kind == .epoch }
+        XCTAssertEqual(markers.count, 2)
+        XCTAssertEqual(markers.first?.message, "engine-epoch start_count=1")
+        XCTAssertEqual(markers.last?.message, "engine-epoch start_count=2")
+        for marker in markers {
+            XCTAssertGreaterThan(marker.wallClockMilliseconds, 0)
+            XCTAssertGreaterThan(marker.timestampMilliseconds, 0)
+        }
+
+        let firstRun = ring.snapshot().first { $0.kind == .epoch }
+        let runningRecord = ring.snapshot().first { $0.kind == .state && $0.state == .running }
+        if let firstRun, let runningRecord {
+            XCTAssertLessThan(firstRun.sequence, runningRecord.sequence)
+        }
+        await handle.destroy()
+    }
+
+    /// Verifies a partitioned registration offsets every published sequence by the
+    /// configured base so records merged from several engines never collide.
+    func testPartitionedEventRingOffsetsSequences() async throws {
+        let base: UInt64 = 7 << 48
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        let ring = DataplaneEventRing(capacity: 32)
+        try await handle.registerEventRing(ring, sequenceBase: base)
+
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+
+        let events = ring.snapshot()
+        XCTAssertFalse(events.isEmpty)
+        XCTAssertEqual(events.first?.sequence, base + 1)
+        XCTAssertTrue(events.allSatisfy { $0.sequence > base })
+        let latest = try await handle.eventRingSequence()
+        XCTAssertEqual(latest, events.last?.sequence)
+        await handle.destroy()
+    }
+
+    /// Verifies the explicit lifecycle state machine walks created -> running -> stopped
+    /// and supports restart from the stopped state.
+    func testLifecycleStateMachineAcrossRestart() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+
+        var state = try await handle.state()
+        XCTAssertEqual(state, .created)
+
+        try await handle.start(tunFD: 0)
+        state = try await handle.state()
+        XCTAssertEqual(state, .running)
+
+        try await handle.stop()
+        state = try await handle.state()
+        XCTAssertEqual(state, .stopped)
+
+        try await handle.start(tunFD: 0)
+        state = try await handle.state()
+        XCTAssertEqual(state, .running)
+
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies a stopped handle accepts replacement callbacks for its next run and a
+    /// running handle refuses them.
+    func testCallbackReplacementRequiresStoppedEngine() async throws {
+        final class RecordingObserver: DataplaneObserver, @unchecked Sendable {
+            private let lock = NSLock()
+            private var storedStates: [DataplaneState] = []
+
+            func dataplaneStateDidChange(to state: DataplaneState) {
+                lock.lock()
+                storedStates.append(state)
+                lock.unlock()
+            }
+
+            var states: [DataplaneState] {
+                lock.lock()
+                defer { lock.unlock() }
+                return storedStates
+            }
+        }
+
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        try await handle.start(tunFD: 0)
+
+        let replacement = RecordingObserver()
+        await XCTAssertThrowsErrorAsync(try await handle.updateObserver(replacement))
+
+        try await handle.stop()
+        try await handle.updateObserver(replacement)
+        try await handle.start(tunFD: 0)
+
+        // Callbacks are delivered from the bridge's queue thread, so poll briefly.
+        let deadline = Date().addingTimeInterval(2)
+        while !replacement.states.contains(.running), Date() < deadline {
+            try await Task.sleep(nanoseconds: 10_000_000)
+        }
+        XCTAssertTrue(replacement.states.contains(.running))
+        try await handle.stop()
+        await handle.destroy()
+    }
+
+    /// Verifies a protocol-based observer receives lifecycle transitions without any
+    /// closure or C callback wiring on the host side.
+    func testObserverReceivesLifecycleStateTransitions() async throws {
+        final class RecordingObserver: DataplaneObserver, @unchecked Sendable {
+            private let lock = NSLock()
+            private var storedStates: [DataplaneState] = []
+
+            func dataplaneStateDidChange(to state: DataplaneState) {
+                lock.lock()
+                storedStates.append(state)
+                lock.unlock()
+            }
+
+            var states: [DataplaneState] {
+                lock.lock()
+                defer { lock.unlock() }
+                return storedStates
+            }
+        }
+
+        let observer = RecordingObserver()
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, observer: observer, logger: logger)
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+
+        // Callbacks are delivered from the bridge's queue thread, so poll briefly.
+        let deadline = Date().addingTimeInterval(2)
+        while !observer.states.contains(.stopped), Date() < deadline {
+            try await Task.sleep(nanoseconds: 10_000_000)
+        }
+        XCTAssertTrue(observer.states.contains(.running))
+        XCTAssertTrue(observer.states.contains(.stopped))
+        await handle.destroy()
+    }
+
+    /// Verifies the bridge's context destructor releases the retained observer when
+    /// the handle is destroyed, so hosts never juggle callback lifetimes manually.
+    func testDestroyReleasesRetainedObserver() async throws {
+        final class TrackedObserver: DataplaneObserver, @unchecked Sendable {
+            func dataplaneStateDidChange(to state: DataplaneState) {}
+        }
+
+        weak var weakObserver: TrackedObserver?
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let handle: DataplaneHandle
+        do {
+            let observer = TrackedObserver()
+            weakObserver = observer
+            handle = try DataplaneHandle(
+                configJSON: deterministicLocalConfig,
+                observer: observer,
+                logger: logger
+            )
+        }
+        XCTAssertNotNil(weakObserver)
+
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+        await handle.destroy()
+
+        // Destroy drains the delivery queue and then frees every callback
+        // context synchronously, dropping the last retain on the observer.
+        XCTAssertNil(weakObserver)
+    }
+
+    /// Verifies repeated create/destroy cycles return the bridge's allocation count
+    /// to its starting baseline, catching teardown leaks deterministically instead
+    /// of sampling process RSS.
+    func testRepeatedCreateDestroyReturnsAllocationsToBaseline() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let baseline = DataplaneHandle.debugLiveAllocations()
+
+        for _ in 0..<1000 {
+            let handle = try DataplaneHandle(
+                configJSON: deterministicLocalConfig,
+                callbacks: .noop,
+                logger: logger
+            )
+            await handle.destroy()
+        }
+
+        XCTAssertEqual(DataplaneHandle.debugLiveAllocations(), baseline)
+    }
+
+    /// Verifies the per-subsystem allocation counters attribute work to the path that
+    /// caused it: a handle lifecycle allocates one handle and one config copy, callback
+    /// delivery accounts for every task, and a callback swap retires one context.
+    func testAllocationCountersAttributePerSubsystem() async throws {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let baseline = DataplaneHandle.debugAllocationCounters()
+
+        let handle = try DataplaneHandle(configJSON: deterministicLocalConfig, callbacks: .noop, logger: logger)
+        try await handle.start(tunFD: 0)
+        try await handle.stop()
+        try await handle.updateCallbacks(.noop)
+        await handle.destroy()
+
+        let after = DataplaneHandle.debugAllocationCounters()
+        XCTAssertEqual(after.totalHandles, baseline.totalHandles + 1)
+        XCTAssertEqual(after.totalConfigCopies, baseline.totalConfigCopies + 1)
+        XCTAssertGreaterThan(after.totalCallbackTasks, baseline.totalCallbackTasks)
+        XCTAssertEqual(after.totalRetiredContexts, baseline.totalRetiredContexts + 1)
+        XCTAssertEqual(after.totalLive, baseline.totalLive)
+    }
 }
 
 private extension XCTestCase {
@@ -0,0 +1,110 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import DataplaneFFI
+import Observability
+import XCTest
+
+/// FFI trace record/replay tests: call capture, file round-trip, and deterministic replay.
+final class DataplaneTraceTests: XCTestCase {
+    private let deterministicLocalConfig = "{\"mode\":\"deterministic-local\"}"
+
+    /// Verifies the recorder captures every FFI call a session issues, in order,
+    /// with the inputs needed to re-issue them.
+    func testRecorderCapturesLifecycleCallsInOrder() async throws {
+        let recorder = DataplaneTraceRecorder()
+        let handle = try DataplaneHandle(
+            configJSON: deterministicLocalConfig,
+            callbacks: .noop,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            traceRecorder: recorder
+        )
+        try await handle.start(tunFD: 0)
+        try await handle.notifyPathChanged(
+            DataplanePathInfo(interfaceKind: .cellular, isExpensive: true)
+        )
+        try await handle.resetStats()
+        try await handle.stop()
+        await handle.destroy()
+
+        let entries = recorder.entries
+        XCTAssertEqual(
+            entries.map(\.kind),
+            [.create, .start, .pathChanged, .resetStats, .stop, .destroy]
+        )
+        XCTAssertEqual(entries[0].configJSON, deterministicLocalConfig)
+        XCTAssertEqual(entries[1].tunFD, 0)
+        XCTAssertEqual(entries[2].interfaceKind, DataplanePathInfo.InterfaceKind.cellular.rawValue)
+        XCTAssertEqual(entries[2].pathFlags, 1)
+        let timestamps = entries.map(\.timestampMs)
+        XCTAssertEqual(timestamps, timestamps.sorted(), "timestamps regressed within one trace")
+    }
+
+    /// Verifies a trace survives the JSON Lines file round-trip unchanged.
+    func testTraceRoundTripsThroughJSONLines() async throws {
+        let recorder = DataplaneTraceRecorder()
+        let handle = try DataplaneHandle(
+            configJSON: deterministicLocalConfig,
+            callbacks: .noop,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            traceRecorder: recorder
+        )
+        try await handle.start(tunFD: 0)
+        try await handle.registerEventRing(DataplaneEventRing(capacity: 16), sequenceBase: 1 << 48)
+        try await handle.stop()
+        await handle.destroy()
+
+        let url = FileManager.default.temporaryDirectory
+            .appendingPathComponent("DataplaneTraceTests-\(UUID().uuidString).jsonl")
+        defer { try? FileManager.default.removeItem(at: url) }
+        try recorder.writeJSONLines(to: url)
+
+        let loaded = try DataplaneTraceRecorder.load(from: url)
+        XCTAssertEqual(loaded, recorder.entries)
+        XCTAssertEqual(loaded[2].ringCapacity, 16)
+        XCTAssertEqual(loaded[2].sequenceBase, 1 << 48)
+    }
+
+    /// Verifies a recorded session replays deterministically against a fresh
+    /// engine with every call applied and none failing.
+    func testReplayerReappliesRecordedTrace() async throws {
+        let recorder = DataplaneTraceRecorder()
+        let handle = try DataplaneHandle(
+            configJSON: deterministicLocalConfig,
+            callbacks: .noop,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            traceRecorder: recorder
+        )
+        try await handle.start(tunFD: 0)
+        try await handle.resetStats()
+        try await handle.stop()
+        await handle.destroy()
+
+        let result = try await DataplaneTraceReplayer.replay(
+            entries: recorder.entries,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        XCTAssertEqual(result.appliedCalls, recorder.entries.count)
+        XCTAssertEqual(result.failedCallIndices, [])
+    }
+
+    /// Verifies replay reports failing calls by index instead of aborting, so a
+    /// trace that reproduces a field failure still plays to the end.
+    func testReplayerReportsFailingCallsWithoutAborting() async throws {
+        let entries = [
+            DataplaneTraceEntry(timestampMs: 0, kind: .create, configJSON: deterministicLocalConfig),
+            DataplaneTraceEntry(timestampMs: 1, kind: .start, tunFD: -1),
+            DataplaneTraceEntry(timestampMs: 2, kind: .start, tunFD: 0),
+            DataplaneTraceEntry(timestampMs: 3, kind: .stop),
+            DataplaneTraceEntry(timestampMs: 4, kind: .destroy)
+        ]
+
+        let result = try await DataplaneTraceReplayer.replay(
+            entries: entries,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        XCTAssertEqual(result.appliedCalls, entries.count)
+        XCTAssertEqual(result.failedCallIndices, [1])
+    }
+}
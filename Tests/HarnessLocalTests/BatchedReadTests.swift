@@ -0,0 +1,81 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import HarnessTunSupport
+import XCTest
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Batched packet-read shim tests over a datagram socketpair, which preserves packet
+/// boundaries the way the capture socket and TUN device do.
+final class BatchedReadTests: XCTestCase {
+    /// Verifies queued datagrams drain through the batched read with per-packet
+    /// lengths and slab offsets intact.
+    func testBatchedReadDrainsQueuedDatagrams() throws {
+#if os(Linux)
+        let datagramType = Int32(SOCK_DGRAM.rawValue)
+#else
+        let datagramType = SOCK_DGRAM
+#endif
+        var fds: [Int32] = [0, 0]
+        XCTAssertEqual(socketpair(AF_UNIX, datagramType, 0, &fds), 0)
+        defer {
+            _ = rp_harness_close_fd(fds[0])
+            _ = rp_harness_close_fd(fds[1])
+        }
+
+        let payloads: [[UInt8]] = [[0x01], [0x02, 0x02], [0x03, 0x03, 0x03]]
+        for payload in payloads {
+            var writeErrno: CInt = 0
+            XCTAssertEqual(
+                payload.withUnsafeBytes { rp_harness_write_fd(fds[0], $0.baseAddress, $0.count, &writeErrno) },
+                payload.count
+            )
+        }
+
+        let bufferBytes = 64
+        var slab = [UInt8](repeating: 0, count: bufferBytes * 8)
+        var lengths = [UInt32](repeating: 0, count: 8)
+        var received: [[UInt8]] = []
+        // One call batches on Linux; other platforms fall back to one packet per call.
+        while received.count < payloads.count {
+            var readErrno: CInt = 0
+            let packetsRead = slab.withUnsafeMutableBytes { slabBuffer in
+                lengths.withUnsafeMutableBufferPointer { lengthsBuffer in
+                    rp_harness_read_packets(
+                        fds[1],
+                        slabBuffer.baseAddress,
+                        bufferBytes,
+                        8,
+                        lengthsBuffer.baseAddress,
+                        &readErrno
+                    )
+                }
+            }
+            XCTAssertGreaterThan(packetsRead, 0, "read failed with errno \(readErrno)")
+            for packetIndex in 0 ..< Int(packetsRead) {
+                let start = packetIndex * bufferBytes
+                received.append(Array(slab[start ..< start + Int(lengths[packetIndex])]))
+            }
+        }
+
+        XCTAssertEqual(received, payloads)
+    }
+
+    /// Verifies invalid arguments are rejected with EINVAL instead of touching the fd.
+    func testBatchedReadRejectsInvalidArguments() {
+        var lengths = [UInt32](repeating: 0, count: 1)
+        var readErrno: CInt = 0
+        let result = lengths.withUnsafeMutableBufferPointer { lengthsBuffer in
+            rp_harness_read_packets(-1, nil, 64, 1, lengthsBuffer.baseAddress, &readErrno)
+        }
+        XCTAssertEqual(result, -1)
+        XCTAssertEqual(readErrno, EINVAL)
+    }
+}
@@ -0,0 +1,37 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import XCTest
+
+/// Self-benchmark tests: option validation and a short loopback load run.
+final class BenchmarkHarnessTests: XCTestCase {
+    /// Verifies a short loopback run moves traffic on both transports and reports
+    /// plausible metrics without external tooling.
+    func testShortLoopbackRunReportsThroughputAndCPU() throws {
+        let result = try BenchmarkHarness.run(
+            options: BenchmarkOptions(durationSeconds: 0.3)
+        )
+
+        XCTAssertGreaterThan(result.tcpBytesTransferred, 0)
+        XCTAssertGreaterThan(result.tcpMegabitsPerSecond, 0)
+        XCTAssertGreaterThan(result.udpDatagramsSent, 0)
+        XCTAssertLessThanOrEqual(result.udpDatagramsReceived, result.udpDatagramsSent)
+        XCTAssertGreaterThanOrEqual(result.cpuUserSeconds, 0)
+        XCTAssertGreaterThanOrEqual(result.cpuSystemSeconds, 0)
+    }
+
+    /// Verifies out-of-range options are rejected before any socket is opened.
+    func testInvalidOptionsAreRejected() {
+        XCTAssertThrowsError(
+            try BenchmarkHarness.run(options: BenchmarkOptions(durationSeconds: 0))
+        )
+        XCTAssertThrowsError(
+            try BenchmarkHarness.run(
+                options: BenchmarkOptions(durationSeconds: 0.1, udpDatagramBytes: 70_000)
+            )
+        )
+    }
+}
@@ -0,0 +1,97 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import XCTest
+
+/// Daemon-mode lifecycle tests: PID file acquisition and config-file overlay semantics.
+final class DaemonSupportTests: XCTestCase {
+    private var scratchDirectory: URL!
+
+    override func setUpWithError() throws {
+        scratchDirectory = FileManager.default.temporaryDirectory
+            .appendingPathComponent("DaemonSupportTests-\(UUID().uuidString)", isDirectory: true)
+        try FileManager.default.createDirectory(at: scratchDirectory, withIntermediateDirectories: true)
+    }
+
+    override func tearDownWithError() throws {
+        try? FileManager.default.removeItem(at: scratchDirectory)
+    }
+
+    /// Verifies acquiring writes this process's PID and releasing removes the file.
+    func testPidFileAcquireWritesAndReleaseRemoves() throws {
+        let path = scratchDirectory.appendingPathComponent("daemon.pid").path
+
+        try DaemonPidFile.acquire(atPath: path)
+
+        let contents = try String(contentsOfFile: path, encoding: .utf8)
+        XCTAssertEqual(
+            Int32(contents.trimmingCharacters(in: .whitespacesAndNewlines)),
+            ProcessInfo.processInfo.processIdentifier
+        )
+
+        DaemonPidFile.release(atPath: path)
+        XCTAssertFalse(FileManager.default.fileExists(atPath: path))
+    }
+
+    /// Verifies a PID file naming a live process refuses acquisition, so two daemons
+    /// never race for one TUN device.
+    func testPidFileHeldByLiveProcessRefusesAcquire() throws {
+        let path = scratchDirectory.appendingPathComponent("daemon.pid").path
+        // PID 1 is always alive.
+        try "1\n".write(toFile: path, atomically: true, encoding: .utf8)
+
+        XCTAssertThrowsError(try DaemonPidFile.acquire(atPath: path)) { error in
+            guard case DaemonHarnessError.pidFileHeld(_, let pid) = error else {
+                return XCTFail("Expected pidFileHeld, got \(error)")
+            }
+            XCTAssertEqual(pid, 1)
+        }
+    }
+
+    /// Verifies a stale PID file left by a dead process is overwritten instead of
+    /// blocking startup forever.
+    func testStalePidFileIsReclaimed() throws {
+        let path = scratchDirectory.appendingPathComponent("daemon.pid").path
+        // Beyond any platform's pid_max, so guaranteed dead.
+        try "99999999\n".write(toFile: path, atomically: true, encoding: .utf8)
+
+        try DaemonPidFile.acquire(atPath: path)
+
+        let contents = try String(contentsOfFile: path, encoding: .utf8)
+        XCTAssertEqual(
+            Int32(contents.trimmingCharacters(in: .whitespacesAndNewlines)),
+            ProcessInfo.processInfo.processIdentifier
+        )
+    }
+
+    /// Verifies config-file keys overlay launch options while absent keys keep the
+    /// launch-time values.
+    func testConfigFileOverlaysOnlyPresentKeys() throws {
+        let url = scratchDirectory.appendingPathComponent("daemon.json")
+        try Data("{\"mtu\": 1500, \"routerMode\": true}".utf8).write(to: url)
+
+        let base = TunRuntimeOptions(mtu: 1280, socksPort: 1090, engineLogLevel: "info")
+        let overlaid = try TunDaemonConfigFile.load(from: url).applying(to: base)
+
+        XCTAssertEqual(overlaid.mtu, 1500)
+        XCTAssertTrue(overlaid.routerMode)
+        XCTAssertEqual(overlaid.socksPort, 1090)
+        XCTAssertEqual(overlaid.engineLogLevel, "info")
+    }
+
+    /// Verifies a malformed config file surfaces as a daemon error naming the path.
+    func testMalformedConfigFileThrowsConfigFileInvalid() throws {
+        let url = scratchDirectory.appendingPathComponent("daemon.json")
+        try Data("{not json".utf8).write(to: url)
+
+        XCTAssertThrowsError(try TunDaemonConfigFile.load(from: url)) { error in
+            guard case DaemonHarnessError.configFileInvalid(let path, _) = error else {
+                return XCTFail("Expected configFileInvalid, got \(error)")
+            }
+            XCTAssertEqual(path, url.path)
+        }
+    }
+}
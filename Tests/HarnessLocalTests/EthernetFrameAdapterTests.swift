@@ -0,0 +1,236 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import XCTest
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// Ethernet framing, MAC learning, and built-in ARP/NDP responder tests.
+final class EthernetFrameAdapterTests: XCTestCase {
+    private static let peerMAC = EthernetMACAddress(octets: [0xAA, 0xBB, 0xCC, 0x00, 0x11, 0x22])!
+
+    /// Verifies an IPv4 frame addressed to the adapter yields its payload and learns the
+    /// peer's hardware address for subsequent outbound unicast.
+    func testIPv4FrameDeliversPayloadAndLearnsPeerMAC() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv4Address: "10.0.0.1"))
+        let payload = Data([0x45, 0x00, 0x00, 0x14] + [UInt8](repeating: 0, count: 16))
+        let frame = Self.frame(
+            destination: .defaultLocal, source: Self.peerMAC, ethertype: 0x0800, payload: payload
+        )
+
+        XCTAssertNil(adapter.peerMACAddress)
+        XCTAssertEqual(adapter.classifyInbound(frame: frame), .ipPacket(family: AF_INET, payload: payload))
+        XCTAssertEqual(adapter.peerMACAddress, Self.peerMAC)
+    }
+
+    /// Verifies outbound packets broadcast before a peer is learned and unicast afterward,
+    /// with the ethertype chosen per family.
+    func testFrameOutboundUsesBroadcastThenLearnedPeer() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv4Address: "10.0.0.1"))
+        let packet = Data([0x45, 0x00, 0x00, 0x14])
+
+        let broadcastFrame = adapter.frameOutbound(packet: packet, family: AF_INET)
+        XCTAssertEqual(broadcastFrame?.prefix(6), Data(EthernetMACAddress.broadcast.octets))
+
+        let inbound = Self.frame(
+            destination: .defaultLocal, source: Self.peerMAC, ethertype: 0x0800, payload: packet
+        )
+        _ = adapter.classifyInbound(frame: inbound)
+
+        let unicastFrame = adapter.frameOutbound(packet: packet, family: AF_INET6)
+        XCTAssertEqual(unicastFrame?.prefix(6), Data(Self.peerMAC.octets))
+        XCTAssertEqual(unicastFrame?[6..<12], Data(EthernetMACAddress.defaultLocal.octets))
+        XCTAssertEqual(unicastFrame?[12..<14], Data([0x86, 0xDD]))
+        XCTAssertEqual(unicastFrame?.suffix(packet.count), packet)
+        XCTAssertNil(adapter.frameOutbound(packet: packet, family: AF_UNIX))
+    }
+
+    /// Verifies an ARP request for the local IPv4 address is answered with a well-formed
+    /// reply naming the adapter's MAC, and the responder counter advances.
+    func testARPRequestForLocalAddressIsAnswered() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv4Address: "10.0.0.1"))
+        let request = Self.arpRequest(
+            senderMAC: Self.peerMAC, senderIP: [10, 0, 0, 2], targetIP: [10, 0, 0, 1]
+        )
+
+        guard case .respond(let reply) = adapter.classifyInbound(frame: request) else {
+            return XCTFail("expected an ARP reply")
+        }
+        let bytes = [UInt8](reply)
+        XCTAssertEqual(Array(bytes[0..<6]), Self.peerMAC.octets)
+        XCTAssertEqual(Array(bytes[6..<12]), EthernetMACAddress.defaultLocal.octets)
+        XCTAssertEqual(Array(bytes[12..<14]), [0x08, 0x06])
+        // Operation 2 (reply), sender = adapter MAC + claimed IPv4, target = requester.
+        XCTAssertEqual(Array(bytes[20..<22]), [0x00, 0x02])
+        XCTAssertEqual(Array(bytes[22..<28]), EthernetMACAddress.defaultLocal.octets)
+        XCTAssertEqual(Array(bytes[28..<32]), [10, 0, 0, 1])
+        XCTAssertEqual(Array(bytes[32..<38]), Self.peerMAC.octets)
+        XCTAssertEqual(Array(bytes[38..<42]), [10, 0, 0, 2])
+        XCTAssertEqual(adapter.stats.arpRepliesSent, 1)
+    }
+
+    /// Verifies ARP requests for a foreign address are dropped, not answered.
+    func testARPRequestForForeignAddressIsDropped() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv4Address: "10.0.0.1"))
+        let request = Self.arpRequest(
+            senderMAC: Self.peerMAC, senderIP: [10, 0, 0, 2], targetIP: [10, 0, 0, 9]
+        )
+
+        XCTAssertEqual(adapter.classifyInbound(frame: request), .drop)
+        XCTAssertEqual(adapter.stats.arpRepliesSent, 0)
+        XCTAssertEqual(adapter.stats.droppedFrames, 1)
+    }
+
+    /// Verifies a neighbor solicitation for the local IPv6 address is answered with an
+    /// advertisement carrying the solicited and override flags, the target link-layer
+    /// address option, and a valid ICMPv6 checksum.
+    func testNeighborSolicitationForLocalAddressIsAnswered() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv6Address: "fd00::1"))
+        var target = [UInt8](repeating: 0, count: 16)
+        target[0] = 0xFD
+        target[15] = 0x01
+        var source = [UInt8](repeating: 0, count: 16)
+        source[0] = 0xFD
+        source[15] = 0x02
+        let solicitation = Self.neighborSolicitation(
+            senderMAC: Self.peerMAC, sourceIP: source, targetIP: target
+        )
+
+        guard case .respond(let reply) = adapter.classifyInbound(frame: solicitation) else {
+            return XCTFail("expected a neighbor advertisement")
+        }
+        let bytes = [UInt8](reply)
+        XCTAssertEqual(Array(bytes[0..<6]), Self.peerMAC.octets)
+        XCTAssertEqual(Array(bytes[12..<14]), [0x86, 0xDD])
+        // IPv6 header: next header ICMPv6, hop limit 255, source = claimed address,
+        // destination = soliciting node.
+        XCTAssertEqual(bytes[20], 58)
+        XCTAssertEqual(bytes[21], 255)
+        XCTAssertEqual(Array(bytes[22..<38]), target)
+        XCTAssertEqual(Array(bytes[38..<54]), source)
+        // ICMPv6: type 136, solicited + override, target, then option type 2 with the MAC.
+        XCTAssertEqual(bytes[54], 136)
+        XCTAssertEqual(bytes[58], 0x60)
+        XCTAssertEqual(Array(bytes[62..<78]), target)
+        XCTAssertEqual(Array(bytes[78..<80]), [2, 1])
+        XCTAssertEqual(Array(bytes[80..<86]), EthernetMACAddress.defaultLocal.octets)
+        XCTAssertEqual(Self.internetChecksum(ipv6PacketBytes: Array(bytes[14...])), 0)
+        XCTAssertEqual(adapter.stats.neighborAdvertisementsSent, 1)
+    }
+
+    /// Verifies non-solicitation IPv6 traffic passes through as an engine packet.
+    func testOrdinaryIPv6TrafficPassesThrough() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv6Address: "fd00::1"))
+        var payload = [UInt8](repeating: 0, count: 48)
+        payload[0] = 0x60
+        payload[6] = 6
+        let frame = Self.frame(
+            destination: .defaultLocal, source: Self.peerMAC, ethertype: 0x86DD, payload: Data(payload)
+        )
+
+        XCTAssertEqual(
+            adapter.classifyInbound(frame: frame),
+            .ipPacket(family: AF_INET6, payload: Data(payload))
+        )
+    }
+
+    /// Verifies frames for a foreign unicast destination and unknown ethertypes are
+    /// dropped like a non-promiscuous interface would.
+    func testForeignDestinationAndUnknownEthertypeAreDropped() {
+        var adapter = EthernetFrameAdapter(options: .init(ipv4Address: "10.0.0.1"))
+        let foreign = EthernetMACAddress(octets: [0x02, 0x00, 0x00, 0x00, 0x00, 0x99])!
+        let toForeign = Self.frame(
+            destination: foreign, source: Self.peerMAC, ethertype: 0x0800, payload: Data([0x45])
+        )
+        let unknownEthertype = Self.frame(
+            destination: .defaultLocal, source: Self.peerMAC, ethertype: 0x88CC, payload: Data([0x00])
+        )
+
+        XCTAssertEqual(adapter.classifyInbound(frame: toForeign), .drop)
+        XCTAssertEqual(adapter.classifyInbound(frame: unknownEthertype), .drop)
+        XCTAssertEqual(adapter.classifyInbound(frame: Data([0x00, 0x01])), .drop)
+        XCTAssertEqual(adapter.stats.droppedFrames, 3)
+    }
+
+    private static func frame(
+        destination: EthernetMACAddress,
+        source: EthernetMACAddress,
+        ethertype: UInt16,
+        payload: Data
+    ) -> Data {
+        var frame = Data()
+        frame.append(contentsOf: destination.octets)
+        frame.append(contentsOf: source.octets)
+        frame.append(contentsOf: [UInt8(ethertype >> 8), UInt8(ethertype & 0xFF)])
+        frame.append(payload)
+        return frame
+    }
+
+    private static func arpRequest(senderMAC: EthernetMACAddress, senderIP: [UInt8], targetIP: [UInt8]) -> Data {
+        var body: [UInt8] = [0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01]
+        body.append(contentsOf: senderMAC.octets)
+        body.append(contentsOf: senderIP)
+        body.append(contentsOf: [UInt8](repeating: 0, count: 6))
+        body.append(contentsOf: targetIP)
+        return frame(
+            destination: .broadcast, source: senderMAC, ethertype: 0x0806, payload: Data(body)
+        )
+    }
+
+    private static func neighborSolicitation(
+        senderMAC: EthernetMACAddress, sourceIP: [UInt8], targetIP: [UInt8]
+    ) -> Data {
+        var icmp: [UInt8] = [135, 0, 0, 0, 0, 0, 0, 0]
+        icmp.append(contentsOf: targetIP)
+        icmp.append(contentsOf: [1, 1])
+        icmp.append(contentsOf: senderMAC.octets)
+        var header: [UInt8] = [0x60, 0, 0, 0, UInt8(icmp.count >> 8), UInt8(icmp.count & 0xFF), 58, 255]
+        header.append(contentsOf: sourceIP)
+        var destination = [UInt8](repeating: 0, count: 16)
+        destination[0] = 0xFF
+        destination[1] = 0x02
+        destination[11] = 0x01
+        destination[12] = 0xFF
+        destination[13] = targetIP[13]
+        destination[14] = targetIP[14]
+        destination[15] = targetIP[15]
+        header.append(contentsOf: destination)
+        return frame(
+            destination: .defaultLocal, source: senderMAC, ethertype: 0x86DD, payload: Data(header + icmp)
+        )
+    }
+
+    /// Recomputes the ICMPv6 checksum over the pseudo-header and payload of a complete
+    /// IPv6 packet; a packet with a correct embedded checksum sums to zero.
+    private static func internetChecksum(ipv6PacketBytes: [UInt8]) -> UInt16 {
+        let payload = Array(ipv6PacketBytes[40...])
+        var words: [UInt8] = Array(ipv6PacketBytes[8..<40])
+        let length = UInt32(payload.count)
+        words.append(contentsOf: [
+            UInt8(length >> 24), UInt8((length >> 16) & 0xFF),
+            UInt8((length >> 8) & 0xFF), UInt8(length & 0xFF),
+        ])
+        words.append(contentsOf: [0, 0, 0, 58])
+        words.append(contentsOf: payload)
+        var sum: UInt32 = 0
+        var index = 0
+        while index + 1 < words.count {
+            sum += UInt32(words[index]) << 8 | UInt32(words[index + 1])
+            index += 2
+        }
+        if index < words.count {
+            sum += UInt32(words[index]) << 8
+        }
+        while sum > 0xFFFF {
+            sum = (sum & 0xFFFF) + (sum >> 16)
+        }
+        return ~UInt16(sum & 0xFFFF)
+    }
+}
@@ -0,0 +1,153 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import XCTest
+
+#if os(Linux)
+import Glibc
+#else
+import Darwin
+#endif
+
+/// TAP pump tests: frames on a stand-in device descriptor must round-trip to raw IP on
+/// the engine descriptor and back, with neighbor queries answered on the device side.
+final class EthernetTapPumpTests: XCTestCase {
+    private static let peerMAC = EthernetMACAddress(octets: [0xAA, 0xBB, 0xCC, 0x00, 0x11, 0x22])!
+
+    private var deviceTestFD: Int32 = -1
+    private var devicePumpFD: Int32 = -1
+    private var pump: EthernetTapPump!
+
+    override func setUpWithError() throws {
+        let pair = try Self.makeDatagramPair()
+        deviceTestFD = pair.0
+        devicePumpFD = pair.1
+        pump = try EthernetTapPump(
+            deviceFD: devicePumpFD,
+            framing: EthernetFramingOptions(ipv4Address: "10.0.0.1")
+        )
+        pump.start()
+    }
+
+    override func tearDown() {
+        pump?.stop()
+        pump = nil
+        if deviceTestFD >= 0 {
+            close(deviceTestFD)
+        }
+        if devicePumpFD >= 0 {
+            close(devicePumpFD)
+        }
+    }
+
+    /// Verifies an ethernet-framed IPv4 packet written to the device side arrives on the
+    /// engine descriptor as the bare IP payload.
+    func testInboundFrameDeliversRawIPToEngine() throws {
+        let payload = Data([0x45, 0x00, 0x00, 0x14] + [UInt8](repeating: 0, count: 16))
+        Self.writeDatagram(to: deviceTestFD, data: Self.frame(ethertype: 0x0800, payload: payload))
+
+        XCTAssertEqual(Self.readDatagram(from: pump.engineFD), payload)
+    }
+
+    /// Verifies a raw IP packet written on the engine descriptor reaches the device side
+    /// framed for the peer learned from earlier inbound traffic.
+    func testEnginePacketIsFramedTowardDevice() throws {
+        let inbound = Data([0x45, 0x00, 0x00, 0x14] + [UInt8](repeating: 0, count: 16))
+        Self.writeDatagram(to: deviceTestFD, data: Self.frame(ethertype: 0x0800, payload: inbound))
+        XCTAssertNotNil(Self.readDatagram(from: pump.engineFD))
+
+        let outbound = Data([0x45, 0x00, 0x00, 0x1C] + [UInt8](repeating: 7, count: 24))
+        Self.writeDatagram(to: pump.engineFD, data: outbound)
+
+        let frame = try XCTUnwrap(Self.readDatagram(from: deviceTestFD))
+        XCTAssertEqual(frame.prefix(6), Data(Self.peerMAC.octets))
+        XCTAssertEqual(frame[6..<12], Data(EthernetMACAddress.defaultLocal.octets))
+        XCTAssertEqual(frame[12..<14], Data([0x08, 0x00]))
+        XCTAssertEqual(frame.suffix(outbound.count), outbound)
+    }
+
+    /// Verifies an ARP request for the claimed address is answered on the device side
+    /// without the engine ever seeing the query.
+    func testARPRequestIsAnsweredWithoutEngineInvolvement() throws {
+        Self.writeDatagram(to: deviceTestFD, data: Self.arpRequest(targetIP: [10, 0, 0, 1]))
+
+        let reply = try XCTUnwrap(Self.readDatagram(from: deviceTestFD))
+        let bytes = [UInt8](reply)
+        XCTAssertEqual(Array(bytes[12..<14]), [0x08, 0x06])
+        XCTAssertEqual(Array(bytes[20..<22]), [0x00, 0x02])
+        XCTAssertEqual(pump.framingStats().arpRepliesSent, 1)
+        XCTAssertNil(Self.readDatagram(from: pump.engineFD, timeout: 0.1))
+    }
+
+    // MARK: - Descriptor helpers
+
+    private static func makeDatagramPair() throws -> (Int32, Int32) {
+#if os(Linux)
+        let datagramType = Int32(SOCK_DGRAM.rawValue)
+#else
+        let datagramType = SOCK_DGRAM
+#endif
+        var fds = [Int32](repeating: -1, count: 2)
+        guard socketpair(AF_UNIX, datagramType, 0, &fds) == 0 else {
+            throw TunHarnessError.unavailable(errno: errno)
+        }
+        for fd in fds {
+            let flags = fcntl(fd, F_GETFL, 0)
+            guard flags >= 0, fcntl(fd, F_SETFL, flags | O_NONBLOCK) >= 0 else {
+                throw TunHarnessError.unavailable(errno: errno)
+            }
+        }
+        return (fds[0], fds[1])
+    }
+
+    private static func writeDatagram(to fd: Int32, data: Data) {
+        _ = data.withUnsafeBytes { buffer in
+            write(fd, buffer.baseAddress, buffer.count)
+        }
+    }
+
+    /// Polls a non-blocking descriptor until one datagram arrives or the deadline passes;
+    /// the pump moves frames on its own queue, so arrival is asynchronous.
+    private static func readDatagram(from fd: Int32, timeout: TimeInterval = 2) -> Data? {
+        var buffer = [UInt8](repeating: 0, count: 65_549)
+        let deadline = Date().addingTimeInterval(timeout)
+        repeat {
+            let count = buffer.withUnsafeMutableBytes { raw in
+                read(fd, raw.baseAddress, raw.count)
+            }
+            if count > 0 {
+                return Data(buffer[0 ..< count])
+            }
+            usleep(5_000)
+        } while Date() < deadline
+        return nil
+    }
+
+    // MARK: - Frame builders
+
+    private static func frame(ethertype: UInt16, payload: Data) -> Data {
+        var frame = Data()
+        frame.append(contentsOf: EthernetMACAddress.defaultLocal.octets)
+        frame.append(contentsOf: peerMAC.octets)
+        frame.append(contentsOf: [UInt8(ethertype >> 8), UInt8(ethertype & 0xFF)])
+        frame.append(payload)
+        return frame
+    }
+
+    private static func arpRequest(targetIP: [UInt8]) -> Data {
+        var body: [UInt8] = [0x00, 0x01, 0x08, 0x00, 6, 4, 0x00, 0x01]
+        body.append(contentsOf: peerMAC.octets)
+        body.append(contentsOf: [10, 0, 0, 2])
+        body.append(contentsOf: [UInt8](repeating: 0, count: 6))
+        body.append(contentsOf: targetIP)
+        var frame = Data()
+        frame.append(contentsOf: EthernetMACAddress.broadcast.octets)
+        frame.append(contentsOf: peerMAC.octets)
+        frame.append(contentsOf: [0x08, 0x06])
+        frame.append(contentsOf: body)
+        return frame
+    }
+}
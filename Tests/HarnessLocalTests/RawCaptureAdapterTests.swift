@@ -0,0 +1,65 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import TunnelRuntime
+import XCTest
+
+/// Live NIC capture adapter tests: frame decapsulation and option validation.
+final class RawCaptureAdapterTests: XCTestCase {
+    /// Verifies a plain Ethernet frame yields its IP payload.
+    func testDecoderExtractsIPv4Payload() {
+        let payload: [UInt8] = [0x45, 0x00, 0x00, 0x14]
+        let frame = Data([UInt8](repeating: 0xAA, count: 12) + [0x08, 0x00] + payload)
+
+        XCTAssertEqual(EthernetFrameDecoder.ipPayload(from: frame), Data(payload))
+    }
+
+    /// Verifies one 802.1Q VLAN tag is skipped before the EtherType check.
+    func testDecoderSkipsVLANTag() {
+        let payload: [UInt8] = [0x60, 0x00, 0x00, 0x00]
+        let frame = Data(
+            [UInt8](repeating: 0xAA, count: 12)
+                + [0x81, 0x00, 0x00, 0x2A]
+                + [0x86, 0xDD]
+                + payload
+        )
+
+        XCTAssertEqual(EthernetFrameDecoder.ipPayload(from: frame), Data(payload))
+    }
+
+    /// Verifies non-IP EtherTypes (here ARP) and runt frames are dropped.
+    func testDecoderDropsNonIPAndRuntFrames() {
+        let arpFrame = Data([UInt8](repeating: 0xAA, count: 12) + [0x08, 0x06] + [0x00, 0x01])
+        XCTAssertNil(EthernetFrameDecoder.ipPayload(from: arpFrame))
+        XCTAssertNil(EthernetFrameDecoder.ipPayload(from: Data([0x08, 0x00])))
+    }
+
+    /// Verifies a non-positive capture window is rejected before the socket is opened.
+    func testNonPositiveDurationThrowsBeforeOpeningSocket() async {
+        let adapter = RawSocketCaptureAdapter(
+            options: RawCaptureOptions(interfaceName: "lo", durationSeconds: 0)
+        )
+        let clock = DeterministicClock(startTime: Date(timeIntervalSince1970: 0))
+
+        do {
+            try await adapter.producePackets(
+                scenario: HarnessScenario(
+                    id: "capture-test",
+                    durationSeconds: 1,
+                    seed: 1,
+                    inputProfile: "capture",
+                    timing: HarnessTiming(startTimeISO8601: "1970-01-01T00:00:00Z", stepIntervalMs: 1),
+                    steps: []
+                ),
+                clock: clock,
+                random: SeededRandomSource(seed: 1)
+            ) { _ in }
+            XCTFail("Expected invalidDuration")
+        } catch {
+            XCTAssertEqual(error as? RawCaptureError, .invalidDuration)
+        }
+    }
+}
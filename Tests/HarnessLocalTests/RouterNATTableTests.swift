@@ -0,0 +1,69 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import HarnessLocal
+import XCTest
+
+/// Router-mode source NAT table tests: mapping stability, reverse lookup, and reclamation.
+final class RouterNATTableTests: XCTestCase {
+    /// Verifies one device's flow keeps a stable translated port and reverse lookup routes
+    /// reply traffic back to the originating device.
+    func testMappingIsStableAndReversible() {
+        var table = RouterNATTable()
+        let now = Date(timeIntervalSince1970: 0)
+
+        let port = table.translateOutbound(clientAddress: "192.168.4.20", clientPort: 50_000, transport: "tcp", now: now)
+        XCTAssertEqual(
+            table.translateOutbound(clientAddress: "192.168.4.20", clientPort: 50_000, transport: "tcp", now: now),
+            port
+        )
+
+        let otherDevice = table.translateOutbound(clientAddress: "192.168.4.21", clientPort: 50_000, transport: "tcp", now: now)
+        XCTAssertNotEqual(port, otherDevice)
+
+        let mapping = table.reverseLookup(translatedPort: port, now: now)
+        XCTAssertEqual(mapping?.clientAddress, "192.168.4.20")
+        XCTAssertEqual(mapping?.clientPort, 50_000)
+        XCTAssertEqual(table.mappingCount, 2)
+    }
+
+    /// Verifies idle mappings expire after the TTL and unknown ports resolve to nil.
+    func testIdleMappingsExpire() {
+        var table = RouterNATTable(options: RouterNATOptions(mappingTTLSeconds: 60))
+        let start = Date(timeIntervalSince1970: 0)
+
+        let port = table.translateOutbound(clientAddress: "192.168.4.20", clientPort: 50_000, transport: "udp", now: start)
+        XCTAssertNotNil(table.reverseLookup(translatedPort: port, now: start.addingTimeInterval(30)))
+
+        // The lookup above refreshed the idle clock, so expiry counts from +30.
+        XCTAssertNil(table.reverseLookup(translatedPort: port, now: start.addingTimeInterval(120)))
+        XCTAssertEqual(table.mappingCount, 0)
+        XCTAssertNil(table.reverseLookup(translatedPort: 40_000, now: start))
+    }
+
+    /// Verifies the capacity cap evicts the least recently used mapping instead of growing.
+    func testCapacityEvictsLeastRecentlyUsed() {
+        var table = RouterNATTable(options: RouterNATOptions(maxMappings: 2))
+        let now = Date(timeIntervalSince1970: 0)
+
+        let first = table.translateOutbound(clientAddress: "192.168.4.20", clientPort: 50_000, transport: "tcp", now: now)
+        let second = table.translateOutbound(
+            clientAddress: "192.168.4.21",
+            clientPort: 50_001,
+            transport: "tcp",
+            now: now.addingTimeInterval(1)
+        )
+        _ = table.translateOutbound(
+            clientAddress: "192.168.4.22",
+            clientPort: 50_002,
+            transport: "tcp",
+            now: now.addingTimeInterval(2)
+        )
+
+        XCTAssertEqual(table.mappingCount, 2)
+        XCTAssertNil(table.reverseLookup(translatedPort: first, now: now.addingTimeInterval(2)))
+        XCTAssertNotNil(table.reverseLookup(translatedPort: second, now: now.addingTimeInterval(2)))
+    }
+}
@@ -0,0 +1,60 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import Observability
+import XCTest
+
+/// Tests for the runtime logging-state snapshot.
+final class LoggingStateTests: XCTestCase {
+    /// Verifies the snapshot reports the configured minimum level and the matching enabled set.
+    func testSnapshotReportsMinimumLevelAndEnabledLevels() async {
+        let logger = StructuredLogger(sink: InMemoryLogSink(), minimumLevel: .notice)
+
+        let state = await logger.loggingState()
+        XCTAssertEqual(state.minimumLevel, .notice)
+        XCTAssertEqual(state.enabledLevels, [.notice, .warning, .error, .fault])
+        XCTAssertTrue(state.suppressedEventCounts.isEmpty)
+    }
+
+    /// Verifies the enabled-level list agrees with `isEnabled(_:)` for every severity.
+    func testSnapshotAgreesWithIsEnabledGate() async {
+        let logger = StructuredLogger(sink: InMemoryLogSink(), minimumLevel: .warning)
+
+        let state = await logger.loggingState()
+        for level in state.enabledLevels {
+            XCTAssertTrue(logger.isEnabled(level))
+        }
+        XCTAssertFalse(state.enabledLevels.contains(.trace))
+        XCTAssertFalse(state.enabledLevels.contains(.info))
+    }
+
+    /// Verifies suppression counters from rate-limited logging surface in the snapshot and
+    /// clear after a reset.
+    func testSnapshotCarriesSuppressionCountsUntilReset() async {
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+        let start = Date(timeIntervalSinceReferenceDate: 0)
+
+        for offset in 0 ..< 3 {
+            await logger.logRateLimited(
+                key: "state-test",
+                minimumInterval: 60,
+                now: start.addingTimeInterval(Double(offset)),
+                level: .warning,
+                phase: .relay,
+                category: .relayUDP,
+                component: "test",
+                event: "flood",
+                message: "duplicate"
+            )
+        }
+
+        var state = await logger.loggingState()
+        XCTAssertEqual(state.suppressedEventCounts, [.relayUDP: 2])
+
+        await logger.resetSuppressedEventCounts()
+        state = await logger.loggingState()
+        XCTAssertTrue(state.suppressedEventCounts.isEmpty)
+    }
+}
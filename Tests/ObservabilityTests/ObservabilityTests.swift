@@ -213,4 +213,62 @@ final class ObservabilityTests: XCTestCase {
         XCTAssertEqual(records[1].metadata["rate_limit_window_ms"], "10000")
         XCTAssertEqual(records[1].metadata["category"], LogCategory.relayUDP.rawValue)
     }
+
+    /// Verifies per-category suppressed counts accumulate across keys and survive the per-key flush.
+    func testSuppressedEventCountsAccumulatePerCategory() async {
+        let sink = InMemoryLogSink()
+        let logger = StructuredLogger(sink: sink)
+        let firstEmission = Date(timeIntervalSince1970: 100)
+
+        for offset in 0..<3 {
+            await logger.logRateLimited(
+                key: "relay.udp.waiting.cellular",
+                minimumInterval: 10,
+                now: firstEmission.addingTimeInterval(TimeInterval(offset)),
+                level: .warning,
+                phase: .relay,
+                category: .relayUDP,
+                component: "NWConnectionUDPSessionAdapter",
+                event: "waiting",
+                message: "Outbound UDP waiting"
+            )
+        }
+        for offset in 0..<2 {
+            await logger.logRateLimited(
+                key: "dataplane.invalid-packet",
+                minimumInterval: 10,
+                now: firstEmission.addingTimeInterval(TimeInterval(offset)),
+                level: .warning,
+                phase: .packetIn,
+                category: .dataplane,
+                component: "DataplaneHandle",
+                event: "invalid-packet",
+                message: "Dropped malformed packet"
+            )
+        }
+
+        var counts = await logger.suppressedEventCounts()
+        XCTAssertEqual(counts[.relayUDP], 2)
+        XCTAssertEqual(counts[.dataplane], 1)
+
+        // Emitting past the window flushes the per-key duplicate count onto the
+        // event metadata, but the cumulative category counts must not reset.
+        await logger.logRateLimited(
+            key: "relay.udp.waiting.cellular",
+            minimumInterval: 10,
+            now: firstEmission.addingTimeInterval(11),
+            level: .warning,
+            phase: .relay,
+            category: .relayUDP,
+            component: "NWConnectionUDPSessionAdapter",
+            event: "waiting",
+            message: "Outbound UDP waiting"
+        )
+        counts = await logger.suppressedEventCounts()
+        XCTAssertEqual(counts[.relayUDP], 2)
+
+        await logger.resetSuppressedEventCounts()
+        let cleared = await logger.suppressedEventCounts()
+        XCTAssertTrue(cleared.isEmpty)
+    }
 }
@@ -0,0 +1,131 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import Observability
+import XCTest
+
+/// Contract tests for the hot-path logging entry point.
+/// Per-packet callers hand `logHotPath(...)` unevaluated closures, so a dropped or
+/// sampled-out event must never pay for message or metadata formatting.
+final class StructuredLoggerHotPathTests: XCTestCase {
+    /// Thread-safe evaluation probe standing in for expensive message formatting.
+    private final class EvaluationProbe: @unchecked Sendable {
+        private let lock = NSLock()
+        private var count = 0
+
+        func formatted() -> String {
+            lock.lock()
+            defer { lock.unlock() }
+            count += 1
+            return "formatted"
+        }
+
+        var evaluationCount: Int {
+            lock.lock()
+            defer { lock.unlock() }
+            return count
+        }
+    }
+
+    /// Verifies events below the minimum level never evaluate their closures or reach the sink.
+    func testDisabledLevelSkipsClosureEvaluation() async {
+        let sink = InMemoryLogSink()
+        let logger = StructuredLogger(sink: sink, minimumLevel: .info)
+        let probe = EvaluationProbe()
+
+        logger.logHotPath(
+            level: .debug,
+            phase: .relay,
+            category: .dataplane,
+            component: "test",
+            event: "dropped",
+            message: probe.formatted(),
+            metadata: ["detail": probe.formatted()]
+        )
+
+        XCTAssertEqual(probe.evaluationCount, 0)
+        let records = await sink.snapshot()
+        XCTAssertTrue(records.isEmpty)
+    }
+
+    /// Verifies a configured category keeps exactly one event out of every N, and skips
+    /// formatting for the suppressed remainder.
+    func testSamplingRatioKeepsOneOfEveryN() async throws {
+        let sink = InMemoryLogSink()
+        let logger = StructuredLogger(sink: sink, hotPathSamplingRatios: [.dataplane: 3])
+        let probe = EvaluationProbe()
+
+        for index in 0..<9 {
+            logger.logHotPath(
+                level: .debug,
+                phase: .relay,
+                category: .dataplane,
+                component: "test",
+                event: "frame-\(index)",
+                message: probe.formatted()
+            )
+        }
+
+        let records = await waitForRecords(count: 3, sink: sink)
+        XCTAssertEqual(Set(records.map(\.event)), ["frame-0", "frame-3", "frame-6"])
+        XCTAssertEqual(probe.evaluationCount, 3)
+    }
+
+    /// Verifies categories without a configured ratio keep every event.
+    func testUnconfiguredCategoryKeepsEveryEvent() async throws {
+        let sink = InMemoryLogSink()
+        let logger = StructuredLogger(sink: sink, hotPathSamplingRatios: [.dataplane: 4])
+
+        for index in 0..<4 {
+            logger.logHotPath(
+                level: .debug,
+                phase: .relay,
+                category: .control,
+                component: "test",
+                event: "event-\(index)",
+                message: "control event"
+            )
+        }
+
+        let records = await waitForRecords(count: 4, sink: sink)
+        XCTAssertEqual(records.count, 4)
+    }
+
+    /// Verifies nonpositive ratios clamp to 1 so a misconfigured host cannot silence a category.
+    func testNonpositiveRatiosClampToOne() async throws {
+        XCTAssertEqual(
+            StructuredLogger(sink: InMemoryLogSink(), hotPathSamplingRatios: [.control: 0]).hotPathSamplingRatios[.control],
+            1
+        )
+        XCTAssertEqual(
+            StructuredLogger(sink: InMemoryLogSink(), hotPathSamplingRatios: [.control: -5]).hotPathSamplingRatios[.control],
+            1
+        )
+
+        let sink = InMemoryLogSink()
+        let logger = StructuredLogger(sink: sink, hotPathSamplingRatios: [.control: 0])
+        logger.logHotPath(
+            level: .debug,
+            phase: .relay,
+            category: .control,
+            component: "test",
+            event: "kept",
+            message: "clamped ratio keeps everything"
+        )
+        let records = await waitForRecords(count: 1, sink: sink)
+        XCTAssertEqual(records.map(\.event), ["kept"])
+    }
+
+    /// Polls the sink until `count` fire-and-forget events have landed or the deadline passes,
+    /// then leaves a settling window so over-emission would still be caught.
+    private func waitForRecords(count: Int, sink: InMemoryLogSink) async -> [LogEnvelope] {
+        let deadline = Date().addingTimeInterval(2)
+        while await sink.snapshot().count < count, Date() < deadline {
+            try? await Task.sleep(nanoseconds: 10_000_000)
+        }
+        try? await Task.sleep(nanoseconds: 50_000_000)
+        return await sink.snapshot()
+    }
+}
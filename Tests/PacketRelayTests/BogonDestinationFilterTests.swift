@@ -0,0 +1,60 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Bogon destination classification and drop-counter tests.
+final class BogonDestinationFilterTests: XCTestCase {
+    /// Verifies reserved IPv4 literals are rejected with the covering prefix named.
+    func testReservedIPv4LiteralsAreRejected() {
+        let filter = BogonDestinationFilter()
+
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "0.1.2.3"), "0.0.0.0/8")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "127.0.0.1"), "127.0.0.0/8")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "169.254.10.20"), "169.254.0.0/16")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "192.0.2.55"), "192.0.2.0/24")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "224.0.0.251"), "224.0.0.0/4")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "255.255.255.255"), "240.0.0.0/4")
+
+        XCTAssertEqual(filter.dropCounts(), .init(ipv4Count: 6, ipv6Count: 0))
+    }
+
+    /// Verifies reserved IPv6 literals are rejected and IPv4-mapped addresses are classified
+    /// against the IPv4 set.
+    func testReservedIPv6LiteralsAreRejected() {
+        let filter = BogonDestinationFilter()
+
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "::1"), "::1/128")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "fe80::1"), "fe80::/10")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "ff02::fb"), "ff00::/8")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "2001:db8::42"), "2001:db8::/32")
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "::ffff:127.0.0.1"), "127.0.0.0/8")
+
+        XCTAssertEqual(filter.dropCounts(), .init(ipv4Count: 1, ipv6Count: 4))
+    }
+
+    /// Verifies public addresses, RFC 1918 space, and domain names all pass untouched.
+    func testDialableDestinationsPass() {
+        let filter = BogonDestinationFilter()
+
+        XCTAssertNil(filter.rejectionPrefix(forHost: "1.1.1.1"))
+        XCTAssertNil(filter.rejectionPrefix(forHost: "10.0.0.7"))
+        XCTAssertNil(filter.rejectionPrefix(forHost: "192.168.1.1"))
+        XCTAssertNil(filter.rejectionPrefix(forHost: "2606:4700::1111"))
+        XCTAssertNil(filter.rejectionPrefix(forHost: "media.example.com"))
+
+        XCTAssertEqual(filter.dropCounts(), .init(ipv4Count: 0, ipv6Count: 0))
+    }
+
+    /// Verifies operator-supplied prefixes extend the default set and unparseable entries
+    /// are ignored.
+    func testAdditionalCIDRsExtendDefaultSet() {
+        let filter = BogonDestinationFilter(additionalCIDRs: ["198.18.0.0/15", "not-a-cidr"])
+
+        XCTAssertEqual(filter.rejectionPrefix(forHost: "198.19.0.1"), "198.18.0.0/15")
+        XCTAssertNil(filter.rejectionPrefix(forHost: "198.20.0.1"))
+    }
+}
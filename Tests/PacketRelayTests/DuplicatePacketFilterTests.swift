@@ -0,0 +1,83 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Darwin
+import Foundation
+import Observability
+@testable import PacketRelay
+import XCTest
+
+/// Recent-packet duplicate detection and bridge drop-counter tests.
+final class DuplicatePacketFilterTests: XCTestCase {
+    /// Verifies an identical packet inside the horizon is flagged while distinct packets
+    /// and the counters all pass through correctly.
+    func testDuplicateInsideHorizonIsFlagged() {
+        var filter = DuplicatePacketFilter()
+        let now = Date(timeIntervalSince1970: 1_000)
+        let packet = Data([0x45, 0x00, 0x00, 0x28, 0x12, 0x34])
+
+        XCTAssertFalse(filter.isDuplicate(packet, now: now))
+        XCTAssertTrue(filter.isDuplicate(packet, now: now.addingTimeInterval(0.01)))
+        XCTAssertFalse(filter.isDuplicate(Data([0x45, 0x00, 0x00, 0x28, 0x12, 0x35]), now: now))
+
+        XCTAssertEqual(filter.stats.inspectedPackets, 3)
+        XCTAssertEqual(filter.stats.duplicatesDropped, 1)
+    }
+
+    /// Verifies the same bytes seen again after the horizon — a plausible genuine
+    /// retransmission — are not flagged.
+    func testRepeatAfterHorizonIsNotFlagged() {
+        var filter = DuplicatePacketFilter(horizonSeconds: 0.05)
+        let now = Date(timeIntervalSince1970: 1_000)
+        let packet = Data([0x45, 0x00, 0x00, 0x28])
+
+        XCTAssertFalse(filter.isDuplicate(packet, now: now))
+        XCTAssertFalse(filter.isDuplicate(packet, now: now.addingTimeInterval(0.3)))
+        XCTAssertEqual(filter.stats.duplicatesDropped, 0)
+    }
+
+    /// Verifies the ring forgets hashes once the window fills, so an old packet repeated
+    /// after enough distinct traffic is treated as new.
+    func testWindowEvictsOldestHashes() {
+        var filter = DuplicatePacketFilter(windowSize: 4, horizonSeconds: 10)
+        let now = Date(timeIntervalSince1970: 1_000)
+        let first = Data([0xAA])
+
+        XCTAssertFalse(filter.isDuplicate(first, now: now))
+        for value in 0 ..< 4 {
+            XCTAssertFalse(filter.isDuplicate(Data([0x10, UInt8(value)]), now: now))
+        }
+        XCTAssertFalse(filter.isDuplicate(first, now: now))
+    }
+
+    /// Verifies a filtered bridge reports duplicate writes as accepted and counts the
+    /// drop, while an unfiltered bridge exposes no stats.
+    func testBridgeDropsDuplicateHostPacketsWhenFiltered() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.bridge.duplicate-filter")
+        let bridge = try TunSocketBridge(
+            mtu: 1500,
+            queue: queue,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            duplicateFilter: DuplicatePacketFilter()
+        )
+        defer { bridge.stop() }
+
+        var packet = Data(repeating: 0, count: 40)
+        packet[0] = 0x45
+        XCTAssertEqual(bridge.writePacket(packet, ipVersionHint: AF_INET), .accepted)
+        XCTAssertEqual(bridge.writePacket(packet, ipVersionHint: AF_INET), .accepted)
+
+        let stats = try XCTUnwrap(bridge.duplicateFilterStats())
+        XCTAssertEqual(stats.inspectedPackets, 2)
+        XCTAssertEqual(stats.duplicatesDropped, 1)
+
+        let unfiltered = try TunSocketBridge(
+            mtu: 1500,
+            queue: DispatchQueue(label: "com.vpnbridge.tests.bridge.duplicate-filter-off"),
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        defer { unfiltered.stop() }
+        XCTAssertNil(unfiltered.duplicateFilterStats())
+    }
+}
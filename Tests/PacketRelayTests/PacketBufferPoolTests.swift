@@ -0,0 +1,58 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import PacketRelay
+import Foundation
+import XCTest
+
+/// Receive-buffer pool reuse and lifetime tests.
+final class PacketBufferPoolTests: XCTestCase {
+    /// Verifies a recycled buffer is handed out again instead of allocating a fresh one.
+    func testAcquireReusesRecycledBuffer() {
+        let pool = PacketBufferPool(bufferSize: 64, maxPooledBuffers: 4)
+
+        let first = pool.acquire()
+        pool.recycle(first)
+        XCTAssertEqual(pool.pooledBufferCount, 1)
+
+        let second = pool.acquire()
+        XCTAssertEqual(first, second)
+        XCTAssertEqual(pool.pooledBufferCount, 0)
+        pool.recycle(second)
+    }
+
+    /// Verifies buffers recycled past the free-list cap are released rather than retained.
+    func testRecyclePastCapDoesNotGrowFreeList() {
+        let pool = PacketBufferPool(bufferSize: 64, maxPooledBuffers: 2)
+
+        let buffers = (0..<3).map { _ in pool.acquire() }
+        for buffer in buffers {
+            pool.recycle(buffer)
+        }
+
+        XCTAssertEqual(pool.pooledBufferCount, 2)
+    }
+
+    /// Verifies a `Data` wrapping a pooled buffer returns it to the pool when the last
+    /// reference drops, mirroring how the bridge hands payloads to its read handler.
+    func testDataDeallocatorReturnsBufferToPool() {
+        let pool = PacketBufferPool(bufferSize: 64, maxPooledBuffers: 4)
+        let buffer = pool.acquire()
+        buffer.assumingMemoryBound(to: UInt8.self).update(repeating: 0xab, count: 8)
+
+        var payload: Data? = Data(
+            bytesNoCopy: buffer,
+            count: 8,
+            deallocator: .custom { pointer, _ in
+                pool.recycle(pointer)
+            }
+        )
+        XCTAssertEqual(payload?.count, 8)
+        XCTAssertEqual(payload?.first, 0xab)
+        XCTAssertEqual(pool.pooledBufferCount, 0)
+
+        payload = nil
+        XCTAssertEqual(pool.pooledBufferCount, 1)
+    }
+}
@@ -0,0 +1,79 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Dial-failure taxonomy tests covering reason classification and per-destination counts.
+final class RelayDialFailureTaxonomyTests: XCTestCase {
+    /// Verifies framework-style reason strings classify into the documented kinds.
+    func testClassifiesReasonStrings() {
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "The operation timed out"), .timeout)
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "Connection refused"), .refused)
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "No route to host"), .unreachable)
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "Network is unreachable"), .unreachable)
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "DNS resolution failed"), .dnsFailure)
+        XCTAssertEqual(
+            RelayDialFailureKind.classify(reason: "nodename nor servname provided, or not known"),
+            .dnsFailure
+        )
+        // DNS checks win over the generic unreachable substring.
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "DNS server unreachable"), .dnsFailure)
+        XCTAssertEqual(RelayDialFailureKind.classify(reason: "Socket is not connected"), .other)
+    }
+
+    /// Verifies counts aggregate per destination bucket with address literals sharing a /24 row.
+    func testCountsAggregatePerDestination() throws {
+        let counters = RelayDialFailureCounters()
+        counters.record(host: "api.example.com", reason: "The operation timed out")
+        counters.record(host: "API.example.com", reason: "Connection refused")
+        counters.record(host: "api.example.com", kind: .policyBlocked)
+        counters.record(host: "203.0.113.5", reason: "No route to host")
+        counters.record(host: "203.0.113.9", reason: "No route to host")
+
+        let entries = counters.snapshot()
+        XCTAssertEqual(entries.count, 2)
+        let api = try XCTUnwrap(entries.first { $0.destination == "api.example.com" })
+        XCTAssertEqual(api.timeouts, 1)
+        XCTAssertEqual(api.refused, 1)
+        XCTAssertEqual(api.policyBlocked, 1)
+        XCTAssertEqual(api.total, 3)
+        let subnet = try XCTUnwrap(entries.first { $0.destination == "203.0.113.0/24" })
+        XCTAssertEqual(subnet.unreachable, 2)
+        XCTAssertEqual(subnet.total, 2)
+    }
+
+    /// Verifies seeding from an exported snapshot restores counts and adds onto anything
+    /// the new session already recorded.
+    func testSeedRestoresExportedCounts() throws {
+        let counters = RelayDialFailureCounters()
+        counters.record(host: "api.example.com", reason: "The operation timed out")
+        counters.record(host: "api.example.com", reason: "Connection refused")
+        let exported = counters.snapshot()
+
+        let restarted = RelayDialFailureCounters()
+        restarted.record(host: "api.example.com", reason: "The operation timed out")
+        restarted.seed(entries: exported)
+
+        let api = try XCTUnwrap(restarted.snapshot().first { $0.destination == "api.example.com" })
+        XCTAssertEqual(api.timeouts, 2)
+        XCTAssertEqual(api.refused, 1)
+        XCTAssertEqual(api.total, 3)
+    }
+
+    /// Verifies export orders destinations by total failures and `removeAll` opens a fresh window.
+    func testSnapshotOrdersMostFailuresFirst() {
+        let counters = RelayDialFailureCounters()
+        counters.record(host: "rare.example", reason: "Connection refused")
+        for _ in 0..<5 {
+            counters.record(host: "flaky.example", reason: "The operation timed out")
+        }
+
+        XCTAssertEqual(counters.snapshot().map(\.destination), ["flaky.example", "rare.example"])
+
+        counters.removeAll()
+        XCTAssertTrue(counters.snapshot().isEmpty)
+    }
+}
@@ -0,0 +1,74 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Per-destination dial-latency heatmap tests covering bucketing, percentiles, and bounds.
+final class RelayDialLatencyHeatmapTests: XCTestCase {
+    /// Verifies samples aggregate per normalized host and export nearest-rank percentiles.
+    func testPercentilesAggregatePerDestination() throws {
+        let heatmap = RelayDialLatencyHeatmap()
+        for milliseconds in 1...100 {
+            heatmap.record(host: milliseconds.isMultiple(of: 2) ? "API.Example.com" : "api.example.com", milliseconds: milliseconds)
+        }
+
+        let entry = try XCTUnwrap(heatmap.snapshot().first)
+        XCTAssertEqual(heatmap.snapshot().count, 1)
+        XCTAssertEqual(entry.destination, "api.example.com")
+        XCTAssertEqual(entry.sampleCount, 100)
+        XCTAssertEqual(entry.p50Milliseconds, 50)
+        XCTAssertEqual(entry.p90Milliseconds, 90)
+        XCTAssertEqual(entry.p99Milliseconds, 99)
+        XCTAssertEqual(entry.maxMilliseconds, 100)
+    }
+
+    /// Verifies address literals bucket by network prefix so one subnet lands in one row.
+    func testAddressLiteralsBucketByPrefix() throws {
+        let heatmap = RelayDialLatencyHeatmap()
+        heatmap.record(host: "203.0.113.5", milliseconds: 10)
+        heatmap.record(host: "203.0.113.200", milliseconds: 30)
+        heatmap.record(host: "2001:db8:aa:bb::1", milliseconds: 20)
+
+        let entries = heatmap.snapshot()
+        XCTAssertEqual(entries.count, 2)
+        let ipv4 = try XCTUnwrap(entries.first { $0.destination == "203.0.113.0/24" })
+        XCTAssertEqual(ipv4.sampleCount, 2)
+        XCTAssertEqual(ipv4.maxMilliseconds, 30)
+        XCTAssertNotNil(entries.first { $0.destination == "2001:db8:aa::/48" })
+    }
+
+    /// Verifies export orders destinations slowest p99 first and drops non-positive samples.
+    func testSnapshotOrdersSlowestFirst() {
+        let heatmap = RelayDialLatencyHeatmap()
+        heatmap.record(host: "fast.example", milliseconds: 5)
+        heatmap.record(host: "slow.example", milliseconds: 900)
+        heatmap.record(host: "ignored.example", milliseconds: 0)
+
+        let destinations = heatmap.snapshot().map(\.destination)
+        XCTAssertEqual(destinations, ["slow.example", "fast.example"])
+    }
+
+    /// Verifies per-destination samples stay bounded as a ring and the destination count
+    /// stays capped by least-recently-dialed eviction.
+    func testSampleAndDestinationBoundsHold() throws {
+        let heatmap = RelayDialLatencyHeatmap()
+        for milliseconds in 1...300 {
+            heatmap.record(host: "busy.example", milliseconds: milliseconds)
+        }
+        let busy = try XCTUnwrap(heatmap.snapshot().first)
+        XCTAssertEqual(busy.sampleCount, 256)
+        XCTAssertEqual(busy.maxMilliseconds, 300)
+
+        for index in 0..<600 {
+            heatmap.record(host: "host-\(index).example", milliseconds: 10)
+        }
+        XCTAssertEqual(heatmap.snapshot().count, 512)
+        XCTAssertNil(heatmap.snapshot().first { $0.destination == "busy.example" })
+
+        heatmap.removeAll()
+        XCTAssertTrue(heatmap.snapshot().isEmpty)
+    }
+}
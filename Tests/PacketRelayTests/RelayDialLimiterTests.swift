@@ -0,0 +1,76 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Dial limiter cap, FIFO ordering, and stats tests.
+final class RelayDialLimiterTests: XCTestCase {
+    private final class DialRecorder: @unchecked Sendable {
+        private let lock = NSLock()
+        private var startedOrder: [Int] = []
+
+        func recordStart(_ index: Int) {
+            lock.lock()
+            defer { lock.unlock() }
+            startedOrder.append(index)
+        }
+
+        var order: [Int] {
+            lock.lock()
+            defer { lock.unlock() }
+            return startedOrder
+        }
+    }
+
+    /// Verifies over-cap dials wait in FIFO arrival order and start as slots free up,
+    /// with the stats counting immediate and deferred admissions separately.
+    func testOverCapDialsRunInArrivalOrder() {
+        let limiter = RelayDialLimiter(maxInFlightDials: 2)
+        let recorder = DialRecorder()
+
+        for index in 1...4 {
+            limiter.admit { recorder.recordStart(index) }
+        }
+
+        XCTAssertEqual(recorder.order, [1, 2])
+        var stats = limiter.stats()
+        XCTAssertEqual(stats.inFlightCount, 2)
+        XCTAssertEqual(stats.queuedCount, 2)
+        XCTAssertEqual(stats.peakQueuedCount, 2)
+        XCTAssertEqual(stats.admittedImmediatelyCount, 2)
+        XCTAssertEqual(stats.deferredCount, 2)
+
+        // The freed slot transfers to the oldest waiter, so in-flight stays at the cap.
+        limiter.release()
+        XCTAssertEqual(recorder.order, [1, 2, 3])
+        stats = limiter.stats()
+        XCTAssertEqual(stats.inFlightCount, 2)
+        XCTAssertEqual(stats.queuedCount, 1)
+
+        limiter.release()
+        limiter.release()
+        limiter.release()
+        XCTAssertEqual(recorder.order, [1, 2, 3, 4])
+        stats = limiter.stats()
+        XCTAssertEqual(stats.inFlightCount, 0)
+        XCTAssertEqual(stats.queuedCount, 0)
+        XCTAssertEqual(stats.peakQueuedCount, 2)
+    }
+
+    /// Verifies the cap clamps to at least one slot so a misconfigured limiter can never
+    /// deadlock every dial.
+    func testCapClampsToAtLeastOneSlot() {
+        let limiter = RelayDialLimiter(maxInFlightDials: 0)
+        let recorder = DialRecorder()
+
+        limiter.admit { recorder.recordStart(1) }
+        limiter.admit { recorder.recordStart(2) }
+
+        XCTAssertEqual(recorder.order, [1])
+        limiter.release()
+        XCTAssertEqual(recorder.order, [1, 2])
+    }
+}
@@ -0,0 +1,170 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// New-flow rate limiter burst, refill, and bounded-tracking tests.
+final class RelayFlowRateLimiterTests: XCTestCase {
+    private final class ClockBox: @unchecked Sendable {
+        private let lock = NSLock()
+        private var current: Date
+
+        init(_ start: Date) {
+            current = start
+        }
+
+        var now: Date {
+            lock.lock()
+            defer { lock.unlock() }
+            return current
+        }
+
+        func advance(by seconds: TimeInterval) {
+            lock.lock()
+            defer { lock.unlock() }
+            current = current.addingTimeInterval(seconds)
+        }
+    }
+
+    /// Verifies one source can burst up to its bucket size, is then rejected, and earns
+    /// tokens back at the sustained rate as time passes.
+    func testPerSourceBurstThenContinuousRefill() {
+        let clock = ClockBox(Date(timeIntervalSince1970: 1_000))
+        let limits = RelayFlowRateLimiter.Limits(
+            globalFlowsPerSecond: 100,
+            globalBurst: 100,
+            perSourceFlowsPerSecond: 2,
+            perSourceBurst: 3
+        )
+        let limiter = RelayFlowRateLimiter(limits: limits, now: { clock.now })
+
+        for _ in 0 ..< 3 {
+            XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .admitted)
+        }
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .rejectedBySourceLimit)
+
+        // One second at 2 flows/s earns two tokens back; the third open is over again.
+        clock.advance(by: 1)
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .rejectedBySourceLimit)
+
+        let stats = limiter.stats()
+        XCTAssertEqual(stats.admittedCount, 5)
+        XCTAssertEqual(stats.rejectedBySourceCount, 2)
+        XCTAssertEqual(stats.rejectedByGlobalCount, 0)
+        XCTAssertEqual(stats.trackedSourceCount, 1)
+    }
+
+    /// Verifies an exhausted source never consumes another source's tokens and that the
+    /// global bucket backstops flows whose source cannot be attributed.
+    func testSourcesAreIndependentAndGlobalBucketBackstopsNilSources() {
+        let clock = ClockBox(Date(timeIntervalSince1970: 1_000))
+        let limits = RelayFlowRateLimiter.Limits(
+            globalFlowsPerSecond: 1,
+            globalBurst: 4,
+            perSourceFlowsPerSecond: 1,
+            perSourceBurst: 2
+        )
+        let limiter = RelayFlowRateLimiter(limits: limits, now: { clock.now })
+
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.2"), .rejectedBySourceLimit)
+
+        // The scanner above burned two global tokens; an unrelated source still gets in.
+        XCTAssertEqual(limiter.admitNewFlow(source: "10.0.0.3"), .admitted)
+
+        // Unattributed flows drain the remaining global token, then hit the global limit.
+        XCTAssertEqual(limiter.admitNewFlow(source: nil), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: nil), .rejectedByGlobalLimit)
+
+        let stats = limiter.stats()
+        XCTAssertEqual(stats.admittedCount, 4)
+        XCTAssertEqual(stats.rejectedBySourceCount, 1)
+        XCTAssertEqual(stats.rejectedByGlobalCount, 1)
+        XCTAssertEqual(stats.trackedSourceCount, 2)
+    }
+
+    /// Verifies the per-source bucket map stays bounded under a source-spraying scan
+    /// instead of growing one entry per spoofed address.
+    func testTrackedSourceMapStaysBounded() {
+        let clock = ClockBox(Date(timeIntervalSince1970: 1_000))
+        let limits = RelayFlowRateLimiter.Limits(
+            globalFlowsPerSecond: 10_000,
+            globalBurst: 10_000,
+            perSourceFlowsPerSecond: 10,
+            perSourceBurst: 10
+        )
+        let limiter = RelayFlowRateLimiter(limits: limits, now: { clock.now })
+
+        for index in 0 ..< 600 {
+            XCTAssertEqual(limiter.admitNewFlow(source: "10.0.\(index / 256).\(index % 256)"), .admitted)
+        }
+
+        XCTAssertEqual(limiter.stats().trackedSourceCount, 512)
+    }
+
+    /// Verifies rotating IPv6 privacy addresses within one /64 share a single bucket
+    /// instead of earning a fresh burst per temporary address.
+    func testIPv6PrivacyAddressRotationSharesOneBucket() {
+        let clock = ClockBox(Date(timeIntervalSince1970: 1_000))
+        let limits = RelayFlowRateLimiter.Limits(
+            globalFlowsPerSecond: 100,
+            globalBurst: 100,
+            perSourceFlowsPerSecond: 1,
+            perSourceBurst: 3
+        )
+        let limiter = RelayFlowRateLimiter(limits: limits, now: { clock.now })
+
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2:aaaa:bbbb:cccc:1"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2:1111:2222:3333:4"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2:dead:beef:0:9"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2:aaaa:0:0:2"), .rejectedBySourceLimit)
+
+        // A device in a different /64 is unaffected.
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:3::1"), .admitted)
+        XCTAssertEqual(limiter.stats().trackedSourceCount, 2)
+    }
+
+    /// Verifies prefix grouping can be disabled, restoring per-address buckets.
+    func testSlash64GroupingCanBeDisabled() {
+        let clock = ClockBox(Date(timeIntervalSince1970: 1_000))
+        let limits = RelayFlowRateLimiter.Limits(
+            globalFlowsPerSecond: 100,
+            globalBurst: 100,
+            perSourceFlowsPerSecond: 1,
+            perSourceBurst: 1,
+            groupIPv6SourcesBySlash64: false
+        )
+        let limiter = RelayFlowRateLimiter(limits: limits, now: { clock.now })
+
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2::1"), .admitted)
+        XCTAssertEqual(limiter.admitNewFlow(source: "2001:db8:1:2::2"), .admitted)
+        XCTAssertEqual(limiter.stats().trackedSourceCount, 2)
+    }
+
+    /// Verifies source-key normalization: /64 keys for IPv6, embedded address for
+    /// IPv4-mapped forms, and pass-through for IPv4 and unparsable strings.
+    func testSourceKeyNormalization() {
+        XCTAssertEqual(
+            RelayFlowRateLimiter.sourceKey("2001:DB8:1:2:aaaa::1", groupIPv6BySlash64: true),
+            "2001:db8:1:2::/64"
+        )
+        XCTAssertEqual(
+            RelayFlowRateLimiter.sourceKey("::ffff:10.0.0.2", groupIPv6BySlash64: true),
+            "10.0.0.2"
+        )
+        XCTAssertEqual(
+            RelayFlowRateLimiter.sourceKey("10.0.0.2", groupIPv6BySlash64: true),
+            "10.0.0.2"
+        )
+        XCTAssertEqual(
+            RelayFlowRateLimiter.sourceKey("Fe80::1%en0", groupIPv6BySlash64: true),
+            "fe80::1%en0"
+        )
+    }
+}
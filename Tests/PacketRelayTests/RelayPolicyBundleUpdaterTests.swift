@@ -0,0 +1,174 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Observability
+@testable import PacketRelay
+import XCTest
+#if canImport(CryptoKit)
+import CryptoKit
+#endif
+
+/// Signed policy bundle verification, atomic install, and updater gating tests.
+final class RelayPolicyBundleUpdaterTests: XCTestCase {
+    /// Verifies the swappable evaluator admits everything before any install and swaps
+    /// whole documents with a generation bump per install.
+    func testSwappablePolicyAllowsUntilInstalled() throws {
+        let policy = RelaySwappablePolicy()
+        let input = RelayPolicyInput(host: "blocked.example", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+
+        XCTAssertEqual(policy.generation, 0)
+        XCTAssertEqual(policy.evaluate(input), .allow)
+
+        policy.install(try RelayPolicyCompiler.compile("block blocked.example"))
+        XCTAssertEqual(policy.generation, 1)
+        XCTAssertEqual(policy.evaluate(input), .block)
+
+        policy.install(try RelayPolicyCompiler.compile(""))
+        XCTAssertEqual(policy.generation, 2)
+        XCTAssertEqual(policy.evaluate(input), .allow)
+    }
+
+    #if canImport(CryptoKit)
+    /// Verifies a correctly signed bundle installs its compiled document and the
+    /// installed rules take effect immediately.
+    func testApplyInstallsVerifiedBundle() throws {
+        let key = Curve25519.Signing.PrivateKey()
+        let policy = RelaySwappablePolicy()
+        let updater = RelayPolicyBundleUpdater(
+            policy: policy,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            isEnabled: true
+        )
+
+        let generation = try updater.apply(
+            bundleData: try Self.signedBundleData(policyText: "block *.ads.example", key: key),
+            publicKey: key.publicKey.rawRepresentation
+        )
+        XCTAssertEqual(generation, 1)
+
+        let blocked = RelayPolicyInput(host: "cdn.ads.example", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(blocked), .block)
+    }
+
+    /// Verifies tampered documents, foreign keys, bad schema versions, and uncompilable
+    /// documents are all refused without touching the installed policy.
+    func testApplyRejectsInvalidBundlesWithoutInstalling() throws {
+        let key = Curve25519.Signing.PrivateKey()
+        let policy = RelaySwappablePolicy()
+        let updater = RelayPolicyBundleUpdater(
+            policy: policy,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            isEnabled: true
+        )
+        let publicKey = key.publicKey.rawRepresentation
+
+        // Signature over different bytes than the shipped document.
+        var bundle = try JSONDecoder().decode(
+            RelayPolicyBundle.self,
+            from: Self.signedBundleData(policyText: "block a.example", key: key)
+        )
+        bundle = RelayPolicyBundle(policyText: "block b.example", signatureBase64: bundle.signatureBase64)
+        XCTAssertThrowsError(try updater.apply(bundleData: try JSONEncoder().encode(bundle), publicKey: publicKey)) {
+            XCTAssertEqual($0 as? RelayPolicyBundleUpdater.UpdateError, .signatureRejected)
+        }
+
+        // Verified by a key other than the signer's.
+        let foreignKey = Curve25519.Signing.PrivateKey().publicKey.rawRepresentation
+        XCTAssertThrowsError(try updater.apply(
+            bundleData: try Self.signedBundleData(policyText: "block a.example", key: key),
+            publicKey: foreignKey
+        )) {
+            XCTAssertEqual($0 as? RelayPolicyBundleUpdater.UpdateError, .signatureRejected)
+        }
+
+        // Unknown schema version.
+        let futureBundle = RelayPolicyBundle(
+            schemaVersion: 99,
+            policyText: "block a.example",
+            signatureBase64: ""
+        )
+        XCTAssertThrowsError(try updater.apply(bundleData: try JSONEncoder().encode(futureBundle), publicKey: publicKey)) {
+            XCTAssertEqual($0 as? RelayPolicyBundleUpdater.UpdateError, .unsupportedSchemaVersion(99))
+        }
+
+        // Signed but uncompilable document.
+        XCTAssertThrowsError(try updater.apply(
+            bundleData: try Self.signedBundleData(policyText: "frobnicate everything", key: key),
+            publicKey: publicKey
+        )) {
+            guard case .compileFailed = $0 as? RelayPolicyBundleUpdater.UpdateError else {
+                return XCTFail("expected compileFailed, got \($0)")
+            }
+        }
+
+        XCTAssertEqual(policy.generation, 0)
+    }
+
+    /// Verifies the feature flag and the HTTPS requirement both gate fetches before any
+    /// network or verification work happens.
+    func testFetchUpdateRequiresFlagAndHTTPS() async throws {
+        let key = Curve25519.Signing.PrivateKey()
+        let publicKey = key.publicKey.rawRepresentation
+        let logger = StructuredLogger(sink: InMemoryLogSink())
+
+        let disabled = RelayPolicyBundleUpdater(
+            policy: RelaySwappablePolicy(),
+            logger: logger,
+            fetch: { _ in XCTFail("disabled updater must not fetch"); return Data() }
+        )
+        do {
+            try await disabled.fetchUpdate(from: URL(string: "https://rules.example/bundle")!, publicKey: publicKey)
+            XCTFail("expected updaterDisabled")
+        } catch {
+            XCTAssertEqual(error as? RelayPolicyBundleUpdater.UpdateError, .updaterDisabled)
+        }
+
+        let enabled = RelayPolicyBundleUpdater(
+            policy: RelaySwappablePolicy(),
+            logger: logger,
+            isEnabled: true,
+            fetch: { _ in XCTFail("insecure URL must not fetch"); return Data() }
+        )
+        do {
+            try await enabled.fetchUpdate(from: URL(string: "http://rules.example/bundle")!, publicKey: publicKey)
+            XCTFail("expected insecureURL")
+        } catch {
+            XCTAssertEqual(error as? RelayPolicyBundleUpdater.UpdateError, .insecureURL)
+        }
+    }
+
+    /// Verifies a fetched bundle flows through verification and install end to end with
+    /// an injected transport.
+    func testFetchUpdateAppliesFetchedBundle() async throws {
+        let key = Curve25519.Signing.PrivateKey()
+        let policy = RelaySwappablePolicy()
+        let bundleData = try Self.signedBundleData(policyText: "block fetched.example", key: key)
+        let updater = RelayPolicyBundleUpdater(
+            policy: policy,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            isEnabled: true,
+            fetch: { _ in bundleData }
+        )
+
+        let generation = try await updater.fetchUpdate(
+            from: URL(string: "https://rules.example/bundle")!,
+            publicKey: key.publicKey.rawRepresentation
+        )
+        XCTAssertEqual(generation, 1)
+
+        let blocked = RelayPolicyInput(host: "fetched.example", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(blocked), .block)
+    }
+
+    private static func signedBundleData(policyText: String, key: Curve25519.Signing.PrivateKey) throws -> Data {
+        let signature = try key.signature(for: Data(policyText.utf8))
+        let bundle = RelayPolicyBundle(
+            policyText: policyText,
+            signatureBase64: signature.base64EncodedString()
+        )
+        return try JSONEncoder().encode(bundle)
+    }
+    #endif
+}
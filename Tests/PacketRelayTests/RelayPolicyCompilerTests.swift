@@ -0,0 +1,691 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Relay policy DSL compiler tests covering parsing, matching, and diagnostics.
+final class RelayPolicyCompilerTests: XCTestCase {
+    /// Verifies the documented example document compiles into ordered rules.
+    func testCompilesExampleDocument() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            "block udp *.tiktok.com:443; shape *.youtube.com latency=200 jitter=40 rate=2mbps"
+        )
+
+        XCTAssertEqual(policy.rules.count, 2)
+        XCTAssertEqual(policy.rules[0].action, .block)
+        XCTAssertEqual(policy.rules[0].transport, "udp")
+        XCTAssertEqual(policy.rules[0].hostPattern, "*.tiktok.com")
+        XCTAssertEqual(policy.rules[0].port, 443)
+
+        guard case .shape(let parameters) = policy.rules[1].action else {
+            return XCTFail("expected shape action")
+        }
+        XCTAssertEqual(parameters.latencyMs, 200)
+        XCTAssertEqual(parameters.jitterMs, 40)
+        XCTAssertEqual(parameters.maxBurstBytes, 250_000)
+        XCTAssertNil(policy.rules[1].transport)
+        XCTAssertNil(policy.rules[1].port)
+    }
+
+    /// Verifies evaluation is first-match-wins with an allow default for unmatched flows.
+    func testEvaluationMatchesFirstRuleAndDefaultsToAllow() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            """
+            # pinned exemption first
+            allow tcp api.example.com:443
+            block *.example.com
+            shape * burst=4096
+            """
+        )
+
+        let pinned = RelayPolicyInput(host: "api.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(pinned), .allow)
+
+        let blocked = RelayPolicyInput(host: "cdn.EXAMPLE.com", port: 80, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(blocked), .block)
+
+        let shaped = RelayPolicyInput(host: "other.net", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(shaped), .shape(maxBurstBytes: 4_096))
+
+        let empty = try RelayPolicyCompiler.compile("")
+        XCTAssertEqual(empty.evaluate(shaped), .allow)
+    }
+
+    /// Verifies `rate=` rules carry the pacing rate through verdicts while `burst=` rules do not.
+    func testRateRulesCarryPacingThroughVerdicts() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            "shape *.example.com rate=8kbps; shape other.net burst=4096"
+        )
+
+        let paced = RelayPolicyInput(host: "video.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(paced), .shape(maxBurstBytes: 1_000, pacingBytesPerSecond: 1_000))
+
+        let capped = RelayPolicyInput(host: "other.net", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(capped), .shape(maxBurstBytes: 4_096, pacingBytesPerSecond: nil))
+    }
+
+    /// Verifies the admission trace reports every rule with a concrete mismatch reason and
+    /// still flags block rules shadowed by an earlier match.
+    func testTraceExplainsEveryRuleIncludingShadowedOnes() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            """
+            allow tcp api.example.com:443
+            block udp *.example.com
+            block *.example.com
+            """
+        )
+
+        let trace = policy.trace(
+            RelayPolicyInput(host: "API.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        )
+        XCTAssertEqual(trace.normalizedHost, "api.example.com")
+        XCTAssertEqual(trace.hostSource, .clientHostname)
+        XCTAssertEqual(trace.verdict, .allow)
+        XCTAssertEqual(trace.steps.count, 3)
+        XCTAssertTrue(trace.steps[0].matched)
+        XCTAssertFalse(trace.steps[1].matched)
+        XCTAssertEqual(trace.steps[1].reason, "transport is tcp, rule wants udp")
+        // The shadowed block rule still reports as matching; that shadowing is exactly
+        // what a "why wasn't this blocked?" investigation needs to see.
+        XCTAssertTrue(trace.steps[2].matched)
+        XCTAssertTrue(trace.summary.contains("#3 matched"))
+    }
+
+    /// Verifies trace host sources distinguish address literals, client hostnames, and
+    /// post-inspection re-evaluations.
+    func testTraceReportsHostSource() throws {
+        let policy = try RelayPolicyCompiler.compile("block *")
+
+        XCTAssertEqual(policy.trace(input(host: "203.0.113.7")).hostSource, .ipv4Literal)
+        XCTAssertEqual(policy.trace(input(host: "2001:db8::1")).hostSource, .ipv6Literal)
+        let inspected = policy.trace(
+            RelayPolicyInput(
+                host: "video.example.com",
+                port: 443,
+                transport: "tcp",
+                firstPayloadSnippet: Data(),
+                echDetected: true
+            )
+        )
+        XCTAssertEqual(inspected.hostSource, .inspectedClientHello)
+    }
+
+    /// Verifies `re:` selectors compile once, anchor over the whole host, and ignore case.
+    func testRegexSelectorMatchesAnchoredAndCaseInsensitive() throws {
+        let policy = try RelayPolicyCompiler.compile("block re:[a-z0-9]{16}\\.cdn\\.example\\.com")
+
+        XCTAssertEqual(policy.rules.count, 1)
+        XCTAssertEqual(policy.rules[0].hostRegex?.pattern, "[a-z0-9]{16}\\.cdn\\.example\\.com")
+        XCTAssertNil(policy.rules[0].hostPattern)
+
+        let rotated = RelayPolicyInput(
+            host: "A1B2C3D4E5F6A7B8.cdn.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data()
+        )
+        XCTAssertEqual(policy.evaluate(rotated), .block)
+
+        let tooShort = RelayPolicyInput(host: "abc.cdn.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(tooShort), .allow)
+
+        // Anchoring: a match inside a longer host must not fire.
+        let embedded = RelayPolicyInput(
+            host: "a1b2c3d4e5f6a7b8.cdn.example.com.evil.net",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data()
+        )
+        XCTAssertEqual(policy.evaluate(embedded), .allow)
+    }
+
+    /// Verifies invalid or oversized regex selectors fail compilation with the statement number.
+    func testRegexSelectorRejectsInvalidAndOversizedPatterns() {
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block re:[unclosed")) { error in
+            guard case RelayPolicyCompileError.invalidStatement(let statement, _) = error else {
+                return XCTFail("expected invalidStatement, got \(error)")
+            }
+            XCTAssertEqual(statement, 1)
+        }
+
+        let oversized = String(repeating: "a", count: RelayHostRegex.maxPatternLength + 1)
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block re:\(oversized)"))
+    }
+
+    /// Verifies `src=` scopes rules to client devices by source CIDR and never matches flows
+    /// without source attribution.
+    func testSourceCIDRScopesRulesPerClientDevice() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            """
+            block *.example.com src=192.168.4.0/24
+            allow *
+            """
+        )
+
+        let kidsDevice = RelayPolicyInput(
+            host: "video.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data(),
+            sourceAddress: "192.168.4.20"
+        )
+        XCTAssertEqual(policy.evaluate(kidsDevice), .block)
+
+        let otherDevice = RelayPolicyInput(
+            host: "video.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data(),
+            sourceAddress: "192.168.5.20"
+        )
+        XCTAssertEqual(policy.evaluate(otherDevice), .allow)
+
+        let unattributed = RelayPolicyInput(host: "video.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(unattributed), .allow)
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block * src=not-a-network")) { error in
+            guard case RelayPolicyCompileError.invalidStatement(let statement, _) = error else {
+                return XCTFail("expected invalidStatement, got \(error)")
+            }
+            XCTAssertEqual(statement, 1)
+        }
+    }
+
+    /// Verifies IPv6 source CIDRs match by prefix and scoped literals parse.
+    func testSourceCIDRMatchesIPv6Prefixes() throws {
+        let cidr = try XCTUnwrap(RelaySourceCIDR("fd00::/8"))
+        XCTAssertTrue(cidr.contains("fd12:3456::1"))
+        XCTAssertTrue(cidr.contains("fd00::1%en0"))
+        XCTAssertFalse(cidr.contains("fe80::1"))
+        XCTAssertFalse(cidr.contains("192.168.4.20"))
+    }
+
+    /// Verifies the public-suffix option flags wildcards spanning an entire suffix without
+    /// changing evaluation, and stays silent when the option is off.
+    func testPublicSuffixOptionWarnsOnSuffixWideWildcards() throws {
+        let options = RelayPolicyCompiler.Options(publicSuffixList: .embedded)
+        let policy = try RelayPolicyCompiler.compile(
+            """
+            block *.co.uk
+            allow *.example.com
+            """,
+            options: options
+        )
+
+        XCTAssertEqual(policy.warnings.count, 1)
+        XCTAssertTrue(policy.warnings[0].hasPrefix("statement 1:"))
+        XCTAssertTrue(policy.warnings[0].contains("*.co.uk"))
+
+        let blocked = RelayPolicyInput(host: "shop.example.co.uk", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(blocked), .block)
+
+        let silent = try RelayPolicyCompiler.compile("block *.co.uk")
+        XCTAssertTrue(silent.warnings.isEmpty)
+    }
+
+    /// Verifies unicode rules and punycode SNI hosts fold to the same canonical form, so
+    /// either representation in the policy matches either representation on the wire.
+    func testHostMatchingNormalizesIDNRepresentations() throws {
+        let unicodeRule = try RelayPolicyCompiler.compile("block bücher.example")
+        let punycodeHost = RelayPolicyInput(
+            host: "xn--bcher-kva.example",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data()
+        )
+        XCTAssertEqual(unicodeRule.evaluate(punycodeHost), .block)
+
+        let punycodeRule = try RelayPolicyCompiler.compile("block *.xn--bcher-kva.example")
+        let unicodeHost = RelayPolicyInput(
+            host: "shop.Bücher.Example.",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data()
+        )
+        XCTAssertEqual(punycodeRule.evaluate(unicodeHost), .block)
+    }
+
+    /// Verifies dry-run evaluation reports the winning statement and action without side effects.
+    func testExplainReportsWinningStatementAndAction() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            """
+            allow tcp api.example.com:443 resolver=doh-primary
+            block *.example.com
+            shape * burst=4096
+            """
+        )
+
+        let pinned = policy.explain(
+            RelayPolicyInput(host: "api.example.com", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        )
+        XCTAssertEqual(pinned.matchedStatement, 1)
+        XCTAssertEqual(pinned.verdict, .allow)
+        XCTAssertEqual(pinned.resolverTag, "doh-primary")
+
+        let blocked = policy.explain(
+            RelayPolicyInput(host: "cdn.example.com", port: 80, transport: "tcp", firstPayloadSnippet: Data())
+        )
+        XCTAssertEqual(blocked.matchedStatement, 2)
+        XCTAssertEqual(blocked.verdict, .block)
+        XCTAssertNil(blocked.resolverTag)
+        XCTAssertEqual(blocked.matchedRule?.hostPattern, "*.example.com")
+
+        let shaped = policy.explain(
+            RelayPolicyInput(host: "other.net", port: 443, transport: "udp", firstPayloadSnippet: Data())
+        )
+        XCTAssertEqual(shaped.matchedStatement, 3)
+        XCTAssertEqual(shaped.verdict, .shape(maxBurstBytes: 4_096))
+    }
+
+    /// Verifies an unmatched probe reports the implicit allow with no winning statement.
+    func testExplainReportsImplicitAllowWhenNothingMatches() throws {
+        let policy = try RelayPolicyCompiler.compile("block *.tiktok.com")
+
+        let result = policy.explain(
+            RelayPolicyInput(host: "example.org", port: 443, transport: "tcp", firstPayloadSnippet: Data())
+        )
+        XCTAssertNil(result.matchedStatement)
+        XCTAssertNil(result.matchedRule)
+        XCTAssertEqual(result.verdict, .allow)
+        XCTAssertNil(result.resolverTag)
+    }
+
+    /// Verifies wildcard patterns match the bare domain and subdomains but not lookalike suffixes.
+    func testWildcardHostMatching() throws {
+        let policy = try RelayPolicyCompiler.compile("block *.tiktok.com")
+
+        XCTAssertEqual(policy.evaluate(input(host: "tiktok.com")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "video.tiktok.com")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "nottiktok.com")), .allow)
+
+        let ipv6 = try RelayPolicyCompiler.compile("block 2001:db8::1")
+        XCTAssertEqual(ipv6.rules[0].hostPattern, "2001:db8::1")
+        XCTAssertNil(ipv6.rules[0].port)
+    }
+
+    /// Verifies diagnostics name the offending statement and reason.
+    func testDiagnosticsNameStatementAndReason() {
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("allow a.example; drop b.example")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 2, reason: "unknown action 'drop'; expected allow, block, shape, or route")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("shape *.example.com latency=200")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "shape rules require burst=<bytes> or rate=<n>bps|kbps|mbps")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("shape *.example.com rate=2gb")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "rate '2gb' must end in bps, kbps, or mbps")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block a.example:99999")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "port '99999' must be in 0...65535")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block tcp a.example extra")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "expected key=value parameter, found 'extra'")
+            )
+        }
+    }
+
+    /// Verifies geo and ASN selectors resolve destinations through the installed resolver.
+    func testGeoSelectorsResolveAgainstDestination() throws {
+        let options = RelayPolicyCompiler.Options(geoSelectorsEnabled: true)
+        let resolver = StubGeoIPResolver(infoByAddress: [
+            "203.0.113.7": RelayGeoIPInfo(countryCode: "cn", autonomousSystemNumber: 4_134),
+            "198.51.100.9": RelayGeoIPInfo(countryCode: "US", autonomousSystemNumber: 13_335)
+        ])
+        let policy = try RelayPolicyCompiler.compile("block geo:CN; shape asn:13335 burst=2048", options: options)
+            .withGeoIPResolver(resolver)
+
+        XCTAssertEqual(policy.rules[0].geoSelector, .country("CN"))
+        XCTAssertNil(policy.rules[0].hostPattern)
+        XCTAssertEqual(policy.rules[1].geoSelector, .asn(13_335))
+
+        XCTAssertEqual(policy.evaluate(input(host: "203.0.113.7")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "198.51.100.9")), .shape(maxBurstBytes: 2_048))
+        XCTAssertEqual(policy.evaluate(input(host: "unattributed.example")), .allow)
+    }
+
+    /// Verifies geo rules never match without a resolver and stay gated behind the compile option.
+    func testGeoSelectorsRequireResolverAndFeatureFlag() throws {
+        let options = RelayPolicyCompiler.Options(geoSelectorsEnabled: true)
+        let withoutResolver = try RelayPolicyCompiler.compile("block geo:CN", options: options)
+        XCTAssertEqual(withoutResolver.evaluate(input(host: "203.0.113.7")), .allow)
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block geo:CN")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "selector 'geo:CN' requires the geoSelectorsEnabled compile option")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block geo:CHN", options: options)) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "geo selector 'geo:CHN' must use a two-letter country code such as geo:CN")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block asn:cloudflare", options: options)) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(
+                    statement: 1,
+                    reason: "asn selector 'asn:cloudflare' must use a numeric autonomous system number such as asn:13335"
+                )
+            )
+        }
+    }
+
+    /// Verifies the encrypted-dns selector blocks DoT ports and known DoH resolvers only.
+    func testEncryptedDNSSelectorBlocksResolverBypass() throws {
+        let policy = try RelayPolicyCompiler.compile("block encrypted-dns")
+
+        XCTAssertEqual(policy.rules[0].builtinSelector, .encryptedDNS)
+        XCTAssertNil(policy.rules[0].hostPattern)
+
+        XCTAssertEqual(policy.evaluate(input(host: "dns.google")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "1.1.1.1")), .block)
+        let dot = RelayPolicyInput(host: "resolver.example", port: 853, transport: "tcp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(dot), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "www.example.com")), .allow)
+    }
+
+    /// Verifies the os-critical selector exempts NTP and OS probe hosts from later rules.
+    func testOSCriticalSelectorPinsProbeExemption() throws {
+        let policy = try RelayPolicyCompiler.compile("allow os-critical; block *")
+
+        XCTAssertEqual(policy.rules[0].builtinSelector, .osCritical)
+        XCTAssertNil(policy.rules[0].hostPattern)
+
+        XCTAssertEqual(policy.evaluate(input(host: "time.apple.com")), .allow)
+        XCTAssertEqual(policy.evaluate(input(host: "captive.apple.com")), .allow)
+        let ntp = RelayPolicyInput(host: "203.0.113.9", port: 123, transport: "udp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(ntp), .allow)
+        XCTAssertEqual(policy.evaluate(input(host: "www.example.com")), .block)
+    }
+
+    /// Verifies the class:p2p selector matches BitTorrent payload signatures and nothing else.
+    func testP2PClassSelectorMatchesBitTorrentSignatures() throws {
+        let policy = try RelayPolicyCompiler.compile("block class:p2p")
+
+        XCTAssertEqual(policy.rules[0].builtinSelector, .p2p)
+        XCTAssertNil(policy.rules[0].hostPattern)
+        XCTAssertEqual(policy.rules[0].selectorDescription, "class:p2p")
+
+        var handshake = Data([0x13])
+        handshake.append(Data("BitTorrent protocol".utf8))
+        handshake.append(Data(repeating: 0, count: 48))
+        let peer = RelayPolicyInput(host: "203.0.113.9", port: 51_413, transport: "tcp", firstPayloadSnippet: handshake)
+        XCTAssertEqual(policy.evaluate(peer), .block)
+
+        let dht = RelayPolicyInput(
+            host: "203.0.113.9",
+            port: 6_881,
+            transport: "udp",
+            firstPayloadSnippet: Data("d1:ad2:id20:abcdefghij0123456789e1:q4:ping1:t2:aa1:y1:qe".utf8)
+        )
+        XCTAssertEqual(policy.evaluate(dht), .block)
+
+        XCTAssertEqual(policy.evaluate(input(host: "www.example.com")), .allow)
+        let tls = RelayPolicyInput(
+            host: "www.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data([0x16, 0x03, 0x01, 0x02, 0x00] + [UInt8](repeating: 0, count: 32))
+        )
+        XCTAssertEqual(policy.evaluate(tls), .allow)
+    }
+
+    /// Verifies unknown traffic classes fail compilation instead of silently never matching.
+    func testUnknownTrafficClassFailsCompilation() {
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block class:gaming")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(
+                    statement: 1,
+                    reason: "unknown traffic class 'class:gaming'; the only supported class selector is class:p2p"
+                )
+            )
+        }
+    }
+
+    /// Verifies the protectOSCriticalEndpoints option exempts OS-critical flows from every
+    /// admission rule and from resolution delays without a document change.
+    func testProtectOSCriticalEndpointsOptionBypassesDocument() throws {
+        let options = RelayPolicyCompiler.Options(protectOSCriticalEndpoints: true)
+        let policy = try RelayPolicyCompiler.compile(
+            "block *.apple.com; delay-dns *.apple.com latency=2000; shape * burst=4096",
+            options: options
+        )
+
+        XCTAssertTrue(policy.protectsOSCriticalEndpoints)
+        XCTAssertEqual(policy.evaluate(input(host: "captive.apple.com")), .allow)
+        let ntp = RelayPolicyInput(host: "pool.ntp.org", port: 123, transport: "udp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.evaluate(ntp), .allow)
+        XCTAssertEqual(policy.evaluate(input(host: "music.apple.com")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "www.example.com")), .shape(maxBurstBytes: 4_096))
+
+        XCTAssertNil(policy.dnsResponseDelayMilliseconds(input(host: "captive.apple.com")))
+        XCTAssertEqual(policy.dnsResponseDelayMilliseconds(input(host: "music.apple.com")), 2_000)
+
+        // The exemption wins before any statement, so explain reports the implicit allow.
+        let explained = policy.explain(input(host: "time.apple.com"))
+        XCTAssertNil(explained.matchedStatement)
+        XCTAssertEqual(explained.verdict, .allow)
+
+        let unprotected = try RelayPolicyCompiler.compile("block *.apple.com")
+        XCTAssertEqual(unprotected.evaluate(input(host: "captive.apple.com")), .block)
+    }
+
+    /// Verifies the ech modifier keeps a rule dormant at connect time and fires it only on
+    /// re-evaluation after ClientHello inspection reports ECH.
+    func testECHModifierMatchesOnlyInspectedFlows() throws {
+        let policy = try RelayPolicyCompiler.compile("block ech *.example.com; allow *")
+
+        XCTAssertTrue(policy.rules[0].requiresECH)
+        XCTAssertEqual(policy.rules[0].hostPattern, "*.example.com")
+
+        XCTAssertEqual(policy.evaluate(input(host: "sub.example.com")), .allow)
+        let inspected = RelayPolicyInput(
+            host: "sub.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data(),
+            echDetected: true
+        )
+        XCTAssertEqual(policy.evaluate(inspected), .block)
+
+        XCTAssertTrue(policy.shouldInspectClientHello(input(host: "sub.example.com")))
+        XCTAssertFalse(policy.shouldInspectClientHello(input(host: "other.net")))
+    }
+
+    /// Verifies the ja3 selector requests inspection, matches only the fingerprint it names,
+    /// and rejects digests that are not 32 hex characters.
+    func testJA3SelectorMatchesInspectedFingerprint() throws {
+        let digest = "771d538b9a4e0fc0c1f2b3a4d5e6f708"
+        let policy = try RelayPolicyCompiler.compile("block ja3:\(digest); allow *")
+
+        XCTAssertEqual(policy.rules[0].ja3Selector, digest)
+        XCTAssertNil(policy.rules[0].hostPattern)
+
+        XCTAssertEqual(policy.evaluate(input(host: "sub.example.com")), .allow)
+        let matching = RelayPolicyInput(
+            host: "sub.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data(),
+            ja3: digest.uppercased()
+        )
+        XCTAssertEqual(policy.evaluate(matching), .block)
+        let other = RelayPolicyInput(
+            host: "sub.example.com",
+            port: 443,
+            transport: "tcp",
+            firstPayloadSnippet: Data(),
+            ja3: String(repeating: "0", count: 32)
+        )
+        XCTAssertEqual(policy.evaluate(other), .allow)
+
+        XCTAssertTrue(policy.shouldInspectClientHello(input(host: "anything.net")))
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block ja3:abc123")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "ja3 selector 'ja3:abc123' must use a 32-character hex MD5 digest")
+            )
+        }
+    }
+
+    /// Verifies the route action stays behind its feature flag, requires via=<tag>, and
+    /// produces route verdicts for matched flows only.
+    func testRouteActionCompilesBehindFeatureFlag() throws {
+        let options = RelayPolicyCompiler.Options(routeActionsEnabled: true)
+        let policy = try RelayPolicyCompiler.compile("route *.example.com via=proxy-a; allow *", options: options)
+
+        XCTAssertEqual(policy.rules[0].action, .route(tag: "proxy-a"))
+        XCTAssertEqual(policy.evaluate(input(host: "cdn.example.com")), .route(tag: "proxy-a"))
+        XCTAssertEqual(policy.evaluate(input(host: "other.net")), .allow)
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("route *.example.com via=proxy-a")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "action 'route' requires the routeActionsEnabled compile option")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("route *.example.com", options: options)) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "route rules require via=<tag> naming an upstream transport")
+            )
+        }
+    }
+
+    /// Verifies resolver parameters compile on non-block rules and dispatch through the
+    /// first-match resolver query, while block rules reject them.
+    func testResolverParameterSelectsNamedResolver() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            "allow *.internal.example resolver=corp-static; block tracker.example; allow *"
+        )
+
+        XCTAssertEqual(policy.rules[0].resolverTag, "corp-static")
+        XCTAssertEqual(policy.resolverTag(input(host: "db.internal.example")), "corp-static")
+        XCTAssertNil(policy.resolverTag(input(host: "tracker.example")))
+        XCTAssertNil(policy.resolverTag(input(host: "other.net")))
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block tracker.example resolver=corp-static")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "block rules never resolve and take no resolver parameter")
+            )
+        }
+        let routed = try RelayPolicyCompiler.compile(
+            "route *.example.com via=proxy-a resolver=doh-primary",
+            options: RelayPolicyCompiler.Options(routeActionsEnabled: true)
+        )
+        XCTAssertEqual(routed.resolverTag(input(host: "cdn.example.com")), "doh-primary")
+    }
+
+    /// Verifies nodelay parameters compile on non-block rules, dispatch through the
+    /// first-match query, and reject unknown values and block rules.
+    func testNoDelayParameterControlsNaglePerRule() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            "allow ssh.example:22 nodelay=on; allow *.backup.example nodelay=off; block tracker.example; allow *"
+        )
+
+        XCTAssertEqual(policy.rules[0].noDelay, true)
+        XCTAssertEqual(policy.rules[1].noDelay, false)
+        XCTAssertEqual(
+            policy.noDelay(RelayPolicyInput(host: "ssh.example", port: 22, transport: "tcp", firstPayloadSnippet: Data())),
+            true
+        )
+        XCTAssertEqual(policy.noDelay(input(host: "vault.backup.example")), false)
+        XCTAssertNil(policy.noDelay(input(host: "tracker.example")))
+        XCTAssertNil(policy.noDelay(input(host: "other.net")))
+
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("block tracker.example nodelay=on")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "block rules never dial and take no nodelay parameter")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("allow ssh.example nodelay=sometimes")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "nodelay parameter must be on or off, found 'sometimes'")
+            )
+        }
+    }
+
+    /// Verifies `delay-dns` rules delay resolution for matching names without touching
+    /// admission verdicts, so data flows to the same hosts stay unshadowed.
+    func testDelayDNSRulesDelayResolutionOnly() throws {
+        let policy = try RelayPolicyCompiler.compile(
+            "delay-dns *.slow.example latency=300; block cdn.slow.example; shape * burst=4096"
+        )
+
+        XCTAssertEqual(policy.rules[0].action, .delayDNS(latencyMs: 300))
+        let query = RelayPolicyInput(host: "api.slow.example", port: 53, transport: "udp", firstPayloadSnippet: Data())
+        XCTAssertEqual(policy.dnsResponseDelayMilliseconds(query), 300)
+        XCTAssertNil(policy.dnsResponseDelayMilliseconds(
+            RelayPolicyInput(host: "other.net", port: 53, transport: "udp", firstPayloadSnippet: Data())
+        ))
+
+        // The delay rule matches first but never wins admission: later rules still decide.
+        XCTAssertEqual(policy.evaluate(input(host: "cdn.slow.example")), .block)
+        XCTAssertEqual(policy.evaluate(input(host: "api.slow.example")), .shape(maxBurstBytes: 4_096))
+        XCTAssertEqual(policy.explain(input(host: "api.slow.example")).matchedStatement, 3)
+    }
+
+    /// Verifies delay-dns compile diagnostics: latency is required and dial-time
+    /// parameters are rejected.
+    func testDelayDNSCompileDiagnostics() {
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("delay-dns *.slow.example")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "delay-dns rules require latency=<ms> naming the response hold time")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("delay-dns *.slow.example burst=4096")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "delay-dns rules take a required latency=<ms> parameter, found 'burst=4096'")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("delay-dns *.slow.example latency=300 resolver=doh")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "delay-dns rules act on resolution timing and take no resolver parameter")
+            )
+        }
+        XCTAssertThrowsError(try RelayPolicyCompiler.compile("delay-dns *.slow.example latency=300 nodelay=on")) { error in
+            XCTAssertEqual(
+                error as? RelayPolicyCompileError,
+                .invalidStatement(statement: 1, reason: "delay-dns rules act on resolution timing and take no nodelay parameter")
+            )
+        }
+    }
+
+    private func input(host: String) -> RelayPolicyInput {
+        RelayPolicyInput(host: host, port: 443, transport: "tcp", firstPayloadSnippet: Data())
+    }
+}
+
+private struct StubGeoIPResolver: RelayGeoIPResolver {
+    let infoByAddress: [String: RelayGeoIPInfo]
+
+    func lookup(address: String) -> RelayGeoIPInfo? {
+        infoByAddress[address]
+    }
+}
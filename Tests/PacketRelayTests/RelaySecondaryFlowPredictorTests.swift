@@ -0,0 +1,119 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// ALG secondary-flow prediction tests: FTP and SDP announcement parsing, one-shot
+/// consumption, and expectation expiry.
+final class RelaySecondaryFlowPredictorTests: XCTestCase {
+    /// Verifies an active-mode PORT command registers the client-announced endpoint once.
+    func testFTPPortCommandRegistersDataFlow() {
+        let predictor = RelaySecondaryFlowPredictor()
+
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .clientToServer,
+            payload: Data("PORT 192,168,1,20,7,138\r\n".utf8)
+        )
+
+        XCTAssertTrue(predictor.consume(host: "192.168.1.20", port: 1_930))
+        XCTAssertFalse(predictor.consume(host: "192.168.1.20", port: 1_930))
+    }
+
+    /// Verifies a passive-mode 227 reply registers the server-announced endpoint and that
+    /// the unspecified address falls back to the control flow's host.
+    func testFTPPassiveReplyRegistersDataFlow() {
+        let predictor = RelaySecondaryFlowPredictor()
+
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .serverToClient,
+            payload: Data("227 Entering Passive Mode (203,0,113,9,195,80)\r\n".utf8)
+        )
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .serverToClient,
+            payload: Data("227 Entering Passive Mode (0,0,0,0,8,0)\r\n".utf8)
+        )
+
+        XCTAssertTrue(predictor.consume(host: "203.0.113.9", port: 50_000))
+        XCTAssertTrue(predictor.consume(host: "ftp.example.com", port: 2_048))
+    }
+
+    /// Verifies SDP media lines register the media port and its RTCP companion against the
+    /// connection-line address.
+    func testSDPMediaLinesRegisterRTPAndRTCPPorts() {
+        let predictor = RelaySecondaryFlowPredictor()
+        let invite = """
+        INVITE sip:bob@example.com SIP/2.0\r
+        Content-Type: application/sdp\r
+        \r
+        v=0\r
+        c=IN IP4 198.51.100.7\r
+        m=audio 49170 RTP/AVP 0\r
+        m=video 51372 RTP/AVP 31\r
+        """
+
+        predictor.observeControlPayload(
+            controlHost: "sip.example.com",
+            controlPort: 5_060,
+            direction: .clientToServer,
+            payload: Data(invite.utf8)
+        )
+
+        XCTAssertTrue(predictor.consume(host: "198.51.100.7", port: 49_170))
+        XCTAssertTrue(predictor.consume(host: "198.51.100.7", port: 49_171))
+        XCTAssertTrue(predictor.consume(host: "198.51.100.7", port: 51_372))
+        XCTAssertTrue(predictor.consume(host: "198.51.100.7", port: 51_373))
+        XCTAssertFalse(predictor.consume(host: "198.51.100.7", port: 49_172))
+    }
+
+    /// Verifies payloads on unrecognized control ports and malformed announcements register nothing.
+    func testMalformedAndUnrecognizedPayloadsRegisterNothing() {
+        let predictor = RelaySecondaryFlowPredictor()
+
+        predictor.observeControlPayload(
+            controlHost: "web.example.com",
+            controlPort: 80,
+            direction: .clientToServer,
+            payload: Data("PORT 192,168,1,20,7,138\r\n".utf8)
+        )
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .clientToServer,
+            payload: Data("PORT 999,168,1,20,7,138\r\n".utf8)
+        )
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .clientToServer,
+            payload: Data("RETR file.bin\r\n".utf8)
+        )
+
+        XCTAssertEqual(predictor.expectationCount, 0)
+    }
+
+    /// Verifies expectations lapse after the TTL so an old announcement cannot authorize a
+    /// late dial.
+    func testExpectationExpiresAfterTTL() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let predictor = RelaySecondaryFlowPredictor(ttl: 30, now: { currentTime })
+
+        predictor.observeControlPayload(
+            controlHost: "ftp.example.com",
+            controlPort: 21,
+            direction: .serverToClient,
+            payload: Data("227 Entering Passive Mode (203,0,113,9,4,1)\r\n".utf8)
+        )
+        currentTime = currentTime.addingTimeInterval(31)
+
+        XCTAssertFalse(predictor.consume(host: "203.0.113.9", port: 1_025))
+    }
+}
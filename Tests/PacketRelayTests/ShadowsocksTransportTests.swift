@@ -0,0 +1,147 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Shadowsocks AEAD stream codec and outbound wrapper tests.
+final class ShadowsocksTransportTests: XCTestCase {
+    private static let config = ShadowsocksServerConfig(
+        host: "ss.example.net",
+        port: 8_388,
+        password: "ss-password"
+    )
+
+    /// Verifies the master key matches OpenSSL EVP_BytesToKey, the derivation every
+    /// Shadowsocks implementation interoperates on.
+    func testMasterKeyMatchesEVPBytesToKey() {
+        let key = Self.config.masterKey().map { String(format: "%02x", $0) }.joined()
+        XCTAssertEqual(key, "16fd3c7633d0e1d5696a916d80e22b666030910e4f85a67027507013c69a1de7")
+    }
+
+    /// Verifies sealed chunks round-trip through a decryptor fed arbitrary fragment sizes.
+    func testStreamRoundTripAcrossFragmentBoundaries() throws {
+        let masterKey = Self.config.masterKey()
+        var encryptor = ShadowsocksStreamEncryptor(masterKey: masterKey, cipher: .chacha20Poly1305)
+        var decryptor = ShadowsocksStreamDecryptor(masterKey: masterKey, cipher: .chacha20Poly1305)
+
+        let first = Data("hello ".utf8)
+        let second = Data("shadowsocks".utf8)
+        var stream = encryptor.salt
+        stream.append(try encryptor.sealChunks(first))
+        stream.append(try encryptor.sealChunks(second))
+
+        var plaintext = Data()
+        // One-byte feeds exercise salt, length-chunk, and payload-chunk resume points.
+        for byte in stream {
+            plaintext.append(try decryptor.feed(Data([byte])))
+        }
+        XCTAssertEqual(plaintext, first + second)
+    }
+
+    /// Verifies payloads above the 0x3FFF spec limit split into multiple chunks and reassemble.
+    func testOversizedPayloadSplitsIntoSpecChunks() throws {
+        let masterKey = Self.config.masterKey()
+        var encryptor = ShadowsocksStreamEncryptor(masterKey: masterKey, cipher: .aes256GCM)
+        var decryptor = ShadowsocksStreamDecryptor(masterKey: masterKey, cipher: .aes256GCM)
+
+        let payload = Data((0 ..< 0x3FFF + 100).map { UInt8($0 & 0xFF) })
+        var stream = encryptor.salt
+        stream.append(try encryptor.sealChunks(payload))
+
+        XCTAssertEqual(try decryptor.feed(stream), payload)
+    }
+
+    /// Verifies a tampered stream fails closed instead of yielding corrupt plaintext.
+    func testTamperedStreamFailsClosed() throws {
+        let masterKey = Self.config.masterKey()
+        var encryptor = ShadowsocksStreamEncryptor(masterKey: masterKey, cipher: .chacha20Poly1305)
+        var decryptor = ShadowsocksStreamDecryptor(masterKey: masterKey, cipher: .chacha20Poly1305)
+
+        var stream = encryptor.salt
+        stream.append(try encryptor.sealChunks(Data("sensitive".utf8)))
+        stream[stream.index(stream.endIndex, offsetBy: -1)] ^= 0xFF
+
+        XCTAssertThrowsError(try decryptor.feed(stream)) { error in
+            XCTAssertEqual(error as? ShadowsocksStreamError, .malformedStream)
+        }
+    }
+
+    /// Verifies the routing table resolves the built-in tags and lets installed outbounds
+    /// shadow them.
+    func testUpstreamRoutesResolveBuiltInAndInstalledTags() {
+        let routes = RelayUpstreamRoutes(outbounds: [
+            "proxy-a": .shadowsocks(Self.config),
+            "direct": .block
+        ])
+
+        XCTAssertEqual(routes.outbound(forTag: "proxy-a"), .shadowsocks(Self.config))
+        XCTAssertEqual(routes.outbound(forTag: "block"), .block)
+        XCTAssertEqual(routes.outbound(forTag: "direct"), .block)
+        XCTAssertNil(routes.outbound(forTag: "missing"))
+        XCTAssertEqual(RelayUpstreamRoutes.none.outbound(forTag: "direct"), .direct)
+    }
+
+    /// Verifies the outbound wrapper emits the salt and encrypted address header exactly once,
+    /// ahead of the first payload, and decrypts inbound chunks for the relay.
+    func testOutboundWrapperFramesWritesAndDecryptsReads() throws {
+        let inner = ScriptedOutbound()
+        let wrapper = try XCTUnwrap(ShadowsocksTCPOutbound(
+            inner: inner,
+            config: Self.config,
+            destination: .domain("media.example.com"),
+            destinationPort: 443
+        ))
+
+        wrapper.write(Data("first".utf8)) { _ in }
+        wrapper.write(Data("second".utf8)) { _ in }
+        XCTAssertEqual(inner.writes.count, 2)
+
+        var serverSide = ShadowsocksStreamDecryptor(masterKey: Self.config.masterKey(), cipher: Self.config.cipher)
+        let header = Data(try XCTUnwrap(Socks5Codec.encodeAddressPort(.domain("media.example.com"), port: 443)))
+        XCTAssertEqual(try serverSide.feed(inner.writes[0]), header + Data("first".utf8))
+        XCTAssertEqual(try serverSide.feed(inner.writes[1]), Data("second".utf8))
+
+        var serverEncryptor = ShadowsocksStreamEncryptor(masterKey: Self.config.masterKey(), cipher: Self.config.cipher)
+        var response = serverEncryptor.salt
+        response.append(try serverEncryptor.sealChunks(Data("reply".utf8)))
+        inner.queuedReads = [response]
+
+        var received: Data?
+        wrapper.readMinimumLength(1, maximumLength: 65_535) { data, _ in
+            received = data
+        }
+        XCTAssertEqual(received, Data("reply".utf8))
+    }
+}
+
+/// Minimal scripted `Socks5TCPOutbound` that records writes and serves queued reads inline.
+private final class ScriptedOutbound: @unchecked Sendable, Socks5TCPOutbound {
+    private(set) var writes: [Data] = []
+    var queuedReads: [Data] = []
+
+    func waitUntilReady(completionHandler: @escaping @Sendable (Result<Void, Error>) -> Void) {
+        completionHandler(.success(()))
+    }
+
+    func readMinimumLength(_ minimumLength: Int, maximumLength: Int, completionHandler: @escaping @Sendable (Data?, Error?) -> Void) {
+        guard !queuedReads.isEmpty else {
+            completionHandler(nil, nil)
+            return
+        }
+        completionHandler(queuedReads.removeFirst(), nil)
+    }
+
+    func write(_ data: Data, completionHandler: @escaping @Sendable (Error?) -> Void) {
+        writes.append(data)
+        completionHandler(nil)
+    }
+
+    func finishWriting(completionHandler: @escaping @Sendable (Error?) -> Void) {
+        completionHandler(nil)
+    }
+
+    func cancel() {}
+}
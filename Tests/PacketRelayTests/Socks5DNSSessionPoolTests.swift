@@ -0,0 +1,455 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@preconcurrency import NetworkExtension
+@preconcurrency @testable import PacketRelay
+import Observability
+import XCTest
+
+// Docs: https://developer.apple.com/documentation/xctest/xctestcase
+/// Shared DNS resolver session pool tests covering multiplexing and claim routing.
+final class Socks5DNSSessionPoolTests: XCTestCase {
+    /// Verifies queries from multiple associations share one pooled session per resolver
+    /// instead of dialing once per caller.
+    func testQueriesFromMultipleCallersShareOnePooledSession() {
+        let provider = PoolFakeProvider()
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0001)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0002), makeDNSMessage(transactionID: 0x0003)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+
+        XCTAssertEqual(pool.activeSessionCount, 1)
+        XCTAssertEqual(provider.sessions.count, 1)
+        XCTAssertEqual(pool.pendingClaimCount, 3)
+        XCTAssertEqual(provider.sessions.first?.writtenDatagrams.count, 3)
+    }
+
+    /// Verifies responses route back to the issuing caller by DNS transaction ID and
+    /// consume their claim exactly once.
+    func testResponsesRouteBackByTransactionID() throws {
+        let provider = PoolFakeProvider()
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        let firstResponses = ResponseBox()
+        let secondResponses = ResponseBox()
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x00aa)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { firstResponses.append($0) }
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x00bb)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { secondResponses.append($0) }
+        XCTAssertEqual(pool.pendingClaimCount, 2)
+
+        let session = try XCTUnwrap(provider.sessions.first)
+        let response = makeDNSMessage(transactionID: 0x00bb)
+        session.deliverRead(datagram: response)
+
+        XCTAssertEqual(pool.pendingClaimCount, 1)
+        XCTAssertEqual(firstResponses.values, [])
+        XCTAssertEqual(secondResponses.values, [response])
+
+        // A duplicate response has no remaining claim and is dropped.
+        session.deliverRead(datagram: response)
+        XCTAssertEqual(secondResponses.values, [response])
+    }
+
+    /// Verifies two pending queries sharing one transaction ID keep separate claims and
+    /// each response reaches only its issuer, instead of the later claim overwriting the
+    /// earlier one.
+    func testCollidingTransactionIDsAreDisambiguatedByQuestionName() throws {
+        let provider = PoolFakeProvider()
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        let firstResponses = ResponseBox()
+        let secondResponses = ResponseBox()
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x00aa, name: "first.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { firstResponses.append($0) }
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x00aa, name: "second.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { secondResponses.append($0) }
+        XCTAssertEqual(pool.pendingClaimCount, 2)
+
+        let session = try XCTUnwrap(provider.sessions.first)
+        let secondResponse = makeDNSQuery(transactionID: 0x00aa, name: "second.example")
+        session.deliverRead(datagram: secondResponse)
+        XCTAssertEqual(firstResponses.values, [])
+        XCTAssertEqual(secondResponses.values, [secondResponse])
+
+        let firstResponse = makeDNSQuery(transactionID: 0x00aa, name: "first.example")
+        session.deliverRead(datagram: firstResponse)
+        XCTAssertEqual(firstResponses.values, [firstResponse])
+        XCTAssertEqual(secondResponses.values, [secondResponse])
+        XCTAssertEqual(pool.pendingClaimCount, 0)
+    }
+
+    /// Verifies unanswered claims are swept once their timeout elapses.
+    func testExpiredClaimsAreSwept() {
+        let provider = PoolFakeProvider()
+        let clock = PoolTestClock(now: Date(timeIntervalSince1970: 0))
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(claimTimeoutSeconds: 10, idleTimeoutSeconds: 1_000),
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            nowProvider: { clock.now }
+        )
+
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0001)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+        XCTAssertEqual(pool.pendingClaimCount, 1)
+
+        clock.now = clock.now.addingTimeInterval(11)
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0002)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+
+        XCTAssertEqual(pool.pendingClaimCount, 1)
+        XCTAssertEqual(pool.expiredClaimCount, 1)
+    }
+
+    /// Verifies datagrams shorter than a DNS header neither dial nor register claims.
+    func testShortQueriesAreDroppedWithoutDialing() {
+        let provider = PoolFakeProvider()
+        let pool = Socks5DNSSessionPool(logger: StructuredLogger(sink: InMemoryLogSink()))
+
+        pool.send(
+            queries: [Data([0x01, 0x02, 0x03])],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in
+            XCTFail("Short query must not receive a response")
+        }
+
+        XCTAssertEqual(pool.activeSessionCount, 0)
+        XCTAssertEqual(pool.pendingClaimCount, 0)
+        XCTAssertTrue(provider.sessions.isEmpty)
+    }
+
+    /// Verifies a failed pooled write drops the session so the next query redials.
+    func testWriteFailureDropsPooledSession() throws {
+        let provider = PoolFakeProvider()
+        provider.onCreate = { session in
+            session.nextWriteError = PoolTestError.writeFailed
+        }
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0001)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+
+        XCTAssertEqual(pool.activeSessionCount, 0)
+        let failed = try XCTUnwrap(provider.sessions.first)
+        XCTAssertTrue(failed.cancelled)
+
+        provider.onCreate = nil
+        pool.send(
+            queries: [makeDNSMessage(transactionID: 0x0002)],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { _ in }
+        XCTAssertEqual(pool.activeSessionCount, 1)
+        XCTAssertEqual(provider.sessions.count, 2)
+    }
+
+    /// Verifies `delay-dns` policy rules hold matched responses for the configured latency
+    /// while responses for other names keep delivering immediately.
+    func testDelayDNSPolicyHoldsMatchedResponses() throws {
+        let provider = PoolFakeProvider()
+        let policy = try RelayPolicyCompiler.compile("delay-dns *.slow.example latency=100")
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: policy
+        )
+
+        let delayed = ResponseBox()
+        let immediate = ResponseBox()
+        let delayedDelivered = expectation(description: "delayed response delivered")
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x0001, name: "api.slow.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) {
+            delayed.append($0)
+            delayedDelivered.fulfill()
+        }
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x0002, name: "fast.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { immediate.append($0) }
+
+        let session = try XCTUnwrap(provider.sessions.first)
+        let delayedResponse = makeDNSQuery(transactionID: 0x0001, name: "api.slow.example")
+        let immediateResponse = makeDNSQuery(transactionID: 0x0002, name: "fast.example")
+        session.deliverRead(datagram: delayedResponse)
+        session.deliverRead(datagram: immediateResponse)
+
+        // Both claims are consumed right away, but only the unmatched name is delivered yet.
+        XCTAssertEqual(pool.pendingClaimCount, 0)
+        XCTAssertEqual(immediate.values, [immediateResponse])
+        XCTAssertEqual(delayed.values, [])
+
+        wait(for: [delayedDelivered], timeout: 2.0)
+        XCTAssertEqual(delayed.values, [delayedResponse])
+    }
+
+    /// Verifies the ordered-delivery option parks responses behind a `delay-dns` hold so
+    /// per-resolver delivery order survives shaping instead of the direct send overtaking.
+    func testPreserveDeliveryOrderHoldsLaterResponsesBehindDelayedOne() throws {
+        let provider = PoolFakeProvider()
+        let policy = try RelayPolicyCompiler.compile("delay-dns *.slow.example latency=100")
+        let pool = Socks5DNSSessionPool(
+            configuration: .init(sessionsPerResolver: 1, preserveDeliveryOrder: true),
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: policy
+        )
+
+        let delivered = ResponseBox()
+        let bothDelivered = expectation(description: "both responses delivered in order")
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x0001, name: "api.slow.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) { delivered.append($0) }
+        pool.send(
+            queries: [makeDNSQuery(transactionID: 0x0002, name: "fast.example")],
+            to: .ipv4("1.1.1.1"),
+            port: 53,
+            provider: provider
+        ) {
+            delivered.append($0)
+            bothDelivered.fulfill()
+        }
+
+        let session = try XCTUnwrap(provider.sessions.first)
+        let delayedResponse = makeDNSQuery(transactionID: 0x0001, name: "api.slow.example")
+        let directResponse = makeDNSQuery(transactionID: 0x0002, name: "fast.example")
+        session.deliverRead(datagram: delayedResponse)
+        session.deliverRead(datagram: directResponse)
+
+        // The direct response queues behind the held one instead of overtaking it.
+        XCTAssertEqual(pool.pendingClaimCount, 0)
+        XCTAssertEqual(delivered.values, [])
+
+        wait(for: [bothDelivered], timeout: 2.0)
+        XCTAssertEqual(delivered.values, [delayedResponse, directResponse])
+    }
+
+    /// Builds a DNS query carrying one question for the given name.
+    /// - Parameters:
+    ///   - transactionID: Big-endian transaction ID placed in the first two bytes.
+    ///   - name: Question name encoded as uncompressed labels with an A/IN question footer.
+    /// - Returns: Parseable single-question DNS query.
+    private func makeDNSQuery(transactionID: UInt16, name: String) -> Data {
+        var message = makeDNSMessage(transactionID: transactionID)
+        message[5] = 1
+        for label in name.split(separator: ".") {
+            message.append(UInt8(label.utf8.count))
+            message.append(contentsOf: Array(label.utf8))
+        }
+        message.append(contentsOf: [0, 0, 1, 0, 1])
+        return message
+    }
+
+    /// Builds a minimal 12-byte DNS message carrying only the given transaction ID.
+    /// - Parameter transactionID: Big-endian transaction ID placed in the first two bytes.
+    /// - Returns: Header-only DNS message.
+    private func makeDNSMessage(transactionID: UInt16) -> Data {
+        var message = Data(count: 12)
+        message[0] = UInt8(transactionID >> 8)
+        message[1] = UInt8(transactionID & 0xff)
+        return message
+    }
+}
+
+private final class ResponseBox: @unchecked Sendable {
+    private let lock = NSLock()
+    private var storedValues: [Data] = []
+
+    var values: [Data] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedValues
+    }
+
+    func append(_ value: Data) {
+        lock.lock()
+        storedValues.append(value)
+        lock.unlock()
+    }
+}
+
+private final class PoolTestClock: @unchecked Sendable {
+    var now: Date
+
+    init(now: Date) {
+        self.now = now
+    }
+}
+
+private final class PoolFakeProvider: Socks5ConnectionProvider, @unchecked Sendable {
+    private let lock = NSLock()
+    private var storedSessions: [PoolFakeSession] = []
+    private var storedOnCreate: ((PoolFakeSession) -> Void)?
+
+    var onCreate: ((PoolFakeSession) -> Void)? {
+        get {
+            lock.lock()
+            defer { lock.unlock() }
+            return storedOnCreate
+        }
+        set {
+            lock.lock()
+            storedOnCreate = newValue
+            lock.unlock()
+        }
+    }
+
+    var sessions: [PoolFakeSession] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedSessions
+    }
+
+    func makeUDPSession(to _: NWHostEndpoint) -> Socks5UDPSession {
+        let session = PoolFakeSession()
+        let onCreate: ((PoolFakeSession) -> Void)?
+        lock.lock()
+        storedSessions.append(session)
+        onCreate = storedOnCreate
+        lock.unlock()
+        onCreate?(session)
+        return session
+    }
+}
+
+private final class PoolFakeSession: Socks5UDPSession, @unchecked Sendable {
+    private let lock = NSLock()
+    private var readHandler: ((Data?, Error?) -> Void)?
+    private var storedCancelled = false
+    private var storedWrittenDatagrams: [Data] = []
+    private var storedNextWriteError: Error?
+
+    var cancelled: Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedCancelled
+    }
+
+    var writtenDatagrams: [Data] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedWrittenDatagrams
+    }
+
+    var nextWriteError: Error? {
+        get {
+            lock.lock()
+            defer { lock.unlock() }
+            return storedNextWriteError
+        }
+        set {
+            lock.lock()
+            storedNextWriteError = newValue
+            lock.unlock()
+        }
+    }
+
+    var eventHandler: ((Socks5UDPSessionEvent) -> Void)?
+
+    func setReadHandler(_ handler: @escaping (Data?, Error?) -> Void) {
+        lock.lock()
+        readHandler = handler
+        lock.unlock()
+    }
+
+    func writeDatagram(_ datagram: Data, completionHandler: @escaping (Error?) -> Void) {
+        let error: Error?
+        lock.lock()
+        storedWrittenDatagrams.append(datagram)
+        if let nextWriteError = storedNextWriteError {
+            storedNextWriteError = nil
+            error = nextWriteError
+        } else {
+            error = nil
+        }
+        lock.unlock()
+        completionHandler(error)
+    }
+
+    func writeDatagrams(_ datagrams: [Data], completionHandler: @escaping (Error?) -> Void) {
+        for datagram in datagrams {
+            writeDatagram(datagram, completionHandler: completionHandler)
+        }
+    }
+
+    func restart() {}
+
+    func cancel() {
+        lock.lock()
+        storedCancelled = true
+        lock.unlock()
+    }
+
+    func deliverRead(datagram: Data?, error: Error? = nil) {
+        lock.lock()
+        let handler = readHandler
+        lock.unlock()
+        handler?(datagram, error)
+    }
+}
+
+private enum PoolTestError: LocalizedError {
+    case writeFailed
+}
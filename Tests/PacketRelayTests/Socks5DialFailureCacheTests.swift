@@ -0,0 +1,166 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Negative dial-failure cache TTL and eviction tests.
+final class Socks5DialFailureCacheTests: XCTestCase {
+    /// Verifies a recorded failure suppresses the destination until the TTL lapses.
+    func testFailureSuppressesDestinationUntilTTLExpires() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 5, now: { currentTime })
+
+        XCTAssertFalse(cache.isSuppressed(host: "media.example.com", port: 443))
+        cache.recordFailure(host: "media.example.com", port: 443)
+        XCTAssertTrue(cache.isSuppressed(host: "media.example.com", port: 443))
+        XCTAssertTrue(cache.isSuppressed(host: "MEDIA.example.com", port: 443))
+        XCTAssertFalse(cache.isSuppressed(host: "media.example.com", port: 80))
+
+        currentTime = currentTime.addingTimeInterval(6)
+        XCTAssertFalse(cache.isSuppressed(host: "media.example.com", port: 443))
+    }
+
+    /// Verifies a successful dial clears suppression before the TTL lapses.
+    func testSuccessClearsSuppressionImmediately() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 30, now: { currentTime })
+
+        cache.recordFailure(host: "media.example.com", port: 443)
+        XCTAssertTrue(cache.isSuppressed(host: "media.example.com", port: 443))
+
+        currentTime = currentTime.addingTimeInterval(1)
+        cache.recordSuccess(host: "media.example.com", port: 443)
+        XCTAssertFalse(cache.isSuppressed(host: "media.example.com", port: 443))
+    }
+
+    /// Verifies a path change wipes the whole cache: old-path failures say nothing about
+    /// reachability on the new path.
+    func testRemoveAllClearsEveryDestination() {
+        let cache = Socks5DialFailureCache(ttl: 3_600, now: { Date() })
+        cache.recordFailure(host: "one.example", port: 443)
+        cache.recordFailure(host: "two.example", port: 80)
+
+        cache.removeAll()
+
+        XCTAssertFalse(cache.isSuppressed(host: "one.example", port: 443))
+        XCTAssertFalse(cache.isSuppressed(host: "two.example", port: 80))
+    }
+
+    /// Verifies the retry-after hint tracks the remaining suppression window, compounds per
+    /// consecutive failure, and clears with the failure state.
+    func testRetryAfterHintCompoundsWithConsecutiveFailures() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 5, now: { currentTime })
+
+        XCTAssertNil(cache.retryAfterMilliseconds(host: "media.example.com", port: 443))
+
+        cache.recordFailure(host: "media.example.com", port: 443)
+        let first = cache.retryAfterMilliseconds(host: "media.example.com", port: 443) ?? 0
+        // One failure: the full 5 s window with up to 20% jitter either way.
+        XCTAssertGreaterThanOrEqual(first, 4_000)
+        XCTAssertLessThanOrEqual(first, 6_000)
+
+        cache.recordFailure(host: "media.example.com", port: 443)
+        let second = cache.retryAfterMilliseconds(host: "MEDIA.example.com", port: 443) ?? 0
+        // Two consecutive failures double the base before jitter.
+        XCTAssertGreaterThanOrEqual(second, 8_000)
+        XCTAssertLessThanOrEqual(second, 12_000)
+
+        currentTime = currentTime.addingTimeInterval(6)
+        XCTAssertNil(cache.retryAfterMilliseconds(host: "media.example.com", port: 443))
+
+        cache.recordFailure(host: "media.example.com", port: 443)
+        cache.recordSuccess(host: "media.example.com", port: 443)
+        XCTAssertNil(cache.retryAfterMilliseconds(host: "media.example.com", port: 443))
+    }
+
+    /// Verifies long failure streaks keep the hint below the documented ceiling.
+    func testRetryAfterHintStaysCapped() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 5, now: { currentTime })
+
+        for _ in 0 ..< 20 {
+            currentTime = currentTime.addingTimeInterval(1)
+            cache.recordFailure(host: "media.example.com", port: 443)
+        }
+
+        let hint = cache.retryAfterMilliseconds(host: "media.example.com", port: 443) ?? 0
+        XCTAssertGreaterThan(hint, 0)
+        XCTAssertLessThanOrEqual(hint, 60_000)
+    }
+
+    /// Verifies export carries remaining lifetimes and streaks, and import pre-seeds a fresh
+    /// cache without granting suppressions a new full TTL.
+    func testExportImportRoundTripKeepsSuppressionAndStreak() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 10, now: { currentTime })
+        cache.recordFailure(host: "down.example", port: 443)
+        cache.recordFailure(host: "down.example", port: 443)
+
+        currentTime = currentTime.addingTimeInterval(4)
+        let exported = cache.exportSnapshot()
+        XCTAssertEqual(exported.count, 1)
+        XCTAssertEqual(exported.first?.host, "down.example")
+        XCTAssertEqual(exported.first?.remainingSuppressionMilliseconds, 6_000)
+        XCTAssertEqual(exported.first?.consecutiveFailures, 2)
+
+        var restartedTime = Date(timeIntervalSinceReferenceDate: 1_000)
+        let restarted = Socks5DialFailureCache(ttl: 10, now: { restartedTime })
+        XCTAssertEqual(restarted.importEntries(exported), 1)
+        XCTAssertTrue(restarted.isSuppressed(host: "down.example", port: 443))
+
+        // The seed lapses when its remaining lifetime runs out, not one full TTL later.
+        restartedTime = restartedTime.addingTimeInterval(7)
+        XCTAssertFalse(restarted.isSuppressed(host: "down.example", port: 443))
+    }
+
+    /// Verifies import skips lapsed entries and never overwrites live failure state.
+    func testImportKeepsLiveEntriesAndSkipsLapsedOnes() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 10, now: { currentTime })
+        cache.recordFailure(host: "down.example", port: 443)
+
+        let accepted = cache.importEntries([
+            Socks5DialFailureCache.ExportEntry(
+                host: "DOWN.example",
+                port: 443,
+                remainingSuppressionMilliseconds: 1,
+                consecutiveFailures: 9
+            ),
+            Socks5DialFailureCache.ExportEntry(
+                host: "lapsed.example",
+                port: 443,
+                remainingSuppressionMilliseconds: 0,
+                consecutiveFailures: 3
+            )
+        ])
+
+        XCTAssertEqual(accepted, 0)
+        // The live entry kept its own window instead of the seed's 1 ms remainder.
+        currentTime = currentTime.addingTimeInterval(5)
+        XCTAssertTrue(cache.isSuppressed(host: "down.example", port: 443))
+        XCTAssertFalse(cache.isSuppressed(host: "lapsed.example", port: 443))
+    }
+
+    /// Verifies the entry cap evicts the earliest-expiring destination instead of growing
+    /// without bound when many destinations fail at once.
+    func testEntryCapEvictsEarliestExpiringDestination() {
+        var currentTime = Date(timeIntervalSinceReferenceDate: 0)
+        let cache = Socks5DialFailureCache(ttl: 3_600, now: { currentTime })
+
+        cache.recordFailure(host: "first-failure.example", port: 443)
+        for index in 1 ..< 512 {
+            currentTime = currentTime.addingTimeInterval(0.001)
+            cache.recordFailure(host: "host-\(index).example", port: 443)
+        }
+        currentTime = currentTime.addingTimeInterval(0.001)
+        cache.recordFailure(host: "overflow.example", port: 443)
+
+        XCTAssertFalse(cache.isSuppressed(host: "first-failure.example", port: 443))
+        XCTAssertTrue(cache.isSuppressed(host: "overflow.example", port: 443))
+        XCTAssertTrue(cache.isSuppressed(host: "host-511.example", port: 443))
+    }
+}
@@ -0,0 +1,193 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+import Network
+@preconcurrency import NetworkExtension
+import Observability
+@testable import PacketRelay
+import XCTest
+
+/// Echo dial provider tests: full relay traversal with no host or network involvement.
+final class Socks5EchoProviderTests: XCTestCase {
+    /// Verifies a complete SOCKS5 handshake, CONNECT, and bidirectional pump run
+    /// against the internal echo so client bytes come back unchanged.
+    func testConnectRelayEchoesClientBytes() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.echo-connect")
+        let inbound = EchoTestInboundConnection()
+        let provider = Socks5EchoConnectionProvider()
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "echo.internal", port: 7))
+        }
+
+        try eventually("CONNECT success reply") {
+            inbound.sentPayloads.count >= 2 && inbound.sentPayloads[1].count >= 2 &&
+                inbound.sentPayloads[1][1] == 0x00
+        }
+        XCTAssertEqual(provider.tcpOutbounds.count, 1)
+
+        let payload = Data([0x68, 0x65, 0x6C, 0x6C, 0x6F])
+        queue.sync {
+            inbound.push(payload)
+        }
+
+        try eventually("echoed payload") {
+            inbound.sentPayloads.dropFirst(2).contains(payload)
+        }
+        XCTAssertEqual(provider.tcpOutbounds.first?.echoedByteCount, payload.count)
+    }
+
+    /// Verifies the echo outbound honors minimum-length gating and terminates
+    /// reads with nil after the writer finishes.
+    func testEchoOutboundHonorsMinimumLengthAndEndOfStream() {
+        let outbound = Socks5EchoTCPOutbound()
+        let reads = LockedReads()
+
+        outbound.readMinimumLength(4, maximumLength: 16) { data, _ in
+            reads.append(data)
+        }
+        outbound.write(Data([0x01, 0x02])) { _ in }
+        XCTAssertTrue(reads.values.isEmpty, "read completed below its minimum length")
+
+        outbound.write(Data([0x03, 0x04])) { _ in }
+        XCTAssertEqual(reads.values, [Data([0x01, 0x02, 0x03, 0x04])])
+
+        outbound.readMinimumLength(1, maximumLength: 16) { data, _ in
+            reads.append(data)
+        }
+        outbound.finishWriting { _ in }
+        XCTAssertEqual(reads.values, [Data([0x01, 0x02, 0x03, 0x04]), nil])
+    }
+
+    /// Verifies the echo UDP session reflects datagrams through the read handler
+    /// with per-datagram boundaries preserved.
+    func testEchoUDPSessionReflectsDatagrams() {
+        let session = Socks5EchoUDPSession(endpoint: NWHostEndpoint(hostname: "1.1.1.1", port: "53"))
+        let reads = LockedReads()
+        session.setReadHandler { data, _ in
+            reads.append(data)
+        }
+
+        session.writeDatagram(Data([0x0A])) { _ in }
+        session.writeDatagram(Data([0x0B, 0x0C])) { _ in }
+
+        XCTAssertEqual(reads.values, [Data([0x0A]), Data([0x0B, 0x0C])])
+        XCTAssertEqual(session.echoedDatagramCount, 2)
+
+        session.cancel()
+        session.writeDatagram(Data([0x0D])) { _ in }
+        XCTAssertEqual(session.echoedDatagramCount, 2, "cancelled session still echoed")
+    }
+
+    private static let greeting = Data([0x05, 0x01, 0x00])
+
+    private static func connectRequest(host: String, port: UInt16) -> Data {
+        let hostBytes = Array(host.utf8)
+        return Data(
+            [0x05, 0x01, 0x00, 0x03, UInt8(hostBytes.count)] +
+            hostBytes +
+            [UInt8((port >> 8) & 0xFF), UInt8(port & 0xFF)]
+        )
+    }
+
+    private func eventually(
+        _ label: String,
+        timeoutSeconds: TimeInterval = 2,
+        _ condition: () -> Bool
+    ) throws {
+        let deadline = Date().addingTimeInterval(timeoutSeconds)
+        while Date() < deadline {
+            if condition() {
+                return
+            }
+            usleep(10_000)
+        }
+        XCTFail("timed out waiting for \(label)")
+    }
+}
+
+private final class LockedReads: @unchecked Sendable {
+    private let lock = NSLock()
+    private var storedValues: [Data?] = []
+
+    func append(_ value: Data?) {
+        lock.lock()
+        storedValues.append(value)
+        lock.unlock()
+    }
+
+    var values: [Data?] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedValues
+    }
+}
+
+private final class EchoTestInboundConnection: @unchecked Sendable, Socks5InboundConnection {
+    var stateUpdateHandler: (@Sendable (NWConnection.State) -> Void)?
+
+    private let lock = NSLock()
+    private var pendingReceives: [(@Sendable (Data?, NWConnection.ContentContext?, Bool, NWError?) -> Void)] = []
+    private var storedSentPayloads: [Data] = []
+    private var storedCancelled = false
+
+    var sentPayloads: [Data] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedSentPayloads
+    }
+
+    func start(queue _: DispatchQueue) {}
+
+    func receive(
+        minimumIncompleteLength _: Int,
+        maximumLength _: Int,
+        completion: @escaping @Sendable (Data?, NWConnection.ContentContext?, Bool, NWError?) -> Void
+    ) {
+        lock.lock()
+        pendingReceives.append(completion)
+        lock.unlock()
+    }
+
+    func send(content: Data?, completion: NWConnection.SendCompletion) {
+        lock.lock()
+        storedSentPayloads.append(content ?? Data())
+        lock.unlock()
+        if case .contentProcessed(let handler) = completion {
+            handler(nil)
+        }
+    }
+
+    func cancel() {
+        lock.lock()
+        let alreadyCancelled = storedCancelled
+        storedCancelled = true
+        lock.unlock()
+        if !alreadyCancelled {
+            stateUpdateHandler?(.cancelled)
+        }
+    }
+
+    func push(_ data: Data) {
+        lock.lock()
+        guard !pendingReceives.isEmpty else {
+            lock.unlock()
+            XCTFail("no pending receive to push into")
+            return
+        }
+        let completion = pendingReceives.removeFirst()
+        lock.unlock()
+        completion(data, nil, false, nil)
+    }
+}
@@ -0,0 +1,36 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import PacketRelay
+import XCTest
+
+final class Socks5MemoryProfileRunnerTests: XCTestCase {
+    /// Runs the full iOS memory-profile scenario at its shipped defaults — bounded budget,
+    /// 200 concurrent shaped flows, telemetry sampling on — and requires the pass verdict.
+    func testMemoryProfileScenarioStaysUnderThreshold() {
+        let report = Socks5MemoryProfileRunner().run()
+
+        XCTAssertTrue(report.passed, report.summaryText)
+        XCTAssertEqual(report.flowCount, 200)
+        XCTAssertEqual(report.telemetrySampleCount, 200)
+        XCTAssertGreaterThan(report.peakTrackedBytes, 0)
+        XCTAssertLessThanOrEqual(report.peakTrackedBytes, 900 * 1024)
+        XCTAssertEqual(report.residualTrackedBytes, 0)
+        XCTAssertEqual(report.deliveredBytes, 200 * 512)
+    }
+
+    /// Verifies the scenario fails loudly when the threshold is set below the load it
+    /// generates, so the check cannot silently pass by never tripping.
+    func testMemoryProfileScenarioReportsThresholdBreach() {
+        let options = Socks5MemoryProfileOptions(
+            flowCount: 8,
+            maxPeakTrackedBytes: 1_024
+        )
+        let report = Socks5MemoryProfileRunner().run(options: options)
+
+        XCTAssertFalse(report.passed)
+        XCTAssertNotNil(report.failureDetail)
+        XCTAssertGreaterThan(report.peakTrackedBytes, 1_024)
+    }
+}
@@ -81,6 +81,775 @@ final class Socks5ServerTests: XCTestCase {
         }
     }
 
+    /// Verifies a `.block` verdict rejects the CONNECT with a ruleset-denied reply before any dial.
+    func testPolicyBlockRejectsConnectBeforeDial() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.policy-block")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .block)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "blocked.example", port: 443))
+
+            XCTAssertEqual(
+                inbound.sentPayloads,
+                [
+                    Socks5Codec.buildMethodSelection(method: 0x00),
+                    Socks5Codec.buildReply(code: 0x02, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+                ]
+            )
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertEqual(outbound.readRequests, 0)
+            XCTAssertEqual(evaluator.inputs.count, 1)
+            XCTAssertEqual(evaluator.inputs.first?.host, "blocked.example")
+            XCTAssertEqual(evaluator.inputs.first?.port, 443)
+            XCTAssertEqual(evaluator.inputs.first?.transport, "tcp")
+        }
+    }
+
+    /// Verifies a `.shape` verdict caps outbound read chunk sizes while still connecting.
+    func testPolicyShapeCapsOutboundReadChunks() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.policy-shape")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 1_024))
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "shaped.example", port: 443))
+            outbound.succeedConnect()
+
+            XCTAssertEqual(
+                inbound.sentPayloads,
+                [
+                    Socks5Codec.buildMethodSelection(method: 0x00),
+                    Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+                ]
+            )
+            XCTAssertGreaterThan(outbound.readRequests, 0)
+            XCTAssertEqual(outbound.lastReadMaximumLength, 1_024)
+        }
+    }
+
+    /// Verifies a paced `.shape` verdict reads in sub-burst slices and delays the next read so
+    /// forwarded bytes spread across the interval instead of arriving as one burst.
+    func testPolicyShapePacingSpreadsReadsAcrossInterval() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.policy-shape-pacing")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 8_000, pacingBytesPerSecond: 8_000))
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "paced.example", port: 443))
+            outbound.succeedConnect()
+
+            // 8,000 B/s split into eight slices caps each read at 1,000 bytes.
+            XCTAssertEqual(outbound.readRequests, 1)
+            XCTAssertEqual(outbound.lastReadMaximumLength, 1_000)
+
+            outbound.queueRead(Data(count: 1_000))
+
+            // The chunk forwards immediately; the next read waits out its earned pacing delay.
+            XCTAssertEqual(inbound.sentPayloads.last, Data(count: 1_000))
+            XCTAssertEqual(outbound.readRequests, 1)
+        }
+
+        let rearmed = expectation(description: "paced read re-armed")
+        // A full 1,000-byte slice at 8,000 B/s earns a 125 ms delay before the next read.
+        queue.asyncAfter(deadline: .now() + 0.5) {
+            XCTAssertEqual(outbound.readRequests, 2)
+            rearmed.fulfill()
+        }
+        wait(for: [rearmed], timeout: 2)
+    }
+
+    /// Verifies the flow debug view accounts per-flow shaping impact: the pacing delay a
+    /// forwarded chunk earned and the undelivered shaped-bytes high-water mark.
+    func testFlowInfoReportsShapingStatistics() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.shaping-stats")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 8_000, pacingBytesPerSecond: 8_000))
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "paced.example", port: 443))
+            outbound.succeedConnect()
+
+            let beforeTraffic = connection.flowInfo()
+            XCTAssertTrue(beforeTraffic.isRateShaped)
+            XCTAssertEqual(beforeTraffic.shapedDelayTotalMilliseconds, 0)
+            XCTAssertEqual(beforeTraffic.peakUndeliveredShapedBytes, 0)
+            XCTAssertEqual(beforeTraffic.shapedDroppedChunkCount, 0)
+
+            outbound.queueRead(Data(count: 1_000))
+
+            // A full 1,000-byte slice at 8,000 B/s earns exactly 125 ms of pacing delay,
+            // and held its bytes in the shaped ledger until the client write completed.
+            let afterChunk = connection.flowInfo()
+            XCTAssertEqual(afterChunk.shapedDelayTotalMilliseconds, 125)
+            XCTAssertEqual(afterChunk.peakUndeliveredShapedBytes, 1_000)
+            XCTAssertEqual(afterChunk.undeliveredShapedBytes, 0)
+            XCTAssertEqual(afterChunk.shapedDroppedChunkCount, 0)
+        }
+    }
+
+    /// Verifies the global shaped-bytes cap closes a shaped flow that cannot reserve budget
+    /// when no other shaped session exists to evict, counting the eviction.
+    func testGlobalShapedCapClosesFlowWhenNoVictimAvailable() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.shaped-cap")
+        let limits = Socks5BufferLimits(maxShapedBytesPerServer: 16)
+        let ledger = Socks5BufferLedger(
+            capacity: limits.maxBufferedBytesPerServer,
+            shapedCapacity: limits.maxShapedBytesPerServer
+        )
+
+        func makeShapedConnection(_ inbound: FakeInboundConnection, outbound: ControlledTCPOutbound) -> Socks5Connection {
+            Socks5Connection(
+                connection: inbound,
+                provider: FakeProvider(outbound: outbound),
+                queue: queue,
+                mtu: 1500,
+                logger: StructuredLogger(sink: InMemoryLogSink()),
+                policyEvaluator: RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 1_024)),
+                bufferLimits: limits,
+                bufferLedger: ledger
+            )
+        }
+
+        let firstInbound = FakeInboundConnection()
+        let firstOutbound = ControlledTCPOutbound()
+        let first = makeShapedConnection(firstInbound, outbound: firstOutbound)
+        let secondInbound = FakeInboundConnection()
+        let secondOutbound = ControlledTCPOutbound()
+        let second = makeShapedConnection(secondInbound, outbound: secondOutbound)
+
+        queue.sync {
+            first.start()
+            firstInbound.push(Self.greeting)
+            firstInbound.push(Self.connectRequest(host: "one.example", port: 443))
+            firstOutbound.succeedConnect()
+            firstInbound.completeSendsAutomatically = false
+            firstOutbound.queueRead(Data(repeating: 0x41, count: 12))
+            XCTAssertEqual(ledger.usage.shapedBytes, 12)
+
+            second.start()
+            secondInbound.push(Self.greeting)
+            secondInbound.push(Self.connectRequest(host: "two.example", port: 443))
+            secondOutbound.succeedConnect()
+            secondOutbound.queueRead(Data(repeating: 0x42, count: 8))
+
+            XCTAssertTrue(secondInbound.cancelled)
+            XCTAssertFalse(firstInbound.cancelled)
+            XCTAssertEqual(ledger.usage.shapedBytes, 12)
+            XCTAssertEqual(ledger.usage.shapedEvictionCount, 1)
+
+            // Delivering the held slice returns its reservation.
+            firstInbound.completeNextSend()
+            XCTAssertEqual(ledger.usage.shapedBytes, 0)
+        }
+    }
+
+    /// Verifies refusal attribution: per-destination tallies, the unknown fallback, and the
+    /// tracking bound spilling extra destinations into the untracked count.
+    func testBudgetRefusalReportAttributesPerDestination() {
+        let ledger = Socks5BufferLedger(capacity: 16)
+
+        ledger.recordRefusal(destination: "big.example")
+        ledger.recordRefusal(destination: "big.example")
+        ledger.recordRefusal(destination: "small.example")
+        ledger.recordRefusal(destination: nil)
+        for index in 0..<Socks5BudgetRefusalReport.maxTrackedDestinations {
+            ledger.recordRefusal(destination: "filler-\(index).example")
+        }
+
+        let report = ledger.refusals
+        XCTAssertEqual(report.totalRefusalCount, 4 + Socks5BudgetRefusalReport.maxTrackedDestinations)
+        XCTAssertEqual(report.refusalsByDestination["big.example"], 2)
+        XCTAssertEqual(report.refusalsByDestination["small.example"], 1)
+        XCTAssertEqual(report.refusalsByDestination["unknown"], 1)
+        XCTAssertEqual(report.refusalsByDestination.count, Socks5BudgetRefusalReport.maxTrackedDestinations)
+        XCTAssertEqual(report.untrackedRefusalCount, 3)
+        XCTAssertFalse(report.isEmpty)
+    }
+
+    /// Verifies a shaped-cap refusal is attributed to the refused flow's destination host.
+    func testShapedCapRefusalRecordsDestination() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.shaped-refusal")
+        let limits = Socks5BufferLimits(maxShapedBytesPerServer: 16)
+        let ledger = Socks5BufferLedger(
+            capacity: limits.maxBufferedBytesPerServer,
+            shapedCapacity: limits.maxShapedBytesPerServer
+        )
+
+        func makeShapedConnection(_ inbound: FakeInboundConnection, outbound: ControlledTCPOutbound) -> Socks5Connection {
+            Socks5Connection(
+                connection: inbound,
+                provider: FakeProvider(outbound: outbound),
+                queue: queue,
+                mtu: 1500,
+                logger: StructuredLogger(sink: InMemoryLogSink()),
+                policyEvaluator: RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 1_024)),
+                bufferLimits: limits,
+                bufferLedger: ledger
+            )
+        }
+
+        let firstInbound = FakeInboundConnection()
+        let firstOutbound = ControlledTCPOutbound()
+        let first = makeShapedConnection(firstInbound, outbound: firstOutbound)
+        let secondInbound = FakeInboundConnection()
+        let secondOutbound = ControlledTCPOutbound()
+        let second = makeShapedConnection(secondInbound, outbound: secondOutbound)
+
+        queue.sync {
+            first.start()
+            firstInbound.push(Self.greeting)
+            firstInbound.push(Self.connectRequest(host: "one.example", port: 443))
+            firstOutbound.succeedConnect()
+            firstInbound.completeSendsAutomatically = false
+            firstOutbound.queueRead(Data(repeating: 0x41, count: 12))
+            XCTAssertTrue(ledger.refusals.isEmpty)
+
+            second.start()
+            secondInbound.push(Self.greeting)
+            secondInbound.push(Self.connectRequest(host: "two.example", port: 443))
+            secondOutbound.succeedConnect()
+            secondOutbound.queueRead(Data(repeating: 0x42, count: 8))
+
+            XCTAssertTrue(secondInbound.cancelled)
+            let report = ledger.refusals
+            XCTAssertEqual(report.totalRefusalCount, 1)
+            XCTAssertEqual(report.refusalsByDestination["two.example"], 1)
+            XCTAssertEqual(report.untrackedRefusalCount, 0)
+        }
+    }
+
+    /// Verifies the eviction hook frees budget for the requester: the longest-shaped session
+    /// is retired, its reservation is released, and the new slice reserves successfully.
+    func testShapedEvictionHookFreesBudgetForRequester() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.shaped-evict")
+        let limits = Socks5BufferLimits(maxShapedBytesPerServer: 16)
+        let ledger = Socks5BufferLedger(
+            capacity: limits.maxBufferedBytesPerServer,
+            shapedCapacity: limits.maxShapedBytesPerServer
+        )
+
+        func makeShapedConnection(_ inbound: FakeInboundConnection, outbound: ControlledTCPOutbound) -> Socks5Connection {
+            Socks5Connection(
+                connection: inbound,
+                provider: FakeProvider(outbound: outbound),
+                queue: queue,
+                mtu: 1500,
+                logger: StructuredLogger(sink: InMemoryLogSink()),
+                policyEvaluator: RecordingPolicyEvaluator(verdict: .shape(maxBurstBytes: 1_024)),
+                bufferLimits: limits,
+                bufferLedger: ledger
+            )
+        }
+
+        let firstInbound = FakeInboundConnection()
+        let firstOutbound = ControlledTCPOutbound()
+        let first = makeShapedConnection(firstInbound, outbound: firstOutbound)
+        let secondInbound = FakeInboundConnection()
+        let secondOutbound = ControlledTCPOutbound()
+        let second = makeShapedConnection(secondInbound, outbound: secondOutbound)
+        second.evictOldestShapedSession = { _ in
+            ledger.recordShapedEviction()
+            first.stop(reason: .bufferLimitExceeded, message: "shaped-budget-evicted")
+            return true
+        }
+
+        queue.sync {
+            first.start()
+            firstInbound.push(Self.greeting)
+            firstInbound.push(Self.connectRequest(host: "one.example", port: 443))
+            firstOutbound.succeedConnect()
+            firstInbound.completeSendsAutomatically = false
+            firstOutbound.queueRead(Data(repeating: 0x41, count: 12))
+            XCTAssertEqual(ledger.usage.shapedBytes, 12)
+
+            second.start()
+            secondInbound.push(Self.greeting)
+            secondInbound.push(Self.connectRequest(host: "two.example", port: 443))
+            secondOutbound.succeedConnect()
+            secondInbound.completeSendsAutomatically = false
+            secondOutbound.queueRead(Data(repeating: 0x42, count: 8))
+
+            XCTAssertTrue(firstInbound.cancelled)
+            XCTAssertFalse(secondInbound.cancelled)
+            XCTAssertEqual(ledger.usage.shapedBytes, 8)
+            XCTAssertEqual(ledger.usage.shapedEvictionCount, 1)
+        }
+    }
+
+    /// Verifies the TLS-alert block mode drains the ClientHello and answers with a fatal alert.
+    func testPolicyBlockWithTLSAlertAnswersClientHello() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.policy-block-tls-alert")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .block)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator,
+            sendTLSAlertOnPolicyBlock: true
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "blocked.example", port: 443))
+
+            // The CONNECT is accepted so the client starts its handshake; no dial happens.
+            XCTAssertEqual(
+                inbound.sentPayloads,
+                [
+                    Socks5Codec.buildMethodSelection(method: 0x00),
+                    Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+                ]
+            )
+            XCTAssertFalse(inbound.cancelled)
+            XCTAssertEqual(outbound.readRequests, 0)
+
+            inbound.push(Self.clientHello(serverName: "blocked.example"))
+
+            XCTAssertEqual(inbound.sentPayloads.count, 3)
+            XCTAssertEqual(inbound.sentPayloads.last, TLSHandshakeReset.makeFatalAlert(.accessDenied))
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertTrue(outbound.writes.isEmpty)
+        }
+    }
+
+    /// Verifies blocked streams that are not TLS are reset without sending an alert.
+    func testPolicyBlockWithTLSAlertResetsNonTLSStreams() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.policy-block-not-tls")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let evaluator = RecordingPolicyEvaluator(verdict: .block)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: evaluator,
+            sendTLSAlertOnPolicyBlock: true
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "blocked.example", port: 80))
+            inbound.push(Data("GET / HTTP/1.1\r\n".utf8))
+
+            XCTAssertEqual(inbound.sentPayloads.count, 2)
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertTrue(outbound.writes.isEmpty)
+        }
+    }
+
+    /// Verifies an `ech` policy rule holds the first client bytes after an allowed dial and
+    /// blocks the flow once ClientHello inspection finds the ECH extension.
+    func testECHPolicyBlocksInspectedFlowAfterDial() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.ech-block")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let policy = try RelayPolicyCompiler.compile("block ech *.example.com; allow *")
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: policy
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "sub.example.com", port: 443))
+            outbound.succeedConnect()
+
+            // Allowed at connect time; the rule cannot fire until the hello is inspected.
+            XCTAssertEqual(
+                inbound.sentPayloads,
+                [
+                    Socks5Codec.buildMethodSelection(method: 0x00),
+                    Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+                ]
+            )
+
+            inbound.push(Self.clientHello(serverName: "cover.example", ech: true))
+
+            XCTAssertTrue(outbound.writes.isEmpty)
+            XCTAssertTrue(inbound.cancelled)
+        }
+    }
+
+    /// Verifies flows the `ech` rule watches still forward normally when the hello has no ECH.
+    func testECHPolicyForwardsNonECHHello() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.ech-forward")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let policy = try RelayPolicyCompiler.compile("block ech *.example.com; allow *")
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: policy
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "sub.example.com", port: 443))
+            outbound.succeedConnect()
+
+            let hello = Self.clientHello(serverName: "sub.example.com")
+            inbound.push(hello)
+
+            XCTAssertEqual(outbound.writes, [hello])
+            XCTAssertFalse(inbound.cancelled)
+        }
+    }
+
+    /// Verifies a `route` verdict dials the named Shadowsocks server instead of the destination
+    /// and that client bytes leave encrypted, carrying the destination in the first chunk.
+    func testRouteVerdictDialsShadowsocksServerAndEncryptsStream() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.route-shadowsocks")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let serverConfig = ShadowsocksServerConfig(host: "ss.example.net", port: 8_388, password: "route-secret")
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .route(tag: "proxy-a")),
+            upstreamRoutes: RelayUpstreamRoutes(shadowsocks: ["proxy-a": serverConfig])
+        )
+
+        try queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+            outbound.succeedConnect()
+
+            XCTAssertEqual(provider.tcpEndpoints.map(\.hostname), ["ss.example.net"])
+            XCTAssertEqual(provider.tcpEndpoints.map(\.port), ["8388"])
+
+            let payload = Data("GET / HTTP/1.1\r\n".utf8)
+            inbound.push(payload)
+
+            let firstWrite = try XCTUnwrap(outbound.writes.first)
+            XCTAssertNil(firstWrite.range(of: payload))
+
+            var serverSide = ShadowsocksStreamDecryptor(masterKey: serverConfig.masterKey(), cipher: serverConfig.cipher)
+            let plaintext = try serverSide.feed(firstWrite)
+            let expectedHeader = Data(try XCTUnwrap(Socks5Codec.encodeAddressPort(.domain("media.example.com"), port: 443)))
+            XCTAssertEqual(plaintext, expectedHeader + payload)
+        }
+    }
+
+    /// Verifies a `route` verdict whose tag has no installed upstream fails the CONNECT.
+    func testRouteVerdictWithUnknownTagFailsConnect() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.route-unknown")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .route(tag: "missing"))
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertTrue(provider.tcpEndpoints.isEmpty)
+            XCTAssertEqual(
+                inbound.sentPayloads.last,
+                Socks5Codec.buildReply(code: 0x01, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+            )
+        }
+    }
+
+    func testRouteVerdictToBuiltInBlockOutboundRejectsConnect() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.route-block")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .route(tag: "block"))
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertTrue(provider.tcpEndpoints.isEmpty)
+            XCTAssertEqual(
+                inbound.sentPayloads.last,
+                Socks5Codec.buildReply(code: 0x02, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+            )
+        }
+    }
+
+    func testRouteVerdictToBuiltInDirectOutboundDialsDestination() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.route-direct")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .route(tag: "direct"))
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+            outbound.succeedConnect()
+
+            XCTAssertEqual(provider.tcpEndpoints.last?.hostname, "media.example.com")
+            XCTAssertEqual(provider.tcpEndpoints.last?.port, "443")
+            XCTAssertEqual(
+                inbound.sentPayloads.last,
+                Socks5Codec.buildReply(code: 0x00, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+            )
+        }
+    }
+
+    func testResolverSelectionRedirectsDialToStaticOverride() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.resolver-static")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .allow, resolverTag: "corp-static"),
+            hostResolvers: RelayHostResolvers(resolvers: [
+                "corp-static": RelayStaticHostOverrides(["media.example.com": "198.51.100.7"])
+            ])
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertEqual(provider.tcpEndpoints.last?.hostname, "198.51.100.7")
+            XCTAssertEqual(provider.tcpEndpoints.last?.port, "443")
+        }
+    }
+
+    func testResolverSelectionWithUnknownTagFailsConnect() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.resolver-unknown")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .allow, resolverTag: "missing")
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertTrue(provider.tcpEndpoints.isEmpty)
+            XCTAssertEqual(
+                inbound.sentPayloads.last,
+                Socks5Codec.buildReply(code: 0x01, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+            )
+        }
+    }
+
+    /// Verifies the global system-resolver override redirects default-path dials without any policy wiring.
+    func testGlobalSystemResolverOverrideRedirectsDefaultPathDial() {
+        RelaySystemResolverOverride.shared.install(
+            RelayStaticHostOverrides(["media.example.com": "203.0.113.9"])
+        )
+        defer { RelaySystemResolverOverride.shared.clear() }
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.resolver-global-override")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertEqual(provider.tcpEndpoints.last?.hostname, "203.0.113.9")
+            XCTAssertEqual(provider.tcpEndpoints.last?.port, "443")
+        }
+    }
+
+    /// Verifies a named resolver's rewrite wins over the global override, which only covers the system path.
+    func testNamedResolverRewriteBeatsGlobalOverride() {
+        RelaySystemResolverOverride.shared.install(
+            RelayStaticHostOverrides(["media.example.com": "203.0.113.9"])
+        )
+        defer { RelaySystemResolverOverride.shared.clear() }
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.resolver-override-precedence")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            policyEvaluator: RecordingPolicyEvaluator(verdict: .allow, resolverTag: "corp-static"),
+            hostResolvers: RelayHostResolvers(resolvers: [
+                "corp-static": RelayStaticHostOverrides(["media.example.com": "198.51.100.7"])
+            ])
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "media.example.com", port: 443))
+
+            XCTAssertEqual(provider.tcpEndpoints.last?.hostname, "198.51.100.7")
+        }
+    }
+
+    func testRecentDialFailureSuppressesNextConnectWithoutDialing() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.failure-cache")
+        let cache = Socks5DialFailureCache(ttl: 30, now: { Date() })
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+
+        let firstInbound = FakeInboundConnection()
+        let first = Socks5Connection(
+            connection: firstInbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dialFailureCache: cache
+        )
+
+        queue.sync {
+            first.start()
+            firstInbound.push(Self.greeting)
+            firstInbound.push(Self.connectRequest(host: "down.example", port: 443))
+            outbound.failConnect(TestConnectError.refused)
+            XCTAssertEqual(provider.tcpEndpoints.count, 1)
+        }
+
+        let secondInbound = FakeInboundConnection()
+        let second = Socks5Connection(
+            connection: secondInbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dialFailureCache: cache
+        )
+
+        queue.sync {
+            second.start()
+            secondInbound.push(Self.greeting)
+            secondInbound.push(Self.connectRequest(host: "down.example", port: 443))
+
+            // The suppressed flow fails fast without consuming a second dial attempt.
+            XCTAssertEqual(provider.tcpEndpoints.count, 1)
+            XCTAssertEqual(
+                secondInbound.sentPayloads.last,
+                Socks5Codec.buildReply(code: 0x05, bindAddress: .ipv4("0.0.0.0"), bindPort: 0)
+            )
+        }
+    }
+
     func testConnectFailureClosesAfterFailureReplyFlushes() {
         let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.failure-flush")
         let inbound = FakeInboundConnection()
@@ -160,6 +929,158 @@ final class Socks5ServerTests: XCTestCase {
         }
     }
 
+    /// Verifies the v2 close callback reports stable reason codes instead of free-form strings.
+    func testCloseCallbackCarriesStableReasonCodes() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.close-reason")
+        let inbound = FakeInboundConnection()
+        let provider = FakeProvider(outbound: ControlledTCPOutbound())
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        var observed: [(Socks5CloseReason, String?, Int?)] = []
+        connection.onCloseWithReason = { reason, message, retryAfterMilliseconds in
+            observed.append((reason, message, retryAfterMilliseconds))
+        }
+
+        queue.sync {
+            connection.start()
+            inbound.push(Data([0x04]))
+
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertEqual(observed.count, 1)
+            XCTAssertEqual(observed.first?.0, .protocolError)
+            XCTAssertEqual(observed.first?.1, "malformed-greeting")
+            // Non-dial closes carry no retry hint.
+            XCTAssertNil(observed.first?.2)
+        }
+    }
+
+    /// Verifies dial failures surface as `.dialFailed` with a retry-after hint so hosts can
+    /// branch retry logic reliably without synchronizing their re-dials.
+    func testConnectFailureClosesWithDialFailedReason() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.dial-failed-reason")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        var observed: [(Socks5CloseReason, Int?)] = []
+        connection.onCloseWithReason = { reason, _, retryAfterMilliseconds in
+            observed.append((reason, retryAfterMilliseconds))
+        }
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "denied.example", port: 80))
+            outbound.failConnect(TestConnectError.refused)
+
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertEqual(observed.map(\.0), [.dialFailed])
+            // The hint covers the default suppression TTL with up to 20% jitter either way.
+            if let hint = observed.first?.1 {
+                XCTAssertGreaterThanOrEqual(hint, Int(Socks5DialFailureCache.defaultTTL * 1_000 * 0.8))
+                XCTAssertLessThanOrEqual(hint, Int(Socks5DialFailureCache.defaultTTL * 1_000 * 1.2))
+            } else {
+                XCTFail("dial-failed close carried no retry-after hint")
+            }
+        }
+    }
+
+    /// Verifies a saturated dial limiter defers outbound creation until a slot frees, then
+    /// the queued CONNECT dials and completes normally.
+    func testDialLimiterDefersConnectUntilSlotFrees() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.dial-limiter")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let limiter = RelayDialLimiter(maxInFlightDials: 1)
+        // Occupy the only slot so the CONNECT below has to wait.
+        limiter.admit {}
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dialLimiter: limiter
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "deferred.example", port: 443))
+
+            XCTAssertTrue(provider.tcpEndpoints.isEmpty)
+            XCTAssertEqual(limiter.stats().queuedCount, 1)
+        }
+
+        limiter.release()
+        queue.sync {
+            XCTAssertEqual(provider.tcpEndpoints.count, 1)
+        }
+
+        outbound.succeedConnect()
+        queue.sync {
+            // 0x00: succeeded. The deferred dial finished the normal CONNECT handshake.
+            XCTAssertEqual(inbound.sentPayloads.last?.prefix(2), Data([0x05, 0x00]))
+            XCTAssertEqual(limiter.stats().inFlightCount, 0)
+        }
+    }
+
+    /// Verifies the persisted-state envelope round-trips through Codable, restores the
+    /// negative dial cache and failure counters, and ignores unknown schema versions.
+    func testPersistedStateRoundTripRestoresDialState() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.persisted-state")
+        let cache = Socks5DialFailureCache()
+        let counters = RelayDialFailureCounters()
+        let server = Socks5Server(
+            provider: FakeProvider(outbound: ControlledTCPOutbound()),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dialFailureCache: cache,
+            dialFailureCounters: counters
+        )
+        cache.recordFailure(host: "down.example", port: 443)
+        counters.record(host: "down.example", reason: "Connection refused")
+
+        let encoded = try JSONEncoder().encode(server.persistedStateSnapshot())
+        let decoded = try JSONDecoder().decode(Socks5ServerPersistedState.self, from: encoded)
+
+        let restartedCache = Socks5DialFailureCache()
+        let restartedCounters = RelayDialFailureCounters()
+        let restarted = Socks5Server(
+            provider: FakeProvider(outbound: ControlledTCPOutbound()),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dialFailureCache: restartedCache,
+            dialFailureCounters: restartedCounters
+        )
+        XCTAssertTrue(restarted.restorePersistedState(decoded))
+        XCTAssertTrue(restartedCache.isSuppressed(host: "down.example", port: 443))
+        XCTAssertEqual(restarted.dialFailureSnapshot().first?.refused, 1)
+
+        let stale = Socks5ServerPersistedState(
+            schemaVersion: 99,
+            dialFailures: decoded.dialFailures,
+            dialFailureCounts: []
+        )
+        XCTAssertFalse(restarted.restorePersistedState(stale))
+    }
+
     func testUDPAssociateReplyFailureStopsRelayAndConnection() {
         let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp-associate-reply-failure")
         let inbound = FakeInboundConnection()
@@ -172,7 +1093,7 @@ final class Socks5ServerTests: XCTestCase {
             queue: queue,
             mtu: 1500,
             logger: StructuredLogger(sink: InMemoryLogSink()),
-            udpRelayFactory: { _, _, _, _ in relay }
+            udpRelayFactory: { _, _, _, _, _ in relay }
         )
 
         queue.sync {
@@ -693,7 +1614,50 @@ final class Socks5ServerTests: XCTestCase {
         let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.tcp-half-close")
         let inbound = FakeInboundConnection()
         let outbound = ControlledTCPOutbound()
-        outbound.autoCompleteWrites = false
+        outbound.autoCompleteWrites = false
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        let finalRequestBytes = Data("final-request".utf8)
+        let responseBytes = Data("response-after-half-close".utf8)
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            outbound.succeedConnect()
+
+            inbound.push(finalRequestBytes, isComplete: true)
+            XCTAssertEqual(outbound.writes, [finalRequestBytes])
+            XCTAssertEqual(outbound.finishWritingCount, 0)
+            XCTAssertFalse(outbound.cancelled)
+            XCTAssertFalse(inbound.cancelled)
+
+            outbound.completeNextWrite()
+            XCTAssertEqual(outbound.finishWritingCount, 1)
+            XCTAssertFalse(outbound.cancelled)
+            XCTAssertFalse(inbound.cancelled)
+
+            outbound.queueRead(responseBytes)
+            XCTAssertEqual(inbound.sentPayloads.last, responseBytes)
+            XCTAssertFalse(inbound.cancelled)
+
+            outbound.queueRead(nil)
+            XCTAssertTrue(inbound.cancelled)
+        }
+    }
+
+    /// Verifies a half-closed flow whose server never finishes its side is forcibly
+    /// aborted by the safety timeout instead of lingering until tunnel teardown.
+    func testHalfClosedFlowAbortsAfterSafetyTimeout() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.half-close-timeout")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
         let provider = FakeProvider(outbound: outbound)
         let connection = Socks5Connection(
             connection: inbound,
@@ -702,8 +1666,14 @@ final class Socks5ServerTests: XCTestCase {
             mtu: 1500,
             logger: StructuredLogger(sink: InMemoryLogSink())
         )
-        let finalRequestBytes = Data("final-request".utf8)
-        let responseBytes = Data("response-after-half-close".utf8)
+        connection.halfCloseTimeoutSeconds = 0.05
+
+        let aborted = expectation(description: "half-closed flow aborted")
+        var observed: [(Socks5CloseReason, String?)] = []
+        connection.onCloseWithReason = { reason, message, _ in
+            observed.append((reason, message))
+            aborted.fulfill()
+        }
 
         queue.sync {
             connection.start()
@@ -711,24 +1681,16 @@ final class Socks5ServerTests: XCTestCase {
             inbound.push(Self.connectRequest(host: "example.com", port: 443))
             outbound.succeedConnect()
 
-            inbound.push(finalRequestBytes, isComplete: true)
-            XCTAssertEqual(outbound.writes, [finalRequestBytes])
-            XCTAssertEqual(outbound.finishWritingCount, 0)
-            XCTAssertFalse(outbound.cancelled)
-            XCTAssertFalse(inbound.cancelled)
-
-            outbound.completeNextWrite()
+            inbound.push(Data(), isComplete: true)
             XCTAssertEqual(outbound.finishWritingCount, 1)
-            XCTAssertFalse(outbound.cancelled)
-            XCTAssertFalse(inbound.cancelled)
-
-            outbound.queueRead(responseBytes)
-            XCTAssertEqual(inbound.sentPayloads.last, responseBytes)
             XCTAssertFalse(inbound.cancelled)
-
-            outbound.queueRead(nil)
-            XCTAssertTrue(inbound.cancelled)
         }
+
+        wait(for: [aborted], timeout: 2)
+        XCTAssertEqual(observed.map(\.0), [.halfCloseTimeout])
+        XCTAssertEqual(observed.first?.1, "half-close-timeout")
+        XCTAssertTrue(inbound.cancelled)
+        XCTAssertTrue(outbound.cancelled)
     }
 
     /// Verifies inbound client reads pause while one outbound relay write is still in flight.
@@ -812,6 +1774,51 @@ final class Socks5ServerTests: XCTestCase {
         }
     }
 
+    /// Verifies a shared ledger closes the flow that pushes aggregate buffering past the
+    /// per-server cap, while the per-flow cap alone would have admitted it.
+    func testServerBufferCapClosesFlowThatExceedsAggregateBudget() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.server-buffer-cap")
+        let limits = Socks5BufferLimits(maxBufferedBytesPerFlow: 64, maxBufferedBytesPerServer: 96)
+        let ledger = Socks5BufferLedger(capacity: limits.maxBufferedBytesPerServer)
+
+        func makeConnection(_ inbound: FakeInboundConnection) -> Socks5Connection {
+            Socks5Connection(
+                connection: inbound,
+                provider: FakeProvider(outbound: ControlledTCPOutbound()),
+                queue: queue,
+                mtu: 1500,
+                logger: StructuredLogger(sink: InMemoryLogSink()),
+                bufferLimits: limits,
+                bufferLedger: ledger
+            )
+        }
+
+        let firstInbound = FakeInboundConnection()
+        let first = makeConnection(firstInbound)
+        let secondInbound = FakeInboundConnection()
+        let second = makeConnection(secondInbound)
+
+        queue.sync {
+            first.start()
+            firstInbound.push(Self.greeting)
+            firstInbound.push(Self.connectRequest(host: "one.example", port: 443))
+            // Connect is still pending, so payload bytes buffer under the flow cap.
+            firstInbound.push(Data(repeating: 0x41, count: 60))
+            XCTAssertFalse(firstInbound.cancelled)
+
+            second.start()
+            secondInbound.push(Self.greeting)
+            secondInbound.push(Self.connectRequest(host: "two.example", port: 443))
+            secondInbound.push(Data(repeating: 0x42, count: 60))
+            XCTAssertTrue(secondInbound.cancelled)
+            XCTAssertFalse(firstInbound.cancelled)
+        }
+
+        // Closing the over-budget flow returned its reservation to the ledger.
+        XCTAssertLessThanOrEqual(ledger.usage.bufferedBytes, 96)
+        XCTAssertGreaterThan(ledger.usage.peakBufferedBytes, 0)
+    }
+
     /// Verifies outbound reads wait for the previous inbound send to finish before requesting more data.
     func testTCPProxyPausesOutboundReadsUntilInboundSendCompletes() {
         let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.reverse-backpressure")
@@ -1476,6 +2483,360 @@ final class Socks5ServerTests: XCTestCase {
         XCTAssertTrue(inbound.cancelled)
     }
 
+    /// Verifies configured chunk sizing bounds the outbound read so writes toward the
+    /// client coalesce tiny fragments and never exceed the chunk ceiling.
+    func testChunkSizingBoundsOutboundReads() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.chunk-sizing")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            chunkSizing: Socks5ChunkSizing(minCoalescedReadBytes: 8_192, maxChunkBytes: 16_384)
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            outbound.succeedConnect()
+
+            XCTAssertEqual(outbound.lastReadMinimumLength, 8_192)
+            XCTAssertEqual(outbound.lastReadMaximumLength, 16_384)
+        }
+    }
+
+    /// Verifies the flow debug view tracks the session phase, destination, and buffered
+    /// byte occupancy as a CONNECT moves through the state machine.
+    func testFlowInfoReportsStateAndBufferOccupancy() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.flow-info")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        queue.sync {
+            connection.start()
+            XCTAssertEqual(connection.flowInfo().state, "greeting")
+
+            inbound.push(Self.greeting)
+            XCTAssertEqual(connection.flowInfo().state, "request")
+
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            inbound.push(Data("hello".utf8))
+            let connecting = connection.flowInfo()
+            XCTAssertEqual(connecting.state, "connecting-tcp")
+            XCTAssertEqual(connecting.bufferedClientBytes, 5)
+
+            outbound.succeedConnect()
+            let proxying = connection.flowInfo()
+            XCTAssertEqual(proxying.state, "tcp-proxy")
+            XCTAssertEqual(proxying.destinationHost, "example.com")
+            XCTAssertEqual(proxying.destinationPort, "443")
+            XCTAssertEqual(proxying.bufferedClientBytes, 0)
+            XCTAssertEqual(proxying.undeliveredShapedBytes, 0)
+            XCTAssertFalse(proxying.isRateShaped)
+            XCTAssertFalse(proxying.isAwaitingClientDrain)
+        }
+    }
+
+    /// Verifies the host's flow cookie attaches once the dial starts, with the destination
+    /// tuple as input, and is echoed in the flow debug view from then on.
+    func testFlowCookieAttachesAtDialAndEchoesInFlowInfo() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.flow-cookie")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let cookieProvider = RecordingFlowCookieProvider(cookie: 0xDEAD_BEEF_0000_0042)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            flowCookieProvider: { cookieProvider.cookie(host: $0, port: $1, transport: $2) }
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            XCTAssertNil(connection.flowInfo().flowCookie)
+
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            XCTAssertEqual(connection.flowInfo().flowCookie, 0xDEAD_BEEF_0000_0042)
+
+            outbound.succeedConnect()
+            XCTAssertEqual(connection.flowInfo().flowCookie, 0xDEAD_BEEF_0000_0042)
+
+            let requests = cookieProvider.requests
+            XCTAssertEqual(requests.count, 1)
+            XCTAssertEqual(requests.first?.host, "example.com")
+            XCTAssertEqual(requests.first?.port, 443)
+            XCTAssertEqual(requests.first?.transport, "tcp")
+        }
+    }
+
+    /// Verifies host-injected payload reaches the network side of a proxying flow as if the
+    /// client had sent it, and is refused while the flow is still handshaking.
+    func testInjectedClientPayloadForwardsOnProxyingFlowOnly() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.inject-payload")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        let probe = Data("keepalive-probe".utf8)
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            XCTAssertFalse(connection.injectClientPayload(probe))
+
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            XCTAssertFalse(connection.injectClientPayload(probe))
+
+            outbound.succeedConnect()
+            XCTAssertTrue(connection.injectClientPayload(probe))
+            XCTAssertEqual(outbound.writes.last, probe)
+            XCTAssertFalse(connection.injectClientPayload(Data()))
+        }
+    }
+
+    /// Verifies the server-level entry point refuses payloads for cookies no active flow carries.
+    func testServerInjectClientPayloadRefusesUnknownCookie() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.inject-unknown-cookie")
+        let server = Socks5Server(
+            provider: FakeProvider(outbound: ControlledTCPOutbound()),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        XCTAssertFalse(server.injectClientPayload(flowCookie: 0x1234, data: Data([0x01])))
+    }
+
+    /// Verifies pausing a proxying flow stops re-arming outbound reads after the chunk
+    /// already in flight, resuming arms a fresh read, and the paused bit is surfaced
+    /// through flow info. Pause is refused while the flow is still handshaking.
+    func testSetFlowPausedStopsOutboundReadsUntilResumed() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.flow-pause")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let provider = FakeProvider(outbound: outbound)
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: provider,
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+
+        queue.sync {
+            connection.start()
+            inbound.push(Self.greeting)
+            XCTAssertFalse(connection.setFlowPaused(true))
+
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            outbound.succeedConnect()
+            let armedAfterConnect = outbound.readRequests
+
+            XCTAssertTrue(connection.setFlowPaused(true))
+            XCTAssertTrue(connection.flowInfo().isFlowPaused)
+
+            // The read armed before the pause still forwards its chunk, but no new read
+            // is armed afterward — backpressure propagates to the remote, not a drop.
+            let chunk = Data("remote-bytes".utf8)
+            outbound.queueRead(chunk)
+            XCTAssertEqual(inbound.sentPayloads.last, chunk)
+            XCTAssertEqual(outbound.readRequests, armedAfterConnect)
+
+            XCTAssertTrue(connection.setFlowPaused(false))
+            XCTAssertFalse(connection.flowInfo().isFlowPaused)
+            XCTAssertEqual(outbound.readRequests, armedAfterConnect + 1)
+        }
+    }
+
+    /// Verifies the receive-window tuner halves toward its floor while deliveries complete
+    /// slower than the slow-consumption threshold and doubles back toward its ceiling once
+    /// the client drains quickly again, holding steady at rates in between.
+    func testReceiveWindowTunerTracksConsumptionRate() {
+        var tuner = Socks5ReceiveWindowTuner(maxWindowBytes: 65_535)
+        XCTAssertEqual(tuner.windowBytes, 65_535)
+
+        // 64 KiB consumed in one second is below 128 KiB/s: halve.
+        tuner.recordDelivery(byteCount: 65_536, seconds: 1.0)
+        XCTAssertEqual(tuner.windowBytes, 32_767)
+
+        // Between the thresholds: hold.
+        tuner.recordDelivery(byteCount: 262_144, seconds: 1.0)
+        XCTAssertEqual(tuner.windowBytes, 32_767)
+
+        for _ in 0..<10 {
+            tuner.recordDelivery(byteCount: 1_024, seconds: 1.0)
+        }
+        XCTAssertEqual(tuner.windowBytes, Socks5ReceiveWindowTuner.minWindowBytes)
+
+        // Instantaneous completions count as fast and earn the window back to the ceiling.
+        tuner.recordDelivery(byteCount: 4_096, seconds: 0)
+        XCTAssertEqual(tuner.windowBytes, Socks5ReceiveWindowTuner.minWindowBytes * 2)
+        for _ in 0..<8 {
+            tuner.recordDelivery(byteCount: 1_048_576, seconds: 0.5)
+        }
+        XCTAssertEqual(tuner.windowBytes, 65_535)
+
+        // Empty deliveries carry no rate signal.
+        tuner.recordDelivery(byteCount: 0, seconds: 10)
+        XCTAssertEqual(tuner.windowBytes, 65_535)
+    }
+
+    /// Verifies a fresh flow starts with its receive window at the configured chunk ceiling.
+    func testFlowInfoReportsReceiveWindowAtChunkCeilingInitially() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.receive-window")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: FakeProvider(outbound: outbound),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            chunkSizing: Socks5ChunkSizing(maxChunkBytes: 16_384)
+        )
+
+        queue.sync {
+            connection.start()
+            XCTAssertEqual(connection.flowInfo().receiveWindowBytes, 16_384)
+        }
+    }
+
+    /// Verifies the send-failure taxonomy classifies framework-formatted error strings and
+    /// the policy table resolves behaviors with its fallback for unmapped kinds.
+    func testSendFailurePolicyClassifiesAndResolvesBehaviors() {
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "Connection reset by peer"), .connectionReset)
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "Broken pipe"), .connectionReset)
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "No route to host"), .unreachable)
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "Network is unreachable"), .unreachable)
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "The operation timed out"), .timeout)
+        XCTAssertEqual(Socks5SendFailureKind.classify(reason: "Something else entirely"), .other)
+
+        let policy = Socks5SendFailurePolicy()
+        XCTAssertEqual(policy.behavior(forReason: "Connection reset by peer"), .reset)
+        XCTAssertEqual(policy.behavior(forReason: "Network is unreachable"), .close)
+
+        let custom = Socks5SendFailurePolicy(
+            behaviors: [.unreachable: .silent],
+            fallback: .reset
+        )
+        XCTAssertEqual(custom.behavior(forReason: "No route to host"), .silent)
+        XCTAssertEqual(custom.behavior(forReason: "Something else"), .reset)
+    }
+
+    /// Verifies a remote reset during the proxy phase aborts the client connection
+    /// without an orderly shutdown under the default policy, while an unreachable
+    /// failure still closes it cleanly.
+    func testSendFailureResetBehaviorAbortsClientConnection() {
+        for (reason, expectForce) in [("Connection reset by peer", true), ("Network is unreachable", false)] {
+            let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.send-failure.\(expectForce)")
+            let inbound = FakeInboundConnection()
+            let outbound = ControlledTCPOutbound()
+            let connection = Socks5Connection(
+                connection: inbound,
+                provider: FakeProvider(outbound: outbound),
+                queue: queue,
+                mtu: 1500,
+                logger: StructuredLogger(sink: InMemoryLogSink())
+            )
+
+            queue.sync {
+                connection.start()
+                inbound.push(Self.greeting)
+                inbound.push(Self.connectRequest(host: "example.com", port: 443))
+                outbound.succeedConnect()
+
+                outbound.autoCompleteWrites = false
+                inbound.push(Data("payload".utf8))
+                outbound.completeNextWrite(error: NSError(
+                    domain: "Network.NWError",
+                    code: 54,
+                    userInfo: [NSLocalizedDescriptionKey: reason]
+                ))
+                XCTAssertTrue(inbound.cancelled)
+                XCTAssertEqual(inbound.forceCancelled, expectForce, reason)
+            }
+        }
+    }
+
+    /// Verifies a `.silent`-mapped failure leaves the client unsignaled until the
+    /// half-close deadline reaps the session with the remote-failed close reason.
+    func testSendFailureSilentBehaviorParksFlowUntilDeadline() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.send-failure-silent")
+        let inbound = FakeInboundConnection()
+        let outbound = ControlledTCPOutbound()
+        let connection = Socks5Connection(
+            connection: inbound,
+            provider: FakeProvider(outbound: outbound),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            sendFailurePolicy: Socks5SendFailurePolicy(behaviors: [.unreachable: .silent])
+        )
+
+        let closed = expectation(description: "session reaped after the silent deadline")
+        queue.sync {
+            connection.halfCloseTimeoutSeconds = 0.05
+            connection.onCloseWithReason = { reason, message, _ in
+                XCTAssertEqual(reason, .remoteFailed)
+                XCTAssertEqual(message, "outbound-write-failed-silent")
+                closed.fulfill()
+            }
+            connection.start()
+            inbound.push(Self.greeting)
+            inbound.push(Self.connectRequest(host: "example.com", port: 443))
+            outbound.succeedConnect()
+
+            outbound.autoCompleteWrites = false
+            inbound.push(Data("payload".utf8))
+            outbound.completeNextWrite(error: NSError(
+                domain: "Network.NWError",
+                code: 51,
+                userInfo: [NSLocalizedDescriptionKey: "Network is unreachable"]
+            ))
+            XCTAssertFalse(inbound.cancelled)
+        }
+
+        wait(for: [closed], timeout: 2.0)
+        queue.sync {
+            XCTAssertTrue(inbound.cancelled)
+            XCTAssertFalse(inbound.forceCancelled)
+        }
+    }
+
+    /// Verifies the server-level pause entry point refuses cookies no active flow carries.
+    func testServerSetFlowPausedRefusesUnknownCookie() {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.pause-unknown-cookie")
+        let server = Socks5Server(
+            provider: FakeProvider(outbound: ControlledTCPOutbound()),
+            queue: queue,
+            mtu: 1500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        XCTAssertFalse(server.setFlowPaused(flowCookie: 0x1234, paused: true))
+    }
+
     private static let greeting = Data([0x05, 0x01, 0x00])
 
     private static func connectRequest(host: String, port: UInt16) -> Data {
@@ -1491,6 +2852,36 @@ final class Socks5ServerTests: XCTestCase {
         )
     }
 
+    private static func clientHello(serverName: String?, ech: Bool = false) -> Data {
+        var body = Data([0x03, 0x03])
+        body.append(Data(repeating: 0, count: 32))
+        body.append(0x00)
+        body.append(contentsOf: [0x00, 0x02, 0x13, 0x01])
+        body.append(contentsOf: [0x01, 0x00])
+        if serverName != nil || ech {
+            var extensions = Data()
+            if let serverName {
+                let name = Array(serverName.utf8)
+                let listLength = name.count + 3
+                let extensionLength = listLength + 2
+                extensions.append(contentsOf: [0x00, 0x00, UInt8(extensionLength >> 8), UInt8(extensionLength & 0xFF)])
+                extensions.append(contentsOf: [UInt8(listLength >> 8), UInt8(listLength & 0xFF), 0x00])
+                extensions.append(contentsOf: [UInt8(name.count >> 8), UInt8(name.count & 0xFF)])
+                extensions.append(contentsOf: name)
+            }
+            if ech {
+                extensions.append(contentsOf: [0xFE, 0x0D, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00])
+            }
+            body.append(contentsOf: [UInt8(extensions.count >> 8), UInt8(extensions.count & 0xFF)])
+            body.append(extensions)
+        }
+        var handshake = Data([0x01, UInt8(body.count >> 16), UInt8((body.count >> 8) & 0xFF), UInt8(body.count & 0xFF)])
+        handshake.append(body)
+        var record = Data([0x16, 0x03, 0x01, UInt8(handshake.count >> 8), UInt8(handshake.count & 0xFF)])
+        record.append(handshake)
+        return record
+    }
+
     private static func tcpForwardUDPFrame(hostIndex: Int) throws -> Data {
         try XCTUnwrap(
             Socks5Codec.buildTCPForwardUDPPacket(
@@ -1513,6 +2904,64 @@ private enum TestConnectError: LocalizedError {
     }
 }
 
+private final class RecordingPolicyEvaluator: RelayPolicyEvaluator, @unchecked Sendable {
+    private let lock = NSLock()
+    private let verdict: RelayPolicyVerdict
+    private let resolverTag: String?
+    private var storedInputs: [RelayPolicyInput] = []
+
+    var inputs: [RelayPolicyInput] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedInputs
+    }
+
+    init(verdict: RelayPolicyVerdict, resolverTag: String? = nil) {
+        self.verdict = verdict
+        self.resolverTag = resolverTag
+    }
+
+    func evaluate(_ input: RelayPolicyInput) -> RelayPolicyVerdict {
+        lock.lock()
+        storedInputs.append(input)
+        lock.unlock()
+        return verdict
+    }
+
+    func resolverTag(_ input: RelayPolicyInput) -> String? {
+        resolverTag
+    }
+}
+
+private final class RecordingFlowCookieProvider: @unchecked Sendable {
+    struct Request {
+        let host: String
+        let port: UInt16
+        let transport: String
+    }
+
+    private let lock = NSLock()
+    private let fixedCookie: UInt64
+    private var storedRequests: [Request] = []
+
+    var requests: [Request] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedRequests
+    }
+
+    init(cookie: UInt64) {
+        self.fixedCookie = cookie
+    }
+
+    func cookie(host: String, port: UInt16, transport: String) -> UInt64 {
+        lock.lock()
+        storedRequests.append(Request(host: host, port: port, transport: transport))
+        lock.unlock()
+        return fixedCookie
+    }
+}
+
 private final class FakeInboundConnection: Socks5InboundConnection {
     var stateUpdateHandler: (@Sendable (NWConnection.State) -> Void)?
 
@@ -1520,6 +2969,7 @@ private final class FakeInboundConnection: Socks5InboundConnection {
     private var pendingSendCompletions: [(NWError?) -> Void] = []
     private(set) var sentPayloads: [Data] = []
     private(set) var cancelled = false
+    private(set) var forceCancelled = false
     var completeSendsAutomatically = true
 
     var pendingReceiveCount: Int {
@@ -1552,6 +3002,11 @@ private final class FakeInboundConnection: Socks5InboundConnection {
         stateUpdateHandler?(.cancelled)
     }
 
+    func forceCancel() {
+        forceCancelled = true
+        cancel()
+    }
+
     func push(_ data: Data, isComplete: Bool = false, error: NWError? = nil) {
         XCTAssertFalse(pendingReceives.isEmpty)
         let completion = pendingReceives.removeFirst()
@@ -1581,6 +3036,8 @@ private final class ControlledTCPOutbound: @unchecked Sendable, Socks5PathAwareT
     private var storedWrites: [Data] = []
     private var storedCancelled = false
     private var storedReadRequests = 0
+    private var storedLastReadMinimumLength: Int?
+    private var storedLastReadMaximumLength: Int?
     private var storedRestartCount = 0
     private var storedFinishWritingCount = 0
     private var storedAutoCompleteWrites = true
@@ -1606,6 +3063,18 @@ private final class ControlledTCPOutbound: @unchecked Sendable, Socks5PathAwareT
         return storedReadRequests
     }
 
+    var lastReadMinimumLength: Int? {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedLastReadMinimumLength
+    }
+
+    var lastReadMaximumLength: Int? {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedLastReadMaximumLength
+    }
+
     var restartCount: Int {
         lock.lock()
         defer { lock.unlock() }
@@ -1681,10 +3150,12 @@ private final class ControlledTCPOutbound: @unchecked Sendable, Socks5PathAwareT
         lock.unlock()
     }
 
-    func readMinimumLength(_: Int, maximumLength _: Int, completionHandler: @escaping @Sendable (Data?, (any Error)?) -> Void) {
+    func readMinimumLength(_ minimumLength: Int, maximumLength: Int, completionHandler: @escaping @Sendable (Data?, (any Error)?) -> Void) {
         let queuedRead: (Data?, Error?)?
         lock.lock()
         storedReadRequests += 1
+        storedLastReadMinimumLength = minimumLength
+        storedLastReadMaximumLength = maximumLength
         if !queuedReads.isEmpty {
             queuedRead = queuedReads.removeFirst()
         } else {
@@ -1864,6 +3335,7 @@ private final class ControlledUDPAssociateRelay: Socks5UDPRelayProtocol {
 
 private final class FakeProvider: Socks5FullConnectionProvider, @unchecked Sendable {
     private let outbound: ControlledTCPOutbound
+    private(set) var tcpEndpoints: [NWHostEndpoint] = []
     private(set) var udpSessions: [ControlledUDPSession] = []
     var onUDPSessionCreate: ((ControlledUDPSession) -> Void)?
 
@@ -1872,12 +3344,13 @@ private final class FakeProvider: Socks5FullConnectionProvider, @unchecked Senda
     }
 
     func makeTCPConnection(
-        to _: NWHostEndpoint,
+        to endpoint: NWHostEndpoint,
         enableTLS _: Bool,
         tlsParameters _: NWTLSParameters?,
         delegate _: (any NWTCPConnectionAuthenticationDelegate)?
     ) -> any Socks5TCPOutbound {
-        outbound
+        tcpEndpoints.append(endpoint)
+        return outbound
     }
 
     func makeUDPSession(to endpoint: NWHostEndpoint) -> any Socks5UDPSession {
@@ -164,11 +164,12 @@ final class Socks5UDPRelayTests: XCTestCase {
             sessionCreated.fulfill()
         }
 
+        // Port outside the single-transaction set, so the session survives the response.
         try sendClientDatagram(
             socketFD: clientSocket,
             relayPort: relay.port,
             destinationAddress: .ipv4("1.1.1.1"),
-            destinationPort: 53
+            destinationPort: 4_500
         )
 
         wait(for: [sessionCreated], timeout: 1.0)
@@ -177,6 +178,102 @@ final class Socks5UDPRelayTests: XCTestCase {
         XCTAssertFalse(try XCTUnwrap(provider.sessions.first).cancelled)
     }
 
+    /// Verifies a flow to a single-transaction port closes as soon as its response arrives
+    /// instead of idling out, so one-shot DNS lookups stop holding sessions.
+    func testSingleTransactionFlowClosesAfterResponse() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.single-transaction")
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionCreated = expectation(description: "udp session created")
+        provider.onCreate = { _ in
+            sessionCreated.fulfill()
+        }
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("1.1.1.1"),
+            destinationPort: 53
+        )
+        wait(for: [sessionCreated], timeout: 1.0)
+        let session = try XCTUnwrap(provider.sessions.first)
+        let outboundDeadline = Date().addingTimeInterval(1)
+        while session.writtenDatagrams.isEmpty, Date() < outboundDeadline {
+            usleep(10_000)
+        }
+
+        session.deliverRead(datagram: Data([0x00, 0x01, 0x81, 0x80]))
+        queue.sync {}
+
+        XCTAssertEqual(relay.activeSessionCount, 0)
+        XCTAssertTrue(session.cancelled)
+    }
+
+    /// Verifies a single-transaction flow with several requests in flight — retransmissions
+    /// or back-to-back lookups — stays open until the last outstanding one is answered.
+    func testSingleTransactionFlowWaitsForAllOutstandingResponses() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.single-transaction-multi")
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionCreated = expectation(description: "udp session created")
+        provider.onCreate = { _ in
+            sessionCreated.fulfill()
+        }
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("2.2.2.2"),
+            destinationPort: 123
+        )
+        wait(for: [sessionCreated], timeout: 1.0)
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("2.2.2.2"),
+            destinationPort: 123
+        )
+        let session = try XCTUnwrap(provider.sessions.first)
+        let outboundDeadline = Date().addingTimeInterval(1)
+        while session.writtenDatagrams.count < 2, Date() < outboundDeadline {
+            usleep(10_000)
+        }
+
+        session.deliverRead(datagram: Data([0x24]))
+        queue.sync {}
+        XCTAssertEqual(relay.activeSessionCount, 1)
+        XCTAssertFalse(session.cancelled)
+
+        session.deliverRead(datagram: Data([0x24]))
+        queue.sync {}
+        XCTAssertEqual(relay.activeSessionCount, 0)
+        XCTAssertTrue(session.cancelled)
+    }
+
     func testUDPRelayEvictsSessionAfterWriteFailure() async throws {
         let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.write-failure")
         let sink = InMemoryLogSink()
@@ -762,6 +859,230 @@ final class Socks5UDPRelayTests: XCTestCase {
         XCTAssertEqual(relay.activeSessionCount, 1)
     }
 
+    /// Verifies datagrams for the same destination drained in one socket wakeup are flushed
+    /// through a single batched session write, in arrival order.
+    func testUDPRelayBatchesSameDestinationDatagramsPerDrain() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.batch")
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink())
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionsCreated = expectation(description: "udp sessions created")
+        sessionsCreated.expectedFulfillmentCount = 2
+        provider.onCreate = { _ in
+            sessionsCreated.fulfill()
+        }
+
+        let payloads = [Data([0x01]), Data([0x02]), Data([0x03])]
+        // Blocking the relay queue while sending lets every datagram land in the socket
+        // buffer before a single drain pass runs.
+        try queue.sync {
+            for payload in payloads {
+                try sendClientDatagram(
+                    socketFD: clientSocket,
+                    relayPort: relay.port,
+                    destinationAddress: .ipv4("1.1.1.1"),
+                    destinationPort: 443,
+                    payload: payload
+                )
+            }
+            try sendClientDatagram(
+                socketFD: clientSocket,
+                relayPort: relay.port,
+                destinationAddress: .ipv4("8.8.8.8"),
+                destinationPort: 53
+            )
+        }
+
+        wait(for: [sessionsCreated], timeout: 1.0)
+        queue.sync {}
+
+        XCTAssertEqual(provider.sessions.count, 2)
+        let sameDestination = try XCTUnwrap(provider.sessions.first)
+        XCTAssertEqual(sameDestination.writtenBatchSizes, [3])
+        XCTAssertEqual(sameDestination.writtenDatagrams, payloads)
+        let otherDestination = try XCTUnwrap(provider.sessions.last)
+        XCTAssertEqual(otherDestination.writtenBatchSizes, [1])
+    }
+
+    /// Verifies a QUIC flow with a completed handshake (short headers both ways) survives the
+    /// generic UDP idle timeout and is only reaped after the extended QUIC timeout.
+    func testQUICEstablishedFlowOutlivesGenericIdleTimeout() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.quic.established")
+        let clock = TestClock(now: Date(timeIntervalSince1970: 0))
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            nowProvider: { clock.now }
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionCreated = expectation(description: "udp session created")
+        provider.onCreate = { _ in
+            sessionCreated.fulfill()
+        }
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("1.1.1.1"),
+            destinationPort: 443,
+            payload: Self.quicLongHeaderInitial
+        )
+        wait(for: [sessionCreated], timeout: 1.0)
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("1.1.1.1"),
+            destinationPort: 443,
+            payload: Self.quicShortHeader
+        )
+        let session = try XCTUnwrap(provider.sessions.first)
+        let outboundDeadline = Date().addingTimeInterval(1)
+        while session.writtenDatagrams.count < 2, Date() < outboundDeadline {
+            usleep(10_000)
+        }
+        session.deliverRead(datagram: Self.quicShortHeader)
+        queue.sync {}
+
+        clock.now = clock.now.addingTimeInterval(61)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 1)
+        }
+
+        clock.now = clock.now.addingTimeInterval(120)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 0)
+        }
+    }
+
+    /// Verifies a QUIC flow stuck in the handshake is reaped before the generic idle timeout.
+    func testQUICHandshakeOnlyFlowIsReapedEarly() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.quic.handshake")
+        let clock = TestClock(now: Date(timeIntervalSince1970: 0))
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            nowProvider: { clock.now }
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionCreated = expectation(description: "udp session created")
+        provider.onCreate = { _ in
+            sessionCreated.fulfill()
+        }
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("1.1.1.1"),
+            destinationPort: 443,
+            payload: Self.quicLongHeaderInitial
+        )
+        wait(for: [sessionCreated], timeout: 1.0)
+
+        clock.now = clock.now.addingTimeInterval(29)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 1)
+        }
+
+        clock.now = clock.now.addingTimeInterval(2)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 0)
+        }
+    }
+
+    /// Verifies an inbound version-negotiation packet marks the flow draining so cleanup
+    /// happens well before any idle timeout.
+    func testQUICVersionNegotiationExpeditesCleanup() throws {
+        let queue = DispatchQueue(label: "com.vpnbridge.tests.socks.udp.quic.draining")
+        let clock = TestClock(now: Date(timeIntervalSince1970: 0))
+        let provider = FakeUDPProvider()
+        let relay = try Socks5UDPRelay(
+            provider: provider,
+            queue: queue,
+            mtu: 1_500,
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            nowProvider: { clock.now }
+        )
+        relay.start()
+        defer { relay.stop() }
+
+        let clientSocket = socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP)
+        XCTAssertGreaterThanOrEqual(clientSocket, 0)
+        defer { close(clientSocket) }
+
+        let sessionCreated = expectation(description: "udp session created")
+        provider.onCreate = { _ in
+            sessionCreated.fulfill()
+        }
+
+        try sendClientDatagram(
+            socketFD: clientSocket,
+            relayPort: relay.port,
+            destinationAddress: .ipv4("1.1.1.1"),
+            destinationPort: 443,
+            payload: Self.quicLongHeaderInitial
+        )
+        wait(for: [sessionCreated], timeout: 1.0)
+
+        let session = try XCTUnwrap(provider.sessions.first)
+        session.deliverRead(datagram: Self.quicVersionNegotiation)
+        queue.sync {}
+
+        clock.now = clock.now.addingTimeInterval(4)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 1)
+        }
+
+        clock.now = clock.now.addingTimeInterval(2)
+        queue.sync {
+            relay.reapIdleSessions(now: clock.now)
+            XCTAssertEqual(relay.activeSessionCount, 0)
+        }
+    }
+
+    /// QUIC v1 Initial long header: fixed bit, type 0, 4-byte DCID, empty SCID.
+    private static let quicLongHeaderInitial = Data([
+        0xc3, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x02, 0x03, 0x04, 0x00
+    ])
+    /// QUIC 1-RTT short header: fixed bit set, high bit clear.
+    private static let quicShortHeader = Data([0x41, 0xaa, 0xbb, 0xcc])
+    /// Version negotiation: long-header form with version zero.
+    private static let quicVersionNegotiation = Data([0xc0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00])
+
     private func sendClientDatagram(
         socketFD: Int32,
         relayPort: UInt16,
@@ -853,6 +1174,7 @@ private final class FakeUDPSession: Socks5UDPSession, @unchecked Sendable {
     private var storedCancelled = false
     private var storedRestartCount = 0
     private var storedWrittenDatagrams: [Data] = []
+    private var storedWrittenBatchSizes: [Int] = []
     private var storedNextWriteError: Error?
     private var storedWriteErrors: [Error] = []
     private var storedOnWrite: ((Data) -> Void)?
@@ -877,6 +1199,12 @@ private final class FakeUDPSession: Socks5UDPSession, @unchecked Sendable {
         return storedWrittenDatagrams
     }
 
+    var writtenBatchSizes: [Int] {
+        lock.lock()
+        defer { lock.unlock() }
+        return storedWrittenBatchSizes
+    }
+
     var nextWriteError: Error? {
         get {
             lock.lock()
@@ -974,6 +1302,15 @@ private final class FakeUDPSession: Socks5UDPSession, @unchecked Sendable {
         completionHandler(nil)
     }
 
+    func writeDatagrams(_ datagrams: [Data], completionHandler: @escaping (Error?) -> Void) {
+        lock.lock()
+        storedWrittenBatchSizes.append(datagrams.count)
+        lock.unlock()
+        for datagram in datagrams {
+            writeDatagram(datagram, completionHandler: completionHandler)
+        }
+    }
+
     func restart() {
         lock.lock()
         storedRestartCount += 1
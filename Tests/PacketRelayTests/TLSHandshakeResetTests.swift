@@ -0,0 +1,105 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// ClientHello parsing and alert encoding tests for the blocked-flow TLS reset path.
+final class TLSHandshakeResetTests: XCTestCase {
+    /// Verifies the SNI host is recovered from a complete ClientHello.
+    func testParsesServerNameFromClientHello() {
+        let result = TLSHandshakeReset.parseClientHello(Self.clientHello(serverName: "blocked.example"))
+        XCTAssertEqual(result, .clientHello(serverName: "blocked.example", hasECH: false))
+    }
+
+    /// Verifies a ClientHello without extensions still parses, with no server name.
+    func testParsesClientHelloWithoutServerName() {
+        let result = TLSHandshakeReset.parseClientHello(Self.clientHello(serverName: nil))
+        XCTAssertEqual(result, .clientHello(serverName: nil, hasECH: false))
+    }
+
+    /// Verifies the ECH extension is detected alongside the cover SNI the client sends with it.
+    func testDetectsECHExtension() {
+        let result = TLSHandshakeReset.parseClientHello(Self.clientHello(serverName: "cover.example", ech: true))
+        XCTAssertEqual(result, .clientHello(serverName: "cover.example", hasECH: true))
+    }
+
+    /// Verifies truncated TLS prefixes wait for more data instead of misclassifying.
+    func testTruncatedRecordNeedsMoreData() {
+        let hello = Self.clientHello(serverName: "blocked.example")
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(hello.prefix(3)), .needsMoreData)
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(hello.prefix(20)), .needsMoreData)
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(Data()), .needsMoreData)
+    }
+
+    /// Verifies a ClientHello fragmented across two TLS records reassembles and parses,
+    /// and that truncations of the fragmented stream wait instead of misclassifying.
+    func testReassemblesClientHelloSplitAcrossRecords() {
+        let hello = Self.clientHello(serverName: "split.example")
+        let handshake = Data(hello.dropFirst(5))
+        let firstLength = handshake.count / 2
+        let secondLength = handshake.count - firstLength
+
+        var fragmented = Data([0x16, 0x03, 0x01, UInt8(firstLength >> 8), UInt8(firstLength & 0xFF)])
+        fragmented.append(handshake.prefix(firstLength))
+        fragmented.append(contentsOf: [0x16, 0x03, 0x01, UInt8(secondLength >> 8), UInt8(secondLength & 0xFF)])
+        fragmented.append(handshake.suffix(secondLength))
+
+        XCTAssertEqual(
+            TLSHandshakeReset.parseClientHello(fragmented),
+            .clientHello(serverName: "split.example", hasECH: false)
+        )
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(fragmented.prefix(fragmented.count - 1)), .needsMoreData)
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(fragmented.prefix(7)), .needsMoreData)
+    }
+
+    /// Verifies non-TLS streams are reported as such so the caller can reset without an alert.
+    func testNonTLSStreamReportsNotTLS() {
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(Data("GET / HTTP/1.1\r\n".utf8)), .notTLS)
+        XCTAssertEqual(TLSHandshakeReset.parseClientHello(Data([0x17, 0x03, 0x03, 0x00, 0x01, 0x00])), .notTLS)
+    }
+
+    /// Verifies the fatal alert record matches the RFC 8446 wire encoding.
+    func testFatalAlertEncoding() {
+        XCTAssertEqual(
+            TLSHandshakeReset.makeFatalAlert(.accessDenied),
+            Data([0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 49])
+        )
+        XCTAssertEqual(
+            TLSHandshakeReset.makeFatalAlert(.unrecognizedName),
+            Data([0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 112])
+        )
+    }
+
+    private static func clientHello(serverName: String?, ech: Bool = false) -> Data {
+        var body = Data([0x03, 0x03])
+        body.append(Data(repeating: 0, count: 32))
+        body.append(0x00)
+        body.append(contentsOf: [0x00, 0x02, 0x13, 0x01])
+        body.append(contentsOf: [0x01, 0x00])
+        if serverName != nil || ech {
+            var extensions = Data()
+            if let serverName {
+                let name = Array(serverName.utf8)
+                let listLength = name.count + 3
+                let extensionLength = listLength + 2
+                extensions.append(contentsOf: [0x00, 0x00, UInt8(extensionLength >> 8), UInt8(extensionLength & 0xFF)])
+                extensions.append(contentsOf: [UInt8(listLength >> 8), UInt8(listLength & 0xFF), 0x00])
+                extensions.append(contentsOf: [UInt8(name.count >> 8), UInt8(name.count & 0xFF)])
+                extensions.append(contentsOf: name)
+            }
+            if ech {
+                extensions.append(contentsOf: [0xFE, 0x0D, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00])
+            }
+            body.append(contentsOf: [UInt8(extensions.count >> 8), UInt8(extensions.count & 0xFF)])
+            body.append(extensions)
+        }
+        var handshake = Data([0x01, UInt8(body.count >> 16), UInt8((body.count >> 8) & 0xFF), UInt8(body.count & 0xFF)])
+        handshake.append(body)
+        var record = Data([0x16, 0x03, 0x01, UInt8(handshake.count >> 8), UInt8(handshake.count & 0xFF)])
+        record.append(handshake)
+        return record
+    }
+}
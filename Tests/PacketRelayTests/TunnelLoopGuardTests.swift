@@ -0,0 +1,42 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import Foundation
+@testable import PacketRelay
+import XCTest
+
+/// Tunnel self-traffic loop guard classification and counter tests.
+final class TunnelLoopGuardTests: XCTestCase {
+    /// Verifies the tunnel's own addresses are flagged and everything else passes.
+    func testSelfAddressesAreFlagged() {
+        let guardUnderTest = TunnelLoopGuard(selfAddresses: ["10.7.0.2", "10.7.0.1", "203.0.113.9"])
+
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "10.7.0.2"))
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "10.7.0.1"))
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "203.0.113.9"))
+        XCTAssertFalse(guardUnderTest.isSelfDestination(host: "10.7.0.3"))
+        XCTAssertFalse(guardUnderTest.isSelfDestination(host: "media.example.com"))
+
+        XCTAssertEqual(guardUnderTest.droppedFlows(), 3)
+    }
+
+    /// Verifies IPv6 self addresses match across textual variants.
+    func testIPv6VariantsMatch() {
+        let guardUnderTest = TunnelLoopGuard(selfAddresses: ["fd00:abcd::2"])
+
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "fd00:abcd::2"))
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "fd00:abcd:0:0:0:0:0:2"))
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "FD00:ABCD::2"))
+        XCTAssertFalse(guardUnderTest.isSelfDestination(host: "fd00:abcd::3"))
+    }
+
+    /// Verifies empty configuration entries are ignored rather than matching empty hosts.
+    func testEmptyEntriesAreIgnored() {
+        let guardUnderTest = TunnelLoopGuard(selfAddresses: ["", "10.7.0.2"])
+
+        XCTAssertFalse(guardUnderTest.isSelfDestination(host: ""))
+        XCTAssertTrue(guardUnderTest.isSelfDestination(host: "10.7.0.2"))
+        XCTAssertEqual(guardUnderTest.droppedFlows(), 1)
+    }
+}
@@ -0,0 +1,162 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import TunnelControl
+import XCTest
+
+/// Gateway-hop ICMP error policy tests.
+final class OutboundICMPResponderTests: XCTestCase {
+    /// Verifies a packet whose TTL runs out at the gateway is answered with a
+    /// gateway-sourced Time Exceeded instead of being forwarded.
+    func testTTLExpiryAnswersWithTimeExceeded() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+        let packet = makeIPv4Packet(protocolNumber: 17, ttl: 1, destination: [8, 8, 8, 8], destinationPort: 33_434)
+
+        let response = try XCTUnwrap(responder.response(forOutboundPacket: packet))
+        let bytes = [UInt8](response)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 1])
+        XCTAssertEqual(bytes[20], 11)
+        XCTAssertEqual(bytes[21], 0)
+    }
+
+    /// Verifies a healthy packet and a TTL-1 packet addressed to the gateway itself
+    /// are both forwarded: the gateway is the final hop for the latter.
+    func testDeliverablePacketsAreForwarded() {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+        XCTAssertNil(
+            responder.response(
+                forOutboundPacket: makeIPv4Packet(protocolNumber: 17, ttl: 64, destination: [8, 8, 8, 8], destinationPort: 443)
+            )
+        )
+        XCTAssertNil(
+            responder.response(
+                forOutboundPacket: makeIPv4Packet(protocolNumber: 6, ttl: 1, destination: [10, 0, 0, 1], destinationPort: 443)
+            )
+        )
+    }
+
+    /// Verifies UDP aimed at the gateway's own address gets a port unreachable,
+    /// except on the DNS port the resolver settings legitimately serve.
+    func testUDPToGatewayAnswersPortUnreachableExceptDNS() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+
+        let probe = makeIPv4Packet(protocolNumber: 17, ttl: 64, destination: [10, 0, 0, 1], destinationPort: 4_500)
+        let response = try XCTUnwrap(responder.response(forOutboundPacket: probe))
+        let bytes = [UInt8](response)
+        XCTAssertEqual(Array(bytes[12..<16]), [10, 0, 0, 1])
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 3)
+
+        let dns = makeIPv4Packet(protocolNumber: 17, ttl: 64, destination: [10, 0, 0, 1], destinationPort: 53)
+        XCTAssertNil(responder.response(forOutboundPacket: dns))
+    }
+
+    /// Verifies ICMP error offenders and non-initial fragments never elicit errors,
+    /// while expiring echo probes still do so ICMP traceroute names the hop.
+    func testErrorSuppressionRules() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+
+        var icmpError = [UInt8](makeIPv4Packet(protocolNumber: 1, ttl: 1, destination: [8, 8, 8, 8], destinationPort: 0))
+        icmpError[20] = 3
+        XCTAssertNil(responder.response(forOutboundPacket: Data(icmpError)))
+
+        var echo = icmpError
+        echo[20] = 8
+        XCTAssertNotNil(responder.response(forOutboundPacket: Data(echo)))
+
+        var fragment = [UInt8](makeIPv4Packet(protocolNumber: 17, ttl: 1, destination: [8, 8, 8, 8], destinationPort: 33_434))
+        fragment[7] = 0x10
+        XCTAssertNil(responder.response(forOutboundPacket: Data(fragment)))
+    }
+
+    /// Verifies a UDP traceroute probe that outlives the virtual path is answered with
+    /// the destination's port unreachable so the trace completes at the final hop.
+    func testUDPProbeBeyondVirtualPathTerminatesAtDestination() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+        let probe = makeIPv4Packet(protocolNumber: 17, ttl: 30, destination: [8, 8, 8, 8], destinationPort: 33_434)
+
+        let response = try XCTUnwrap(responder.response(forOutboundPacket: probe))
+        let bytes = [UInt8](response)
+        XCTAssertEqual(Array(bytes[12..<16]), [8, 8, 8, 8])
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 3)
+    }
+
+    /// Verifies a longer virtual path answers middle-hop probes from synthetic
+    /// documentation-range addresses and still terminates at the destination.
+    func testSyntheticMiddleHopsAnswerProbes() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1", virtualHopCount: 4)
+
+        for ttl: UInt8 in [2, 3] {
+            let probe = makeIPv4Packet(protocolNumber: 17, ttl: ttl, destination: [8, 8, 8, 8], destinationPort: 33_434)
+            let response = try XCTUnwrap(responder.response(forOutboundPacket: probe))
+            let bytes = [UInt8](response)
+            XCTAssertEqual(Array(bytes[12..<16]), [198, 51, 100, ttl])
+            XCTAssertEqual(bytes[20], 11)
+            XCTAssertEqual(bytes[21], 0)
+        }
+
+        let finalProbe = makeIPv4Packet(protocolNumber: 17, ttl: 4, destination: [8, 8, 8, 8], destinationPort: 33_434)
+        let terminal = try XCTUnwrap(responder.response(forOutboundPacket: finalProbe))
+        XCTAssertEqual([UInt8](terminal)[20], 3)
+
+        let ordinary = makeIPv4Packet(protocolNumber: 17, ttl: 2, destination: [8, 8, 8, 8], destinationPort: 443)
+        XCTAssertNil(responder.response(forOutboundPacket: ordinary))
+    }
+
+    /// Verifies echo probes walk the synthetic middle hops but are forwarded once they
+    /// outlive the path: the responder never forges an echo reply.
+    func testEchoProbesWalkMiddleHopsButAreNeverFakedAtDestination() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1", virtualHopCount: 4)
+
+        var echo = [UInt8](makeIPv4Packet(protocolNumber: 1, ttl: 2, destination: [8, 8, 8, 8], destinationPort: 0))
+        echo[20] = 8
+        let response = try XCTUnwrap(responder.response(forOutboundPacket: Data(echo)))
+        let bytes = [UInt8](response)
+        XCTAssertEqual(Array(bytes[12..<16]), [198, 51, 100, 2])
+        XCTAssertEqual(bytes[20], 11)
+
+        echo[8] = 10
+        XCTAssertNil(responder.response(forOutboundPacket: Data(echo)))
+    }
+
+    /// Verifies policy rejections are answered with an administratively-prohibited
+    /// unreachable and that an unparseable gateway leaves the responder disabled.
+    func testAdminProhibitedAndDisabledResponder() throws {
+        let responder = OutboundICMPResponder(ipv4Gateway: "10.0.0.1")
+        let rejected = makeIPv4Packet(protocolNumber: 17, ttl: 64, destination: [8, 8, 8, 8], destinationPort: 443)
+
+        let response = try XCTUnwrap(responder.adminProhibitedResponse(forRejectedPacket: rejected))
+        let bytes = [UInt8](response)
+        XCTAssertEqual(bytes[20], 3)
+        XCTAssertEqual(bytes[21], 13)
+
+        let disabled = OutboundICMPResponder(ipv4Gateway: "not-an-address")
+        XCTAssertFalse(disabled.isEnabled)
+        XCTAssertNil(disabled.response(forOutboundPacket: makeIPv4Packet(protocolNumber: 17, ttl: 1, destination: [8, 8, 8, 8], destinationPort: 33_434)))
+        XCTAssertNil(disabled.adminProhibitedResponse(forRejectedPacket: rejected))
+        XCTAssertFalse(OutboundICMPResponder.disabled.isEnabled)
+    }
+
+    private func makeIPv4Packet(
+        protocolNumber: UInt8,
+        ttl: UInt8,
+        destination: [UInt8],
+        destinationPort: UInt16
+    ) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 28)
+        bytes[0] = 0x45
+        bytes[2] = 0
+        bytes[3] = 28
+        bytes[8] = ttl
+        bytes[9] = protocolNumber
+        bytes.replaceSubrange(12..<16, with: [10, 0, 0, 2])
+        bytes.replaceSubrange(16..<20, with: destination)
+        bytes[20] = 0xc3
+        bytes[21] = 0x50
+        bytes[22] = UInt8(truncatingIfNeeded: destinationPort >> 8)
+        bytes[23] = UInt8(truncatingIfNeeded: destinationPort)
+        return Data(bytes)
+    }
+}
@@ -0,0 +1,220 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+@testable import TunnelControl
+import XCTest
+
+/// pf-style outbound normalization tests.
+final class PacketScrubberTests: XCTestCase {
+    /// Verifies an unfragmented IPv4 packet gets the injected IP ID and the header
+    /// checksum still verifies after the rewrite.
+    func testRandomizesIPv4IDWithValidChecksum() throws {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0xBEEF })
+        let packet = makeIPv4UDPPacket(id: 0x0001, ttl: 64)
+
+        let scrubbed = try XCTUnwrap(passedPacket(scrubber.scrub(packet)))
+        let bytes = [UInt8](scrubbed)
+        XCTAssertEqual(UInt16(bytes[4]) << 8 | UInt16(bytes[5]), 0xBEEF)
+        XCTAssertEqual(internetChecksum(Array(bytes[0..<20])), 0)
+    }
+
+    /// Verifies a fragment whose byte range overlaps an earlier fragment of the same
+    /// train is dropped while non-overlapping fragments pass.
+    func testDropsOverlappingFragments() {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0 })
+        let first = makeIPv4Fragment(id: 7, fragmentOffsetUnits: 0, moreFragments: true, payloadLength: 16)
+        let overlap = makeIPv4Fragment(id: 7, fragmentOffsetUnits: 1, moreFragments: true, payloadLength: 16)
+        let adjacent = makeIPv4Fragment(id: 7, fragmentOffsetUnits: 2, moreFragments: true, payloadLength: 16)
+
+        XCTAssertNotNil(passedPacket(scrubber.scrub(first)))
+        XCTAssertEqual(scrubber.scrub(overlap), .drop)
+        XCTAssertNotNil(passedPacket(scrubber.scrub(adjacent)))
+    }
+
+    /// Verifies the last fragment retires the train so a new train reusing the same
+    /// IP ID starts from a clean slate.
+    func testLastFragmentRetiresTrainState() {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0 })
+        let first = makeIPv4Fragment(id: 9, fragmentOffsetUnits: 0, moreFragments: true, payloadLength: 16)
+        let last = makeIPv4Fragment(id: 9, fragmentOffsetUnits: 2, moreFragments: false, payloadLength: 16)
+
+        XCTAssertNotNil(passedPacket(scrubber.scrub(first)))
+        XCTAssertNotNil(passedPacket(scrubber.scrub(last)))
+        XCTAssertNotNil(passedPacket(scrubber.scrub(first)))
+    }
+
+    /// Verifies reserved TCP header bits are cleared and the TCP checksum is adjusted
+    /// by the same delta so the segment still verifies end to end.
+    func testClearsReservedTCPFlagsAndAdjustsChecksum() throws {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0x1234 })
+        let packet = makeIPv4TCPPacket(reservedBits: 0x0f)
+
+        let scrubbed = try XCTUnwrap(passedPacket(scrubber.scrub(packet)))
+        let bytes = [UInt8](scrubbed)
+        XCTAssertEqual(bytes[20 + 12] & 0x0f, 0)
+        XCTAssertEqual(bytes[20 + 12] >> 4, 5)
+        XCTAssertEqual(internetChecksum(tcpPseudoHeaderAndSegment(bytes)), 0)
+    }
+
+    /// Verifies a near-zero TTL is raised to the policy floor, a zero TTL is left for
+    /// the engine's own expiry handling, and the header checksum stays valid.
+    func testEnforcesMinimumTTL() throws {
+        var scrubber = PacketScrubber(
+            enabled: true,
+            policy: PacketScrubber.Policy(randomizeIPv4ID: false, minimumTTL: 8),
+            makeIPID: { 0 }
+        )
+        let low = try XCTUnwrap(passedPacket(scrubber.scrub(makeIPv4UDPPacket(id: 1, ttl: 3))))
+        XCTAssertEqual([UInt8](low)[8], 8)
+        XCTAssertEqual(internetChecksum(Array([UInt8](low)[0..<20])), 0)
+
+        let zero = try XCTUnwrap(passedPacket(scrubber.scrub(makeIPv4UDPPacket(id: 2, ttl: 0))))
+        XCTAssertEqual([UInt8](zero)[8], 0)
+
+        let healthy = try XCTUnwrap(passedPacket(scrubber.scrub(makeIPv4UDPPacket(id: 3, ttl: 64))))
+        XCTAssertEqual([UInt8](healthy)[8], 64)
+    }
+
+    /// Verifies the IPv6 hop limit floor applies and direct-TCP reserved bits clear.
+    func testScrubsIPv6HopLimitAndReservedBits() throws {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0 })
+        let packet = makeIPv6TCPPacket(hopLimit: 2, reservedBits: 0x01)
+
+        let scrubbed = try XCTUnwrap(passedPacket(scrubber.scrub(packet)))
+        let bytes = [UInt8](scrubbed)
+        XCTAssertEqual(bytes[7], 8)
+        XCTAssertEqual(bytes[40 + 12] & 0x0f, 0)
+    }
+
+    /// Verifies the disabled scrubber passes every packet through byte-for-byte,
+    /// including fragments that an enabled scrubber would drop.
+    func testDisabledScrubberPassesPacketsUnchanged() {
+        var scrubber = PacketScrubber.disabled
+        let packet = makeIPv4UDPPacket(id: 0x0001, ttl: 1)
+        let overlapA = makeIPv4Fragment(id: 7, fragmentOffsetUnits: 0, moreFragments: true, payloadLength: 16)
+        let overlapB = makeIPv4Fragment(id: 7, fragmentOffsetUnits: 1, moreFragments: true, payloadLength: 16)
+
+        XCTAssertEqual(scrubber.scrub(packet), .pass(packet))
+        XCTAssertEqual(scrubber.scrub(overlapA), .pass(overlapA))
+        XCTAssertEqual(scrubber.scrub(overlapB), .pass(overlapB))
+    }
+
+    /// Verifies packets too short to carry the headers being normalized pass through
+    /// untouched instead of being rewritten out of bounds.
+    func testTruncatedPacketsPassThrough() {
+        var scrubber = PacketScrubber(enabled: true, makeIPID: { 0xBEEF })
+        let runt = Data([0x45, 0x00, 0x00])
+        XCTAssertEqual(scrubber.scrub(runt), .pass(runt))
+        XCTAssertEqual(scrubber.scrub(Data()), .pass(Data()))
+    }
+
+    private func passedPacket(_ verdict: PacketScrubber.Verdict) -> Data? {
+        if case .pass(let packet) = verdict {
+            return packet
+        }
+        return nil
+    }
+
+    private func makeIPv4UDPPacket(id: UInt16, ttl: UInt8) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 28)
+        bytes[0] = 0x45
+        bytes[2] = 0
+        bytes[3] = 28
+        bytes[4] = UInt8(id >> 8)
+        bytes[5] = UInt8(id & 0xff)
+        bytes[8] = ttl
+        bytes[9] = 17
+        bytes[12..<16] = [10, 0, 0, 2][0...]
+        bytes[16..<20] = [8, 8, 8, 8][0...]
+        storeHeaderChecksum(&bytes)
+        return Data(bytes)
+    }
+
+    private func makeIPv4Fragment(
+        id: UInt16,
+        fragmentOffsetUnits: UInt16,
+        moreFragments: Bool,
+        payloadLength: Int
+    ) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 20 + payloadLength)
+        bytes[0] = 0x45
+        bytes[2] = UInt8((20 + payloadLength) >> 8)
+        bytes[3] = UInt8((20 + payloadLength) & 0xff)
+        bytes[4] = UInt8(id >> 8)
+        bytes[5] = UInt8(id & 0xff)
+        let fragmentField = (moreFragments ? UInt16(0x2000) : 0) | fragmentOffsetUnits
+        bytes[6] = UInt8(fragmentField >> 8)
+        bytes[7] = UInt8(fragmentField & 0xff)
+        bytes[8] = 64
+        bytes[9] = 17
+        bytes[12..<16] = [10, 0, 0, 2][0...]
+        bytes[16..<20] = [8, 8, 8, 8][0...]
+        storeHeaderChecksum(&bytes)
+        return Data(bytes)
+    }
+
+    private func makeIPv4TCPPacket(reservedBits: UInt8) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 40)
+        bytes[0] = 0x45
+        bytes[2] = 0
+        bytes[3] = 40
+        bytes[8] = 64
+        bytes[9] = 6
+        bytes[12..<16] = [10, 0, 0, 2][0...]
+        bytes[16..<20] = [1, 1, 1, 1][0...]
+        storeHeaderChecksum(&bytes)
+        bytes[20 + 12] = 0x50 | (reservedBits & 0x0f)
+        bytes[20 + 13] = 0x18
+        let checksum = internetChecksum(tcpPseudoHeaderAndSegment(bytes))
+        bytes[20 + 16] = UInt8(checksum >> 8)
+        bytes[20 + 17] = UInt8(checksum & 0xff)
+        return Data(bytes)
+    }
+
+    /// IPv4 pseudo-header plus the TCP segment, the input a receiver checksums.
+    private func tcpPseudoHeaderAndSegment(_ bytes: [UInt8]) -> [UInt8] {
+        let segment = Array(bytes[20...])
+        var input = Array(bytes[12..<20])
+        input += [0, 6, UInt8(segment.count >> 8), UInt8(segment.count & 0xff)]
+        return input + segment
+    }
+
+    private func makeIPv6TCPPacket(hopLimit: UInt8, reservedBits: UInt8) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 60)
+        bytes[0] = 0x60
+        bytes[4] = 0
+        bytes[5] = 20
+        bytes[6] = 6
+        bytes[7] = hopLimit
+        bytes[23] = 2
+        bytes[39] = 1
+        bytes[40 + 12] = 0x50 | (reservedBits & 0x0f)
+        bytes[40 + 13] = 0x18
+        return Data(bytes)
+    }
+
+    private func storeHeaderChecksum(_ bytes: inout [UInt8]) {
+        bytes[10] = 0
+        bytes[11] = 0
+        let checksum = internetChecksum(Array(bytes[0..<20]))
+        bytes[10] = UInt8(checksum >> 8)
+        bytes[11] = UInt8(checksum & 0xff)
+    }
+
+    private func internetChecksum(_ bytes: [UInt8]) -> UInt16 {
+        var sum: UInt32 = 0
+        var index = 0
+        while index + 1 < bytes.count {
+            sum &+= UInt32(bytes[index]) << 8 | UInt32(bytes[index + 1])
+            index += 2
+        }
+        if index < bytes.count {
+            sum &+= UInt32(bytes[index]) << 8
+        }
+        while sum > 0xffff {
+            sum = (sum & 0xffff) &+ (sum >> 16)
+        }
+        return ~UInt16(sum) & 0xffff
+    }
+}
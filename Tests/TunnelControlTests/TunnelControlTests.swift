@@ -474,6 +474,23 @@ final class TunnelControlTests: XCTestCase {
         }
     }
 
+    func testRuntimeProfileValidationRejectsMalformedIncludedIPv6Routes() {
+        var configuration = makeRuntimeProviderConfiguration()
+        configuration["ipv6IncludedRoutes"] = [
+            [
+                "destinationAddress": "2001:db8::ffff",
+                "prefixLength": 129
+            ]
+        ]
+
+        XCTAssertThrowsError(try TunnelProfile.validatedRuntimeProfile(providerConfiguration: configuration)) { error in
+            XCTAssertEqual(
+                error as? TunnelProfileValidationError,
+                .invalidValue(key: "ipv6IncludedRoutes", reason: "prefixLength must be in 1...128")
+            )
+        }
+    }
+
     func testAutomaticTunnelOverheadUsesSafeInternalMTUBuffer() {
         let profile = TunnelProfile.from(providerConfiguration: [
             "mtuStrategy": "automaticTunnelOverhead",
@@ -509,6 +526,71 @@ final class TunnelControlTests: XCTestCase {
         )
     }
 
+    func testTunnelProfileParsesIncludedIPv6Routes() {
+        let profile = TunnelProfile.from(providerConfiguration: [
+            "ipv6IncludedRoutes": [
+                [
+                    "destinationAddress": "2001:db8::",
+                    "prefixLength": 32
+                ]
+            ]
+        ])
+
+        XCTAssertEqual(
+            profile.ipv6RouteStrategy,
+            .includedRoutes([
+                TunnelIPv6Route(destinationAddress: "2001:db8::", prefixLength: 32)
+            ])
+        )
+    }
+
+    func testClientSubnetPolicyFiltersSourcesOutsideConfiguredRanges() throws {
+        let policy = ClientSubnetPolicy(subnets: [
+            try XCTUnwrap(ClientSubnet(cidr: "10.0.0.0/24")),
+            try XCTUnwrap(ClientSubnet(cidr: "fd00:1::/64"))
+        ])
+
+        XCTAssertTrue(policy.allows(packet: makeIPv4Header(source: [10, 0, 0, 7])))
+        XCTAssertFalse(policy.allows(packet: makeIPv4Header(source: [192, 168, 1, 5])))
+        XCTAssertTrue(policy.allows(packet: makeIPv6Header(sourcePrefix: [0xfd, 0x00, 0x00, 0x01])))
+        XCTAssertFalse(policy.allows(packet: makeIPv6Header(sourcePrefix: [0x20, 0x01, 0x0d, 0xb8])))
+        XCTAssertFalse(policy.allows(packet: Data([0x45, 0x00])))
+        XCTAssertTrue(ClientSubnetPolicy.acceptAll.allows(packet: makeIPv4Header(source: [192, 168, 1, 5])))
+    }
+
+    func testTunnelProfileParsesClientSubnetsAndRejectsMalformedCIDRs() {
+        let profile = TunnelProfile.from(providerConfiguration: [
+            "clientSubnets": ["10.0.0.0/24", "fd00:1::/64"]
+        ])
+        XCTAssertEqual(profile.clientSubnets.map(\.cidr), ["10.0.0.0/24", "fd00:1::/64"])
+
+        var configuration = makeRuntimeProviderConfiguration()
+        configuration["clientSubnets"] = ["10.0.0.0/24", "not-a-cidr"]
+        XCTAssertThrowsError(try TunnelProfile.validatedRuntimeProfile(providerConfiguration: configuration)) { error in
+            XCTAssertEqual(
+                error as? TunnelProfileValidationError,
+                .invalidValue(key: "clientSubnets", reason: "must contain only valid IPv4 or IPv6 CIDRs")
+            )
+        }
+    }
+
+    private func makeIPv4Header(source: [UInt8]) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 20)
+        bytes[0] = 0x45
+        bytes[12..<16] = source[0..<4]
+        bytes[16..<20] = [203, 0, 113, 9][0..<4]
+        return Data(bytes)
+    }
+
+    private func makeIPv6Header(sourcePrefix: [UInt8]) -> Data {
+        var bytes = [UInt8](repeating: 0, count: 40)
+        bytes[0] = 0x60
+        bytes[8..<12] = sourcePrefix[0..<4]
+        bytes[23] = 0x02
+        bytes[39] = 0x01
+        return Data(bytes)
+    }
+
     func testTunnelProfilePreservesEphemeralEngineSocksPort() {
         let profile = TunnelProfile.from(providerConfiguration: [
             "engineSocksPort": 0
@@ -923,6 +1005,20 @@ final class TunnelControlTests: XCTestCase {
         XCTAssertTrue(udpOverUDPConfig.contains("  udp: 'udp'"))
     }
 
+    /// Verifies the documented `TunnelEngine` surface keeps pointing at the supported
+    /// concrete types, so embedders using only the aliases never break on module moves.
+    func testTunnelEngineSurfaceAliasesResolveToSupportedTypes() {
+        XCTAssertTrue(TunnelEngine.Shell.self == PacketTunnelProviderShell.self)
+        XCTAssertTrue(TunnelEngine.Profile.self == TunnelProfile.self)
+        XCTAssertTrue(TunnelEngine.ProfileManager.self == TunnelProfileManager.self)
+        XCTAssertTrue(TunnelEngine.Policy.self == RelaySwappablePolicy.self)
+        XCTAssertTrue(TunnelEngine.PolicyCompiler.self == RelayPolicyCompiler.self)
+        XCTAssertTrue(TunnelEngine.PolicyUpdater.self == RelayPolicyBundleUpdater.self)
+        XCTAssertTrue(TunnelEngine.TelemetryWorker.self == PacketTelemetryWorker.self)
+        XCTAssertTrue(TunnelEngine.TelemetrySnapshot.self == TunnelTelemetrySnapshot.self)
+        XCTAssertTrue(TunnelEngine.TelemetrySeries.self == TunnelTelemetrySeries.self)
+    }
+
     private func makeProfile(
         appGroupID: String = "group.example",
         mtu: Int = 1_280,
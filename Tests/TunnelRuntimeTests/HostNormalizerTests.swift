@@ -0,0 +1,30 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import TunnelRuntime
+import XCTest
+
+/// Canonical-hostname folding tests covering IDNA, case, and root-dot handling.
+final class HostNormalizerTests: XCTestCase {
+    /// Verifies unicode labels fold to their `xn--` Punycode form while ASCII labels pass through.
+    func testUnicodeHostEncodesToPunycode() {
+        XCTAssertEqual(HostNormalizer.normalize("bücher.example"), "xn--bcher-kva.example")
+        XCTAssertEqual(HostNormalizer.normalize("пример.example"), "xn--e1afmkfd.example")
+        XCTAssertEqual(HostNormalizer.normalize("mixed.bücher.example"), "mixed.xn--bcher-kva.example")
+    }
+
+    /// Verifies normalization is idempotent: already-encoded hosts come back unchanged.
+    func testNormalizationIsIdempotent() {
+        let once = HostNormalizer.normalize("Bücher.Example.")
+        XCTAssertEqual(once, "xn--bcher-kva.example")
+        XCTAssertEqual(HostNormalizer.normalize(once), once)
+    }
+
+    /// Verifies case folding and trailing root-dot stripping for plain ASCII hosts.
+    func testCaseAndRootDotFolding() {
+        XCTAssertEqual(HostNormalizer.normalize("API.Example.COM."), "api.example.com")
+        XCTAssertEqual(HostNormalizer.normalize("example.com"), "example.com")
+        XCTAssertEqual(HostNormalizer.normalize("*.Example.com"), "*.example.com")
+    }
+}
@@ -0,0 +1,44 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import TunnelRuntime
+import XCTest
+
+/// Public-suffix rule parsing and registrable-domain grouping tests.
+final class PublicSuffixListTests: XCTestCase {
+    /// Verifies exact rules group hosts at the suffix-plus-one-label boundary.
+    func testRegistrableDomainAtExactSuffixBoundary() {
+        let list = PublicSuffixList.embedded
+        XCTAssertEqual(list.registrableDomain(of: "shop.example.co.uk"), "example.co.uk")
+        XCTAssertEqual(list.registrableDomain(of: "Example.CO.UK."), "example.co.uk")
+        XCTAssertEqual(list.registrableDomain(of: "deep.api.example.com"), "example.com")
+        XCTAssertNil(list.registrableDomain(of: "co.uk"))
+        XCTAssertNil(list.registrableDomain(of: "com"))
+    }
+
+    /// Verifies wildcard rules make each child a suffix and exception rules re-admit a label.
+    func testWildcardAndExceptionRules() {
+        let list = PublicSuffixList.embedded
+        XCTAssertTrue(list.isPublicSuffix("anything.ck"))
+        XCTAssertEqual(list.registrableDomain(of: "host.anything.ck"), "host.anything.ck")
+        XCTAssertFalse(list.isPublicSuffix("www.ck"))
+        XCTAssertEqual(list.registrableDomain(of: "sub.www.ck"), "www.ck")
+    }
+
+    /// Verifies custom PSL text parses comments, wildcards, and exceptions, and that
+    /// `isPublicSuffix` flags suffixes wildcard patterns would span.
+    func testParsesListTextAndFlagsSuffixes() {
+        let list = PublicSuffixList(listText: """
+            // comment line
+            dev.example
+
+            *.hosted.example
+            !apps.hosted.example
+            """)
+        XCTAssertTrue(list.isPublicSuffix("dev.example"))
+        XCTAssertTrue(list.isPublicSuffix("tenant.hosted.example"))
+        XCTAssertFalse(list.isPublicSuffix("apps.hosted.example"))
+        XCTAssertEqual(list.registrableDomain(of: "team.dev.example"), "team.dev.example")
+    }
+}
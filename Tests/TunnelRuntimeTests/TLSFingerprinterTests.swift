@@ -0,0 +1,205 @@
+// Created by Will Kusch, Relative Companies, Inc.
+// Copyright (c) 2026 Relative Companies, Inc.
+// Licensed for personal, non-commercial use only. See LICENSE for terms.
+
+import TunnelRuntime
+import XCTest
+
+/// JA3/JA4 fingerprinting tests over synthetic ClientHello bytes.
+final class TLSFingerprinterTests: XCTestCase {
+    /// Verifies a well-formed ClientHello yields a 32-hex JA3 and a JA4 header that
+    /// encodes transport, version, SNI presence, counts, and the ALPN protocol.
+    func testFingerprintShapeForKnownHello() throws {
+        let fingerprint = try XCTUnwrap(
+            TLSFingerprinter.fingerprint(record: Self.clientHelloRecord(includeGREASE: false))
+        )
+        XCTAssertEqual(fingerprint.ja3.count, 32)
+        XCTAssertTrue(fingerprint.ja3.allSatisfy(\.isHexDigit))
+        // t=TCP, 13=TLS 1.3 from supported_versions, d=SNI present, 2 ciphers,
+        // 5 extensions, ALPN h2.
+        XCTAssertTrue(fingerprint.ja4.hasPrefix("t13d0205h2_"), fingerprint.ja4)
+    }
+
+    /// Verifies GREASE ciphers, extensions, groups, and versions (RFC 8701) do not
+    /// change either fingerprint.
+    func testGREASEValuesDoNotAffectFingerprints() throws {
+        let plain = try XCTUnwrap(
+            TLSFingerprinter.fingerprint(record: Self.clientHelloRecord(includeGREASE: false))
+        )
+        let greased = try XCTUnwrap(
+            TLSFingerprinter.fingerprint(record: Self.clientHelloRecord(includeGREASE: true))
+        )
+        XCTAssertEqual(plain, greased)
+    }
+
+    /// Verifies the bare-handshake entry point used for QUIC encodes `q` as the
+    /// JA4 transport while JA3 stays transport-independent.
+    func testQUICTransportChangesOnlyJA4Header() throws {
+        let record = Self.clientHelloRecord(includeGREASE: false)
+        let tcp = try XCTUnwrap(TLSFingerprinter.fingerprint(record: record))
+        let quic = try XCTUnwrap(
+            TLSFingerprinter.fingerprint(handshake: record.dropFirst(5), transport: .quic)
+        )
+        XCTAssertEqual(tcp.ja3, quic.ja3)
+        XCTAssertTrue(quic.ja4.hasPrefix("q13d"), quic.ja4)
+        XCTAssertEqual(tcp.ja4.dropFirst(), quic.ja4.dropFirst())
+    }
+
+    /// Verifies malformed input returns nil instead of a partial fingerprint.
+    func testMalformedInputReturnsNil() {
+        XCTAssertNil(TLSFingerprinter.fingerprint(record: Data([0x17, 0x03, 0x03, 0x00, 0x02, 0xAA, 0xBB])))
+        XCTAssertNil(TLSFingerprinter.fingerprint(record: Self.clientHelloRecord(includeGREASE: false).dropLast(4)))
+        XCTAssertNil(TLSFingerprinter.fingerprint(handshake: Data([0x02, 0x00, 0x00, 0x00]), transport: .tcp))
+    }
+
+    /// Verifies variation extraction counts every GREASE value across ciphers, extensions,
+    /// groups, and supported versions, and reports zero for an ungreased hello.
+    func testVariationCountsGREASEValues() throws {
+        let plain = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false))
+        )
+        XCTAssertEqual(plain.greaseValueCount, 0)
+        XCTAssertFalse(plain.hasUnusualCipherOrder)
+        XCTAssertFalse(plain.offersSessionResumption)
+
+        // One GREASE value each in ciphers, groups, supported versions, and extension types.
+        let greased = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: true))
+        )
+        XCTAssertEqual(greased.greaseValueCount, 4)
+        XCTAssertFalse(greased.hasUnusualCipherOrder)
+    }
+
+    /// Verifies the unusual-order flag trips only when a TLS 1.3 suite follows a
+    /// non-GREASE legacy suite; GREASE placement does not count as a legacy suite.
+    func testVariationFlagsUnusualCipherOrder() throws {
+        let legacyFirst = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false, ciphers: [0xC02F, 0x1301]))
+        )
+        XCTAssertTrue(legacyFirst.hasUnusualCipherOrder)
+
+        let modernFirst = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false, ciphers: [0x1301, 0xC02F]))
+        )
+        XCTAssertFalse(modernFirst.hasUnusualCipherOrder)
+
+        let greaseFirst = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false, ciphers: [0x0A0A, 0x1301]))
+        )
+        XCTAssertFalse(greaseFirst.hasUnusualCipherOrder)
+    }
+
+    /// Verifies both resumption signals flag the hello: a non-empty legacy session ID and
+    /// a session ticket extension.
+    func testVariationFlagsSessionResumption() throws {
+        let legacyID = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false, sessionIDLength: 32))
+        )
+        XCTAssertTrue(legacyID.offersSessionResumption)
+
+        let ticket = try XCTUnwrap(
+            TLSFingerprinter.variation(record: Self.clientHelloRecord(includeGREASE: false, includeSessionTicket: true))
+        )
+        XCTAssertTrue(ticket.offersSessionResumption)
+
+        XCTAssertNil(TLSFingerprinter.variation(record: Data([0x17, 0x03, 0x03, 0x00, 0x00])))
+    }
+
+    // MARK: - ClientHello builder
+
+    /// TLS 1.3-style ClientHello record with SNI, groups, signature algorithms,
+    /// ALPN `h2`, and supported_versions; GREASE values optionally interleaved.
+    private static func clientHelloRecord(
+        includeGREASE: Bool,
+        ciphers baseCiphers: [UInt16] = [0x1301, 0x1302],
+        sessionIDLength: Int = 0,
+        includeSessionTicket: Bool = false
+    ) -> Data {
+        var ciphers = baseCiphers
+        if includeGREASE {
+            ciphers.insert(0x0A0A, at: 0)
+        }
+        var cipherBytes: [UInt8] = []
+        for cipher in ciphers {
+            cipherBytes.append(contentsOf: [UInt8(cipher >> 8), UInt8(cipher & 0xFF)])
+        }
+
+        var extensions = Data()
+        extensions.append(sniExtension(host: "www.example.com"))
+        extensions.append(uint16ListExtension(type: 10, values: includeGREASE ? [0x1A1A, 0x001D] : [0x001D]))
+        extensions.append(uint16ListExtension(type: 13, values: [0x0403]))
+        extensions.append(alpnExtension(protocolName: "h2"))
+        extensions.append(supportedVersionsExtension(versions: includeGREASE ? [0x2A2A, 0x0304] : [0x0304]))
+        if includeGREASE {
+            extensions.append(contentsOf: [0x3A, 0x3A, 0x00, 0x00])
+        }
+        if includeSessionTicket {
+            extensions.append(extensionRecord(type: 35, body: Data()))
+        }
+
+        var body = Data([0x03, 0x03])
+        body.append(Data(repeating: 0x42, count: 32))
+        body.append(UInt8(sessionIDLength))
+        body.append(Data(repeating: 0x24, count: sessionIDLength))
+        body.append(contentsOf: [UInt8(cipherBytes.count >> 8), UInt8(cipherBytes.count & 0xFF)])
+        body.append(contentsOf: cipherBytes)
+        body.append(contentsOf: [0x01, 0x00])
+        body.append(contentsOf: [UInt8(extensions.count >> 8), UInt8(extensions.count & 0xFF)])
+        body.append(extensions)
+
+        var handshake = Data([0x01, 0x00])
+        handshake.append(contentsOf: [UInt8(body.count >> 8), UInt8(body.count & 0xFF)])
+        handshake.append(body)
+
+        var record = Data([0x16, 0x03, 0x01])
+        record.append(contentsOf: [UInt8(handshake.count >> 8), UInt8(handshake.count & 0xFF)])
+        record.append(handshake)
+        return record
+    }
+
+    private static func sniExtension(host: String) -> Data {
+        let name = Data(host.utf8)
+        var entry = Data([0x00])
+        entry.append(contentsOf: [UInt8(name.count >> 8), UInt8(name.count & 0xFF)])
+        entry.append(name)
+        var body = Data([UInt8(entry.count >> 8), UInt8(entry.count & 0xFF)])
+        body.append(entry)
+        return extensionRecord(type: 0, body: body)
+    }
+
+    private static func uint16ListExtension(type: UInt16, values: [UInt16]) -> Data {
+        var list = Data()
+        for value in values {
+            list.append(contentsOf: [UInt8(value >> 8), UInt8(value & 0xFF)])
+        }
+        var body = Data([UInt8(list.count >> 8), UInt8(list.count & 0xFF)])
+        body.append(list)
+        return extensionRecord(type: type, body: body)
+    }
+
+    private static func alpnExtension(protocolName: String) -> Data {
+        let name = Data(protocolName.utf8)
+        var list = Data([UInt8(name.count)])
+        list.append(name)
+        var body = Data([UInt8(list.count >> 8), UInt8(list.count & 0xFF)])
+        body.append(list)
+        return extensionRecord(type: 16, body: body)
+    }
+
+    private static func supportedVersionsExtension(versions: [UInt16]) -> Data {
+        var list = Data()
+        for version in versions {
+            list.append(contentsOf: [UInt8(version >> 8), UInt8(version & 0xFF)])
+        }
+        var body = Data([UInt8(list.count)])
+        body.append(list)
+        return extensionRecord(type: 43, body: body)
+    }
+
+    private static func extensionRecord(type: UInt16, body: Data) -> Data {
+        var record = Data([UInt8(type >> 8), UInt8(type & 0xFF)])
+        record.append(contentsOf: [UInt8(body.count >> 8), UInt8(body.count & 0xFF)])
+        record.append(body)
+        return record
+    }
+}
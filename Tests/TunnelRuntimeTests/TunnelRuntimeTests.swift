@@ -31,6 +31,47 @@ final class TunnelRuntimeTests: XCTestCase {
         XCTAssertEqual(finalState, .idle)
     }
 
+    /// Verifies an injected dataplane observer receives lifecycle transitions, so harnesses
+    /// can mock dataplane behavior without closure or callback wiring.
+    func testInjectedDataplaneObserverReceivesStateTransitions() async throws {
+        final class RecordingObserver: DataplaneObserver, @unchecked Sendable {
+            private let lock = NSLock()
+            private var storedStates: [DataplaneState] = []
+
+            func dataplaneStateDidChange(to state: DataplaneState) {
+                lock.lock()
+                storedStates.append(state)
+                lock.unlock()
+            }
+
+            var states: [DataplaneState] {
+                lock.lock()
+                defer { lock.unlock() }
+                return storedStates
+            }
+        }
+
+        let observer = RecordingObserver()
+        let runtime = TunnelRuntime(
+            clock: SystemClock(),
+            runIdGenerator: DeterministicRunIdGenerator(),
+            randomSource: SeededRandomSource(seed: 1),
+            logger: StructuredLogger(sink: InMemoryLogSink()),
+            dataplaneObserver: observer
+        )
+
+        try await runtime.start(configJSON: deterministicLocalConfig, tunFD: 0)
+        try await runtime.stop()
+
+        // Callbacks are delivered from the bridge's queue thread, so poll briefly.
+        let deadline = Date().addingTimeInterval(2)
+        while !observer.states.contains(.stopped), Date() < deadline {
+            try await Task.sleep(nanoseconds: 10_000_000)
+        }
+        XCTAssertTrue(observer.states.contains(.running))
+        XCTAssertTrue(observer.states.contains(.stopped))
+    }
+
     /// Verifies replayable runtime output with deterministic seed.
     func testDeterministicRunIdAndSessionReplay() async throws {
         let first = try await executeReplay(seed: 7)
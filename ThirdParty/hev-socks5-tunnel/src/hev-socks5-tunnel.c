@@ -15,6 +15,7 @@
 
 #include <lwip/tcp.h>
 #include <lwip/udp.h>
+#include <lwip/stats.h>
 #include <lwip/nd6.h>
 #include <lwip/netif.h>
 #include <lwip/ip4_frag.h>
@@ -732,3 +733,31 @@ hev_socks5_tunnel_stats (size_t *tx_packets, size_t *tx_bytes,
     if (rx_bytes)
         *rx_bytes = stat_rx_bytes;
 }
+
+void
+hev_socks5_tunnel_stack_stats (size_t *rx_drops, size_t *rx_errors,
+                               size_t *unaddressable, size_t *memory_failures)
+{
+    LOG_D ("socks5 tunnel stack stats");
+
+    if (rx_drops)
+        *rx_drops = lwip_stats.ip.drop + lwip_stats.ip6.drop +
+                    lwip_stats.tcp.drop + lwip_stats.udp.drop;
+
+    if (rx_errors)
+        *rx_errors = lwip_stats.ip.err + lwip_stats.ip.chkerr +
+                     lwip_stats.ip.lenerr + lwip_stats.ip.proterr +
+                     lwip_stats.ip6.err + lwip_stats.ip6.chkerr +
+                     lwip_stats.ip6.lenerr + lwip_stats.ip6.proterr +
+                     lwip_stats.tcp.err + lwip_stats.tcp.chkerr +
+                     lwip_stats.tcp.lenerr + lwip_stats.udp.err +
+                     lwip_stats.udp.chkerr + lwip_stats.udp.lenerr;
+
+    if (unaddressable)
+        *unaddressable = lwip_stats.ip.rterr + lwip_stats.ip6.rterr +
+                         lwip_stats.tcp.proterr + lwip_stats.udp.proterr;
+
+    if (memory_failures)
+        *memory_failures = lwip_stats.ip.memerr + lwip_stats.ip6.memerr +
+                           lwip_stats.tcp.memerr + lwip_stats.udp.memerr;
+}
@@ -21,6 +21,10 @@ void hev_socks5_tunnel_stop (void);
 void hev_socks5_tunnel_stats (size_t *tx_packets, size_t *tx_bytes,
                               size_t *rx_packets, size_t *rx_bytes);
 
+void hev_socks5_tunnel_stack_stats (size_t *rx_drops, size_t *rx_errors,
+                                    size_t *unaddressable,
+                                    size_t *memory_failures);
+
 void hev_socks5_tunnel_update_session (HevListNode *node);
 
 #endif /* __HEV_SOCKS5_TUNNEL_H__ */
@@ -477,8 +477,16 @@ void *hev_calloc (size_t nmemb, size_t size);
 */
 /**
  * LWIP_STATS==1: Enable statistics collection in lwip_stats.
- */
-#define LWIP_STATS                      0
+ * Enabled so the host can surface stack-internal drop/error counters and
+ * distinguish stack drops from host drops; counters are widened to 32 bits so
+ * they stay meaningful over long-lived tunnels.
+ */
+#define LWIP_STATS                      1
+#define LWIP_STATS_LARGE                1
+#define LWIP_STATS_DISPLAY              0
+#define MEM_STATS                       0
+#define MEMP_STATS                      0
+#define SYS_STATS                       0
 
 /*
    --------------------------------------